@@ -39,6 +39,19 @@ pub struct GrugConfig {
     pub wasm_cache_capacity: usize,
     pub query_gas_limit: u64,
     pub priority_range: Option<(HexBinary, HexBinary)>,
+
+    /// Take a state sync snapshot every this many blocks. `0` disables
+    /// taking snapshots.
+    #[serde(default)]
+    pub snapshot_interval: u64,
+
+    /// Number of most recent snapshots to keep on disk.
+    #[serde(default = "default_snapshot_keep_recent")]
+    pub snapshot_keep_recent: usize,
+}
+
+fn default_snapshot_keep_recent() -> usize {
+    2
 }
 
 impl Default for GrugConfig {
@@ -47,6 +60,8 @@ impl Default for GrugConfig {
             wasm_cache_capacity: 1000,
             query_gas_limit: 100_000_000,
             priority_range: None,
+            snapshot_interval: 0,
+            snapshot_keep_recent: default_snapshot_keep_recent(),
         }
     }
 }
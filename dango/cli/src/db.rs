@@ -1,10 +1,11 @@
 use {
     crate::home_directory::HomeDirectory,
+    anyhow::anyhow,
     clap::Subcommand,
     colored::Colorize,
     dango_app::{Db, SimpleCommitment},
     dango_db_disk::DiskDb,
-    std::fs,
+    std::{fs, path::PathBuf},
 };
 
 #[derive(Subcommand)]
@@ -17,13 +18,32 @@ pub enum DbCmd {
         #[arg(short, long)]
         yes: bool,
     },
+    /// Export or import state snapshots
+    #[command(subcommand, next_display_order = None)]
+    Snapshot(SnapshotCmd),
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotCmd {
+    /// Export the state at the latest version into a snapshot
+    Export {
+        /// Directory to write the snapshot into [default: ~/.dango/snapshots/{version}]
+        dir: Option<PathBuf>,
+    },
+    /// Restore the state from a snapshot; the database must be empty
+    Import {
+        /// Directory containing the snapshot
+        dir: PathBuf,
+    },
 }
 
 impl DbCmd {
     pub fn run(self, dir: HomeDirectory) -> anyhow::Result<()> {
         let data_dir = dir.data_dir();
 
-        if !data_dir.exists() {
+        // Importing a snapshot is how a new node gets its state, so the data
+        // directory doesn't need to exist.
+        if !data_dir.exists() && !matches!(self, DbCmd::Snapshot(SnapshotCmd::Import { .. })) {
             println!("Data directory {data_dir:?} not found, nothing to do.");
             return Ok(());
         }
@@ -48,6 +68,39 @@ impl DbCmd {
 
                 fs::remove_dir_all(data_dir)?;
             },
+            DbCmd::Snapshot(SnapshotCmd::Export { dir: snapshot_dir }) => {
+                let db = DiskDb::<SimpleCommitment>::open(data_dir)?;
+
+                let snapshot_dir = match snapshot_dir {
+                    Some(snapshot_dir) => snapshot_dir,
+                    None => {
+                        let version = db
+                            .latest_version()
+                            .ok_or_else(|| anyhow!("database is empty, nothing to export"))?;
+                        dir.snapshots_dir().join(version.to_string())
+                    },
+                };
+
+                let metadata = db.export_snapshot(&snapshot_dir)?;
+
+                println!("Exported snapshot to {snapshot_dir:?}");
+                println!("Version: {}", metadata.version);
+                println!("Root hash: {}", metadata.root_hash);
+                println!("Chunks: {}", metadata.chunks());
+            },
+            DbCmd::Snapshot(SnapshotCmd::Import { dir: snapshot_dir }) => {
+                let db = DiskDb::<SimpleCommitment>::open(data_dir)?;
+                let metadata = db.import_snapshot(&snapshot_dir)?;
+
+                println!("Restored snapshot from {snapshot_dir:?}");
+                println!("Version: {}", metadata.version);
+                println!("Root hash: {}", metadata.root_hash);
+                println!(
+                    "{}",
+                    "Make sure the root hash matches the chain's app hash at this height before starting the node."
+                        .bold()
+                );
+            },
         }
 
         Ok(())
@@ -55,6 +55,11 @@ impl HomeDirectory {
         self.home.join("data")
     }
 
+    /// Return the path to the directory where state sync snapshots are stored.
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.home.join("snapshots")
+    }
+
     /// Return the path to the directory used by the indexer to store blocks.
     pub fn indexer_dir(&self) -> PathBuf {
         self.home.join("indexer")
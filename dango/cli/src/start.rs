@@ -11,7 +11,7 @@ use {
         SimpleCommitment,
     },
     dango_config_parser::parse_config,
-    dango_db_disk::DiskDb,
+    dango_db_disk::{DiskDb, DiskSnapshotter},
     dango_genesis::GenesisCodes,
     dango_indexer_hooked::HookedIndexer,
     dango_indexer_httpd::TendermintRpcClient,
@@ -82,6 +82,14 @@ impl StartCmd {
             // ]
         );
 
+        // Create the snapshotter for ABCI state sync.
+        let snapshotter = DiskSnapshotter::new(
+            db.clone(),
+            app_dir.snapshots_dir(),
+            cfg.grug.snapshot_interval,
+            cfg.grug.snapshot_keep_recent,
+        );
+
         // Create the base app instance for HTTP server
         let app = App::new(
             db.clone(),
@@ -127,6 +135,7 @@ impl StartCmd {
                         cfg.tendermint,
                         cfg.pyth,
                         db,
+                        snapshotter,
                         vm,
                         hooked_indexer.clone(),
                         hooked_indexer,
@@ -148,6 +157,7 @@ impl StartCmd {
                         cfg.tendermint,
                         cfg.pyth,
                         db,
+                        snapshotter,
                         vm,
                         hooked_indexer.clone(),
                         hooked_indexer,
@@ -165,6 +175,7 @@ impl StartCmd {
                         cfg.tendermint,
                         cfg.pyth,
                         db,
+                        snapshotter,
                         vm,
                         hooked_indexer.clone(),
                         hooked_indexer,
@@ -180,6 +191,7 @@ impl StartCmd {
                     cfg.tendermint,
                     cfg.pyth,
                     db,
+                    snapshotter,
                     vm,
                     hooked_indexer.clone(),
                     hooked_indexer,
@@ -346,6 +358,7 @@ impl StartCmd {
         tendermint_cfg: TendermintConfig,
         pyth_lazer_cfg: PythLazerConfig,
         db: DiskDb<SimpleCommitment>,
+        snapshotter: DiskSnapshotter<SimpleCommitment>,
         vm: RustVm,
         indexer: ID,
         mut indexer_for_shutdown: ID,
@@ -375,7 +388,8 @@ impl StartCmd {
                 env!("CARGO_PKG_VERSION"),
             )
             .with_shutdown_trigger(halt_tx)
-            .with_retain_recent_blocks(tendermint_cfg.retain_recent_blocks),
+            .with_retain_recent_blocks(tendermint_cfg.retain_recent_blocks)
+            .with_snapshotter(Arc::new(snapshotter)),
         );
 
         let (consensus, mempool, snapshot, info) = split::service(service, 1);
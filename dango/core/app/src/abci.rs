@@ -1,8 +1,11 @@
 use {
-    crate::{App, AppError, AppResult, Db, Indexer, ProposalPreparer, Vm},
+    crate::{
+        App, AppError, AppResult, ApplyChunkOutcome, Db, Indexer, ProposalPreparer,
+        SNAPSHOT_FORMAT, SnapshotMetadata, Vm,
+    },
     dango_primitives::{
-        BlockInfo, CheckTxOutcome, Duration, GENESIS_BLOCK_HASH, GenericResult, Hash256, Inner,
-        JsonSerExt, StdResult, TxOutcome,
+        BlockInfo, BorshDeExt, BorshSerExt, CheckTxOutcome, Duration, GENESIS_BLOCK_HASH,
+        GenericResult, Hash256, HashExt, Inner, JsonSerExt, StdResult, TxOutcome,
     },
     prost::bytes::Bytes,
     std::{
@@ -14,7 +17,11 @@ use {
     },
     tendermint::{
         AppHash, Hash, Time,
-        abci::{self, Code, request, response, types::ExecTxResult},
+        abci::{
+            self, Code, request,
+            response::{self, ApplySnapshotChunkResult},
+            types::{ExecTxResult, Snapshot},
+        },
         block::Height,
         v0_38::abci::{Request, Response},
     },
@@ -88,9 +95,8 @@ where
                 let res = self.tower_prepare_proposal(req)?;
                 Ok(Response::PrepareProposal(res))
             },
-            Request::ProcessProposal(_) => {
-                // Always accept.
-                let res = response::ProcessProposal::Accept;
+            Request::ProcessProposal(req) => {
+                let res = self.tower_process_proposal(req)?;
                 Ok(Response::ProcessProposal(res))
             },
             Request::ExtendVote(_) => {
@@ -131,10 +137,22 @@ where
             },
 
             // ---------------------- state sync methods -----------------------
-            Request::ListSnapshots => Ok(Response::ListSnapshots(Default::default())),
-            Request::OfferSnapshot(_) => Ok(Response::OfferSnapshot(Default::default())),
-            Request::LoadSnapshotChunk(_) => Ok(Response::LoadSnapshotChunk(Default::default())),
-            Request::ApplySnapshotChunk(_) => Ok(Response::ApplySnapshotChunk(Default::default())),
+            Request::ListSnapshots => {
+                let res = self.tower_list_snapshots()?;
+                Ok(Response::ListSnapshots(res))
+            },
+            Request::OfferSnapshot(req) => {
+                let res = self.tower_offer_snapshot(req)?;
+                Ok(Response::OfferSnapshot(res))
+            },
+            Request::LoadSnapshotChunk(req) => {
+                let res = self.tower_load_snapshot_chunk(req)?;
+                Ok(Response::LoadSnapshotChunk(res))
+            },
+            Request::ApplySnapshotChunk(req) => {
+                let res = self.tower_apply_snapshot_chunk(req)?;
+                Ok(Response::ApplySnapshotChunk(res))
+            },
 
             // ------------------------- other methods -------------------------
             Request::Echo(req) => {
@@ -300,12 +318,30 @@ where
         &self,
         req: request::PrepareProposal,
     ) -> AppResult<response::PrepareProposal> {
+        let block = from_tm_block(req.height.value(), req.time, None);
         let max_tx_bytes = req.max_tx_bytes.try_into().unwrap_or(0);
-        let txs = self.do_prepare_proposal(req.txs.clone(), max_tx_bytes);
+        let txs = self.do_prepare_proposal(block, req.txs.clone(), max_tx_bytes);
 
         Ok(response::PrepareProposal { txs })
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument("abci::process_proposal", skip_all)
+    )]
+    fn tower_process_proposal(
+        &self,
+        req: request::ProcessProposal,
+    ) -> AppResult<response::ProcessProposal> {
+        let block = from_tm_block(req.height.value(), req.time, Some(req.hash));
+
+        if self.do_process_proposal(block, &req.txs) {
+            Ok(response::ProcessProposal::Accept)
+        } else {
+            Ok(response::ProcessProposal::Reject)
+        }
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument("abci::query", skip_all))]
     fn tower_query(&self, req: request::Query) -> AppResult<response::Query> {
         let res = match req.path.as_str() {
@@ -345,6 +381,119 @@ where
 
         Ok(res)
     }
+
+    fn tower_list_snapshots(&self) -> AppResult<response::ListSnapshots> {
+        let Some(snapshotter) = &self.snapshotter else {
+            return Ok(Default::default());
+        };
+
+        let snapshots = snapshotter
+            .list_snapshots()?
+            .iter()
+            .map(into_tm_snapshot)
+            .collect::<StdResult<_>>()?;
+
+        Ok(response::ListSnapshots { snapshots })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument("abci::offer_snapshot", skip_all, fields(height = req.snapshot.height.value()))
+    )]
+    fn tower_offer_snapshot(
+        &self,
+        req: request::OfferSnapshot,
+    ) -> AppResult<response::OfferSnapshot> {
+        let Some(snapshotter) = &self.snapshotter else {
+            return Ok(response::OfferSnapshot::Reject);
+        };
+
+        let metadata = match from_tm_snapshot(&req.snapshot, req.app_hash.as_bytes()) {
+            Ok(metadata) => metadata,
+            Err(res) => return Ok(res),
+        };
+
+        match snapshotter.begin_restore(metadata) {
+            Ok(()) => Ok(response::OfferSnapshot::Accept),
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::error!(err = %_err, "Failed to begin restoring snapshot");
+                }
+
+                Ok(response::OfferSnapshot::Abort)
+            },
+        }
+    }
+
+    fn tower_load_snapshot_chunk(
+        &self,
+        req: request::LoadSnapshotChunk,
+    ) -> AppResult<response::LoadSnapshotChunk> {
+        let Some(snapshotter) = &self.snapshotter else {
+            return Ok(Default::default());
+        };
+
+        if req.format != SNAPSHOT_FORMAT {
+            return Ok(Default::default());
+        }
+
+        let chunk = snapshotter
+            .load_chunk(req.height.value(), req.chunk)?
+            .unwrap_or_default();
+
+        Ok(response::LoadSnapshotChunk {
+            chunk: chunk.into(),
+        })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument("abci::apply_snapshot_chunk", skip_all, fields(index = req.index))
+    )]
+    fn tower_apply_snapshot_chunk(
+        &self,
+        req: request::ApplySnapshotChunk,
+    ) -> AppResult<response::ApplySnapshotChunk> {
+        let Some(snapshotter) = &self.snapshotter else {
+            return Ok(response::ApplySnapshotChunk {
+                result: ApplySnapshotChunkResult::Abort,
+                ..Default::default()
+            });
+        };
+
+        let res = match snapshotter.apply_chunk(req.index, &req.chunk) {
+            Ok(ApplyChunkOutcome::Accepted | ApplyChunkOutcome::Restored) => {
+                response::ApplySnapshotChunk {
+                    result: ApplySnapshotChunkResult::Accept,
+                    ..Default::default()
+                }
+            },
+            // Fetch the chunk again, from a different peer.
+            Ok(ApplyChunkOutcome::BadChunk) => response::ApplySnapshotChunk {
+                result: ApplySnapshotChunkResult::Retry,
+                refetch_chunks: vec![req.index],
+                reject_senders: vec![req.sender],
+            },
+            Ok(ApplyChunkOutcome::BadSnapshot) => response::ApplySnapshotChunk {
+                result: ApplySnapshotChunkResult::RejectSnapshot,
+                ..Default::default()
+            },
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::error!(err = %_err, "Failed to apply snapshot chunk");
+                }
+
+                response::ApplySnapshotChunk {
+                    result: ApplySnapshotChunkResult::Abort,
+                    ..Default::default()
+                }
+            },
+        };
+
+        Ok(res)
+    }
 }
 
 /// Compute the `retain_height` to return in the ABCI `Commit` response, given
@@ -424,6 +573,52 @@ fn into_tm_tx_result(outcome: TxOutcome) -> StdResult<ExecTxResult> {
     })
 }
 
+fn into_tm_snapshot(metadata: &SnapshotMetadata) -> StdResult<Snapshot> {
+    let bytes = metadata.to_borsh_vec()?;
+
+    Ok(Snapshot {
+        height: Height::try_from(metadata.version).expect("snapshot height exceeds i64::MAX"),
+        format: SNAPSHOT_FORMAT,
+        chunks: metadata.chunks(),
+        hash: bytes.sha2_256().to_vec().into(),
+        metadata: bytes.into(),
+    })
+}
+
+/// Decode and validate the metadata of a snapshot offered by a peer. On
+/// failure, return the response to send back to CometBFT.
+///
+/// `app_hash` is the app hash at the snapshot's height, as verified by
+/// CometBFT's light client. It's the only piece of data here that can be
+/// trusted; everything else comes from the peer.
+fn from_tm_snapshot(
+    snapshot: &Snapshot,
+    app_hash: &[u8],
+) -> Result<SnapshotMetadata, response::OfferSnapshot> {
+    if snapshot.format != SNAPSHOT_FORMAT {
+        return Err(response::OfferSnapshot::RejectFormat);
+    }
+
+    if snapshot.hash.as_ref() != snapshot.metadata.sha2_256().as_ref() {
+        return Err(response::OfferSnapshot::Reject);
+    }
+
+    let metadata: SnapshotMetadata = snapshot
+        .metadata
+        .deserialize_borsh()
+        .map_err(|_| response::OfferSnapshot::Reject)?;
+
+    if metadata.version != snapshot.height.value()
+        || metadata.chunks() != snapshot.chunks
+        || metadata.chunks() == 0
+        || metadata.root_hash.as_ref() != app_hash
+    {
+        return Err(response::OfferSnapshot::Reject);
+    }
+
+    Ok(metadata)
+}
+
 fn into_tm_app_hash(hash: Hash256) -> AppHash {
     hash.into_inner().to_vec().try_into().unwrap()
 }
@@ -456,4 +651,59 @@ mod tests {
         // regardless of how tall the chain is.
         assert_eq!(retain_height(1500, 0).value(), 0);
     }
+
+    #[test]
+    fn offered_snapshot_is_validated() {
+        let metadata = SnapshotMetadata {
+            version: 100,
+            root_hash: Hash256::from_inner([1; 32]),
+            chunk_hashes: vec![b"chunk".sha2_256()],
+        };
+        let snapshot = into_tm_snapshot(&metadata).unwrap();
+
+        // Matching the trusted app hash.
+        assert_eq!(from_tm_snapshot(&snapshot, &[1; 32]), Ok(metadata.clone()));
+
+        // Not matching the trusted app hash.
+        assert_eq!(
+            from_tm_snapshot(&snapshot, &[2; 32]),
+            Err(response::OfferSnapshot::Reject)
+        );
+
+        // Unknown format.
+        assert_eq!(
+            from_tm_snapshot(
+                &Snapshot {
+                    format: SNAPSHOT_FORMAT + 1,
+                    ..snapshot.clone()
+                },
+                &[1; 32]
+            ),
+            Err(response::OfferSnapshot::RejectFormat)
+        );
+
+        // Snapshot hash doesn't match the metadata.
+        assert_eq!(
+            from_tm_snapshot(
+                &Snapshot {
+                    hash: vec![0; 32].into(),
+                    ..snapshot.clone()
+                },
+                &[1; 32]
+            ),
+            Err(response::OfferSnapshot::Reject)
+        );
+
+        // Height doesn't match the metadata.
+        assert_eq!(
+            from_tm_snapshot(
+                &Snapshot {
+                    height: Height::from(99_u32),
+                    ..snapshot
+                },
+                &[1; 32]
+            ),
+            Err(response::OfferSnapshot::Reject)
+        );
+    }
 }
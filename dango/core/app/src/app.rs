@@ -6,22 +6,22 @@ use dango_primitives::{HashExt, JsonDeExt};
 use data_encoding::BASE64;
 use {
     crate::{
-        APP_CONFIG, AppError, AppResult, CHAIN_ID, CODES, CONFIG, Db, EventResult, GasTracker,
-        Indexer, LAST_FINALIZED_BLOCK, NEXT_CRONJOBS, NEXT_UPGRADE, NaiveProposalPreparer,
-        NaiveQuerier, NullIndexer, PAST_UPGRADES, ProposalPreparer, QuerierProviderImpl,
-        TraceOption, Vm, catch_and_push_event, do_authenticate, do_configure, do_cron_execute,
-        do_execute, do_instantiate, do_migrate, do_transfer, do_upgrade, do_upload,
-        do_withhold_fee, query_app_config, query_balance, query_balances, query_code, query_codes,
-        query_config, query_contract, query_contracts, query_next_upgrade, query_past_upgrades,
-        query_status, query_supplies, query_supply, query_wasm_raw, query_wasm_scan,
-        query_wasm_smart,
+        APP_CONFIG, AppError, AppResult, CHAIN_ID, CODES, CONFIG, CONTRACT_NAMESPACE, Db,
+        EventResult, GasTracker, Indexer, LAST_FINALIZED_BLOCK, NEXT_CRONJOBS, NEXT_UPGRADE,
+        NaiveProposalPreparer, NaiveQuerier, NullIndexer, PAST_UPGRADES, ProposalPreparer,
+        QuerierProviderImpl, Snapshotter, TraceOption, Vm, catch_and_push_event, do_authenticate,
+        do_configure, do_cron_execute, do_execute, do_instantiate, do_migrate, do_transfer,
+        do_upgrade, do_upload, do_withhold_fee, query_app_config, query_balance, query_balances,
+        query_code, query_codes, query_config, query_contract, query_contracts, query_next_upgrade,
+        query_past_upgrades, query_status, query_supplies, query_supply, query_wasm_raw,
+        query_wasm_scan, query_wasm_smart,
     },
     dango_primitives::{
-        Addr, AuthMode, Block, BlockInfo, BlockOutcome, Buffer, CheckTxEvents, CheckTxOutcome,
-        CodeStatus, CronOutcome, Duration, Event, GENESIS_SENDER, GenericResult, GenericResultExt,
-        GenesisState, Hash256, Json, Message, MsgsAndBackrunEvents, Order, Permission,
-        QuerierWrapper, Query, QueryResponse, Shared, StdResult, Storage, Timestamp, Tx, TxEvents,
-        TxOutcome, UnsignedTx,
+        Addr, AuthMode, Block, BlockInfo, BlockOutcome, BorshSerExt, Buffer, CheckTxEvents,
+        CheckTxOutcome, CodeStatus, CronOutcome, Duration, Event, GENESIS_SENDER, GenericResult,
+        GenericResultExt, GenesisState, Hash256, Json, Message, MsgsAndBackrunEvents, Order,
+        Permission, ProvenQueryResponse, QuerierWrapper, Query, QueryResponse, Shared, StdResult,
+        Storage, Timestamp, Tx, TxEvents, TxOutcome, UnsignedTx,
     },
     dango_storage::PrefixBound,
     prost::bytes::Bytes,
@@ -97,6 +97,14 @@ pub struct App<DB, VM, PP = NaiveProposalPreparer, ID = NullIndexer> {
     /// Dango doesn't need CometBFT to keep historical blocks, as the embedded
     /// indexer persists every block to disk independently.
     pub(crate) retain_recent_blocks: u64,
+
+    /// If set, the app takes periodic state snapshots and serves them to
+    /// peers via ABCI state sync, and can restore its state from a snapshot
+    /// provided by peers.
+    ///
+    /// `None` in tests and in the read-only `App` instance used by the HTTP
+    /// server.
+    pub(crate) snapshotter: Option<Arc<dyn Snapshotter>>,
 }
 
 impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
@@ -127,6 +135,7 @@ impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
             cargo_version: cargo_version.into(),
             shutdown_trigger: None,
             retain_recent_blocks: 0,
+            snapshotter: None,
         }
     }
 
@@ -151,6 +160,16 @@ impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
         self.retain_recent_blocks = retain_recent_blocks;
         self
     }
+
+    /// Attach a snapshotter, enabling ABCI state sync (see [`Snapshotter`]).
+    ///
+    /// Intended to be called by the binary's `start` command right after
+    /// `App::new`; tests and the HTTP-only `App` instance leave this unset.
+    #[must_use]
+    pub fn with_snapshotter(mut self, snapshotter: Arc<dyn Snapshotter>) -> Self {
+        self.snapshotter = Some(snapshotter);
+        self
+    }
 }
 
 #[cfg(feature = "testing")]
@@ -188,6 +207,7 @@ where
             cargo_version: self.cargo_version.clone(),
             shutdown_trigger: self.shutdown_trigger.clone(),
             retain_recent_blocks: self.retain_recent_blocks,
+            snapshotter: self.snapshotter.clone(),
         }
     }
 }
@@ -306,10 +326,16 @@ where
         Ok(root_hash.unwrap())
     }
 
-    pub fn do_prepare_proposal(&self, txs: Vec<Bytes>, max_tx_bytes: usize) -> Vec<Bytes> {
+    pub fn do_prepare_proposal(
+        &self,
+        block: BlockInfo,
+        txs: Vec<Bytes>,
+        max_tx_bytes: usize,
+    ) -> Vec<Bytes> {
         #[cfg(feature = "tracing")]
         {
             tracing::info!(
+                block_height = block.height,
                 num_txs = txs.len(),
                 max_tx_bytes,
                 "Received PrepareProposal request",
@@ -321,7 +347,7 @@ where
 
         #[cfg_attr(not(feature = "tracing"), allow(clippy::unnecessary_lazy_evaluations))]
         let txs = self
-            ._do_prepare_proposal(txs.clone(), max_tx_bytes)
+            ._do_prepare_proposal(block, txs.clone(), max_tx_bytes)
             .unwrap_or_else(|_err| {
                 #[cfg(feature = "tracing")]
                 {
@@ -336,7 +362,7 @@ where
 
         // Call naive proposal preparer to check the `max_tx_bytes`.
         let bytes = NaiveProposalPreparer
-            .prepare_proposal(QuerierWrapper::new(&NaiveQuerier), txs, max_tx_bytes)
+            .prepare_proposal(QuerierWrapper::new(&NaiveQuerier), block, txs, max_tx_bytes)
             .unwrap();
 
         #[cfg(feature = "tracing")]
@@ -354,21 +380,85 @@ where
     }
 
     #[inline]
-    fn _do_prepare_proposal(&self, txs: Vec<Bytes>, max_tx_bytes: usize) -> AppResult<Vec<Bytes>> {
+    fn _do_prepare_proposal(
+        &self,
+        block: BlockInfo,
+        txs: Vec<Bytes>,
+        max_tx_bytes: usize,
+    ) -> AppResult<Vec<Bytes>> {
         let storage = self
             .db
             .state_storage_with_comment(None, "prepare_proposal")?;
-        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let last_finalized_block = LAST_FINALIZED_BLOCK.load(&storage)?;
         let querier = QuerierProviderImpl::new_boxed(
             self.vm.clone(),
             Box::new(storage),
             GasTracker::new_limitless(),
-            block,
+            last_finalized_block,
         );
 
         Ok(self
             .pp
-            .prepare_proposal(QuerierWrapper::new(&querier), txs, max_tx_bytes)?)
+            .prepare_proposal(QuerierWrapper::new(&querier), block, txs, max_tx_bytes)?)
+    }
+
+    /// Check a block proposed by another validator. Return `false` to reject
+    /// it.
+    pub fn do_process_proposal(&self, block: BlockInfo, txs: &[Bytes]) -> bool {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::info!(
+                block_height = block.height,
+                num_txs = txs.len(),
+                "Received ProcessProposal request",
+            );
+        }
+
+        #[cfg(feature = "metrics")]
+        let process_proposal_duration = std::time::Instant::now();
+
+        let accepted = match self._do_process_proposal(block, txs) {
+            Ok(()) => true,
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::warn!(
+                        block_height = block.height,
+                        err = _err.to_string(),
+                        "Rejected proposal"
+                    );
+                }
+
+                false
+            },
+        };
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::histogram!(crate::metrics::LABEL_DURATION_PROCESS_PROPOSAL)
+                .record(process_proposal_duration.elapsed().as_secs_f64());
+        }
+
+        accepted
+    }
+
+    #[inline]
+    fn _do_process_proposal(&self, block: BlockInfo, txs: &[Bytes]) -> AppResult<()> {
+        let storage = self
+            .db
+            .state_storage_with_comment(None, "process_proposal")?;
+        let last_finalized_block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let querier = QuerierProviderImpl::new_boxed(
+            self.vm.clone(),
+            Box::new(storage),
+            GasTracker::new_limitless(),
+            last_finalized_block,
+        );
+
+        // Keep rejections apart from failures to prepare a proposal.
+        self.pp
+            .process_proposal(QuerierWrapper::new(&querier), block, txs)
+            .map_err(|err| AppError::process_proposal(err.to_string()))
     }
 
     // Finalize a block by performing the following actions in order:
@@ -728,6 +818,17 @@ where
             tracing::info!(height = version, "Committed state");
         }
 
+        // Failing to take a snapshot shouldn't halt the chain, so only log
+        // the error.
+        if let Some(snapshotter) = &self.snapshotter {
+            let _ = snapshotter.on_commit(version).inspect_err(|_err| {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::error!(err = %_err, height = version, "Failed to take snapshot");
+                }
+            });
+        }
+
         let storage = self.db.state_storage_with_comment(None, "post_indexing")?;
         let cfg = CONFIG.load(&storage)?;
         let app_cfg = APP_CONFIG.load(&storage)?;
@@ -847,6 +948,46 @@ where
         Ok((res, block.height))
     }
 
+    /// Perform a query, and generate a Merkle proof of the raw storage that
+    /// the response was read from.
+    ///
+    /// Only queries that read raw contract storage can be proven, that is,
+    /// `wasm_raw`, `wasm_scan`, and `multi` consisting of these.
+    ///
+    /// For `wasm_scan`, the proof covers the returned key-value pairs, but
+    /// not the absence of other pairs in the scanned range: as the Merkle tree
+    /// is keyed by hashed keys, it has no notion of a range of raw keys.
+    pub fn do_query_app_with_proof(&self, req: Query) -> AppResult<ProvenQueryResponse> {
+        ensure_provable(&req)?;
+
+        // Query and prove against the same version, in case a block is
+        // committed in between.
+        let version = self.db.latest_version();
+        let storage = self.db.state_storage_with_comment(version, "query_app")?;
+        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+
+        let res = process_query(
+            self.vm.clone(),
+            Box::new(storage),
+            GasTracker::new_limited(self.query_gas_limit),
+            block,
+            0,
+            req.clone(),
+        )?;
+
+        let mut keys = Vec::new();
+        collect_proven_keys(&req, &res, &mut keys)?;
+
+        let keys = keys.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let proof = self.db.prove_multi(&keys, version)?;
+
+        Ok(ProvenQueryResponse {
+            response: res,
+            height: block.height,
+            proof: proof.to_borsh_vec()?.into(),
+        })
+    }
+
     pub fn do_simulate(&self, unsigned_tx: UnsignedTx) -> AppResult<TxOutcome> {
         let buffer = Buffer::new(
             self.db.state_storage_with_comment(None, "simulate")?,
@@ -979,11 +1120,14 @@ where
             return Err(AppError::past_height_not_supported());
         };
 
+        let req = raw_req.deserialize_json()?;
+
         if prove {
-            return Err(AppError::proof_not_supported());
+            let res = self.do_query_app_with_proof(req)?;
+
+            return Ok(res.to_json_vec()?);
         }
 
-        let req = raw_req.deserialize_json()?;
         let res = self.do_query_app(req)?;
 
         Ok(res.to_json_vec()?)
@@ -1257,6 +1401,49 @@ where
     }
 }
 
+/// Error if the query reads anything other than raw contract storage, which
+/// `do_query_app_with_proof` can't prove.
+fn ensure_provable(req: &Query) -> AppResult<()> {
+    match req {
+        Query::WasmRaw(_) | Query::WasmScan(_) => Ok(()),
+        Query::Multi(reqs) => reqs.iter().try_for_each(ensure_provable),
+        _ => Err(AppError::query_not_provable()),
+    }
+}
+
+/// Collect the full storage keys that the response was read from.
+///
+/// For a failed sub-query of a `multi` query, there is nothing to prove.
+/// Error if the response isn't of the type the query should have produced.
+fn collect_proven_keys(req: &Query, res: &QueryResponse, keys: &mut Vec<Vec<u8>>) -> AppResult<()> {
+    match (req, res) {
+        (Query::WasmRaw(req), QueryResponse::WasmRaw(_)) => {
+            keys.push(contract_storage_key(req.contract, &req.key));
+        },
+        (Query::WasmScan(req), QueryResponse::WasmScan(res)) => {
+            keys.extend(
+                res.keys()
+                    .map(|key| contract_storage_key(req.contract, key)),
+            );
+        },
+        (Query::Multi(reqs), QueryResponse::Multi(res)) if reqs.len() == res.len() => {
+            for (req, res) in reqs.iter().zip(res) {
+                if let Ok(res) = res {
+                    collect_proven_keys(req, res, keys)?;
+                }
+            }
+        },
+        _ => return Err(AppError::query_response_mismatch()),
+    }
+
+    Ok(())
+}
+
+/// The full storage key of a key in a contract's storage.
+fn contract_storage_key(contract: Addr, key: &[u8]) -> Vec<u8> {
+    [CONTRACT_NAMESPACE, contract.as_ref(), key].concat()
+}
+
 pub fn process_query<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
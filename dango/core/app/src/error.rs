@@ -24,6 +24,9 @@ pub enum AppError {
     #[error("proposal preparer error: {error}")]
     PrepareProposal { error: String },
 
+    #[error("proposal rejected: {error}")]
+    ProcessProposal { error: String },
+
     #[error("contract returned error! address: {address}, method: {name}, msg: {msg}")]
     Guest {
         address: Addr,
@@ -44,6 +47,12 @@ pub enum AppError {
     #[error("merkle proof is not supported for `/app` query")]
     ProofNotSupported,
 
+    #[error("merkle proof is only supported for `wasm_raw` and `wasm_scan` queries")]
+    QueryNotProvable,
+
+    #[error("query response doesn't match the query, so its storage keys can't be proven")]
+    QueryResponseMismatch,
+
     #[error("simulating a transaction at past block height is not supported")]
     PastHeightNotSupported,
 
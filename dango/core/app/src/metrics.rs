@@ -19,6 +19,8 @@ pub const LABEL_DURATION_TX: &str = "grug.app.tx.duration";
 
 pub const LABEL_DURATION_PREPARE_PROPOSAL: &str = "grug.app.prepare_proposal.duration";
 
+pub const LABEL_DURATION_PROCESS_PROPOSAL: &str = "grug.app.process_proposal.duration";
+
 pub const LABEL_DURATION_COMMIT: &str = "grug.app.commit.duration";
 
 pub(crate) fn init_metrics() {
@@ -44,6 +46,11 @@ pub(crate) fn init_metrics() {
             "Duration of prepare proposal"
         );
 
+        describe_histogram!(
+            LABEL_DURATION_PROCESS_PROPOSAL,
+            "Duration of process proposal"
+        );
+
         describe_histogram!(LABEL_DURATION_COMMIT, "Duration of commit");
     });
 }
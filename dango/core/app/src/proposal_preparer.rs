@@ -2,7 +2,7 @@
 use tracing::info;
 use {
     crate::{AppError, ProposalPreparer},
-    dango_primitives::{Querier, QuerierWrapper, Query, QueryResponse, StdResult},
+    dango_primitives::{BlockInfo, Querier, QuerierWrapper, Query, QueryResponse, StdResult},
     prost::bytes::Bytes,
     std::{
         convert::Infallible,
@@ -24,6 +24,7 @@ impl ProposalPreparer for NaiveProposalPreparer {
     fn prepare_proposal(
         &self,
         _querier: QuerierWrapper,
+        _block: BlockInfo,
         mut txs: Vec<Bytes>,
         max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error> {
@@ -2,6 +2,7 @@ mod commitment;
 mod db;
 mod indexer;
 mod proposal_preparer;
+mod snapshot;
 mod vm;
 
-pub use {commitment::*, db::*, indexer::*, proposal_preparer::*, vm::*};
+pub use {commitment::*, db::*, indexer::*, proposal_preparer::*, snapshot::*, vm::*};
@@ -1,6 +1,6 @@
 use {
     borsh::{BorshDeserialize, BorshSerialize},
-    dango_primitives::{Batch, Bound, Hash256, Op, StdResult, Storage},
+    dango_primitives::{Batch, Bound, Hash256, Op, Record, StdResult, Storage},
     dango_storage::Map,
     sha2::{Digest, Sha256},
 };
@@ -19,6 +19,8 @@ use {
 pub trait Commitment {
     type Proof: BorshSerialize + BorshDeserialize;
 
+    type MultiProof: BorshSerialize + BorshDeserialize;
+
     fn root_hash(storage: &dyn Storage, version: u64) -> StdResult<Option<Hash256>>;
 
     fn apply(
@@ -30,6 +32,26 @@ pub trait Commitment {
 
     fn prove(storage: &dyn Storage, key_hash: Hash256, version: u64) -> StdResult<Self::Proof>;
 
+    /// Generate a single proof for the existence or non-existence of each of
+    /// the given keys.
+    fn prove_multi(
+        storage: &dyn Storage,
+        key_hashes: &[Hash256],
+        version: u64,
+    ) -> StdResult<Self::MultiProof>;
+
+    /// Check that the given key-value pairs are exactly the state committed to
+    /// at the given version: each of them is committed with the same value,
+    /// and none is missing. The committed structure itself must be checked
+    /// against the root hash, not trusted as stored.
+    ///
+    /// Used to verify a state restored from a snapshot, whose root hash alone
+    /// says nothing about the rest of the restored commitment, or about the
+    /// raw key-value pairs.
+    fn verify_state<I>(storage: &dyn Storage, version: u64, state: I) -> StdResult<bool>
+    where
+        I: IntoIterator<Item = StdResult<Record>>;
+
     fn prune(storage: &mut dyn Storage, up_to_version: u64) -> StdResult<()>;
 }
 
@@ -41,6 +63,7 @@ impl SimpleCommitment {
 }
 
 impl Commitment for SimpleCommitment {
+    type MultiProof = ();
     type Proof = ();
 
     fn root_hash(storage: &dyn Storage, version: u64) -> StdResult<Option<Hash256>> {
@@ -81,6 +104,23 @@ impl Commitment for SimpleCommitment {
         Ok(())
     }
 
+    fn prove_multi(
+        _storage: &dyn Storage,
+        _key_hashes: &[Hash256],
+        _version: u64,
+    ) -> StdResult<Self::MultiProof> {
+        Ok(())
+    }
+
+    /// This scheme only commits to the changesets, not the state itself, so
+    /// there is nothing to check the state against.
+    fn verify_state<I>(_storage: &dyn Storage, _version: u64, _state: I) -> StdResult<bool>
+    where
+        I: IntoIterator<Item = StdResult<Record>>,
+    {
+        Ok(true)
+    }
+
     fn prune(storage: &mut dyn Storage, up_to_version: u64) -> StdResult<()> {
         Self::ROOT_HASHES.clear(storage, None, Some(Bound::Exclusive(up_to_version)));
 
@@ -40,6 +40,9 @@ pub trait Db {
     /// Type of the Merkle proof. The DB can choose any Merkle tree scheme.
     type Proof: BorshSerialize + BorshDeserialize;
 
+    /// Type of the Merkle proof covering multiple keys.
+    type MultiProof: BorshSerialize + BorshDeserialize;
+
     /// Return the state commitment.
     fn state_commitment(&self) -> Self::StateCommitment;
 
@@ -87,6 +90,18 @@ pub trait Db {
     /// _membership_ proof; otherwise, it should be a _non-membership_ proof.
     fn prove(&self, key: &[u8], version: Option<u64>) -> Result<Self::Proof, Self::Error>;
 
+    /// Generate a single Merkle proof of the given keys at the given version.
+    ///
+    /// If version is unspecified, use the latest version.
+    ///
+    /// The proof shows, for each key, either that it exists with its current
+    /// value or that it doesn't exist.
+    fn prove_multi(
+        &self,
+        keys: &[&[u8]],
+        version: Option<u64>,
+    ) -> Result<Self::MultiProof, Self::Error>;
+
     /// Accept a batch ops (an op is either a DB insertion or a deletion), keep
     /// them in the memory, but do not persist to disk yet; also, increment the
     /// version.
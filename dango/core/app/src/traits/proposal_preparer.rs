@@ -1,17 +1,40 @@
-use {dango_primitives::QuerierWrapper, prost::bytes::Bytes};
+use {
+    dango_primitives::{BlockInfo, QuerierWrapper},
+    prost::bytes::Bytes,
+};
 
-/// Represents a worker that processes the ABCI++ `PrepareProposal` request.
+/// Represents a worker that processes the ABCI++ `PrepareProposal` and
+/// `ProcessProposal` requests.
 pub trait ProposalPreparer {
     type Error: ToString;
 
     /// Process the ABCI++ `PrepareProposal` request.
     ///
     /// The preparer is provided with a querier so that it can do its work based
-    /// on the state of the chain.
+    /// on the state of the chain. `block` is the proposed block's info; its
+    /// hash isn't known yet, so it's the genesis block hash as a placeholder.
     fn prepare_proposal(
         &self,
         querier: QuerierWrapper,
+        block: BlockInfo,
         txs: Vec<Bytes>,
         max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error>;
+
+    /// Process the ABCI++ `ProcessProposal` request.
+    ///
+    /// Called on every validator with a block proposed by someone else. The
+    /// preparer should check that the proposer did what `prepare_proposal`
+    /// would have done, and return an error to reject the block. `block` is
+    /// the proposed block's info, not the last finalized block's.
+    ///
+    /// The default implementation accepts any block.
+    fn process_proposal(
+        &self,
+        _querier: QuerierWrapper,
+        _block: BlockInfo,
+        _txs: &[Bytes],
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
@@ -0,0 +1,106 @@
+use {
+    crate::AppResult,
+    borsh::{BorshDeserialize, BorshSerialize},
+    dango_primitives::{Hash256, HashExt},
+};
+
+/// Version of the snapshot format. Bump this whenever the layout of the
+/// metadata or the chunks changes, so that nodes running different versions
+/// reject each other's snapshots instead of failing halfway through a restore.
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Describes a state snapshot. Sent to peers as the `metadata` field of the
+/// ABCI `Snapshot`, and stored next to the chunks on disk.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotMetadata {
+    /// The block height at which the snapshot was taken.
+    pub version: u64,
+    /// The state root hash at that height.
+    pub root_hash: Hash256,
+    /// SHA-256 hash of each chunk, in order.
+    pub chunk_hashes: Vec<Hash256>,
+}
+
+impl SnapshotMetadata {
+    /// Number of chunks in the snapshot.
+    pub fn chunks(&self) -> u32 {
+        self.chunk_hashes.len() as u32
+    }
+
+    /// Return whether the given bytes are the chunk of the given index.
+    pub fn verify_chunk(&self, index: u32, chunk: &[u8]) -> bool {
+        self.chunk_hashes
+            .get(index as usize)
+            .is_some_and(|hash| *hash == chunk.sha2_256())
+    }
+}
+
+/// Result of applying a single chunk during a snapshot restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyChunkOutcome {
+    /// The chunk has been written; more chunks are expected.
+    Accepted,
+    /// The chunk was the last one; the state has been fully restored and its
+    /// root hash matches the one in the snapshot's metadata.
+    Restored,
+    /// The chunk doesn't match its hash in the metadata. It should be fetched
+    /// again, preferably from a different peer.
+    BadChunk,
+    /// All chunks have been written but the resulting state doesn't match the
+    /// root hash. The partially restored state has been discarded.
+    BadSnapshot,
+}
+
+/// Produces state snapshots for peers to state-sync from, and restores the
+/// state from a snapshot provided by peers.
+///
+/// Attached to the [`App`](crate::App) optionally. Without a snapshotter, the
+/// app neither offers snapshots nor accepts them.
+pub trait Snapshotter: Send + Sync {
+    /// Called after each block is committed. The implementation decides
+    /// whether to take a snapshot at this height.
+    ///
+    /// Taking a snapshot may be slow, so implementations should do the bulk
+    /// of the work in the background instead of blocking the commit.
+    fn on_commit(&self, version: u64) -> AppResult<()>;
+
+    /// List the snapshots available locally, from the oldest to the newest.
+    fn list_snapshots(&self) -> AppResult<Vec<SnapshotMetadata>>;
+
+    /// Load a chunk of a locally available snapshot.
+    ///
+    /// `None` if the snapshot or the chunk doesn't exist.
+    fn load_chunk(&self, version: u64, index: u32) -> AppResult<Option<Vec<u8>>>;
+
+    /// Prepare to restore the state from the given snapshot. The metadata has
+    /// already been checked against the trusted app hash by the caller.
+    ///
+    /// Error if the local state isn't empty.
+    fn begin_restore(&self, metadata: SnapshotMetadata) -> AppResult<()>;
+
+    /// Apply a chunk of the snapshot being restored. Chunks are applied in
+    /// order, starting from zero.
+    fn apply_chunk(&self, index: u32, chunk: &[u8]) -> AppResult<ApplyChunkOutcome>;
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifying_chunks() {
+        let metadata = SnapshotMetadata {
+            version: 123,
+            root_hash: Hash256::ZERO,
+            chunk_hashes: vec![b"foo".sha2_256(), b"bar".sha2_256()],
+        };
+
+        assert_eq!(metadata.chunks(), 2);
+        assert!(metadata.verify_chunk(0, b"foo"));
+        assert!(metadata.verify_chunk(1, b"bar"));
+        assert!(!metadata.verify_chunk(0, b"bar"));
+        assert!(!metadata.verify_chunk(2, b"foo"));
+    }
+}
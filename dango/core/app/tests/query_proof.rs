@@ -0,0 +1,141 @@
+use {
+    dango_app::{
+        App, CONTRACT_NAMESPACE, Db, LAST_FINALIZED_BLOCK, NaiveProposalPreparer, NullIndexer,
+    },
+    dango_db_memory::MemDb,
+    dango_jmt::{MerkleTree, verify_multi_proof},
+    dango_primitives::{
+        Addr, Batch, BlockInfo, BorshDeExt, BorshSerExt, Hash256, HashExt, MultiProof, Op, Query,
+        QueryConfigRequest, QueryResponse, QueryWasmRawRequest, QueryWasmScanRequest, ResultExt,
+        Timestamp,
+    },
+    std::collections::BTreeMap,
+};
+
+const CONTRACT: Addr = Addr::mock(1);
+
+const BLOCK: BlockInfo = BlockInfo {
+    height: 12,
+    timestamp: Timestamp::from_seconds(34),
+    hash: Hash256::ZERO,
+};
+
+fn contract_key(key: &str) -> Vec<u8> {
+    [CONTRACT_NAMESPACE, CONTRACT.as_ref(), key.as_bytes()].concat()
+}
+
+/// Set up an app whose state contains the last finalized block, and a
+/// contract with a few key-value pairs in its storage. Return the app and the
+/// root hash of its state.
+fn setup_app() -> (
+    App<MemDb<MerkleTree>, dango_vm_rust::RustVm, NaiveProposalPreparer, NullIndexer>,
+    Hash256,
+) {
+    let db = MemDb::new();
+
+    let mut batch = Batch::from(
+        [("donald", "trump"), ("jake", "shepherd"), ("joe", "biden")]
+            .map(|(key, value)| (contract_key(key), Op::Insert(value.as_bytes().to_vec()))),
+    );
+
+    batch.insert(
+        LAST_FINALIZED_BLOCK.path().storage_key().to_vec(),
+        Op::Insert(BLOCK.to_borsh_vec().unwrap()),
+    );
+
+    let (_, root_hash) = db.flush_and_commit(batch).unwrap();
+
+    let app = App::new(
+        db,
+        dango_vm_rust::RustVm::new(),
+        NaiveProposalPreparer,
+        NullIndexer,
+        u64::MAX,
+        None,
+        "0.0.0",
+    );
+
+    (app, root_hash.unwrap())
+}
+
+#[test]
+fn query_with_proof_works() {
+    let (app, root_hash) = setup_app();
+
+    let res = app
+        .do_query_app_with_proof(Query::Multi(vec![
+            Query::WasmRaw(QueryWasmRawRequest {
+                contract: CONTRACT,
+                key: b"donald".to_vec().into(),
+            }),
+            Query::WasmRaw(QueryWasmRawRequest {
+                contract: CONTRACT,
+                key: b"larry".to_vec().into(),
+            }),
+            Query::WasmScan(QueryWasmScanRequest {
+                contract: CONTRACT,
+                min: Some(b"j".to_vec().into()),
+                max: Some(b"k".to_vec().into()),
+                limit: None,
+            }),
+        ]))
+        .unwrap();
+
+    assert_eq!(res.height, BLOCK.height);
+    assert_eq!(
+        res.response,
+        QueryResponse::Multi(vec![
+            Ok(QueryResponse::WasmRaw(Some(b"trump".to_vec().into()))),
+            Ok(QueryResponse::WasmRaw(None)),
+            Ok(QueryResponse::WasmScan(BTreeMap::from([
+                (b"jake".to_vec().into(), b"shepherd".to_vec().into()),
+                (b"joe".to_vec().into(), b"biden".to_vec().into()),
+            ]))),
+        ])
+    );
+
+    // The proof covers every key the response was read from, including the
+    // absence of `larry`.
+    let proof: MultiProof = res.proof.deserialize_borsh().unwrap();
+    let items = [
+        ("donald", Some("trump")),
+        ("larry", None),
+        ("jake", Some("shepherd")),
+        ("joe", Some("biden")),
+    ]
+    .map(|(key, value)| {
+        (
+            contract_key(key).sha2_256(),
+            value.map(|value| value.as_bytes().sha2_256()),
+        )
+    });
+
+    assert!(verify_multi_proof(root_hash, &items, &proof).is_ok());
+
+    // A proof of a different value must not verify.
+    let items = [("donald", Some("duck"))].map(|(key, value)| {
+        (
+            contract_key(key).sha2_256(),
+            value.map(|value| value.as_bytes().sha2_256()),
+        )
+    });
+
+    assert!(verify_multi_proof(root_hash, &items, &proof).is_err());
+}
+
+#[test]
+fn query_with_proof_rejects_unprovable_queries() {
+    let (app, _) = setup_app();
+
+    app.do_query_app_with_proof(Query::Config(QueryConfigRequest {}))
+        .should_fail_with_error("merkle proof is only supported");
+
+    app.do_query_app_with_proof(Query::Multi(vec![
+        Query::WasmRaw(QueryWasmRawRequest {
+            contract: CONTRACT,
+            key: b"donald".to_vec().into(),
+        }),
+        Query::Config(QueryConfigRequest {}),
+    ]))
+    .should_fail_with_error("merkle proof is only supported");
+}
@@ -111,6 +111,26 @@ pub(crate) struct Data {
     priority_data: Option<PriorityData>,
 }
 
+impl Data {
+    /// Reload the priority data from disk. Needed after the state has been
+    /// written to RocksDB directly, e.g. when restoring from a snapshot.
+    pub(crate) fn reload_priority_data(&mut self) {
+        let Data { db, priority_data } = self;
+
+        if let Some(priority) = priority_data {
+            priority.records = create_rocksdb_storage_iter(
+                db,
+                Some(&priority.min),
+                Some(&priority.max),
+                Order::Ascending,
+                #[cfg(feature = "metrics")]
+                "priority_data/reload",
+            )
+            .collect();
+        }
+    }
+}
+
 #[derive(Debug)]
 struct PriorityData {
     min: Vec<u8>, // inclusive
@@ -223,6 +243,7 @@ where
     T: Commitment,
 {
     type Error = DbError;
+    type MultiProof = T::MultiProof;
     type Proof = T::Proof;
     type StateCommitment = StateCommitment;
     type StateStorage = StateStorage;
@@ -321,6 +342,16 @@ where
         Ok(T::prove(&self.state_commitment(), key.sha2_256(), version)?)
     }
 
+    fn prove_multi(&self, keys: &[&[u8]], version: Option<u64>) -> DbResult<Self::MultiProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        let key_hashes = keys.iter().map(|key| key.sha2_256()).collect::<Vec<_>>();
+        Ok(T::prove_multi(
+            &self.state_commitment(),
+            &key_hashes,
+            version,
+        )?)
+    }
+
     fn flush_but_not_commit(&self, batch: Batch) -> DbResult<(u64, Option<Hash256>)> {
         #[cfg(feature = "metrics")]
         let duration = std::time::Instant::now();
@@ -1276,7 +1307,7 @@ mod tests_jmt {
     use {
         crate::DiskDb,
         dango_app::Db,
-        dango_jmt::{MerkleTree, verify_proof},
+        dango_jmt::{MerkleTree, verify_multi_proof, verify_proof},
         dango_primitives::{
             Batch, Hash256, HashExt, MembershipProof, NonMembershipProof, Op, Order, Proof,
             ProofNode, Storage,
@@ -1634,6 +1665,43 @@ mod tests_jmt {
                 .is_ok()
             );
         }
+
+        // Prove all keys at once in each version.
+        for (version, root_hash, items) in [
+            (
+                0,
+                v0::ROOT_HASH,
+                [
+                    ("donald", Some("trump")),
+                    ("jake", Some("shepherd")),
+                    ("joe", Some("biden")),
+                    ("larry", Some("engineer")),
+                    ("pumpkin", None),
+                ],
+            ),
+            (
+                1,
+                v1::ROOT_HASH,
+                [
+                    ("donald", Some("duck")),
+                    ("jake", Some("shepherd")),
+                    ("joe", None),
+                    ("larry", Some("engineer")),
+                    ("pumpkin", Some("cat")),
+                ],
+            ),
+        ] {
+            let keys = items.map(|(key, _)| key.as_bytes());
+            let proof = db.prove_multi(&keys, Some(version)).unwrap();
+
+            let items = items.map(|(key, value)| {
+                (
+                    key.as_bytes().sha2_256(),
+                    value.map(|value| value.as_bytes().sha2_256()),
+                )
+            });
+            assert!(verify_multi_proof(root_hash, &items, &proof).is_ok());
+        }
     }
 
     #[test]
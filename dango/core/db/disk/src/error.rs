@@ -1,4 +1,8 @@
-use {dango_app::AppError, dango_backtrace::Backtraceable, dango_primitives::StdError};
+use {
+    dango_app::AppError,
+    dango_backtrace::Backtraceable,
+    dango_primitives::{Hash256, StdError},
+};
 
 #[dango_backtrace::backtrace]
 #[derive(Debug, Clone, thiserror::Error)]
@@ -21,6 +25,33 @@ pub enum DbError {
 
     #[error("key prefixed with `wasm` but is not a wasm key: {}", hex::encode(key))]
     NotWasmKey { key: Vec<u8> },
+
+    #[error("io error: {error}")]
+    Io { error: String },
+
+    #[error("can't take a snapshot of an empty database")]
+    SnapshotEmptyDb,
+
+    #[error("can't restore a snapshot into a non-empty database")]
+    RestoreNonEmptyDb,
+
+    #[error("no snapshot is being restored")]
+    NoRestoreInProgress,
+
+    #[error("expecting snapshot chunk {expected}, got {actual}")]
+    UnexpectedSnapshotChunk { expected: u32, actual: u32 },
+
+    #[error("snapshot chunk {index} doesn't match its hash")]
+    BadSnapshotChunk { index: u32 },
+
+    #[error("restored state doesn't match root hash {root_hash} at version {version}")]
+    SnapshotRootHashMismatch { version: u64, root_hash: Hash256 },
+}
+
+impl From<std::io::Error> for DbError {
+    fn from(err: std::io::Error) -> Self {
+        DbError::io(err.to_string())
+    }
 }
 
 impl From<DbError> for AppError {
@@ -1,8 +1,9 @@
 mod db;
 mod error;
+mod snapshot;
 #[cfg(feature = "metrics")]
 mod statistics;
 
 #[cfg(feature = "metrics")]
 pub use statistics::*;
-pub use {db::*, error::*};
+pub use {db::*, error::*, snapshot::*};
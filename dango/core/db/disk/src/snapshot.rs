@@ -0,0 +1,715 @@
+use {
+    crate::{
+        CF_NAME_DEFAULT, CF_NAME_STATE_COMMITMENT, CF_NAME_STATE_STORAGE, CF_NAME_WASM_STORAGE,
+        DbError, DbResult, DiskDb, LATEST_VERSION_KEY, cf_default, cf_state_commitment,
+        cf_state_storage, cf_wasm_storage, is_wasm_key,
+    },
+    borsh::{BorshDeserialize, BorshSerialize},
+    dango_app::{AppResult, ApplyChunkOutcome, Commitment, Db, SnapshotMetadata, Snapshotter},
+    dango_primitives::{BorshDeExt, BorshSerExt, Hash256, HashExt, StdError},
+    parking_lot::Mutex,
+    rocksdb::{DB, IteratorMode, Options, WriteBatch, checkpoint::Checkpoint},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        thread,
+    },
+};
+
+/// Approximate size of each snapshot chunk, in bytes.
+///
+/// CometBFT limits the size of a chunk to 16 MiB.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
+/// Name of the file that holds the metadata within a snapshot directory.
+const METADATA_FILE_NAME: &str = "metadata";
+
+/// A record in a snapshot chunk.
+///
+/// A chunk is the Borsh encoding of a `Vec<SnapshotItem>`.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+enum SnapshotItem {
+    /// A record in the state storage, including the wasm storage.
+    Storage { key: Vec<u8>, value: Vec<u8> },
+    /// A record in the state commitment.
+    Commitment { key: Vec<u8>, value: Vec<u8> },
+}
+
+impl SnapshotItem {
+    /// Size of the item in the Borsh encoding.
+    fn encoded_size(&self) -> usize {
+        match self {
+            SnapshotItem::Storage { key, value } | SnapshotItem::Commitment { key, value } => {
+                1 + 4 + key.len() + 4 + value.len()
+            },
+        }
+    }
+}
+
+/// A snapshot that is being restored.
+#[derive(Debug)]
+pub struct Restore {
+    metadata: SnapshotMetadata,
+    next_index: u32,
+}
+
+impl<T> DiskDb<T>
+where
+    T: Commitment,
+{
+    /// Export the state at the latest version into a snapshot in the given
+    /// directory.
+    ///
+    /// Holds a read lock on the database until finished, so this is meant for
+    /// nodes that aren't running. A running node uses [`DiskSnapshotter`]
+    /// instead, which exports from a RocksDB checkpoint.
+    pub fn export_snapshot<P>(&self, dir: P) -> DbResult<SnapshotMetadata>
+    where
+        P: AsRef<Path>,
+    {
+        let version = self.latest_version().ok_or(DbError::snapshot_empty_db())?;
+        let root_hash = self
+            .root_hash(Some(version))?
+            .ok_or(DbError::snapshot_empty_db())?;
+
+        let data = self.data.read();
+
+        write_snapshot(&data.db, version, root_hash, dir.as_ref())
+    }
+
+    /// Restore the state from a snapshot in the given directory. The database
+    /// must be empty.
+    ///
+    /// Each chunk is checked against its hash in the metadata, and the restored
+    /// state against the root hash and the commitment's leaves. It's up to the
+    /// caller to check the root hash against a trusted source.
+    pub fn import_snapshot<P>(&self, dir: P) -> DbResult<SnapshotMetadata>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref();
+        let metadata: SnapshotMetadata =
+            fs::read(dir.join(METADATA_FILE_NAME))?.deserialize_borsh()?;
+        let mut restore = self.begin_restore(metadata.clone())?;
+
+        for index in 0..metadata.chunks() {
+            let chunk = fs::read(dir.join(chunk_file_name(index)))?;
+
+            match self.apply_chunk(&mut restore, index, &chunk)? {
+                ApplyChunkOutcome::Accepted | ApplyChunkOutcome::Restored => {},
+                ApplyChunkOutcome::BadChunk => {
+                    self.wipe_state()?;
+                    return Err(DbError::bad_snapshot_chunk(index));
+                },
+                ApplyChunkOutcome::BadSnapshot => {
+                    return Err(DbError::snapshot_root_hash_mismatch(
+                        metadata.version,
+                        metadata.root_hash,
+                    ));
+                },
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Prepare to restore the state from a snapshot. The database must be
+    /// empty; leftovers from an earlier, interrupted restore are discarded.
+    pub fn begin_restore(&self, metadata: SnapshotMetadata) -> DbResult<Restore> {
+        if self.latest_version().is_some() {
+            return Err(DbError::restore_non_empty_db());
+        }
+
+        self.wipe_state()?;
+
+        Ok(Restore {
+            metadata,
+            next_index: 0,
+        })
+    }
+
+    /// Write a snapshot chunk into the database. Chunks must be applied in
+    /// order.
+    ///
+    /// Once the last chunk is written, the state's root hash is checked against
+    /// the metadata, and every restored key-value pair against the state
+    /// commitment. On mismatch, the restored state is discarded. On success,
+    /// the latest version is set, and the database is ready for use.
+    pub fn apply_chunk(
+        &self,
+        restore: &mut Restore,
+        index: u32,
+        chunk: &[u8],
+    ) -> DbResult<ApplyChunkOutcome> {
+        if index != restore.next_index {
+            return Err(DbError::unexpected_snapshot_chunk(
+                restore.next_index,
+                index,
+            ));
+        }
+
+        if !restore.metadata.verify_chunk(index, chunk) {
+            return Ok(ApplyChunkOutcome::BadChunk);
+        }
+
+        let items: Vec<SnapshotItem> = chunk.deserialize_borsh()?;
+
+        {
+            let data = self.data.write();
+            let cf_state = cf_state_storage(&data.db);
+            let cf_wasm = cf_wasm_storage(&data.db);
+            let cf_commitment = cf_state_commitment(&data.db);
+
+            let mut batch = WriteBatch::default();
+            for item in items {
+                match item {
+                    SnapshotItem::Storage { key, value } => {
+                        let cf = if is_wasm_key(&key) {
+                            cf_wasm
+                        } else {
+                            cf_state
+                        };
+                        batch.put_cf(cf, key, value);
+                    },
+                    SnapshotItem::Commitment { key, value } => {
+                        batch.put_cf(cf_commitment, key, value);
+                    },
+                }
+            }
+
+            data.db.write(batch)?;
+        }
+
+        restore.next_index += 1;
+
+        if restore.next_index < restore.metadata.chunks() {
+            return Ok(ApplyChunkOutcome::Accepted);
+        }
+
+        let version = restore.metadata.version;
+
+        if T::root_hash(&self.state_commitment(), version)? != Some(restore.metadata.root_hash) {
+            self.wipe_state()?;
+            return Ok(ApplyChunkOutcome::BadSnapshot);
+        }
+
+        // The root hash only covers the root node of the state commitment.
+        // Make sure the rest of the commitment, as well as the state storage,
+        // match it.
+        let state_matches = {
+            let state_commitment = self.state_commitment();
+            // `state_commitment` already holds a read lock.
+            let data = self.data.read_recursive();
+            let state = [cf_state_storage(&data.db), cf_wasm_storage(&data.db)]
+                .into_iter()
+                .flat_map(|cf| data.db.iterator_cf(cf, IteratorMode::Start))
+                .map(|record| {
+                    record
+                        .map(|(key, value)| (key.into_vec(), value.into_vec()))
+                        .map_err(|err| StdError::host(err.to_string()))
+                });
+
+            T::verify_state(&state_commitment, version, state)?
+        };
+
+        if !state_matches {
+            self.wipe_state()?;
+            return Ok(ApplyChunkOutcome::BadSnapshot);
+        }
+
+        let mut data = self.data.write();
+        data.db.put_cf(
+            cf_default(&data.db),
+            LATEST_VERSION_KEY,
+            version.to_le_bytes(),
+        )?;
+        data.reload_priority_data();
+
+        Ok(ApplyChunkOutcome::Restored)
+    }
+
+    /// Delete all records from the state storage and state commitment.
+    fn wipe_state(&self) -> DbResult<()> {
+        let data = self.data.write();
+
+        let mut batch = WriteBatch::default();
+        for cf in [
+            cf_state_storage(&data.db),
+            cf_wasm_storage(&data.db),
+            cf_state_commitment(&data.db),
+        ] {
+            for record in data.db.iterator_cf(cf, IteratorMode::Start) {
+                let (key, _) = record?;
+                batch.delete_cf(cf, key);
+            }
+        }
+
+        data.db.write(batch)?;
+
+        Ok(())
+    }
+}
+
+/// Takes snapshots of a [`DiskDb`] at regular intervals, serves them to peers,
+/// and restores the database from snapshots provided by peers.
+///
+/// Snapshots are stored in the given directory, one sub-directory per version:
+///
+/// ```plain
+/// {dir}/{version}/metadata
+/// {dir}/{version}/chunk-0
+/// {dir}/{version}/chunk-1
+/// ...
+/// ```
+///
+/// To avoid blocking the chain, a snapshot is exported from a RocksDB
+/// checkpoint in a background thread. The checkpoint consists of hard links to
+/// the database files, so `dir` should be on the same filesystem as the
+/// database.
+pub struct DiskSnapshotter<T> {
+    db: DiskDb<T>,
+    dir: PathBuf,
+    /// Take a snapshot every this many blocks. `0` disables taking snapshots.
+    interval: u64,
+    /// Number of most recent snapshots to keep.
+    keep_recent: usize,
+    /// Whether a snapshot is being exported in the background.
+    exporting: Arc<AtomicBool>,
+    /// The snapshot being restored, if any.
+    restore: Mutex<Option<Restore>>,
+}
+
+impl<T> DiskSnapshotter<T> {
+    pub fn new<P>(db: DiskDb<T>, dir: P, interval: u64, keep_recent: usize) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            db,
+            dir: dir.into(),
+            interval,
+            keep_recent,
+            exporting: Arc::new(AtomicBool::new(false)),
+            restore: Mutex::new(None),
+        }
+    }
+
+    fn take_snapshot(&self, version: u64, root_hash: Hash256) -> DbResult<()> {
+        // Skip this snapshot if the previous one is still being exported.
+        if self.exporting.swap(true, Ordering::SeqCst) {
+            #[cfg(feature = "tracing")]
+            {
+                tracing::warn!(
+                    version,
+                    "Previous snapshot is still being exported; skipping"
+                );
+            }
+
+            return Ok(());
+        }
+
+        let checkpoint_dir = self.dir.join(format!("{version}.checkpoint"));
+
+        if let Err(err) = self.create_checkpoint(&checkpoint_dir) {
+            self.exporting.store(false, Ordering::SeqCst);
+            return Err(err);
+        }
+
+        let dir = self.dir.clone();
+        let keep_recent = self.keep_recent;
+        let exporting = self.exporting.clone();
+
+        thread::spawn(move || {
+            let _result = export_checkpoint(&checkpoint_dir, &dir, version, root_hash);
+
+            #[cfg(feature = "tracing")]
+            {
+                match &_result {
+                    Ok(metadata) => {
+                        tracing::info!(version, chunks = metadata.chunks(), "Took snapshot");
+                    },
+                    Err(err) => {
+                        tracing::error!(version, %err, "Failed to take snapshot");
+                    },
+                }
+            }
+
+            let _ = fs::remove_dir_all(&checkpoint_dir);
+            let _ = prune_snapshots(&dir, keep_recent);
+
+            exporting.store(false, Ordering::SeqCst);
+        });
+
+        Ok(())
+    }
+
+    /// Create a RocksDB checkpoint of the database at its current version.
+    fn create_checkpoint(&self, checkpoint_dir: &Path) -> DbResult<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        if checkpoint_dir.exists() {
+            fs::remove_dir_all(checkpoint_dir)?;
+        }
+
+        // Hold the read lock so that no block is committed in the meantime.
+        let data = self.db.data.read();
+        Checkpoint::new(&data.db)?.create_checkpoint(checkpoint_dir)?;
+
+        Ok(())
+    }
+}
+
+impl<T> Snapshotter for DiskSnapshotter<T>
+where
+    T: Commitment + Send + Sync,
+{
+    fn on_commit(&self, version: u64) -> AppResult<()> {
+        if self.interval == 0 || version % self.interval != 0 {
+            return Ok(());
+        }
+
+        let Some(root_hash) = self.db.root_hash(Some(version))? else {
+            return Ok(());
+        };
+
+        Ok(self.take_snapshot(version, root_hash)?)
+    }
+
+    fn list_snapshots(&self) -> AppResult<Vec<SnapshotMetadata>> {
+        Ok(list_snapshot_versions(&self.dir)?
+            .into_iter()
+            .map(|version| {
+                fs::read(self.dir.join(version.to_string()).join(METADATA_FILE_NAME))?
+                    .deserialize_borsh()
+                    .map_err(DbError::from)
+            })
+            .collect::<DbResult<_>>()?)
+    }
+
+    fn load_chunk(&self, version: u64, index: u32) -> AppResult<Option<Vec<u8>>> {
+        let path = self
+            .dir
+            .join(version.to_string())
+            .join(chunk_file_name(index));
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(fs::read(path).map_err(DbError::from)?))
+    }
+
+    fn begin_restore(&self, metadata: SnapshotMetadata) -> AppResult<()> {
+        let restore = self.db.begin_restore(metadata)?;
+
+        *self.restore.lock() = Some(restore);
+
+        Ok(())
+    }
+
+    fn apply_chunk(&self, index: u32, chunk: &[u8]) -> AppResult<ApplyChunkOutcome> {
+        let mut guard = self.restore.lock();
+        let restore = guard.as_mut().ok_or(DbError::no_restore_in_progress())?;
+
+        let outcome = self.db.apply_chunk(restore, index, chunk)?;
+
+        if matches!(
+            outcome,
+            ApplyChunkOutcome::Restored | ApplyChunkOutcome::BadSnapshot
+        ) {
+            *guard = None;
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Write the snapshot of a RocksDB checkpoint into `{dir}/{version}`.
+///
+/// The snapshot is first written into a temporary directory, then moved into
+/// place, so that an incomplete snapshot is never served.
+fn export_checkpoint(
+    checkpoint_dir: &Path,
+    dir: &Path,
+    version: u64,
+    root_hash: Hash256,
+) -> DbResult<SnapshotMetadata> {
+    let tmp_dir = dir.join(format!("{version}.tmp"));
+    let final_dir = dir.join(version.to_string());
+
+    if tmp_dir.exists() {
+        fs::remove_dir_all(&tmp_dir)?;
+    }
+
+    let metadata = {
+        let db = DB::open_cf_for_read_only(
+            &Options::default(),
+            checkpoint_dir,
+            [
+                CF_NAME_DEFAULT,
+                CF_NAME_STATE_STORAGE,
+                CF_NAME_STATE_COMMITMENT,
+                CF_NAME_WASM_STORAGE,
+            ],
+            false,
+        )?;
+
+        write_snapshot(&db, version, root_hash, &tmp_dir)?
+    };
+
+    if final_dir.exists() {
+        fs::remove_dir_all(&final_dir)?;
+    }
+
+    fs::rename(&tmp_dir, &final_dir)?;
+
+    Ok(metadata)
+}
+
+/// Write the state storage and state commitment of the given RocksDB instance
+/// into a snapshot in the given directory.
+fn write_snapshot(
+    db: &DB,
+    version: u64,
+    root_hash: Hash256,
+    dir: &Path,
+) -> DbResult<SnapshotMetadata> {
+    fs::create_dir_all(dir)?;
+
+    let mut writer = ChunkWriter::new(dir);
+
+    for cf in [cf_state_storage(db), cf_wasm_storage(db)] {
+        for record in db.iterator_cf(cf, IteratorMode::Start) {
+            let (key, value) = record?;
+            writer.push(SnapshotItem::Storage {
+                key: key.into(),
+                value: value.into(),
+            })?;
+        }
+    }
+
+    let cf = cf_state_commitment(db);
+    for record in db.iterator_cf(cf, IteratorMode::Start) {
+        let (key, value) = record?;
+        writer.push(SnapshotItem::Commitment {
+            key: key.into(),
+            value: value.into(),
+        })?;
+    }
+
+    let metadata = SnapshotMetadata {
+        version,
+        root_hash,
+        chunk_hashes: writer.finish()?,
+    };
+
+    fs::write(dir.join(METADATA_FILE_NAME), metadata.to_borsh_vec()?)?;
+
+    Ok(metadata)
+}
+
+/// Delete all but the most recent `keep_recent` snapshots in the directory.
+fn prune_snapshots(dir: &Path, keep_recent: usize) -> DbResult<()> {
+    let versions = list_snapshot_versions(dir)?;
+
+    for version in versions.iter().rev().skip(keep_recent) {
+        fs::remove_dir_all(dir.join(version.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// List the versions of the complete snapshots in the directory, in ascending
+/// order. Temporary directories and checkpoints are ignored.
+fn list_snapshot_versions(dir: &Path) -> DbResult<Vec<u64>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut versions = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if !path.join(METADATA_FILE_NAME).is_file() {
+                return None;
+            }
+
+            path.file_name()?.to_str()?.parse::<u64>().ok()
+        })
+        .collect::<Vec<_>>();
+
+    versions.sort();
+
+    Ok(versions)
+}
+
+fn chunk_file_name(index: u32) -> String {
+    format!("chunk-{index}")
+}
+
+/// Groups snapshot items into chunks of approximately `SNAPSHOT_CHUNK_SIZE`
+/// bytes, and writes each chunk into a file.
+struct ChunkWriter<'a> {
+    dir: &'a Path,
+    items: Vec<SnapshotItem>,
+    size: usize,
+    chunk_hashes: Vec<Hash256>,
+}
+
+impl<'a> ChunkWriter<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Self {
+            dir,
+            items: Vec::new(),
+            size: 0,
+            chunk_hashes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, item: SnapshotItem) -> DbResult<()> {
+        self.size += item.encoded_size();
+        self.items.push(item);
+
+        if self.size >= SNAPSHOT_CHUNK_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> DbResult<()> {
+        let chunk = std::mem::take(&mut self.items).to_borsh_vec()?;
+        let index = self.chunk_hashes.len() as u32;
+
+        fs::write(self.dir.join(chunk_file_name(index)), &chunk)?;
+
+        self.chunk_hashes.push(chunk.sha2_256());
+        self.size = 0;
+
+        Ok(())
+    }
+
+    /// Write the remaining items, and return the hashes of all chunks.
+    ///
+    /// A snapshot has at least one chunk, even if the state is empty.
+    fn finish(mut self) -> DbResult<Vec<Hash256>> {
+        if !self.items.is_empty() || self.chunk_hashes.is_empty() {
+            self.flush()?;
+        }
+
+        Ok(self.chunk_hashes)
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_jmt::MerkleTree,
+        dango_primitives::{Batch, Op, Storage},
+        dango_temp_rocksdb::TempDataDir,
+    };
+
+    #[test]
+    fn snapshot_roundtrip_works() {
+        let path = TempDataDir::new("_dango_snapshot_roundtrip_works");
+        let db = DiskDb::<MerkleTree>::open(&path).unwrap();
+
+        // A key in the wasm storage: `b"wasm" | address | sub_key`.
+        let wasm_key = [b"wasm".as_slice(), &[0; 20], b"foo"].concat();
+
+        for version in 0..3_u8 {
+            let batch = Batch::from([
+                (vec![b'k', version], Op::Insert(vec![version])),
+                (wasm_key.clone(), Op::Insert(vec![version])),
+            ]);
+            db.flush_and_commit(batch).unwrap();
+        }
+
+        let snapshot_path = TempDataDir::new("_dango_snapshot_roundtrip_works_snapshot");
+        let metadata = db.export_snapshot(&snapshot_path).unwrap();
+
+        assert_eq!(metadata.version, 2);
+        assert_eq!(Some(metadata.root_hash), db.root_hash(None).unwrap());
+
+        // Restoring into a non-empty database is rejected.
+        assert!(db.import_snapshot(&snapshot_path).is_err());
+
+        // Restore into an empty database.
+        let restored_path = TempDataDir::new("_dango_snapshot_roundtrip_works_restored");
+        let restored = DiskDb::<MerkleTree>::open(&restored_path).unwrap();
+
+        restored.import_snapshot(&snapshot_path).unwrap();
+
+        assert_eq!(restored.latest_version(), Some(2));
+        assert_eq!(
+            restored.root_hash(None).unwrap(),
+            db.root_hash(None).unwrap()
+        );
+
+        let storage = restored.state_storage(None).unwrap();
+        assert_eq!(storage.read(b"k\x01"), Some(vec![1]));
+        assert_eq!(storage.read(&wasm_key), Some(vec![2]));
+
+        // A chunk that doesn't match its hash is rejected.
+        let corrupted_path = TempDataDir::new("_dango_snapshot_roundtrip_works_corrupted");
+        let corrupted = DiskDb::<MerkleTree>::open(&corrupted_path).unwrap();
+        let mut restore = corrupted.begin_restore(metadata).unwrap();
+
+        assert_eq!(
+            corrupted.apply_chunk(&mut restore, 0, b"garbage").unwrap(),
+            ApplyChunkOutcome::BadChunk
+        );
+        assert_eq!(corrupted.latest_version(), None);
+    }
+
+    #[test]
+    fn tampered_state_is_rejected() {
+        let path = TempDataDir::new("_dango_tampered_state_is_rejected");
+        let db = DiskDb::<MerkleTree>::open(&path).unwrap();
+
+        db.flush_and_commit(Batch::from([
+            (b"foo".to_vec(), Op::Insert(b"bar".to_vec())),
+            (b"fuzz".to_vec(), Op::Insert(b"buzz".to_vec())),
+        ]))
+        .unwrap();
+
+        let snapshot_path = TempDataDir::new("_dango_tampered_state_is_rejected_snapshot");
+        let metadata = db.export_snapshot(&snapshot_path).unwrap();
+
+        // Alter a value in the state storage, leaving the state commitment
+        // intact. Update the chunk hash so the chunk itself is accepted.
+        let chunk = fs::read((&snapshot_path).as_ref().join(chunk_file_name(0))).unwrap();
+        let mut items: Vec<SnapshotItem> = chunk.deserialize_borsh().unwrap();
+
+        for item in &mut items {
+            if let SnapshotItem::Storage { key, value } = item
+                && *key == b"foo"
+            {
+                *value = b"baz".to_vec();
+            }
+        }
+
+        let chunk = items.to_borsh_vec().unwrap();
+        let metadata = SnapshotMetadata {
+            chunk_hashes: vec![chunk.sha2_256()],
+            ..metadata
+        };
+
+        let restored_path = TempDataDir::new("_dango_tampered_state_is_rejected_restored");
+        let restored = DiskDb::<MerkleTree>::open(&restored_path).unwrap();
+        let mut restore = restored.begin_restore(metadata).unwrap();
+
+        assert_eq!(
+            restored.apply_chunk(&mut restore, 0, &chunk).unwrap(),
+            ApplyChunkOutcome::BadSnapshot
+        );
+        assert_eq!(restored.latest_version(), None);
+        assert_eq!(restored.state_storage(None).unwrap().read(b"foo"), None);
+    }
+}
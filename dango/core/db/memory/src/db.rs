@@ -125,6 +125,7 @@ where
     T: Commitment,
 {
     type Error = DbError;
+    type MultiProof = T::MultiProof;
     type Proof = T::Proof;
     type StateCommitment = StateCommitment;
     type StateStorage = StateStorage;
@@ -177,6 +178,16 @@ where
         Ok(T::prove(&self.state_commitment(), key.sha2_256(), version)?)
     }
 
+    fn prove_multi(&self, keys: &[&[u8]], version: Option<u64>) -> DbResult<Self::MultiProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        let key_hashes = keys.iter().map(|key| key.sha2_256()).collect::<Vec<_>>();
+        Ok(T::prove_multi(
+            &self.state_commitment(),
+            &key_hashes,
+            version,
+        )?)
+    }
+
     // Note on implementing this function: We must make sure that we don't
     // attempt to lock the DB (either read or write) inside the `with_write`
     // callback. Doing so will result in error:
@@ -392,6 +403,81 @@ impl<'a, S, T> Iterator for SharedIter<'a, S, T> {
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
-mod test {
-    // TODO
+mod tests {
+    use {
+        super::*,
+        dango_jmt::{MerkleTree, verify_multi_proof},
+    };
+
+    // hash(donald)  = 01000001...
+    // hash(jake)    = 11001101...
+    // hash(joe)     = 01111000...
+    // hash(larry)   = 00001101...
+    // hash(pumpkin) = 11111111...
+    fn setup_db() -> (MemDb<MerkleTree>, Hash256, Hash256) {
+        let db = MemDb::new();
+
+        let (_, root_hash_0) = db
+            .flush_and_commit(Batch::from([
+                (b"donald".to_vec(), Op::Insert(b"trump".to_vec())),
+                (b"jake".to_vec(), Op::Insert(b"shepherd".to_vec())),
+                (b"joe".to_vec(), Op::Insert(b"biden".to_vec())),
+                (b"larry".to_vec(), Op::Insert(b"engineer".to_vec())),
+            ]))
+            .unwrap();
+
+        let (_, root_hash_1) = db
+            .flush_and_commit(Batch::from([
+                (b"donald".to_vec(), Op::Insert(b"duck".to_vec())),
+                (b"joe".to_vec(), Op::Delete),
+                (b"pumpkin".to_vec(), Op::Insert(b"cat".to_vec())),
+            ]))
+            .unwrap();
+
+        (db, root_hash_0.unwrap(), root_hash_1.unwrap())
+    }
+
+    fn hash_items<'a, const N: usize>(
+        items: [(&'a str, Option<&'a str>); N],
+    ) -> [(Hash256, Option<Hash256>); N] {
+        items.map(|(key, value)| (key.sha2_256(), value.map(|value| value.sha2_256())))
+    }
+
+    #[test]
+    fn proving_multiple_keys_works() {
+        let (db, root_hash_0, root_hash_1) = setup_db();
+
+        for (version, root_hash, items) in [
+            (
+                0,
+                root_hash_0,
+                [
+                    ("donald", Some("trump")),
+                    ("joe", Some("biden")),
+                    ("pumpkin", None),
+                ],
+            ),
+            (
+                1,
+                root_hash_1,
+                [
+                    ("donald", Some("duck")),
+                    ("joe", None),
+                    ("pumpkin", Some("cat")),
+                ],
+            ),
+        ] {
+            let keys = items.map(|(key, _)| key.as_bytes());
+            let proof = db.prove_multi(&keys, Some(version)).unwrap();
+
+            assert!(verify_multi_proof(root_hash, &hash_items(items), &proof).is_ok());
+        }
+
+        // Without a version, the latest one is proven.
+        let proof = db.prove_multi(&[b"donald"], None).unwrap();
+        let items = hash_items([("donald", Some("duck"))]);
+
+        assert!(verify_multi_proof(root_hash_1, &items, &proof).is_ok());
+        assert!(verify_multi_proof(root_hash_0, &items, &proof).is_err());
+    }
 }
@@ -1,7 +1,13 @@
+#[cfg(feature = "std")]
+use dango_primitives::Inner;
 use {
-    dango_primitives::{Hash256, Inner, Order, StdResult, split_one_key},
+    crate::{Hash256, Order},
+    core::fmt,
+};
+#[cfg(feature = "tree")]
+use {
+    dango_primitives::{StdResult, split_one_key},
     dango_storage::{PrimaryKey, RawKey},
-    std::fmt,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -125,6 +131,25 @@ impl BitArray {
         self.num_bits += 1;
     }
 
+    /// The smallest and the largest hashes that start with these bits. In
+    /// other words, the range of key hashes that can be found in the subtree
+    /// at this bit path.
+    pub fn hash_range(&self) -> (Hash256, Hash256) {
+        let mut min = [0; Self::MAX_BYTE_LENGTH];
+        let mut max = [0xff; Self::MAX_BYTE_LENGTH];
+
+        for (index, bit) in self.range(None, None, Order::Ascending).enumerate() {
+            let (quotient, remainder) = (index / 8, index % 8);
+            if bit == 1 {
+                min[quotient] |= 0b1 << (7 - remainder);
+            } else {
+                max[quotient] &= !(0b1 << (7 - remainder));
+            }
+        }
+
+        (Hash256::from_inner(min), Hash256::from_inner(max))
+    }
+
     /// Iterate the bits in the index range. `min` is inclusive, `max` exclusive.
     /// If min >= max, an empty iterator is returned.
     pub fn range(&self, min: Option<usize>, max: Option<usize>, order: Order) -> BitIterator<'_> {
@@ -142,7 +167,7 @@ impl fmt::Debug for BitArray {
         }
 
         for bit in self.range(None, None, Order::Ascending) {
-            f.write_str(&bit.to_string())?;
+            write!(f, "{bit}")?;
         }
 
         Ok(())
@@ -164,6 +189,7 @@ impl PartialEq<Hash256> for BitArray {
     }
 }
 
+#[cfg(feature = "tree")]
 impl<'a> PrimaryKey for &'a BitArray {
     type Output = BitArray;
     type Prefix = u16;
@@ -297,6 +323,35 @@ mod tests {
     }
 
     proptest! {
+        /// The hash range of a bit path must contain exactly the hashes that
+        /// start with the path.
+        #[test]
+        fn computing_hash_range(
+            num_bits in 0..=BitArray::MAX_BIT_LENGTH,
+            bytes in any::<[u8; BitArray::MAX_BYTE_LENGTH]>(),
+        ) {
+            let hash = Hash256::from_inner(bytes);
+            let full = BitArray::from(hash);
+            let prefix = BitArray::from_bits(
+                &full.range(None, Some(num_bits), Order::Ascending).collect::<Vec<_>>(),
+            );
+            let (min, max) = prefix.hash_range();
+
+            prop_assert!(min <= hash && hash <= max);
+
+            // The bounds themselves start with the path.
+            for bound in [min, max] {
+                let bound = BitArray::from(bound);
+                prop_assert!(bound
+                    .range(None, Some(num_bits), Order::Ascending)
+                    .eq(full.range(None, Some(num_bits), Order::Ascending)));
+            }
+
+            // All bits after the path are 0 in the min and 1 in the max.
+            prop_assert!(BitArray::from(min).range(Some(num_bits), None, Order::Ascending).all(|bit| bit == 0));
+            prop_assert!(BitArray::from(max).range(Some(num_bits), None, Order::Ascending).all(|bit| bit == 1));
+        }
+
         /// Generate 256 random bits, push them one-by-one into the BitArray,
         /// then retrieve them one-by-one. The retrieved msut match the original.
         #[test]
@@ -1,6 +1,21 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 mod bitarray;
 mod node;
 mod proof;
+#[cfg(feature = "tree")]
 mod tree;
+#[cfg(not(feature = "std"))]
+mod types;
 
-pub use crate::{bitarray::*, node::*, proof::*, tree::*};
+#[cfg(feature = "tree")]
+pub use crate::tree::*;
+#[cfg(not(feature = "std"))]
+pub use crate::types::*;
+pub use crate::{bitarray::*, node::*, proof::*};
+#[cfg(feature = "std")]
+use dango_primitives::{
+    Hash256, HashExt, MembershipProof, MultiProof, NonMembershipProof, Order, Proof, ProofNode,
+};
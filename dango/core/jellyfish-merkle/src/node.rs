@@ -1,6 +1,7 @@
 use {
+    crate::{Hash256, HashExt},
+    alloc::vec::Vec,
     borsh::{BorshDeserialize, BorshSerialize},
-    dango_primitives::{Hash256, HashExt},
 };
 
 pub const INTERNAL_NODE_HASH_PREFIX: &[u8] = &[0];
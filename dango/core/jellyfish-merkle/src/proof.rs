@@ -1,6 +1,8 @@
 use {
-    crate::{BitArray, hash_internal_node, hash_leaf_node},
-    dango_primitives::{Hash256, MembershipProof, NonMembershipProof, Order, Proof, ProofNode},
+    crate::{
+        BitArray, Hash256, MembershipProof, MultiProof, NonMembershipProof, Order, Proof,
+        ProofNode, hash_internal_node, hash_leaf_node,
+    },
     thiserror::Error,
 };
 
@@ -22,6 +24,22 @@ pub enum ProofError {
     // TODO: add more details to the error message?
     #[error("expecting bitarrays to share a common prefix but they do not")]
     NotCommonPrefix,
+
+    #[error(
+        "proof is deeper than the maximum of {} levels",
+        BitArray::MAX_BIT_LENGTH
+    )]
+    TooDeep,
+
+    #[error("key hash {key_hash} is in a pruned subtree of the proof")]
+    KeyNotCovered { key_hash: Hash256 },
+
+    #[error("value hash mismatch for key hash {key_hash}! expect: {expect:?}, actual: {actual:?}")]
+    ValueHashMismatch {
+        key_hash: Hash256,
+        expect: Option<Hash256>,
+        actual: Option<Hash256>,
+    },
 }
 
 pub fn verify_proof(
@@ -123,9 +141,124 @@ fn compute_and_compare_root_hash(
         }
     }
 
-    if hash != root_hash {
+    compare_root_hash(root_hash, hash)
+}
+
+/// Verify a multi-proof against the existence or non-existence of each of the
+/// given keys.
+///
+/// Each item is a key hash and, if the key exists, the hash of its value.
+/// Keys that aren't given here may also be covered by the proof; they are
+/// simply not checked.
+pub fn verify_multi_proof(
+    root_hash: Hash256,
+    items: &[(Hash256, Option<Hash256>)],
+    proof: &MultiProof,
+) -> Result<(), ProofError> {
+    compare_root_hash(root_hash, compute_multi_proof_hash(proof, 0)?)?;
+
+    for (key_hash, value_hash) in items {
+        let actual = find_in_multi_proof(proof, *key_hash)?;
+        if actual != *value_hash {
+            return Err(ProofError::ValueHashMismatch {
+                key_hash: *key_hash,
+                expect: *value_hash,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn compute_multi_proof_hash(proof: &MultiProof, depth: usize) -> Result<Hash256, ProofError> {
+    match proof {
+        MultiProof::Internal { left, right } => {
+            // An internal node can't be at the maximum depth, as its children
+            // would have bit paths longer than a key hash.
+            if depth >= BitArray::MAX_BIT_LENGTH {
+                return Err(ProofError::TooDeep);
+            }
+
+            let left_hash = left
+                .as_deref()
+                .map(|child| compute_multi_proof_hash(child, depth + 1))
+                .transpose()?;
+            let right_hash = right
+                .as_deref()
+                .map(|child| compute_multi_proof_hash(child, depth + 1))
+                .transpose()?;
+
+            Ok(hash_internal_node(left_hash, right_hash))
+        },
+        MultiProof::Leaf {
+            key_hash,
+            value_hash,
+        } => Ok(hash_leaf_node(*key_hash, *value_hash)),
+        MultiProof::Pruned { hash } => Ok(*hash),
+    }
+}
+
+/// Follow the path of the key hash in the proof. Return the value hash if the
+/// key exists, or `None` if it doesn't.
+///
+/// The proof must have been checked with `compute_multi_proof_hash`, which
+/// ensures the path is no longer than a key hash.
+fn find_in_multi_proof(
+    mut proof: &MultiProof,
+    key_hash: Hash256,
+) -> Result<Option<Hash256>, ProofError> {
+    let bitarray = BitArray::from_bytes(&key_hash);
+    let mut depth = 0;
+
+    loop {
+        match proof {
+            // Move on to the child based on the bit at the depth. If the child
+            // doesn't exist, neither does the key.
+            MultiProof::Internal { left, right } => {
+                let child = if bitarray.bit_at_index(depth) == 0 {
+                    left
+                } else {
+                    right
+                };
+
+                let Some(child) = child else {
+                    return Ok(None);
+                };
+
+                proof = child;
+                depth += 1;
+            },
+            // If the leaf isn't the key, then the key doesn't exist. As with
+            // non-membership proofs, the leaf must share the path with the key.
+            MultiProof::Leaf {
+                key_hash: leaf_key_hash,
+                value_hash,
+            } => {
+                if *leaf_key_hash == key_hash {
+                    return Ok(Some(*value_hash));
+                }
+
+                let leaf_bitarray = BitArray::from_bytes(leaf_key_hash);
+                let bits = bitarray.range(None, Some(depth), Order::Ascending);
+                let leaf_bits = leaf_bitarray.range(None, Some(depth), Order::Ascending);
+                if bits.ne(leaf_bits) {
+                    return Err(ProofError::NotCommonPrefix);
+                }
+
+                return Ok(None);
+            },
+            MultiProof::Pruned { .. } => {
+                return Err(ProofError::KeyNotCovered { key_hash });
+            },
+        }
+    }
+}
+
+fn compare_root_hash(root_hash: Hash256, computed: Hash256) -> Result<(), ProofError> {
+    if computed != root_hash {
         return Err(ProofError::RootHashMismatch {
-            computed: hash,
+            computed,
             actual: root_hash,
         });
     }
@@ -137,12 +270,7 @@ fn compute_and_compare_root_hash(
 
 #[cfg(test)]
 mod tests {
-    use {
-        super::*,
-        dango_primitives::{Hash256, HashExt, NonMembershipProof},
-        hex_literal::hex,
-        test_case::test_case,
-    };
+    use {super::*, crate::HashExt, hex_literal::hex, test_case::test_case};
 
     // use the same test case as in tree.rs
     const HASH_ROOT: Hash256 = Hash256::from_inner(hex!(
@@ -2,8 +2,8 @@ use {
     crate::{BitArray, Child, InternalNode, LeafNode, Node},
     dango_app::Commitment,
     dango_primitives::{
-        Batch, Hash256, HashExt, MembershipProof, NonMembershipProof, Op, Order, Proof, ProofNode,
-        StdResult, Storage,
+        Batch, Hash256, HashExt, MembershipProof, MultiProof, NonMembershipProof, Op, Order, Proof,
+        ProofNode, Record, StdResult, Storage,
     },
     dango_storage::{Map, PrefixBound, Set},
 };
@@ -44,6 +44,7 @@ enum Outcome {
 pub struct MerkleTree;
 
 impl Commitment for MerkleTree {
+    type MultiProof = MultiProof;
     type Proof = Proof;
 
     /// Get the root hash at the given version. Use latest version if unspecified.
@@ -192,6 +193,64 @@ impl Commitment for MerkleTree {
         }
     }
 
+    /// Generate a single Merkle proof for multiple keys at the given version.
+    ///
+    /// The proof contains the paths to each of the keys, with the subtrees
+    /// that don't contain any of them pruned. Like `prove`, it proves either
+    /// membership or non-membership of each key, and fails with "data not
+    /// found" if the tree is empty.
+    fn prove_multi(
+        storage: &dyn Storage,
+        key_hashes: &[Hash256],
+        version: u64,
+    ) -> StdResult<MultiProof> {
+        let mut key_hashes = key_hashes.to_vec();
+        key_hashes.sort();
+        key_hashes.dedup();
+
+        // A subtree is relevant if any of the keys falls in its range.
+        prove_subtree(storage, version, &|min, max| {
+            let index = key_hashes.partition_point(|key_hash| *key_hash < min);
+            key_hashes
+                .get(index)
+                .is_some_and(|key_hash| *key_hash <= max)
+        })
+    }
+
+    /// Check that the given key-value pairs are exactly the leaves of the tree
+    /// at the given version.
+    ///
+    /// First, every node reachable from the root must hash to what its parent
+    /// records, so that the nodes are authenticated by the root hash. Then,
+    /// each pair is hashed and looked up in the tree; its value hash must match
+    /// the leaf's. Since keys are unique, the pairs cover the entire tree if
+    /// their number equals the number of leaves.
+    fn verify_state<I>(storage: &dyn Storage, version: u64, state: I) -> StdResult<bool>
+    where
+        I: IntoIterator<Item = StdResult<Record>>,
+    {
+        let num_leaves = match NODES.may_load(storage, (version, &ROOT_BITS))? {
+            Some(root_node) => match count_authentic_leaves(storage, ROOT_BITS, root_node)? {
+                Some(num_leaves) => num_leaves,
+                None => return Ok(false),
+            },
+            None => 0,
+        };
+
+        let mut count = 0;
+
+        for record in state {
+            let (key, value) = record?;
+
+            match find_leaf(storage, key.sha2_256(), version)? {
+                Some(leaf) if leaf.value_hash == value.sha2_256() => count += 1,
+                _ => return Ok(false),
+            }
+        }
+
+        Ok(count == num_leaves)
+    }
+
     /// Delete nodes that are no longer part of the tree as of `up_to_version`.
     ///
     /// Note: We must make sure `up_to_version` is smaller or equal to the
@@ -605,6 +664,162 @@ fn bit_at_index(bytes: &[u8], index: usize) -> u8 {
     (byte >> (7 - remainder)) & 0b1
 }
 
+/// Generate a multi-proof of the tree at the given version.
+///
+/// `is_relevant` is given the range of key hashes that a subtree covers, and
+/// decides whether the subtree should be expanded or pruned.
+fn prove_subtree<F>(storage: &dyn Storage, version: u64, is_relevant: &F) -> StdResult<MultiProof>
+where
+    F: Fn(Hash256, Hash256) -> bool,
+{
+    let root_node = NODES.load(storage, (version, &ROOT_BITS))?;
+    let (min, max) = ROOT_BITS.hash_range();
+
+    if !is_relevant(min, max) {
+        return Ok(MultiProof::Pruned {
+            hash: root_node.hash(),
+        });
+    }
+
+    prove_node(storage, ROOT_BITS, root_node, is_relevant)
+}
+
+fn prove_node<F>(
+    storage: &dyn Storage,
+    bits: BitArray,
+    node: Node,
+    is_relevant: &F,
+) -> StdResult<MultiProof>
+where
+    F: Fn(Hash256, Hash256) -> bool,
+{
+    match node {
+        Node::Leaf(LeafNode {
+            key_hash,
+            value_hash,
+        }) => Ok(MultiProof::Leaf {
+            key_hash,
+            value_hash,
+        }),
+        Node::Internal(InternalNode {
+            left_child,
+            right_child,
+        }) => Ok(MultiProof::Internal {
+            left: prove_child(storage, bits.extend_one_bit(true), left_child, is_relevant)?,
+            right: prove_child(
+                storage,
+                bits.extend_one_bit(false),
+                right_child,
+                is_relevant,
+            )?,
+        }),
+    }
+}
+
+fn prove_child<F>(
+    storage: &dyn Storage,
+    bits: BitArray,
+    child: Option<Child>,
+    is_relevant: &F,
+) -> StdResult<Option<Box<MultiProof>>>
+where
+    F: Fn(Hash256, Hash256) -> bool,
+{
+    let Some(child) = child else {
+        return Ok(None);
+    };
+
+    let (min, max) = bits.hash_range();
+
+    if !is_relevant(min, max) {
+        return Ok(Some(Box::new(MultiProof::Pruned { hash: child.hash })));
+    }
+
+    let node = NODES.load(storage, (child.version, &bits))?;
+
+    prove_node(storage, bits, node, is_relevant).map(|proof| Some(Box::new(proof)))
+}
+
+/// Find the leaf of the given key hash in the tree at the given version.
+fn find_leaf(
+    storage: &dyn Storage,
+    key_hash: Hash256,
+    version: u64,
+) -> StdResult<Option<LeafNode>> {
+    let mut bits = ROOT_BITS;
+    let bitarray = BitArray::from_bytes(&key_hash);
+    let mut iter = bitarray.range(None, None, Order::Ascending);
+    let mut node = NODES.may_load(storage, (version, &bits))?;
+
+    loop {
+        match node {
+            None => return Ok(None),
+            Some(Node::Leaf(leaf)) => return Ok((leaf.key_hash == key_hash).then_some(leaf)),
+            Some(Node::Internal(InternalNode {
+                left_child,
+                right_child,
+            })) => {
+                // The next bit must exist, because if we have reached the end
+                // of the bitarray, the node is definitely a leaf.
+                let bit = iter.next().expect("bitarray ended at an internal node");
+                let child = if bit == 0 {
+                    left_child
+                } else {
+                    right_child
+                };
+
+                let Some(child) = child else {
+                    return Ok(None);
+                };
+
+                bits.push(bit);
+                node = Some(NODES.load(storage, (child.version, &bits))?);
+            },
+        }
+    }
+}
+
+/// Count the leaves in the subtree under the given node, checking that each
+/// child hashes to what the node records for it. Return `None` if a child is
+/// missing or doesn't match, i.e. the subtree isn't authenticated by the
+/// node's hash.
+fn count_authentic_leaves(
+    storage: &dyn Storage,
+    bits: BitArray,
+    node: Node,
+) -> StdResult<Option<usize>> {
+    match node {
+        Node::Leaf(_) => Ok(Some(1)),
+        Node::Internal(InternalNode {
+            left_child,
+            right_child,
+        }) => {
+            let mut count = 0;
+
+            for (child, is_left) in [(left_child, true), (right_child, false)] {
+                if let Some(child) = child {
+                    let bits = bits.extend_one_bit(is_left);
+
+                    let Some(node) = NODES.may_load(storage, (child.version, &bits))? else {
+                        return Ok(None);
+                    };
+
+                    if node.hash() != child.hash {
+                        return Ok(None);
+                    }
+
+                    match count_authentic_leaves(storage, bits, node)? {
+                        Some(num_leaves) => count += num_leaves,
+                        None => return Ok(None),
+                    }
+                }
+            }
+
+            Ok(Some(count))
+        },
+    }
+}
+
 #[inline]
 fn hash_of(child: Option<Child>) -> Option<Hash256> {
     child.map(|child| child.hash)
@@ -687,6 +902,7 @@ fn into_child(version: u64, outcome: Outcome) -> Option<Child> {
 mod tests {
     use {
         super::*,
+        crate::{ProofError, verify_multi_proof},
         dango_primitives::{MembershipProof, MockStorage, NonMembershipProof, ResultExt, StdError},
         hex_literal::hex,
         test_case::test_case,
@@ -938,6 +1154,172 @@ mod tests {
         );
     }
 
+    fn leaf(key: &str, value: &str) -> Box<MultiProof> {
+        Box::new(MultiProof::Leaf {
+            key_hash: key.as_bytes().sha2_256(),
+            value_hash: value.as_bytes().sha2_256(),
+        })
+    }
+
+    fn pruned(hash: Hash256) -> Box<MultiProof> {
+        Box::new(MultiProof::Pruned { hash })
+    }
+
+    // Prove r (010), a (1) and b (0011..., doesn't exist) at once. The paths
+    // to the three keys are expanded; node 011 isn't on any of them so it's
+    // pruned.
+    #[test]
+    fn proving_multi() {
+        let (storage, _) = build_test_case().unwrap();
+
+        let proof = MerkleTree::prove_multi(
+            &storage,
+            &[
+                b"r".sha2_256(),
+                b"a".sha2_256(),
+                b"b".sha2_256(),
+                b"r".sha2_256(), // duplicate keys are ignored
+            ],
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(
+            proof,
+            MultiProof::Internal {
+                left: Some(Box::new(MultiProof::Internal {
+                    left: None,
+                    right: Some(Box::new(MultiProof::Internal {
+                        left: Some(leaf("r", "foo")),
+                        right: Some(pruned(HASH_011)),
+                    })),
+                })),
+                right: Some(leaf("a", "buzz")),
+            }
+        );
+
+        verify_multi_proof(
+            HASH_ROOT,
+            &[
+                (b"r".sha2_256(), Some(b"foo".sha2_256())),
+                (b"a".sha2_256(), Some(b"buzz".sha2_256())),
+                (b"b".sha2_256(), None),
+            ],
+            &proof,
+        )
+        .unwrap();
+
+        // Wrong value.
+        assert!(matches!(
+            verify_multi_proof(
+                HASH_ROOT,
+                &[(b"r".sha2_256(), Some(b"bar".sha2_256()))],
+                &proof,
+            ),
+            Err(ProofError::ValueHashMismatch { .. })
+        ));
+
+        // m is in the pruned node 011, so the proof says nothing about it.
+        assert!(matches!(
+            verify_multi_proof(HASH_ROOT, &[(b"m".sha2_256(), None)], &proof),
+            Err(ProofError::KeyNotCovered { .. })
+        ));
+
+        // Wrong root hash.
+        assert!(matches!(
+            verify_multi_proof(HASH_0, &[], &proof),
+            Err(ProofError::RootHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verifying_state() {
+        let (storage, _) = build_test_case().unwrap();
+
+        let state = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| Ok((k.as_bytes().to_vec(), v.as_bytes().to_vec())))
+                .collect::<Vec<_>>()
+        };
+
+        // The exact state.
+        assert!(
+            MerkleTree::verify_state(
+                &storage,
+                0,
+                state(&[("r", "foo"), ("m", "bar"), ("L", "fuzz"), ("a", "buzz")]),
+            )
+            .unwrap()
+        );
+
+        // A tampered value.
+        assert!(
+            !MerkleTree::verify_state(
+                &storage,
+                0,
+                state(&[("r", "foo"), ("m", "bar"), ("L", "fuzz"), ("a", "fizz")]),
+            )
+            .unwrap()
+        );
+
+        // An extra key.
+        assert!(
+            !MerkleTree::verify_state(
+                &storage,
+                0,
+                state(&[
+                    ("r", "foo"),
+                    ("m", "bar"),
+                    ("L", "fuzz"),
+                    ("a", "buzz"),
+                    ("b", "jazz"),
+                ]),
+            )
+            .unwrap()
+        );
+
+        // A missing key.
+        assert!(
+            !MerkleTree::verify_state(
+                &storage,
+                0,
+                state(&[("r", "foo"), ("m", "bar"), ("L", "fuzz")]),
+            )
+            .unwrap()
+        );
+
+        // A tampered leaf, together with its value. The lookup alone passes,
+        // but the leaf no longer hashes to what its parent records.
+        {
+            let mut storage = storage.clone();
+
+            NODES
+                .save(
+                    &mut storage,
+                    (0, &BitArray::from_bits(&[1])),
+                    &Node::Leaf(LeafNode {
+                        key_hash: b"a".sha2_256(),
+                        value_hash: b"fizz".sha2_256(),
+                    }),
+                )
+                .unwrap();
+
+            assert!(
+                !MerkleTree::verify_state(
+                    &storage,
+                    0,
+                    state(&[("r", "foo"), ("m", "bar"), ("L", "fuzz"), ("a", "fizz")]),
+                )
+                .unwrap()
+            );
+        }
+
+        // An empty tree only matches an empty state.
+        assert!(MerkleTree::verify_state(&storage, 1, state(&[])).unwrap());
+        assert!(!MerkleTree::verify_state(&storage, 1, state(&[("r", "foo")])).unwrap());
+    }
+
     /// An edge case found in the Zellic audit.
     ///
     /// Attempting to generate proofs in an empty tree would fail with a "data
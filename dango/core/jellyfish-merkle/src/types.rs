@@ -0,0 +1,193 @@
+//! Copies of the `dango-primitives` types that proof verification needs, for
+//! use without std. They must be Borsh-encoded exactly like the originals, so
+//! that proofs generated by a node can be decoded here.
+
+use {
+    alloc::{boxed::Box, vec::Vec},
+    borsh::{
+        BorshDeserialize, BorshSerialize,
+        io::{self, Read, Write},
+    },
+    core::{fmt, ops::Deref},
+    sha2::{Digest, Sha256},
+};
+
+/// A 32-byte hash, displayed in uppercase hex.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hash256([u8; 32]);
+
+impl Hash256 {
+    pub const LENGTH: usize = 32;
+    pub const ZERO: Self = Self([0; Self::LENGTH]);
+
+    pub const fn from_inner(bytes: [u8; Self::LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn into_inner(self) -> [u8; Self::LENGTH] {
+        self.0
+    }
+}
+
+impl Deref for Hash256 {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Hash256 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash({self})")
+    }
+}
+
+// `dango-primitives` encodes hashes as byte slices, i.e. prefixed with their
+// length, so we have to do the same.
+impl BorshSerialize for Hash256 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(&self.0[..], writer)
+    }
+}
+
+impl BorshDeserialize for Hash256 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        let bytes = bytes.try_into().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "hash must be 32 bytes long")
+        })?;
+
+        Ok(Self(bytes))
+    }
+}
+
+/// Represents a data that can be hashed.
+pub trait HashExt {
+    /// Produce a hash of the data using the SHA2-256 algorithm.
+    fn sha2_256(&self) -> Hash256;
+}
+
+impl<T> HashExt for T
+where
+    T: AsRef<[u8]>,
+{
+    fn sha2_256(&self) -> Hash256 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.as_ref());
+        Hash256::from_inner(hasher.finalize().into())
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[borsh(use_discriminant = true)]
+pub enum Order {
+    Ascending = 1,
+    Descending = 2,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Proof {
+    Membership(MembershipProof),
+    NonMembership(NonMembershipProof),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MembershipProof {
+    pub sibling_hashes: Vec<Option<Hash256>>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NonMembershipProof {
+    pub node: ProofNode,
+    pub sibling_hashes: Vec<Option<Hash256>>,
+}
+
+/// `ProofNode` is just like `Node`, but for internal nodes it omits the child
+/// versions, which aren't needed for proving, only including child node hashes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ProofNode {
+    Internal {
+        left_hash: Option<Hash256>,
+        right_hash: Option<Hash256>,
+    },
+    Leaf {
+        key_hash: Hash256,
+        value_hash: Hash256,
+    },
+}
+
+/// Merkle proof for multiple keys at once. Subtrees that aren't relevant to
+/// the proof are pruned, represented only by their hashes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MultiProof {
+    Internal {
+        left: Option<Box<MultiProof>>,
+        right: Option<Box<MultiProof>>,
+    },
+    Leaf {
+        key_hash: Hash256,
+        value_hash: Hash256,
+    },
+    Pruned {
+        hash: Hash256,
+    },
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings_match_primitives() {
+        let hash = b"foo".sha2_256();
+        let other = dango_primitives::Hash256::from_inner(hash.into_inner());
+        assert_eq!(hash.to_string(), other.to_string());
+        assert_eq!(format!("{hash:?}"), format!("{other:?}"));
+
+        let proof = MultiProof::Internal {
+            left: Some(Box::new(MultiProof::Pruned { hash })),
+            right: Some(Box::new(MultiProof::Leaf {
+                key_hash: hash,
+                value_hash: Hash256::ZERO,
+            })),
+        };
+        let other_proof = dango_primitives::MultiProof::Internal {
+            left: Some(Box::new(dango_primitives::MultiProof::Pruned {
+                hash: other,
+            })),
+            right: Some(Box::new(dango_primitives::MultiProof::Leaf {
+                key_hash: other,
+                value_hash: dango_primitives::Hash256::ZERO,
+            })),
+        };
+        let bytes = borsh::to_vec(&proof).unwrap();
+        assert_eq!(bytes, borsh::to_vec(&other_proof).unwrap());
+        assert_eq!(borsh::from_slice::<MultiProof>(&bytes).unwrap(), proof);
+
+        assert_eq!(
+            borsh::to_vec(&Order::Descending).unwrap(),
+            borsh::to_vec(&dango_primitives::Order::Descending).unwrap(),
+        );
+
+        // A hash of the wrong length is rejected.
+        assert!(borsh::from_slice::<Hash256>(&borsh::to_vec(&[0u8; 31][..]).unwrap()).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use {
     super::Indexer,
-    crate::{Borsh, Codec, Index, Map, PrimaryKey, Raw},
+    crate::{Borsh, Codec, Index, Map, Path, PrimaryKey, Raw},
     dango_primitives::{Bound, Order, StdError, StdResult, Storage},
 };
 
@@ -66,6 +66,13 @@ where
         self.index_map.is_empty(storage)
     }
 
+    /// Storage path of the raw primary key indexed at the given index value.
+    ///
+    /// Useful for resolving an index from another contract with a raw query.
+    pub fn key_path(&self, idx: IK) -> Path<'_, Vec<u8>, Raw> {
+        self.index_map.path(idx)
+    }
+
     /// Given an index value, which may or may not exist, load the corresponding
     /// key.
     pub fn may_load_key(&self, storage: &dyn Storage, idx: IK) -> StdResult<Option<PK::Output>> {
@@ -36,3 +36,25 @@ pub enum ProofNode {
         value_hash: Hash256,
     },
 }
+
+/// Merkle proof for multiple keys at once.
+///
+/// It is the part of the tree that is relevant to the proof. Subtrees that
+/// aren't relevant are pruned, represented only by their hashes. As the paths
+/// to the proven keys share their upper nodes, this is much smaller than a
+/// separate [`Proof`] for each key.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiProof {
+    Internal {
+        left: Option<Box<MultiProof>>,
+        right: Option<Box<MultiProof>>,
+    },
+    Leaf {
+        key_hash: Hash256,
+        value_hash: Hash256,
+    },
+    Pruned {
+        hash: Hash256,
+    },
+}
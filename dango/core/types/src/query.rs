@@ -440,3 +440,17 @@ impl QueryResponse {
         Multi        => Vec<GenericResult<QueryResponse>>,
     }
 }
+
+/// A query response together with a Merkle proof of the raw storage that it
+/// was read from.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvenQueryResponse {
+    pub response: QueryResponse,
+    /// The block height at which the query was performed. The proof is to be
+    /// verified against the app hash of this height.
+    pub height: u64,
+    /// Borsh-encoded Merkle proof of the raw storage keys that the response
+    /// was read from, in the scheme used by the node's database (for the
+    /// Jellyfish Merkle tree, a `MultiProof`).
+    pub proof: Binary,
+}
@@ -4,7 +4,7 @@ use {
     dango_primitives::{Context, Hash256},
     dango_vm_rust::{RustInstance, RustVm},
     dango_vm_wasm::{WasmInstance, WasmVm},
-    std::collections::HashSet,
+    std::{collections::HashSet, io, path::Path},
 };
 
 #[dango_backtrace::backtrace]
@@ -46,6 +46,26 @@ impl HybridVm {
             code_hashes_for_rust: code_hashes_for_rust.into(),
         }
     }
+
+    /// Create a VM whose Wasm VM additionally caches compiled modules in the
+    /// given directory. See [`WasmVm::new_with_file_cache`].
+    ///
+    /// Like the latter, this is library-only: the node doesn't call it.
+    pub fn new_with_wasm_file_cache<P, T>(
+        wasm_cache_capacity: usize,
+        wasm_file_cache_dir: P,
+        code_hashes_for_rust: T,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        T: Into<HashSet<Hash256>>,
+    {
+        Ok(Self {
+            rust: RustVm::new(),
+            wasm: WasmVm::new_with_file_cache(wasm_cache_capacity, wasm_file_cache_dir)?,
+            code_hashes_for_rust: code_hashes_for_rust.into(),
+        })
+    }
 }
 
 impl Vm for HybridVm {
@@ -1,5 +1,5 @@
 use {
-    crate::VmResult,
+    crate::{FileCache, VmResult},
     clru::CLruCache,
     dango_primitives::{Hash256, Shared},
     std::num::NonZeroUsize,
@@ -18,11 +18,21 @@ type Data = (Module, Engine);
 pub struct Metrics {
     pub hits: usize,
     pub misses: usize,
+    /// Number of in-memory cache misses that were served by the file cache.
+    pub file_hits: usize,
+    /// Number of in-memory cache misses that weren't served by the file cache,
+    /// so the module had to be compiled.
+    pub file_misses: usize,
 }
 
 impl Metrics {
     pub fn new() -> Self {
-        Self { hits: 0, misses: 0 }
+        Self {
+            hits: 0,
+            misses: 0,
+            file_hits: 0,
+            file_misses: 0,
+        }
     }
 
     pub fn increment_hits(&mut self) {
@@ -35,57 +45,124 @@ impl Metrics {
         // Same as above, use saturating add to avoid panicking on overflow.
         self.misses = self.misses.saturating_add(1);
     }
+
+    pub fn increment_file_hits(&mut self) {
+        self.file_hits = self.file_hits.saturating_add(1);
+    }
+
+    pub fn increment_file_misses(&mut self) {
+        self.file_misses = self.file_misses.saturating_add(1);
+    }
 }
 
-/// An in-memory cache for wasm modules, so that they don't need to be re-built
-/// every time the same contract is called.
+/// A cache for wasm modules, so that they don't need to be re-built every time
+/// the same contract is called.
+///
+/// Modules are cached in memory, and optionally on disk by a [`FileCache`],
+/// which survives node restarts. The in-memory cache is consulted first.
 #[derive(Clone)]
 pub struct Cache {
     inner: Shared<CacheInner>,
 }
 
 struct CacheInner {
-    lru_cache: CLruCache<Hash256, Data>,
+    lru_cache: Option<CLruCache<Hash256, Data>>,
+    file_cache: Option<FileCache>,
     metrics: Metrics,
 }
 
 impl Cache {
-    /// Create an empty cache with the given capacity.
+    /// Create an empty in-memory cache with the given capacity.
     pub fn new(capacity: NonZeroUsize) -> Self {
+        Self::new_inner(Some(capacity), None)
+    }
+
+    /// Create a cache backed by the given file cache, optionally with an
+    /// in-memory cache of the given capacity on top of it.
+    pub fn new_with_file_cache(capacity: Option<NonZeroUsize>, file_cache: FileCache) -> Self {
+        Self::new_inner(capacity, Some(file_cache))
+    }
+
+    fn new_inner(capacity: Option<NonZeroUsize>, file_cache: Option<FileCache>) -> Self {
         Self {
             inner: Shared::new(CacheInner {
-                lru_cache: CLruCache::new(capacity),
+                lru_cache: capacity.map(CLruCache::new),
+                file_cache,
                 metrics: Metrics::new(),
             }),
         }
     }
 
-    /// Attempt to get a cached module by hash. If not found, build the module
-    /// using the given method, insert the built module into the cache, and
-    /// return the module.
-    pub fn get_or_build_with<B>(&self, code_hash: Hash256, builder: B) -> VmResult<Data>
+    /// Return the statistics about the usage of the cache.
+    pub fn metrics(&self) -> Metrics {
+        self.inner.read_with(|inner| inner.metrics)
+    }
+
+    /// Attempt to get a cached module by hash. If not found in memory, attempt
+    /// to load it from the file cache using an engine created by the given
+    /// method. If not found there either, compile the module using the given
+    /// method and save it to the file cache. Either way, insert the module into
+    /// the in-memory cache, and return it.
+    pub fn get_or_build_with<E, C>(
+        &self,
+        code_hash: Hash256,
+        build_engine: E,
+        compile: C,
+    ) -> VmResult<Data>
     where
-        B: FnOnce() -> VmResult<Data>,
+        E: FnOnce() -> Engine,
+        C: FnOnce(&Engine) -> VmResult<Module>,
     {
         self.inner.write_with(|mut inner| {
-            match inner.lru_cache.get(&code_hash).cloned() {
-                // Cache hit - simply clone the cached data and return.
-                Some(data) => {
-                    inner.metrics.increment_hits();
+            // Cache hit - simply clone the cached data and return.
+            if let Some(data) = inner
+                .lru_cache
+                .as_mut()
+                .and_then(|lru_cache| lru_cache.get(&code_hash).cloned())
+            {
+                inner.metrics.increment_hits();
 
-                    Ok(data)
-                },
-                // Cache miss - build the module using the given builder method;
-                // insert both the module and engine to the cache.
-                None => {
-                    let data = builder()?;
+                return Ok(data);
+            }
+
+            inner.metrics.increment_misses();
+
+            let engine = build_engine();
 
-                    inner.lru_cache.put(code_hash, data.clone());
-                    inner.metrics.increment_misses();
+            let module = match &inner.file_cache {
+                Some(file_cache) => match file_cache.load(code_hash, &engine) {
+                    // File cache hit - use the deserialized module.
+                    Some(module) => {
+                        inner.metrics.increment_file_hits();
 
-                    Ok(data)
+                        module
+                    },
+                    // File cache miss - compile the module, and save it to the
+                    // file cache. Failing to save it isn't fatal; it will just
+                    // be compiled again next time.
+                    None => {
+                        let module = compile(&engine)?;
+
+                        if let Err(err) = file_cache.store(code_hash, &module) {
+                            tracing::warn!(%code_hash, %err, "Failed to save Wasm artifact");
+                        }
+
+                        inner.metrics.increment_file_misses();
+
+                        module
+                    },
                 },
+                None => compile(&engine)?,
+            };
+
+            let data = (module, engine);
+
+            // Insert both the module and engine to the cache.
+            if let Some(lru_cache) = inner.lru_cache.as_mut() {
+                lru_cache.put(code_hash, data.clone());
             }
+
+            Ok(data)
         })
     }
 }
@@ -95,18 +172,20 @@ impl Cache {
 #[cfg(test)]
 mod tests {
     use {
-        crate::{Cache, VmResult},
-        dango_primitives::HashExt,
-        std::num::NonZeroUsize,
+        crate::{Cache, FileCache, VmResult},
+        dango_primitives::{Hash256, HashExt},
+        std::{fs, num::NonZeroUsize},
         wasmer::{Engine, Module, sys::Singlepass},
     };
 
     const CONTRACT: &[u8] = br#"(module)"#;
 
-    fn builder() -> VmResult<(Module, Engine)> {
-        let engine = Engine::from(Singlepass::new());
-        let module = Module::new(&engine, CONTRACT)?;
-        Ok((module, engine))
+    fn build_engine() -> Engine {
+        Engine::from(Singlepass::new())
+    }
+
+    fn compile(engine: &Engine) -> VmResult<Module> {
+        Ok(Module::new(engine, CONTRACT)?)
     }
 
     #[test]
@@ -116,19 +195,24 @@ mod tests {
         // Build the 1st contract. Should be a cache miss, and the data is
         // inserted into the cache.
         let hash1 = CONTRACT.sha2_256();
-        cache.get_or_build_with(hash1, builder).unwrap();
+        cache
+            .get_or_build_with(hash1, build_engine, compile)
+            .unwrap();
 
         // Build the 2nd contract. Should also be a cache miss, and the data is
         // inserted. Data of the previous build should have been removed,
         // because the cache only has a capacity of 1.
         let hash2 = b"jake".sha2_256();
-        cache.get_or_build_with(hash2, builder).unwrap();
+        cache
+            .get_or_build_with(hash2, build_engine, compile)
+            .unwrap();
 
         // Cache should have had 2 misses, with hash2 cached but hash1 not.
         cache.inner.read_with(|inner| {
-            assert!(!inner.lru_cache.contains(&hash1));
-            assert!(inner.lru_cache.contains(&hash2));
-            assert_eq!(inner.lru_cache.len(), 1);
+            let lru_cache = inner.lru_cache.as_ref().unwrap();
+            assert!(!lru_cache.contains(&hash1));
+            assert!(lru_cache.contains(&hash2));
+            assert_eq!(lru_cache.len(), 1);
             assert_eq!(inner.metrics.hits, 0);
             assert_eq!(inner.metrics.misses, 2);
         });
@@ -141,14 +225,96 @@ mod tests {
         // Build the same contract twice. 1st time should be a cache miss, 2nd
         // time should be a cache hit.
         let hash = CONTRACT.sha2_256();
-        cache.get_or_build_with(hash, builder).unwrap();
-        cache.get_or_build_with(hash, builder).unwrap();
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
 
         cache.inner.read_with(|inner| {
-            assert!(inner.lru_cache.contains(&hash));
-            assert_eq!(inner.lru_cache.len(), 1);
+            let lru_cache = inner.lru_cache.as_ref().unwrap();
+            assert!(lru_cache.contains(&hash));
+            assert_eq!(lru_cache.len(), 1);
             assert_eq!(inner.metrics.hits, 1);
             assert_eq!(inner.metrics.misses, 1);
         });
     }
+
+    #[test]
+    fn get_file_cached_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = CONTRACT.sha2_256();
+
+        // The 1st node run compiles the contract and saves the artifact.
+        let file_cache = FileCache::new(dir.path(), &build_engine()).unwrap();
+        let cache = Cache::new_with_file_cache(NonZeroUsize::new(1), file_cache);
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.file_hits, 0);
+        assert_eq!(metrics.file_misses, 1);
+
+        // The 2nd node run starts with an empty in-memory cache, but loads the
+        // artifact from the disk instead of compiling it.
+        let file_cache = FileCache::new(dir.path(), &build_engine()).unwrap();
+        let cache = Cache::new_with_file_cache(NonZeroUsize::new(1), file_cache);
+        cache
+            .get_or_build_with(hash, build_engine, |_| {
+                panic!("module should be loaded from the file cache")
+            })
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.file_hits, 1);
+        assert_eq!(metrics.file_misses, 0);
+    }
+
+    #[test]
+    fn corrupted_artifact_is_recompiled() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = CONTRACT.sha2_256();
+
+        let file_cache = FileCache::new(dir.path(), &build_engine()).unwrap();
+        let cache = Cache::new_with_file_cache(None, file_cache);
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
+
+        // Flip a byte of the artifact.
+        let path = find_artifact(dir.path(), hash);
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&path, bytes).unwrap();
+
+        // The corrupted artifact fails the integrity check, so the module is
+        // compiled again and the artifact rewritten.
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
+        cache
+            .get_or_build_with(hash, build_engine, compile)
+            .unwrap();
+
+        // Without an in-memory cache, every call is a miss.
+        let metrics = cache.metrics();
+        assert_eq!(metrics.hits, 0);
+        assert_eq!(metrics.misses, 3);
+        assert_eq!(metrics.file_hits, 1);
+        assert_eq!(metrics.file_misses, 2);
+    }
+
+    fn find_artifact(dir: &std::path::Path, code_hash: Hash256) -> std::path::PathBuf {
+        let file_name = format!("{code_hash}.module");
+
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path().join(&file_name))
+            .find(|path| path.exists())
+            .unwrap()
+    }
 }
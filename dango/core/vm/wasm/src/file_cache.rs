@@ -0,0 +1,136 @@
+use {
+    crate::{GAS_PER_OPERATION, MAX_MEMORY_PAGES},
+    dango_crypto::sha2_256,
+    dango_primitives::Hash256,
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+    },
+    wasmer::{
+        Engine, Module,
+        sys::{Target, vm::VERSION as WASMER_VERSION},
+    },
+};
+
+/// Version of the way Wasm modules are compiled.
+///
+/// Bump this whenever a change to our side of the compilation pipeline (the
+/// gatekeeper, the gas metering, or the tunables) makes previously compiled
+/// artifacts invalid. Artifacts are stored under a directory named after this
+/// version and the Wasmer version, so artifacts compiled under an older
+/// pipeline or Wasmer release are never loaded.
+pub const ARTIFACT_VERSION: u32 = 1;
+
+/// File extension of the artifact files.
+const ARTIFACT_EXTENSION: &str = "module";
+
+/// An on-disk cache of compiled Wasm modules, so that they don't need to be
+/// re-compiled every time the node restarts.
+///
+/// Each artifact is stored in a file named after the code hash, prefixed with
+/// the SHA-256 checksum of the serialized module. The checksum is verified
+/// before the module is deserialized; corrupted artifacts are discarded.
+#[derive(Debug, Clone)]
+pub struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    /// Open the cache at the given directory, creating it if it doesn't exist.
+    ///
+    /// Artifacts are stored in a subdirectory keyed by the engine and its
+    /// configuration, so the same base directory can be shared across
+    /// incompatible versions.
+    pub fn new<P>(base_dir: P, engine: &Engine) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = base_dir.as_ref().join(engine_version(engine));
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self { dir })
+    }
+
+    /// Attempt to load a module from the cache. Return `None` if the artifact
+    /// doesn't exist, or it fails the integrity check or can't be deserialized,
+    /// in which case it's deleted.
+    pub fn load(&self, code_hash: Hash256, engine: &Engine) -> Option<Module> {
+        let path = self.artifact_path(code_hash);
+
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::warn!(%code_hash, %err, "Failed to read Wasm artifact");
+                return None;
+            },
+        };
+
+        match verify_and_deserialize(&bytes, engine) {
+            Ok(module) => Some(module),
+            Err(reason) => {
+                tracing::warn!(%code_hash, reason, "Discarding invalid Wasm artifact");
+
+                let _ = fs::remove_file(&path);
+
+                None
+            },
+        }
+    }
+
+    /// Save a module to the cache.
+    ///
+    /// The artifact is first written to a temporary file, then renamed, so
+    /// that a crash halfway doesn't leave a partially written artifact.
+    pub fn store(&self, code_hash: Hash256, module: &Module) -> io::Result<()> {
+        let module = module.serialize().map_err(io::Error::other)?;
+        let checksum = sha2_256(&module);
+
+        let mut bytes = Vec::with_capacity(checksum.len() + module.len());
+        bytes.extend_from_slice(&checksum);
+        bytes.extend_from_slice(&module);
+
+        let path = self.artifact_path(code_hash);
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)
+    }
+
+    fn artifact_path(&self, code_hash: Hash256) -> PathBuf {
+        self.dir
+            .join(code_hash.to_string())
+            .with_extension(ARTIFACT_EXTENSION)
+    }
+}
+
+/// A string that uniquely identifies the compilation pipeline: the artifact
+/// version, the Wasmer version, the compiler, the target, and the limits baked
+/// into the modules.
+fn engine_version(engine: &Engine) -> String {
+    format!(
+        "v{}-wasmer{}-{}-{}-gas{}-pages{}",
+        ARTIFACT_VERSION,
+        WASMER_VERSION,
+        engine.deterministic_id(),
+        Target::default().triple(),
+        GAS_PER_OPERATION,
+        MAX_MEMORY_PAGES,
+    )
+}
+
+fn verify_and_deserialize(bytes: &[u8], engine: &Engine) -> Result<Module, String> {
+    let Some((checksum, module)) = bytes.split_first_chunk::<32>() else {
+        return Err("artifact is too short".to_string());
+    };
+
+    if sha2_256(module) != *checksum {
+        return Err("checksum mismatch".to_string());
+    }
+
+    // Safety: the artifact was serialized by `FileCache::store` using an engine
+    // of the same version and configuration, and its integrity has just been
+    // verified. `deserialize` additionally checks the Wasmer artifact header.
+    unsafe { Module::deserialize(engine, module) }.map_err(|err| err.to_string())
+}
@@ -1,6 +1,7 @@
 mod cache;
 mod environment;
 mod error;
+mod file_cache;
 mod gatekeeper;
 mod imports;
 mod iterator;
@@ -10,6 +11,6 @@ mod tunables;
 mod vm;
 
 pub use {
-    cache::*, environment::*, error::*, gatekeeper::*, imports::*, iterator::*, memory::*,
-    region::*, tunables::*, vm::*,
+    cache::*, environment::*, error::*, file_cache::*, gatekeeper::*, imports::*, iterator::*,
+    memory::*, region::*, tunables::*, vm::*,
 };
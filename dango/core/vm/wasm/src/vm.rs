@@ -1,13 +1,13 @@
 use {
     crate::{
-        Cache, Environment, Gatekeeper, LimitingTunables, VmError, VmResult, db_next, db_next_key,
-        db_next_value, db_read, db_remove, db_remove_range, db_scan, db_write, debug, keccak256,
-        query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify,
-        sha2_256, write_to_memory,
+        Cache, Environment, FileCache, Gatekeeper, LimitingTunables, Metrics, VmError, VmResult,
+        db_next, db_next_key, db_next_value, db_read, db_remove, db_remove_range, db_scan,
+        db_write, debug, keccak256, query_chain, read_then_wipe, secp256k1_pubkey_recover,
+        secp256k1_verify, secp256r1_verify, sha2_256, write_to_memory,
     },
     dango_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
     dango_primitives::{BorshSerExt, Context, Hash256},
-    std::{num::NonZeroUsize, sync::Arc},
+    std::{io, num::NonZeroUsize, path::Path, sync::Arc},
     wasmer::{
         Engine, Function, FunctionEnv, Module, Store, StoreMut, WASM_PAGE_SIZE, imports,
         sys::{BaseTunables, CompilerConfig, NativeEngineExt, Singlepass, Target},
@@ -62,6 +62,30 @@ impl WasmVm {
             cache: NonZeroUsize::new(cache_capacity).map(Cache::new),
         }
     }
+
+    /// Create a VM that additionally caches compiled modules in the given
+    /// directory, so that they don't need to be re-compiled after a restart.
+    ///
+    /// This is only available to library users; the node runs the Rust VM and
+    /// doesn't expose a config option for it.
+    pub fn new_with_file_cache<P>(cache_capacity: usize, file_cache_dir: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file_cache = FileCache::new(file_cache_dir, &build_engine())?;
+
+        Ok(Self {
+            cache: Some(Cache::new_with_file_cache(
+                NonZeroUsize::new(cache_capacity),
+                file_cache,
+            )),
+        })
+    }
+
+    /// Return the statistics about the usage of the module cache, if enabled.
+    pub fn cache_metrics(&self) -> Option<Metrics> {
+        self.cache.as_ref().map(Cache::metrics)
+    }
 }
 
 impl Vm for WasmVm {
@@ -85,9 +109,13 @@ impl Vm for WasmVm {
         let (module, engine) = if let Some(cache) = &self.cache {
             // Attempt to fetch a pre-built Wasmer module from the cache.
             // If not found, build it and insert it into the cache.
-            cache.get_or_build_with(code_hash, || compile_wasmer(code))?
+            cache.get_or_build_with(code_hash, build_engine, |engine| {
+                Ok(Module::new(engine, code)?)
+            })?
         } else {
-            compile_wasmer(code)?
+            let engine = build_engine();
+            let module = Module::new(&engine, code)?;
+            (module, engine)
         };
 
         // Compute the amount of gas left for this call. This will be used as
@@ -157,7 +185,7 @@ impl Vm for WasmVm {
     }
 }
 
-fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
+fn build_engine() -> Engine {
     let mut compiler = Singlepass::new();
 
     // Set up the gas metering middleware.
@@ -188,9 +216,7 @@ fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
     let tunables = LimitingTunables::new(base, MAX_MEMORY_PAGES);
     engine.set_tunables(tunables);
 
-    let module = Module::new(&engine, code)?;
-
-    Ok((module, engine))
+    engine
 }
 
 // --------------------------------- instance ----------------------------------
@@ -1,5 +1,8 @@
 use {
-    crate::{CODE_HASH, MAX_ACCOUNTS_PER_USER, NEXT_ACCOUNT_INDEX, NEXT_USER_INDEX, USERS},
+    crate::{
+        CODE_HASH, MAX_ACCOUNTS_PER_USER, MULTI_CODE_HASH, MULTISIGS, NEXT_ACCOUNT_INDEX,
+        NEXT_USER_INDEX, USERS,
+    },
     anyhow::{bail, ensure},
     dango_auth::{VerifyData, verify_signature},
     dango_primitives::{
@@ -10,17 +13,19 @@ use {
         DangoQuerier, account,
         account_factory::{
             AccountOwned, AccountRegistered, ExecuteMsg, InstantiateMsg, KeyDisowned, KeyOwned,
-            NewUserSalt, RegisterUserData, Salt, User, UserIndex, UserRegistered, Username,
-            UsernameUpdated,
+            MultisigRegistered, NewUserSalt, RegisterUserData, Salt, User, UserIndex,
+            UserRegistered, Username, UsernameUpdated,
         },
+        account_multi,
         auth::{Key, Signature},
         perps,
     },
 };
 
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
-    // Save the code hashes associated with the Dango account contract.
+    // Save the code hashes associated with the Dango account contracts.
     CODE_HASH.save(ctx.storage, &msg.account_code_hash)?;
+    MULTI_CODE_HASH.save(ctx.storage, &msg.multi_code_hash)?;
 
     // During genesis:
     // 1. We use an incremental number, which should equal the account's index,
@@ -114,6 +119,7 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             referrer,
         } => register_user(ctx, key, key_hash, seed, signature, referrer),
         ExecuteMsg::RegisterAccount {} => register_account(ctx),
+        ExecuteMsg::RegisterMultisig { params } => register_multisig(ctx, params),
         ExecuteMsg::UpdateKey { key_hash, key } => update_key(ctx, key_hash, key),
         ExecuteMsg::UpdateUsername(username) => update_username(ctx, username),
         ExecuteMsg::ForceResetUsername { user_index } => force_reset_username(ctx, user_index),
@@ -299,6 +305,35 @@ fn register_account(ctx: MutableCtx) -> anyhow::Result<Response> {
         })?)
 }
 
+fn register_multisig(ctx: MutableCtx, params: account_multi::Params) -> anyhow::Result<Response> {
+    // Only existing users can create multisigs. The multisig contract validates
+    // the parameters upon instantiation.
+    let creator = USERS.idx.by_account.load_key(ctx.storage, ctx.sender)?;
+
+    let (index, _) = NEXT_ACCOUNT_INDEX.increment(ctx.storage)?;
+    let salt = Salt { index }.into_bytes();
+
+    let code_hash = MULTI_CODE_HASH.load(ctx.storage)?;
+    let address = Addr::derive(ctx.contract, code_hash, &salt);
+
+    MULTISIGS.save(ctx.storage, address, &index)?;
+
+    Ok(Response::new()
+        .add_message(Message::instantiate(
+            code_hash,
+            &account_multi::InstantiateMsg { params },
+            salt,
+            Some(format!("dango/account/multi/{index}")),
+            Some(ctx.contract),
+            ctx.funds, // Forward the received funds to the multisig.
+        )?)
+        .add_event(MultisigRegistered {
+            account_index: index,
+            address,
+            creator,
+        })?)
+}
+
 fn update_key(ctx: MutableCtx, key_hash: Hash256, key: Op<Key>) -> anyhow::Result<Response> {
     let user_index = USERS.idx.by_account.load_key(ctx.storage, ctx.sender)?;
     let mut user = USERS.load(ctx.storage, user_index)?;
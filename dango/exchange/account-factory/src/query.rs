@@ -1,5 +1,5 @@
 use {
-    crate::{CODE_HASH, NEXT_ACCOUNT_INDEX, NEXT_USER_INDEX, USERS},
+    crate::{CODE_HASH, MULTI_CODE_HASH, MULTISIGS, NEXT_ACCOUNT_INDEX, NEXT_USER_INDEX, USERS},
     dango_primitives::{
         Addr, Bound, DEFAULT_PAGE_LIMIT, Hash256, ImmutableCtx, Json, JsonSerExt, Order, StdResult,
         Storage,
@@ -16,6 +16,10 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_code_hash(ctx.storage)?;
             res.to_json_value()
         },
+        QueryMsg::MultiCodeHash {} => {
+            let res = query_multi_code_hash(ctx.storage)?;
+            res.to_json_value()
+        },
         QueryMsg::NextUserIndex {} => {
             let res = query_next_user_index(ctx.storage)?;
             res.to_json_value()
@@ -40,6 +44,10 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_accounts(ctx.storage, start_after, limit)?;
             res.to_json_value()
         },
+        QueryMsg::Multisigs { start_after, limit } => {
+            let res = query_multisigs(ctx.storage, start_after, limit)?;
+            res.to_json_value()
+        },
         QueryMsg::ForgotUsername {
             key_hash,
             start_after,
@@ -56,6 +64,10 @@ fn query_code_hash(storage: &dyn Storage) -> StdResult<Hash256> {
     CODE_HASH.load(storage)
 }
 
+fn query_multi_code_hash(storage: &dyn Storage) -> StdResult<Hash256> {
+    MULTI_CODE_HASH.load(storage)
+}
+
 fn query_next_user_index(storage: &dyn Storage) -> StdResult<UserIndex> {
     NEXT_USER_INDEX.current(storage)
 }
@@ -122,6 +134,20 @@ fn query_accounts(
         .collect()
 }
 
+fn query_multisigs(
+    storage: &dyn Storage,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Addr, AccountIndex>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    MULTISIGS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 fn forgot_username(
     storage: &dyn Storage,
     key_hash: Hash256,
@@ -1,15 +1,21 @@
 use {
     dango_primitives::{Addr, Hash256},
-    dango_storage::{Counter, IndexedMap, Item, MultiIndex, UniqueIndex},
+    dango_storage::{Counter, IndexedMap, Item, Map, MultiIndex, UniqueIndex},
     dango_types::account_factory::{AccountIndex, User, UserIndex, Username},
 };
 
 pub const CODE_HASH: Item<Hash256> = Item::new("hash");
 
+pub const MULTI_CODE_HASH: Item<Hash256> = Item::new("multi_hash");
+
 pub const NEXT_USER_INDEX: Counter<UserIndex> = Counter::new("user_index", 0, 1);
 
 pub const NEXT_ACCOUNT_INDEX: Counter<AccountIndex> = Counter::new("account_index", 0, 1);
 
+/// Multi-signature accounts, which aren't owned by any single user, and hence
+/// aren't tracked in `USERS`.
+pub const MULTISIGS: Map<Addr, AccountIndex> = Map::new("multi");
+
 pub const USERS: IndexedMap<UserIndex, User, UserIndexes> = IndexedMap::new(
     "user",
     UserIndexes {
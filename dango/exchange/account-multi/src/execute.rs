@@ -0,0 +1,324 @@
+use {
+    crate::{NEXT_PROPOSAL_ID, PARAMS, PROPOSALS, VOTES},
+    anyhow::{bail, ensure},
+    dango_auth::account_factory::USERS,
+    dango_primitives::{
+        AuthCtx, JsonDeExt, Message, MsgExecute, MutableCtx, QuerierWrapper, Response, Tx,
+    },
+    dango_storage::StorageQuerier,
+    dango_types::{
+        DangoQuerier,
+        account_factory::{User, UserIndex},
+        account_multi::{
+            ExecuteMsg, InstantiateMsg, Params, Proposal, ProposalExecuted, ProposalId, Proposed,
+            Status, Vote, Voted,
+        },
+        auth::{AccountStatus, Credential, Metadata},
+    },
+};
+
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+    validate_params(ctx.querier, &msg.params)?;
+
+    PARAMS.save(ctx.storage, &msg.params)?;
+
+    // Multisigs are always activated upon creation. Unlike a new user's first
+    // account, there's no onboarding deposit to wait for.
+    dango_auth::create_account(ctx, true)?;
+
+    Ok(Response::new())
+}
+
+// A multisig member interacts with the multisig by sending a transaction with
+// the multisig as sender, that contains exactly one message, to execute the
+// multisig itself with either `ExecuteMsg::Propose` or `ExecuteMsg::Vote`.
+//
+// The member signs the transaction with any of their own keys, the same way
+// they would for their single-signature account.
+pub fn authenticate(ctx: AuthCtx, tx: Tx) -> anyhow::Result<Response> {
+    let mut msgs = tx.msgs.iter();
+
+    let (Some(Message::Execute(MsgExecute { contract, msg, .. })), None) =
+        (msgs.next(), msgs.next())
+    else {
+        bail!("transaction must contain exactly one message");
+    };
+
+    ensure!(
+        *contract == ctx.contract,
+        "the contract being executed must be the multisig itself"
+    );
+
+    let metadata: Metadata = tx.data.clone().deserialize_json()?;
+
+    // Find the parameters against which the signer's membership is checked:
+    // - for a new proposal, the current parameters;
+    // - for a vote, the parameters at the time the proposal was created.
+    let params = match msg.clone().deserialize_json()? {
+        ExecuteMsg::Propose { .. } => PARAMS.load(ctx.storage)?,
+        ExecuteMsg::Vote {
+            proposal_id, voter, ..
+        } => {
+            ensure!(
+                voter == metadata.user_index,
+                "voter {voter} doesn't match the signer {}",
+                metadata.user_index
+            );
+
+            match PROPOSALS.load(ctx.storage, proposal_id)?.status {
+                Status::Voting { params, .. } => params,
+                _ => bail!("proposal {proposal_id} is not in the voting state"),
+            }
+        },
+        _ => bail!("the execute message must be either proposing or voting"),
+    };
+
+    ensure!(
+        params.members.contains_key(&metadata.user_index),
+        "user {} isn't a member of multisig {}",
+        metadata.user_index,
+        ctx.contract
+    );
+
+    // Session keys are scoped for trading, not for governing a shared account.
+    // Only standard (master-key) credentials are accepted.
+    ensure!(
+        !matches!(
+            tx.credential.clone().deserialize_json(),
+            Ok(Credential::Session(_))
+        ),
+        "session credentials can't be used with a multisig"
+    );
+
+    ensure!(
+        dango_auth::query_status(ctx.storage)? == AccountStatus::Active,
+        "account {} is not active",
+        ctx.contract
+    );
+
+    let factory = ctx.querier.query_account_factory()?;
+    let user = ctx
+        .querier
+        .query_wasm_path::<User, _>(factory, &USERS.path(metadata.user_index))?;
+
+    dango_auth::verify_nonce_and_signature(ctx, tx, &user, Some(metadata))?;
+
+    Ok(Response::new())
+}
+
+pub fn receive(ctx: MutableCtx) -> anyhow::Result<Response> {
+    dango_auth::receive_transfer(ctx)?;
+
+    Ok(Response::new())
+}
+
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::Propose {
+            title,
+            description,
+            messages,
+        } => propose(ctx, title, description, messages),
+        ExecuteMsg::Vote {
+            proposal_id,
+            voter,
+            vote,
+            execute,
+        } => do_vote(ctx, proposal_id, voter, vote, execute),
+        ExecuteMsg::Execute { proposal_id } => do_execute(ctx, proposal_id),
+        ExecuteMsg::UpdateParams(params) => update_params(ctx, params),
+    }
+}
+
+fn propose(
+    ctx: MutableCtx,
+    title: String,
+    description: Option<String>,
+    messages: Vec<Message>,
+) -> anyhow::Result<Response> {
+    // Membership of the proposer has been checked in `authenticate`.
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the multisig itself can create proposals"
+    );
+
+    let params = PARAMS.load(ctx.storage)?;
+    let until = ctx.block.timestamp + params.voting_period;
+    let (proposal_id, _) = NEXT_PROPOSAL_ID.increment(ctx.storage)?;
+
+    PROPOSALS.save(
+        ctx.storage,
+        proposal_id,
+        &Proposal {
+            title,
+            description,
+            messages,
+            status: Status::Voting {
+                params,
+                until,
+                yes: 0,
+                no: 0,
+            },
+        },
+    )?;
+
+    Ok(Response::new().add_event(Proposed { proposal_id })?)
+}
+
+fn do_vote(
+    ctx: MutableCtx,
+    proposal_id: ProposalId,
+    voter: UserIndex,
+    vote: Vote,
+    execute: bool,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the multisig itself can vote"
+    );
+
+    let mut proposal = PROPOSALS.load(ctx.storage, proposal_id)?;
+
+    let Status::Voting {
+        params,
+        until,
+        yes,
+        no,
+    } = &mut proposal.status
+    else {
+        bail!("proposal {proposal_id} is not in the voting state");
+    };
+
+    ensure!(
+        ctx.block.timestamp < *until,
+        "voting period of proposal {proposal_id} ended at {until:?}"
+    );
+
+    ensure!(
+        !VOTES.has(ctx.storage, (proposal_id, voter)),
+        "user {voter} has already voted on proposal {proposal_id}"
+    );
+
+    // The voter's membership has normally been checked in `authenticate`.
+    // However, a passed proposal may also execute the multisig with a vote, so
+    // we check again here.
+    let Some(weight) = params.members.get(&voter).copied() else {
+        bail!("user {voter} isn't a member of multisig {}", ctx.contract);
+    };
+
+    match vote {
+        Vote::Yes => *yes += weight,
+        Vote::No => *no += weight,
+    }
+
+    // The proposal passes once YES votes reach the threshold, and fails once
+    // NO votes are large enough that the threshold can no longer be reached.
+    if *yes >= params.threshold {
+        proposal.status = Status::Passed;
+    } else if *no > params.total_weight() - params.threshold {
+        proposal.status = Status::Failed;
+    }
+
+    VOTES.save(ctx.storage, (proposal_id, voter), &vote)?;
+
+    let response = Response::new().add_event(Voted {
+        proposal_id,
+        voter,
+        vote,
+    })?;
+
+    if execute && proposal.status == Status::Passed {
+        return execute_proposal(ctx, proposal_id, proposal, response);
+    }
+
+    PROPOSALS.save(ctx.storage, proposal_id, &proposal)?;
+
+    Ok(response)
+}
+
+fn do_execute(ctx: MutableCtx, proposal_id: ProposalId) -> anyhow::Result<Response> {
+    let proposal = PROPOSALS.load(ctx.storage, proposal_id)?;
+
+    ensure!(
+        proposal.status == Status::Passed,
+        "proposal {proposal_id} has not passed"
+    );
+
+    execute_proposal(ctx, proposal_id, proposal, Response::new())
+}
+
+fn execute_proposal(
+    ctx: MutableCtx,
+    proposal_id: ProposalId,
+    mut proposal: Proposal,
+    response: Response,
+) -> anyhow::Result<Response> {
+    let messages = proposal.messages.clone();
+
+    proposal.status = Status::Executed;
+
+    PROPOSALS.save(ctx.storage, proposal_id, &proposal)?;
+
+    Ok(response
+        .add_messages(messages)
+        .add_event(ProposalExecuted { proposal_id })?)
+}
+
+fn update_params(ctx: MutableCtx, params: Params) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the multisig itself can update its parameters"
+    );
+
+    validate_params(ctx.querier, &params)?;
+
+    PARAMS.save(ctx.storage, &params)?;
+
+    Ok(Response::new())
+}
+
+fn validate_params(querier: QuerierWrapper, params: &Params) -> anyhow::Result<()> {
+    ensure!(
+        !params.members.is_empty(),
+        "multisig must have at least one member"
+    );
+
+    ensure!(
+        params.members.values().all(|weight| *weight > 0),
+        "member weights must be non-zero"
+    );
+
+    let total_weight = params
+        .members
+        .values()
+        .try_fold(0u32, |total, weight| total.checked_add(*weight));
+
+    let Some(total_weight) = total_weight else {
+        bail!("total member weight overflows");
+    };
+
+    ensure!(
+        params.threshold > 0 && params.threshold <= total_weight,
+        "invalid `threshold`! bounds: (0, {total_weight}], found: {}",
+        params.threshold
+    );
+
+    ensure!(
+        params.voting_period.into_nanos() > 0,
+        "voting period must be greater than zero"
+    );
+
+    // Every member must be an existing user.
+    let factory = querier.query_account_factory()?;
+
+    for user_index in params.members.keys() {
+        ensure!(
+            querier
+                .may_query_wasm_path(factory, &USERS.path(*user_index))?
+                .is_some(),
+            "user {user_index} doesn't exist"
+        );
+    }
+
+    Ok(())
+}
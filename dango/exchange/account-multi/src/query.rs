@@ -0,0 +1,122 @@
+use {
+    crate::{PARAMS, PROPOSALS, VOTES},
+    dango_auth::{query_seen_nonces, query_status},
+    dango_primitives::{
+        Bound, DEFAULT_PAGE_LIMIT, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage,
+        Timestamp,
+    },
+    dango_types::{
+        account_factory::UserIndex,
+        account_multi::{Params, Proposal, ProposalId, QueryMsg, Status, Vote},
+    },
+    std::collections::BTreeMap,
+};
+
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+    match msg {
+        QueryMsg::Status {} => {
+            let res = query_status(ctx.storage)?;
+            res.to_json_value()
+        },
+        QueryMsg::SeenNonces {} => {
+            let res = query_seen_nonces(ctx.storage)?;
+            res.to_json_value()
+        },
+        QueryMsg::Params {} => {
+            let res = query_params(ctx.storage)?;
+            res.to_json_value()
+        },
+        QueryMsg::Proposal { proposal_id } => {
+            let res = query_proposal(ctx.storage, ctx.block.timestamp, proposal_id)?;
+            res.to_json_value()
+        },
+        QueryMsg::Proposals { start_after, limit } => {
+            let res = query_proposals(ctx.storage, ctx.block.timestamp, start_after, limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::Vote {
+            proposal_id,
+            member,
+        } => {
+            let res = query_vote(ctx.storage, proposal_id, member)?;
+            res.to_json_value()
+        },
+        QueryMsg::Votes {
+            proposal_id,
+            start_after,
+            limit,
+        } => {
+            let res = query_votes(ctx.storage, proposal_id, start_after, limit)?;
+            res.to_json_value()
+        },
+    }
+}
+
+fn query_params(storage: &dyn Storage) -> StdResult<Params> {
+    PARAMS.load(storage)
+}
+
+fn query_proposal(
+    storage: &dyn Storage,
+    current_time: Timestamp,
+    proposal_id: ProposalId,
+) -> StdResult<Proposal> {
+    PROPOSALS
+        .load(storage, proposal_id)
+        .map(|proposal| with_expiry(proposal, current_time))
+}
+
+fn query_proposals(
+    storage: &dyn Storage,
+    current_time: Timestamp,
+    start_after: Option<ProposalId>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<ProposalId, Proposal>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    PROPOSALS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| {
+            let (proposal_id, proposal) = res?;
+            Ok((proposal_id, with_expiry(proposal, current_time)))
+        })
+        .collect()
+}
+
+fn query_vote(
+    storage: &dyn Storage,
+    proposal_id: ProposalId,
+    member: UserIndex,
+) -> StdResult<Option<Vote>> {
+    VOTES.may_load(storage, (proposal_id, member))
+}
+
+fn query_votes(
+    storage: &dyn Storage,
+    proposal_id: ProposalId,
+    start_after: Option<UserIndex>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<UserIndex, Vote>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    VOTES
+        .prefix(proposal_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+/// A proposal whose voting period has ended without it having passed is failed.
+/// Its status in storage remains `Voting`, so reflect that here.
+fn with_expiry(mut proposal: Proposal, current_time: Timestamp) -> Proposal {
+    if let Status::Voting { until, .. } = &proposal.status
+        && current_time >= *until
+    {
+        proposal.status = Status::Failed;
+    }
+
+    proposal
+}
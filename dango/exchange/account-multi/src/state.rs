@@ -0,0 +1,15 @@
+use {
+    dango_storage::{Counter, Item, Map},
+    dango_types::{
+        account_factory::UserIndex,
+        account_multi::{Params, Proposal, ProposalId, Vote},
+    },
+};
+
+pub const PARAMS: Item<Params> = Item::new("params");
+
+pub const NEXT_PROPOSAL_ID: Counter<ProposalId> = Counter::new("proposal_id", 0, 1);
+
+pub const PROPOSALS: Map<ProposalId, Proposal> = Map::new("proposal");
+
+pub const VOTES: Map<(ProposalId, UserIndex), Vote> = Map::new("vote");
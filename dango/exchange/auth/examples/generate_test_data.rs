@@ -58,6 +58,7 @@ fn generate_secp256k1_session_test_data() -> anyhow::Result<()> {
         chain_id: MOCK_CHAIN_ID.to_string(),
         session_key: vk2.into(),
         expire_at: Timestamp::from_nanos(u128::MAX),
+        permissions: None,
     };
 
     // Main key signs the authorization.
@@ -146,6 +147,7 @@ fn generate_eip712_session_test_data() -> anyhow::Result<()> {
         chain_id: MOCK_CHAIN_ID.to_string(),
         session_key: vk2.into(),
         expire_at: Timestamp::from_nanos(u128::MAX),
+        permissions: None,
     });
 
     // Main key signs the SessionInfo via EIP-712.
@@ -230,6 +232,7 @@ fn generate_passkey_session_test_data() -> anyhow::Result<()> {
         chain_id: MOCK_CHAIN_ID.to_string(),
         session_key: vk2.into(),
         expire_at: Timestamp::from_nanos(u128::MAX),
+        permissions: None,
     };
 
     // Sign SessionInfo via simulated passkey/WebAuthn.
@@ -6,16 +6,16 @@ use {
     anyhow::{anyhow, bail, ensure},
     dango_primitives::{
         Addr, Api, AuthCtx, AuthMode, ByteArray, Coins, GENESIS_BLOCK_HEIGHT, Inner, JsonDeExt,
-        JsonSerExt, MutableCtx, QuerierExt, QuerierWrapper, SignData, StdError, StdResult, Storage,
-        Tx, json,
+        JsonSerExt, Message, MutableCtx, QuerierExt, QuerierWrapper, SignData, StdError, StdResult,
+        Storage, Tx, json,
     },
     dango_storage::StorageQuerier,
     dango_types::{
         DangoQuerier,
         account_factory::{RegisterUserData, User},
         auth::{
-            AccountStatus, ClientData, Credential, Key, Metadata, Nonce, SessionInfo, SignDoc,
-            Signature, StandardCredential,
+            AccountStatus, ClientData, Credential, Key, Metadata, Nonce, SessionInfo, SessionKey,
+            SessionPermissions, SignDoc, Signature, StandardCredential,
         },
     },
     data_encoding::BASE64URL_NOPAD,
@@ -37,6 +37,7 @@ pub mod account_factory {
 /// The expected storage layout of the account contract.
 pub mod account {
     use {
+        dango_primitives::Coins,
         dango_storage::{Item, Map},
         dango_types::auth::{AccountStatus, Nonce, SessionKey},
         std::collections::BTreeSet,
@@ -69,6 +70,12 @@ pub mod account {
     /// `MAX_SEEN_NONCES` nonces.
     pub const SESSION_SEEN_NONCES: Map<SessionKey, BTreeSet<Nonce>> =
         Map::new("session_seen_nonces");
+
+    /// The total amount of coins each scoped session key has attached to its
+    /// messages so far, keyed by the session public key.
+    ///
+    /// Only tracked for session keys whose permissions carry a spend limit.
+    pub const SESSION_SPENT: Map<SessionKey, Coins> = Map::new("session_spent");
 }
 
 /// The [EIP-155](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-155.md)
@@ -172,6 +179,99 @@ where
     Ok(())
 }
 
+// ---------------------------- session permissions ----------------------------
+
+/// Ensure every message in a transaction signed by a scoped session key is
+/// within the session's permissions, and return the key's new running total of
+/// coins attached to its messages, if it's to be updated.
+///
+/// The caller records the total once the signatures have been verified. Like
+/// the nonce, it's recorded during authentication, so it counts even if the
+/// transaction later fails.
+fn check_session_permissions(
+    storage: &dyn Storage,
+    session_key: SessionKey,
+    permissions: &SessionPermissions,
+    messages: &[Message],
+) -> anyhow::Result<Option<Coins>> {
+    let mut spent = Coins::new();
+
+    for msg in messages {
+        let Message::Execute(msg) = msg else {
+            bail!("session key is only allowed to execute contracts");
+        };
+
+        let variants = permissions.contracts.get(&msg.contract).ok_or_else(|| {
+            anyhow!(
+                "session key is not allowed to execute contract {}",
+                msg.contract
+            )
+        })?;
+
+        ensure!(
+            variants.is_empty()
+                || variants
+                    .iter()
+                    .any(|variant| is_execute_variant(msg.msg.inner(), variant)),
+            "session key is not allowed to send this message to contract {}",
+            msg.contract
+        );
+
+        spent.insert_many(msg.funds.clone())?;
+    }
+
+    if let Some(spend_limit) = &permissions.spend_limit {
+        if spent.is_empty() {
+            return Ok(None);
+        }
+
+        let mut total = account::SESSION_SPENT
+            .may_load(storage, session_key)?
+            .unwrap_or_default();
+
+        total.insert_many(spent)?;
+
+        for coin in &total {
+            let limit = spend_limit.amount_of(coin.denom);
+
+            ensure!(
+                *coin.amount <= limit,
+                "session spend limit exceeded for {}: {} > {}",
+                coin.denom,
+                coin.amount,
+                limit
+            );
+        }
+
+        return Ok(Some(total));
+    }
+
+    Ok(None)
+}
+
+/// Return whether an execute message is of the given variant, identified by
+/// the dot-separated path of its enum tags (e.g. `trade.submit_order`).
+///
+/// Enums are externally tagged in JSON, so each tag is either the only key of
+/// an object, or, for a unit variant, a bare string.
+fn is_execute_variant(msg: &JsonValue, variant: &str) -> bool {
+    let mut value = msg;
+    let mut tags = variant.split('.').peekable();
+
+    while let Some(tag) = tags.next() {
+        match value {
+            JsonValue::Object(obj) if obj.len() == 1 => match obj.get(tag) {
+                Some(inner) => value = inner,
+                None => return false,
+            },
+            JsonValue::String(unit) => return unit == tag && tags.peek().is_none(),
+            _ => return false,
+        }
+    }
+
+    true
+}
+
 /// Query the account's status.
 pub fn query_status(storage: &dyn Storage) -> StdResult<AccountStatus> {
     account::STATUS
@@ -464,6 +564,16 @@ pub fn verify_nonce_and_signature(
                     session.session_info.chain_id
                 );
 
+                let new_spent = match &session.session_info.permissions {
+                    Some(permissions) => check_session_permissions(
+                        ctx.storage,
+                        session.session_info.session_key,
+                        permissions,
+                        &sign_doc.messages,
+                    )?,
+                    None => None,
+                };
+
                 // Verify the `SessionInfo` signature.
                 //
                 // TODO: we can consider saving authorized session keys in the
@@ -482,6 +592,16 @@ pub fn verify_nonce_and_signature(
                     Signature::Secp256k1(session.session_signature),
                     VerifyData::Transaction(sign_doc),
                 )?;
+
+                // Only now that the session is known to be authorized, record
+                // what it has spent.
+                if let Some(new_spent) = new_spent {
+                    account::SESSION_SPENT.save(
+                        ctx.storage,
+                        session.session_info.session_key,
+                        &new_spent,
+                    )?;
+                }
             } else {
                 // Verify the `SignDoc` signature.
                 verify_signature(ctx.api, key, signature, VerifyData::Transaction(sign_doc))?;
@@ -582,17 +702,31 @@ fn arbitrary_eip712_domain() -> Eip712Domain {
     }
 }
 
-/// The canonical EIP-712 resolver for a [`SessionInfo`]. All fields are atomic
-/// strings, so no special encoding is needed.
-fn session_eip712_resolver() -> StdResult<Resolver> {
-    json!({
-        "Message": [
-            { "name": "chain_id",    "type": "string" },
-            { "name": "expire_at",   "type": "string" },
-            { "name": "session_key", "type": "string" },
-        ],
-    })
-    .deserialize_json()
+/// The canonical EIP-712 resolver for a [`SessionInfo`]. `has_permissions`
+/// toggles the optional `permissions` field, omitted from serialization when
+/// `None`; when present, it's typed as `string` (see [`session_eip712_message`]).
+/// All other fields are atomic strings, so no special encoding is needed.
+fn session_eip712_resolver(has_permissions: bool) -> StdResult<Resolver> {
+    let mut fields = vec![
+        json!({ "name": "chain_id",  "type": "string" }),
+        json!({ "name": "expire_at", "type": "string" }),
+    ];
+
+    // Field order must match the order the client signs, which is alphabetical.
+    if has_permissions {
+        fields.push(json!({ "name": "permissions", "type": "string" }));
+    }
+
+    fields.push(json!({ "name": "session_key", "type": "string" }));
+
+    json!({ "Message": fields }).deserialize_json()
+}
+
+/// The EIP-712 `message` value for a [`SessionInfo`]: the same payload the
+/// other signers sign (see [`SessionInfo::to_signing_json`]), with
+/// `permissions`, if any, bound as its canonical JSON string.
+fn session_eip712_message(session_info: &SessionInfo) -> StdResult<JsonValue> {
+    session_info.to_signing_json().map(Inner::into_inner)
 }
 
 /// The canonical EIP-712 resolver for a [`RegisterUserData`]. `key` is typed as
@@ -648,9 +782,9 @@ pub fn build_eip712_typed_data(data: &VerifyData) -> StdResult<TypedData> {
             tx_eip712_message(sign_doc)?,
         ),
         VerifyData::Session(session_info) => (
-            session_eip712_resolver()?,
+            session_eip712_resolver(session_info.permissions.is_some())?,
             arbitrary_eip712_domain(),
-            session_info.to_json_value()?.into_inner(),
+            session_eip712_message(session_info)?,
         ),
         VerifyData::Onboard(onboard) => (
             onboard_eip712_resolver(onboard.referrer.is_some())?,
@@ -794,14 +928,16 @@ mod tests {
         super::*,
         crate::account_factory::USERS,
         dango_primitives::{
-            Addr, AuthMode, Hash256, MockContext, MockQuerier, MockStorage, ResultExt, addr,
-            btree_map, hash,
+            Addr, AuthMode, Hash256, HashExt, MockContext, MockQuerier, MockStorage, NonEmpty,
+            ResultExt, Timestamp, addr, btree_map, hash,
         },
         dango_types::{
             account_factory::Username,
+            auth::SessionCredential,
             config::{AppAddresses, AppConfig},
         },
         hex_literal::hex,
+        k256::{ecdsa::signature::hazmat::PrehashSigner, elliptic_curve::Generate},
         std::str::FromStr,
     };
 
@@ -1544,4 +1680,331 @@ mod tests {
             "nonce near u32::MAX caused overflow rejection: {err_str}"
         );
     }
+
+    #[test]
+    fn is_execute_variant_works() {
+        let msg = json!({ "trade": { "submit_order": { "pair_id": "perp/btcusd" } } });
+
+        assert!(is_execute_variant(&msg, "trade"));
+        assert!(is_execute_variant(&msg, "trade.submit_order"));
+        assert!(!is_execute_variant(&msg, "trade.cancel_order"));
+        assert!(!is_execute_variant(&msg, "maintain.submit_order"));
+
+        // Unit variants are serialized as bare strings.
+        let msg = json!({ "trade": { "cancel_order": "all" } });
+
+        assert!(is_execute_variant(&msg, "trade.cancel_order.all"));
+        assert!(!is_execute_variant(&msg, "trade.cancel_order.all.more"));
+    }
+
+    #[test]
+    fn session_permissions_are_enforced() {
+        const PERPS: Addr = Addr::mock(1);
+        const SESSION_KEY: SessionKey = ByteArray::from_inner([2; 33]);
+
+        let mut storage = MockStorage::new();
+
+        let permissions = SessionPermissions {
+            contracts: btree_map! {
+                PERPS => ["trade.submit_order".to_string(), "trade.deposit".to_string()].into(),
+            },
+            spend_limit: Some(Coins::one("bridge/usdc", 100).unwrap()),
+        };
+
+        let submit_order = json!({ "trade": { "submit_order": {} } });
+        let withdraw = json!({ "trade": { "withdraw": {} } });
+        let deposit = json!({ "trade": { "deposit": {} } });
+
+        // An allowed variant passes. Without funds attached, there's no spending
+        // to record.
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &submit_order, Coins::new()).unwrap()],
+        )
+        .should_succeed_and_equal(None);
+
+        // A variant not in the allow-list is rejected.
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &withdraw, Coins::new()).unwrap()],
+        )
+        .should_fail_with_error("not allowed to send this message");
+
+        // So is any other contract, and any other message type.
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(Addr::mock(3), &submit_order, Coins::new()).unwrap()],
+        )
+        .should_fail_with_error("not allowed to execute contract");
+
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::transfer(Addr::mock(3), Coins::one("bridge/usdc", 1).unwrap()).unwrap()],
+        )
+        .should_fail_with_error("only allowed to execute contracts");
+
+        // Spending is tracked cumulatively against the limit.
+        let spent = check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &deposit, Coins::one("bridge/usdc", 60).unwrap()).unwrap()],
+        )
+        .unwrap()
+        .unwrap();
+
+        account::SESSION_SPENT
+            .save(&mut storage, SESSION_KEY, &spent)
+            .unwrap();
+
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &deposit, Coins::one("bridge/usdc", 41).unwrap()).unwrap()],
+        )
+        .should_fail_with_error("session spend limit exceeded");
+
+        let spent = check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &deposit, Coins::one("bridge/usdc", 40).unwrap()).unwrap()],
+        )
+        .unwrap()
+        .unwrap();
+
+        account::SESSION_SPENT
+            .save(&mut storage, SESSION_KEY, &spent)
+            .unwrap();
+
+        assert_eq!(
+            account::SESSION_SPENT.load(&storage, SESSION_KEY).unwrap(),
+            Coins::one("bridge/usdc", 100).unwrap()
+        );
+
+        // Denoms absent from the limit can't be spent at all.
+        check_session_permissions(
+            &storage,
+            SESSION_KEY,
+            &permissions,
+            &[Message::execute(PERPS, &deposit, Coins::one("uatom", 1).unwrap()).unwrap()],
+        )
+        .should_fail_with_error("session spend limit exceeded for uatom");
+    }
+
+    /// A scoped session key's spending must only be recorded once the
+    /// transaction is known to be signed by it.
+    #[test]
+    fn session_spending_is_recorded_after_signature_verification() {
+        const PERPS: Addr = Addr::mock(1);
+        const CHAIN_ID: &str = "dev-1";
+
+        let user_address = Addr::mock(2);
+        let user_index = 123;
+
+        let (user_sk, user_key, user_key_hash) = secp256k1_key_pair();
+        let (session_sk, session_key, _) = secp256k1_key_pair();
+        let (rogue_sk, ..) = secp256k1_key_pair();
+
+        let sign = |sk: &k256::ecdsa::SigningKey, data: &dyn Fn() -> Vec<u8>| {
+            let signature: k256::ecdsa::Signature =
+                sk.sign_prehash(&data().sha2_256().into_inner()).unwrap();
+            ByteArray::from_inner(signature.to_bytes().into())
+        };
+
+        let session_info = SessionInfo {
+            chain_id: CHAIN_ID.to_string(),
+            session_key,
+            expire_at: Timestamp::from_nanos(u128::MAX),
+            permissions: Some(SessionPermissions {
+                contracts: btree_map! { PERPS => BTreeSet::new() },
+                spend_limit: Some(Coins::one("bridge/usdc", 100).unwrap()),
+            }),
+        };
+
+        let authorization = StandardCredential {
+            key_hash: user_key_hash,
+            signature: Signature::Secp256k1(sign(&user_sk, &|| {
+                session_info.to_prehash_sign_data().unwrap()
+            })),
+        };
+
+        let msgs = NonEmpty::new_unchecked(vec![
+            Message::execute(
+                PERPS,
+                &json!({ "trade": { "deposit": {} } }),
+                Coins::one("bridge/usdc", 60).unwrap(),
+            )
+            .unwrap(),
+        ]);
+
+        // Build a transaction with the given nonce, signed by the given key in
+        // place of the session key.
+        let tx = |nonce: Nonce, signer: &k256::ecdsa::SigningKey| {
+            let metadata = Metadata {
+                user_index,
+                chain_id: CHAIN_ID.to_string(),
+                nonce,
+                expiry: None,
+            };
+
+            let sign_doc = SignDoc {
+                sender: user_address,
+                gas_limit: 1_000_000,
+                messages: msgs.clone(),
+                data: metadata.clone(),
+            };
+
+            Tx {
+                sender: user_address,
+                gas_limit: 1_000_000,
+                msgs: msgs.clone(),
+                data: metadata.to_json_value().unwrap(),
+                credential: Credential::Session(SessionCredential {
+                    session_info: session_info.clone(),
+                    session_signature: sign(signer, &|| sign_doc.to_prehash_sign_data().unwrap()),
+                    authorization: authorization.clone(),
+                })
+                .to_json_value()
+                .unwrap(),
+            }
+        };
+
+        let mut storage = MockStorage::new();
+
+        account::STATUS
+            .save(&mut storage, &AccountStatus::Active)
+            .unwrap();
+
+        let querier = MockQuerier::new()
+            .with_app_config(AppConfig {
+                addresses: AppAddresses {
+                    account_factory: ACCOUNT_FACTORY,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap()
+            .with_raw_contract_storage(ACCOUNT_FACTORY, |storage| {
+                let user = User {
+                    index: user_index,
+                    name: Username::default_for_index(user_index),
+                    accounts: btree_map! { 0u32 => user_address },
+                    keys: btree_map! { user_key_hash => Key::Secp256k1(user_key) },
+                };
+                USERS.save(storage, user_index, &user).unwrap();
+            });
+
+        let mut ctx = MockContext::new()
+            .with_storage(storage)
+            .with_querier(querier)
+            .with_contract(user_address)
+            .with_chain_id(CHAIN_ID)
+            .with_mode(AuthMode::Finalize);
+
+        // A transaction not signed by the session key is rejected, and nothing
+        // is recorded against the session's spend limit.
+        authenticate_tx(ctx.as_auth(), tx(0, &rogue_sk), None).should_fail();
+
+        assert!(
+            account::SESSION_SPENT
+                .may_load(&ctx.storage, session_key)
+                .unwrap()
+                .is_none()
+        );
+
+        // A properly signed one is accepted, and its spending recorded.
+        authenticate_tx(ctx.as_auth(), tx(1, &session_sk), None).should_succeed();
+
+        assert_eq!(
+            account::SESSION_SPENT
+                .load(&ctx.storage, session_key)
+                .unwrap(),
+            Coins::one("bridge/usdc", 60).unwrap()
+        );
+    }
+
+    /// Every signer signs a scoped session as its canonical JSON, with the
+    /// permissions bound as their own canonical JSON string. The TypeScript SDK
+    /// has the same fixture in `createSession.spec.ts`.
+    #[test]
+    fn session_permissions_sign_data_is_canonical() {
+        let session_info = SessionInfo {
+            chain_id: "dev-1".to_string(),
+            session_key: ByteArray::from_inner([2; 33]),
+            expire_at: Timestamp::from_seconds(100),
+            permissions: Some(SessionPermissions {
+                contracts: btree_map! {
+                    Addr::mock(2) => BTreeSet::new(),
+                    Addr::mock(1) => [
+                        "trade.submit_order".to_string(),
+                        "trade.cancel_order".to_string(),
+                    ].into(),
+                },
+                spend_limit: Some(
+                    Coins::try_from(btree_map! {
+                        "uatom" => 5,
+                        "bridge/usdc" => 100,
+                    })
+                    .unwrap(),
+                ),
+            }),
+        };
+
+        assert_eq!(
+            String::from_utf8(session_info.to_prehash_sign_data().unwrap()).unwrap(),
+            SESSION_PERMISSIONS_SIGN_DATA
+        );
+    }
+
+    const SESSION_PERMISSIONS_SIGN_DATA: &str = r#"{"chain_id":"dev-1","expire_at":"100","permissions":"{\"contracts\":{\"0x0000000000000000000000000000000000000001\":[\"trade.cancel_order\",\"trade.submit_order\"],\"0x0000000000000000000000000000000000000002\":[]},\"spend_limit\":{\"bridge/usdc\":\"100\",\"uatom\":\"5\"}}","session_key":"AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgIC"}"#;
+
+    fn secp256k1_key_pair() -> (k256::ecdsa::SigningKey, ByteArray<33>, Hash256) {
+        let sk = k256::ecdsa::SigningKey::generate();
+        let pk: [u8; 33] = sk
+            .verifying_key()
+            .to_sec1_point(true)
+            .as_bytes()
+            .try_into()
+            .unwrap();
+
+        (sk, pk.into(), pk.sha2_256())
+    }
+
+    /// A `SessionInfo` with permissions must produce valid EIP-712 typed data
+    /// that commits to the allow-list.
+    #[test]
+    fn session_permissions_are_bound_in_eip712() {
+        let mut session_info = SessionInfo {
+            chain_id: "dev-1".to_string(),
+            session_key: ByteArray::from_inner([2; 33]),
+            expire_at: Timestamp::from_seconds(100),
+            permissions: Some(SessionPermissions {
+                contracts: btree_map! { Addr::mock(1) => BTreeSet::new() },
+                spend_limit: None,
+            }),
+        };
+
+        let hash = |session_info: &SessionInfo| {
+            build_eip712_typed_data(&VerifyData::Session(session_info.clone()))
+                .unwrap()
+                .eip712_signing_hash()
+                .unwrap()
+        };
+
+        let original = hash(&session_info);
+
+        session_info.permissions.as_mut().unwrap().spend_limit = Some(Coins::new());
+
+        assert_ne!(hash(&session_info), original);
+    }
 }
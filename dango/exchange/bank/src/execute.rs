@@ -1,15 +1,16 @@
 use {
-    crate::{BALANCES, METADATAS, NAMESPACE_OWNERS, ORPHANED_TRANSFERS, SUPPLIES},
+    crate::{BALANCES, METADATAS, NAMESPACE_OWNERS, ORPHANED_TRANSFERS, SEND_HOOKS, SUPPLIES},
     anyhow::{anyhow, bail, ensure},
     dango_math::{IsZero, Number, NumberConst, Uint128},
     dango_primitives::{
-        Addr, BankMsg, Coins, Denom, EventBuilder, MutableCtx, Part, QuerierExt, Response,
-        StdError, StdResult, Storage, SudoCtx,
+        Addr, BankMsg, Coin, Coins, Denom, EventBuilder, MutableCtx, Part, QuerierExt,
+        QuerierWrapper, Response, StdError, StdResult, Storage, SudoCtx,
     },
     dango_types::bank::{
-        Burned, ExecuteMsg, InstantiateMsg, Metadata, Minted, Received, Sent, TransferOrphaned,
+        Burned, ExecuteMsg, InstantiateMsg, Metadata, Minted, QueryBeforeSendHookRequest, Received,
+        SendHookQuery, Sent, TransferOrphaned,
     },
-    std::collections::HashMap,
+    std::collections::{BTreeMap, HashMap},
 };
 
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
@@ -52,6 +53,13 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
         ExecuteMsg::SetNamespaceOwner { namespace, owner } => {
             set_namespace_owner(ctx, namespace, owner)
         },
+        ExecuteMsg::RevokeNamespaceOwner { namespace } => revoke_namespace_owner(ctx, namespace),
+        ExecuteMsg::TransferNamespace {
+            namespace,
+            new_owner,
+        } => transfer_namespace(ctx, namespace, new_owner),
+        ExecuteMsg::RenounceNamespace { namespace } => renounce_namespace(ctx, namespace),
+        ExecuteMsg::SetSendHook { namespace, hook } => set_send_hook(ctx, namespace, hook),
         ExecuteMsg::SetMetadata { denom, metadata } => set_metadata(ctx, denom, metadata),
         ExecuteMsg::Mint { to, coins } => mint(ctx, to, coins),
         ExecuteMsg::Burn { from, coins } => burn(ctx, from, coins),
@@ -81,8 +89,73 @@ fn set_namespace_owner(ctx: MutableCtx, namespace: Part, owner: Addr) -> anyhow:
     Ok(Response::new())
 }
 
+fn revoke_namespace_owner(ctx: MutableCtx, namespace: Part) -> anyhow::Result<Response> {
+    // Only chain owner can revoke namespace.
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "you don't have the right, O you don't have the right"
+    );
+
+    ensure!(
+        NAMESPACE_OWNERS.has(ctx.storage, &namespace),
+        "namespace `{namespace}` isn't granted to anyone"
+    );
+
+    // The send hook is kept. The chain owner can grant the namespace to a new
+    // owner, who can then change it.
+    NAMESPACE_OWNERS.remove(ctx.storage, &namespace);
+
+    Ok(Response::new())
+}
+
+fn transfer_namespace(
+    ctx: MutableCtx,
+    namespace: Part,
+    new_owner: Addr,
+) -> anyhow::Result<Response> {
+    ensure_namespace_owner(&ctx, &namespace)?;
+
+    // Ensure the new owner exists, so that the namespace isn't lost to a typo.
+    ctx.querier
+        .query_contract(new_owner)
+        .map_err(|_| anyhow!("new owner `{new_owner}` doesn't exist"))?;
+
+    NAMESPACE_OWNERS.save(ctx.storage, &namespace, &new_owner)?;
+
+    Ok(Response::new())
+}
+
+fn renounce_namespace(ctx: MutableCtx, namespace: Part) -> anyhow::Result<Response> {
+    ensure_namespace_owner(&ctx, &namespace)?;
+
+    NAMESPACE_OWNERS.remove(ctx.storage, &namespace);
+
+    Ok(Response::new())
+}
+
+fn set_send_hook(ctx: MutableCtx, namespace: Part, hook: Option<Addr>) -> anyhow::Result<Response> {
+    ensure_namespace_owner(&ctx, &namespace)?;
+
+    match hook {
+        Some(hook) => {
+            // Ensure the hook exists. A non-existent hook would reject every
+            // transfer of the namespace's tokens.
+            ctx.querier
+                .query_contract(hook)
+                .map_err(|_| anyhow!("send hook `{hook}` doesn't exist"))?;
+
+            SEND_HOOKS.save(ctx.storage, &namespace, &hook)?;
+        },
+        None => {
+            SEND_HOOKS.remove(ctx.storage, &namespace);
+        },
+    }
+
+    Ok(Response::new())
+}
+
 fn set_metadata(ctx: MutableCtx, denom: Denom, metadata: Metadata) -> anyhow::Result<Response> {
-    ensure_namespace_owner(&ctx, &denom)?;
+    ensure_denom_owner(&ctx, &denom)?;
 
     METADATAS.save(ctx.storage, &denom, &metadata)?;
 
@@ -106,7 +179,7 @@ fn mint(ctx: MutableCtx, to: Addr, coins: Coins) -> anyhow::Result<Response> {
     };
 
     for coin in &coins {
-        ensure_namespace_owner(&ctx, coin.denom)?;
+        ensure_denom_owner(&ctx, coin.denom)?;
 
         increase_supply(ctx.storage, coin.denom, *coin.amount)?;
         increase_balance(ctx.storage, &recipient, coin.denom, *coin.amount)?;
@@ -131,7 +204,7 @@ fn mint(ctx: MutableCtx, to: Addr, coins: Coins) -> anyhow::Result<Response> {
 
 fn burn(ctx: MutableCtx, from: Addr, coins: Coins) -> anyhow::Result<Response> {
     for coin in &coins {
-        ensure_namespace_owner(&ctx, coin.denom)?;
+        ensure_denom_owner(&ctx, coin.denom)?;
 
         decrease_supply(ctx.storage, coin.denom, *coin.amount)?;
         decrease_balance(ctx.storage, &from, coin.denom, *coin.amount)?;
@@ -144,15 +217,11 @@ fn burn(ctx: MutableCtx, from: Addr, coins: Coins) -> anyhow::Result<Response> {
     })?)
 }
 
-fn ensure_namespace_owner(ctx: &MutableCtx, denom: &Denom) -> anyhow::Result<()> {
+fn ensure_denom_owner(ctx: &MutableCtx, denom: &Denom) -> anyhow::Result<()> {
     match denom.namespace() {
         // The denom has a namespace. The namespace's owner can mint/burn.
         Some(part) => {
-            let maybe_owner = NAMESPACE_OWNERS.may_load(ctx.storage, part)?;
-            ensure!(
-                maybe_owner == Some(ctx.sender),
-                "sender does not own the namespace `{part}`"
-            );
+            ensure_namespace_owner(ctx, part)?;
         },
         // The denom is a top-level denom (i.e. doesn't have a namespace).
         // Only the chain owner can mint/burn.
@@ -167,6 +236,17 @@ fn ensure_namespace_owner(ctx: &MutableCtx, denom: &Denom) -> anyhow::Result<()>
     Ok(())
 }
 
+fn ensure_namespace_owner(ctx: &MutableCtx, namespace: &Part) -> anyhow::Result<()> {
+    let maybe_owner = NAMESPACE_OWNERS.may_load(ctx.storage, namespace)?;
+
+    ensure!(
+        maybe_owner == Some(ctx.sender),
+        "sender does not own the namespace `{namespace}`"
+    );
+
+    Ok(())
+}
+
 fn recover_transfer(ctx: MutableCtx, sender: Addr, recipient: Addr) -> anyhow::Result<Response> {
     ensure!(
         ctx.sender == sender
@@ -228,6 +308,10 @@ pub fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> anyhow::Result<Response> {
     let mut events = EventBuilder::with_capacity(msg.transfers.len() * 3);
 
     for (to, coins) in msg.transfers {
+        // Query the send hooks of the namespaces, which may reject the transfer.
+        // The hooks see the intended recipient even if it doesn't exist.
+        call_send_hooks(ctx.storage, ctx.querier, msg.from, to, &coins)?;
+
         // If the recipient exists, increase the recipient's balance. Otherwise,
         // 1. withhold the tokens in the bank contract;
         // 2. record the transfer in the `ORPHANED_TRANSFERS` map.
@@ -274,6 +358,42 @@ pub fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> anyhow::Result<Response> {
     Ok(Response::new().add_events(events)?)
 }
 
+fn call_send_hooks(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    from: Addr,
+    to: Addr,
+    coins: &Coins,
+) -> anyhow::Result<()> {
+    // Group the coins by hook, so that each hook is queried once per transfer.
+    let mut coins_by_hook = BTreeMap::<Addr, Coins>::new();
+
+    for coin in coins {
+        let Some(namespace) = coin.denom.namespace() else {
+            continue;
+        };
+
+        if let Some(hook) = SEND_HOOKS.may_load(storage, namespace)? {
+            coins_by_hook
+                .entry(hook)
+                .or_default()
+                .insert(Coin::new(coin.denom.clone(), *coin.amount)?)?;
+        }
+    }
+
+    for (hook, coins) in coins_by_hook {
+        querier
+            .query_wasm_smart(
+                hook,
+                QueryBeforeSendHookRequest(SendHookQuery::BeforeSend { from, to, coins }),
+            )
+            .map(|res| res.into_before_send())
+            .map_err(|err| anyhow!("transfer rejected by send hook `{hook}`: {err}"))?;
+    }
+
+    Ok(())
+}
+
 fn increase_supply(
     storage: &mut dyn Storage,
     denom: &Denom,
@@ -1,5 +1,5 @@
 use {
-    crate::{BALANCES, METADATAS, NAMESPACE_OWNERS, ORPHANED_TRANSFERS, SUPPLIES},
+    crate::{BALANCES, METADATAS, NAMESPACE_OWNERS, ORPHANED_TRANSFERS, SEND_HOOKS, SUPPLIES},
     dango_math::{NumberConst, Uint128},
     dango_primitives::{
         Addr, BankQuery, BankQueryResponse, Bound, Coin, Coins, DEFAULT_PAGE_LIMIT, Denom,
@@ -23,6 +23,14 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             let res = query_namespace_owners(ctx, start_after, limit)?;
             res.to_json_value()
         },
+        QueryMsg::SendHook { namespace } => {
+            let res = query_send_hook(ctx, namespace)?;
+            res.to_json_value()
+        },
+        QueryMsg::SendHooks { start_after, limit } => {
+            let res = query_send_hooks(ctx, start_after, limit)?;
+            res.to_json_value()
+        },
         QueryMsg::Metadata { denom } => {
             let res = query_metadata(ctx, denom)?;
             res.to_json_value()
@@ -76,6 +84,24 @@ fn query_namespace_owners(
         .collect()
 }
 
+fn query_send_hook(ctx: ImmutableCtx, namespace: Part) -> StdResult<Option<Addr>> {
+    SEND_HOOKS.may_load(ctx.storage, &namespace)
+}
+
+fn query_send_hooks(
+    ctx: ImmutableCtx,
+    start_after: Option<Part>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Part, Addr>> {
+    let start = start_after.as_ref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    SEND_HOOKS
+        .range(ctx.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 fn query_metadata(ctx: ImmutableCtx, denom: Denom) -> StdResult<Metadata> {
     METADATAS.load(ctx.storage, &denom)
 }
@@ -7,6 +7,8 @@ use {
 
 pub const NAMESPACE_OWNERS: Map<&Part, Addr> = Map::new("namespace_owner");
 
+pub const SEND_HOOKS: Map<&Part, Addr> = Map::new("send_hook");
+
 pub const METADATAS: Map<&Denom, Metadata> = Map::new("metadata");
 
 pub const SUPPLIES: Map<&Denom, Uint128> = Map::new("supply");
@@ -1,14 +1,14 @@
 use {
     crate::{
-        FROZEN_WITHDRAWAL_REQUESTS, NEXT_WITHDRAWAL_REQUEST_ID, PERSONAL_QUOTAS, RESERVES,
-        REVERSE_ROUTES, ROUTES, WITHDRAWAL_FEES, WITHDRAWAL_GUARDIAN, WITHDRAWAL_REQUESTS,
-        rate_limit,
+        FROZEN_WITHDRAWAL_REQUESTS, INTERCHAIN_GAS_PAYMENTS, NEXT_WITHDRAWAL_REQUEST_ID,
+        PERSONAL_QUOTAS, RESERVES, REVERSE_ROUTES, ROUTES, WITHDRAWAL_FEES, WITHDRAWAL_GUARDIAN,
+        WITHDRAWAL_REQUESTS, rate_limit,
     },
     anyhow::{anyhow, bail, ensure},
     dango_math::{IsZero, Number, NumberConst, Uint128},
     dango_primitives::{
-        Addr, Coin, Coins, Denom, Inner, Message, MutableCtx, Op, Order, QuerierExt, Response,
-        StdError, StdResult, Storage, SudoCtx, Timestamp, coins,
+        Addr, Coin, Coins, Denom, Inner, Message, MutableCtx, Op, Order, QuerierExt,
+        QuerierWrapper, Response, StdError, StdResult, Storage, SudoCtx, Timestamp, coins,
     },
     dango_types::{
         bank,
@@ -18,7 +18,7 @@ use {
             WithdrawalConfiscated, WithdrawalFee, WithdrawalFrozen, WithdrawalRejected,
             WithdrawalRequest, WithdrawalRequested, WithdrawalResponse, WithdrawalStatus,
             Withdrawn,
-            bridge::{self, BridgeMsg},
+            bridge::{self, BridgeMsg, BridgeQuery},
         },
     },
     std::collections::{BTreeMap, BTreeSet},
@@ -314,8 +314,10 @@ fn receive_remote(
 }
 
 fn transfer_remote(ctx: MutableCtx, remote: Remote, recipient: Addr32) -> anyhow::Result<Response> {
-    // The user must have sent exactly one coin.
-    let coin = ctx.funds.into_one_coin()?;
+    // The user must have sent exactly one coin to withdraw, plus the
+    // interchain gas payment quoted by the bridge, if any.
+    let (coin, interchain_gas) =
+        split_interchain_gas(ctx.storage, ctx.querier, ctx.funds, remote, recipient)?;
 
     // Fail fast if the withdrawal couldn't be executed against the current
     // state: missing route, amount not covering the fee, insufficient
@@ -323,7 +325,16 @@ fn transfer_remote(ctx: MutableCtx, remote: Remote, recipient: Addr32) -> anyhow
     // nothing is consumed or recorded until the request is approved, so
     // several pending requests may each pass this validation yet still
     // fail at approval, where it runs again authoritatively.
-    validate_withdrawal(ctx.storage, ctx.sender, &coin, remote, ctx.block.timestamp)?;
+    validate_withdrawal(
+        ctx.storage,
+        ctx.querier,
+        ctx.sender,
+        &coin,
+        remote,
+        recipient,
+        &interchain_gas,
+        ctx.block.timestamp,
+    )?;
 
     // Hold the funds in escrow and store the request. Fees, reserves,
     // personal quotas, and rate limits are all applied if and when the
@@ -343,6 +354,10 @@ fn transfer_remote(ctx: MutableCtx, remote: Remote, recipient: Addr32) -> anyhow
         },
     )?;
 
+    if interchain_gas.is_non_empty() {
+        INTERCHAIN_GAS_PAYMENTS.save(ctx.storage, id, &interchain_gas)?;
+    }
+
     Ok(Response::new().add_event(WithdrawalRequested {
         id,
         user: ctx.sender,
@@ -350,9 +365,87 @@ fn transfer_remote(ctx: MutableCtx, remote: Remote, recipient: Addr32) -> anyhow
         recipient,
         denom: coin.denom,
         amount: coin.amount,
+        interchain_gas,
     })?)
 }
 
+/// Split the funds sent along `TransferRemote` into the coin to withdraw and
+/// the interchain gas payment quoted by the bridge.
+///
+/// If the payment is in the same denom as the withdrawal, the funds are a
+/// single coin covering both. Otherwise, the funds are two coins, and since
+/// both denoms may have routes to the remote, each is tried as the one to
+/// withdraw: it's the one left over once its route's quote is deducted.
+fn split_interchain_gas(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    funds: Coins,
+    remote: Remote,
+    recipient: Addr32,
+) -> anyhow::Result<(Coin, Coins)> {
+    if funds.len() <= 1 {
+        let mut coin = funds.into_one_coin()?;
+        let bridge = REVERSE_ROUTES.load(storage, (&coin.denom, remote))?;
+        let interchain_gas =
+            quote_transfer_remote(querier, bridge, remote, coin.amount, recipient)?;
+
+        for fee in &interchain_gas {
+            // Strictly greater, to leave a non-zero amount to withdraw.
+            ensure!(
+                fee.denom == &coin.denom && coin.amount > *fee.amount,
+                "insufficient interchain gas payment! required: {interchain_gas}, sent: {coin}"
+            );
+
+            coin.amount.checked_sub_assign(*fee.amount)?;
+        }
+
+        return Ok((coin, interchain_gas));
+    }
+
+    for candidate in &funds {
+        let Some(bridge) = REVERSE_ROUTES.may_load(storage, (candidate.denom, remote))? else {
+            continue;
+        };
+
+        let interchain_gas =
+            quote_transfer_remote(querier, bridge, remote, *candidate.amount, recipient)?;
+
+        let mut rest = funds.clone();
+        if rest.deduct_many(interchain_gas.clone()).is_err() {
+            continue;
+        }
+
+        match rest.into_one_coin() {
+            Ok(coin) if coin.denom == *candidate.denom => return Ok((coin, interchain_gas)),
+            _ => continue,
+        }
+    }
+
+    bail!(
+        "funds must be the coin to withdraw plus the interchain gas payment quoted by the bridge: {funds}"
+    );
+}
+
+/// Query the fee the bridge charges for a remote transfer.
+fn quote_transfer_remote(
+    querier: QuerierWrapper,
+    bridge: Addr,
+    remote: Remote,
+    amount: Uint128,
+    recipient: Addr32,
+) -> StdResult<Coins> {
+    querier
+        .query_wasm_smart(
+            bridge,
+            bridge::QueryBridgeRequest(BridgeQuery::QuoteTransferRemote {
+                remote,
+                amount,
+                recipient,
+            }),
+        )
+        .map(|res| res.into_quote_transfer_remote())
+}
+
 fn respond_to_withdrawal(
     ctx: MutableCtx,
     id: u64,
@@ -371,6 +464,9 @@ fn respond_to_withdrawal(
 
     let owner = ctx.querier.query_owner()?;
     let guardian = WITHDRAWAL_GUARDIAN.may_load(ctx.storage)?;
+    let interchain_gas = INTERCHAIN_GAS_PAYMENTS
+        .may_load(ctx.storage, id)?
+        .unwrap_or_default();
 
     ensure!(
         ctx.sender == owner || Some(ctx.sender) == guardian,
@@ -389,6 +485,7 @@ fn respond_to_withdrawal(
     match response {
         WithdrawalResponse::Approve => {
             queue.remove(ctx.storage, id);
+            INTERCHAIN_GAS_PAYMENTS.remove(ctx.storage, id);
 
             // Validation runs here, not inside `process_withdrawal`: if the
             // withdrawal can no longer be executed (the fee, reserve, or
@@ -398,14 +495,20 @@ fn respond_to_withdrawal(
             // nothing, so the refund path carries no partial state.
             match validate_withdrawal(
                 ctx.storage,
+                ctx.querier,
                 request.user,
                 &request.coin,
                 request.remote,
+                request.recipient,
+                &interchain_gas,
                 ctx.block.timestamp,
             ) {
                 Ok(plan) => process_withdrawal(ctx, id, request, plan),
                 Err(err) => Ok(Response::new()
-                    .add_message(Message::transfer(request.user, request.coin.clone())?)
+                    .add_message(Message::transfer(
+                        request.user,
+                        escrow_of(&request.coin, interchain_gas)?,
+                    )?)
                     .add_event(WithdrawalApprovalFailed {
                         id,
                         user: request.user,
@@ -417,11 +520,15 @@ fn respond_to_withdrawal(
         },
         WithdrawalResponse::Reject => {
             queue.remove(ctx.storage, id);
+            INTERCHAIN_GAS_PAYMENTS.remove(ctx.storage, id);
 
             // Refund the full escrowed amount to the user; no fee is
             // charged on a rejected withdrawal.
             Ok(Response::new()
-                .add_message(Message::transfer(request.user, request.coin.clone())?)
+                .add_message(Message::transfer(
+                    request.user,
+                    escrow_of(&request.coin, interchain_gas)?,
+                )?)
                 .add_event(WithdrawalRejected {
                     id,
                     user: request.user,
@@ -467,9 +574,13 @@ fn respond_to_withdrawal(
             );
 
             FROZEN_WITHDRAWAL_REQUESTS.remove(ctx.storage, id);
+            INTERCHAIN_GAS_PAYMENTS.remove(ctx.storage, id);
 
             Ok(Response::new()
-                .add_message(Message::transfer(owner, request.coin.clone())?)
+                .add_message(Message::transfer(
+                    owner,
+                    escrow_of(&request.coin, interchain_gas)?,
+                )?)
                 .add_event(WithdrawalConfiscated {
                     id,
                     user: request.user,
@@ -522,9 +633,11 @@ fn process_withdrawal(
 
     let (bank, owner) = ctx.querier.query_bank_and_owner()?;
 
-    // 1. Call the bridge contract to make the remote transfer.
+    // 1. Call the bridge contract to make the remote transfer, paying the
+    //    interchain gas.
     // 2. Burn the alloyed token to be transferred (only if the token is not native on Dango).
     // 3. Send the withdrawal fee to the chain owner.
+    // 4. Refund the interchain gas escrowed in excess of the current quote.
     Ok(Response::new()
         .add_message(Message::execute(
             bridge,
@@ -533,7 +646,7 @@ fn process_withdrawal(
                 amount: coin.amount,
                 recipient,
             }),
-            Coins::new(),
+            plan.interchain_gas.clone(),
         )?)
         .may_add_message(if coin.denom.is_remote() {
             Some(Message::execute(
@@ -555,6 +668,11 @@ fn process_withdrawal(
         } else {
             None
         })
+        .may_add_message(if plan.interchain_gas_refund.is_non_empty() {
+            Some(Message::transfer(user, plan.interchain_gas_refund)?)
+        } else {
+            None
+        })
         .add_event(Withdrawn {
             id,
             user,
@@ -564,9 +682,19 @@ fn process_withdrawal(
             denom: coin.denom,
             amount: coin.amount,
             fee: maybe_fee.unwrap_or(Uint128::ZERO),
+            interchain_gas: plan.interchain_gas,
         })?)
 }
 
+/// The full escrow of a withdrawal request: the coin to withdraw plus the
+/// interchain gas payment.
+fn escrow_of(coin: &Coin, interchain_gas: Coins) -> StdResult<Coins> {
+    let mut escrow = Coins::from(coin.clone());
+    escrow.insert_many(interchain_gas)?;
+
+    Ok(escrow)
+}
+
 /// The state updates a withdrawal entails, precomputed by
 /// [`validate_withdrawal`]. Everything here is derived read-only; applying
 /// it is infallible, so a withdrawal either fails validation with no
@@ -590,6 +718,11 @@ struct WithdrawalPlan {
     /// The post-personal-quota residue counting against the trailing-24h
     /// rolling window.
     residue: Uint128,
+    /// The interchain gas payment the bridge currently quotes.
+    interchain_gas: Coins,
+    /// The escrowed interchain gas payment in excess of the quote, refunded
+    /// to the user.
+    interchain_gas_refund: Coins,
 }
 
 /// Validate a withdrawal of `coin` by `user` against the current state:
@@ -598,7 +731,8 @@ struct WithdrawalPlan {
 /// - the escrowed amount must exceed the withdrawal fee, leaving a
 ///   non-zero amount to bridge;
 /// - the route's reserve must cover the bridged amount (remote denoms only);
-/// - the post-personal-quota residue must fit the rate-limit headroom.
+/// - the post-personal-quota residue must fit the rate-limit headroom;
+/// - the escrowed interchain gas payment must cover the bridge's quote.
 ///
 /// Reads storage but never writes it. On success, returns the
 /// [`WithdrawalPlan`] with every update the withdrawal entails; the caller
@@ -606,9 +740,12 @@ struct WithdrawalPlan {
 /// validation when the request is created).
 fn validate_withdrawal(
     storage: &dyn Storage,
+    querier: QuerierWrapper,
     user: Addr,
     coin: &Coin,
     remote: Remote,
+    recipient: Addr32,
+    escrowed_interchain_gas: &Coins,
     now: Timestamp,
 ) -> anyhow::Result<WithdrawalPlan> {
     // Find the bridge contract corresponding to the (denom, remote) tuple.
@@ -679,6 +816,20 @@ fn validate_withdrawal(
     // Check the trailing-24h rolling window against the cap.
     rate_limit::check(storage, &coin.denom, now, bridged, residue)?;
 
+    // The quote may have changed since the request was made.
+    let interchain_gas = quote_transfer_remote(querier, bridge, remote, bridged, recipient)?;
+    let mut interchain_gas_refund = escrowed_interchain_gas.clone();
+
+    interchain_gas_refund
+        .deduct_many(interchain_gas.clone())
+        .map_err(|_| {
+            anyhow!(
+                "insufficient interchain gas payment! required: {}, escrowed: {}",
+                interchain_gas,
+                escrowed_interchain_gas
+            )
+        })?;
+
     Ok(WithdrawalPlan {
         bridge,
         fee,
@@ -686,6 +837,8 @@ fn validate_withdrawal(
         new_reserve,
         personal_quota_update: personal_quota,
         residue,
+        interchain_gas,
+        interchain_gas_refund,
     })
 }
 
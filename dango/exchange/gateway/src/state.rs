@@ -1,6 +1,6 @@
 use {
     dango_math::Uint128,
-    dango_primitives::{Addr, Denom},
+    dango_primitives::{Addr, Coins, Denom},
     dango_storage::{Counter, Item, Map},
     dango_types::gateway::{PersonalQuota, Remote, WithdrawalRequest},
 };
@@ -32,3 +32,7 @@ pub const WITHDRAWAL_REQUESTS: Map<u64, WithdrawalRequest> = Map::new("withdrawa
 /// doesn't re-read requests it has already flagged.
 pub const FROZEN_WITHDRAWAL_REQUESTS: Map<u64, WithdrawalRequest> =
     Map::new("frozen_withdrawal_request");
+
+/// Interchain gas payments escrowed alongside withdrawal requests, keyed by
+/// request ID. Absent if the bridge didn't charge a fee at request time.
+pub const INTERCHAIN_GAS_PAYMENTS: Map<u64, Coins> = Map::new("interchain_gas_payment");
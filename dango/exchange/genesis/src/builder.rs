@@ -1,6 +1,6 @@
 use {
-    crate::{Codes, Contracts, GenesisOption},
-    dango_hyperlane_types::{isms, mailbox, va},
+    crate::{Codes, Contracts, GenesisOption, IsmRoute},
+    dango_hyperlane_types::{hooks::igp, isms, mailbox, va},
     dango_math::IsZero,
     dango_primitives::{
         Addr, Binary, Coins, Config, Duration, GENESIS_SENDER, GenesisState, Hash256, HashExt,
@@ -28,13 +28,20 @@ where
     // Upload all the codes and compute code hashes.
     let account_code_hash = upload(&mut msgs, codes.account);
     let account_factory_code_hash = upload(&mut msgs, codes.account_factory);
+    let account_multi_code_hash = upload(&mut msgs, codes.account_multi);
     let bank_code_hash = upload(&mut msgs, codes.bank);
     let gateway_code_hash = upload(&mut msgs, codes.gateway);
+    let hyperlane_aggregation_ism_code_hash = upload(&mut msgs, codes.hyperlane.aggregation_ism);
+    let hyperlane_igp_code_hash = upload(&mut msgs, codes.hyperlane.igp);
     let hyperlane_ism_code_hash = upload(&mut msgs, codes.hyperlane.ism);
     let hyperlane_mailbox_code_hash = upload(&mut msgs, codes.hyperlane.mailbox);
+    let hyperlane_routing_ism_code_hash = upload(&mut msgs, codes.hyperlane.routing_ism);
     let hyperlane_va_code_hash = upload(&mut msgs, codes.hyperlane.va);
     let oracle_code_hash = upload(&mut msgs, codes.oracle);
     let perps_code_hash = upload(&mut msgs, codes.perps);
+    // The timelock is not instantiated during genesis. The owner is expected to
+    // instantiate it and transfer ownership to it afterwards.
+    upload(&mut msgs, codes.timelock);
     let vesting_code_hash = upload(&mut msgs, codes.vesting);
     let warp_code_hash = upload(&mut msgs, codes.warp);
 
@@ -74,6 +81,7 @@ where
         account_factory_code_hash,
         &account_factory::InstantiateMsg {
             account_code_hash,
+            multi_code_hash: account_multi_code_hash,
             users,
         },
         "dango/account_factory",
@@ -101,6 +109,65 @@ where
         owner,
     )?;
 
+    // Instantiate Hyperlane aggregation ISM. It starts out as the multisig ISM
+    // alone; the chain owner can add more ISMs for a stricter security setup.
+    // Recipients opt into it by specifying it as their ISM, or the chain owner
+    // routes origin domains to it.
+    let aggregation_ism = instantiate(
+        &mut msgs,
+        hyperlane_aggregation_ism_code_hash,
+        &isms::aggregation::InstantiateMsg {
+            ism_set: isms::aggregation::IsmSet {
+                threshold: 1,
+                isms: vec![ism],
+            },
+        },
+        "hyperlane/ism/aggregation",
+        "hyperlane/ism/aggregation",
+        owner,
+    )?;
+
+    // Instantiate Hyperlane domain routing ISM, as the mailbox's default ISM.
+    // Origin domains without a route are verified by the multisig ISM.
+    let routing_ism = instantiate(
+        &mut msgs,
+        hyperlane_routing_ism_code_hash,
+        &isms::routing::InstantiateMsg {
+            default_ism: Some(ism),
+            routes: opt
+                .hyperlane
+                .ism_routes
+                .into_iter()
+                .map(|(domain, route)| {
+                    let ism = match route {
+                        IsmRoute::Multisig => ism,
+                        IsmRoute::Aggregation => aggregation_ism,
+                    };
+                    (domain, ism)
+                })
+                .collect(),
+        },
+        "hyperlane/ism/routing",
+        "hyperlane/ism/routing",
+        owner,
+    )?;
+
+    // Instantiate Hyperlane interchain gas paymaster (IGP), as the mailbox's
+    // default hook. The collected fees are claimed to the chain owner.
+    let igp = instantiate(
+        &mut msgs,
+        hyperlane_igp_code_hash,
+        &igp::InstantiateMsg {
+            mailbox,
+            fee_denom: opt.hyperlane.igp_fee_denom,
+            beneficiary: owner,
+            destination_gas_configs: opt.hyperlane.igp_destination_gas_configs,
+        },
+        "hyperlane/hook/igp",
+        "hyperlane/hook/igp",
+        owner,
+    )?;
+
     // Instantiate Warp contract.
     let warp = instantiate(
         &mut msgs,
@@ -118,7 +185,11 @@ where
         &mailbox::InstantiateMsg {
             config: mailbox::Config {
                 local_domain: opt.hyperlane.local_domain,
-                default_ism: ism,
+                default_ism: routing_ism,
+            },
+            hooks: mailbox::Hooks {
+                required: None,
+                default: Some(igp),
             },
         },
         "hyperlane/mailbox",
@@ -239,7 +310,14 @@ where
         account_factory,
         bank,
         gateway,
-        hyperlane: Hyperlane { ism, mailbox, va },
+        hyperlane: Hyperlane {
+            aggregation_ism,
+            igp,
+            ism,
+            mailbox,
+            routing_ism,
+            va,
+        },
         oracle,
         perps,
         vesting,
@@ -270,7 +348,14 @@ where
         addresses: AppAddresses {
             account_factory,
             gateway,
-            hyperlane: Hyperlane { ism, mailbox, va },
+            hyperlane: Hyperlane {
+                aggregation_ism,
+                igp,
+                ism,
+                mailbox,
+                routing_ism,
+                va,
+            },
             oracle,
             perps,
             warp,
@@ -23,11 +23,12 @@ impl GenesisCodes for RustVm {
             .with_authenticate(Box::new(dango_account_factory::authenticate))
             .build();
 
-        // Previously this was the multisig account code hash, now removed.
-        let _account_multi = ContractBuilder::new(Box::new(|_, _: Empty| -> StdResult<_> {
-            unreachable!("the multisig contract has been deleted");
-        }))
-        .build();
+        let account_multi = ContractBuilder::new(Box::new(dango_account_multi::instantiate))
+            .with_authenticate(Box::new(dango_account_multi::authenticate))
+            .with_receive(Box::new(dango_account_multi::receive))
+            .with_execute(Box::new(dango_account_multi::execute))
+            .with_query(Box::new(dango_account_multi::query))
+            .build();
 
         let account = ContractBuilder::new(Box::new(dango_account::instantiate))
             .with_authenticate(Box::new(dango_account::authenticate))
@@ -99,6 +100,32 @@ impl GenesisCodes for RustVm {
             .with_authenticate(Box::new(dango_perps::authenticate))
             .build();
 
+        // Contracts added after the chain launched must be built after all the
+        // existing ones: a Rust contract's code is its index in this order, so
+        // building one earlier would change the code hashes of the others.
+        // The chain upgrade deploys them from these indexes.
+        let igp = ContractBuilder::new(Box::new(dango_hyperlane_igp::instantiate))
+            .with_execute(Box::new(dango_hyperlane_igp::execute))
+            .with_query(Box::new(dango_hyperlane_igp::query))
+            .build();
+
+        let aggregation_ism =
+            ContractBuilder::new(Box::new(dango_hyperlane_aggregation::instantiate))
+                .with_execute(Box::new(dango_hyperlane_aggregation::execute))
+                .with_query(Box::new(dango_hyperlane_aggregation::query))
+                .build();
+
+        let routing_ism = ContractBuilder::new(Box::new(dango_hyperlane_routing::instantiate))
+            .with_execute(Box::new(dango_hyperlane_routing::execute))
+            .with_query(Box::new(dango_hyperlane_routing::query))
+            .build();
+
+        let timelock = ContractBuilder::new(Box::new(dango_timelock::instantiate))
+            .with_execute(Box::new(dango_timelock::execute))
+            .with_query(Box::new(dango_timelock::query))
+            .with_receive(Box::new(dango_timelock::receive))
+            .build();
+
         #[cfg(feature = "metrics")]
         {
             dango_oracle::metrics::init_metrics();
@@ -109,11 +136,20 @@ impl GenesisCodes for RustVm {
         Codes {
             account,
             account_factory,
+            account_multi,
             bank,
             gateway,
-            hyperlane: Hyperlane { ism, mailbox, va },
+            hyperlane: Hyperlane {
+                aggregation_ism,
+                igp,
+                ism,
+                mailbox,
+                routing_ism,
+                va,
+            },
             oracle,
             perps,
+            timelock,
             vesting,
             warp,
         }
@@ -1,5 +1,7 @@
 use {
-    dango_hyperlane_types::{isms::multisig::ValidatorSet, mailbox::Domain},
+    dango_hyperlane_types::{
+        hooks::igp::DestinationGasConfig, isms::multisig::ValidatorSet, mailbox::Domain,
+    },
     dango_math::{Udec128, Uint128},
     dango_order_book::PairId,
     dango_primitives::{Addr, Binary, Coin, Coins, Denom, Duration, Hash256, HashExt, Timestamp},
@@ -30,11 +32,13 @@ pub struct Contracts {
 pub struct Codes<T> {
     pub account: T,
     pub account_factory: T,
+    pub account_multi: T,
     pub bank: T,
     pub gateway: T,
     pub hyperlane: Hyperlane<T>,
     pub oracle: T,
     pub perps: T,
+    pub timelock: T,
     pub vesting: T,
     pub warp: T,
 }
@@ -47,13 +51,18 @@ where
         [
             &self.account,
             &self.account_factory,
+            &self.account_multi,
             &self.bank,
             &self.gateway,
+            &self.hyperlane.aggregation_ism,
+            &self.hyperlane.igp,
             &self.hyperlane.ism,
             &self.hyperlane.mailbox,
+            &self.hyperlane.routing_ism,
             &self.hyperlane.va,
             &self.oracle,
             &self.perps,
+            &self.timelock,
             &self.vesting,
             &self.warp,
         ]
@@ -126,8 +135,25 @@ pub struct HyperlaneOption {
     pub local_domain: Domain,
     /// Hyperlane validator sets for remote domains.
     pub ism_validator_sets: BTreeMap<Domain, ValidatorSet>,
+    /// The ISMs that verify messages from remote domains. Domains not listed
+    /// here are verified by the multisig ISM.
+    pub ism_routes: BTreeMap<Domain, IsmRoute>,
     /// Hyperlane validator announce fee rate.
     pub va_announce_fee_per_byte: Coin,
+    /// The denom in which interchain gas is paid to the IGP.
+    pub igp_fee_denom: Denom,
+    /// Gas configs of remote domains for quoting interchain gas payments.
+    /// Messages to domains not listed here are relayed free of charge.
+    pub igp_destination_gas_configs: BTreeMap<Domain, DestinationGasConfig>,
+}
+
+/// An ISM instantiated at genesis, that a remote domain can be routed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsmRoute {
+    /// The message ID multisig ISM.
+    Multisig,
+    /// The aggregation ISM, which initially consists of the multisig ISM only.
+    Aggregation,
 }
 
 pub struct OracleOption {
@@ -1,12 +1,18 @@
 use {
-    crate::{PRICE_SOURCES, PYTH_PRICES, PYTH_TRUSTED_SIGNERS},
+    crate::{
+        CIRCUIT_BREAKER_STATES, CIRCUIT_BREAKERS, OracleQuerierNoCache, PRICE_SOURCES, PYTH_PRICES,
+        PYTH_TRUSTED_SIGNERS, relative_change,
+    },
     anyhow::{bail, ensure},
     dango_primitives::{
-        Api, AuthCtx, AuthMode, Binary, Denom, Inner, JsonDeExt, Message, MsgExecute, MutableCtx,
-        QuerierExt, Response, Storage, Timestamp, Tx,
+        Api, AuthCtx, AuthMode, Binary, Denom, EventBuilder, Inner, JsonDeExt, Message, MsgExecute,
+        MutableCtx, Order, QuerierExt, Response, StdResult, Storage, Timestamp, Tx,
     },
     dango_pyth_types::{LeEcdsaMessage, PayloadData, PriceUpdate},
-    dango_types::oracle::{ExecuteMsg, InstantiateMsg, Price, PriceConfig},
+    dango_types::oracle::{
+        CircuitBreaker, CircuitBreakerState, CircuitBreakerTripped, ExecuteMsg, InstantiateMsg,
+        Price, PriceConfig,
+    },
     std::collections::{BTreeMap, BTreeSet},
 };
 
@@ -65,6 +71,10 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             register_price_sources(ctx, price_sources)
         },
         ExecuteMsg::RemovePriceSources(denoms) => remove_price_sources(ctx, denoms),
+        ExecuteMsg::RegisterCircuitBreakers(circuit_breakers) => {
+            register_circuit_breakers(ctx, circuit_breakers)
+        },
+        ExecuteMsg::RemoveCircuitBreakers(denoms) => remove_circuit_breakers(ctx, denoms),
         ExecuteMsg::RegisterTrustedSigner {
             public_key,
             expires_at,
@@ -110,6 +120,37 @@ fn remove_price_sources(ctx: MutableCtx, denoms: BTreeSet<Denom>) -> anyhow::Res
     Ok(Response::new())
 }
 
+fn register_circuit_breakers(
+    ctx: MutableCtx,
+    circuit_breakers: BTreeMap<Denom, CircuitBreaker>,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "you don't have the right, O you don't have the right"
+    );
+
+    for (denom, circuit_breaker) in circuit_breakers {
+        circuit_breaker.validate()?;
+        CIRCUIT_BREAKERS.save(ctx.storage, &denom, &circuit_breaker)?;
+    }
+
+    Ok(Response::new())
+}
+
+fn remove_circuit_breakers(ctx: MutableCtx, denoms: BTreeSet<Denom>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "you don't have the right, O you don't have the right"
+    );
+
+    for denom in denoms {
+        CIRCUIT_BREAKERS.remove(ctx.storage, &denom);
+        CIRCUIT_BREAKER_STATES.remove(ctx.storage, &denom);
+    }
+
+    Ok(Response::new())
+}
+
 fn register_trusted_signer(
     ctx: MutableCtx,
     public_key: Binary,
@@ -171,7 +212,73 @@ fn feed_prices(ctx: MutableCtx, price_update: PriceUpdate) -> anyhow::Result<Res
         }
     }
 
-    Ok(Response::new())
+    let events = update_circuit_breakers(ctx.storage, ctx.block.timestamp)?;
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Compare each circuit-breaker-guarded denom's freshly fed price against its
+/// last accepted price, tripping the breaker on a move beyond `max_change`.
+///
+/// While tripped, a price back within `max_change` of the last accepted price
+/// ends the trip early; otherwise the new level is accepted once the cooldown
+/// has passed. A denom whose price can't be computed keeps its state.
+fn update_circuit_breakers(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+) -> anyhow::Result<EventBuilder> {
+    let circuit_breakers = CIRCUIT_BREAKERS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut events = EventBuilder::new();
+
+    for (denom, circuit_breaker) in circuit_breakers {
+        let Ok(price) =
+            OracleQuerierNoCache::new_local(storage, now).query_unguarded_price(&denom, None)
+        else {
+            continue;
+        };
+
+        let price = price.humanized_price;
+        let accepted = CircuitBreakerState {
+            reference_price: price,
+            tripped_until: None,
+        };
+
+        let state = match CIRCUIT_BREAKER_STATES.may_load(storage, &denom)? {
+            None => accepted,
+            Some(state) => {
+                let within =
+                    relative_change(state.reference_price, price)? <= circuit_breaker.max_change;
+
+                match state.tripped_until {
+                    _ if within => accepted,
+                    Some(until) if now >= until => accepted,
+                    Some(_) => state,
+                    None => {
+                        let tripped_until = now + circuit_breaker.cooldown;
+
+                        events.push(CircuitBreakerTripped {
+                            denom: denom.clone(),
+                            reference_price: state.reference_price,
+                            price,
+                            tripped_until,
+                        })?;
+
+                        CircuitBreakerState {
+                            reference_price: state.reference_price,
+                            tripped_until: Some(tripped_until),
+                        }
+                    },
+                }
+            },
+        };
+
+        CIRCUIT_BREAKER_STATES.save(storage, &denom, &state)?;
+    }
+
+    Ok(events)
 }
 
 fn verify_pyth_lazer_message(
@@ -211,8 +318,12 @@ fn verify_pyth_lazer_message(
 mod tests {
     use {
         super::*,
+        dango_order_book::{Dimensionless, UsdPrice},
         dango_primitives::{Binary, ByteArray, Duration, MockApi, MockStorage, ResultExt},
-        dango_pyth_types::{LeEcdsaMessage, MarketSession, constants::LAZER_TRUSTED_SIGNER},
+        dango_pyth_types::{
+            Channel, LeEcdsaMessage, MarketSession, constants::LAZER_TRUSTED_SIGNER,
+        },
+        dango_types::oracle::PriceSource,
         std::str::FromStr,
     };
 
@@ -286,4 +397,70 @@ mod tests {
             assert_eq!(price.market_session, MarketSession::Other);
         }
     }
+
+    #[test]
+    fn circuit_breaker_trips_and_recovers() {
+        let mut storage = MockStorage::default();
+        let denom = Denom::from_str("eth").unwrap();
+
+        PRICE_SOURCES
+            .save(
+                &mut storage,
+                &denom,
+                &PriceConfig::Single(PriceSource {
+                    id: 1,
+                    channel: Channel::RealTime,
+                }),
+            )
+            .unwrap();
+        CIRCUIT_BREAKERS
+            .save(
+                &mut storage,
+                &denom,
+                &CircuitBreaker {
+                    max_change: Dimensionless::new_percent(10),
+                    cooldown: Duration::from_seconds(60),
+                },
+            )
+            .unwrap();
+
+        // Feed `price` at time `now`; return the number of trips and the
+        // resulting state.
+        let mut feed = |price: i128, now: u128| {
+            let now = Timestamp::from_seconds(now);
+
+            PYTH_PRICES
+                .save(
+                    &mut storage,
+                    1,
+                    &Price::new(UsdPrice::new_int(price), now, MarketSession::Regular),
+                )
+                .unwrap();
+
+            let events = update_circuit_breakers(&mut storage, now).unwrap();
+            let state = CIRCUIT_BREAKER_STATES.load(&storage, &denom).unwrap();
+
+            (events.into_iter().count(), state)
+        };
+
+        let state = |reference: i128, until: Option<u128>| CircuitBreakerState {
+            reference_price: UsdPrice::new_int(reference),
+            tripped_until: until.map(Timestamp::from_seconds),
+        };
+
+        // The first price is accepted; small moves track it.
+        assert_eq!(feed(100, 0), (0, state(100, None)));
+        assert_eq!(feed(105, 1), (0, state(105, None)));
+
+        // A 20% jump trips the breaker; a print back within the band ends the
+        // trip early.
+        assert_eq!(feed(126, 2), (1, state(105, Some(62))));
+        assert_eq!(feed(110, 3), (0, state(110, None)));
+
+        // A sustained move stays tripped until the cooldown has passed, then
+        // becomes the new reference.
+        assert_eq!(feed(150, 10), (1, state(110, Some(70))));
+        assert_eq!(feed(151, 69), (0, state(110, Some(70))));
+        assert_eq!(feed(152, 70), (0, state(152, None)));
+    }
 }
@@ -1,11 +1,12 @@
 use {
-    crate::{PRICE_SOURCES, PYTH_PRICES},
+    crate::{CIRCUIT_BREAKER_STATES, PRICE_SOURCES, PYTH_PRICES},
     anyhow::{anyhow, ensure},
-    dango_order_book::UsdPrice,
+    dango_math::MathResult,
+    dango_order_book::{Dimensionless, UsdPrice},
     dango_primitives::{Addr, Cache, Denom, QuerierWrapper, StdResult, Storage, Timestamp},
     dango_pyth_types::{MarketSession, PythId},
     dango_storage::StorageQuerier,
-    dango_types::oracle::{Price, PriceConfig},
+    dango_types::oracle::{CircuitBreakerState, MedianConfig, Price, PriceConfig},
     std::collections::HashMap,
 };
 
@@ -64,7 +65,26 @@ impl<'a> OracleQuerierNoCache<'a> {
         Self { ctx, now }
     }
 
-    /// Query the combined price of a denom from its price config.
+    /// Query the combined price of a denom from its price config, failing if
+    /// the denom's circuit breaker is tripped.
+    pub fn query_price(&self, denom: &Denom, config: Option<PriceConfig>) -> anyhow::Result<Price> {
+        if let Some(CircuitBreakerState {
+            tripped_until: Some(until),
+            ..
+        }) = self.ctx.may_get_circuit_breaker_state(denom)?
+        {
+            ensure!(
+                self.now >= until,
+                "price for denom `{denom}` is unavailable: circuit breaker tripped until {}",
+                until.to_rfc3339_string()
+            );
+        }
+
+        self.query_unguarded_price(denom, config)
+    }
+
+    /// Query the combined price of a denom from its price config, ignoring its
+    /// circuit breaker. The oracle uses this to evaluate the breaker itself.
     ///
     /// A single-source denom returns that feed's price. A futures-roll denom
     /// returns the weighted blend of its active contracts at the current block
@@ -77,10 +97,20 @@ impl<'a> OracleQuerierNoCache<'a> {
     ///   session, else `Other`.
     ///
     /// If any component has no on-chain price yet, the whole query fails.
-    pub fn query_price(&self, denom: &Denom, config: Option<PriceConfig>) -> anyhow::Result<Price> {
+    ///
+    /// A median denom is priced as described in [`MedianConfig`].
+    pub(crate) fn query_unguarded_price(
+        &self,
+        denom: &Denom,
+        config: Option<PriceConfig>,
+    ) -> anyhow::Result<Price> {
         // Load the denom's price config, unless one was provided by the caller.
         let config = config.map_or_else(|| self.ctx.get_price_config(denom), Ok)?;
 
+        if let PriceConfig::Median(median) = &config {
+            return self.query_median_price(denom, median);
+        }
+
         // Resolve the feeds to blend at the current time: one for a single-source
         // denom, one or two during a futures roll. The weights sum to one by
         // construction, so the weighted sum needs no normalizing division.
@@ -125,6 +155,93 @@ impl<'a> OracleQuerierNoCache<'a> {
 
         Ok(Price::new(humanized_price, timestamp, market_session))
     }
+
+    /// The median of the sources within `max_deviation` of the median of all
+    /// sources, with the timestamp and market session combined as for a
+    /// roll. Sources with no on-chain price yet, or one older than `max_age`,
+    /// are skipped; the quorum decides whether enough remain.
+    fn query_median_price(&self, denom: &Denom, median: &MedianConfig) -> anyhow::Result<Price> {
+        let mut prices = median
+            .sources
+            .iter()
+            .filter_map(|source| self.ctx.get_price(source.id).ok())
+            .filter(|price| price.timestamp + median.max_age >= self.now)
+            .collect::<Vec<_>>();
+
+        ensure!(
+            !prices.is_empty(),
+            "no source of denom `{denom}` has a fresh price"
+        );
+
+        let mid = median_of(prices.iter().map(|price| price.humanized_price).collect())?;
+
+        ensure!(
+            mid.is_positive(),
+            "median price for denom `{denom}` is non-positive: {mid}"
+        );
+
+        let total = prices.len();
+        let mut outliers = Vec::new();
+
+        prices.retain(|price| {
+            let within = relative_change(mid, price.humanized_price)
+                .is_ok_and(|change| change <= median.max_deviation);
+
+            if !within {
+                outliers.push(price.humanized_price);
+            }
+
+            within
+        });
+
+        ensure!(
+            prices.len() >= median.min_quorum as usize,
+            "only {} of {total} fresh sources of denom `{denom}` are within {} of the median \
+             {mid} (outliers: {outliers:?}); quorum is {}",
+            prices.len(),
+            median.max_deviation,
+            median.min_quorum
+        );
+
+        let humanized_price =
+            median_of(prices.iter().map(|price| price.humanized_price).collect())?;
+
+        let timestamp = prices
+            .iter()
+            .map(|price| price.timestamp)
+            .min()
+            .ok_or_else(|| anyhow!("no price components for denom `{denom}`"))?;
+
+        let market_session = if prices
+            .iter()
+            .all(|price| price.market_session == MarketSession::Regular)
+        {
+            MarketSession::Regular
+        } else {
+            MarketSession::Other
+        };
+
+        Ok(Price::new(humanized_price, timestamp, market_session))
+    }
+}
+
+/// The median of a non-empty list of prices; the mean of the middle two for
+/// an even count.
+fn median_of(mut prices: Vec<UsdPrice>) -> MathResult<UsdPrice> {
+    prices.sort_unstable();
+
+    let mid = prices.len() / 2;
+
+    if prices.len() % 2 == 1 {
+        Ok(prices[mid])
+    } else {
+        Ok(prices[mid - 1].checked_add(prices[mid])?.half())
+    }
+}
+
+/// The size of the move from `from` to `to`, relative to `from`.
+pub(crate) fn relative_change(from: UsdPrice, to: UsdPrice) -> MathResult<Dimensionless> {
+    to.checked_sub(from)?.checked_div(from)?.checked_abs()
 }
 
 enum OracleContext<'a> {
@@ -160,6 +277,20 @@ impl OracleContext<'_> {
             },
         }
     }
+
+    fn may_get_circuit_breaker_state(
+        &self,
+        denom: &Denom,
+    ) -> StdResult<Option<CircuitBreakerState>> {
+        match self {
+            OracleContext::Local { storage } => {
+                CIRCUIT_BREAKER_STATES.may_load(*storage, denom)
+            },
+            OracleContext::Remote { address, querier } => {
+                querier.may_query_wasm_path(*address, &CIRCUIT_BREAKER_STATES.path(denom))
+            },
+        }
+    }
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -169,11 +300,11 @@ mod tests {
     use {
         super::*,
         dango_order_book::{Dimensionless, UsdPrice},
-        dango_primitives::{MockStorage, ResultExt, Timestamp, hash_map},
+        dango_primitives::{Duration, MockStorage, ResultExt, Timestamp, hash_map},
         dango_pyth_types::{Channel, MarketSession},
         dango_types::{
             constants::{eth, perp_btc, usdc},
-            oracle::{Fixing, PriceSource, RollState},
+            oracle::{Fixing, MedianConfig, PriceSource, RollState},
         },
         test_case::test_case,
     };
@@ -316,4 +447,107 @@ mod tests {
         )
         .should_fail_with_error("non-positive");
     }
+
+    fn median(min_quorum: u32) -> PriceConfig {
+        PriceConfig::Median(MedianConfig {
+            sources: vec![source(1), source(2), source(3), source(4)],
+            min_quorum,
+            max_deviation: Dimensionless::new_percent(2),
+            max_age: Duration::from_seconds(100),
+        })
+    }
+
+    /// The outlier (150) is discarded; the median of the rest is served, as
+    /// old as its oldest accepted source.
+    #[test]
+    fn median_discards_outliers() {
+        query_local(
+            &eth::DENOM,
+            median(3),
+            vec![
+                (1, price(100, 1_000, MarketSession::Regular)),
+                (2, price(101, 990, MarketSession::Regular)),
+                (3, price(99, 1_000, MarketSession::Regular)),
+                (4, price(150, 900, MarketSession::Regular)),
+            ],
+            1_000,
+        )
+        .should_succeed_and_equal(price(100, 990, MarketSession::Regular));
+    }
+
+    /// Source 4 has no price yet and the two others disagree: no quorum.
+    #[test]
+    fn median_requires_quorum() {
+        query_local(
+            &eth::DENOM,
+            median(2),
+            vec![
+                (1, price(100, 1_000, MarketSession::Regular)),
+                (2, price(120, 1_000, MarketSession::Regular)),
+                (3, price(150, 1_000, MarketSession::Regular)),
+            ],
+            1_000,
+        )
+        .should_fail_with_error("quorum is 2");
+    }
+
+    /// Source 1 only counts while its price is no older than `max_age`.
+    /// Without it, the two others disagree and fall short of the quorum.
+    #[test]
+    fn median_discards_stale_sources() {
+        let prices = vec![
+            (1, price(100, 800, MarketSession::Regular)),
+            (2, price(100, 1_000, MarketSession::Regular)),
+            (3, price(120, 1_000, MarketSession::Regular)),
+        ];
+
+        query_local(&eth::DENOM, median(2), prices.clone(), 900).should_succeed_and_equal(price(
+            100,
+            800,
+            MarketSession::Regular,
+        ));
+
+        query_local(&eth::DENOM, median(2), prices, 1_000).should_fail_with_error("quorum is 2");
+
+        query_local(
+            &eth::DENOM,
+            median(1),
+            vec![(1, price(100, 800, MarketSession::Regular))],
+            1_000,
+        )
+        .should_fail_with_error("has a fresh price");
+    }
+
+    #[test]
+    fn tripped_circuit_breaker_makes_price_unavailable() {
+        let mut storage = MockStorage::default();
+        PRICE_SOURCES
+            .save(&mut storage, &eth::DENOM, &PriceConfig::Single(source(1)))
+            .unwrap();
+        PYTH_PRICES
+            .save(
+                &mut storage,
+                1,
+                &price(2_000, 1_000, MarketSession::Regular),
+            )
+            .unwrap();
+        CIRCUIT_BREAKER_STATES
+            .save(
+                &mut storage,
+                &eth::DENOM,
+                &CircuitBreakerState {
+                    reference_price: UsdPrice::new_int(1_500),
+                    tripped_until: Some(Timestamp::from_seconds(1_060)),
+                },
+            )
+            .unwrap();
+
+        OracleQuerierNoCache::new_local(&storage, Timestamp::from_seconds(1_059))
+            .query_price(&eth::DENOM, None)
+            .should_fail_with_error("circuit breaker tripped");
+
+        OracleQuerierNoCache::new_local(&storage, Timestamp::from_seconds(1_060))
+            .query_price(&eth::DENOM, None)
+            .should_succeed_and_equal(price(2_000, 1_000, MarketSession::Regular));
+    }
 }
@@ -1,10 +1,13 @@
 use {
-    crate::{OracleQuerierNoCache, PRICE_SOURCES, PYTH_TRUSTED_SIGNERS},
+    crate::{
+        CIRCUIT_BREAKER_STATES, CIRCUIT_BREAKERS, OracleQuerierNoCache, PRICE_SOURCES,
+        PYTH_TRUSTED_SIGNERS,
+    },
     dango_primitives::{
         Binary, Bound, DEFAULT_PAGE_LIMIT, Denom, ImmutableCtx, Json, JsonSerExt, Order, StdResult,
         Timestamp,
     },
-    dango_types::oracle::{Price, PriceConfig, QueryMsg},
+    dango_types::oracle::{CircuitBreaker, CircuitBreakerState, Price, PriceConfig, QueryMsg},
     std::collections::BTreeMap,
 };
 
@@ -30,6 +33,14 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_price_sources(ctx, start_after, limit)?;
             Ok(res.to_json_value()?)
         },
+        QueryMsg::CircuitBreaker { denom } => {
+            let res = query_circuit_breaker(ctx, denom)?;
+            Ok(res.to_json_value()?)
+        },
+        QueryMsg::CircuitBreakerState { denom } => {
+            let res = query_circuit_breaker_state(ctx, denom)?;
+            Ok(res.to_json_value()?)
+        },
     }
 }
 
@@ -97,3 +108,14 @@ fn query_price_sources(
         .take(limit)
         .collect()
 }
+
+fn query_circuit_breaker(ctx: ImmutableCtx, denom: Denom) -> StdResult<Option<CircuitBreaker>> {
+    CIRCUIT_BREAKERS.may_load(ctx.storage, &denom)
+}
+
+fn query_circuit_breaker_state(
+    ctx: ImmutableCtx,
+    denom: Denom,
+) -> StdResult<Option<CircuitBreakerState>> {
+    CIRCUIT_BREAKER_STATES.may_load(ctx.storage, &denom)
+}
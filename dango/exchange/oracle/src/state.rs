@@ -2,7 +2,7 @@ use {
     dango_primitives::{Denom, Timestamp},
     dango_pyth_types::PythId,
     dango_storage::{Map, Serde},
-    dango_types::oracle::{Price, PriceConfig},
+    dango_types::oracle::{CircuitBreaker, CircuitBreakerState, Price, PriceConfig},
 };
 
 pub const PRICE_SOURCES: Map<&Denom, PriceConfig, Serde> = Map::new("price_source");
@@ -10,3 +10,8 @@ pub const PRICE_SOURCES: Map<&Denom, PriceConfig, Serde> = Map::new("price_sourc
 pub const PYTH_TRUSTED_SIGNERS: Map<&[u8], Timestamp> = Map::new("pyth_trusted_signer");
 
 pub const PYTH_PRICES: Map<PythId, Price> = Map::new("pyth_price");
+
+pub const CIRCUIT_BREAKERS: Map<&Denom, CircuitBreaker, Serde> = Map::new("circuit_breaker");
+
+pub const CIRCUIT_BREAKER_STATES: Map<&Denom, CircuitBreakerState> =
+    Map::new("circuit_breaker_state");
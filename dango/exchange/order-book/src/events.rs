@@ -1,5 +1,8 @@
 use {
-    crate::{ClientOrderId, Dimensionless, OrderId, PairId, Quantity, TriggerDirection, UsdPrice},
+    crate::{
+        ClientOrderId, ConditionalOrderId, Dimensionless, OrderId, PairId, Quantity,
+        SelfTradePreventionMode, TrailingOffset, TriggerDirection, UsdPrice,
+    },
     dango_primitives::Addr,
 };
 
@@ -25,6 +28,9 @@ pub struct OrderPersisted {
 /// sizes sum to no more than the new position. An order whose clamped size is
 /// still non-zero is rewritten with the smaller size rather than removed (a
 /// clamp to zero removes it instead, emitting `OrderRemoved`).
+///
+/// Also emitted when a resting order is decremented by self-trade prevention
+/// in `DecrementAndCancel` mode.
 #[dango_primitives::event("order_resized")]
 #[dango_primitives::derive(Serde)]
 pub struct OrderResized {
@@ -58,22 +64,49 @@ pub struct OrderRemoved {
     pub client_order_id: Option<ClientOrderId>,
 }
 
+/// Event indicating a taker order crossed a resting order of the same user,
+/// and self-trade prevention was applied instead of a fill.
+///
+/// If the resting order is canceled or decremented, it's additionally reported
+/// by `OrderRemoved` (with reason `SelfTradePrevention`) or `OrderResized`.
+#[dango_primitives::event("self_trade_prevented")]
+#[dango_primitives::derive(Serde)]
+pub struct SelfTradePrevented {
+    pub pair_id: PairId,
+    pub user: Addr,
+    pub mode: SelfTradePreventionMode,
+    pub taker_order_id: OrderId,
+    pub maker_order_id: OrderId,
+
+    /// Signed size canceled from the taker order. Zero if the taker order
+    /// continues matching in full.
+    pub taker_canceled_size: Quantity,
+
+    /// Signed size canceled from the resting order. Zero if it's left in
+    /// place.
+    pub maker_canceled_size: Quantity,
+}
+
 /// Event indicating a conditional (TP/SL) order has been placed.
 #[dango_primitives::event("conditional_order_placed")]
 #[dango_primitives::derive(Serde)]
 pub struct ConditionalOrderPlaced {
+    pub order_id: ConditionalOrderId,
     pub pair_id: PairId,
     pub user: Addr,
     pub trigger_price: UsdPrice,
     pub trigger_direction: TriggerDirection,
     pub size: Option<Quantity>,
     pub max_slippage: Dimensionless,
+    pub limit_price: Option<UsdPrice>,
+    pub trailing_offset: Option<TrailingOffset>,
 }
 
 /// Event indicating a conditional order was triggered by an oracle price move.
 #[dango_primitives::event("conditional_order_triggered")]
 #[dango_primitives::derive(Serde)]
 pub struct ConditionalOrderTriggered {
+    pub order_id: ConditionalOrderId,
     pub pair_id: PairId,
     pub user: Addr,
     pub trigger_price: UsdPrice,
@@ -85,6 +118,7 @@ pub struct ConditionalOrderTriggered {
 #[dango_primitives::event("conditional_order_removed")]
 #[dango_primitives::derive(Serde)]
 pub struct ConditionalOrderRemoved {
+    pub order_id: ConditionalOrderId,
     pub pair_id: PairId,
     pub user: Addr,
     pub trigger_direction: TriggerDirection,
@@ -105,7 +139,7 @@ pub enum ReasonForOrderRemoval {
 
     /// The user submitted an order on the other side of the order book whose
     /// price crossed this order's. Following the principle of self-trade prevention,
-    /// this order was canceled.
+    /// this order was canceled. See `SelfTradePreventionMode`.
     SelfTradePrevention,
 
     /// The user was liquidated.
@@ -139,4 +173,20 @@ pub enum ReasonForOrderRemoval {
     /// from `SlippageExceeded` so the event stream can tell a policy
     /// tightening apart from a liquidity shortfall.
     SlippageCapTightened,
+
+    /// A stop-limit conditional order was triggered but the limit order it
+    /// places was rejected, e.g. because its limit price fell outside the
+    /// pair's `max_limit_price_deviation` band.
+    LimitOrderRejected,
+
+    /// The user's dead-man's switch expired without being refreshed, so all of
+    /// their resting orders were canceled.
+    ScheduledCancel,
+
+    /// Another order in the same one-cancels-other (OCO) group was filled or
+    /// triggered.
+    OneCancelsOther,
+
+    /// The order's pair is being delisted.
+    Delisted,
 }
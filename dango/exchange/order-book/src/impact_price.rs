@@ -1,8 +1,42 @@
 use {
-    crate::{Quantity, UsdPrice, UsdValue},
-    dango_primitives::StdResult,
+    crate::{ASKS, BIDS, PairId, Quantity, UsdPrice, UsdValue, may_invert_price},
+    dango_primitives::{Order as IterationOrder, StdResult, Storage},
 };
 
+/// Compute the impact bid and ask prices of a pair's order book, i.e. the
+/// average execution prices for selling and buying `impact_size` worth of
+/// notional value, respectively.
+///
+/// Returns: `(impact_bid, impact_ask)`, each `None` if the side of the book
+/// lacks the depth.
+pub fn compute_impact_bid_ask(
+    storage: &dyn Storage,
+    pair_id: &PairId,
+    impact_size: UsdValue,
+) -> StdResult<(Option<UsdPrice>, Option<UsdPrice>)> {
+    let bid_iter = BIDS
+        .prefix(pair_id.clone())
+        .range(storage, None, None, IterationOrder::Ascending)
+        .map(|res| {
+            let ((stored_price, _), order) = res?;
+            let real_price = may_invert_price(stored_price, true);
+            Ok((real_price, order.size))
+        });
+
+    let ask_iter = ASKS
+        .prefix(pair_id.clone())
+        .range(storage, None, None, IterationOrder::Ascending)
+        .map(|res| {
+            let ((stored_price, _), order) = res?;
+            Ok((stored_price, order.size.checked_abs()?))
+        });
+
+    let impact_bid = compute_impact_price(bid_iter, impact_size)?;
+    let impact_ask = compute_impact_price(ask_iter, impact_size)?;
+
+    Ok((impact_bid, impact_ask))
+}
+
 /// Walk an ordered sequence of `(limit_price, size)` pairs and compute the
 /// volume-weighted average execution price for filling `impact_size` worth
 /// of notional value.
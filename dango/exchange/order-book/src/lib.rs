@@ -15,12 +15,13 @@ mod price_band;
 mod slippage;
 mod state;
 mod target_price;
+mod trailing;
 mod typed_number;
 mod types;
 mod volume;
 
 pub use {
     cancel::*, decompose::*, events::*, impact_price::*, liquidity_depth::*, matching_engine::*,
-    min_size::*, price::*, price_band::*, slippage::*, state::*, target_price::*, typed_number::*,
-    types::*, volume::*,
+    min_size::*, price::*, price_band::*, slippage::*, state::*, target_price::*, trailing::*,
+    typed_number::*, types::*, volume::*,
 };
@@ -1,14 +1,15 @@
 //! Generic price-time-priority matching engine.
 //!
 //! `walk_book` iterates one side of the order book in price-time priority,
-//! producing two streams of records:
+//! producing three streams of records:
 //!
 //! - `RawFill` — a maker order intersected with the taker's remaining size,
 //!   priced at the maker's stored price.
 //! - `RemovedMaker` — a maker order the engine walked past without filling
-//!   because it tripped a generic guard (self-trade prevention or the
-//!   resting price drifted outside the pair's `max_limit_price_deviation`
-//!   band).
+//!   because its resting price drifted outside the pair's
+//!   `max_limit_price_deviation` band.
+//! - `SelfTrade` — a maker order of the taker himself, to which self-trade
+//!   prevention was applied instead of a fill.
 //!
 //! Records are emitted in the order in which the walk encountered the
 //! corresponding maker — so callers can replay them and produce the same
@@ -21,8 +22,8 @@
 use {
     crate::{
         ASKS, BIDS, ClientOrderId, Dimensionless, LimitOrder, OrderId, PairId, Quantity,
-        ReasonForOrderRemoval, TriggerDirection, UsdPrice, check_price_band, decompose_fill,
-        is_price_constraint_violated, may_invert_price,
+        ReasonForOrderRemoval, SelfTradePreventionMode, TriggerDirection, UsdPrice,
+        check_price_band, decompose_fill, is_price_constraint_violated, may_invert_price,
     },
     dango_primitives::{Addr, Order as IterationOrder, StdResult, Storage},
     std::collections::BTreeMap,
@@ -120,25 +121,56 @@ pub struct RemovedMaker {
     /// Carries the `reserved_margin` the caller releases.
     pub maker_order: LimitOrder,
 
-    /// Why the engine walked past this maker. Either
-    /// [`ReasonForOrderRemoval::SelfTradePrevention`] (the maker is
-    /// the taker himself) or
+    /// Why the engine walked past this maker. Currently always
     /// [`ReasonForOrderRemoval::PriceBandViolation`] (the resting
     /// price drifted out of band as the oracle moved since the
     /// maker was placed). The caller writes this verbatim into the
-    /// `OrderRemoved` event so STP and out-of-band cancels stay
-    /// distinguishable in the event stream.
+    /// `OrderRemoved` event.
     pub reason: ReasonForOrderRemoval,
 }
 
-/// One step of the walk: either a fill against a maker, or a
-/// "walked-past" removal. Stored in chronological encounter order
+/// A maker order of the taker himself, to which self-trade prevention was
+/// applied according to the taker's [`SelfTradePreventionMode`]. Nothing is
+/// filled. The caller releases the reserved margin of the canceled portion of
+/// the maker order, then removes the order from `BIDS` / `ASKS` if it's
+/// canceled in full, or rewrites it with the decremented size otherwise, and
+/// adjusts liquidity depth accordingly.
+#[derive(Debug, Clone)]
+pub struct SelfTrade {
+    /// The maker order's id.
+    pub maker_order_id: OrderId,
+
+    /// Caller-assigned id, surfaced on the `OrderRemoved` / `OrderResized`
+    /// event.
+    pub maker_client_order_id: Option<ClientOrderId>,
+
+    /// `|size|` of the maker order before self-trade prevention.
+    pub maker_pre_fill_size: Quantity,
+
+    /// Stored price of the maker order in `BIDS` / `ASKS`.
+    pub maker_stored_price: UsdPrice,
+
+    /// Signed size canceled from the maker order: zero if the order is left
+    /// untouched, equal to the order's size if it's canceled in full.
+    pub maker_canceled_size: Quantity,
+
+    /// Signed taker-perspective size canceled from the taker order. Already
+    /// deducted from the walk's remaining size.
+    pub taker_canceled_size: Quantity,
+
+    /// Snapshot of the maker order before self-trade prevention.
+    pub maker_order: LimitOrder,
+}
+
+/// One step of the walk: a fill against a maker, a "walked-past"
+/// removal, or a self-trade. Stored in chronological encounter order
 /// so the caller can emit `OrderFilled` and `OrderRemoved` events in
 /// the same sequence the legacy interleaved engine did.
 #[derive(Debug, Clone)]
 pub enum WalkStep {
     Fill(RawFill),
     Removed(RemovedMaker),
+    SelfTrade(SelfTrade),
 }
 
 /// Owned outcome of a [`walk_book`] call. Carries the chronological
@@ -150,12 +182,17 @@ pub struct WalkBookOutcome {
     /// legacy interleaved matching engine.
     pub steps: Vec<WalkStep>,
 
-    /// Signed taker-perspective remaining size after the walk. Zero
-    /// iff the order was fully filled. For partial fills the caller
+    /// Signed taker-perspective remaining size after the walk, excluding
+    /// any size canceled by self-trade prevention. Zero iff nothing is
+    /// left of the order. For partial fills the caller
     /// either parks the remainder as a resting limit order
     /// (`TimeInForce::GoodTilCanceled`) or discards it (`Market` /
     /// `ImmediateOrCancel`).
     pub remaining_size: Quantity,
+
+    /// Signed taker-perspective size canceled by self-trade prevention.
+    /// Zero unless the mode cancels the taker, in full or in part.
+    pub self_trade_canceled: Quantity,
 }
 
 /// Walk the maker side of the book in price-time priority, producing
@@ -166,7 +203,9 @@ pub struct WalkBookOutcome {
 /// # Arguments
 ///
 /// - `taker` — the taker's address; resting orders owned by this
-///   address trigger self-trade prevention (EXPIRE_MAKER mode).
+///   address trigger self-trade prevention.
+/// - `stp_mode` — how self-trade prevention treats the taker and the
+///   resting order; see [`SelfTradePreventionMode`].
 /// - `vault_addr` — the perps vault contract address; vault-owned
 ///   resting orders are exempt from the `max_limit_price_deviation`
 ///   re-check because their prices are algorithmically bounded by the
@@ -206,7 +245,8 @@ pub struct WalkBookOutcome {
 ///
 /// The walk stops when any of these holds:
 ///
-/// 1. `remaining_size` reaches zero — the taker is fully filled;
+/// 1. `remaining_size` reaches zero — the taker is fully filled, or
+///    canceled by self-trade prevention;
 /// 2. the next maker's resting price is worse than `target_price` for
 ///    the taker (uses [`is_price_constraint_violated`]);
 /// 3. the maker side of the book is exhausted.
@@ -215,6 +255,7 @@ pub fn walk_book(
     storage: &dyn Storage,
     pair_id: &PairId,
     taker: Addr,
+    stp_mode: SelfTradePreventionMode,
     vault_addr: Addr,
     taker_is_bid: bool,
     target_price: UsdPrice,
@@ -235,6 +276,7 @@ pub fn walk_book(
             .range(storage, None, None, IterationOrder::Ascending);
 
     let mut steps: Vec<WalkStep> = Vec::new();
+    let mut self_trade_canceled = Quantity::ZERO;
 
     // Cumulative maker-side fill applied so far in this walk, per maker (the
     // maker fills the opposite of the taker: -taker_fill_size). Used by the
@@ -262,20 +304,34 @@ pub fn walk_book(
 
         // ----------------------- Self-trade prevention -----------------------
         //
-        // EXPIRE_MAKER mode: cancel the maker and continue.
-        // <https://developers.binance.com/docs/binance-spot-api-docs/faqs/stp_faq>
+        // Cancel the maker, the taker, or both, in full or in part, depending
+        // on the mode. The maker's signed size is always of the opposite sign
+        // of the taker's.
         if maker_order.user == taker {
-            let pre_fill_abs_size = maker_order.size.checked_abs()?;
-
-            steps.push(WalkStep::Removed(RemovedMaker {
+            let (maker_canceled_size, taker_canceled_size) = match stp_mode {
+                SelfTradePreventionMode::ExpireMaker => (maker_order.size, Quantity::ZERO),
+                SelfTradePreventionMode::ExpireTaker => (Quantity::ZERO, remaining_size),
+                SelfTradePreventionMode::ExpireBoth => (maker_order.size, remaining_size),
+                SelfTradePreventionMode::DecrementAndCancel => {
+                    if maker_order.size.checked_abs()? <= remaining_size.checked_abs()? {
+                        (maker_order.size, maker_order.size.checked_neg()?)
+                    } else {
+                        (remaining_size.checked_neg()?, remaining_size)
+                    }
+                },
+            };
+
+            remaining_size.checked_sub_assign(taker_canceled_size)?;
+            self_trade_canceled.checked_add_assign(taker_canceled_size)?;
+
+            steps.push(WalkStep::SelfTrade(SelfTrade {
                 maker_order_id,
-                maker_addr: maker_order.user,
                 maker_client_order_id: maker_order.client_order_id,
-                maker_pre_fill_size: pre_fill_abs_size,
+                maker_pre_fill_size: maker_order.size.checked_abs()?,
                 maker_stored_price: stored_price,
-                maker_real_price: resting_price,
+                maker_canceled_size,
+                taker_canceled_size,
                 maker_order,
-                reason: ReasonForOrderRemoval::SelfTradePrevention,
             }));
 
             continue;
@@ -388,6 +444,7 @@ pub fn walk_book(
     Ok(WalkBookOutcome {
         steps,
         remaining_size,
+        self_trade_canceled,
     })
 }
 
@@ -0,0 +1,135 @@
+use {
+    crate::{Dimensionless, TrailingOffset, TriggerDirection, UsdPrice},
+    anyhow::ensure,
+    dango_math::MathResult,
+};
+
+pub fn validate_trailing_offset(trailing_offset: TrailingOffset) -> anyhow::Result<()> {
+    match trailing_offset {
+        TrailingOffset::Percent(percent) => {
+            ensure!(
+                percent.is_positive() && percent < Dimensionless::ONE,
+                "trailing offset percent must be between 0 and 1 exclusive, got {percent}"
+            );
+        },
+        TrailingOffset::Absolute(amount) => {
+            ensure!(
+                amount.is_positive(),
+                "trailing offset must be positive, got {amount}"
+            );
+        },
+    }
+
+    Ok(())
+}
+
+/// Compute a trailing stop's trigger price after the oracle price moved.
+///
+/// The trigger follows the oracle price at the given offset, but only in the
+/// direction that tightens the stop:
+///
+/// ```plain
+/// below_trigger = max(trigger_price, oracle_price - offset)
+/// above_trigger = min(trigger_price, oracle_price + offset)
+/// ```
+///
+/// For a percent offset, `oracle_price * (1 ∓ percent)` is used instead. As
+/// such, a `Below` trigger trails the highest oracle price seen since the
+/// order was placed (the high-water mark), and an `Above` trigger the lowest.
+pub fn compute_trailing_trigger_price(
+    trigger_price: UsdPrice,
+    trigger_direction: TriggerDirection,
+    trailing_offset: TrailingOffset,
+    oracle_price: UsdPrice,
+) -> MathResult<UsdPrice> {
+    match trigger_direction {
+        TriggerDirection::Below => {
+            let trailed = match trailing_offset {
+                TrailingOffset::Percent(percent) => {
+                    oracle_price.checked_mul(Dimensionless::ONE.checked_sub(percent)?)?
+                },
+                TrailingOffset::Absolute(amount) => oracle_price.checked_sub(amount)?,
+            };
+
+            Ok(trigger_price.max(trailed))
+        },
+        TriggerDirection::Above => {
+            let trailed = match trailing_offset {
+                TrailingOffset::Percent(percent) => {
+                    oracle_price.checked_mul(Dimensionless::ONE.checked_add(percent)?)?
+                },
+                TrailingOffset::Absolute(amount) => oracle_price.checked_add(amount)?,
+            };
+
+            Ok(trigger_price.min(trailed))
+        },
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, dango_primitives::ResultExt, test_case::test_case};
+
+    #[test_case(TrailingOffset::Percent(Dimensionless::new_percent(5)), None ; "5 percent")]
+    #[test_case(TrailingOffset::Absolute(UsdPrice::new_int(100)), None ; "100 dollars")]
+    #[test_case(
+        TrailingOffset::Percent(Dimensionless::ZERO),
+        Some("trailing offset percent must be between 0 and 1 exclusive") ;
+        "zero percent"
+    )]
+    #[test_case(
+        TrailingOffset::Percent(Dimensionless::ONE),
+        Some("trailing offset percent must be between 0 and 1 exclusive") ;
+        "100 percent"
+    )]
+    #[test_case(
+        TrailingOffset::Absolute(UsdPrice::ZERO),
+        Some("trailing offset must be positive") ;
+        "zero dollars"
+    )]
+    #[test_case(
+        TrailingOffset::Absolute(UsdPrice::new_int(-1)),
+        Some("trailing offset must be positive") ;
+        "negative dollars"
+    )]
+    fn validating_trailing_offset(trailing_offset: TrailingOffset, error: Option<&str>) {
+        let result = validate_trailing_offset(trailing_offset);
+
+        match error {
+            Some(error) => {
+                result.should_fail_with_error(error);
+            },
+            None => {
+                result.should_succeed();
+            },
+        }
+    }
+
+    // (trigger_price, direction, offset, oracle_price, expected)
+    #[test_case(1_900, TriggerDirection::Below, TrailingOffset::Absolute(UsdPrice::new_int(100)), 2_100, 2_000 ; "below ratchets up")]
+    #[test_case(1_900, TriggerDirection::Below, TrailingOffset::Absolute(UsdPrice::new_int(100)), 1_950, 1_900 ; "below never ratchets down")]
+    #[test_case(1_900, TriggerDirection::Below, TrailingOffset::Percent(Dimensionless::new_percent(10)), 2_500, 2_250 ; "below percent")]
+    #[test_case(2_100, TriggerDirection::Above, TrailingOffset::Absolute(UsdPrice::new_int(100)), 1_900, 2_000 ; "above ratchets down")]
+    #[test_case(2_100, TriggerDirection::Above, TrailingOffset::Absolute(UsdPrice::new_int(100)), 2_050, 2_100 ; "above never ratchets up")]
+    #[test_case(2_100, TriggerDirection::Above, TrailingOffset::Percent(Dimensionless::new_percent(10)), 1_500, 1_650 ; "above percent")]
+    fn computing_trailing_trigger_price(
+        trigger_price: i128,
+        trigger_direction: TriggerDirection,
+        trailing_offset: TrailingOffset,
+        oracle_price: i128,
+        expected: i128,
+    ) {
+        assert_eq!(
+            compute_trailing_trigger_price(
+                UsdPrice::new_int(trigger_price),
+                trigger_direction,
+                trailing_offset,
+                UsdPrice::new_int(oracle_price),
+            )
+            .unwrap(),
+            UsdPrice::new_int(expected)
+        );
+    }
+}
@@ -11,6 +11,7 @@ use {
         fmt,
         marker::PhantomData,
         ops::{Add, Neg, Not, Sub},
+        str::FromStr,
     },
     typenum::{N1, P1, Z0},
 };
@@ -272,6 +273,14 @@ impl<Q, U, D> fmt::Display for Number<Q, U, D> {
     }
 }
 
+impl<Q, U, D> FromStr for Number<Q, U, D> {
+    type Err = MathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Dec128_6::from_str(s).map(Self::new)
+    }
+}
+
 impl<Q, U, D> serde::ser::Serialize for Number<Q, U, D> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -391,7 +400,7 @@ pub type FundingRate = Number<Z0, Z0, N1>;
 
 #[cfg(test)]
 mod tests {
-    use {super::*, std::str::FromStr, test_case::test_case};
+    use {super::*, test_case::test_case};
 
     /// Helper: parse a string into a `Dimensionless` number.
     fn d(s: &str) -> Dimensionless {
@@ -63,6 +63,31 @@ pub enum TimeInForce {
     PostOnly,
 }
 
+/// What to do when a taker order crosses a resting order of the same user.
+///
+/// Modeled after Binance's self-trade prevention modes:
+/// <https://developers.binance.com/docs/binance-spot-api-docs/faqs/stp_faq>
+#[dango_primitives::derive(Serde)]
+#[derive(Copy, Default)]
+pub enum SelfTradePreventionMode {
+    /// Cancel the resting order, and continue matching the taker order deeper
+    /// in the book.
+    #[default]
+    ExpireMaker,
+
+    /// Cancel the remainder of the taker order, leaving the resting order in
+    /// the book.
+    ExpireTaker,
+
+    /// Cancel both the resting order and the remainder of the taker order.
+    ExpireBoth,
+
+    /// Cancel the smaller of the two orders, and decrement the larger one by
+    /// the same size; if they are of the same size, cancel both. If some of
+    /// the taker order is left, it continues matching deeper in the book.
+    DecrementAndCancel,
+}
+
 #[dango_primitives::derive(Serde)]
 #[derive(Copy)]
 pub enum OrderKind {
@@ -147,8 +172,30 @@ pub struct ConditionalOrder {
     /// Oracle price that activates this order.
     pub trigger_price: UsdPrice,
 
-    /// Max slippage for the market order executed at trigger.
+    /// Max slippage for the market order executed at trigger. Not used if
+    /// `limit_price` is set.
     pub max_slippage: Dimensionless,
+
+    /// If `Some`, this is a stop-limit order: instead of a market order, a
+    /// reduce-only GTC limit order at this price is placed at trigger.
+    pub limit_price: Option<UsdPrice>,
+
+    /// If `Some`, this is a trailing stop: `trigger_price` follows the oracle
+    /// price by this offset as the price moves in the position's favor, but
+    /// never moves back.
+    pub trailing_offset: Option<TrailingOffset>,
+}
+
+/// For a trailing stop, the distance between the best oracle price seen since
+/// the order was placed and the order's trigger price.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy)]
+pub enum TrailingOffset {
+    /// A fraction of the oracle price, e.g. 0.05 for 5%. Must be in (0, 1).
+    Percent(Dimensionless),
+
+    /// A fixed USD amount. Must be positive.
+    Absolute(UsdPrice),
 }
 
 /// TP or SL parameters attached to a parent order as a "child order".
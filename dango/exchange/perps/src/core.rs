@@ -5,6 +5,7 @@ mod fees;
 mod fill;
 mod funding;
 mod index_price;
+mod isolated;
 mod liq_price;
 mod margin;
 mod oi;
@@ -13,5 +14,5 @@ mod vault_premium;
 
 pub use {
     available_to_trade::*, closure::*, fee_invariant::*, fees::*, fill::*, funding::*,
-    index_price::*, liq_price::*, margin::*, oi::*, vault::*, vault_premium::*,
+    index_price::*, isolated::*, liq_price::*, margin::*, oi::*, vault::*, vault_premium::*,
 };
@@ -76,7 +76,7 @@ pub fn compute_available_to_trade(
     let equity = compute_user_equity(perp_querier, user_state)?;
 
     let mut other_im = UsdValue::ZERO;
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         if pair_id == current_pair_id {
             continue;
         }
@@ -189,8 +189,8 @@ mod tests {
                     // entry_price = oracle so unrealized pnl is zero
                     entry_price: CURRENT_PRICE,
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             );
         }
@@ -201,8 +201,8 @@ mod tests {
                     size: Quantity::new_int(other_pos),
                     entry_price: OTHER_PRICE,
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             );
         }
@@ -28,7 +28,8 @@ pub fn is_liquidatable(
 
 /// A policy for selecting which position(s) to close during liquidation.
 /// We start from the position that contributes the most to maintenance margin
-/// and go down, until the maintenance margin deficit is covered.
+/// and go down, until the maintenance margin deficit is covered. Isolated
+/// positions are never part of the schedule.
 ///
 /// ## Returns
 ///
@@ -56,7 +57,7 @@ pub fn compute_close_schedule(
     // Build (mm_contribution, pair_id) list, sorted descending by MM.
     let mut mm_entries = Vec::new();
 
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         let oracle_price = oracle_prices[pair_id];
         let pair_param = &pair_params[pair_id];
 
@@ -158,6 +159,8 @@ pub fn compute_close_schedule(
 /// ```plain
 /// equity = margin + user_pnl - user_fees + Σ(size × (oracle - entry))
 /// ```
+///
+/// The sum runs over cross-margin positions only.
 pub fn compute_user_equity_with_pnl(
     user_state: &UserState,
     oracle_prices: &BTreeMap<PairId, UsdPrice>,
@@ -169,7 +172,7 @@ pub fn compute_user_equity_with_pnl(
         .checked_add(user_pnl)?
         .checked_sub(user_fees)?;
 
-    for (pid, pos) in &user_state.positions {
+    for (pid, pos) in user_state.cross_positions() {
         let oracle_price = oracle_prices[pid];
         let unrealized = pos
             .size
@@ -286,8 +289,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -328,8 +331,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -370,8 +373,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -420,8 +423,8 @@ mod tests {
                         size: Quantity::new_int(10),
                         entry_price: UsdPrice::new_int(2000),
                         entry_funding_per_unit: FundingPerUnit::new_int(0),
-                        conditional_order_above: None,
-                        conditional_order_below: None,
+                        conditional_orders_above: BTreeMap::new(),
+                        conditional_orders_below: BTreeMap::new(),
                     },
                 },
                 ..Default::default()
@@ -468,8 +471,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -506,15 +509,15 @@ mod tests {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
                 pair_eth() => Position {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(3_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -559,8 +562,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -610,8 +613,8 @@ mod tests {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -650,8 +653,8 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -690,8 +693,8 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -732,8 +735,8 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -774,8 +777,8 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -817,8 +820,8 @@ mod tests {
                     size: Quantity::new_int(5),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -860,8 +863,8 @@ mod tests {
                     size: Quantity::new_int(-10),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -909,15 +912,15 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(2),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
                 pair_eth() => Position {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -973,15 +976,15 @@ mod tests {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(2),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
                 pair_eth() => Position {
                     size: Quantity::new_int(100),
                     entry_price: UsdPrice::new_int(1),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             },
             ..Default::default()
@@ -1060,8 +1063,8 @@ mod tests {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             )]),
             ..Default::default()
@@ -1094,8 +1097,8 @@ mod tests {
                     size: Quantity::new_int(-1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             )]),
             ..Default::default()
@@ -1137,8 +1140,8 @@ mod tests {
                         size: Quantity::new_int(1),
                         entry_price: UsdPrice::new_int(50_000),
                         entry_funding_per_unit: FundingPerUnit::ZERO,
-                        conditional_order_above: None,
-                        conditional_order_below: None,
+                        conditional_orders_above: BTreeMap::new(),
+                        conditional_orders_below: BTreeMap::new(),
                     },
                 ),
                 (
@@ -1147,8 +1150,8 @@ mod tests {
                         size: Quantity::new_int(10),
                         entry_price: UsdPrice::new_int(3_000),
                         entry_funding_per_unit: FundingPerUnit::ZERO,
-                        conditional_order_above: None,
-                        conditional_order_below: None,
+                        conditional_orders_above: BTreeMap::new(),
+                        conditional_orders_below: BTreeMap::new(),
                     },
                 ),
             ]),
@@ -1205,8 +1208,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             )]),
             ..Default::default()
@@ -1246,8 +1249,8 @@ mod tests {
                     size: Quantity::new_int(-10),
                     entry_price: UsdPrice::new_int(2_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             )]),
             ..Default::default()
@@ -32,6 +32,35 @@ impl FillPnl {
     }
 }
 
+/// What a match did to a resting (maker) order.
+///
+/// Recorded for every maker order the matching engine touches, so that the
+/// order groups the order may belong to can be updated once the match has been
+/// applied. See [`crate::order_group`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MakerOrderUpdate {
+    /// The order was filled, and some of it is still resting.
+    PartiallyFilled,
+
+    /// The order was filled and removed from the book.
+    Filled,
+
+    /// The order was removed from the book without being filled, e.g. due
+    /// to self-trade prevention.
+    Removed,
+}
+
+impl MakerOrderUpdate {
+    /// Classify a fill of a maker order by the order's size after the fill.
+    pub fn from_fill(maker_post_fill_size: Quantity) -> Self {
+        if maker_post_fill_size.is_zero() {
+            MakerOrderUpdate::Filled
+        } else {
+            MakerOrderUpdate::PartiallyFilled
+        }
+    }
+}
+
 /// Execute a fill for a single user. Updates position and OI; settles
 /// funding on the existing position.
 ///
@@ -141,8 +170,8 @@ fn apply_opening(
                 size: opening_size,
                 entry_price: fill_price,
                 entry_funding_per_unit: pair_state.funding_per_unit,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: Default::default(),
+                conditional_orders_below: Default::default(),
             },
         );
     }
@@ -225,8 +254,8 @@ mod tests {
                 size: Quantity::new_int(size),
                 entry_price: UsdPrice::new_int(entry_price),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         UserState {
@@ -458,8 +487,8 @@ mod tests {
                 size: Quantity::new_int(10),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO, // entered at 0
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         let mut user_state = UserState {
@@ -508,8 +537,8 @@ mod tests {
                 size: Quantity::new_int(10),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         let mut user_state = UserState {
@@ -0,0 +1,202 @@
+use {
+    dango_math::MathResult,
+    dango_order_book::{Dimensionless, PairId, Quantity, UsdPrice, UsdValue},
+    dango_types::perps::UserState,
+    std::collections::BTreeMap,
+};
+
+/// Build the account against which an isolated position is margined: the
+/// position's isolated margin as `margin`, and the position as the only one.
+///
+/// The view is an ordinary cross-margin `UserState`, so the equity, margin,
+/// and liquidation functions apply to it unchanged. Resting orders are not
+/// part of the view; their reserved margin is always drawn from the cross
+/// margin.
+pub fn compute_isolated_view(user_state: &UserState, pair_id: &PairId) -> UserState {
+    let mut positions = BTreeMap::new();
+
+    if let Some(position) = user_state.positions.get(pair_id) {
+        positions.insert(pair_id.clone(), position.clone());
+    }
+
+    UserState {
+        margin: user_state
+            .isolated_margins
+            .get(pair_id)
+            .copied()
+            .unwrap_or_default(),
+        positions,
+        ..Default::default()
+    }
+}
+
+/// Return the margin balance that backs the user's position in the given
+/// pair: its isolated margin if the pair is in isolated-margin mode, the cross
+/// margin otherwise.
+pub fn margin_for_pair_mut<'a>(
+    user_state: &'a mut UserState,
+    pair_id: &PairId,
+) -> &'a mut UsdValue {
+    match user_state.isolated_margins.get_mut(pair_id) {
+        Some(isolated_margin) => isolated_margin,
+        None => &mut user_state.margin,
+    }
+}
+
+/// Move the initial margin of a fill's opening portion from the cross margin
+/// into the isolated margin of the pair. No-op for cross pairs.
+///
+/// ```plain
+/// allocation = |opening_size| * fill_price * initial_margin_ratio
+/// ```
+pub fn allocate_isolated_margin(
+    user_state: &mut UserState,
+    pair_id: &PairId,
+    opening_size: Quantity,
+    fill_price: UsdPrice,
+    initial_margin_ratio: Dimensionless,
+) -> MathResult<()> {
+    let Some(isolated_margin) = user_state.isolated_margins.get_mut(pair_id) else {
+        return Ok(());
+    };
+
+    let allocation = opening_size
+        .checked_abs()?
+        .checked_mul(fill_price)?
+        .checked_mul(initial_margin_ratio)?;
+
+    isolated_margin.checked_add_assign(allocation)?;
+    user_state.margin.checked_sub_assign(allocation)
+}
+
+/// Once an isolated position is fully closed, return whatever is left of its
+/// isolated margin to the cross margin. The pair stays in isolated-margin
+/// mode. No-op for cross pairs and for pairs that still have a position.
+pub fn release_isolated_margin(user_state: &mut UserState, pair_id: &PairId) -> MathResult<()> {
+    if user_state.positions.contains_key(pair_id) {
+        return Ok(());
+    }
+
+    let Some(isolated_margin) = user_state.isolated_margins.get_mut(pair_id) else {
+        return Ok(());
+    };
+
+    let released = std::mem::replace(isolated_margin, UsdValue::ZERO);
+
+    user_state.margin.checked_add_assign(released)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_order_book::FundingPerUnit,
+        dango_primitives::btree_map,
+        dango_types::{constants::perp_eth, perps::Position},
+    };
+
+    fn position(size: i128) -> Position {
+        Position {
+            size: Quantity::new_int(size),
+            entry_price: UsdPrice::new_int(2000),
+            entry_funding_per_unit: FundingPerUnit::ZERO,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
+        }
+    }
+
+    fn pair_btc() -> PairId {
+        "perp/btcusd".parse().unwrap()
+    }
+
+    #[test]
+    fn isolated_view_contains_only_the_isolated_position() {
+        let user_state = UserState {
+            margin: UsdValue::new_int(10_000),
+            reserved_margin: UsdValue::new_int(100),
+            open_order_count: 1,
+            positions: btree_map! {
+                perp_eth::DENOM.clone() => position(5),
+                pair_btc() => position(-1),
+            },
+            isolated_margins: btree_map! {
+                perp_eth::DENOM.clone() => UsdValue::new_int(1_000),
+            },
+            ..Default::default()
+        };
+
+        let view = compute_isolated_view(&user_state, &perp_eth::DENOM);
+
+        assert_eq!(
+            view,
+            UserState {
+                margin: UsdValue::new_int(1_000),
+                positions: btree_map! {
+                    perp_eth::DENOM.clone() => position(5),
+                },
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn allocate_and_release_isolated_margin() {
+        let mut user_state = UserState {
+            margin: UsdValue::new_int(10_000),
+            positions: btree_map! {
+                perp_eth::DENOM.clone() => position(5),
+            },
+            isolated_margins: btree_map! {
+                perp_eth::DENOM.clone() => UsdValue::ZERO,
+            },
+            ..Default::default()
+        };
+
+        // 5 * 2000 * 10% = 1000 moves from cross into the isolated margin.
+        allocate_isolated_margin(
+            &mut user_state,
+            &perp_eth::DENOM,
+            Quantity::new_int(5),
+            UsdPrice::new_int(2000),
+            Dimensionless::new_percent(10),
+        )
+        .unwrap();
+
+        assert_eq!(user_state.margin, UsdValue::new_int(9_000));
+        assert_eq!(
+            user_state.isolated_margins[&perp_eth::DENOM],
+            UsdValue::new_int(1_000)
+        );
+
+        // Cross pairs are unaffected.
+        allocate_isolated_margin(
+            &mut user_state,
+            &pair_btc(),
+            Quantity::new_int(1),
+            UsdPrice::new_int(50_000),
+            Dimensionless::new_percent(10),
+        )
+        .unwrap();
+
+        assert_eq!(user_state.margin, UsdValue::new_int(9_000));
+
+        // Nothing is released while the position is open.
+        release_isolated_margin(&mut user_state, &perp_eth::DENOM).unwrap();
+
+        assert_eq!(user_state.margin, UsdValue::new_int(9_000));
+
+        // Once closed, the isolated margin returns to cross, but the pair
+        // stays in isolated-margin mode.
+        user_state.positions.clear();
+        *margin_for_pair_mut(&mut user_state, &perp_eth::DENOM) = UsdValue::new_int(1_200);
+        release_isolated_margin(&mut user_state, &perp_eth::DENOM).unwrap();
+
+        assert_eq!(user_state.margin, UsdValue::new_int(10_200));
+        assert_eq!(
+            user_state.isolated_margins[&perp_eth::DENOM],
+            UsdValue::ZERO
+        );
+    }
+}
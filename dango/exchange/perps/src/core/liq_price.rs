@@ -1,6 +1,9 @@
 use {
     crate::{
-        core::{compute_position_unrealized_funding, compute_position_unrealized_pnl},
+        core::{
+            compute_isolated_view, compute_position_unrealized_funding,
+            compute_position_unrealized_pnl,
+        },
         querier::NoCachePerpQuerier,
     },
     dango_order_book::{PairId, UsdPrice, UsdValue},
@@ -27,6 +30,10 @@ use {
 /// p = C / (|sⱼ|*mmrⱼ - sⱼ)
 /// ```
 ///
+/// For a position in isolated-margin mode, the same formula is applied to the
+/// position's isolated view, where `M` is its isolated margin and there are no
+/// other positions.
+///
 /// Returns `None` when:
 /// - The computed price is non-positive (the position alone cannot trigger liquidation).
 /// - The position does not exist for the given pair.
@@ -39,6 +46,11 @@ pub fn compute_liquidation_price(
         return Ok(None);
     };
 
+    if user_state.is_isolated(pair_id) {
+        let isolated_view = compute_isolated_view(user_state, pair_id);
+        return compute_liquidation_price(pair_id, &isolated_view, perp_querier);
+    }
+
     let target_param = perp_querier.query_pair_param(pair_id)?;
 
     // Accumulate contributions from all positions.
@@ -46,7 +58,7 @@ pub fn compute_liquidation_price(
     let mut total_funding = UsdValue::ZERO;
     let mut other_mm = UsdValue::ZERO;
 
-    for (pid, position) in &user_state.positions {
+    for (pid, position) in user_state.cross_positions() {
         let pair_state = perp_querier.query_pair_state(pid)?;
         let oracle_price = pair_state.index_price;
 
@@ -129,8 +141,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -166,8 +178,8 @@ mod tests {
                     size: Quantity::new_int(-5),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -204,8 +216,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -258,15 +270,15 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
                 perp_btc::DENOM.clone() => Position {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -316,8 +328,8 @@ mod tests {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -374,8 +386,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -1,7 +1,7 @@
 use {
     crate::{core::compute_trading_fee, querier::NoCachePerpQuerier},
     anyhow::ensure,
-    dango_order_book::{Dimensionless, PairId, Quantity, UsdPrice, UsdValue},
+    dango_order_book::{Dimensionless, PairId, Quantity, UsdPrice, UsdValue, decompose_fill},
     dango_types::perps::{PairParam, PairState, Position, UserState},
 };
 
@@ -43,11 +43,15 @@ pub fn compute_position_unrealized_funding(
     position.size.checked_mul(delta)
 }
 
-/// Compute a user's equity (net account value) across all open positions.
+/// Compute a user's equity (net account value) across all open cross-margin
+/// positions.
 ///
 /// ```plain
 /// equity = user_state.margin + Σ(unrealized_pnl) - Σ(accrued_funding)
 /// ```
+///
+/// Isolated positions are excluded; for those, apply this function to the
+/// position's isolated view (see `compute_isolated_view`).
 pub fn compute_user_equity(
     perp_querier: &NoCachePerpQuerier,
     user_state: &UserState,
@@ -55,7 +59,7 @@ pub fn compute_user_equity(
     let mut total_pnl = UsdValue::ZERO;
     let mut total_funding = UsdValue::ZERO;
 
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         let pair_state = perp_querier.query_pair_state(pair_id)?;
         let oracle_price = pair_state.index_price;
 
@@ -78,7 +82,8 @@ pub fn compute_user_equity(
 /// |position.size| * oracle_price * maintenance_margin_ratio
 /// ```
 ///
-/// The total maintenance margin is the sum of that of all positions.
+/// The total maintenance margin is the sum of that of all cross-margin
+/// positions.
 ///
 /// The maintenance margin acts as the liquidation trigger. If a user's collateral
 /// value falls below the maintenance margin, he becomes eligible for liquidation.
@@ -88,7 +93,7 @@ pub fn compute_maintenance_margin(
 ) -> anyhow::Result<UsdValue> {
     let mut total = UsdValue::ZERO;
 
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         let oracle_price = perp_querier.query_pair_state(pair_id)?.index_price;
         let pair_param = perp_querier.query_pair_param(pair_id)?;
 
@@ -112,9 +117,10 @@ pub fn compute_maintenance_margin(
 /// |position.size| * oracle_price * initial_margin_ratio
 /// ```
 ///
-/// The total initial margin is the sum of that of all positions.
+/// The total initial margin is the sum of that of all cross-margin positions.
 /// One position's size is overriden by a "projected" value, reflecting the size
-/// if the order is executed.
+/// if the order is executed. The projection is ignored if the pair is in
+/// isolated-margin mode.
 ///
 /// When submitting an order, the user must have no less collateral than the
 /// initial margin, otherwise the order is rejected.
@@ -127,7 +133,7 @@ pub(super) fn compute_initial_margin(
     let mut total = UsdValue::ZERO;
     let mut projected_pair_seen = false;
 
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         let oracle_price = perp_querier.query_pair_state(pair_id)?.index_price;
         let pair_param = perp_querier.query_pair_param(pair_id)?;
 
@@ -148,7 +154,10 @@ pub(super) fn compute_initial_margin(
 
     // If the projected pair is not in existing positions and the projected size
     // is non-zero, add its margin contribution.
-    if !projected_pair_seen && projected_size.is_non_zero() {
+    if !projected_pair_seen
+        && projected_size.is_non_zero()
+        && !user_state.is_isolated(projected_pair_id)
+    {
         let oracle_price = perp_querier
             .query_pair_state(projected_pair_id)?
             .index_price;
@@ -191,7 +200,7 @@ pub fn compute_required_margin(
 /// ```
 ///
 /// where `used_margin = Σ |size| * oracle_price * initial_margin_ratio`
-/// over all existing positions (no projection).
+/// over all existing cross-margin positions (no projection).
 ///
 /// Returns zero when equity falls below the used + reserved requirement
 /// (the user cannot open new positions or withdraw, and may face liquidation).
@@ -203,7 +212,7 @@ pub fn compute_available_margin(
 
     let mut used_margin = UsdValue::ZERO;
 
-    for (pair_id, position) in &user_state.positions {
+    for (pair_id, position) in user_state.cross_positions() {
         let oracle_price = perp_querier.query_pair_state(pair_id)?.index_price;
         let pair_param = perp_querier.query_pair_param(pair_id)?;
 
@@ -228,6 +237,11 @@ pub fn compute_available_margin(
 ///
 /// The 0%-fill scenario (limit-order reservation) is checked separately
 /// inside `store_limit_order`.
+///
+/// If the pair is in isolated-margin mode, the isolated position doesn't count
+/// towards the cross initial margin. Instead, the initial margin of the
+/// order's opening portion, which gets moved into the isolated margin upon
+/// filling, must be covered on top of the cross requirement.
 pub fn check_margin(
     pair_id: &PairId,
     perp_querier: &NoCachePerpQuerier,
@@ -238,16 +252,30 @@ pub fn check_margin(
 ) -> anyhow::Result<()> {
     let equity = compute_user_equity(perp_querier, taker_state)?;
 
-    let projected_size = {
-        let current_position = taker_state
-            .positions
-            .get(pair_id)
-            .map(|p| p.size)
-            .unwrap_or_default();
-        current_position.checked_add(size)?
-    };
+    let current_position = taker_state
+        .positions
+        .get(pair_id)
+        .map(|p| p.size)
+        .unwrap_or_default();
 
-    let projected_im = compute_initial_margin(perp_querier, taker_state, pair_id, projected_size)?;
+    let projected_im = if taker_state.is_isolated(pair_id) {
+        let (_, opening_size) = decompose_fill(size, current_position);
+        let pair_param = perp_querier.query_pair_param(pair_id)?;
+        let opening_im = opening_size
+            .checked_abs()?
+            .checked_mul(oracle_price)?
+            .checked_mul(pair_param.initial_margin_ratio)?;
+
+        compute_initial_margin(perp_querier, taker_state, pair_id, Quantity::ZERO)?
+            .checked_add(opening_im)?
+    } else {
+        compute_initial_margin(
+            perp_querier,
+            taker_state,
+            pair_id,
+            current_position.checked_add(size)?,
+        )?
+    };
 
     let projected_fee = compute_trading_fee(size, oracle_price, taker_fee_rate)?;
 
@@ -300,8 +328,8 @@ mod tests {
             size: Quantity::new_int(size),
             entry_price: UsdPrice::new_int(entry_price),
             entry_funding_per_unit: FundingPerUnit::new_int(0),
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: Default::default(),
+            conditional_orders_below: Default::default(),
         };
 
         assert_eq!(
@@ -334,8 +362,8 @@ mod tests {
             size: Quantity::new_raw(size_raw),
             entry_price: UsdPrice::new_raw(0),
             entry_funding_per_unit: FundingPerUnit::new_raw(entry_raw),
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: Default::default(),
+            conditional_orders_below: Default::default(),
         };
         let pair_state = PairState {
             funding_per_unit: FundingPerUnit::new_raw(cumulative_raw),
@@ -376,8 +404,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -412,8 +440,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(1),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -450,15 +478,15 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(1),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
                 perp_btc::DENOM.clone() => Position {
                     size: Quantity::new_int(-1),
                     entry_price: UsdPrice::new_int(50_000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -497,8 +525,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -544,8 +572,8 @@ mod tests {
                     size: Quantity::new_int(size),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -582,15 +610,15 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
                 perp_btc::DENOM.clone() => Position {
                     size: Quantity::new_int(-1),
                     entry_price: UsdPrice::new_int(50000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -668,8 +696,8 @@ mod tests {
                     size: Quantity::new_int(5),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -713,8 +741,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -792,15 +820,15 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
                 perp_btc::DENOM.clone() => Position {
                     size: Quantity::new_int(-1),
                     entry_price: UsdPrice::new_int(50000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -899,8 +927,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -939,8 +967,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -981,8 +1009,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
@@ -1023,8 +1051,8 @@ mod tests {
                     size: Quantity::new_int(10),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(1),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
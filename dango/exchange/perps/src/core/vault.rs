@@ -1,28 +1,34 @@
 use {
     dango_math::MathResult,
     dango_order_book::{Dimensionless, Quantity, UsdPrice, UsdValue},
-    dango_types::perps::PairParam,
+    dango_types::perps::{PairParam, VaultLadder},
 };
 
-/// A single quote (one side of the vault's market-making order).
+/// A single quote (one order of the vault's market-making ladder).
 pub struct VaultQuote {
     pub price: UsdPrice,
     pub size: Quantity,
 }
 
-/// Compute the vault's bid and ask quotes for a single pair.
+/// Compute the vault's bid and ask ladders for a single pair.
 ///
-/// The vault never crosses the book: if the calculated bid >= `best_ask`,
-/// it is clamped to `best_ask - tick_size`. Symmetrically for asks.
+/// Each side's size is split across the levels of the pair's `vault_ladder`,
+/// each level quoted `spread_step` further from the oracle price than the one
+/// above it.
+///
+/// The vault never crosses the book: if the calculated top bid >= `best_ask`,
+/// it is clamped to `best_ask - tick_size`. Symmetrically for asks. Deeper
+/// levels are kept at least one tick behind the level above them.
 ///
 /// Uses inventory skew: when the vault has a directional position, order
 /// sizes and spreads are tilted to encourage unwinding.
 ///
 /// Mutates: nothing (pure function).
 ///
-/// Returns: `(Option<bid>, Option<ask>)`. A side is `None` if the
-/// pair's vault params are zero, or if clamping would produce a
-/// non-positive price.
+/// Returns: `(bids, asks)`, each ordered from the top of the book. A side is
+/// empty if the pair's vault params are zero. Individual levels are omitted
+/// if their price is non-positive or their notional is below the minimum
+/// order size.
 pub fn compute_vault_quotes(
     oracle_price: UsdPrice,
     pair_param: &PairParam,
@@ -30,15 +36,16 @@ pub fn compute_vault_quotes(
     best_ask: Option<UsdPrice>,
     allocated_margin: UsdValue,
     position_size: Quantity,
-) -> MathResult<(Option<VaultQuote>, Option<VaultQuote>)> {
+) -> MathResult<(Vec<VaultQuote>, Vec<VaultQuote>)> {
     // Skip the pair entirely if any vault param is zero.
     if pair_param.vault_half_spread.is_zero()
         || pair_param.vault_max_quote_size.is_zero()
         || pair_param.vault_liquidity_weight.is_zero()
         || pair_param.tick_size.is_zero()
+        || pair_param.vault_ladder.levels == 0
         || allocated_margin.is_zero()
     {
-        return Ok((None, None));
+        return Ok((Vec::new(), Vec::new()));
     }
 
     // Compute inventory skew: clamp(position_size / max_skew_size, -1, 1).
@@ -62,7 +69,7 @@ pub fn compute_vault_quotes(
     let base_size = margin_size.min(pair_param.vault_max_quote_size);
 
     if base_size.is_zero() {
-        return Ok((None, None));
+        return Ok((Vec::new(), Vec::new()));
     }
 
     // Tilt sizes by skew: when long, reduce bid size, increase ask size.
@@ -71,123 +78,185 @@ pub fn compute_vault_quotes(
     let skew_size_term = skew.checked_mul(pair_param.vault_size_skew_factor)?;
 
     let bid_size = base_size.checked_mul(Dimensionless::ONE.checked_sub(skew_size_term)?)?;
-    let bid = compute_bid(oracle_price, pair_param, best_ask, bid_size, skew)?;
+    let bid_sizes = split_across_ladder(bid_size, &pair_param.vault_ladder)?;
+    let bids = compute_bids(oracle_price, pair_param, best_ask, &bid_sizes, skew)?;
 
     let ask_size = base_size.checked_mul(Dimensionless::ONE.checked_add(skew_size_term)?)?;
-    let ask = compute_ask(oracle_price, pair_param, best_bid, ask_size, skew)?;
+    let ask_sizes = split_across_ladder(ask_size, &pair_param.vault_ladder)?;
+    let asks = compute_asks(oracle_price, pair_param, best_bid, &ask_sizes, skew)?;
+
+    Ok((bids, asks))
+}
+
+/// Split a side's size across the levels of the ladder, in proportion to
+/// `size_ratio ^ i`.
+///
+/// Mutates: nothing.
+///
+/// Returns: one size per level, from the top of the book; empty if `size` is
+/// zero or negative (fully skewed away).
+fn split_across_ladder(size: Quantity, ladder: &VaultLadder) -> MathResult<Vec<Quantity>> {
+    if size.is_zero() || size.is_negative() {
+        return Ok(Vec::new());
+    }
 
-    Ok((bid, ask))
+    let mut weights = Vec::with_capacity(ladder.levels as usize);
+    let mut weight = Dimensionless::ONE;
+    let mut total = Dimensionless::ZERO;
+
+    for _ in 0..ladder.levels {
+        weights.push(weight);
+        total.checked_add_assign(weight)?;
+        weight = weight.checked_mul(ladder.size_ratio)?;
+    }
+
+    weights
+        .into_iter()
+        .map(|weight| size.checked_mul(weight)?.checked_div(total))
+        .collect()
 }
 
-/// Compute the vault's bid quote.
+/// Compute the vault's bid ladder.
 ///
 /// Mutates: nothing.
 ///
-/// Returns: `Some(VaultQuote)` if a valid bid can be placed, `None` otherwise.
-fn compute_bid(
+/// Returns: the bids that can be placed, from the highest price.
+fn compute_bids(
     oracle_price: UsdPrice,
     pair_param: &PairParam,
     best_ask: Option<UsdPrice>,
-    size: Quantity,
+    sizes: &[Quantity],
     skew: Dimensionless,
-) -> MathResult<Option<VaultQuote>> {
+) -> MathResult<Vec<VaultQuote>> {
     // Effective spread = vault_half_spread * (1 + skew * spread_skew_factor).
     // When long, bid spread widens (less likely to accumulate more).
     let effective_spread = pair_param.vault_half_spread.checked_mul(
         Dimensionless::ONE.checked_add(skew.checked_mul(pair_param.vault_spread_skew_factor)?)?,
     )?;
 
-    // Raw bid = oracle_price * (1 - effective_spread).
-    let raw_bid = oracle_price.checked_sub(oracle_price.checked_mul(effective_spread)?)?;
-
-    // Snap down to nearest tick: floor(raw / tick) * tick.
-    let remainder = raw_bid.checked_rem(pair_param.tick_size)?;
-    let mut bid_price = raw_bid.checked_sub(remainder)?;
+    let mut spread = effective_spread;
+    let mut quotes = Vec::with_capacity(sizes.len());
+    let mut previous_price: Option<UsdPrice> = None;
+
+    for &level_size in sizes {
+        // Raw bid = oracle_price * (1 - spread).
+        let raw_bid = oracle_price.checked_sub(oracle_price.checked_mul(spread)?)?;
+
+        // Snap down to nearest tick: floor(raw / tick) * tick.
+        let remainder = raw_bid.checked_rem(pair_param.tick_size)?;
+        let mut bid_price = raw_bid.checked_sub(remainder)?;
+
+        match previous_price {
+            // Clamp: the top bid must be strictly below best ask.
+            None => {
+                if let Some(best_ask) = best_ask
+                    && bid_price >= best_ask
+                {
+                    bid_price = best_ask.checked_sub(pair_param.tick_size)?;
+                }
+            },
+            // Clamp: each deeper bid must be strictly below the one above it.
+            Some(previous_price) => {
+                if bid_price >= previous_price {
+                    bid_price = previous_price.checked_sub(pair_param.tick_size)?;
+                }
+            },
+        }
 
-    // Clamp: bid must be strictly below best ask.
-    if let Some(best_ask) = best_ask
-        && bid_price >= best_ask
-    {
-        bid_price = best_ask.checked_sub(pair_param.tick_size)?;
-    }
+        // Stop if price is zero or negative; deeper levels are only lower.
+        if bid_price.is_zero() || bid_price.is_negative() {
+            break;
+        }
 
-    // Skip if price is zero or negative.
-    if bid_price.is_zero() || bid_price.is_negative() {
-        return Ok(None);
-    }
+        previous_price = Some(bid_price);
+        spread.checked_add_assign(pair_param.vault_ladder.spread_step)?;
 
-    // Skip if size is zero or negative (fully skewed away).
-    if size.is_zero() || size.is_negative() {
-        return Ok(None);
-    }
+        // Skip the level if it's below the minimum order size.
+        let notional = level_size.checked_mul(bid_price)?;
+        if level_size.is_zero() || notional < pair_param.min_order_size {
+            continue;
+        }
 
-    // Check minimum order size.
-    let notional = size.checked_mul(bid_price)?;
-    if notional < pair_param.min_order_size {
-        return Ok(None);
+        // Bid size is positive (buy).
+        quotes.push(VaultQuote {
+            price: bid_price,
+            size: level_size,
+        });
     }
 
-    // Bid size is positive (buy).
-    Ok(Some(VaultQuote {
-        price: bid_price,
-        size,
-    }))
+    Ok(quotes)
 }
 
-/// Compute the vault's ask quote.
+/// Compute the vault's ask ladder.
 ///
 /// Mutates: nothing.
 ///
-/// Returns: `Some(VaultQuote)` if a valid ask can be placed, `None` otherwise.
-fn compute_ask(
+/// Returns: the asks that can be placed, from the lowest price.
+fn compute_asks(
     oracle_price: UsdPrice,
     pair_param: &PairParam,
     best_bid: Option<UsdPrice>,
-    size: Quantity,
+    sizes: &[Quantity],
     skew: Dimensionless,
-) -> MathResult<Option<VaultQuote>> {
+) -> MathResult<Vec<VaultQuote>> {
     // Effective spread = vault_half_spread * (1 - skew * spread_skew_factor).
     // When long, ask spread tightens (more attractive to takers).
     let effective_spread = pair_param.vault_half_spread.checked_mul(
         Dimensionless::ONE.checked_sub(skew.checked_mul(pair_param.vault_spread_skew_factor)?)?,
     )?;
 
-    // Raw ask = oracle_price * (1 + effective_spread).
-    let raw_ask = oracle_price.checked_add(oracle_price.checked_mul(effective_spread)?)?;
+    let mut spread = effective_spread;
+    let mut quotes = Vec::with_capacity(sizes.len());
+    let mut previous_price: Option<UsdPrice> = None;
+
+    for &level_size in sizes {
+        // Raw ask = oracle_price * (1 + spread).
+        let raw_ask = oracle_price.checked_add(oracle_price.checked_mul(spread)?)?;
+
+        // Snap up to nearest tick: ceil(raw / tick) * tick.
+        let remainder = raw_ask.checked_rem(pair_param.tick_size)?;
+        let mut ask_price = if remainder.is_zero() {
+            raw_ask
+        } else {
+            raw_ask
+                .checked_sub(remainder)?
+                .checked_add(pair_param.tick_size)?
+        };
 
-    // Snap up to nearest tick: ceil(raw / tick) * tick.
-    let remainder = raw_ask.checked_rem(pair_param.tick_size)?;
-    let mut ask_price = if remainder.is_zero() {
-        raw_ask
-    } else {
-        raw_ask
-            .checked_sub(remainder)?
-            .checked_add(pair_param.tick_size)?
-    };
+        match previous_price {
+            // Clamp: the top ask must be strictly above best bid.
+            None => {
+                if let Some(best_bid) = best_bid
+                    && ask_price <= best_bid
+                {
+                    ask_price = best_bid.checked_add(pair_param.tick_size)?;
+                }
+            },
+            // Clamp: each deeper ask must be strictly above the one below it.
+            Some(previous_price) => {
+                if ask_price <= previous_price {
+                    ask_price = previous_price.checked_add(pair_param.tick_size)?;
+                }
+            },
+        }
 
-    // Clamp: ask must be strictly above best bid.
-    if let Some(best_bid) = best_bid
-        && ask_price <= best_bid
-    {
-        ask_price = best_bid.checked_add(pair_param.tick_size)?;
-    }
+        previous_price = Some(ask_price);
+        spread.checked_add_assign(pair_param.vault_ladder.spread_step)?;
 
-    // Skip if size is zero or negative (fully skewed away).
-    if size.is_zero() || size.is_negative() {
-        return Ok(None);
-    }
+        // Skip the level if it's below the minimum order size.
+        let notional = level_size.checked_mul(ask_price)?;
+        if level_size.is_zero() || notional < pair_param.min_order_size {
+            continue;
+        }
 
-    // Check minimum order size.
-    let notional = size.checked_mul(ask_price)?;
-    if notional < pair_param.min_order_size {
-        return Ok(None);
+        // Ask size is negative (sell).
+        quotes.push(VaultQuote {
+            price: ask_price,
+            size: level_size.checked_neg()?,
+        });
     }
 
-    // Ask size is negative (sell).
-    Ok(Some(VaultQuote {
-        price: ask_price,
-        size: size.checked_neg()?,
-    }))
+    Ok(quotes)
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -196,6 +265,12 @@ fn compute_ask(
 mod tests {
     use {super::*, dango_order_book::Dimensionless};
 
+    /// Unwrap the only quote of a single-level ladder.
+    fn single(quotes: Vec<VaultQuote>) -> VaultQuote {
+        assert_eq!(quotes.len(), 1);
+        quotes.into_iter().next().unwrap()
+    }
+
     fn default_pair_param() -> PairParam {
         PairParam {
             tick_size: UsdPrice::new_int(1),
@@ -224,8 +299,8 @@ mod tests {
         )
         .unwrap();
 
-        let bid = bid.unwrap();
-        let ask = ask.unwrap();
+        let bid = single(bid);
+        let ask = single(ask);
 
         // Bid: 1000 * (1 - 0.01) = 990, snapped down to tick = 990.
         assert_eq!(bid.price, UsdPrice::new_int(990));
@@ -253,8 +328,8 @@ mod tests {
         )
         .unwrap();
 
-        assert!(bid.is_none());
-        assert!(ask.is_none());
+        assert!(bid.is_empty());
+        assert!(ask.is_empty());
     }
 
     #[test]
@@ -274,8 +349,8 @@ mod tests {
         )
         .unwrap();
 
-        assert!(bid.is_none());
-        assert!(ask.is_none());
+        assert!(bid.is_empty());
+        assert!(ask.is_empty());
     }
 
     #[test]
@@ -294,7 +369,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(bid.unwrap().price, UsdPrice::new_int(990));
+        assert_eq!(single(bid).price, UsdPrice::new_int(990));
 
         // Best ask at 990 — would cross, so clamp to 990 - 1 = 989.
         let (bid, _) = compute_vault_quotes(
@@ -307,7 +382,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(bid.unwrap().price, UsdPrice::new_int(989));
+        assert_eq!(single(bid).price, UsdPrice::new_int(989));
     }
 
     #[test]
@@ -326,7 +401,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(ask.unwrap().price, UsdPrice::new_int(1016));
+        assert_eq!(single(ask).price, UsdPrice::new_int(1016));
     }
 
     #[test]
@@ -347,8 +422,8 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(bid.unwrap().size, Quantity::new_int(5));
-        assert_eq!(ask.unwrap().size, Quantity::new_int(-5));
+        assert_eq!(single(bid).size, Quantity::new_int(5));
+        assert_eq!(single(ask).size, Quantity::new_int(-5));
     }
 
     #[test]
@@ -371,8 +446,8 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(bid.unwrap().size, Quantity::new_int(5));
-        assert_eq!(ask.unwrap().size, Quantity::new_int(-5));
+        assert_eq!(single(bid).size, Quantity::new_int(5));
+        assert_eq!(single(ask).size, Quantity::new_int(-5));
     }
 
     // ----------------------- inventory skew tests -----------------------
@@ -412,8 +487,8 @@ mod tests {
         let (nb, na) =
             compute_vault_quotes(oracle, &naive, None, None, margin, Quantity::ZERO).unwrap();
 
-        let (sb, sa) = (sb.unwrap(), sa.unwrap());
-        let (nb, na) = (nb.unwrap(), na.unwrap());
+        let (sb, sa) = (single(sb), single(sa));
+        let (nb, na) = (single(nb), single(na));
 
         assert_eq!(sb.price, nb.price);
         assert_eq!(sa.price, na.price);
@@ -450,8 +525,8 @@ mod tests {
         )
         .unwrap();
 
-        let (bn, an) = (b_neutral.unwrap(), a_neutral.unwrap());
-        let (bl, al) = (b_long.unwrap(), a_long.unwrap());
+        let (bn, an) = (single(b_neutral), single(a_neutral));
+        let (bl, al) = (single(b_long), single(a_long));
 
         assert_eq!(bn.price, bl.price);
         assert_eq!(an.price, al.price);
@@ -487,8 +562,8 @@ mod tests {
         )
         .unwrap();
 
-        let bid = bid.unwrap();
-        let ask = ask.unwrap();
+        let bid = single(bid);
+        let ask = single(ask);
 
         // bid_size < ask_size (in absolute value)
         assert!(bid.size.checked_abs().unwrap() < ask.size.checked_abs().unwrap());
@@ -522,8 +597,8 @@ mod tests {
         )
         .unwrap();
 
-        let bid = bid.unwrap();
-        let ask = ask.unwrap();
+        let bid = single(bid);
+        let ask = single(ask);
 
         // bid_size > ask_size (in absolute value)
         assert!(bid.size.checked_abs().unwrap() > ask.size.checked_abs().unwrap());
@@ -563,8 +638,8 @@ mod tests {
         )
         .unwrap();
 
-        let (nb, na) = (nb.unwrap(), na.unwrap());
-        let (lb, la) = (lb.unwrap(), la.unwrap());
+        let (nb, na) = (single(nb), single(na));
+        let (lb, la) = (single(lb), single(la));
 
         // Bid further from oracle (lower price).
         assert!(lb.price <= nb.price);
@@ -612,8 +687,8 @@ mod tests {
         .unwrap();
 
         // Both should produce identical quotes.
-        let (b1, a1) = (b1.unwrap(), a1.unwrap());
-        let (b2, a2) = (b2.unwrap(), a2.unwrap());
+        let (b1, a1) = (single(b1), single(a1));
+        let (b2, a2) = (single(b2), single(a2));
         assert_eq!(b1.price, b2.price);
         assert_eq!(a1.price, a2.price);
         assert_eq!(b1.size, b2.size);
@@ -656,9 +731,9 @@ mod tests {
         .unwrap();
 
         // Bid side fully disabled (size = 0).
-        assert!(bid.is_none());
+        assert!(bid.is_empty());
         // Ask side still active.
-        assert!(ask.is_some());
+        assert!(!ask.is_empty());
     }
 
     /// `vault_spread_skew_factor > 1` causes the tightened side to cross
@@ -694,8 +769,8 @@ mod tests {
         )
         .unwrap();
 
-        let bid = bid.unwrap();
-        let ask = ask.unwrap();
+        let bid = single(bid);
+        let ask = single(ask);
 
         assert_eq!(bid.price, UsdPrice::new_int(970));
         assert_eq!(ask.price, UsdPrice::new_int(990));
@@ -704,4 +779,213 @@ mod tests {
         assert!(bid.price > UsdPrice::ZERO, "bid must stay positive");
         assert!(bid.price < ask.price, "bid < ask invariant must hold");
     }
+
+    // --------------------------- ladder tests ---------------------------
+
+    fn ladder_pair_param(levels: u32, spread_step: Dimensionless) -> PairParam {
+        PairParam {
+            vault_ladder: VaultLadder {
+                levels,
+                spread_step,
+                size_ratio: Dimensionless::ONE,
+            },
+            ..default_pair_param()
+        }
+    }
+
+    fn prices(quotes: &[VaultQuote]) -> Vec<UsdPrice> {
+        quotes.iter().map(|q| q.price).collect()
+    }
+
+    fn sizes(quotes: &[VaultQuote]) -> Vec<Quantity> {
+        quotes.iter().map(|q| q.size).collect()
+    }
+
+    /// Each level is `spread_step` further from the oracle price, and the
+    /// side's size is split evenly when `size_ratio = 1`.
+    ///
+    /// Setup: oracle = $1000, margin = $10k, half_spread = 1%, base_size = 50.
+    /// Ladder: 3 levels, spread_step = 0.5%.
+    ///
+    /// | level | bid            | ask             |
+    /// |-------|----------------|-----------------|
+    /// | 0     | 16.67 @ $990   | 16.67 @ $1010   |
+    /// | 1     | 16.67 @ $985   | 16.67 @ $1015   |
+    /// | 2     | 16.67 @ $980   | 16.67 @ $1020   |
+    #[test]
+    fn ladder_places_levels_spread_step_apart() {
+        let pair_param = ladder_pair_param(3, Dimensionless::new_permille(5));
+
+        let (bids, asks) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            None,
+            UsdValue::new_int(10_000),
+            Quantity::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(prices(&bids), [990, 985, 980].map(UsdPrice::new_int));
+        assert_eq!(prices(&asks), [1010, 1015, 1020].map(UsdPrice::new_int));
+        assert_eq!(sizes(&bids), [Quantity::new_raw(16_666_666); 3]);
+        assert_eq!(sizes(&asks), [Quantity::new_raw(-16_666_666); 3]);
+    }
+
+    /// `size_ratio` sets the size of each level relative to the one above it.
+    ///
+    /// Setup: base_size = 50, 2 levels, size_ratio = 3
+    /// → weights = 1/4 and 3/4 → sizes = 12.5 and 37.5.
+    #[test]
+    fn ladder_size_ratio_distributes_size() {
+        let pair_param = PairParam {
+            vault_ladder: VaultLadder {
+                levels: 2,
+                spread_step: Dimensionless::new_permille(5),
+                size_ratio: Dimensionless::new_int(3),
+            },
+            ..default_pair_param()
+        };
+
+        let (bids, asks) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            None,
+            UsdValue::new_int(10_000),
+            Quantity::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sizes(&bids),
+            [Quantity::new_raw(12_500_000), Quantity::new_raw(37_500_000)]
+        );
+        assert_eq!(
+            sizes(&asks),
+            [
+                Quantity::new_raw(-12_500_000),
+                Quantity::new_raw(-37_500_000)
+            ]
+        );
+    }
+
+    /// Levels that would snap onto the same tick are pushed one tick apart.
+    ///
+    /// Setup: oracle = $1000, tick = $10, half_spread = 1%, 3 levels,
+    /// spread_step = 0.1% ($1).
+    ///
+    /// - bids: raw $990, $989, $988 → snapped $990, $980, $980 → $990, $980, $970
+    /// - asks: raw $1010, $1011, $1012 → snapped $1010, $1020, $1020 → $1010, $1020, $1030
+    #[test]
+    fn ladder_levels_kept_a_tick_apart() {
+        let pair_param = PairParam {
+            tick_size: UsdPrice::new_int(10),
+            ..ladder_pair_param(3, Dimensionless::new_permille(1))
+        };
+
+        let (bids, asks) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            None,
+            UsdValue::new_int(10_000),
+            Quantity::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(prices(&bids), [990, 980, 970].map(UsdPrice::new_int));
+        assert_eq!(prices(&asks), [1010, 1020, 1030].map(UsdPrice::new_int));
+    }
+
+    /// Only the top of the ladder is clamped against the opposite side of the
+    /// book; deeper levels stay behind it.
+    ///
+    /// Setup: best ask = $986, ladder bids at $990, $985, $980.
+    /// → top bid clamped to $985, second bid pushed to $984, third at $980.
+    #[test]
+    fn ladder_top_clamped_below_best_ask() {
+        let pair_param = ladder_pair_param(3, Dimensionless::new_permille(5));
+
+        let (bids, _) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            Some(UsdPrice::new_int(986)),
+            UsdValue::new_int(10_000),
+            Quantity::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(prices(&bids), [985, 984, 980].map(UsdPrice::new_int));
+    }
+
+    /// Inventory skew shifts and resizes the whole ladder.
+    ///
+    /// Setup: oracle = $1000, margin = $10k, half_spread = 1%, 2 levels,
+    /// spread_step = 0.5%. Skew params: size_factor = 0.5,
+    /// spread_factor = 0.3, max_skew = 50. Position = 25 (long) → skew = 0.5.
+    ///
+    /// | level | bid (spread)           | ask (spread)            |
+    /// |-------|------------------------|-------------------------|
+    /// | 0     | 18.75 @ $988 (1.15%)   | 31.25 @ $1009 (0.85%)   |
+    /// | 1     | 18.75 @ $983 (1.65%)   | 31.25 @ $1014 (1.35%)   |
+    #[test]
+    fn ladder_skewed_by_inventory() {
+        let pair_param = PairParam {
+            vault_ladder: VaultLadder {
+                levels: 2,
+                spread_step: Dimensionless::new_permille(5),
+                size_ratio: Dimensionless::ONE,
+            },
+            ..skew_pair_param()
+        };
+
+        let (bids, asks) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            None,
+            UsdValue::new_int(10_000),
+            Quantity::new_int(25),
+        )
+        .unwrap();
+
+        assert_eq!(prices(&bids), [988, 983].map(UsdPrice::new_int));
+        assert_eq!(prices(&asks), [1009, 1014].map(UsdPrice::new_int));
+        assert_eq!(sizes(&bids), [Quantity::new_raw(18_750_000); 2]);
+        assert_eq!(sizes(&asks), [Quantity::new_raw(-31_250_000); 2]);
+    }
+
+    /// Levels whose notional falls below the minimum order size are omitted,
+    /// without affecting the other levels.
+    ///
+    /// Setup: base_size = 50, 3 levels, size_ratio = 0.1
+    /// → sizes ≈ 45.05, 4.50, 0.45 → notional ≈ $44.6k, $4.4k, $441.
+    /// With min_order_size = $1000, the third level is dropped.
+    #[test]
+    fn ladder_drops_levels_below_min_order_size() {
+        let pair_param = PairParam {
+            min_order_size: UsdValue::new_int(1000),
+            vault_ladder: VaultLadder {
+                levels: 3,
+                spread_step: Dimensionless::new_permille(5),
+                size_ratio: Dimensionless::new_permille(100),
+            },
+            ..default_pair_param()
+        };
+
+        let (bids, asks) = compute_vault_quotes(
+            UsdPrice::new_int(1000),
+            &pair_param,
+            None,
+            None,
+            UsdValue::new_int(10_000),
+            Quantity::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(prices(&bids), [990, 985].map(UsdPrice::new_int));
+        assert_eq!(prices(&asks), [1010, 1015].map(UsdPrice::new_int));
+    }
 }
@@ -1,12 +1,15 @@
 #[cfg(feature = "metrics")]
 mod emit_cron_metrics;
+mod process_cancel_all_deadlines;
 mod process_conditional_orders;
 mod process_funding;
+mod process_twap_orders;
 mod process_unlocks;
 mod vault_snapshot;
 
 #[cfg(feature = "metrics")]
 pub use emit_cron_metrics::*;
 pub use {
-    process_conditional_orders::*, process_funding::*, process_unlocks::*, vault_snapshot::*,
+    process_cancel_all_deadlines::*, process_conditional_orders::*, process_funding::*,
+    process_twap_orders::*, process_unlocks::*, vault_snapshot::*,
 };
@@ -0,0 +1,193 @@
+use {
+    crate::{
+        state::{CANCEL_ALL_DEADLINES, USER_STATES},
+        trade::{CancelAllOrdersOutcome, compute_cancel_all_orders_outcome},
+    },
+    dango_order_book::ReasonForOrderRemoval,
+    dango_primitives::{EventBuilder, Order as IterationOrder, StdResult, Storage, Timestamp},
+    dango_storage::PrefixBound,
+    dango_types::perps::CancelAllTriggered,
+};
+
+/// Cancel all resting limit orders of each user whose dead-man's switch has
+/// expired, then disarm the switch.
+///
+/// Uses range-bounded iteration over the deadline index so only expired
+/// switches are visited. Conditional and TWAP orders are left untouched, same
+/// as with `CancelOrderRequest::All`.
+pub fn process_cancel_all_deadlines(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let expired = CANCEL_ALL_DEADLINES
+        .idx
+        .deadline
+        .prefix_range(
+            storage,
+            None,
+            Some(PrefixBound::Inclusive(current_time)),
+            IterationOrder::Ascending,
+        )
+        .map(|res| res.map(|(deadline, user, _)| (user, deadline)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    #[cfg(feature = "tracing")]
+    let num_users = expired.len();
+
+    for (user, deadline) in expired {
+        CANCEL_ALL_DEADLINES.remove(storage, user)?;
+
+        events.push(CancelAllTriggered { user, deadline })?;
+
+        // A user without a state has no orders to cancel.
+        let Some(user_state) = USER_STATES.may_load(storage, user)? else {
+            continue;
+        };
+
+        let CancelAllOrdersOutcome { user_state } = compute_cancel_all_orders_outcome(
+            storage,
+            user,
+            &user_state,
+            Some(events),
+            ReasonForOrderRemoval::ScheduledCancel,
+        )?;
+
+        if user_state.is_empty() {
+            USER_STATES.remove(storage, user)?;
+        } else {
+            USER_STATES.save(storage, user, &user_state)?;
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(num_users, "Processed expired dead-man's switches");
+    }
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::state::PAIR_PARAMS,
+        dango_math::Uint64,
+        dango_order_book::{BIDS, LimitOrder, PairId, Quantity, UsdPrice, UsdValue},
+        dango_primitives::{Addr, MockStorage},
+        dango_types::perps::{PairParam, UserState},
+    };
+
+    const USER: Addr = Addr::mock(1);
+    const OTHER: Addr = Addr::mock(2);
+
+    fn pair_id() -> PairId {
+        "perp/btcusd".parse().unwrap()
+    }
+
+    fn save_bid(storage: &mut dyn Storage, order_id: u64, user: Addr) {
+        BIDS.save(
+            storage,
+            (pair_id(), UsdPrice::new_int(50_000), Uint64::new(order_id)),
+            &LimitOrder {
+                user,
+                size: Quantity::new_int(1),
+                reduce_only: false,
+                reserved_margin: UsdValue::new_int(100),
+                created_at: Timestamp::from_nanos(0),
+                tp: None,
+                sl: None,
+                client_order_id: None,
+            },
+        )
+        .unwrap();
+
+        USER_STATES
+            .save(
+                storage,
+                user,
+                &UserState {
+                    margin: UsdValue::new_int(1_000),
+                    reserved_margin: UsdValue::new_int(100),
+                    open_order_count: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    /// Only the switches whose deadline has passed fire. Each fired switch
+    /// cancels the user's orders, releases their reserved margin, and is
+    /// disarmed.
+    #[test]
+    fn canceling_orders_of_expired_switches() {
+        let mut storage = MockStorage::new();
+
+        PAIR_PARAMS
+            .save(&mut storage, &pair_id(), &PairParam::default())
+            .unwrap();
+
+        save_bid(&mut storage, 1, USER);
+        save_bid(&mut storage, 2, OTHER);
+
+        CANCEL_ALL_DEADLINES
+            .save(&mut storage, USER, &Timestamp::from_seconds(100))
+            .unwrap();
+        CANCEL_ALL_DEADLINES
+            .save(&mut storage, OTHER, &Timestamp::from_seconds(101))
+            .unwrap();
+
+        let mut events = EventBuilder::new();
+
+        process_cancel_all_deadlines(&mut storage, Timestamp::from_seconds(100), &mut events)
+            .unwrap();
+
+        // The user's order is canceled and the switch disarmed.
+        assert!(
+            BIDS.idx
+                .order_id
+                .may_load(&storage, Uint64::new(1))
+                .unwrap()
+                .is_none()
+        );
+        assert!(!CANCEL_ALL_DEADLINES.has(&storage, USER));
+
+        let user_state = USER_STATES.load(&storage, USER).unwrap();
+        assert_eq!(user_state.reserved_margin, UsdValue::ZERO);
+        assert_eq!(user_state.open_order_count, 0);
+
+        // The other user's switch hasn't expired yet.
+        assert!(
+            BIDS.idx
+                .order_id
+                .may_load(&storage, Uint64::new(2))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            CANCEL_ALL_DEADLINES.load(&storage, OTHER).unwrap(),
+            Timestamp::from_seconds(101)
+        );
+    }
+
+    /// A switch armed by an address without a user state is simply disarmed.
+    #[test]
+    fn expired_switch_without_user_state() {
+        let mut storage = MockStorage::new();
+
+        CANCEL_ALL_DEADLINES
+            .save(&mut storage, USER, &Timestamp::from_seconds(100))
+            .unwrap();
+
+        let mut events = EventBuilder::new();
+
+        process_cancel_all_deadlines(&mut storage, Timestamp::from_seconds(200), &mut events)
+            .unwrap();
+
+        assert!(!CANCEL_ALL_DEADLINES.has(&storage, USER));
+        assert!(!USER_STATES.has(&storage, USER));
+    }
+}
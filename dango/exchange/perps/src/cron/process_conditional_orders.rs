@@ -1,25 +1,38 @@
 use {
     crate::{
+        order_group,
         position_index::apply_position_index_updates,
         referral::{FeeCommissionsOutcome, apply_fee_commissions},
-        state::{PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM, STATE, USER_STATES},
-        trade::{SubmitOrderOutcome, compute_submit_order_outcome, resize_reduce_only_orders},
+        state::{
+            ACTIVATED_ORDER_GROUPS, ORDER_GROUPS, PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM, STATE,
+            USER_STATES,
+        },
+        trade::{
+            SubmitOrderOutcome, compute_submit_order_outcome, place_group_order, place_oco_group,
+            resize_reduce_only_orders,
+        },
     },
     dango_math::{NumberConst, Uint64},
     dango_order_book::{
-        ASKS, BIDS, ConditionalOrderRemoved, ConditionalOrderTriggered, NEXT_FILL_ID,
-        NEXT_ORDER_ID, OrderKind, PairId, ReasonForOrderRemoval, TriggerDirection, UsdPrice,
-        decrease_liquidity_depths, flush_volumes, increase_liquidity_depths,
-        is_conditional_order_triggered, may_invert_price,
+        ASKS, BIDS, ConditionalOrderId, ConditionalOrderRemoved, ConditionalOrderTriggered,
+        NEXT_FILL_ID, NEXT_ORDER_ID, OrderKind, OrderPersisted, PairId, ReasonForOrderRemoval,
+        SelfTradePreventionMode, TimeInForce, TriggerDirection, UsdPrice,
+        compute_trailing_trigger_price, decrease_liquidity_depths, flush_volumes,
+        increase_liquidity_depths, is_conditional_order_triggered, may_invert_price,
     },
     dango_primitives::{
         Addr, EventBuilder, Order as IterationOrder, QuerierWrapper, StdResult, Storage, Timestamp,
     },
     dango_storage::PrefixBound,
-    dango_types::perps::{PairParam, PairState, Param, State},
+    dango_types::perps::{
+        GroupOrder, OrderGroupActivated, OrderGroupId, OrderGroupKind, PairParam, PairState, Param,
+        State,
+    },
+    std::collections::BTreeSet,
 };
 
-/// Evaluate and trigger conditional orders whose trigger conditions are met.
+/// Evaluate and trigger conditional orders whose trigger conditions are met,
+/// then activate the OTO order groups whose primary order has been filled.
 ///
 /// Called from `cron_execute` after `process_funding`. Uses range-bounded
 /// iteration so only triggered orders are visited (no full scan).
@@ -49,6 +62,11 @@ pub fn process_conditional_orders(
 
     STATE.save(storage, &state)?;
 
+    // Activate the groups after the state has been saved, since placing the
+    // secondary orders loads and saves it again. This includes the groups
+    // activated by the conditional orders triggered above.
+    process_order_group_activations(storage, querier, contract, current_time, events)?;
+
     Ok(())
 }
 
@@ -66,121 +84,157 @@ fn process_conditional_orders_for_pair(
     let pair_param = PAIR_PARAMS.load(storage, pair_id)?;
     let mut pair_state = PAIR_STATES.load(storage, pair_id)?;
 
+    // Move the trailing stops along with the oracle price first. A trailing
+    // trigger always ends up on the untriggered side of the oracle price, so
+    // this never causes an order to trigger in the same block.
+    update_trailing_stops(storage, pair_id, oracle_price)?;
+
+    // Conditional orders canceled by the OCO groups of orders triggered
+    // earlier in the loop. They may still be in the lists collected below.
+    let mut canceled_order_ids = BTreeSet::new();
+
     // ABOVE orders: trigger when oracle_price >= trigger_price.
     // Range: all keys with trigger_price <= oracle_price.
-    let above_triggered = USER_STATES
-        .idx
-        .conditional_orders
-        .sub_prefix(pair_id.clone())
-        .prefix_range(
-            storage,
-            Some(PrefixBound::Inclusive((
-                TriggerDirection::Above,
-                UsdPrice::MIN,
-                Uint64::MIN,
-            ))),
-            Some(PrefixBound::Inclusive((
-                TriggerDirection::Above,
-                oracle_price,
-                Uint64::MAX,
-            ))),
-            IterationOrder::Ascending,
-        )
-        .collect::<StdResult<Vec<_>>>()?;
+    //
+    // BELOW orders: trigger when oracle_price <= trigger_price.
+    // Keys store inverted trigger_price, so stored <= !oracle_price ≡ real >= oracle_price.
+    for (trigger_direction, max_stored_price) in [
+        (TriggerDirection::Above, oracle_price),
+        (TriggerDirection::Below, !oracle_price),
+    ] {
+        let triggered = USER_STATES
+            .idx
+            .conditional_orders
+            .sub_prefix(pair_id.clone())
+            .prefix_keys(
+                storage,
+                Some(PrefixBound::Inclusive((
+                    trigger_direction,
+                    UsdPrice::MIN,
+                    Uint64::MIN,
+                ))),
+                Some(PrefixBound::Inclusive((
+                    trigger_direction,
+                    max_stored_price,
+                    Uint64::MAX,
+                ))),
+                IterationOrder::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((_, _, order_id), user) in triggered {
+            if canceled_order_ids.contains(&order_id) {
+                continue;
+            }
 
-    for (user, _user_state) in above_triggered {
-        let TriggeredOrderOutcome {
-            state: updated_state,
-            pair_state: updated_pair_state,
-        } = process_triggered_order(
-            storage,
-            querier,
-            contract,
-            current_time,
-            param,
-            state,
-            pair_id,
-            &pair_param,
-            &pair_state,
-            user,
-            TriggerDirection::Above,
-            oracle_price,
-            events,
-        )?;
+            let TriggeredOrderOutcome {
+                state: updated_state,
+                pair_state: updated_pair_state,
+                canceled_order_ids: newly_canceled_order_ids,
+            } = process_triggered_order(
+                storage,
+                querier,
+                contract,
+                current_time,
+                param,
+                state,
+                pair_id,
+                &pair_param,
+                &pair_state,
+                user,
+                trigger_direction,
+                order_id,
+                oracle_price,
+                events,
+            )?;
 
-        *state = updated_state;
-        pair_state = updated_pair_state;
+            *state = updated_state;
+            pair_state = updated_pair_state;
+            canceled_order_ids.extend(newly_canceled_order_ids);
+        }
     }
 
-    // BELOW orders: trigger when oracle_price <= trigger_price.
-    // Keys store inverted trigger_price, so stored <= !oracle_price ≡ real >= oracle_price.
-    let below_triggered = USER_STATES
+    PAIR_STATES.save(storage, pair_id, &pair_state)?;
+
+    Ok(())
+}
+
+/// Update the trigger prices of the pair's trailing stops to follow the
+/// oracle price. See `compute_trailing_trigger_price` for the formula.
+fn update_trailing_stops(
+    storage: &mut dyn Storage,
+    pair_id: &PairId,
+    oracle_price: UsdPrice,
+) -> anyhow::Result<()> {
+    let users = USER_STATES
         .idx
-        .conditional_orders
+        .trailing_conditional_orders
         .sub_prefix(pair_id.clone())
-        .prefix_range(
-            storage,
-            Some(PrefixBound::Inclusive((
-                TriggerDirection::Below,
-                UsdPrice::MIN,
-                Uint64::MIN,
-            ))),
-            Some(PrefixBound::Inclusive((
-                TriggerDirection::Below,
-                !oracle_price,
-                Uint64::MAX,
-            ))),
-            IterationOrder::Ascending,
-        )
-        .collect::<StdResult<Vec<_>>>()?;
+        .keys(storage, None, None, IterationOrder::Ascending)
+        .map(|res| res.map(|(_, user)| user))
+        .collect::<StdResult<BTreeSet<_>>>()?;
+
+    for user in users {
+        let mut user_state = USER_STATES.load(storage, user)?;
+        let mut updated = false;
+
+        // Unwrap is safe: the user is in the index because of a position in
+        // this pair.
+        let position = user_state.positions.get_mut(pair_id).unwrap();
+
+        for trigger_direction in [TriggerDirection::Above, TriggerDirection::Below] {
+            for order in position
+                .conditional_orders_mut(trigger_direction)
+                .values_mut()
+            {
+                let Some(trailing_offset) = order.trailing_offset else {
+                    continue;
+                };
+
+                let trigger_price = compute_trailing_trigger_price(
+                    order.trigger_price,
+                    trigger_direction,
+                    trailing_offset,
+                    oracle_price,
+                )?;
 
-    for (user, _user_state) in below_triggered {
-        let TriggeredOrderOutcome {
-            state: updated_state,
-            pair_state: updated_pair_state,
-        } = process_triggered_order(
-            storage,
-            querier,
-            contract,
-            current_time,
-            param,
-            state,
-            pair_id,
-            &pair_param,
-            &pair_state,
-            user,
-            TriggerDirection::Below,
-            oracle_price,
-            events,
-        )?;
+                if trigger_price != order.trigger_price {
+                    order.trigger_price = trigger_price;
+                    updated = true;
+                }
+            }
+        }
 
-        *state = updated_state;
-        pair_state = updated_pair_state;
+        if updated {
+            USER_STATES.save(storage, user, &user_state)?;
+        }
     }
 
-    PAIR_STATES.save(storage, pair_id, &pair_state)?;
-
     Ok(())
 }
 
 /// Owned outcome of a `process_triggered_order` call.
 ///
-/// Carries only the post-call copies of `state` and `pair_state` —
+/// Carries the post-call copies of `state` and `pair_state` —
 /// every other piece of state the function touches is loaded inside
 /// the function (`user_state` from `USER_STATES`, `next_order_id`
 /// from `NEXT_ORDER_ID`) and written back to storage inline. The
 /// caller (`process_conditional_orders_for_pair`) maintains a rolling
 /// `state` / `pair_state` across multiple invocations and writes them
 /// back at the end of the per-pair loop, so we have to thread the
-/// updated copies out via the outcome.
+/// updated copies out via the outcome. Also carries the IDs of the
+/// conditional orders canceled because the triggered order was a member of
+/// an OCO group, so that the caller doesn't process them.
 #[derive(Debug)]
 pub struct TriggeredOrderOutcome {
     pub state: State,
     pub pair_state: PairState,
+    pub canceled_order_ids: Vec<ConditionalOrderId>,
 }
 
 /// Process a single triggered conditional order: verify position, clamp size,
-/// submit a market order to close. Pure w.r.t. `state` and `pair_state` —
+/// submit a market order to close (or, for a stop-limit, a reduce-only GTC
+/// limit order). Pure w.r.t. `state` and `pair_state` —
 /// takes them by `&` and returns the updated copies in the outcome. On the
 /// graceful-cancel path (slippage / no liquidity / position closed), the
 /// returned `state` and `pair_state` equal the inputs because `compute_submit_order_outcome`
@@ -197,31 +251,24 @@ fn process_triggered_order(
     pair_state: &PairState,
     user: Addr,
     trigger_direction: TriggerDirection,
+    order_id: ConditionalOrderId,
     oracle_price: UsdPrice,
     events: &mut EventBuilder,
 ) -> anyhow::Result<TriggeredOrderOutcome> {
     let mut user_state = USER_STATES.may_load(storage, user)?.unwrap_or_default();
 
-    // Extract the conditional order and position size info before mutating.
-    let (order, position_size) = match user_state.positions.get(pair_id) {
-        Some(pos) => {
-            let ord = match trigger_direction {
-                TriggerDirection::Above => pos.conditional_order_above.clone(),
-                TriggerDirection::Below => pos.conditional_order_below.clone(),
-            };
-            (ord, Some(pos.size))
-        },
+    // Take the conditional order out of the position BEFORE saving so the
+    // MultiIndex is updated. The order is gone if an earlier order in this
+    // block closed the position.
+    let (order, position_size) = match user_state.positions.get_mut(pair_id) {
+        Some(pos) => (
+            pos.conditional_orders_mut(trigger_direction)
+                .remove(&order_id),
+            Some(pos.size),
+        ),
         None => (None, None),
     };
 
-    // Clear the conditional order field BEFORE saving so the MultiIndex is updated.
-    if let Some(pos) = user_state.positions.get_mut(pair_id) {
-        match trigger_direction {
-            TriggerDirection::Above => pos.conditional_order_above = None,
-            TriggerDirection::Below => pos.conditional_order_below = None,
-        }
-    }
-
     // ------------- Pre-trigger check 1. position closed/flipped --------------
 
     let should_cancel = match (&order, position_size) {
@@ -242,6 +289,7 @@ fn process_triggered_order(
 
     if should_cancel {
         events.push(ConditionalOrderRemoved {
+            order_id,
             pair_id: pair_id.clone(),
             user,
             trigger_direction,
@@ -265,9 +313,12 @@ fn process_triggered_order(
         }
 
         // Graceful cancel: caller's `state` / `pair_state` are unchanged.
+        order_group::on_order_removed(storage, order_id, Some(events))?;
+
         return Ok(TriggeredOrderOutcome {
             state: state.clone(),
             pair_state: pair_state.clone(),
+            canceled_order_ids: Vec::new(),
         });
     }
 
@@ -290,8 +341,10 @@ fn process_triggered_order(
     // If governance has tightened `max_market_slippage` since the order
     // was submitted, the stored `order.max_slippage` may now exceed the
     // cap. Cancel it here instead of submitting to the matching engine.
-    if order.max_slippage > pair_param.max_market_slippage {
+    // Stop-limit orders don't use the slippage, so they're not affected.
+    if order.limit_price.is_none() && order.max_slippage > pair_param.max_market_slippage {
         events.push(ConditionalOrderRemoved {
+            order_id,
             pair_id: pair_id.clone(),
             user,
             trigger_direction,
@@ -316,9 +369,12 @@ fn process_triggered_order(
             );
         }
 
+        order_group::on_order_removed(storage, order_id, Some(events))?;
+
         return Ok(TriggeredOrderOutcome {
             state: state.clone(),
             pair_state: pair_state.clone(),
+            canceled_order_ids: Vec::new(),
         });
     }
 
@@ -347,6 +403,7 @@ fn process_triggered_order(
     };
 
     events.push(ConditionalOrderTriggered {
+        order_id,
         pair_id: pair_id.clone(),
         user,
         trigger_price: order.trigger_price,
@@ -354,6 +411,18 @@ fn process_triggered_order(
         oracle_price,
     })?;
 
+    // A stop-limit order rests the unfilled portion in the book.
+    let kind = match order.limit_price {
+        Some(limit_price) => OrderKind::Limit {
+            limit_price,
+            time_in_force: TimeInForce::GoodTilCanceled,
+            client_order_id: None,
+        },
+        None => OrderKind::Market {
+            max_slippage: order.max_slippage,
+        },
+    };
+
     // `compute_submit_order_outcome` is pure: takes `state` / `pair_state` / `user_state`
     // by `&` and returns updated copies in its outcome. On `Err`, the
     // caller's locals are untouched by construction, so the graceful-cancel
@@ -367,7 +436,8 @@ fn process_triggered_order(
         taker_state: user_state,
         mut maker_states,
         order_mutations,
-        order_to_store: _order_to_store,
+        maker_order_updates,
+        order_to_store,
         next_order_id,
         next_fill_id,
         index_updates,
@@ -386,22 +456,28 @@ fn process_triggered_order(
         &user_state,
         oracle_price,
         clamped_size,
-        OrderKind::Market {
-            max_slippage: order.max_slippage,
-        },
+        kind,
         true, // reduce_only
         None, // tp
         None, // sl
+        SelfTradePreventionMode::ExpireMaker,
         events,
     ) {
         Err(_) => {
-            // Order couldn't fill (slippage exceeded or no liquidity).
+            // Market order couldn't fill (slippage exceeded or no liquidity),
+            // or limit order was rejected (e.g. outside the price band).
             // Cancel it gracefully — don't block other orders.
+            let reason = match kind {
+                OrderKind::Market { .. } => ReasonForOrderRemoval::SlippageExceeded,
+                OrderKind::Limit { .. } => ReasonForOrderRemoval::LimitOrderRejected,
+            };
+
             events.push(ConditionalOrderRemoved {
+                order_id,
                 pair_id: pair_id.clone(),
                 user,
                 trigger_direction,
-                reason: ReasonForOrderRemoval::SlippageExceeded,
+                reason,
             })?;
 
             if user_state.is_empty() {
@@ -416,13 +492,17 @@ fn process_triggered_order(
                     %pair_id,
                     %user,
                     ?trigger_direction,
-                    "Conditional order cancelled: slippage exceeded"
+                    ?reason,
+                    "Conditional order cancelled: order not accepted"
                 );
             }
 
+            order_group::on_order_removed(storage, order_id, Some(events))?;
+
             return Ok(TriggeredOrderOutcome {
                 state: state.clone(),
                 pair_state: pair_state.clone(),
+                canceled_order_ids: Vec::new(),
             });
         },
         Ok(outcome) => outcome,
@@ -458,7 +538,7 @@ fn process_triggered_order(
     apply_position_index_updates(storage, &index_updates)?;
 
     let is_buy = clamped_size.is_positive();
-    let (maker_book, _taker_book) = if is_buy {
+    let (maker_book, taker_book) = if is_buy {
         (ASKS, BIDS)
     } else {
         (BIDS, ASKS)
@@ -497,7 +577,42 @@ fn process_triggered_order(
         }
     }
 
-    // Dynamic re-size of reduce-only orders. The triggered market order closed
+    // The unfilled portion of a stop-limit order rests in the book.
+    if let Some((stored_price, order_id, order)) = order_to_store {
+        let limit_price = may_invert_price(stored_price, is_buy);
+
+        increase_liquidity_depths(
+            storage,
+            pair_id,
+            is_buy,
+            limit_price,
+            order.size.checked_abs()?,
+            &pair_param.bucket_sizes,
+        )?;
+
+        taker_book.save(storage, (pair_id.clone(), stored_price, order_id), &order)?;
+
+        events.push(OrderPersisted {
+            order_id,
+            pair_id: pair_id.clone(),
+            user,
+            limit_price,
+            size: order.size,
+            client_order_id: None,
+        })?;
+    }
+
+    // Cancel the other members of the triggered order's OCO group, if any,
+    // then update the groups of the makers filled above, and those of
+    // conditional orders dropped along with a closed or flipped position.
+    // Done before the re-size below, which may cancel some of the same orders.
+    let canceled_order_ids =
+        order_group::on_conditional_order_triggered(storage, order_id, events)?;
+
+    order_group::on_maker_orders_updated(storage, &maker_order_updates, events)?;
+    order_group::on_positions_updated(storage, &index_updates, events)?;
+
+    // Dynamic re-size of reduce-only orders. The triggered order closed
     // (part of) `user`'s position and may have filled makers, so every affected
     // user's resting reduce-only orders are re-clamped to their new position.
     // The affected users are exactly the keys of `maker_states` (the triggered
@@ -509,5 +624,111 @@ fn process_triggered_order(
         resize_reduce_only_orders(storage, *user, pair_id, events)?;
     }
 
-    Ok(TriggeredOrderOutcome { state, pair_state })
+    Ok(TriggeredOrderOutcome {
+        state,
+        pair_state,
+        canceled_order_ids,
+    })
+}
+
+/// Place the secondary orders of the OTO groups whose primary order has been
+/// fully filled. Two or more secondaries are placed as a new OCO group.
+///
+/// Secondaries that can't be placed, e.g. a conditional order whose position
+/// has since been closed, are skipped. If trading has been disabled, only the
+/// reduce-only limit orders are placed.
+fn process_order_group_activations(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    contract: Addr,
+    current_time: Timestamp,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let trading_enabled = PARAM.load(storage)?.trading_enabled;
+
+    // Collect first, since placing the secondaries may activate more groups.
+    // Those are activated in the next block.
+    let group_ids = ACTIVATED_ORDER_GROUPS
+        .range(storage, None, None, IterationOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for group_id in group_ids {
+        let group = ORDER_GROUPS.load(storage, group_id)?;
+
+        order_group::remove_order_group(storage, group_id, &group)?;
+
+        let OrderGroupKind::Oto { secondaries, .. } = group.kind else {
+            continue;
+        };
+
+        let secondaries = secondaries
+            .into_iter()
+            .filter(|order| {
+                trading_enabled || matches!(order, GroupOrder::Limit(order) if order.reduce_only)
+            })
+            .collect::<Vec<_>>();
+
+        let order_ids = if secondaries.len() >= 2 {
+            // Assign group ID and increment.
+            let oco_group_id = NEXT_ORDER_ID.load(storage)?;
+            NEXT_ORDER_ID.save(storage, &(oco_group_id + OrderGroupId::ONE))?;
+
+            place_oco_group(
+                storage,
+                querier,
+                current_time,
+                contract,
+                group.user,
+                &group.pair_id,
+                oco_group_id,
+                &secondaries,
+                false,
+                events,
+            )?
+        } else {
+            let mut order_ids = Vec::new();
+
+            for order in &secondaries {
+                match place_group_order(
+                    storage,
+                    querier,
+                    current_time,
+                    contract,
+                    group.user,
+                    &group.pair_id,
+                    order,
+                    events,
+                ) {
+                    Ok(placed) => {
+                        if placed.member.is_some() || placed.filled {
+                            order_ids.push(placed.order_id);
+                        }
+                    },
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        {
+                            tracing::info!(
+                                %group_id,
+                                user = %group.user,
+                                pair_id = %group.pair_id,
+                                err = %_err,
+                                "Secondary order not placed"
+                            );
+                        }
+                    },
+                }
+            }
+
+            order_ids
+        };
+
+        events.push(OrderGroupActivated {
+            group_id,
+            user: group.user,
+            pair_id: group.pair_id,
+            order_ids,
+        })?;
+    }
+
+    Ok(())
 }
@@ -1,14 +1,23 @@
 use {
     crate::{
-        core::{compute_funding_delta, compute_vault_premium},
-        state::{PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM, STATE, USER_STATES},
+        core::{compute_funding_delta, compute_impact_premium, compute_vault_premium},
+        state::{
+            FUNDING_HISTORY, PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM, PREMIUM_SAMPLES, STATE,
+            USER_STATES,
+        },
+    },
+    dango_order_book::{
+        Days, Dimensionless, FundingRate, PairId, Quantity, compute_impact_bid_ask,
     },
-    dango_order_book::{Days, PairId, Quantity},
     dango_primitives::{Addr, Storage, Timestamp},
+    dango_types::perps::{FundingMode, FundingRecord},
 };
 
-/// Compute and apply funding deltas for each trading pair using the vault's
-/// inventory-skew-derived premium.
+/// Compute and apply funding deltas for each trading pair, using the premium
+/// derived according to the pair's `FundingMode`.
+///
+/// The premium of pairs in `FundingMode::PremiumIndex` is sampled on every
+/// call, regardless of whether the funding period has elapsed.
 pub fn process_funding(
     storage: &mut dyn Storage,
     current_time: Timestamp,
@@ -16,6 +25,11 @@ pub fn process_funding(
 ) -> anyhow::Result<()> {
     let param = PARAM.load(storage)?;
     let mut state = STATE.load(storage)?;
+    let pair_ids = PAIR_IDS.load(storage)?;
+
+    for pair_id in &pair_ids {
+        sample_premium(storage, pair_id)?;
+    }
 
     // Only process funding if sufficient time has elapsed since the last funding time.
     let elapsed = current_time - state.last_funding_time;
@@ -24,10 +38,9 @@ pub fn process_funding(
     }
 
     let interval = Days::from_duration(elapsed)?;
-    let pair_ids = PAIR_IDS.load(storage)?;
 
     for pair_id in pair_ids {
-        process_funding_for_pair(storage, contract, interval, pair_id)?;
+        process_funding_for_pair(storage, current_time, contract, interval, pair_id)?;
     }
 
     state.last_funding_time = current_time;
@@ -37,8 +50,47 @@ pub fn process_funding(
     Ok(())
 }
 
+/// Sample the premium of the order book's impact bid/ask mid price over the
+/// index price, if the pair is in `FundingMode::PremiumIndex`.
+///
+/// The sample is skipped if either side of the book is thinner than the pair's
+/// `impact_size`, or the index price isn't yet known.
+fn sample_premium(storage: &mut dyn Storage, pair_id: &PairId) -> anyhow::Result<()> {
+    let pair_param = PAIR_PARAMS.load(storage, pair_id)?;
+
+    let FundingMode::PremiumIndex { .. } = pair_param.funding_mode else {
+        return Ok(());
+    };
+
+    let index_price = PAIR_STATES.load(storage, pair_id)?.index_price;
+
+    if index_price.is_zero() {
+        return Ok(());
+    }
+
+    let (Some(impact_bid), Some(impact_ask)) =
+        compute_impact_bid_ask(storage, pair_id, pair_param.impact_size)?
+    else {
+        return Ok(());
+    };
+
+    let premium = compute_impact_premium(impact_bid, impact_ask, index_price)?;
+
+    let mut samples = PREMIUM_SAMPLES
+        .may_load(storage, pair_id)?
+        .unwrap_or_default();
+
+    samples.sum.checked_add_assign(premium)?;
+    samples.count += 1;
+
+    PREMIUM_SAMPLES.save(storage, pair_id, &samples)?;
+
+    Ok(())
+}
+
 fn process_funding_for_pair(
     storage: &mut dyn Storage,
+    current_time: Timestamp,
     contract: Addr,
     interval: Days,
     pair_id: PairId,
@@ -48,48 +100,53 @@ fn process_funding_for_pair(
 
     let oracle_price = pair_state.index_price;
 
-    // Compute premium from the vault's inventory skew. When the vault is
-    // the dominant maker, its skew-aware pricing directly determines the
-    // mid-market price, yielding:
-    //   premium = -halfSpread × skew × spreadSkewFactor
-    let vault_position_size = USER_STATES
-        .may_load(storage, contract)?
-        .and_then(|vs| vs.positions.get(&pair_id).map(|p| p.size))
-        .unwrap_or(Quantity::ZERO);
-
-    let premium = compute_vault_premium(vault_position_size, &pair_param)?;
-
-    // --- Impact-price model (replaced by vault premium above) -----------
-    // let bid_iter = BIDS
-    //     .prefix(pair_id.clone())
-    //     .range(storage, None, None, IterationOrder::Ascending)
-    //     .map(|res| {
-    //         let ((stored_price, _), order) = res?;
-    //         let real_price = may_invert_price(stored_price, true);
-    //         Ok((real_price, order.size))
-    //     });
-    //
-    // let ask_iter = ASKS
-    //     .prefix(pair_id.clone())
-    //     .range(storage, None, None, IterationOrder::Ascending)
-    //     .map(|res| {
-    //         let ((stored_price, _), order) = res?;
-    //         Ok((stored_price, order.size.checked_abs()?))
-    //     });
-    //
-    // let impact_bid = compute_impact_price(bid_iter, pair_param.impact_size)?;
-    // let impact_ask = compute_impact_price(ask_iter, pair_param.impact_size)?;
-    //
-    // let premium = match (impact_bid, impact_ask) {
-    //     (Some(impact_bid), Some(impact_ask)) => {
-    //         compute_impact_premium(impact_bid, impact_ask, oracle_price)?
-    //     },
-    //     _ => Dimensionless::ZERO,
-    // };
-    // -------------------------------------------------------------------
+    // Consume the samples taken over the funding period, so the next period
+    // starts afresh. Pairs not in premium-index mode have none, unless they
+    // were switched out of it during the period.
+    let samples = PREMIUM_SAMPLES
+        .may_load(storage, &pair_id)?
+        .unwrap_or_default();
+
+    PREMIUM_SAMPLES.remove(storage, &pair_id);
+
+    let (premium, interest_rate) = match pair_param.funding_mode {
+        // Compute premium from the vault's inventory skew. When the vault is
+        // the dominant maker, its skew-aware pricing directly determines the
+        // mid-market price, yielding:
+        //   premium = -halfSpread × skew × spreadSkewFactor
+        FundingMode::VaultSkew => {
+            let vault_position_size = USER_STATES
+                .may_load(storage, contract)?
+                .and_then(|vs| vs.positions.get(&pair_id).map(|p| p.size))
+                .unwrap_or(Quantity::ZERO);
+
+            let premium = compute_vault_premium(vault_position_size, &pair_param)?;
+
+            (premium, FundingRate::ZERO)
+        },
+        // Average the premiums sampled over the funding period. Without any
+        // sample (e.g. the book was too thin throughout), only interest is
+        // charged.
+        FundingMode::PremiumIndex { interest_rate } => {
+            let premium = if samples.count > 0 {
+                samples
+                    .sum
+                    .checked_div(Dimensionless::new_int(samples.count as i128))?
+            } else {
+                Dimensionless::ZERO
+            };
+
+            (premium, interest_rate)
+        },
+    };
+
+    // The interest rate is per day, as is the premium once interpreted as a
+    // funding rate, so they add up directly.
+    let premium_with_interest =
+        premium.checked_add(Dimensionless::new(interest_rate.into_inner()))?;
 
     let (funding_delta, funding_rate) = compute_funding_delta(
-        premium,
+        premium_with_interest,
         oracle_price,
         pair_param.max_abs_funding_rate,
         interval,
@@ -100,6 +157,17 @@ fn process_funding_for_pair(
 
     PAIR_STATES.save(storage, &pair_id, &pair_state)?;
 
+    FUNDING_HISTORY.save(
+        storage,
+        (&pair_id, current_time),
+        &FundingRecord {
+            funding_rate,
+            funding_delta,
+            premium,
+            index_price: oracle_price,
+        },
+    )?;
+
     #[cfg(feature = "tracing")]
     {
         tracing::info!(
@@ -135,7 +203,10 @@ fn process_funding_for_pair(
 mod tests {
     use {
         super::*,
-        dango_order_book::{Dimensionless, FundingPerUnit, FundingRate, UsdPrice},
+        dango_math::Uint64,
+        dango_order_book::{
+            ASKS, BIDS, FundingPerUnit, LimitOrder, UsdPrice, UsdValue, may_invert_price,
+        },
         dango_primitives::{Duration, MockStorage},
         dango_types::perps::{PairParam, PairState, Param, Position, State, UserState},
         std::collections::{BTreeMap, BTreeSet},
@@ -163,8 +234,8 @@ mod tests {
                 size: Quantity::new_int(size),
                 entry_price: UsdPrice::ZERO,
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         USER_STATES
@@ -434,8 +505,8 @@ mod tests {
                 size: Quantity::new_int(50),
                 entry_price: UsdPrice::ZERO,
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         positions.insert(
@@ -444,8 +515,8 @@ mod tests {
                 size: Quantity::new_int(-50),
                 entry_price: UsdPrice::ZERO,
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         USER_STATES
@@ -503,4 +574,208 @@ mod tests {
         // Accumulator = initial (100) + positive delta, so strictly greater.
         assert!(pair_state.funding_per_unit > initial_funding);
     }
+
+    fn premium_index_pair_param(interest_rate: FundingRate) -> PairParam {
+        PairParam {
+            impact_size: UsdValue::new_int(10_000),
+            funding_mode: FundingMode::PremiumIndex { interest_rate },
+            ..default_funding_pair_param()
+        }
+    }
+
+    /// Bid prices are stored inverted, so impact prices computed from the book
+    /// may be off by a few units in the last decimal place.
+    fn assert_approx(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected ~{expected}, got {actual}"
+        );
+    }
+
+    /// Rest a bid and an ask of 1 unit each at the given prices, so that they
+    /// are the impact bid and ask.
+    fn save_bid_and_ask(storage: &mut dyn Storage, pair_id: &PairId, bid: i128, ask: i128) {
+        let order = |size| LimitOrder {
+            user: Addr::mock(1),
+            size: Quantity::new_int(size),
+            reduce_only: false,
+            reserved_margin: UsdValue::ZERO,
+            created_at: Timestamp::from_seconds(0),
+            tp: None,
+            sl: None,
+            client_order_id: None,
+        };
+
+        let bid_price = may_invert_price(UsdPrice::new_int(bid), true);
+        BIDS.save(
+            storage,
+            (pair_id.clone(), bid_price, Uint64::new(1)),
+            &order(1),
+        )
+        .unwrap();
+        ASKS.save(
+            storage,
+            (pair_id.clone(), UsdPrice::new_int(ask), Uint64::new(2)),
+            &order(-1),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn funding_premium_index_settles_average_premium_plus_interest() {
+        let mut storage = MockStorage::new();
+        let pair_id = btc_pair_id();
+
+        init_funding_storage(
+            &mut storage,
+            &pair_id,
+            &premium_index_pair_param(FundingRate::new_permille(1)), // 0.1%/day
+            &PairState {
+                index_price: UsdPrice::new_int(50_000),
+                ..Default::default()
+            },
+            3600,
+            0,
+        );
+
+        // Mid = $50,200 → premium = 200 / 50,000 = 0.4%.
+        save_bid_and_ask(&mut storage, &pair_id, 50_100, 50_300);
+
+        // Before the funding period elapses, the premium is only sampled.
+        process_funding(&mut storage, Timestamp::from_seconds(1800), CONTRACT).unwrap();
+
+        let samples = PREMIUM_SAMPLES.load(&storage, &pair_id).unwrap();
+        assert_eq!(samples.count, 1);
+        assert_approx(samples.sum.to_f64(), 0.004);
+
+        let pair_state = PAIR_STATES.load(&storage, &pair_id).unwrap();
+        assert_eq!(pair_state.funding_per_unit, FundingPerUnit::ZERO);
+
+        // Mid = $49,900 → premium = -100 / 50,000 = -0.2%.
+        BIDS.remove(
+            &mut storage,
+            (
+                pair_id.clone(),
+                may_invert_price(UsdPrice::new_int(50_100), true),
+                Uint64::new(1),
+            ),
+        )
+        .unwrap();
+        ASKS.remove(
+            &mut storage,
+            (pair_id.clone(), UsdPrice::new_int(50_300), Uint64::new(2)),
+        )
+        .unwrap();
+        save_bid_and_ask(&mut storage, &pair_id, 49_800, 50_000);
+
+        process_funding(&mut storage, Timestamp::from_seconds(3600), CONTRACT).unwrap();
+
+        // Average premium = (0.4% - 0.2%) / 2 = 0.1%; plus 0.1% interest.
+        let pair_state = PAIR_STATES.load(&storage, &pair_id).unwrap();
+        assert_approx(pair_state.funding_rate.to_f64(), 0.002);
+        assert!(pair_state.funding_per_unit > FundingPerUnit::ZERO);
+
+        // The samples are consumed.
+        assert!(!PREMIUM_SAMPLES.has(&storage, &pair_id));
+
+        let record = FUNDING_HISTORY
+            .load(&storage, (&pair_id, Timestamp::from_seconds(3600)))
+            .unwrap();
+        assert_eq!(record.funding_rate, pair_state.funding_rate);
+        assert_eq!(record.funding_delta, pair_state.funding_per_unit);
+        assert_approx(record.premium.to_f64(), 0.001);
+        assert_eq!(record.index_price, UsdPrice::new_int(50_000));
+    }
+
+    #[test]
+    fn funding_premium_index_thin_book_charges_interest_only() {
+        let mut storage = MockStorage::new();
+        let pair_id = btc_pair_id();
+
+        init_funding_storage(
+            &mut storage,
+            &pair_id,
+            &premium_index_pair_param(FundingRate::new_permille(1)),
+            &PairState {
+                index_price: UsdPrice::new_int(50_000),
+                ..Default::default()
+            },
+            3600,
+            0,
+        );
+
+        // The vault's skew is ignored in premium-index mode.
+        set_vault_position(&mut storage, &pair_id, 50);
+
+        // The book is empty, so no sample is taken.
+        process_funding(&mut storage, Timestamp::from_seconds(3600), CONTRACT).unwrap();
+
+        let pair_state = PAIR_STATES.load(&storage, &pair_id).unwrap();
+        assert_eq!(pair_state.funding_rate, FundingRate::new_permille(1));
+    }
+
+    #[test]
+    fn funding_premium_index_clamped_by_max_rate() {
+        let mut storage = MockStorage::new();
+        let pair_id = btc_pair_id();
+
+        init_funding_storage(
+            &mut storage,
+            &pair_id,
+            &premium_index_pair_param(FundingRate::ZERO),
+            &PairState {
+                index_price: UsdPrice::new_int(50_000),
+                ..Default::default()
+            },
+            3600,
+            0,
+        );
+
+        // Mid = $55,000 → premium = 10%, above the 5% cap.
+        save_bid_and_ask(&mut storage, &pair_id, 54_000, 56_000);
+
+        process_funding(&mut storage, Timestamp::from_seconds(3600), CONTRACT).unwrap();
+
+        let pair_state = PAIR_STATES.load(&storage, &pair_id).unwrap();
+        assert_eq!(pair_state.funding_rate, FundingRate::new_permille(50));
+
+        let record = FUNDING_HISTORY
+            .load(&storage, (&pair_id, Timestamp::from_seconds(3600)))
+            .unwrap();
+        assert_approx(record.premium.to_f64(), 0.1);
+    }
+
+    #[test]
+    fn funding_vault_skew_records_history() {
+        let mut storage = MockStorage::new();
+        let pair_id = btc_pair_id();
+
+        init_funding_storage(
+            &mut storage,
+            &pair_id,
+            &default_funding_pair_param(),
+            &PairState {
+                index_price: UsdPrice::new_int(50_000),
+                ..Default::default()
+            },
+            3600,
+            0,
+        );
+
+        // Vault is short 50 → premium = 0.0015. No sample is taken in
+        // vault-skew mode, even though the book is deep enough.
+        set_vault_position(&mut storage, &pair_id, -50);
+        save_bid_and_ask(&mut storage, &pair_id, 50_100, 50_300);
+
+        process_funding(&mut storage, Timestamp::from_seconds(1800), CONTRACT).unwrap();
+        assert!(!PREMIUM_SAMPLES.has(&storage, &pair_id));
+
+        process_funding(&mut storage, Timestamp::from_seconds(3600), CONTRACT).unwrap();
+
+        let record = FUNDING_HISTORY
+            .load(&storage, (&pair_id, Timestamp::from_seconds(3600)))
+            .unwrap();
+        assert_eq!(record.premium, Dimensionless::new_raw(1_500));
+        assert_eq!(record.funding_rate, FundingRate::new_raw(1_500));
+    }
 }
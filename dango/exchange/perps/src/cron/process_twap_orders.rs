@@ -0,0 +1,250 @@
+use {
+    crate::{
+        state::{PAIR_PARAMS, PAIR_STATES, PARAM, STATE, TWAP_ORDERS, USER_STATES},
+        trade::{
+            apply_submit_order_outcome, compute_submit_order_outcome, ensure_pair_accepts_order,
+        },
+    },
+    dango_order_book::{
+        Dimensionless, OrderKind, PairId, Quantity, SelfTradePreventionMode,
+        check_minimum_order_size,
+    },
+    dango_primitives::{
+        Addr, EventBuilder, Order as IterationOrder, QuerierWrapper, StdResult, Storage, Timestamp,
+    },
+    dango_storage::PrefixBound,
+    dango_types::perps::{
+        ReasonForTwapRemoval, TwapOrder, TwapOrderId, TwapOrderRemoved, TwapSliceFilled,
+        TwapSliceSkipped, UserState,
+    },
+};
+
+/// Execute one slice of each TWAP order whose next slice is due.
+///
+/// Called from `cron_execute` after `process_conditional_orders`. Uses
+/// range-bounded iteration over the `next_slice_time` index so only due orders
+/// are visited.
+///
+/// Each slice is a market order of size `unfilled_size / remaining_slices`. If
+/// that falls below the pair's minimum order size, the remaining slices are
+/// merged into this one. A slice that can't be executed (e.g. slippage
+/// exceeded, no liquidity, or insufficient margin) fills nothing; the unfilled
+/// size is carried over to the subsequent slices.
+pub fn process_twap_orders(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    contract: Addr,
+    current_time: Timestamp,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let due = TWAP_ORDERS
+        .idx
+        .next_slice_time
+        .prefix_range(
+            storage,
+            None,
+            Some(PrefixBound::Inclusive(current_time)),
+            IterationOrder::Ascending,
+        )
+        .map(|res| res.map(|(_, twap_id, order)| (twap_id, order)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (twap_id, order) in due {
+        process_twap_slice(
+            storage,
+            querier,
+            contract,
+            current_time,
+            twap_id,
+            order,
+            events,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn process_twap_slice(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    contract: Addr,
+    current_time: Timestamp,
+    twap_id: TwapOrderId,
+    mut order: TwapOrder,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let param = PARAM.load(storage)?;
+    let pair_param = PAIR_PARAMS.load(storage, &order.pair_id)?;
+
+    // Once the exchange or the pair is wound down, only orders that reduce
+    // risk may keep executing; none once the pair is being delisted.
+    if (!param.trading_enabled && !order.reduce_only)
+        || ensure_pair_accepts_order(&order.pair_id, &pair_param, order.reduce_only).is_err()
+    {
+        return remove_twap_order(
+            storage,
+            twap_id,
+            order,
+            ReasonForTwapRemoval::TradingDisabled,
+            events,
+        );
+    }
+
+    let state = STATE.load(storage)?;
+    let pair_state = PAIR_STATES.load(storage, &order.pair_id)?;
+    let user_state = USER_STATES
+        .may_load(storage, order.user)?
+        .unwrap_or_default();
+
+    let oracle_price = pair_state.index_price;
+
+    let unfilled_size = order.size.checked_sub(order.filled_size)?;
+    let mut slice_size =
+        unfilled_size.checked_div(Dimensionless::new_int(order.remaining_slices as i128))?;
+
+    // Partial fills, or a drop in the oracle price, may have shrunk the slices
+    // below the minimum order size, so that all of them would be rejected.
+    // Execute the unfilled size in one go instead. Reduce-only orders are
+    // exempt from the minimum, same as in `submit_order`.
+    if !order.reduce_only
+        && order.remaining_slices > 1
+        && check_minimum_order_size(slice_size, oracle_price, pair_param.min_order_size).is_err()
+    {
+        slice_size = unfilled_size;
+        order.remaining_slices = 1;
+    }
+
+    let size_before = position_size(&user_state, &order.pair_id);
+
+    // `compute_submit_order_outcome` is pure, so on `Err` nothing needs to be
+    // reverted; the slice is just skipped.
+    let (filled_size, size_after) = match compute_submit_order_outcome(
+        storage,
+        order.user,
+        contract,
+        current_time,
+        &param,
+        &state,
+        &order.pair_id,
+        &pair_param,
+        &pair_state,
+        &user_state,
+        oracle_price,
+        slice_size,
+        OrderKind::Market {
+            max_slippage: order.max_slippage,
+        },
+        order.reduce_only,
+        None, // tp
+        None, // sl
+        SelfTradePreventionMode::ExpireMaker,
+        events,
+    ) {
+        Ok(outcome) => {
+            let size_after = position_size(&outcome.taker_state, &order.pair_id);
+
+            apply_submit_order_outcome(
+                storage,
+                querier,
+                current_time,
+                contract,
+                order.user,
+                &order.pair_id,
+                &param,
+                &pair_param,
+                slice_size,
+                order.reduce_only,
+                outcome,
+                events,
+            )?;
+
+            (size_after.checked_sub(size_before)?, size_after)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            {
+                tracing::info!(
+                    %twap_id,
+                    user = %order.user,
+                    pair_id = %order.pair_id,
+                    %slice_size,
+                    %err,
+                    "TWAP slice not executed"
+                );
+            }
+
+            events.push(TwapSliceSkipped {
+                twap_id,
+                user: order.user,
+                pair_id: order.pair_id.clone(),
+                slice_size,
+                reason: err.to_string(),
+                remaining_slices: order.remaining_slices - 1,
+            })?;
+
+            (Quantity::ZERO, size_before)
+        },
+    };
+
+    order.filled_size.checked_add_assign(filled_size)?;
+    order.remaining_slices -= 1;
+
+    if filled_size.is_non_zero() {
+        events.push(TwapSliceFilled {
+            twap_id,
+            user: order.user,
+            pair_id: order.pair_id.clone(),
+            slice_size,
+            filled_size,
+            total_filled_size: order.filled_size,
+            remaining_slices: order.remaining_slices,
+        })?;
+    }
+
+    // A reduce-only order is also done once there's no position left to reduce.
+    if order.remaining_slices == 0
+        || order.filled_size == order.size
+        || (order.reduce_only && size_after.is_zero())
+    {
+        return remove_twap_order(
+            storage,
+            twap_id,
+            order,
+            ReasonForTwapRemoval::Completed,
+            events,
+        );
+    }
+
+    order.next_slice_time = order.next_slice_time + order.slice_interval;
+
+    TWAP_ORDERS.save(storage, twap_id, &order)?;
+
+    Ok(())
+}
+
+fn remove_twap_order(
+    storage: &mut dyn Storage,
+    twap_id: TwapOrderId,
+    order: TwapOrder,
+    reason: ReasonForTwapRemoval,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    TWAP_ORDERS.remove(storage, twap_id)?;
+
+    events.push(TwapOrderRemoved {
+        twap_id,
+        user: order.user,
+        pair_id: order.pair_id,
+        filled_size: order.filled_size,
+        reason,
+    })?;
+
+    Ok(())
+}
+
+fn position_size(user_state: &UserState, pair_id: &PairId) -> Quantity {
+    user_state
+        .positions
+        .get(pair_id)
+        .map_or(Quantity::ZERO, |position| position.size)
+}
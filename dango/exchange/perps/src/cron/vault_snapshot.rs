@@ -103,8 +103,8 @@ mod tests {
                     size: Quantity::new_int(position_size),
                     entry_price: UsdPrice::ZERO,
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             );
         }
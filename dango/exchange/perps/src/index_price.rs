@@ -5,8 +5,8 @@ use {
         state::{PAIR_IDS, PAIR_PARAMS, PAIR_STATES},
     },
     dango_oracle::OracleQuerier,
-    dango_order_book::{ASKS, BIDS, Dimensionless, PairId, compute_impact_price, may_invert_price},
-    dango_primitives::{Order as IterationOrder, Storage, Timestamp},
+    dango_order_book::{Dimensionless, PairId, compute_impact_bid_ask},
+    dango_primitives::{Storage, Timestamp},
     dango_pyth_types::MarketSession,
     dango_types::{
         oracle::Price,
@@ -103,25 +103,8 @@ fn process_index_price_for_pair(
                 },
             }
 
-            let bid_iter = BIDS
-                .prefix(pair_id.clone())
-                .range(storage, None, None, IterationOrder::Ascending)
-                .map(|res| {
-                    let ((stored_price, _), order) = res?;
-                    let real_price = may_invert_price(stored_price, true);
-                    Ok((real_price, order.size))
-                });
-
-            let ask_iter = ASKS
-                .prefix(pair_id.clone())
-                .range(storage, None, None, IterationOrder::Ascending)
-                .map(|res| {
-                    let ((stored_price, _), order) = res?;
-                    Ok((stored_price, order.size.checked_abs()?))
-                });
-
-            let impact_bid = compute_impact_price(bid_iter, pair_param.impact_size)?;
-            let impact_ask = compute_impact_price(ask_iter, pair_param.impact_size)?;
+            let (impact_bid, impact_ask) =
+                compute_impact_bid_ask(storage, pair_id, pair_param.impact_size)?;
 
             let delta_t = current_time - pair_state.last_index_time;
 
@@ -164,7 +147,10 @@ mod tests {
         super::*,
         anyhow::anyhow,
         dango_math::Uint64,
-        dango_order_book::{Dimensionless, LimitOrder, OrderKey, Quantity, UsdPrice, UsdValue},
+        dango_order_book::{
+            ASKS, BIDS, Dimensionless, LimitOrder, OrderKey, Quantity, UsdPrice, UsdValue,
+            may_invert_price,
+        },
         dango_primitives::{Addr, Duration, MockStorage},
     };
 
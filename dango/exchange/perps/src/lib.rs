@@ -4,6 +4,7 @@ pub mod index_price;
 pub mod maintain;
 #[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod order_group;
 pub mod position_index;
 pub mod querier;
 pub mod query;
@@ -13,7 +14,10 @@ pub mod trade;
 pub mod vault;
 
 use {
-    crate::state::{FEE_RATE_OVERRIDES, PAIR_PARAMS, PAIR_STATES, PARAM, STATE, USER_STATES},
+    crate::state::{
+        ACCOUNT_OWNERS, CANCEL_ALL_DEADLINES, FEE_RATE_OVERRIDES, ORDER_GROUPS, PAIR_PARAMS,
+        PAIR_STATES, PARAM, STATE, TWAP_ORDERS, USER_STATES,
+    },
     anyhow::{bail, ensure},
     dango_math::{NumberConst, Uint128},
     dango_order_book::{FillId, NEXT_FILL_ID, NEXT_ORDER_ID, OrderId, UsdValue},
@@ -24,8 +28,9 @@ use {
     dango_types::{
         DangoQuerier,
         perps::{
-            CancelConditionalOrderRequest, CancelOrderRequest, ExecuteMsg, InstantiateMsg,
-            MaintainerMsg, QueryMsg, ReferralMsg, State, SubmitOrderRequest, TraderMsg, VaultMsg,
+            CancelConditionalOrderRequest, CancelOrderRequest, CancelTwapOrderRequest, ExecuteMsg,
+            InstantiateMsg, MaintainerMsg, QueryMsg, ReferralMsg, State, SubmitOrderRequest,
+            TraderMsg, VaultMsg,
         },
     },
 };
@@ -46,6 +51,38 @@ const VOLUME_LOOKBACK: Duration = Duration::from_days(14);
 /// Reject oracle prices for being too old if older than this threshold.
 const MAX_ORACLE_STALENESS: Duration = Duration::from_millis(500);
 
+/// Maximum number of conditional orders a position can have in each trigger
+/// direction. Bounds the work done on the position whenever it's touched.
+const MAX_CONDITIONAL_ORDERS_PER_DIRECTION: usize = 10;
+
+/// Maximum number of active TWAP orders a user can have. Bounds the work done
+/// by the cron on behalf of a single user.
+const MAX_TWAP_ORDERS_PER_USER: usize = 5;
+
+/// Minimum time between two slices of a TWAP order.
+const MIN_TWAP_SLICE_INTERVAL: Duration = Duration::from_seconds(30);
+
+/// Maximum duration of a TWAP order.
+const MAX_TWAP_DURATION: Duration = Duration::from_days(1);
+
+/// Maximum number of orders in an OCO group, or of secondary orders in an OTO
+/// group.
+const MAX_ORDERS_PER_GROUP: usize = 5;
+
+/// Maximum number of active order groups a user can have.
+const MAX_ORDER_GROUPS_PER_USER: usize = 10;
+
+/// Minimum timeout of a dead-man's switch. Prevents a switch from expiring in
+/// the same block it's armed.
+const MIN_CANCEL_ALL_TIMEOUT: Duration = Duration::from_seconds(5);
+
+/// Maximum timeout of a dead-man's switch.
+const MAX_CANCEL_ALL_TIMEOUT: Duration = Duration::from_days(1);
+
+/// Maximum number of levels on each side of the vault's quote ladder. Bounds
+/// the number of orders the vault places per pair every block.
+const MAX_VAULT_LADDER_LEVELS: u32 = 10;
+
 /// Returns the oracle contract address.
 ///
 /// In release builds, returns a compile-time constant for zero-cost lookups.
@@ -104,6 +141,8 @@ pub fn cron_execute(ctx: SudoCtx) -> anyhow::Result<Response> {
 
     cron::process_funding(ctx.storage, ctx.block.timestamp, ctx.contract)?;
 
+    cron::process_cancel_all_deadlines(ctx.storage, ctx.block.timestamp, &mut events)?;
+
     cron::process_conditional_orders(
         ctx.storage,
         ctx.querier,
@@ -112,9 +151,17 @@ pub fn cron_execute(ctx: SudoCtx) -> anyhow::Result<Response> {
         &mut events,
     )?;
 
+    cron::process_twap_orders(
+        ctx.storage,
+        ctx.querier,
+        ctx.contract,
+        ctx.block.timestamp,
+        &mut events,
+    )?;
+
     // Take the vault snapshot last, so equity reflects the end-of-block state
-    // — including funding application and any conditional-order fills that
-    // settled this block. Mirrors what the metrics path captures below.
+    // — including funding application and any conditional-order and TWAP
+    // fills that settled this block. Mirrors what the metrics path captures below.
     cron::take_vault_snapshot(ctx.storage, ctx.block.timestamp, ctx.contract)?;
 
     #[cfg(feature = "metrics")]
@@ -147,18 +194,30 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
                 maintain::configure(ctx, param, pair_params)
             },
             MaintainerMsg::Liquidate { user } => maintain::liquidate(ctx, user),
+            MaintainerMsg::LiquidateIsolated { user, pair_id } => {
+                maintain::liquidate_isolated(ctx, user, pair_id)
+            },
             MaintainerMsg::Donate {} => maintain::donate(ctx),
             MaintainerMsg::WithdrawTreasury {} => maintain::withdraw_treasury(ctx),
+            MaintainerMsg::DisableTrading {} => maintain::disable_trading(ctx),
             MaintainerMsg::SetFeeRateOverride {
                 user,
                 maker_taker_fee_rates,
             } => maintain::set_fee_rate_override(ctx, user, maker_taker_fee_rates),
             MaintainerMsg::RefreshIndexPrices {} => maintain::refresh_index_prices(ctx),
             MaintainerMsg::RefreshVaultOrders {} => maintain::refresh_vault_orders(ctx),
+            MaintainerMsg::SettlePair {
+                pair_id,
+                settlement_price,
+            } => maintain::settle_pair(ctx, pair_id, settlement_price),
+            MaintainerMsg::ProcessPairSettlement { pair_id, limit } => {
+                maintain::process_pair_settlement(ctx, pair_id, limit)
+            },
         },
         ExecuteMsg::Trade(msg) => match msg {
             TraderMsg::Deposit { to } => trade::deposit(ctx, to),
             TraderMsg::Withdraw { amount } => trade::withdraw(ctx, amount),
+            TraderMsg::TransferMargin { to, amount } => trade::transfer_margin(ctx, to, amount),
             TraderMsg::SubmitOrder(SubmitOrderRequest {
                 pair_id,
                 size,
@@ -166,7 +225,8 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
                 reduce_only,
                 tp,
                 sl,
-            }) => trade::submit_order(ctx, pair_id, size, kind, reduce_only, tp, sl),
+                stp_mode,
+            }) => trade::submit_order(ctx, pair_id, size, kind, reduce_only, tp, sl, stp_mode),
             TraderMsg::CancelOrder(CancelOrderRequest::One(order_id)) => {
                 trade::cancel_one_order(ctx, order_id)
             },
@@ -181,6 +241,8 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
                 trigger_price,
                 trigger_direction,
                 max_slippage,
+                limit_price,
+                trailing_offset,
             } => trade::submit_conditional_order(
                 ctx,
                 pair_id,
@@ -188,17 +250,57 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
                 trigger_price,
                 trigger_direction,
                 max_slippage,
+                limit_price,
+                trailing_offset,
             ),
             TraderMsg::CancelConditionalOrder(CancelConditionalOrderRequest::One {
                 pair_id,
                 trigger_direction,
-            }) => trade::cancel_one_conditional_order(ctx, pair_id, trigger_direction),
+                order_id,
+            }) => trade::cancel_one_conditional_order(ctx, pair_id, trigger_direction, order_id),
             TraderMsg::CancelConditionalOrder(CancelConditionalOrderRequest::AllForPair {
                 pair_id,
             }) => trade::cancel_conditional_orders_for_pair(ctx, pair_id),
             TraderMsg::CancelConditionalOrder(CancelConditionalOrderRequest::All) => {
                 trade::cancel_all_conditional_orders(ctx)
             },
+            TraderMsg::SubmitTwapOrder {
+                pair_id,
+                size,
+                duration,
+                slice_interval,
+                max_slippage,
+                reduce_only,
+            } => trade::submit_twap_order(
+                ctx,
+                pair_id,
+                size,
+                duration,
+                slice_interval,
+                max_slippage,
+                reduce_only,
+            ),
+            TraderMsg::CancelTwapOrder(CancelTwapOrderRequest::One(twap_id)) => {
+                trade::cancel_one_twap_order(ctx, twap_id)
+            },
+            TraderMsg::CancelTwapOrder(CancelTwapOrderRequest::All) => {
+                trade::cancel_all_twap_orders(ctx)
+            },
+            TraderMsg::SubmitOrderGroup { pair_id, group } => {
+                trade::submit_order_group(ctx, pair_id, group)
+            },
+            TraderMsg::CancelOrderGroup { group_id } => trade::cancel_order_group(ctx, group_id),
+            TraderMsg::CancelAllAfter { timeout } => trade::cancel_all_after(ctx, timeout),
+            TraderMsg::SetMarginMode {
+                pair_id,
+                margin_mode,
+            } => trade::set_margin_mode(ctx, pair_id, margin_mode),
+            TraderMsg::AddIsolatedMargin { pair_id, amount } => {
+                trade::add_isolated_margin(ctx, pair_id, amount)
+            },
+            TraderMsg::RemoveIsolatedMargin { pair_id, amount } => {
+                trade::remove_isolated_margin(ctx, pair_id, amount)
+            },
         },
         ExecuteMsg::Vault(msg) => match msg {
             VaultMsg::AddLiquidity {
@@ -261,6 +363,10 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query::query_user_states(ctx, start_after, limit)?;
             res.to_json_value()
         },
+        QueryMsg::SubaccountStates { user } => {
+            let res = query::query_subaccount_states(ctx, user)?;
+            res.to_json_value()
+        },
         QueryMsg::UserStateExtended {
             user,
             include_equity,
@@ -324,6 +430,26 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query::query_order_by_client_order_id(ctx, user, client_order_id)?;
             res.to_json_value()
         },
+        QueryMsg::TwapOrder { twap_id } => {
+            let res = TWAP_ORDERS.may_load(ctx.storage, twap_id)?;
+            res.to_json_value()
+        },
+        QueryMsg::TwapOrdersByUser { user } => {
+            let res = query::query_twap_orders_by_user(ctx, user)?;
+            res.to_json_value()
+        },
+        QueryMsg::OrderGroup { group_id } => {
+            let res = ORDER_GROUPS.may_load(ctx.storage, group_id)?;
+            res.to_json_value()
+        },
+        QueryMsg::OrderGroupsByUser { user } => {
+            let res = query::query_order_groups_by_user(ctx, user)?;
+            res.to_json_value()
+        },
+        QueryMsg::CancelAllDeadline { user } => {
+            let res = CANCEL_ALL_DEADLINES.may_load(ctx.storage, user)?;
+            res.to_json_value()
+        },
         QueryMsg::LiquidityDepth {
             pair_id,
             bucket_size,
@@ -344,6 +470,15 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query::query_vault_snapshots(ctx.storage, min, max)?;
             res.to_json_value()
         },
+        QueryMsg::FundingHistory {
+            pair_id,
+            min,
+            max,
+            limit,
+        } => {
+            let res = query::query_funding_history(ctx.storage, pair_id, min, max, limit)?;
+            res.to_json_value()
+        },
         QueryMsg::Referrer { referee } => {
             let res = query::query_referrer(ctx.storage, referee)?;
             res.to_json_value()
@@ -1,12 +1,14 @@
 mod configure;
+mod disable_trading;
 mod donate;
 mod liquidate;
 mod refresh_index_prices;
 mod refresh_vault_orders;
 mod set_fee_rate_override;
+mod settle_pair;
 mod withdraw_treasury;
 
 pub use {
-    configure::*, donate::*, liquidate::*, refresh_index_prices::*, refresh_vault_orders::*,
-    set_fee_rate_override::*, withdraw_treasury::*,
+    configure::*, disable_trading::*, donate::*, liquidate::*, refresh_index_prices::*,
+    refresh_vault_orders::*, set_fee_rate_override::*, settle_pair::*, withdraw_treasury::*,
 };
@@ -1,5 +1,6 @@
 use {
     crate::{
+        MAX_VAULT_LADDER_LEVELS,
         core::check_fee_sign_invariant,
         oracle,
         state::{PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM},
@@ -10,7 +11,7 @@ use {
     dango_primitives::{
         Duration, GENESIS_BLOCK_HEIGHT, GENESIS_SENDER, MutableCtx, QuerierExt, Response,
     },
-    dango_types::perps::{PairParam, PairState, Param, RateSchedule},
+    dango_types::perps::{FundingMode, PairParam, PairState, PairStatus, Param, RateSchedule},
     std::collections::BTreeMap,
 };
 
@@ -49,6 +50,19 @@ pub fn configure(
 
     for (pair_id, pair_param) in &pair_params {
         validate_pair_param(pair_id, pair_param)?;
+
+        // A delisted pair's params, in particular its settlement price, are
+        // final. Putting it back in `PAIR_IDS` would resume its funding and
+        // vault quoting.
+        if let Some(old_pair_param) = PAIR_PARAMS.may_load(ctx.storage, pair_id)? {
+            ensure!(
+                matches!(
+                    old_pair_param.status,
+                    PairStatus::Active | PairStatus::ReduceOnly
+                ),
+                "pair {pair_id} has been delisted and can't be configured"
+            );
+        }
     }
 
     validate_vault_total_weight(&param, &pair_params)?;
@@ -329,6 +343,50 @@ fn validate_pair_param(pair_id: &PairId, pair_param: &PairParam) -> anyhow::Resu
         pair_param.vault_max_skew_size,
     );
 
+    let ladder = &pair_param.vault_ladder;
+
+    ensure!(
+        (1..=MAX_VAULT_LADDER_LEVELS).contains(&ladder.levels),
+        "invalid `vault_ladder.levels`! pair id: {}, bounds: [1, {}], found: {}",
+        pair_id,
+        MAX_VAULT_LADDER_LEVELS,
+        ladder.levels,
+    );
+
+    ensure!(
+        ladder.spread_step >= Dimensionless::ZERO && ladder.spread_step < Dimensionless::ONE,
+        "invalid `vault_ladder.spread_step`! pair id: {}, bounds: [0, 1), found: {}",
+        pair_id,
+        ladder.spread_step,
+    );
+
+    ensure!(
+        ladder.levels == 1 || ladder.spread_step.is_positive(),
+        "invalid `vault_ladder.spread_step`! pair id: {}, bounds: > 0 when levels > 1, found: {}",
+        pair_id,
+        ladder.spread_step,
+    );
+
+    ensure!(
+        ladder.size_ratio > Dimensionless::ZERO && ladder.size_ratio <= Dimensionless::new_int(10),
+        "invalid `vault_ladder.size_ratio`! pair id: {}, bounds: (0, 10], found: {}",
+        pair_id,
+        ladder.size_ratio,
+    );
+
+    // Cross-field: the deepest bid of the ladder sits a further
+    // `(levels - 1) * spread_step` below the top one. Same as for the top bid,
+    // its spread must stay strictly below 1 under maximum positive skew.
+    let max_ladder_spread = Dimensionless::new_int(ladder.levels as i128 - 1)
+        .checked_mul(ladder.spread_step)?
+        .checked_add(max_bid_effective_spread)?;
+    ensure!(
+        max_ladder_spread < Dimensionless::ONE,
+        "invalid `vault_ladder`! pair id: {}, bounds: vault_half_spread * (1 + vault_spread_skew_factor) + (levels - 1) * spread_step < 1, found: {}",
+        pair_id,
+        max_ladder_spread,
+    );
+
     ensure!(
         !pair_param.funding_rate_multiplier.is_negative(),
         "invalid `funding_rate_multiplier`! pair id: {}, bounds: >= 0, found: {}",
@@ -336,6 +394,18 @@ fn validate_pair_param(pair_id: &PairId, pair_param: &PairParam) -> anyhow::Resu
         pair_param.funding_rate_multiplier,
     );
 
+    if let FundingMode::PremiumIndex { interest_rate } = pair_param.funding_mode {
+        ensure!(
+            interest_rate >= -pair_param.max_abs_funding_rate
+                && interest_rate <= pair_param.max_abs_funding_rate,
+            "invalid `interest_rate`! pair id: {}, bounds: [-max_abs_funding_rate, max_abs_funding_rate] = [-{}, {}], found: {}",
+            pair_id,
+            pair_param.max_abs_funding_rate,
+            pair_param.max_abs_funding_rate,
+            interest_rate,
+        );
+    }
+
     ensure!(
         pair_param.max_limit_price_deviation > Dimensionless::ZERO
             && pair_param.max_limit_price_deviation < Dimensionless::ONE,
@@ -346,16 +416,16 @@ fn validate_pair_param(pair_id: &PairId, pair_param: &PairParam) -> anyhow::Resu
 
     // Cross-field: the band must be wide enough to admit any price the
     // vault may legitimately quote into. The vault's widest quote under
-    // maximum skew sits at oracle_price × (1 ± max_bid_effective_spread)
+    // maximum skew sits at oracle_price × (1 ± max_ladder_spread)
     // (computed above), so a user's crossing limit at that price must
     // also pass the band check. If `max_limit_price_deviation` is set
     // tighter than the vault's widest deviation, users cannot match the
     // vault at its legitimately-quoted edges.
     ensure!(
-        pair_param.max_limit_price_deviation >= max_bid_effective_spread,
-        "invalid `max_limit_price_deviation`! pair id: {}, bounds: must be >= vault_half_spread * (1 + vault_spread_skew_factor) = {}, found: {}",
+        pair_param.max_limit_price_deviation >= max_ladder_spread,
+        "invalid `max_limit_price_deviation`! pair id: {}, bounds: must be >= vault_half_spread * (1 + vault_spread_skew_factor) + (vault_ladder.levels - 1) * vault_ladder.spread_step = {}, found: {}",
         pair_id,
-        max_bid_effective_spread,
+        max_ladder_spread,
         pair_param.max_limit_price_deviation,
     );
 
@@ -376,6 +446,18 @@ fn validate_pair_param(pair_id: &PairId, pair_param: &PairParam) -> anyhow::Resu
         );
     }
 
+    // A pair is only put in `Settling` by `SettlePair`, which also carries
+    // out the settlement, and in `Delisted` once the settlement is done.
+    ensure!(
+        matches!(
+            pair_param.status,
+            PairStatus::Active | PairStatus::ReduceOnly
+        ),
+        "invalid `status`! pair id: {}, bounds: active or reduce-only, found: {:?}",
+        pair_id,
+        pair_param.status,
+    );
+
     Ok(())
 }
 
@@ -444,6 +526,7 @@ mod tests {
         dango_math::Number as _,
         dango_order_book::{FundingRate, Quantity},
         dango_primitives::{Duration, btree_map, btree_set},
+        dango_types::perps::VaultLadder,
     };
 
     /// A `Param` that passes validation. Individual tests mutate one field to
@@ -487,8 +570,11 @@ mod tests {
             vault_size_skew_factor: Dimensionless::ZERO,
             vault_spread_skew_factor: Dimensionless::ZERO,
             vault_max_skew_size: Quantity::ZERO,
+            vault_ladder: VaultLadder::default(),
             funding_rate_multiplier: Dimensionless::ONE,
+            funding_mode: FundingMode::VaultSkew,
             bucket_sizes: btree_set! {},
+            status: PairStatus::Active,
         }
     }
 
@@ -866,6 +952,31 @@ mod tests {
         validate_pair_param(&pair(), &p).unwrap();
     }
 
+    #[test]
+    fn pair_param_premium_index_interest_rate_within_bounds_accepted() {
+        let p = PairParam {
+            max_abs_funding_rate: FundingRate::new_permille(50),
+            funding_mode: FundingMode::PremiumIndex {
+                interest_rate: FundingRate::new_permille(-50),
+            },
+            ..valid_pair_param()
+        };
+        validate_pair_param(&pair(), &p).unwrap();
+    }
+
+    #[test]
+    fn pair_param_premium_index_interest_rate_above_cap_rejected() {
+        let p = PairParam {
+            max_abs_funding_rate: FundingRate::new_permille(50),
+            funding_mode: FundingMode::PremiumIndex {
+                interest_rate: FundingRate::new_permille(51),
+            },
+            ..valid_pair_param()
+        };
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("`interest_rate`"), "{err}");
+    }
+
     #[test]
     fn pair_param_large_funding_rate_multiplier_accepted() {
         // No upper bound on the multiplier.
@@ -1267,6 +1378,39 @@ mod tests {
         assert!(err.contains("`vault_liquidity_weight`"), "{err}");
     }
 
+    #[test]
+    fn pair_param_reduce_only_status_accepted() {
+        let p = PairParam {
+            status: PairStatus::ReduceOnly,
+            ..valid_pair_param()
+        };
+        validate_pair_param(&pair(), &p).unwrap();
+    }
+
+    #[test]
+    fn pair_param_settling_status_rejected() {
+        let p = PairParam {
+            status: PairStatus::Settling {
+                settlement_price: UsdPrice::new_int(100),
+            },
+            ..valid_pair_param()
+        };
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("`status`"), "{err}");
+    }
+
+    #[test]
+    fn pair_param_delisted_status_rejected() {
+        let p = PairParam {
+            status: PairStatus::Delisted {
+                settlement_price: UsdPrice::new_int(100),
+            },
+            ..valid_pair_param()
+        };
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("`status`"), "{err}");
+    }
+
     #[test]
     fn pair_param_negative_vault_size_skew_factor_rejected() {
         let p = PairParam {
@@ -1392,6 +1536,67 @@ mod tests {
         validate_pair_param(&pair(), &p).unwrap();
     }
 
+    // ------------------- validate_pair_param — vault_ladder ---------------------
+
+    fn ladder(levels: u32, spread_step: Dimensionless, size_ratio: Dimensionless) -> PairParam {
+        PairParam {
+            vault_ladder: VaultLadder {
+                levels,
+                spread_step,
+                size_ratio,
+            },
+            ..valid_pair_param()
+        }
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_accepted() {
+        // Deepest level at 1% + 4 * 1% = 5%, within the 10% band.
+        let p = ladder(5, Dimensionless::new_permille(10), Dimensionless::ONE);
+        validate_pair_param(&pair(), &p).unwrap();
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_levels_out_of_bounds_rejected() {
+        for levels in [0, MAX_VAULT_LADDER_LEVELS + 1] {
+            let p = ladder(levels, Dimensionless::new_permille(1), Dimensionless::ONE);
+            let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+            assert!(err.contains("`vault_ladder.levels`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_zero_spread_step_with_multiple_levels_rejected() {
+        let p = ladder(3, Dimensionless::ZERO, Dimensionless::ONE);
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("`vault_ladder.spread_step`"), "{err}");
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_size_ratio_out_of_bounds_rejected() {
+        for size_ratio in [Dimensionless::ZERO, Dimensionless::new_int(11)] {
+            let p = ladder(3, Dimensionless::new_permille(1), size_ratio);
+            let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+            assert!(err.contains("`vault_ladder.size_ratio`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_deepest_level_non_positive_bid_rejected() {
+        // 1% + 9 * 15% = 136% — the deepest bid would be negative.
+        let p = ladder(10, Dimensionless::new_permille(150), Dimensionless::ONE);
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("invalid `vault_ladder`!"), "{err}");
+    }
+
+    #[test]
+    fn pair_param_vault_ladder_outside_price_band_rejected() {
+        // Deepest level at 1% + 4 * 3% = 13%, outside the 10% band.
+        let p = ladder(5, Dimensionless::new_permille(30), Dimensionless::ONE);
+        let err = validate_pair_param(&pair(), &p).unwrap_err().to_string();
+        assert!(err.contains("`max_limit_price_deviation`"), "{err}");
+    }
+
     // ------------------- validate_vault_total_weight ----------------------------
 
     #[test]
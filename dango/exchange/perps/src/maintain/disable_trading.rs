@@ -0,0 +1,105 @@
+use {
+    crate::state::PARAM,
+    anyhow::ensure,
+    dango_primitives::{MutableCtx, QuerierExt, Response},
+};
+
+/// Disable trading globally, leaving all other parameters unchanged.
+///
+/// Only callable by the chain owner. Idempotent: disabling trading when it's
+/// already disabled is a no-op.
+///
+/// Mutates: `PARAM` (`trading_enabled` set to false).
+pub fn disable_trading(ctx: MutableCtx) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can disable trading"
+    );
+
+    let mut param = PARAM.load(ctx.storage)?;
+    param.trading_enabled = false;
+    PARAM.save(ctx.storage, &param)?;
+
+    Ok(Response::new())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_primitives::{
+            Addr, Coins, Config, Duration, MockContext, MockQuerier, Permission, Permissions,
+            ResultExt, Storage,
+        },
+        dango_types::perps::Param,
+        std::collections::BTreeMap,
+    };
+
+    const OWNER: Addr = Addr::mock(0);
+    const NON_OWNER: Addr = Addr::mock(1);
+
+    fn mock_config() -> Config {
+        Config {
+            owner: OWNER,
+            bank: Addr::mock(2),
+            gas_token: dango_primitives::Denom::new_unchecked(["ugas"]),
+            gas_fee_rate: Default::default(),
+            gas_exemptions: Default::default(),
+            cronjobs: BTreeMap::new(),
+            permissions: Permissions {
+                upload: Permission::Nobody,
+                instantiate: Permission::Nobody,
+            },
+            max_orphan_age: Duration::from_seconds(0),
+        }
+    }
+
+    fn seed_param(storage: &mut dyn Storage) {
+        PARAM
+            .save(
+                storage,
+                &Param {
+                    max_open_orders: 10,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn non_owner_rejected() {
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(NON_OWNER)
+            .with_funds(Coins::default());
+        seed_param(&mut ctx.storage);
+
+        disable_trading(ctx.as_mutable())
+            .should_fail_with_error("only the chain owner can disable trading");
+
+        assert!(PARAM.load(&ctx.storage).unwrap().trading_enabled);
+    }
+
+    #[test]
+    fn owner_disables_trading() {
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(OWNER)
+            .with_funds(Coins::default());
+        seed_param(&mut ctx.storage);
+
+        disable_trading(ctx.as_mutable()).should_succeed();
+
+        let param = PARAM.load(&ctx.storage).unwrap();
+        assert!(!param.trading_enabled);
+        // Other parameters are left untouched.
+        assert_eq!(param.max_open_orders, 10);
+
+        // Disabling again is a no-op.
+        disable_trading(ctx.as_mutable()).should_succeed();
+
+        assert!(!PARAM.load(&ctx.storage).unwrap().trading_enabled);
+    }
+}
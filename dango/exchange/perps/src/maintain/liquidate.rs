@@ -1,9 +1,11 @@
 use {
     crate::{
         core::{
-            compute_bankruptcy_price, compute_close_schedule, compute_maintenance_margin,
-            compute_user_equity, compute_user_equity_with_pnl, is_liquidatable,
+            MakerOrderUpdate, compute_bankruptcy_price, compute_close_schedule,
+            compute_isolated_view, compute_maintenance_margin, compute_user_equity,
+            compute_user_equity_with_pnl, is_liquidatable, release_isolated_margin,
         },
+        order_group,
         position_index::{
             PositionIndexUpdate, apply_position_index_updates, compute_position_diff,
         },
@@ -11,24 +13,26 @@ use {
         state::{LONGS, PAIR_PARAMS, PAIR_STATES, PARAM, SHORTS, STATE, USER_STATES},
         trade::{
             CancelAllOrdersOutcome, FeeBreakdown, MatchOrderOutcome,
-            compute_cancel_all_orders_outcome, match_order, merge_fee_breakdown,
-            resize_reduce_only_orders, settle_fill, settle_pnls,
+            compute_cancel_all_orders_outcome, compute_cancel_pair_orders_outcome, match_order,
+            merge_fee_breakdown, remove_twap_orders, resize_reduce_only_orders, settle_fill,
+            settle_pnls,
         },
     },
     anyhow::ensure,
     dango_math::NumberConst,
     dango_order_book::{
         ASKS, BIDS, ConditionalOrderRemoved, Dimensionless, FillId, LimitOrder, NEXT_FILL_ID,
-        NEXT_ORDER_ID, OrderId, PairId, Quantity, ReasonForOrderRemoval, TriggerDirection,
-        UsdPrice, UsdValue, decrease_liquidity_depths, flush_volumes, increase_liquidity_depths,
-        may_invert_price,
+        NEXT_ORDER_ID, OrderId, PairId, Quantity, ReasonForOrderRemoval, SelfTradePreventionMode,
+        TriggerDirection, UsdPrice, UsdValue, decrease_liquidity_depths, flush_volumes,
+        increase_liquidity_depths, may_invert_price,
     },
     dango_primitives::{
-        Addr, EventBuilder, MutableCtx, Order as IterationOrder, Response, Storage, Timestamp,
+        Addr, EventBuilder, MutableCtx, Order as IterationOrder, Response, StdResult, Storage,
+        Timestamp,
     },
     dango_types::perps::{
-        BadDebtCovered, Deleveraged, Liquidated, PairParam, PairState, Param, RateSchedule, State,
-        UserState,
+        BadDebtCovered, Deleveraged, Liquidated, PairParam, PairState, Param, Position,
+        RateSchedule, ReasonForTwapRemoval, State, UserState,
     },
     std::collections::BTreeMap,
 };
@@ -38,6 +42,9 @@ use {
 /// Unfilled positions are ADL'd against counter-parties at the bankruptcy price.
 /// Any remaining bad debt is absorbed by the insurance fund.
 ///
+/// Only cross-margin positions are considered. Isolated positions and their
+/// margins are left untouched; see [`liquidate_isolated`].
+///
 /// Mutates: `STATE`, `PAIR_STATES`, `USER_STATES` (liquidated user + makers +
 /// ADL counter-parties), `LONGS`, `SHORTS`.
 ///
@@ -57,6 +64,8 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
 
     // -------------------- 2. Cancel all resting orders -----------------------
 
+    // This includes orders in isolated pairs: the margin they reserve is drawn
+    // from the cross margin.
     let CancelAllOrdersOutcome { mut user_state } = compute_cancel_all_orders_outcome(
         ctx.storage,
         user,
@@ -65,43 +74,159 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         ReasonForOrderRemoval::Liquidated,
     )?;
 
-    // Cancel all embedded conditional orders. Positions may survive partial
-    // liquidation, so we must explicitly clear the fields.
-    for (pair_id, position) in &mut user_state.positions {
-        if position.conditional_order_above.take().is_some() {
-            events.push(ConditionalOrderRemoved {
-                pair_id: pair_id.clone(),
-                user,
-                trigger_direction: TriggerDirection::Above,
-                reason: ReasonForOrderRemoval::Liquidated,
-            })?;
-        }
-        if position.conditional_order_below.take().is_some() {
-            events.push(ConditionalOrderRemoved {
-                pair_id: pair_id.clone(),
-                user,
-                trigger_direction: TriggerDirection::Below,
-                reason: ReasonForOrderRemoval::Liquidated,
-            })?;
+    // Cancel all embedded conditional orders of cross positions. Positions may
+    // survive partial liquidation, so we must explicitly clear the fields.
+    let UserState {
+        positions,
+        isolated_margins,
+        ..
+    } = &mut user_state;
+
+    for (pair_id, position) in positions {
+        if !isolated_margins.contains_key(pair_id) {
+            remove_conditional_orders(ctx.storage, user, pair_id, position, &mut events)?;
         }
     }
 
+    // Cancel the TWAP orders in cross pairs, so that the cron doesn't reopen
+    // the positions being liquidated.
+    for event in remove_twap_orders(
+        ctx.storage,
+        user,
+        |pair_id| !user_state.is_isolated(pair_id),
+        ReasonForTwapRemoval::Liquidated,
+    )? {
+        events.push(event)?;
+    }
+
     // ------------------- 3. Load pair params and states ---------------------
 
     // Collect pair IDs first to avoid borrow conflicts.
-    let pair_ids = user_state.positions.keys().cloned().collect::<Vec<_>>();
+    let pair_ids = user_state
+        .cross_positions()
+        .map(|(pair_id, _)| pair_id.clone())
+        .collect::<Vec<_>>();
 
-    let mut pair_params = BTreeMap::new();
-    let mut pair_states = BTreeMap::new();
+    let (pair_params, pair_states) = load_pairs(ctx.storage, &pair_ids)?;
 
-    for pair_id in &pair_ids {
-        let pair_param = PAIR_PARAMS.load(ctx.storage, pair_id)?;
-        let pair_state = PAIR_STATES.load(ctx.storage, pair_id)?;
+    // -------------------- 4. Compute oracle prices ---------------------------
 
-        pair_params.insert(pair_id.clone(), pair_param);
-        pair_states.insert(pair_id.clone(), pair_state);
+    let oracle_prices = pair_states
+        .iter()
+        .map(|(pair_id, pair_state)| (pair_id.clone(), pair_state.index_price))
+        .collect::<BTreeMap<_, _>>();
+
+    // --------------------------- 5. Business logic ---------------------------
+
+    let outcome = _liquidate(
+        ctx.storage,
+        user,
+        ctx.contract,
+        ctx.block.timestamp,
+        &param,
+        &state,
+        &pair_params,
+        &pair_states,
+        &user_state,
+        &oracle_prices,
+        &mut events,
+    )?;
+
+    // ------------------------ 6. Apply state changes -------------------------
+
+    apply_liquidate_outcome(
+        ctx.storage,
+        ctx.block.timestamp,
+        user,
+        outcome,
+        &pair_params,
+        &mut events,
+    )?;
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(
+            %user,
+            num_positions = pair_ids.len(),
+            "Liquidation executed"
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!(crate::metrics::LABEL_LIQUIDATIONS).increment(1);
+
+        metrics::histogram!(crate::metrics::LABEL_DURATION_LIQUIDATE)
+            .record(start.elapsed().as_secs_f64());
+    }
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Liquidate a trader's isolated position in the given pair, once its isolated
+/// margin plus unrealized PnL has fallen below its maintenance margin.
+///
+/// Runs the same close schedule, ADL, liquidation fee, and bad debt logic as
+/// [`liquidate`], but against the position's isolated view (see
+/// `compute_isolated_view`): only the isolated margin is at stake. The user's
+/// cross margin, other positions, and resting orders in other pairs are left
+/// untouched. If the position is fully closed, whatever is left of the
+/// isolated margin is returned to the cross margin.
+///
+/// Mutates: `STATE`, `PAIR_STATES`, `USER_STATES` (liquidated user + makers +
+/// ADL counter-parties), `LONGS`, `SHORTS`.
+///
+/// Returns: empty `Response` (all PnL/fees settled via internal margins).
+pub fn liquidate_isolated(
+    ctx: MutableCtx,
+    user: Addr,
+    pair_id: PairId,
+) -> anyhow::Result<Response> {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
+
+    // --------------------- 1. Preparation + basic checks ---------------------
+
+    let param = PARAM.load(ctx.storage)?;
+    let state = STATE.load(ctx.storage)?;
+
+    let user_state = USER_STATES.may_load(ctx.storage, user)?.unwrap_or_default();
+
+    ensure!(
+        user_state.is_isolated(&pair_id) && user_state.positions.contains_key(&pair_id),
+        "user {user} has no isolated position in pair {pair_id}"
+    );
+
+    let mut events = EventBuilder::new();
+
+    // --------------- 2. Cancel resting orders in this pair only --------------
+
+    let CancelAllOrdersOutcome { mut user_state } = compute_cancel_pair_orders_outcome(
+        ctx.storage,
+        user,
+        &pair_id,
+        &user_state,
+        Some(&mut events),
+        ReasonForOrderRemoval::Liquidated,
+    )?;
+
+    if let Some(position) = user_state.positions.get_mut(&pair_id) {
+        remove_conditional_orders(ctx.storage, user, &pair_id, position, &mut events)?;
+    }
+
+    for event in remove_twap_orders(
+        ctx.storage,
+        user,
+        |twap_pair_id| twap_pair_id == &pair_id,
+        ReasonForTwapRemoval::Liquidated,
+    )? {
+        events.push(event)?;
     }
 
+    // ------------------- 3. Load pair params and states ---------------------
+
+    let (pair_params, pair_states) = load_pairs(ctx.storage, std::slice::from_ref(&pair_id))?;
+
     // -------------------- 4. Compute oracle prices ---------------------------
 
     let oracle_prices = pair_states
@@ -111,17 +236,7 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
 
     // --------------------------- 5. Business logic ---------------------------
 
-    let LiquidateOutcome {
-        state,
-        pair_states,
-        user_state,
-        maker_states,
-        order_mutations,
-        index_updates,
-        volumes,
-        next_order_id,
-        next_fill_id,
-    } = _liquidate(
+    let mut outcome = _liquidate(
         ctx.storage,
         user,
         ctx.contract,
@@ -130,39 +245,158 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         &state,
         &pair_params,
         &pair_states,
-        &user_state,
+        &compute_isolated_view(&user_state, &pair_id),
         &oracle_prices,
         &mut events,
     )?;
 
-    // --------------------- 6. Apply state changes ----------------------------
+    // Merge the liquidated isolated view back into the user's state.
+    match outcome.user_state.positions.remove(&pair_id) {
+        Some(position) => {
+            user_state.positions.insert(pair_id.clone(), position);
+        },
+        None => {
+            user_state.positions.remove(&pair_id);
+        },
+    }
+
+    user_state
+        .isolated_margins
+        .insert(pair_id.clone(), outcome.user_state.margin);
+
+    release_isolated_margin(&mut user_state, &pair_id)?;
+
+    outcome.user_state = user_state;
+
+    // ------------------------ 6. Apply state changes -------------------------
+
+    apply_liquidate_outcome(
+        ctx.storage,
+        ctx.block.timestamp,
+        user,
+        outcome,
+        &pair_params,
+        &mut events,
+    )?;
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(
+            %user,
+            %pair_id,
+            "Isolated liquidation executed"
+        );
+    }
 
-    flush_volumes(ctx.storage, ctx.block.timestamp, &volumes)?;
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!(crate::metrics::LABEL_LIQUIDATIONS).increment(1);
 
-    STATE.save(ctx.storage, &state)?;
+        metrics::histogram!(crate::metrics::LABEL_DURATION_LIQUIDATE)
+            .record(start.elapsed().as_secs_f64());
+    }
 
-    NEXT_ORDER_ID.save(ctx.storage, &next_order_id)?;
-    NEXT_FILL_ID.save(ctx.storage, &next_fill_id)?;
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Remove all conditional orders embedded in a position being liquidated.
+fn remove_conditional_orders(
+    storage: &mut dyn Storage,
+    user: Addr,
+    pair_id: &PairId,
+    position: &mut Position,
+    events: &mut EventBuilder,
+) -> StdResult<()> {
+    for trigger_direction in [TriggerDirection::Above, TriggerDirection::Below] {
+        for order_id in
+            std::mem::take(position.conditional_orders_mut(trigger_direction)).into_keys()
+        {
+            events.push(ConditionalOrderRemoved {
+                order_id,
+                pair_id: pair_id.clone(),
+                user,
+                trigger_direction,
+                reason: ReasonForOrderRemoval::Liquidated,
+            })?;
+
+            order_group::on_order_removed(storage, order_id, Some(events))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the params and states of the given pairs.
+fn load_pairs(
+    storage: &dyn Storage,
+    pair_ids: &[PairId],
+) -> StdResult<(BTreeMap<PairId, PairParam>, BTreeMap<PairId, PairState>)> {
+    let mut pair_params = BTreeMap::new();
+    let mut pair_states = BTreeMap::new();
+
+    for pair_id in pair_ids {
+        let pair_param = PAIR_PARAMS.load(storage, pair_id)?;
+        let pair_state = PAIR_STATES.load(storage, pair_id)?;
+
+        pair_params.insert(pair_id.clone(), pair_param);
+        pair_states.insert(pair_id.clone(), pair_state);
+    }
+
+    Ok((pair_params, pair_states))
+}
+
+/// Persist the outcome of a `_liquidate` call: dense states, order book
+/// mutations, position indexes, and the re-sizing of the affected makers'
+/// reduce-only orders.
+fn apply_liquidate_outcome(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    user: Addr,
+    outcome: LiquidateOutcome,
+    pair_params: &BTreeMap<PairId, PairParam>,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let LiquidateOutcome {
+        state,
+        pair_states,
+        user_state,
+        maker_states,
+        order_mutations,
+        maker_order_updates,
+        index_updates,
+        volumes,
+        next_order_id,
+        next_fill_id,
+    } = outcome;
+
+    // -------------------------- Dense state ----------------------------------
+
+    flush_volumes(storage, current_time, &volumes)?;
+
+    STATE.save(storage, &state)?;
+
+    NEXT_ORDER_ID.save(storage, &next_order_id)?;
+    NEXT_FILL_ID.save(storage, &next_fill_id)?;
 
     for (pair_id, pair_state) in &pair_states {
-        PAIR_STATES.save(ctx.storage, pair_id, pair_state)?;
+        PAIR_STATES.save(storage, pair_id, pair_state)?;
     }
 
     if user_state.is_empty() {
-        USER_STATES.remove(ctx.storage, user)?;
+        USER_STATES.remove(storage, user)?;
     } else {
-        USER_STATES.save(ctx.storage, user, &user_state)?;
+        USER_STATES.save(storage, user, &user_state)?;
     }
 
     for (addr, maker_state) in &maker_states {
         if maker_state.is_empty() {
-            USER_STATES.remove(ctx.storage, *addr)?;
+            USER_STATES.remove(storage, *addr)?;
         } else {
-            USER_STATES.save(ctx.storage, *addr, maker_state)?;
+            USER_STATES.save(storage, *addr, maker_state)?;
         }
     }
 
-    // -------------------- 7. Apply order mutations ---------------------------
+    // ------------------------ Order mutations --------------------------------
 
     for (pair_id, taker_is_bid, stored_price, order_id, mutation, pre_fill_abs_size) in
         order_mutations
@@ -184,7 +418,7 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         // Complete remove the order's liquidity depth contribution, and re-add
         // the remaining size (if any) to prevent notional drift.
         decrease_liquidity_depths(
-            ctx.storage,
+            storage,
             &pair_id,
             maker_is_bid,
             real_price,
@@ -195,7 +429,7 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         match mutation {
             Some(order) => {
                 increase_liquidity_depths(
-                    ctx.storage,
+                    storage,
                     &pair_id,
                     maker_is_bid,
                     real_price,
@@ -203,51 +437,43 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
                     &pair_param.bucket_sizes,
                 )?;
 
-                maker_book.save(ctx.storage, order_key, &order)?;
+                maker_book.save(storage, order_key, &order)?;
             },
             None => {
-                maker_book.remove(ctx.storage, order_key)?;
+                maker_book.remove(storage, order_key)?;
             },
         }
     }
 
-    // -------------------- 8. Apply position index updates --------------------
+    // --------------------- Position index updates ----------------------------
+
+    apply_position_index_updates(storage, &index_updates)?;
+
+    // ------------------------- Order groups ----------------------------------
 
-    apply_position_index_updates(ctx.storage, &index_updates)?;
+    order_group::on_maker_orders_updated(storage, &maker_order_updates, events)?;
+    order_group::on_positions_updated(storage, &index_updates, events)?;
 
-    // ---------- 9. Dynamic re-size of reduce-only orders ---------------------
+    // ---------------- Dynamic re-size of reduce-only orders ------------------
     //
     // Liquidation forcibly reduced the positions of book-fill makers and ADL
     // counter-parties (the liquidated user's own orders were already cancelled
-    // in step 2), so each of their resting reduce-only orders is re-clamped to
+    // beforehand), so each of their resting reduce-only orders is re-clamped to
     // its new position. The affected users are the keys of `maker_states`; the
-    // pairs are those the liquidated user held (`pair_ids`), the only pairs a
-    // fill could have touched. Over-scanning (a maker not on a given pair) is a
-    // safe no-op. We iterate the maps rather than the `index_updates`, because a
-    // pure ADL reduction leaves `(entry_price, side)` — hence the index —
-    // unchanged, which is exactly the case re-sizing must catch.
-    for pair_id in &pair_ids {
+    // pairs are those the liquidation closed (the keys of `pair_params`), the
+    // only pairs a fill could have touched. Over-scanning (a maker not on a
+    // given pair) is a safe no-op. We iterate the maps rather than the
+    // `index_updates`, because a pure ADL reduction leaves
+    // `(entry_price, side)` — hence the index — unchanged, which is exactly the
+    // case re-sizing must catch.
+    for pair_id in pair_params.keys() {
         for maker in maker_states.keys() {
-            resize_reduce_only_orders(ctx.storage, *maker, pair_id, &mut events)?;
+            resize_reduce_only_orders(storage, *maker, pair_id, events)?;
         }
     }
 
-    #[cfg(feature = "tracing")]
-    {
-        tracing::info!(
-            %user,
-            num_positions = pair_ids.len(),
-            "Liquidation executed"
-        );
-    }
-
     #[cfg(feature = "metrics")]
     {
-        metrics::counter!(crate::metrics::LABEL_LIQUIDATIONS).increment(1);
-
-        metrics::histogram!(crate::metrics::LABEL_DURATION_LIQUIDATE)
-            .record(start.elapsed().as_secs_f64());
-
         // OI gauges are updated per pair after liquidation.
         for (pair_id, pair_state) in &pair_states {
             let pair_label = pair_id.to_string();
@@ -266,7 +492,7 @@ pub fn liquidate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         }
     }
 
-    Ok(Response::new().add_events(events)?)
+    Ok(())
 }
 
 /// Owned outcome of a `_liquidate` call. Returns every piece of
@@ -288,6 +514,7 @@ pub struct LiquidateOutcome {
         Option<LimitOrder>,
         Quantity,
     )>,
+    pub maker_order_updates: Vec<(OrderId, MakerOrderUpdate)>,
     pub index_updates: Vec<PositionIndexUpdate>,
     pub volumes: BTreeMap<Addr, UsdValue>,
     pub next_order_id: OrderId,
@@ -364,6 +591,7 @@ fn _liquidate(
         updated_state,
         _all_fee_breakdowns,
         all_order_mutations,
+        all_maker_order_updates,
         closed_notional,
         all_index_updates,
         all_volumes,
@@ -466,6 +694,7 @@ fn _liquidate(
         user_state,
         maker_states: all_maker_states,
         order_mutations: all_order_mutations,
+        maker_order_updates: all_maker_order_updates,
         index_updates: all_index_updates,
         volumes: all_volumes,
         next_order_id,
@@ -481,6 +710,7 @@ fn _liquidate(
 ///
 /// This is a leaf helper private to `_liquidate` and keeps `&mut` parameters
 /// by design; it is not part of the pure set.
+#[allow(clippy::type_complexity)]
 fn execute_close_schedule(
     storage: &dyn Storage,
     user: Addr,
@@ -506,6 +736,7 @@ fn execute_close_schedule(
         Option<LimitOrder>,
         Quantity,
     )>,
+    Vec<(OrderId, MakerOrderUpdate)>,
     UsdValue,
     Vec<PositionIndexUpdate>,
     BTreeMap<Addr, UsdValue>,
@@ -526,6 +757,7 @@ fn execute_close_schedule(
     let mut all_fee_breakdowns = BTreeMap::<Addr, FeeBreakdown>::new();
     let mut all_volumes = BTreeMap::<_, UsdValue>::new();
     let mut all_order_mutations = Vec::new();
+    let mut all_maker_order_updates = Vec::new();
     let mut closed_notional = UsdValue::ZERO;
     let mut all_index_updates = Vec::new();
 
@@ -597,15 +829,18 @@ fn execute_close_schedule(
             taker_state: updated_user_state,
             maker_states: updated_maker_states,
             unfilled,
+            self_trade_canceled: _,
             volumes,
             fee_breakdowns,
             order_mutations,
+            maker_order_updates,
             index_updates,
             next_order_id: updated_next_order_id,
             next_fill_id: updated_next_fill_id,
         } = match_order(
             storage,
             user,
+            SelfTradePreventionMode::ExpireMaker,
             contract,
             current_time,
             &liq_param,
@@ -622,6 +857,7 @@ fn execute_close_schedule(
             target_price,
             oracle_price,
             pair_params.get(pair_id).unwrap().max_limit_price_deviation,
+            pair_params.get(pair_id).unwrap().initial_margin_ratio,
             *close_size,
             next_order_id,
             next_fill_id,
@@ -661,6 +897,7 @@ fn execute_close_schedule(
             ));
         }
 
+        all_maker_order_updates.extend(maker_order_updates);
         all_index_updates.extend(index_updates);
 
         // Track closed notional for fee calculation.
@@ -750,6 +987,7 @@ fn execute_close_schedule(
         state,
         all_fee_breakdowns,
         all_order_mutations,
+        all_maker_order_updates,
         closed_notional,
         all_index_updates,
         all_volumes,
@@ -858,6 +1096,9 @@ fn execute_adl(
             user_close,
             bankruptcy_price,
             Dimensionless::ZERO,
+            // ADL fills only ever reduce positions, so there is no opening
+            // portion to allocate isolated margin for.
+            Dimensionless::ZERO,
             None,
         )?;
 
@@ -888,6 +1129,7 @@ fn execute_adl(
             user_close.checked_neg()?,
             bankruptcy_price,
             Dimensionless::ZERO,
+            Dimensionless::ZERO,
             None,
         )?;
         all_volumes
@@ -924,6 +1166,7 @@ fn execute_adl(
                 contract,
                 param,
                 state,
+                pair_id,
                 user,
                 user_state,
                 user_settlement.pnl.total()?,
@@ -1073,8 +1316,8 @@ mod tests {
                 size: Quantity::new_int(size),
                 entry_price: UsdPrice::new_int(entry_price),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
 
@@ -2451,15 +2694,17 @@ mod tests {
 
         // TP → Above for long.
         let above = pos
-            .conditional_order_above
-            .as_ref()
+            .conditional_orders_above
+            .values()
+            .next()
             .expect("TP should be set");
         assert_eq!(above.trigger_price, UsdPrice::new_int(55_000));
 
         // SL → Below for long.
         let below = pos
-            .conditional_order_below
-            .as_ref()
+            .conditional_orders_below
+            .values()
+            .next()
             .expect("SL should be set");
         assert_eq!(below.trigger_price, UsdPrice::new_int(40_000));
 
@@ -2633,4 +2878,75 @@ mod tests {
              without={remaining_without_buffer}, with={remaining_with_buffer}"
         );
     }
+
+    /// An underwater isolated position is liquidated against its isolated
+    /// margin alone: the loss beyond that margin is bad debt for the insurance
+    /// fund, and the user's cross margin is left untouched.
+    #[test]
+    fn isolated_liquidation_leaves_cross_margin_untouched() {
+        let mut ctx = MockContext::new()
+            .with_contract(CONTRACT)
+            .with_sender(COUNTER)
+            .with_funds(Coins::default());
+
+        let param = Param {
+            liquidation_fee_rate: Dimensionless::ZERO,
+            ..default_param()
+        };
+        let pair_state = PairState {
+            long_oi: Quantity::new_int(1),
+            index_price: UsdPrice::new_int(47_500),
+            ..Default::default()
+        };
+
+        setup_storage(
+            &mut ctx.storage,
+            &param,
+            &[(pair_btc(), btc_pair_param(), pair_state)],
+        );
+
+        // USER is long 1 BTC @ $50,000 in isolated-margin mode with $100 of
+        // isolated margin. Oracle at $47,500 → isolated equity = -$2,400.
+        // The cross margin of $10,000 would easily cover the loss.
+        save_position(&mut ctx.storage, USER, &pair_btc(), 1, 50_000);
+
+        let mut user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        user_state.margin = UsdValue::new_int(10_000);
+        user_state
+            .isolated_margins
+            .insert(pair_btc(), UsdValue::new_int(100));
+        USER_STATES
+            .save(&mut ctx.storage, USER, &user_state)
+            .unwrap();
+
+        let maker_state = UserState {
+            margin: UsdValue::new_int(100_000),
+            open_order_count: 1,
+            ..Default::default()
+        };
+        USER_STATES
+            .save(&mut ctx.storage, MAKER, &maker_state)
+            .unwrap();
+        save_bid(&mut ctx.storage, &pair_btc(), 1, MAKER, 1, 47_500);
+
+        // The cross account holds no cross positions, so it isn't liquidatable.
+        assert!(liquidate(ctx.as_mutable(), USER).is_err());
+
+        liquidate_isolated(ctx.as_mutable(), USER, pair_btc()).unwrap();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+
+        // Position closed; the pair stays in isolated-margin mode with nothing
+        // left in it, and the cross margin is unchanged.
+        assert!(user_state.positions.is_empty());
+        assert_eq!(user_state.isolated_margins[&pair_btc()], UsdValue::ZERO);
+        assert_eq!(user_state.margin, UsdValue::new_int(10_000));
+
+        // The bad debt was absorbed by the insurance fund instead.
+        let state = STATE.load(&ctx.storage).unwrap();
+        assert!(state.insurance_fund.is_negative());
+
+        let maker_state = USER_STATES.load(&ctx.storage, MAKER).unwrap();
+        assert!(maker_state.positions.contains_key(&pair_btc()));
+    }
 }
@@ -12,6 +12,7 @@ use {
         increase_liquidity_depths, may_invert_price,
     },
     dango_primitives::{MutableCtx, Order as IterationOrder, QuerierExt, Response},
+    dango_types::perps::{VaultOrder, VaultOrdersRefreshed},
 };
 
 /// Entry point for vault market-making, triggered at the beginning of each
@@ -19,12 +20,13 @@ use {
 ///
 /// 1. Cancels all existing vault orders.
 /// 2. Computes available margin for the vault.
-/// 3. For each trading pair, places fresh bid/ask limit orders based on the
-///    oracle price and the pair's market-making parameters.
+/// 3. For each trading pair, places a fresh ladder of bid/ask limit orders
+///    based on the oracle price and the pair's market-making parameters.
 ///
 /// Mutates: `USER_STATES[contract]`, `BIDS`, `ASKS`, `NEXT_ORDER_ID`.
 ///
-/// Returns: empty `Response` (no token transfers).
+/// Returns: `Response` with a single `VaultOrdersRefreshed` event listing the
+/// newly placed orders (no token transfers).
 pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
     #[cfg(feature = "metrics")]
     let start = std::time::Instant::now();
@@ -84,12 +86,13 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
 
         LAST_VAULT_ORDERS_UPDATE.save(ctx.storage, &ctx.block.height)?;
 
-        return Ok(Response::new());
+        return Ok(Response::new().add_event(VaultOrdersRefreshed { orders: Vec::new() })?);
     }
 
     // ----------- Step 3: Iterate each pair and place vault orders ------------
 
     let mut next_order_id = NEXT_ORDER_ID.load(ctx.storage)?;
+    let mut orders = Vec::new();
 
     for pair_id in &pair_ids {
         let pair_param = PAIR_PARAMS.load(ctx.storage, pair_id)?;
@@ -128,8 +131,8 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
             .map(|p| p.size)
             .unwrap_or(Quantity::ZERO);
 
-        // Compute vault quotes with inventory skew.
-        let (bid, ask) = compute_vault_quotes(
+        // Compute vault quote ladders with inventory skew.
+        let (bids, asks) = compute_vault_quotes(
             oracle_price,
             &pair_param,
             best_bid,
@@ -138,8 +141,8 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
             position_size,
         )?;
 
-        // Place bid order.
-        if let Some(bid_quote) = bid {
+        // Place bid orders.
+        for bid_quote in bids {
             let stored_price = may_invert_price(bid_quote.price, true);
             let order = LimitOrder {
                 user: ctx.contract,
@@ -167,12 +170,19 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
                 &pair_param.bucket_sizes,
             )?;
 
+            orders.push(VaultOrder {
+                order_id: next_order_id,
+                pair_id: pair_id.clone(),
+                limit_price: bid_quote.price,
+                size: bid_quote.size,
+            });
+
             vault_state.open_order_count += 1;
             next_order_id.checked_add_assign(Uint64::ONE)?;
         }
 
-        // Place ask order.
-        if let Some(ask_quote) = ask {
+        // Place ask orders.
+        for ask_quote in asks {
             let order = LimitOrder {
                 user: ctx.contract,
                 size: ask_quote.size,
@@ -199,6 +209,13 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
                 &pair_param.bucket_sizes,
             )?;
 
+            orders.push(VaultOrder {
+                order_id: next_order_id,
+                pair_id: pair_id.clone(),
+                limit_price: ask_quote.price,
+                size: ask_quote.size,
+            });
+
             vault_state.open_order_count += 1;
             next_order_id.checked_add_assign(Uint64::ONE)?;
         }
@@ -231,7 +248,7 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
             .record(start.elapsed().as_secs_f64());
     }
 
-    Ok(Response::new())
+    Ok(Response::new().add_event(VaultOrdersRefreshed { orders })?)
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -240,12 +257,16 @@ pub fn refresh_vault_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
 mod tests {
     use {
         super::*,
+        dango_order_book::{Dimensionless, PairId, UsdPrice},
         dango_primitives::{
-            Addr, Coins, Config, Duration, MockContext, MockQuerier, Permission, Permissions,
-            ResultExt,
+            Addr, Coins, Config, Duration, EventName, JsonDeExt, MockContext, MockQuerier,
+            Permission, Permissions, ResultExt,
         },
-        dango_types::config::AppConfig,
-        std::collections::BTreeMap,
+        dango_types::{
+            config::AppConfig,
+            perps::{PairParam, PairState, Param, UserState, VaultLadder},
+        },
+        std::collections::{BTreeMap, BTreeSet},
     };
 
     const CONTRACT: Addr = Addr::mock(0);
@@ -345,4 +366,152 @@ mod tests {
         refresh_vault_orders(ctx.as_mutable())
             .should_fail_with_error("vault orders already updated this block");
     }
+
+    /// A refresh that places no quotes still reports the cancellation of the
+    /// vault's previous orders, as a `VaultOrdersRefreshed` with no orders.
+    #[test]
+    fn emits_vault_orders_refreshed_without_quotes() {
+        let mut ctx = MockContext::new()
+            .with_querier(mock_querier())
+            .with_contract(CONTRACT)
+            .with_sender(CONTRACT)
+            .with_funds(Coins::default())
+            .with_block_height(10);
+
+        PARAM.save(&mut ctx.storage, &Default::default()).unwrap();
+        PAIR_IDS
+            .save(&mut ctx.storage, &Default::default())
+            .unwrap();
+
+        let response = refresh_vault_orders(ctx.as_mutable()).unwrap();
+        let event = response
+            .subevents
+            .iter()
+            .find(|e| e.ty == VaultOrdersRefreshed::EVENT_NAME)
+            .expect("VaultOrdersRefreshed event missing");
+        let refreshed: VaultOrdersRefreshed = event.data.clone().deserialize_json().unwrap();
+        assert!(refreshed.orders.is_empty());
+    }
+
+    /// The vault quotes a 3-level ladder on each side, and the next refresh
+    /// cancels the whole ladder and replaces it around the new index price.
+    #[test]
+    fn places_and_replaces_ladder() {
+        let pair_id: PairId = "perp/btcusd".parse().unwrap();
+
+        let mut ctx = MockContext::new()
+            .with_querier(mock_querier())
+            .with_contract(CONTRACT)
+            .with_sender(CONTRACT)
+            .with_funds(Coins::default())
+            .with_block_height(10);
+
+        PARAM
+            .save(
+                &mut ctx.storage,
+                &Param {
+                    vault_total_weight: Dimensionless::ONE,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        PAIR_IDS
+            .save(&mut ctx.storage, &BTreeSet::from([pair_id.clone()]))
+            .unwrap();
+        PAIR_PARAMS
+            .save(
+                &mut ctx.storage,
+                &pair_id,
+                &PairParam {
+                    tick_size: UsdPrice::new_int(1),
+                    initial_margin_ratio: Dimensionless::new_permille(100),
+                    vault_liquidity_weight: Dimensionless::ONE,
+                    vault_ladder: VaultLadder {
+                        levels: 3,
+                        spread_step: Dimensionless::new_permille(5),
+                        size_ratio: Dimensionless::ONE,
+                    },
+                    ..PairParam::new_mock()
+                },
+            )
+            .unwrap();
+        PAIR_STATES
+            .save(
+                &mut ctx.storage,
+                &pair_id,
+                &PairState {
+                    index_price: UsdPrice::new_int(1000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        USER_STATES
+            .save(
+                &mut ctx.storage,
+                CONTRACT,
+                &UserState {
+                    margin: UsdValue::new_int(10_000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        NEXT_ORDER_ID.save(&mut ctx.storage, &Uint64::ONE).unwrap();
+
+        let book_prices = |ctx: &MockContext<_, _, _, _, _>| {
+            let bids = BIDS
+                .prefix(pair_id.clone())
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .map(|res| may_invert_price(res.unwrap().0, true))
+                .collect::<Vec<_>>();
+            let asks = ASKS
+                .prefix(pair_id.clone())
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .map(|res| res.unwrap().0)
+                .collect::<Vec<_>>();
+            (bids, asks)
+        };
+
+        let response = refresh_vault_orders(ctx.as_mutable()).unwrap();
+        let refreshed: VaultOrdersRefreshed = response
+            .subevents
+            .iter()
+            .find(|e| e.ty == VaultOrdersRefreshed::EVENT_NAME)
+            .unwrap()
+            .data
+            .clone()
+            .deserialize_json()
+            .unwrap();
+        assert_eq!(refreshed.orders.len(), 6);
+
+        let (bids, asks) = book_prices(&ctx);
+        assert_eq!(bids, [990, 985, 980].map(UsdPrice::new_int));
+        assert_eq!(asks, [1010, 1015, 1020].map(UsdPrice::new_int));
+
+        let vault_state = USER_STATES.load(&ctx.storage, CONTRACT).unwrap();
+        assert_eq!(vault_state.open_order_count, 6);
+
+        // Next block, the index price moves up by 10%.
+        ctx.block.height = 11;
+        PAIR_STATES
+            .save(
+                &mut ctx.storage,
+                &pair_id,
+                &PairState {
+                    index_price: UsdPrice::new_int(1100),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        refresh_vault_orders(ctx.as_mutable()).should_succeed();
+
+        // The old ladder is gone entirely.
+        let (bids, asks) = book_prices(&ctx);
+        assert_eq!(bids, [1089, 1083, 1078].map(UsdPrice::new_int));
+        assert_eq!(asks, [1111, 1117, 1122].map(UsdPrice::new_int));
+
+        let vault_state = USER_STATES.load(&ctx.storage, CONTRACT).unwrap();
+        assert_eq!(vault_state.open_order_count, 6);
+        assert_eq!(NEXT_ORDER_ID.load(&ctx.storage).unwrap(), Uint64::new(13));
+    }
 }
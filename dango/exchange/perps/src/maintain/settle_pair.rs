@@ -0,0 +1,714 @@
+use {
+    crate::{
+        core::{margin_for_pair_mut, release_isolated_margin},
+        order_group,
+        position_index::{apply_position_index_updates, compute_position_diff},
+        state::{
+            LONGS, PAIR_IDS, PAIR_PARAMS, PAIR_STATES, PARAM, PREMIUM_SAMPLES, SHORTS, STATE,
+            USER_STATES,
+        },
+        trade::{compute_cancel_one_order_outcome, settle_fill, update_user_state_with},
+    },
+    anyhow::{anyhow, bail, ensure},
+    dango_order_book::{
+        ASKS, BIDS, ConditionalOrderRemoved, Dimensionless, OrderKey, PairId,
+        ReasonForOrderRemoval, TriggerDirection, UsdPrice, UsdValue,
+    },
+    dango_primitives::{
+        Addr, EventBuilder, MutableCtx, Order as IterationOrder, QuerierExt, Response, StdResult,
+        Storage,
+    },
+    dango_types::perps::{
+        BadDebtCovered, PairDelisted, PairSettlementStarted, PairState, PairStatus,
+        PositionSettled, State,
+    },
+};
+
+/// Start delisting a pair at the given final price.
+///
+/// Only callable by the chain owner. The pair must be active or reduce-only.
+///
+/// Mutates: `PAIR_PARAMS` (status set to `Settling`), `PAIR_STATES` (index
+/// price set to the settlement price), `PAIR_IDS` (pair removed), `PARAM`
+/// (the pair's weight deducted from `vault_total_weight`), `PREMIUM_SAMPLES`
+/// (the pair's samples discarded).
+pub fn settle_pair(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    settlement_price: UsdPrice,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "you don't have the right, O you don't have the right"
+    );
+
+    ensure!(
+        settlement_price.is_positive(),
+        "settlement price must be positive, found: {settlement_price}"
+    );
+
+    let mut pair_ids = PAIR_IDS.load(ctx.storage)?;
+    let mut pair_param = PAIR_PARAMS
+        .may_load(ctx.storage, &pair_id)?
+        .ok_or_else(|| anyhow!("pair not found: {pair_id}"))?;
+
+    ensure!(
+        matches!(
+            pair_param.status,
+            PairStatus::Active | PairStatus::ReduceOnly
+        ),
+        "pair {pair_id} has been delisted"
+    );
+
+    // From here on, the pair's positions are valued at the settlement price.
+    // Since the pair is removed from `PAIR_IDS`, the index price is no longer
+    // updated by the cron, and funding no longer accrues.
+    let mut pair_state = PAIR_STATES.load(ctx.storage, &pair_id)?;
+    pair_state.index_price = settlement_price;
+
+    pair_param.status = PairStatus::Settling { settlement_price };
+    pair_ids.remove(&pair_id);
+
+    // The vault no longer quotes the pair. Deduct its weight, so that the
+    // vault's margin is allocated across the remaining pairs.
+    let mut param = PARAM.load(ctx.storage)?;
+    param
+        .vault_total_weight
+        .checked_sub_assign(pair_param.vault_liquidity_weight)?;
+
+    PARAM.save(ctx.storage, &param)?;
+    PAIR_IDS.save(ctx.storage, &pair_ids)?;
+    PAIR_PARAMS.save(ctx.storage, &pair_id, &pair_param)?;
+    PAIR_STATES.save(ctx.storage, &pair_id, &pair_state)?;
+    PREMIUM_SAMPLES.remove(ctx.storage, &pair_id);
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(%pair_id, %settlement_price, "Pair settlement started");
+    }
+
+    Ok(Response::new().add_event(PairSettlementStarted {
+        pair_id,
+        settlement_price,
+    })?)
+}
+
+/// Cancel the resting orders of a settling pair, then close its positions at
+/// the settlement price, up to `limit` of either in total. Once none of either
+/// are left, mark the pair as delisted.
+///
+/// Callable by anyone, so that the settlement can't be stalled.
+///
+/// Mutates: `BIDS`, `ASKS`, `USER_STATES`, `LONGS`, `SHORTS`, `PAIR_STATES`,
+/// `STATE` (insurance fund, if there's bad debt), `PAIR_PARAMS` (status set to
+/// `Delisted` once done).
+pub fn process_pair_settlement(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    limit: u32,
+) -> anyhow::Result<Response> {
+    let mut pair_param = PAIR_PARAMS
+        .may_load(ctx.storage, &pair_id)?
+        .ok_or_else(|| anyhow!("pair not found: {pair_id}"))?;
+
+    let PairStatus::Settling { settlement_price } = pair_param.status else {
+        bail!("pair {pair_id} is not being settled");
+    };
+
+    let mut pair_state = PAIR_STATES.load(ctx.storage, &pair_id)?;
+    let mut state = STATE.load(ctx.storage)?;
+    let mut events = EventBuilder::new();
+    let mut budget = limit as usize;
+
+    // ------------------------ 1. Cancel resting orders -----------------------
+
+    let orders = BIDS
+        .prefix(pair_id.clone())
+        .range(ctx.storage, None, None, IterationOrder::Ascending)
+        .chain(ASKS.prefix(pair_id.clone()).range(
+            ctx.storage,
+            None,
+            None,
+            IterationOrder::Ascending,
+        ))
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    budget -= orders.len();
+
+    for ((stored_price, order_id), order) in orders {
+        let order_key: OrderKey = (pair_id.clone(), stored_price, order_id);
+
+        update_user_state_with(ctx.storage, order.user, |storage, user_state| {
+            compute_cancel_one_order_outcome(
+                storage,
+                user_state,
+                order_key,
+                order,
+                Some(&mut events),
+                ReasonForOrderRemoval::Delisted,
+                |_, _| Ok(pair_param.clone()),
+            )
+        })?;
+    }
+
+    // ------------------------- 2. Close positions ----------------------------
+
+    let users = LONGS
+        .prefix(pair_id.clone())
+        .keys(ctx.storage, None, None, IterationOrder::Ascending)
+        .chain(SHORTS.prefix(pair_id.clone()).keys(
+            ctx.storage,
+            None,
+            None,
+            IterationOrder::Ascending,
+        ))
+        .take(budget)
+        .map(|res| res.map(|(_, user)| user))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for user in users {
+        settle_position(
+            ctx.storage,
+            ctx.contract,
+            &pair_id,
+            &mut pair_state,
+            &mut state,
+            user,
+            settlement_price,
+            &mut events,
+        )?;
+    }
+
+    // ---------------------- 3. Mark the pair as delisted ---------------------
+
+    let done = BIDS.prefix(pair_id.clone()).is_empty(ctx.storage)
+        && ASKS.prefix(pair_id.clone()).is_empty(ctx.storage)
+        && LONGS.prefix(pair_id.clone()).is_empty(ctx.storage)
+        && SHORTS.prefix(pair_id.clone()).is_empty(ctx.storage);
+
+    if done {
+        pair_param.status = PairStatus::Delisted { settlement_price };
+
+        PAIR_PARAMS.save(ctx.storage, &pair_id, &pair_param)?;
+
+        events.push(PairDelisted {
+            pair_id: pair_id.clone(),
+            settlement_price,
+        })?;
+
+        #[cfg(feature = "tracing")]
+        {
+            tracing::info!(%pair_id, %settlement_price, "Pair delisted");
+        }
+    }
+
+    PAIR_STATES.save(ctx.storage, &pair_id, &pair_state)?;
+    STATE.save(ctx.storage, &state)?;
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Close a user's position in a settling pair at the settlement price, along
+/// with its conditional orders. No fee is charged.
+#[allow(clippy::too_many_arguments)]
+fn settle_position(
+    storage: &mut dyn Storage,
+    contract: Addr,
+    pair_id: &PairId,
+    pair_state: &mut PairState,
+    state: &mut State,
+    user: Addr,
+    settlement_price: UsdPrice,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let mut user_state = USER_STATES.load(storage, user)?;
+
+    let Some(old_position) = user_state.positions.get(pair_id).cloned() else {
+        return Ok(());
+    };
+
+    for trigger_direction in [TriggerDirection::Above, TriggerDirection::Below] {
+        for order_id in old_position.conditional_orders(trigger_direction).keys() {
+            events.push(ConditionalOrderRemoved {
+                order_id: *order_id,
+                pair_id: pair_id.clone(),
+                user,
+                trigger_direction,
+                reason: ReasonForOrderRemoval::Delisted,
+            })?;
+
+            order_group::on_order_removed(storage, *order_id, Some(events))?;
+        }
+    }
+
+    let closing_size = old_position.size.checked_neg()?;
+
+    let settlement = settle_fill(
+        contract,
+        pair_id,
+        pair_state,
+        &mut user_state,
+        user,
+        closing_size,
+        settlement_price,
+        Dimensionless::ZERO,
+        Dimensionless::ZERO,
+        None,
+    )?;
+
+    let pnl = settlement.pnl.total()?;
+
+    margin_for_pair_mut(&mut user_state, pair_id).checked_add_assign(pnl)?;
+
+    // An isolated position's losses are limited to its own margin: a negative
+    // isolated margin is bad debt, and mustn't be released into the cross
+    // margin, where it would eat into the other positions' collateral.
+    if let Some(isolated_margin) = user_state.isolated_margins.get_mut(pair_id) {
+        cover_bad_debt(isolated_margin, state, user, events)?;
+    }
+
+    release_isolated_margin(&mut user_state, pair_id)?;
+
+    if let Some(diff) = compute_position_diff(
+        pair_id,
+        user,
+        Some(&old_position),
+        user_state.positions.get(pair_id),
+    ) {
+        apply_position_index_updates(storage, &[diff])?;
+    }
+
+    // Without any position left, the account's equity is its margin. A
+    // negative one is bad debt, which nobody else can be liquidated to cover.
+    if user_state.positions.is_empty() {
+        cover_bad_debt(&mut user_state.margin, state, user, events)?;
+    }
+
+    events.push(PositionSettled {
+        user,
+        pair_id: pair_id.clone(),
+        closing_size,
+        settlement_price,
+        realized_pnl: settlement.pnl.closing,
+        realized_funding: settlement.pnl.funding,
+    })?;
+
+    if user_state.is_empty() {
+        USER_STATES.remove(storage, user)?;
+    } else {
+        USER_STATES.save(storage, user, &user_state)?;
+    }
+
+    Ok(())
+}
+
+/// If the margin is negative, cover the shortfall from the insurance fund,
+/// bringing the margin back to zero.
+fn cover_bad_debt(
+    margin: &mut UsdValue,
+    state: &mut State,
+    user: Addr,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    if !margin.is_negative() {
+        return Ok(());
+    }
+
+    let bad_debt = margin.checked_abs()?;
+
+    margin.checked_add_assign(bad_debt)?;
+    state.insurance_fund.checked_sub_assign(bad_debt)?;
+
+    events.push(BadDebtCovered {
+        liquidated_user: user,
+        amount: bad_debt,
+        insurance_fund_remaining: state.insurance_fund,
+    })?;
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_math::Uint64,
+        dango_order_book::{FundingPerUnit, LimitOrder, Quantity, may_invert_price},
+        dango_primitives::{
+            Coins, Config, Duration, MockContext, MockQuerier, Permission, Permissions, ResultExt,
+            Timestamp, btree_set,
+        },
+        dango_types::perps::{PairParam, Param, Position, UserState},
+        std::collections::BTreeMap,
+    };
+
+    const OWNER: Addr = Addr::mock(0);
+    const LONG_USER: Addr = Addr::mock(1);
+    const SHORT_USER: Addr = Addr::mock(2);
+    const MAKER: Addr = Addr::mock(3);
+    const CONTRACT: Addr = Addr::mock(9);
+
+    fn pair() -> PairId {
+        "perp/ethusd".parse().unwrap()
+    }
+
+    fn mock_config() -> Config {
+        Config {
+            owner: OWNER,
+            bank: Addr::mock(8),
+            gas_token: dango_primitives::Denom::new_unchecked(["ugas"]),
+            gas_fee_rate: Default::default(),
+            gas_exemptions: Default::default(),
+            cronjobs: BTreeMap::new(),
+            permissions: Permissions {
+                upload: Permission::Nobody,
+                instantiate: Permission::Nobody,
+            },
+            max_orphan_age: Duration::from_seconds(0),
+        }
+    }
+
+    fn setup(storage: &mut dyn Storage, status: PairStatus) {
+        PARAM
+            .save(
+                storage,
+                &Param {
+                    vault_total_weight: Dimensionless::new_int(3),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        STATE.save(storage, &State::default()).unwrap();
+        PAIR_IDS.save(storage, &btree_set! { pair() }).unwrap();
+        PAIR_PARAMS
+            .save(
+                storage,
+                &pair(),
+                &PairParam {
+                    vault_liquidity_weight: Dimensionless::new_int(1),
+                    status,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        PAIR_STATES
+            .save(
+                storage,
+                &pair(),
+                &PairState {
+                    index_price: UsdPrice::new_int(100),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    /// Save a position with the given margin, and update the position index
+    /// and open interest accordingly.
+    fn save_position(storage: &mut dyn Storage, user: Addr, size: i128, margin: i128) {
+        let entry_price = UsdPrice::new_int(100);
+
+        USER_STATES
+            .save(
+                storage,
+                user,
+                &UserState {
+                    margin: UsdValue::new_int(margin),
+                    positions: BTreeMap::from([(
+                        pair(),
+                        Position {
+                            size: Quantity::new_int(size),
+                            entry_price,
+                            entry_funding_per_unit: FundingPerUnit::ZERO,
+                            conditional_orders_above: BTreeMap::new(),
+                            conditional_orders_below: BTreeMap::new(),
+                        },
+                    )]),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let mut pair_state = PAIR_STATES.load(storage, &pair()).unwrap();
+
+        if size > 0 {
+            LONGS.insert(storage, (pair(), entry_price, user)).unwrap();
+            pair_state
+                .long_oi
+                .checked_add_assign(Quantity::new_int(size))
+                .unwrap();
+        } else {
+            SHORTS.insert(storage, (pair(), entry_price, user)).unwrap();
+            pair_state
+                .short_oi
+                .checked_add_assign(Quantity::new_int(-size))
+                .unwrap();
+        }
+
+        PAIR_STATES.save(storage, &pair(), &pair_state).unwrap();
+    }
+
+    /// Save a resting order of `MAKER`, who has no position.
+    fn save_order(storage: &mut dyn Storage, order_id: u64, size: i128) {
+        let is_bid = size > 0;
+        let stored_price = may_invert_price(UsdPrice::new_int(100), is_bid);
+        let order = LimitOrder {
+            user: MAKER,
+            size: Quantity::new_int(size),
+            reduce_only: false,
+            reserved_margin: UsdValue::new_int(10),
+            created_at: Timestamp::ZERO,
+            tp: None,
+            sl: None,
+            client_order_id: None,
+        };
+
+        let book = if is_bid {
+            BIDS
+        } else {
+            ASKS
+        };
+
+        book.save(
+            storage,
+            (pair(), stored_price, Uint64::new(order_id)),
+            &order,
+        )
+        .unwrap();
+
+        let mut user_state = USER_STATES
+            .may_load(storage, MAKER)
+            .unwrap()
+            .unwrap_or_default();
+        user_state.margin = UsdValue::new_int(100);
+        user_state
+            .reserved_margin
+            .checked_add_assign(order.reserved_margin)
+            .unwrap();
+        user_state.open_order_count += 1;
+        USER_STATES.save(storage, MAKER, &user_state).unwrap();
+    }
+
+    fn settling() -> PairStatus {
+        PairStatus::Settling {
+            settlement_price: UsdPrice::new_int(110),
+        }
+    }
+
+    #[test]
+    fn settle_pair_works() {
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(OWNER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, PairStatus::ReduceOnly);
+
+        settle_pair(ctx.as_mutable(), pair(), UsdPrice::new_int(110)).should_succeed();
+
+        assert_eq!(
+            PAIR_PARAMS.load(&ctx.storage, &pair()).unwrap().status,
+            settling()
+        );
+        assert_eq!(
+            PAIR_STATES.load(&ctx.storage, &pair()).unwrap().index_price,
+            UsdPrice::new_int(110)
+        );
+        assert!(PAIR_IDS.load(&ctx.storage).unwrap().is_empty());
+        assert_eq!(
+            PARAM.load(&ctx.storage).unwrap().vault_total_weight,
+            Dimensionless::new_int(2)
+        );
+
+        // A pair can only be settled once.
+        settle_pair(ctx.as_mutable(), pair(), UsdPrice::new_int(120))
+            .should_fail_with_error("has been delisted");
+    }
+
+    #[test]
+    fn settle_pair_rejects_non_owner() {
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, PairStatus::Active);
+
+        settle_pair(ctx.as_mutable(), pair(), UsdPrice::new_int(110))
+            .should_fail_with_error("you don't have the right");
+    }
+
+    #[test]
+    fn process_pair_settlement_rejects_active_pair() {
+        let mut ctx = MockContext::new()
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, PairStatus::Active);
+
+        process_pair_settlement(ctx.as_mutable(), pair(), 10)
+            .should_fail_with_error("is not being settled");
+    }
+
+    #[test]
+    fn process_pair_settlement_closes_positions_at_settlement_price() {
+        let mut ctx = MockContext::new()
+            .with_contract(CONTRACT)
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, settling());
+        save_position(&mut ctx.storage, LONG_USER, 2, 1_000);
+        save_position(&mut ctx.storage, SHORT_USER, -2, 1_000);
+
+        process_pair_settlement(ctx.as_mutable(), pair(), 10).should_succeed();
+
+        // Both positions are closed at $110, without a fee.
+        let long_state = USER_STATES.load(&ctx.storage, LONG_USER).unwrap();
+        assert!(long_state.positions.is_empty());
+        assert_eq!(long_state.margin, UsdValue::new_int(1_020));
+
+        let short_state = USER_STATES.load(&ctx.storage, SHORT_USER).unwrap();
+        assert!(short_state.positions.is_empty());
+        assert_eq!(short_state.margin, UsdValue::new_int(980));
+
+        assert!(LONGS.is_empty(&ctx.storage));
+        assert!(SHORTS.is_empty(&ctx.storage));
+
+        let pair_state = PAIR_STATES.load(&ctx.storage, &pair()).unwrap();
+        assert_eq!(pair_state.long_oi, Quantity::ZERO);
+        assert_eq!(pair_state.short_oi, Quantity::ZERO);
+
+        assert_eq!(
+            PAIR_PARAMS.load(&ctx.storage, &pair()).unwrap().status,
+            PairStatus::Delisted {
+                settlement_price: UsdPrice::new_int(110),
+            }
+        );
+    }
+
+    #[test]
+    fn process_pair_settlement_respects_limit() {
+        let mut ctx = MockContext::new()
+            .with_contract(CONTRACT)
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, settling());
+        save_order(&mut ctx.storage, 1, 5);
+        save_order(&mut ctx.storage, 2, -5);
+        save_position(&mut ctx.storage, LONG_USER, 2, 1_000);
+        save_position(&mut ctx.storage, SHORT_USER, -2, 1_000);
+
+        // The orders are canceled first, then one of the positions is closed.
+        process_pair_settlement(ctx.as_mutable(), pair(), 3).should_succeed();
+
+        assert!(BIDS.is_empty(&ctx.storage));
+        assert!(ASKS.is_empty(&ctx.storage));
+
+        let maker_state = USER_STATES.load(&ctx.storage, MAKER).unwrap();
+        assert_eq!(maker_state.open_order_count, 0);
+        assert_eq!(maker_state.reserved_margin, UsdValue::ZERO);
+
+        assert!(LONGS.is_empty(&ctx.storage));
+        assert!(!SHORTS.is_empty(&ctx.storage));
+        assert_eq!(
+            PAIR_PARAMS.load(&ctx.storage, &pair()).unwrap().status,
+            settling()
+        );
+
+        process_pair_settlement(ctx.as_mutable(), pair(), 3).should_succeed();
+
+        assert!(SHORTS.is_empty(&ctx.storage));
+        assert_eq!(
+            PAIR_PARAMS.load(&ctx.storage, &pair()).unwrap().status,
+            PairStatus::Delisted {
+                settlement_price: UsdPrice::new_int(110),
+            }
+        );
+    }
+
+    #[test]
+    fn process_pair_settlement_covers_bad_debt() {
+        let mut ctx = MockContext::new()
+            .with_contract(CONTRACT)
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, settling());
+        save_position(&mut ctx.storage, LONG_USER, 10, 1_000);
+        save_position(&mut ctx.storage, SHORT_USER, -10, 50);
+
+        process_pair_settlement(ctx.as_mutable(), pair(), 10).should_succeed();
+
+        // The short loses $100 against a margin of $50. The insurance fund
+        // covers the difference, and the emptied account is removed.
+        assert!(
+            USER_STATES
+                .may_load(&ctx.storage, SHORT_USER)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            STATE.load(&ctx.storage).unwrap().insurance_fund,
+            UsdValue::new_int(-50)
+        );
+        assert_eq!(
+            USER_STATES.load(&ctx.storage, LONG_USER).unwrap().margin,
+            UsdValue::new_int(1_100)
+        );
+    }
+
+    #[test]
+    fn process_pair_settlement_covers_isolated_bad_debt() {
+        let mut ctx = MockContext::new()
+            .with_contract(CONTRACT)
+            .with_sender(LONG_USER)
+            .with_funds(Coins::default());
+
+        setup(&mut ctx.storage, settling());
+        save_position(&mut ctx.storage, LONG_USER, 10, 1_000);
+        save_position(&mut ctx.storage, SHORT_USER, -10, 1_000);
+
+        // The short position is isolated with a margin of $50, alongside a
+        // cross position in another pair.
+        let other_pair: PairId = "perp/btcusd".parse().unwrap();
+        let other_position = Position {
+            size: Quantity::new_int(1),
+            entry_price: UsdPrice::new_int(100),
+            entry_funding_per_unit: FundingPerUnit::ZERO,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
+        };
+        let mut short_state = USER_STATES.load(&ctx.storage, SHORT_USER).unwrap();
+        short_state
+            .isolated_margins
+            .insert(pair(), UsdValue::new_int(50));
+        short_state
+            .positions
+            .insert(other_pair.clone(), other_position.clone());
+        USER_STATES
+            .save(&mut ctx.storage, SHORT_USER, &short_state)
+            .unwrap();
+
+        process_pair_settlement(ctx.as_mutable(), pair(), 10).should_succeed();
+
+        // The short loses $100 against an isolated margin of $50. The
+        // insurance fund covers the difference, leaving the cross margin
+        // backing the other position untouched.
+        let short_state = USER_STATES.load(&ctx.storage, SHORT_USER).unwrap();
+        assert_eq!(short_state.margin, UsdValue::new_int(1_000));
+        assert_eq!(short_state.isolated_margins[&pair()], UsdValue::ZERO);
+        assert_eq!(
+            short_state.positions,
+            BTreeMap::from([(other_pair, other_position)])
+        );
+        assert_eq!(
+            STATE.load(&ctx.storage).unwrap().insurance_fund,
+            UsdValue::new_int(-50)
+        );
+    }
+}
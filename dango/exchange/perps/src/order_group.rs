@@ -0,0 +1,353 @@
+//! Lifecycle hooks of order groups.
+//!
+//! An order group links the lifecycles of orders in the same pair:
+//!
+//! - **One-cancels-other (OCO):** once a member is filled, even partially, or
+//!   triggered in case of a conditional order, the other members are canceled.
+//! - **One-triggers-other (OTO):** once the primary limit order is fully
+//!   filled, the group is marked as activated, and the cron places the
+//!   secondary orders.
+//!
+//! A member removed for any other reason (canceled by the user, self-trade
+//! prevention, liquidation, a conditional order dropped along with its
+//! position, etc.) dissolves the group. Members that are no longer live when
+//! the group is resolved are skipped.
+
+use {
+    crate::{
+        core::MakerOrderUpdate,
+        position_index::PositionIndexUpdate,
+        state::{
+            ACTIVATED_ORDER_GROUPS, ORDER_GROUP_MEMBERS, ORDER_GROUPS, PAIR_PARAMS, USER_STATES,
+        },
+        trade::{compute_cancel_one_order_outcome, update_user_state_with},
+    },
+    dango_order_book::{
+        ASKS, BIDS, ConditionalOrderId, ConditionalOrderRemoved, LimitOrder, OrderId, OrderKey,
+        ReasonForOrderRemoval, TriggerDirection,
+    },
+    dango_primitives::{EventBuilder, StdResult, Storage},
+    dango_types::perps::{
+        GroupMember, OrderGroup, OrderGroupId, OrderGroupKind, OrderGroupRemoved,
+        ReasonForOrderGroupRemoval,
+    },
+};
+
+/// Update the groups of the maker orders touched by a match: a fill resolves
+/// an OCO group, or activates an OTO group if it fully fills the primary order;
+/// a removal dissolves the group.
+///
+/// Must be called after the match has been written to storage, since resolving
+/// an OCO group cancels the makers' other orders.
+pub fn on_maker_orders_updated(
+    storage: &mut dyn Storage,
+    updates: &[(OrderId, MakerOrderUpdate)],
+    events: &mut EventBuilder,
+) -> StdResult<()> {
+    for (order_id, update) in updates {
+        match update {
+            MakerOrderUpdate::PartiallyFilled => {
+                on_order_filled(storage, *order_id, false, events)?;
+            },
+            MakerOrderUpdate::Filled => {
+                on_order_filled(storage, *order_id, true, events)?;
+            },
+            MakerOrderUpdate::Removed => {
+                on_order_removed(storage, *order_id, Some(events))?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the groups of the conditional orders dropped along with positions
+/// that fills closed or flipped, which would otherwise never be resolved.
+///
+/// Must be called after the groups of the filled or triggered orders have been
+/// updated, so that a group resolved by the fill isn't dissolved instead.
+pub fn on_positions_updated(
+    storage: &mut dyn Storage,
+    updates: &[PositionIndexUpdate],
+    events: &mut EventBuilder,
+) -> StdResult<()> {
+    for update in updates {
+        for order_id in &update.dropped_conditional_orders {
+            on_order_removed(storage, *order_id, Some(events))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A limit order has been filled, fully or partially.
+pub fn on_order_filled(
+    storage: &mut dyn Storage,
+    order_id: OrderId,
+    fully_filled: bool,
+    events: &mut EventBuilder,
+) -> StdResult<()> {
+    let Some(group_id) = ORDER_GROUP_MEMBERS.may_load(storage, order_id)? else {
+        return Ok(());
+    };
+
+    let group = ORDER_GROUPS.load(storage, group_id)?;
+
+    match group.kind {
+        OrderGroupKind::Oco { .. } => {
+            resolve_oco_group(storage, group_id, group, order_id, events)?;
+        },
+        OrderGroupKind::Oto { .. } => {
+            // The secondaries are placed by the cron, not right away, so that
+            // the fill that activated the group is fully settled first.
+            if fully_filled {
+                ORDER_GROUP_MEMBERS.remove(storage, order_id);
+                ACTIVATED_ORDER_GROUPS.insert(storage, group_id)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// A conditional order has been triggered. Returns the IDs of the conditional
+/// orders canceled as a result.
+pub fn on_conditional_order_triggered(
+    storage: &mut dyn Storage,
+    order_id: ConditionalOrderId,
+    events: &mut EventBuilder,
+) -> StdResult<Vec<ConditionalOrderId>> {
+    let Some(group_id) = ORDER_GROUP_MEMBERS.may_load(storage, order_id)? else {
+        return Ok(Vec::new());
+    };
+
+    let group = ORDER_GROUPS.load(storage, group_id)?;
+
+    resolve_oco_group(storage, group_id, group, order_id, events)
+}
+
+/// An order has been removed for a reason other than a fill or a trigger.
+/// Dissolve the group it belongs to, leaving the group's other orders in place.
+pub fn on_order_removed(
+    storage: &mut dyn Storage,
+    order_id: OrderId,
+    events: Option<&mut EventBuilder>,
+) -> StdResult<()> {
+    let Some(group_id) = ORDER_GROUP_MEMBERS.may_load(storage, order_id)? else {
+        return Ok(());
+    };
+
+    let group = ORDER_GROUPS.load(storage, group_id)?;
+
+    remove_order_group(storage, group_id, &group)?;
+
+    if let Some(events) = events {
+        events.push(OrderGroupRemoved {
+            group_id,
+            user: group.user,
+            pair_id: group.pair_id,
+            reason: ReasonForOrderGroupRemoval::MemberRemoved,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Save a new order group, and record the group of each of its live orders.
+pub fn save_order_group(
+    storage: &mut dyn Storage,
+    group_id: OrderGroupId,
+    group: &OrderGroup,
+) -> StdResult<()> {
+    ORDER_GROUPS.save(storage, group_id, group)?;
+
+    for member in group.members() {
+        ORDER_GROUP_MEMBERS.save(storage, member.order_id(), &group_id)?;
+    }
+
+    Ok(())
+}
+
+/// Delete the records of an order group. Its orders are left untouched.
+pub fn remove_order_group(
+    storage: &mut dyn Storage,
+    group_id: OrderGroupId,
+    group: &OrderGroup,
+) -> StdResult<()> {
+    ORDER_GROUPS.remove(storage, group_id)?;
+
+    for member in group.members() {
+        ORDER_GROUP_MEMBERS.remove(storage, member.order_id());
+    }
+
+    ACTIVATED_ORDER_GROUPS.remove(storage, group_id);
+
+    Ok(())
+}
+
+/// Resolve an OCO group, one of whose members has been executed: remove the
+/// group and cancel the other members. Returns the IDs of the conditional
+/// orders canceled.
+pub fn resolve_oco_group(
+    storage: &mut dyn Storage,
+    group_id: OrderGroupId,
+    group: OrderGroup,
+    executed_order_id: OrderId,
+    events: &mut EventBuilder,
+) -> StdResult<Vec<ConditionalOrderId>> {
+    // Remove the group before canceling the members, so that the cancellations
+    // don't attempt to dissolve it.
+    remove_order_group(storage, group_id, &group)?;
+
+    let canceled = cancel_members(
+        storage,
+        &group,
+        Some(executed_order_id),
+        ReasonForOrderRemoval::OneCancelsOther,
+        events,
+    )?;
+
+    events.push(OrderGroupRemoved {
+        group_id,
+        user: group.user,
+        pair_id: group.pair_id,
+        reason: ReasonForOrderGroupRemoval::Resolved,
+    })?;
+
+    Ok(canceled)
+}
+
+/// Cancel an order group along with all of its live orders.
+pub fn cancel_order_group(
+    storage: &mut dyn Storage,
+    group_id: OrderGroupId,
+    group: OrderGroup,
+    events: &mut EventBuilder,
+) -> StdResult<()> {
+    remove_order_group(storage, group_id, &group)?;
+
+    cancel_members(
+        storage,
+        &group,
+        None,
+        ReasonForOrderRemoval::Canceled,
+        events,
+    )?;
+
+    events.push(OrderGroupRemoved {
+        group_id,
+        user: group.user,
+        pair_id: group.pair_id,
+        reason: ReasonForOrderGroupRemoval::Canceled,
+    })?;
+
+    Ok(())
+}
+
+/// Cancel the live orders of a group, except for the given one. Orders that
+/// are no longer live are skipped. Returns the IDs of the conditional orders
+/// canceled.
+fn cancel_members(
+    storage: &mut dyn Storage,
+    group: &OrderGroup,
+    except: Option<OrderId>,
+    reason: ReasonForOrderRemoval,
+    events: &mut EventBuilder,
+) -> StdResult<Vec<ConditionalOrderId>> {
+    let mut canceled_conditional_orders = Vec::new();
+
+    for member in group.members() {
+        if Some(member.order_id()) == except {
+            continue;
+        }
+
+        match member {
+            GroupMember::Limit { order_id } => {
+                let Some((order_key, order)) = may_load_limit_order(storage, order_id)? else {
+                    continue;
+                };
+
+                update_user_state_with(storage, order.user, |storage, user_state| {
+                    compute_cancel_one_order_outcome(
+                        storage,
+                        user_state,
+                        order_key,
+                        order,
+                        Some(events),
+                        reason,
+                        |storage, pair_id| PAIR_PARAMS.load(storage, pair_id),
+                    )
+                })?;
+            },
+            GroupMember::Conditional {
+                order_id,
+                trigger_direction,
+            } => {
+                if cancel_conditional_order(
+                    storage,
+                    group,
+                    order_id,
+                    trigger_direction,
+                    reason,
+                    events,
+                )? {
+                    canceled_conditional_orders.push(order_id);
+                }
+            },
+        }
+    }
+
+    Ok(canceled_conditional_orders)
+}
+
+/// Remove a conditional order from the group owner's position. Returns whether
+/// the order was found.
+fn cancel_conditional_order(
+    storage: &mut dyn Storage,
+    group: &OrderGroup,
+    order_id: ConditionalOrderId,
+    trigger_direction: TriggerDirection,
+    reason: ReasonForOrderRemoval,
+    events: &mut EventBuilder,
+) -> StdResult<bool> {
+    let Some(mut user_state) = USER_STATES.may_load(storage, group.user)? else {
+        return Ok(false);
+    };
+
+    let Some(position) = user_state.positions.get_mut(&group.pair_id) else {
+        return Ok(false);
+    };
+
+    if position
+        .conditional_orders_mut(trigger_direction)
+        .remove(&order_id)
+        .is_none()
+    {
+        return Ok(false);
+    }
+
+    USER_STATES.save(storage, group.user, &user_state)?;
+
+    events.push(ConditionalOrderRemoved {
+        order_id,
+        pair_id: group.pair_id.clone(),
+        user: group.user,
+        trigger_direction,
+        reason,
+    })?;
+
+    Ok(true)
+}
+
+/// Load a resting limit order by ID from either side of the book.
+pub fn may_load_limit_order(
+    storage: &dyn Storage,
+    order_id: OrderId,
+) -> StdResult<Option<(OrderKey, LimitOrder)>> {
+    BIDS.idx
+        .order_id
+        .may_load(storage, order_id)
+        .transpose()
+        .or_else(|| ASKS.idx.order_id.may_load(storage, order_id).transpose())
+        .transpose()
+}
@@ -1,6 +1,6 @@
 use {
     crate::state::{LONGS, SHORTS},
-    dango_order_book::{PairId, UsdPrice},
+    dango_order_book::{ConditionalOrderId, PairId, TriggerDirection, UsdPrice},
     dango_primitives::{Addr, StdResult, Storage},
     dango_types::perps::Position,
 };
@@ -14,6 +14,9 @@ pub struct PositionIndexUpdate {
     pub old_entry: Option<(UsdPrice, bool)>,
     /// New entry to insert: (entry_price, is_long).
     pub new_entry: Option<(UsdPrice, bool)>,
+    /// Conditional orders of the old position that the new one doesn't have,
+    /// i.e. dropped along with the position as it was closed or flipped.
+    pub dropped_conditional_orders: Vec<ConditionalOrderId>,
 }
 
 /// Build a `PositionIndexUpdate` by comparing old and new position state.
@@ -31,11 +34,30 @@ pub fn compute_position_diff(
         return None;
     }
 
+    let mut dropped_conditional_orders = Vec::new();
+
+    if let Some(old_pos) = old_pos {
+        for trigger_direction in [TriggerDirection::Above, TriggerDirection::Below] {
+            for order_id in old_pos.conditional_orders(trigger_direction).keys() {
+                let kept = new_pos.is_some_and(|new_pos| {
+                    new_pos
+                        .conditional_orders(trigger_direction)
+                        .contains_key(order_id)
+                });
+
+                if !kept {
+                    dropped_conditional_orders.push(*order_id);
+                }
+            }
+        }
+    }
+
     Some(PositionIndexUpdate {
         pair_id: pair_id.clone(),
         user,
         old_entry,
         new_entry,
+        dropped_conditional_orders,
     })
 }
 
@@ -1,19 +1,21 @@
 use {
     crate::{
         core::{
-            compute_available_margin, compute_liquidation_price, compute_maintenance_margin,
-            compute_position_unrealized_funding, compute_position_unrealized_pnl,
-            compute_user_equity,
+            compute_available_margin, compute_isolated_view, compute_liquidation_price,
+            compute_maintenance_margin, compute_position_unrealized_funding,
+            compute_position_unrealized_pnl, compute_user_equity,
         },
         querier::NoCachePerpQuerier,
         referral::calculate_commission_rate,
         state::{
-            COMMISSION_RATE_OVERRIDES, FEE_RATE_OVERRIDES, FEE_SHARE_RATIO, PAIR_PARAMS,
-            PAIR_STATES, REFEREE_TO_REFERRER, REFERRER_TO_REFEREE_STATISTICS, USER_REFERRAL_DATA,
-            USER_STATES, VAULT_SNAPSHOTS,
+            ACCOUNT_OWNERS, COMMISSION_RATE_OVERRIDES, FEE_RATE_OVERRIDES, FEE_SHARE_RATIO,
+            FUNDING_HISTORY, ORDER_GROUPS, PAIR_PARAMS, PAIR_STATES, REFEREE_TO_REFERRER,
+            REFERRER_TO_REFEREE_STATISTICS, TWAP_ORDERS, USER_REFERRAL_DATA, USER_STATES,
+            VAULT_SNAPSHOTS,
         },
     },
     anyhow::ensure,
+    dango_math::{Number, NumberConst, Uint128},
     dango_order_book::{
         ASKS, BIDS, ClientOrderId, DEPTHS, Dimensionless, LimitOrder, LiquidityDepth,
         LiquidityDepthResponse, OrderId, PairId, QueryOrderByClientOrderIdResponse,
@@ -28,9 +30,10 @@ use {
     dango_types::{
         account_factory::UserIndex,
         perps::{
-            CommissionRate, PairParam, PairState, PositionExtended, Referee, RefereeStats,
-            Referrer, ReferrerSettings, ReferrerStatsOrderBy, ReferrerStatsOrderIndex,
-            UserReferralData, UserState, UserStateExtended, VaultSnapshot,
+            CommissionRate, FundingRecord, OrderGroup, OrderGroupId, PairParam, PairState,
+            PositionExtended, Referee, RefereeStats, Referrer, ReferrerSettings,
+            ReferrerStatsOrderBy, ReferrerStatsOrderIndex, SubaccountStates, TwapOrder,
+            TwapOrderId, UserReferralData, UserState, UserStateExtended, VaultSnapshot,
         },
     },
     std::collections::BTreeMap,
@@ -135,17 +138,37 @@ pub fn query_user_state_extended(
                 None
             };
 
+            let isolated_view = user_state
+                .is_isolated(pair_id)
+                .then(|| compute_isolated_view(&user_state, pair_id));
+
+            let isolated_equity = match &isolated_view {
+                Some(view) if include_all || include_equity => {
+                    Some(compute_user_equity(&perp_querier, view)?)
+                },
+                _ => None,
+            };
+
+            let isolated_maintenance_margin = match &isolated_view {
+                Some(view) if include_all || include_maintenance_margin => {
+                    Some(compute_maintenance_margin(&perp_querier, view)?)
+                },
+                _ => None,
+            };
+
             Ok((
                 pair_id.clone(),
                 PositionExtended {
                     size: position.size,
                     entry_price: position.entry_price,
                     entry_funding_per_unit: position.entry_funding_per_unit,
-                    conditional_order_above: position.conditional_order_above.clone(),
-                    conditional_order_below: position.conditional_order_below.clone(),
+                    conditional_orders_above: position.conditional_orders_above.clone(),
+                    conditional_orders_below: position.conditional_orders_below.clone(),
                     unrealized_pnl,
                     unrealized_funding,
                     liquidation_price,
+                    isolated_equity,
+                    isolated_maintenance_margin,
                 },
             ))
         })
@@ -157,6 +180,7 @@ pub fn query_user_state_extended(
         unlocks: user_state.unlocks,
         reserved_margin: user_state.reserved_margin,
         open_order_count: user_state.open_order_count,
+        isolated_margins: user_state.isolated_margins,
         equity,
         available_margin,
         maintenance_margin,
@@ -164,6 +188,59 @@ pub fn query_user_state_extended(
     })
 }
 
+pub fn query_subaccount_states(
+    ctx: ImmutableCtx,
+    user: UserIndex,
+) -> anyhow::Result<SubaccountStates> {
+    let account_factory = crate::account_factory(ctx.querier);
+    let user_data = ctx
+        .querier
+        .query_wasm_path(account_factory, &dango_account_factory::USERS.path(user))?;
+
+    let mut margin = UsdValue::ZERO;
+    let mut equity = UsdValue::ZERO;
+    let mut available_margin = UsdValue::ZERO;
+    let mut maintenance_margin = UsdValue::ZERO;
+    let mut vault_shares = Uint128::ZERO;
+    let mut subaccounts = BTreeMap::new();
+
+    for account in user_data.accounts.into_values() {
+        if !USER_STATES.has(ctx.storage, account) {
+            continue;
+        }
+
+        let state = query_user_state_extended(
+            ctx.storage,
+            account,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            true, // include_all
+        )?;
+
+        // With `include_all`, these are always `Some`.
+        margin.checked_add_assign(state.margin)?;
+        equity.checked_add_assign(state.equity.unwrap_or_default())?;
+        available_margin.checked_add_assign(state.available_margin.unwrap_or_default())?;
+        maintenance_margin.checked_add_assign(state.maintenance_margin.unwrap_or_default())?;
+        vault_shares.checked_add_assign(state.vault_shares)?;
+
+        subaccounts.insert(account, state);
+    }
+
+    Ok(SubaccountStates {
+        margin,
+        equity,
+        available_margin,
+        maintenance_margin,
+        vault_shares,
+        subaccounts,
+    })
+}
+
 pub fn query_user_states_extended(
     storage: &dyn Storage,
     start_after: Option<Addr>,
@@ -289,6 +366,34 @@ pub fn query_order_by_client_order_id(
     Ok(None)
 }
 
+/// Active TWAP orders of the given user, keyed by TWAP order ID. The number is
+/// bounded by `MAX_TWAP_ORDERS_PER_USER`, so no pagination is needed.
+pub fn query_twap_orders_by_user(
+    ctx: ImmutableCtx,
+    user: Addr,
+) -> StdResult<BTreeMap<TwapOrderId, TwapOrder>> {
+    TWAP_ORDERS
+        .idx
+        .user
+        .prefix(user)
+        .range(ctx.storage, None, None, IterationOrder::Ascending)
+        .collect()
+}
+
+/// Active order groups of the given user, keyed by group ID. The number is
+/// bounded by `MAX_ORDER_GROUPS_PER_USER`, so no pagination is needed.
+pub fn query_order_groups_by_user(
+    ctx: ImmutableCtx,
+    user: Addr,
+) -> StdResult<BTreeMap<OrderGroupId, OrderGroup>> {
+    ORDER_GROUPS
+        .idx
+        .user
+        .prefix(user)
+        .range(ctx.storage, None, None, IterationOrder::Ascending)
+        .collect()
+}
+
 fn limit_order_to_response(
     pair_id: PairId,
     limit_price: UsdPrice,
@@ -420,6 +525,31 @@ pub fn query_volume(
     }
 }
 
+/// The trading volume that determines an account's fee tier: the combined
+/// volume of all accounts owned by the same user, or only the account's own
+/// volume if its owner isn't known (see `ACCOUNT_OWNERS`).
+///
+/// Unlike [`compute_user_volume`], doesn't query the account factory, so it
+/// can be called while matching orders.
+pub fn query_fee_tier_volume(
+    storage: &dyn Storage,
+    account: Addr,
+    since: Option<Timestamp>,
+) -> StdResult<UsdValue> {
+    let Some(user) = ACCOUNT_OWNERS.may_load(storage, account)? else {
+        return query_volume(storage, account, since);
+    };
+
+    ACCOUNT_OWNERS
+        .idx
+        .user
+        .prefix(user)
+        .keys(storage, None, None, IterationOrder::Ascending)
+        .try_fold(UsdValue::ZERO, |total, account| {
+            Ok(total.checked_add(query_volume(storage, account?, since)?)?)
+        })
+}
+
 pub fn query_volume_by_user(
     ctx: ImmutableCtx,
     user: UserIndex,
@@ -461,6 +591,24 @@ pub fn query_vault_snapshots(
         .collect()
 }
 
+pub fn query_funding_history(
+    storage: &dyn Storage,
+    pair_id: PairId,
+    min: Option<Timestamp>,
+    max: Option<Timestamp>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Timestamp, FundingRecord>> {
+    let min = min.map(Bound::Inclusive);
+    let max = max.map(Bound::Inclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    FUNDING_HISTORY
+        .prefix(&pair_id)
+        .range(storage, min, max, IterationOrder::Ascending)
+        .take(limit)
+        .collect()
+}
+
 pub fn query_referrer(storage: &dyn Storage, referee: UserIndex) -> StdResult<Option<Referrer>> {
     REFEREE_TO_REFERRER.may_load(storage, referee)
 }
@@ -1,14 +1,15 @@
 use {
     dango_order_book::{
-        ConditionalOrderId, Dimensionless, PairId, TriggerDirection, UsdPrice, UsdValue,
+        ConditionalOrderId, Dimensionless, OrderId, PairId, TriggerDirection, UsdPrice, UsdValue,
     },
     dango_primitives::{Addr, Timestamp},
     dango_storage::{IndexedMap, Item, Map, MultiIndex, Set},
     dango_types::{
         account_factory::UserIndex,
         perps::{
-            CommissionRate, FeeShareRatio, PairParam, PairState, Param, Referee, RefereeStats,
-            Referrer, State, UserReferralData, UserState, VaultSnapshot,
+            CommissionRate, FeeShareRatio, FundingRecord, OrderGroup, OrderGroupId, PairParam,
+            PairState, Param, PremiumSamples, Referee, RefereeStats, Referrer, State, TwapOrder,
+            TwapOrderId, UserReferralData, UserState, VaultSnapshot,
         },
     },
     std::collections::BTreeSet,
@@ -30,8 +31,50 @@ pub const PAIR_PARAMS: Map<&PairId, PairParam> = Map::new("pair_param");
 
 pub const PAIR_STATES: Map<&PairId, PairState> = Map::new("pair_state");
 
-pub const USER_STATES: IndexedMap<Addr, UserState, UserStateIndexes> =
-    IndexedMap::new("us", UserStateIndexes::new("us", "us__unlock", "us__cond"));
+/// Premium samples taken since the last funding, for pairs in
+/// `FundingMode::PremiumIndex`.
+pub const PREMIUM_SAMPLES: Map<&PairId, PremiumSamples> = Map::new("premium_samples");
+
+/// The funding applied to each pair, keyed by funding time.
+pub const FUNDING_HISTORY: Map<(&PairId, Timestamp), FundingRecord> = Map::new("funding_hist");
+
+pub const USER_STATES: IndexedMap<Addr, UserState, UserStateIndexes> = IndexedMap::new(
+    "us",
+    UserStateIndexes::new("us", "us__unlock", "us__cond", "us__trail"),
+);
+
+/// Active TWAP orders, indexed by user and by the time of their next slice.
+pub const TWAP_ORDERS: IndexedMap<TwapOrderId, TwapOrder, TwapOrderIndexes> = IndexedMap::new(
+    "twap",
+    TwapOrderIndexes::new("twap", "twap__user", "twap__next"),
+);
+
+/// Active order groups, indexed by user.
+pub const ORDER_GROUPS: IndexedMap<OrderGroupId, OrderGroup, OrderGroupIndexes> =
+    IndexedMap::new("og", OrderGroupIndexes::new("og", "og__user"));
+
+/// The group each live order belongs to: the members of OCO groups, and the
+/// primary orders of OTO groups.
+pub const ORDER_GROUP_MEMBERS: Map<OrderId, OrderGroupId> = Map::new("og_member");
+
+/// OTO groups whose primary order has been fully filled, to be activated by
+/// the cron.
+pub const ACTIVATED_ORDER_GROUPS: Set<OrderGroupId> = Set::new("og_activated");
+
+/// Armed dead-man's switches: for each user, the time at which all of their
+/// resting limit orders are to be canceled, indexed by that time.
+pub const CANCEL_ALL_DEADLINES: IndexedMap<Addr, Timestamp, CancelAllDeadlineIndexes> =
+    IndexedMap::new("cad", CancelAllDeadlineIndexes::new("cad", "cad__time"));
+
+/// The user who owns each account known to the exchange, indexed by user.
+///
+/// Mirrors the account factory's records, so that accounts can be grouped by
+/// user (e.g. to resolve fee tiers) without querying it. An account is added
+/// when it first receives margin.
+pub const ACCOUNT_OWNERS: IndexedMap<Addr, UserIndex, AccountOwnerIndexes> = IndexedMap::new(
+    "acc_owner",
+    AccountOwnerIndexes::new("acc_owner", "acc_owner__user"),
+);
 
 /// For a given trading pair, users who have _long_ positions in this pair,
 /// indexed by their entry prices.
@@ -98,6 +141,11 @@ pub struct UserStateIndexes<'a> {
     /// iteration visits the highest prices first.
     pub conditional_orders:
         MultiIndex<'a, Addr, (PairId, TriggerDirection, UsdPrice, ConditionalOrderId), UserState>,
+
+    /// Trailing-stop conditional orders across a user's positions, whose
+    /// trigger prices are to be updated as the oracle price moves.
+    pub trailing_conditional_orders:
+        MultiIndex<'a, Addr, (PairId, TriggerDirection, ConditionalOrderId), UserState>,
 }
 
 impl UserStateIndexes<'static> {
@@ -105,6 +153,7 @@ impl UserStateIndexes<'static> {
         pk_namespace: &'static str,
         unlock_namespace: &'static str,
         cond_namespace: &'static str,
+        trail_namespace: &'static str,
     ) -> Self {
         UserStateIndexes {
             earliest_unlock_end_time: MultiIndex::new(
@@ -122,7 +171,7 @@ impl UserStateIndexes<'static> {
                 |_, user_state| {
                     let mut keys = Vec::new();
                     for (pair_id, position) in &user_state.positions {
-                        if let Some(order) = &position.conditional_order_above {
+                        for order in position.conditional_orders_above.values() {
                             keys.push((
                                 pair_id.clone(),
                                 TriggerDirection::Above,
@@ -130,7 +179,7 @@ impl UserStateIndexes<'static> {
                                 order.order_id,
                             ));
                         }
-                        if let Some(order) = &position.conditional_order_below {
+                        for order in position.conditional_orders_below.values() {
                             keys.push((
                                 pair_id.clone(),
                                 TriggerDirection::Below,
@@ -144,6 +193,88 @@ impl UserStateIndexes<'static> {
                 pk_namespace,
                 cond_namespace,
             ),
+            trailing_conditional_orders: MultiIndex::new2(
+                |_, user_state| {
+                    let mut keys = Vec::new();
+                    for (pair_id, position) in &user_state.positions {
+                        for direction in [TriggerDirection::Above, TriggerDirection::Below] {
+                            for order in position.conditional_orders(direction).values() {
+                                if order.trailing_offset.is_some() {
+                                    keys.push((pair_id.clone(), direction, order.order_id));
+                                }
+                            }
+                        }
+                    }
+                    keys
+                },
+                pk_namespace,
+                trail_namespace,
+            ),
+        }
+    }
+}
+
+#[dango_storage::index_list(TwapOrderId, TwapOrder)]
+pub struct TwapOrderIndexes<'a> {
+    pub user: MultiIndex<'a, TwapOrderId, Addr, TwapOrder>,
+
+    /// Used by the cron to find the TWAP orders whose next slice is due.
+    pub next_slice_time: MultiIndex<'a, TwapOrderId, Timestamp, TwapOrder>,
+}
+
+impl TwapOrderIndexes<'static> {
+    pub const fn new(
+        pk_namespace: &'static str,
+        user_namespace: &'static str,
+        next_slice_time_namespace: &'static str,
+    ) -> Self {
+        TwapOrderIndexes {
+            user: MultiIndex::new(|_, order| order.user, pk_namespace, user_namespace),
+            next_slice_time: MultiIndex::new(
+                |_, order| order.next_slice_time,
+                pk_namespace,
+                next_slice_time_namespace,
+            ),
+        }
+    }
+}
+
+#[dango_storage::index_list(OrderGroupId, OrderGroup)]
+pub struct OrderGroupIndexes<'a> {
+    pub user: MultiIndex<'a, OrderGroupId, Addr, OrderGroup>,
+}
+
+impl OrderGroupIndexes<'static> {
+    pub const fn new(pk_namespace: &'static str, user_namespace: &'static str) -> Self {
+        OrderGroupIndexes {
+            user: MultiIndex::new(|_, group| group.user, pk_namespace, user_namespace),
+        }
+    }
+}
+
+#[dango_storage::index_list(Addr, UserIndex)]
+pub struct AccountOwnerIndexes<'a> {
+    pub user: MultiIndex<'a, Addr, UserIndex, UserIndex>,
+}
+
+impl AccountOwnerIndexes<'static> {
+    pub const fn new(pk_namespace: &'static str, user_namespace: &'static str) -> Self {
+        AccountOwnerIndexes {
+            user: MultiIndex::new(|_, user| *user, pk_namespace, user_namespace),
+        }
+    }
+}
+
+#[dango_storage::index_list(Addr, Timestamp)]
+pub struct CancelAllDeadlineIndexes<'a> {
+    /// Used by the cron to find the dead-man's switches that have expired.
+    pub deadline: MultiIndex<'a, Addr, Timestamp, Timestamp>,
+}
+
+impl CancelAllDeadlineIndexes<'static> {
+    pub const fn new(pk_namespace: &'static str, deadline_namespace: &'static str) -> Self {
+        CancelAllDeadlineIndexes {
+            deadline: MultiIndex::new(|_, deadline| *deadline, pk_namespace, deadline_namespace),
         }
     }
 }
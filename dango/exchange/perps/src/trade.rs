@@ -1,22 +1,36 @@
 mod batch_update_orders;
+mod cancel_all_after;
 mod cancel_conditional_order;
 mod cancel_order;
+mod cancel_order_group;
+mod cancel_twap_order;
 mod deposit;
+mod isolated_margin;
 mod resize_reduce_only;
 mod submit_conditional_order;
 mod submit_order;
+mod submit_order_group;
+mod submit_twap_order;
+mod transfer_margin;
 mod withdraw;
 
 pub use {
-    batch_update_orders::*, cancel_conditional_order::*, cancel_order::*, deposit::*,
-    resize_reduce_only::*, submit_conditional_order::*, submit_order::*, withdraw::*,
+    batch_update_orders::*, cancel_all_after::*, cancel_conditional_order::*, cancel_order::*,
+    cancel_order_group::*, cancel_twap_order::*, deposit::*, isolated_margin::*,
+    resize_reduce_only::*, submit_conditional_order::*, submit_order::*, submit_order_group::*,
+    submit_twap_order::*, transfer_margin::*, withdraw::*,
 };
 
 use {
-    crate::USER_STATES,
-    anyhow::ensure,
-    dango_primitives::{Addr, StdResult, Storage},
-    dango_types::perps::{Param, UserState},
+    crate::{ACCOUNT_OWNERS, USER_STATES, account_factory},
+    anyhow::{bail, ensure},
+    dango_order_book::PairId,
+    dango_primitives::{Addr, QuerierWrapper, StdResult, Storage},
+    dango_storage::{PrimaryKey, StorageQuerier},
+    dango_types::{
+        account_factory::UserIndex,
+        perps::{PairParam, PairStatus, Param, UserState},
+    },
 };
 
 /// Trading must be enabled.
@@ -26,10 +40,30 @@ use {
 /// the counterparty vault. Actions that reduce risk or return funds to the
 /// user are deliberately not guarded.
 pub fn ensure_trading_enabled(param: &Param) -> anyhow::Result<()> {
-    ensure!(
-        param.trading_enabled,
-        "trading is disabled; the exchange has been wound down"
-    );
+    ensure!(param.trading_enabled, "trading is disabled");
+
+    Ok(())
+}
+
+/// The pair must accept the order: any order if it's active, only reduce-only
+/// orders if it's in reduce-only mode, and none once it's being delisted.
+pub fn ensure_pair_accepts_order(
+    pair_id: &PairId,
+    pair_param: &PairParam,
+    reduce_only: bool,
+) -> anyhow::Result<()> {
+    match pair_param.status {
+        PairStatus::Active => {},
+        PairStatus::ReduceOnly => {
+            ensure!(
+                reduce_only,
+                "pair {pair_id} is in reduce-only mode; only reduce-only orders are accepted"
+            );
+        },
+        PairStatus::Settling { .. } | PairStatus::Delisted { .. } => {
+            bail!("pair {pair_id} has been delisted");
+        },
+    }
 
     Ok(())
 }
@@ -39,7 +73,11 @@ pub fn ensure_trading_enabled(param: &Param) -> anyhow::Result<()> {
 ///    effect on the storage.
 /// 3. If the user state becomes empty, delete it from storage; otherwise, save
 ///    the updated user state to storage.
-fn update_user_state_with<F, T>(storage: &mut dyn Storage, user: Addr, action: F) -> StdResult<T>
+pub(crate) fn update_user_state_with<F, T>(
+    storage: &mut dyn Storage,
+    user: Addr,
+    action: F,
+) -> StdResult<T>
 where
     F: FnOnce(&mut dyn Storage, &mut UserState) -> StdResult<T>,
 {
@@ -55,3 +93,36 @@ where
 
     Ok(result)
 }
+
+/// Resolve the user who owns the given account.
+///
+/// Looks up `ACCOUNT_OWNERS` first; only if the account isn't there, reads the
+/// account factory's user index and records the result, so each account is
+/// queried at most once. Returns `None` if the address isn't a user account
+/// created by the account factory (e.g. a multisig or a regular contract).
+pub(crate) fn load_or_register_account_owner(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    account: Addr,
+) -> StdResult<Option<UserIndex>> {
+    if let Some(user) = ACCOUNT_OWNERS.may_load(storage, account)? {
+        return Ok(Some(user));
+    }
+
+    let Some(user_raw) = querier.may_query_wasm_path(
+        account_factory(querier),
+        &dango_account_factory::USERS
+            .idx
+            .by_account
+            .key_path(account),
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let user = UserIndex::from_slice(&user_raw)?;
+
+    ACCOUNT_OWNERS.save(storage, account, &user)?;
+
+    Ok(Some(user))
+}
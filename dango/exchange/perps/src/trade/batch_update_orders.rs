@@ -51,6 +51,7 @@ pub fn batch_update_orders(
                 reduce_only,
                 tp,
                 sl,
+                stp_mode,
             }) => _submit_order(
                 ctx.storage,
                 ctx.querier,
@@ -63,6 +64,7 @@ pub fn batch_update_orders(
                 reduce_only,
                 tp,
                 sl,
+                stp_mode,
                 &mut events,
             )?,
             SubmitOrCancelOrderRequest::Cancel(CancelOrderRequest::One(order_id)) => {
@@ -0,0 +1,111 @@
+use {
+    crate::{CANCEL_ALL_DEADLINES, MAX_CANCEL_ALL_TIMEOUT, MIN_CANCEL_ALL_TIMEOUT},
+    anyhow::ensure,
+    dango_primitives::{Duration, MutableCtx, Response},
+    dango_types::perps::CancelAllDeadlineSet,
+};
+
+/// Arm, refresh, or disarm the sender's dead-man's switch.
+///
+/// Arming an already armed switch replaces its deadline, which is how a market
+/// maker's quoting process keeps it from expiring. Once expired, the switch is
+/// enforced by `process_cancel_all_deadlines` in the cron.
+pub fn cancel_all_after(ctx: MutableCtx, timeout: Option<Duration>) -> anyhow::Result<Response> {
+    let deadline = match timeout {
+        Some(timeout) => {
+            ensure!(
+                timeout >= MIN_CANCEL_ALL_TIMEOUT,
+                "invalid `timeout`! min: {MIN_CANCEL_ALL_TIMEOUT:?}, found: {timeout:?}"
+            );
+
+            ensure!(
+                timeout <= MAX_CANCEL_ALL_TIMEOUT,
+                "invalid `timeout`! max: {MAX_CANCEL_ALL_TIMEOUT:?}, found: {timeout:?}"
+            );
+
+            let deadline = ctx.block.timestamp + timeout;
+
+            CANCEL_ALL_DEADLINES.save(ctx.storage, ctx.sender, &deadline)?;
+
+            Some(deadline)
+        },
+        None => {
+            CANCEL_ALL_DEADLINES.remove(ctx.storage, ctx.sender)?;
+
+            None
+        },
+    };
+
+    Ok(Response::new().add_event(CancelAllDeadlineSet {
+        user: ctx.sender,
+        deadline,
+    })?)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_primitives::{
+            Addr, Coins, MockContext, Order as IterationOrder, ResultExt, Timestamp,
+        },
+        test_case::test_case,
+    };
+
+    const USER: Addr = Addr::mock(1);
+
+    #[test]
+    fn arming_refreshing_and_disarming() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default())
+            .with_block_timestamp(Timestamp::from_seconds(100));
+
+        cancel_all_after(ctx.as_mutable(), Some(Duration::from_seconds(10))).should_succeed();
+
+        assert_eq!(
+            CANCEL_ALL_DEADLINES.load(&ctx.storage, USER).unwrap(),
+            Timestamp::from_seconds(110)
+        );
+
+        // Refreshing the switch replaces the deadline.
+        ctx = ctx.with_block_timestamp(Timestamp::from_seconds(105));
+
+        cancel_all_after(ctx.as_mutable(), Some(Duration::from_seconds(10))).should_succeed();
+
+        assert_eq!(
+            CANCEL_ALL_DEADLINES.load(&ctx.storage, USER).unwrap(),
+            Timestamp::from_seconds(115)
+        );
+
+        // The old deadline is no longer indexed.
+        assert_eq!(
+            CANCEL_ALL_DEADLINES
+                .idx
+                .deadline
+                .prefix(Timestamp::from_seconds(110))
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .count(),
+            0
+        );
+
+        cancel_all_after(ctx.as_mutable(), None).should_succeed();
+
+        assert!(!CANCEL_ALL_DEADLINES.has(&ctx.storage, USER));
+    }
+
+    #[test_case(1, "invalid `timeout`! min" ; "timeout too short")]
+    #[test_case(172_800, "invalid `timeout`! max" ; "timeout too long")]
+    fn rejecting_invalid_timeout(timeout_secs: u128, error: &str) {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        cancel_all_after(ctx.as_mutable(), Some(Duration::from_seconds(timeout_secs)))
+            .should_fail_with_error(error);
+
+        assert!(!CANCEL_ALL_DEADLINES.has(&ctx.storage, USER));
+    }
+}
@@ -1,14 +1,19 @@
 use {
-    crate::USER_STATES,
-    anyhow::anyhow,
-    dango_order_book::{ConditionalOrderRemoved, PairId, ReasonForOrderRemoval, TriggerDirection},
-    dango_primitives::{MutableCtx, Response},
+    crate::{USER_STATES, order_group},
+    anyhow::{anyhow, ensure},
+    dango_order_book::{
+        ConditionalOrderId, ConditionalOrderRemoved, PairId, ReasonForOrderRemoval,
+        TriggerDirection,
+    },
+    dango_primitives::{Addr, EventBuilder, MutableCtx, Response, StdResult, Storage},
+    dango_types::perps::Position,
 };
 
 pub fn cancel_one_conditional_order(
     ctx: MutableCtx,
     pair_id: PairId,
     trigger_direction: TriggerDirection,
+    order_id: Option<ConditionalOrderId>,
 ) -> anyhow::Result<Response> {
     let mut user_state = USER_STATES.load(ctx.storage, ctx.sender)?;
 
@@ -17,25 +22,43 @@ pub fn cancel_one_conditional_order(
         .get_mut(&pair_id)
         .ok_or_else(|| anyhow!("no position in pair {pair_id}"))?;
 
-    let _order = match trigger_direction {
-        TriggerDirection::Above => position
-            .conditional_order_above
-            .take()
-            .ok_or_else(|| anyhow!("no conditional order above for pair {pair_id}"))?,
-        TriggerDirection::Below => position
-            .conditional_order_below
-            .take()
-            .ok_or_else(|| anyhow!("no conditional order below for pair {pair_id}"))?,
+    let orders = position.conditional_orders_mut(trigger_direction);
+
+    let order_ids = match order_id {
+        Some(order_id) => {
+            ensure!(
+                orders.remove(&order_id).is_some(),
+                "no conditional order with id {order_id} {} for pair {pair_id}",
+                direction_str(trigger_direction)
+            );
+
+            vec![order_id]
+        },
+        None => {
+            ensure!(
+                !orders.is_empty(),
+                "no conditional order {} for pair {pair_id}",
+                direction_str(trigger_direction)
+            );
+
+            std::mem::take(orders).into_keys().collect()
+        },
     };
 
     USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
 
-    Ok(Response::new().add_event(ConditionalOrderRemoved {
-        pair_id,
-        user: ctx.sender,
-        trigger_direction,
-        reason: ReasonForOrderRemoval::Canceled,
-    })?)
+    let events = order_ids
+        .into_iter()
+        .map(|order_id| ConditionalOrderRemoved {
+            order_id,
+            pair_id: pair_id.clone(),
+            user: ctx.sender,
+            trigger_direction,
+            reason: ReasonForOrderRemoval::Canceled,
+        })
+        .collect();
+
+    Ok(Response::new().add_events(on_orders_removed(ctx.storage, events)?)?)
 }
 
 pub fn cancel_conditional_orders_for_pair(
@@ -49,62 +72,75 @@ pub fn cancel_conditional_orders_for_pair(
         .get_mut(&pair_id)
         .ok_or_else(|| anyhow!("no position in pair {pair_id}"))?;
 
-    let had_above = position.conditional_order_above.take().is_some();
-    let had_below = position.conditional_order_below.take().is_some();
+    let events = take_conditional_orders(position, &pair_id, ctx.sender);
 
     USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
 
-    let mut response = Response::new();
-
-    if had_above {
-        response = response.add_event(ConditionalOrderRemoved {
-            pair_id: pair_id.clone(),
-            user: ctx.sender,
-            trigger_direction: TriggerDirection::Above,
-            reason: ReasonForOrderRemoval::Canceled,
-        })?;
-    }
-
-    if had_below {
-        response = response.add_event(ConditionalOrderRemoved {
-            pair_id,
-            user: ctx.sender,
-            trigger_direction: TriggerDirection::Below,
-            reason: ReasonForOrderRemoval::Canceled,
-        })?;
-    }
-
-    Ok(response)
+    Ok(Response::new().add_events(on_orders_removed(ctx.storage, events)?)?)
 }
 
 pub fn cancel_all_conditional_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
     let mut user_state = USER_STATES.load(ctx.storage, ctx.sender)?;
 
-    let mut response = Response::new();
+    let mut events = Vec::new();
 
     for (pair_id, position) in &mut user_state.positions {
-        if let Some(_order) = position.conditional_order_above.take() {
-            response = response.add_event(ConditionalOrderRemoved {
-                pair_id: pair_id.clone(),
-                user: ctx.sender,
-                trigger_direction: TriggerDirection::Above,
-                reason: ReasonForOrderRemoval::Canceled,
-            })?;
-        }
+        events.extend(take_conditional_orders(position, pair_id, ctx.sender));
+    }
+
+    USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+
+    Ok(Response::new().add_events(on_orders_removed(ctx.storage, events)?)?)
+}
+
+/// Collect the removal events, dissolving the order groups the removed orders
+/// belong to along the way.
+fn on_orders_removed(
+    storage: &mut dyn Storage,
+    removed: Vec<ConditionalOrderRemoved>,
+) -> StdResult<EventBuilder> {
+    let mut events = EventBuilder::new();
+
+    for event in removed {
+        let order_id = event.order_id;
+        events.push(event)?;
+        order_group::on_order_removed(storage, order_id, Some(&mut events))?;
+    }
+
+    Ok(events)
+}
 
-        if let Some(_order) = position.conditional_order_below.take() {
-            response = response.add_event(ConditionalOrderRemoved {
+/// Remove all conditional orders from the position, returning a removal event
+/// for each of them.
+fn take_conditional_orders(
+    position: &mut Position,
+    pair_id: &PairId,
+    user: Addr,
+) -> Vec<ConditionalOrderRemoved> {
+    let mut events = Vec::new();
+
+    for trigger_direction in [TriggerDirection::Above, TriggerDirection::Below] {
+        for order_id in
+            std::mem::take(position.conditional_orders_mut(trigger_direction)).into_keys()
+        {
+            events.push(ConditionalOrderRemoved {
+                order_id,
                 pair_id: pair_id.clone(),
-                user: ctx.sender,
-                trigger_direction: TriggerDirection::Below,
+                user,
+                trigger_direction,
                 reason: ReasonForOrderRemoval::Canceled,
-            })?;
+            });
         }
     }
 
-    USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+    events
+}
 
-    Ok(response)
+fn direction_str(trigger_direction: TriggerDirection) -> &'static str {
+    match trigger_direction {
+        TriggerDirection::Above => "above",
+        TriggerDirection::Below => "below",
+    }
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -139,22 +175,22 @@ mod tests {
             size: Quantity::new_int(size),
             entry_price: UsdPrice::new_int(2_000),
             entry_funding_per_unit: FundingPerUnit::ZERO,
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
         }
     }
 
     fn long_position_with_orders(
         size: i128,
-        above: Option<ConditionalOrder>,
-        below: Option<ConditionalOrder>,
+        above: Vec<ConditionalOrder>,
+        below: Vec<ConditionalOrder>,
     ) -> Position {
         Position {
             size: Quantity::new_int(size),
             entry_price: UsdPrice::new_int(2_000),
             entry_funding_per_unit: FundingPerUnit::ZERO,
-            conditional_order_above: above,
-            conditional_order_below: below,
+            conditional_orders_above: above.into_iter().map(|o| (o.order_id, o)).collect(),
+            conditional_orders_below: below.into_iter().map(|o| (o.order_id, o)).collect(),
         }
     }
 
@@ -164,6 +200,8 @@ mod tests {
             size: Some(Quantity::new_int(size)),
             trigger_price: UsdPrice::new_int(trigger_price),
             max_slippage: Dimensionless::new_percent(1),
+            limit_price: None,
+            trailing_offset: None,
         }
     }
 
@@ -190,16 +228,16 @@ mod tests {
             .with_funds(Coins::default());
 
         let position =
-            long_position_with_orders(10, Some(make_conditional_order(1, -5, 2_500)), None);
+            long_position_with_orders(10, vec![make_conditional_order(1, -5, 2_500)], vec![]);
         init_storage(&mut ctx.storage, user_state_with_position(position));
 
-        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Above)
+        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Above, None)
             .should_succeed();
 
         // Order removed from position.
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_above.is_none());
+        assert!(position.conditional_orders_above.is_empty());
     }
 
     #[test]
@@ -209,15 +247,15 @@ mod tests {
             .with_funds(Coins::default());
 
         let position =
-            long_position_with_orders(10, None, Some(make_conditional_order(1, -10, 1_800)));
+            long_position_with_orders(10, vec![], vec![make_conditional_order(1, -10, 1_800)]);
         init_storage(&mut ctx.storage, user_state_with_position(position));
 
-        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Below)
+        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Below, None)
             .should_succeed();
 
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_below.is_none());
+        assert!(position.conditional_orders_below.is_empty());
     }
 
     #[test]
@@ -231,7 +269,7 @@ mod tests {
             user_state_with_position(long_position(10)),
         );
 
-        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Above)
+        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Above, None)
             .should_fail_with_error("no conditional order above");
     }
 
@@ -243,8 +281,8 @@ mod tests {
 
         let position = long_position_with_orders(
             10,
-            Some(make_conditional_order(1, -5, 2_500)),
-            Some(make_conditional_order(2, -10, 1_800)),
+            vec![make_conditional_order(1, -5, 2_500)],
+            vec![make_conditional_order(2, -10, 1_800)],
         );
         init_storage(&mut ctx.storage, user_state_with_position(position));
 
@@ -253,8 +291,8 @@ mod tests {
         // All orders removed.
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_above.is_none());
-        assert!(position.conditional_order_below.is_none());
+        assert!(position.conditional_orders_above.is_empty());
+        assert!(position.conditional_orders_below.is_empty());
     }
 
     #[test]
@@ -269,13 +307,13 @@ mod tests {
             pair_id(),
             long_position_with_orders(
                 10,
-                Some(make_conditional_order(1, -5, 2_500)),
-                Some(make_conditional_order(2, -10, 1_800)),
+                vec![make_conditional_order(1, -5, 2_500)],
+                vec![make_conditional_order(2, -10, 1_800)],
             ),
         );
         positions.insert(
             pair_id_2(),
-            long_position_with_orders(5, Some(make_conditional_order(3, -3, 50_000)), None),
+            long_position_with_orders(5, vec![make_conditional_order(3, -3, 50_000)], vec![]),
         );
 
         let user_state = UserState {
@@ -292,19 +330,15 @@ mod tests {
 
         // pair_id orders cleared.
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_above.is_none());
-        assert!(position.conditional_order_below.is_none());
+        assert!(position.conditional_orders_above.is_empty());
+        assert!(position.conditional_orders_below.is_empty());
 
         // pair_id_2 order still exists.
         let position_2 = user_state.positions.get(&pair_id_2()).unwrap();
-        assert!(position_2.conditional_order_above.is_some());
-        assert_eq!(
+        assert!(
             position_2
-                .conditional_order_above
-                .as_ref()
-                .unwrap()
-                .order_id,
-            Uint64::new(3)
+                .conditional_orders_above
+                .contains_key(&Uint64::new(3))
         );
     }
 
@@ -322,4 +356,69 @@ mod tests {
         // No conditional orders exist — should succeed with no changes.
         cancel_all_conditional_orders(ctx.as_mutable()).should_succeed();
     }
+
+    /// With several orders in the same direction, giving an order ID cancels
+    /// only that order; omitting it cancels all of them.
+    #[test]
+    fn c7_cancel_one_of_many() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        let position = long_position_with_orders(
+            10,
+            vec![
+                make_conditional_order(1, -3, 2_500),
+                make_conditional_order(2, -3, 2_600),
+                make_conditional_order(3, -4, 2_700),
+            ],
+            vec![make_conditional_order(4, -10, 1_800)],
+        );
+        init_storage(&mut ctx.storage, user_state_with_position(position));
+
+        cancel_one_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            TriggerDirection::Above,
+            Some(Uint64::new(2)),
+        )
+        .should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        let position = user_state.positions.get(&pair_id()).unwrap();
+        assert_eq!(
+            position
+                .conditional_orders_above
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![Uint64::new(1), Uint64::new(3)]
+        );
+
+        // The order no longer exists.
+        cancel_one_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            TriggerDirection::Above,
+            Some(Uint64::new(2)),
+        )
+        .should_fail_with_error("no conditional order with id 2 above");
+
+        // The order exists, but not in this direction.
+        cancel_one_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            TriggerDirection::Below,
+            Some(Uint64::new(3)),
+        )
+        .should_fail_with_error("no conditional order with id 3 below");
+
+        cancel_one_conditional_order(ctx.as_mutable(), pair_id(), TriggerDirection::Above, None)
+            .should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        let position = user_state.positions.get(&pair_id()).unwrap();
+        assert!(position.conditional_orders_above.is_empty());
+        assert_eq!(position.conditional_orders_below.len(), 1);
+    }
 }
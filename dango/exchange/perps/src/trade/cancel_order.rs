@@ -1,5 +1,5 @@
 use {
-    crate::{state::PAIR_PARAMS, trade::update_user_state_with},
+    crate::{order_group, state::PAIR_PARAMS, trade::update_user_state_with},
     anyhow::{anyhow, ensure},
     dango_order_book::{
         ASKS, BIDS, ClientOrderId, LimitOrder, OrderId, OrderKey, PairId, ReasonForOrderRemoval,
@@ -64,19 +64,21 @@ pub(crate) fn _cancel_one_order(
 ///   to the user state before saving.
 /// - Remove the order from the `BIDS` or `ASKS` map.
 /// - Remove liquidity depth contributed by this order.
+/// - Dissolve the order group the order belongs to, if any.
 pub(crate) fn compute_cancel_one_order_outcome<F>(
     storage: &mut dyn Storage,
     user_state: &mut UserState,
     order_key: OrderKey,
     order: LimitOrder,
-    events: Option<&mut EventBuilder>,
+    mut events: Option<&mut EventBuilder>,
     reason: ReasonForOrderRemoval,
     pair_param: F,
 ) -> StdResult<()>
 where
     F: FnOnce(&dyn Storage, &PairId) -> StdResult<PairParam>,
 {
-    let (pair_id, ..) = &order_key;
+    let (pair_id, _, order_id) = &order_key;
+    let order_id = *order_id;
     let pair_param = pair_param(storage, pair_id)?;
 
     // Perp-side: release reserved margin and decrement the user's
@@ -92,9 +94,11 @@ where
         &order,
         reason,
         &pair_param.bucket_sizes,
-        events,
+        events.as_deref_mut(),
     )?;
 
+    order_group::on_order_removed(storage, order_id, events)?;
+
     Ok(())
 }
 
@@ -206,27 +210,62 @@ pub fn compute_cancel_all_orders_outcome(
     storage: &mut dyn Storage,
     user: Addr,
     user_state: &UserState,
-    mut events: Option<&mut EventBuilder>,
+    events: Option<&mut EventBuilder>,
+    reason: ReasonForOrderRemoval,
+) -> StdResult<CancelAllOrdersOutcome> {
+    cancel_orders_where(storage, user, user_state, events, reason, |_| true)
+}
+
+/// Same as [`compute_cancel_all_orders_outcome`], but only cancels the user's
+/// resting orders in the given pair.
+pub fn compute_cancel_pair_orders_outcome(
+    storage: &mut dyn Storage,
+    user: Addr,
+    pair_id: &PairId,
+    user_state: &UserState,
+    events: Option<&mut EventBuilder>,
     reason: ReasonForOrderRemoval,
 ) -> StdResult<CancelAllOrdersOutcome> {
+    cancel_orders_where(storage, user, user_state, events, reason, |order_pair_id| {
+        order_pair_id == pair_id
+    })
+}
+
+fn cancel_orders_where<F>(
+    storage: &mut dyn Storage,
+    user: Addr,
+    user_state: &UserState,
+    mut events: Option<&mut EventBuilder>,
+    reason: ReasonForOrderRemoval,
+    pair_filter: F,
+) -> StdResult<CancelAllOrdersOutcome>
+where
+    F: Fn(&PairId) -> bool,
+{
     // Clone the user state and mutate the local copy. On `Err` the clone
     // is dropped with the rest of the call frame; the caller's
     // `&UserState` is never touched.
     let mut user_state = user_state.clone();
 
-    // Collect all orders from the caller.
+    // Collect the caller's orders in the pairs of interest.
     let bids = BIDS
         .idx
         .user
         .prefix(user)
         .range(storage, None, None, IterationOrder::Ascending)
-        .collect::<StdResult<Vec<_>>>()?;
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(key, _)| pair_filter(&key.0))
+        .collect::<Vec<_>>();
     let asks = ASKS
         .idx
         .user
         .prefix(user)
         .range(storage, None, None, IterationOrder::Ascending)
-        .collect::<StdResult<Vec<_>>>()?;
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(key, _)| pair_filter(&key.0))
+        .collect::<Vec<_>>();
 
     // Collect the parameters of all pairs involved, so that we don't need to
     // load it each time we cancel an order (DB read is slow).
@@ -426,8 +465,8 @@ mod tests {
             size: Quantity::new_int(1),
             entry_price: UsdPrice::new_int(50_000),
             entry_funding_per_unit: FundingPerUnit::ZERO,
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
         }
     }
 
@@ -0,0 +1,23 @@
+use {
+    crate::{ORDER_GROUPS, order_group},
+    anyhow::{anyhow, ensure},
+    dango_primitives::{EventBuilder, MutableCtx, Response},
+    dango_types::perps::OrderGroupId,
+};
+
+pub fn cancel_order_group(ctx: MutableCtx, group_id: OrderGroupId) -> anyhow::Result<Response> {
+    let group = ORDER_GROUPS
+        .may_load(ctx.storage, group_id)?
+        .ok_or_else(|| anyhow!("order group not found with id {group_id}"))?;
+
+    ensure!(
+        group.user == ctx.sender,
+        "order group {group_id} doesn't belong to the sender"
+    );
+
+    let mut events = EventBuilder::new();
+
+    order_group::cancel_order_group(ctx.storage, group_id, group, &mut events)?;
+
+    Ok(Response::new().add_events(events)?)
+}
@@ -0,0 +1,209 @@
+use {
+    crate::TWAP_ORDERS,
+    anyhow::{anyhow, ensure},
+    dango_order_book::PairId,
+    dango_primitives::{Addr, MutableCtx, Order as IterationOrder, Response, StdResult, Storage},
+    dango_types::perps::{ReasonForTwapRemoval, TwapOrderId, TwapOrderRemoved},
+};
+
+pub fn cancel_one_twap_order(ctx: MutableCtx, twap_id: TwapOrderId) -> anyhow::Result<Response> {
+    let order = TWAP_ORDERS
+        .may_load(ctx.storage, twap_id)?
+        .ok_or_else(|| anyhow!("twap order not found with id {twap_id}"))?;
+
+    ensure!(
+        order.user == ctx.sender,
+        "twap order {twap_id} doesn't belong to the sender"
+    );
+
+    TWAP_ORDERS.remove(ctx.storage, twap_id)?;
+
+    Ok(Response::new().add_event(TwapOrderRemoved {
+        twap_id,
+        user: order.user,
+        pair_id: order.pair_id,
+        filled_size: order.filled_size,
+        reason: ReasonForTwapRemoval::Canceled,
+    })?)
+}
+
+pub fn cancel_all_twap_orders(ctx: MutableCtx) -> anyhow::Result<Response> {
+    let events = remove_twap_orders(
+        ctx.storage,
+        ctx.sender,
+        |_| true,
+        ReasonForTwapRemoval::Canceled,
+    )?;
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Remove the user's TWAP orders in the pairs matching the filter, returning a
+/// removal event for each of them.
+pub(crate) fn remove_twap_orders<F>(
+    storage: &mut dyn Storage,
+    user: Addr,
+    filter: F,
+    reason: ReasonForTwapRemoval,
+) -> StdResult<Vec<TwapOrderRemoved>>
+where
+    F: Fn(&PairId) -> bool,
+{
+    let orders = TWAP_ORDERS
+        .idx
+        .user
+        .prefix(user)
+        .range(storage, None, None, IterationOrder::Ascending)
+        .filter(|res| {
+            res.as_ref()
+                .map_or(true, |(_, order)| filter(&order.pair_id))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut events = Vec::with_capacity(orders.len());
+
+    for (twap_id, order) in orders {
+        TWAP_ORDERS.remove(storage, twap_id)?;
+
+        events.push(TwapOrderRemoved {
+            twap_id,
+            user,
+            pair_id: order.pair_id,
+            filled_size: order.filled_size,
+            reason,
+        });
+    }
+
+    Ok(events)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_math::Uint64,
+        dango_order_book::{Dimensionless, Quantity},
+        dango_primitives::{Coins, Duration, MockContext, ResultExt, Timestamp},
+        dango_types::perps::TwapOrder,
+    };
+
+    const USER: Addr = Addr::mock(1);
+    const OTHER: Addr = Addr::mock(2);
+
+    fn pair_id() -> PairId {
+        "perp/ethusd".parse().unwrap()
+    }
+
+    fn pair_id_2() -> PairId {
+        "perp/btcusd".parse().unwrap()
+    }
+
+    fn save_twap_order(storage: &mut dyn Storage, twap_id: u64, user: Addr, pair_id: PairId) {
+        TWAP_ORDERS
+            .save(
+                storage,
+                Uint64::new(twap_id),
+                &TwapOrder {
+                    user,
+                    pair_id,
+                    size: Quantity::new_int(10),
+                    filled_size: Quantity::new_int(4),
+                    max_slippage: Dimensionless::new_percent(1),
+                    reduce_only: false,
+                    slice_interval: Duration::from_minutes(1),
+                    remaining_slices: 6,
+                    next_slice_time: Timestamp::from_seconds(100),
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn cancel_one() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        save_twap_order(&mut ctx.storage, 1, USER, pair_id());
+        save_twap_order(&mut ctx.storage, 2, USER, pair_id());
+
+        cancel_one_twap_order(ctx.as_mutable(), Uint64::new(1)).should_succeed();
+
+        assert!(!TWAP_ORDERS.has(&ctx.storage, Uint64::new(1)));
+        assert!(TWAP_ORDERS.has(&ctx.storage, Uint64::new(2)));
+
+        // The order no longer exists.
+        cancel_one_twap_order(ctx.as_mutable(), Uint64::new(1))
+            .should_fail_with_error("twap order not found with id 1");
+    }
+
+    #[test]
+    fn cancel_one_not_owned() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        save_twap_order(&mut ctx.storage, 1, OTHER, pair_id());
+
+        cancel_one_twap_order(ctx.as_mutable(), Uint64::new(1))
+            .should_fail_with_error("twap order 1 doesn't belong to the sender");
+
+        assert!(TWAP_ORDERS.has(&ctx.storage, Uint64::new(1)));
+    }
+
+    #[test]
+    fn cancel_all() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        save_twap_order(&mut ctx.storage, 1, USER, pair_id());
+        save_twap_order(&mut ctx.storage, 2, USER, pair_id_2());
+        save_twap_order(&mut ctx.storage, 3, OTHER, pair_id());
+
+        cancel_all_twap_orders(ctx.as_mutable()).should_succeed();
+
+        // Only the other user's order is left.
+        assert_eq!(
+            TWAP_ORDERS
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap(),
+            vec![Uint64::new(3)]
+        );
+    }
+
+    /// Only the orders in pairs matching the filter are removed, each with a
+    /// removal event carrying the given reason.
+    #[test]
+    fn removing_twap_orders_with_filter() {
+        let mut ctx = MockContext::new();
+
+        save_twap_order(&mut ctx.storage, 1, USER, pair_id());
+        save_twap_order(&mut ctx.storage, 2, USER, pair_id_2());
+
+        let events = remove_twap_orders(
+            &mut ctx.storage,
+            USER,
+            |pair_id| pair_id == &pair_id_2(),
+            ReasonForTwapRemoval::Liquidated,
+        )
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![TwapOrderRemoved {
+                twap_id: Uint64::new(2),
+                user: USER,
+                pair_id: pair_id_2(),
+                filled_size: Quantity::new_int(4),
+                reason: ReasonForTwapRemoval::Liquidated,
+            }]
+        );
+
+        assert!(TWAP_ORDERS.has(&ctx.storage, Uint64::new(1)));
+        assert!(!TWAP_ORDERS.has(&ctx.storage, Uint64::new(2)));
+    }
+}
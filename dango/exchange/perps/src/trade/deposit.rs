@@ -1,5 +1,9 @@
 use {
-    crate::{USER_STATES, state::PARAM, trade::ensure_trading_enabled},
+    crate::{
+        USER_STATES,
+        state::PARAM,
+        trade::{ensure_trading_enabled, load_or_register_account_owner},
+    },
     anyhow::ensure,
     dango_math::IsZero,
     dango_order_book::Quantity,
@@ -37,6 +41,10 @@ pub fn deposit(mut ctx: MutableCtx, to: Option<Addr>) -> anyhow::Result<Response
 
     USER_STATES.save(ctx.storage, to, &user_state)?;
 
+    // Record the account's owner, so that its trading volume counts towards
+    // the owner's fee tier.
+    load_or_register_account_owner(ctx.storage, ctx.querier, to)?;
+
     #[cfg(feature = "tracing")]
     {
         tracing::info!(
@@ -63,15 +71,44 @@ pub fn deposit(mut ctx: MutableCtx, to: Option<Addr>) -> anyhow::Result<Response
 mod tests {
     use {
         super::*,
-        crate::USER_STATES,
+        crate::{ACCOUNT_OWNERS, USER_STATES},
+        dango_account_factory::USERS,
         dango_math::Uint128,
         dango_order_book::UsdValue,
-        dango_primitives::{Addr, Coins, MockContext, ResultExt},
-        dango_types::perps::Param,
+        dango_primitives::{Addr, Coins, MockContext, MockQuerier, ResultExt, btree_map},
+        dango_types::{
+            account_factory::{User, Username},
+            config::{AppAddresses, AppConfig},
+            perps::Param,
+        },
     };
 
     const SENDER: Addr = Addr::mock(1);
     const RECIPIENT: Addr = Addr::mock(2);
+    const ACCOUNT_FACTORY: Addr = Addr::mock(100);
+
+    /// Querier whose account factory knows `SENDER` and `RECIPIENT` as
+    /// accounts owned by user 1.
+    fn querier() -> MockQuerier {
+        MockQuerier::new()
+            .with_app_config(AppConfig {
+                addresses: AppAddresses {
+                    account_factory: ACCOUNT_FACTORY,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap()
+            .with_raw_contract_storage(ACCOUNT_FACTORY, |storage| {
+                let user = User {
+                    index: 1,
+                    name: Username::default_for_index(1),
+                    accounts: btree_map! { 0 => SENDER, 1 => RECIPIENT },
+                    keys: btree_map! {},
+                };
+                USERS.save(storage, 1, &user).unwrap();
+            })
+    }
 
     /// 1 USDC = 1_000_000 base units (6 decimals).
     fn usdc_coins(amount: u128) -> Coins {
@@ -85,6 +122,7 @@ mod tests {
     #[test]
     fn deposit_to_another_account() {
         let mut ctx = MockContext::new()
+            .with_querier(querier())
             .with_sender(SENDER)
             .with_funds(usdc_coins(1_000));
 
@@ -97,6 +135,9 @@ mod tests {
         let recipient_state = USER_STATES.load(&ctx.storage, RECIPIENT).unwrap();
         assert_eq!(recipient_state.margin, UsdValue::new_int(1_000));
 
+        // Recipient's owner should have been recorded.
+        assert_eq!(ACCOUNT_OWNERS.load(&ctx.storage, RECIPIENT).unwrap(), 1);
+
         // Sender should have no state — funds went to the recipient.
         assert!(
             USER_STATES
@@ -0,0 +1,352 @@
+use {
+    crate::{
+        core::{compute_available_margin, compute_isolated_view},
+        querier::NoCachePerpQuerier,
+        state::{PAIR_PARAMS, USER_STATES},
+    },
+    anyhow::{anyhow, ensure},
+    dango_order_book::{ASKS, BIDS, PairId, UsdValue},
+    dango_primitives::{Addr, MutableCtx, Order as IterationOrder, Response, StdResult, Storage},
+    dango_types::perps::{IsolatedMarginAdded, IsolatedMarginRemoved, MarginMode, MarginModeSet},
+};
+
+/// Choose whether the sender's position in the given pair is backed by their
+/// cross margin or by margin allocated to it alone.
+///
+/// The mode can only be changed while the sender has neither a position nor
+/// resting orders in the pair, so no margin ever needs to be moved here.
+///
+/// Mutates: `USER_STATES` (`isolated_margins` entry inserted or removed).
+///
+/// Returns: `Response` with a `MarginModeSet` event.
+pub fn set_margin_mode(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    margin_mode: MarginMode,
+) -> anyhow::Result<Response> {
+    ensure!(
+        PAIR_PARAMS.has(ctx.storage, &pair_id),
+        "pair not found: {pair_id}"
+    );
+
+    let mut user_state = USER_STATES
+        .may_load(ctx.storage, ctx.sender)?
+        .unwrap_or_default();
+
+    ensure!(
+        !user_state.positions.contains_key(&pair_id),
+        "can't change margin mode with an open position in pair {pair_id}"
+    );
+
+    ensure!(
+        !has_resting_orders(ctx.storage, ctx.sender, &pair_id)?,
+        "can't change margin mode with resting orders in pair {pair_id}"
+    );
+
+    match margin_mode {
+        MarginMode::Cross => {
+            // The pair has no position, so its isolated margin has already
+            // been released back to the cross margin and must be zero.
+            if let Some(isolated_margin) = user_state.isolated_margins.remove(&pair_id) {
+                user_state.margin.checked_add_assign(isolated_margin)?;
+            }
+        },
+        MarginMode::Isolated => {
+            user_state
+                .isolated_margins
+                .entry(pair_id.clone())
+                .or_insert(UsdValue::ZERO);
+        },
+    }
+
+    if user_state.is_empty() {
+        USER_STATES.remove(ctx.storage, ctx.sender)?;
+    } else {
+        USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+    }
+
+    Ok(Response::new().add_event(MarginModeSet {
+        user: ctx.sender,
+        pair_id,
+        margin_mode,
+    })?)
+}
+
+/// Move margin from the sender's cross margin into the isolated margin of their
+/// position in the given pair, pushing the position's liquidation price
+/// further away.
+///
+/// The amount is bounded by the sender's available (cross) margin.
+///
+/// Mutates: `USER_STATES` (`margin` decreased, isolated margin increased).
+///
+/// Returns: `Response` with an `IsolatedMarginAdded` event.
+pub fn add_isolated_margin(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    amount: UsdValue,
+) -> anyhow::Result<Response> {
+    ensure!(
+        amount.is_positive(),
+        "can only add positive amount of isolated margin"
+    );
+
+    let perp_querier = NoCachePerpQuerier::new_local(ctx.storage);
+
+    let mut user_state = USER_STATES
+        .may_load(ctx.storage, ctx.sender)?
+        .unwrap_or_default();
+
+    ensure!(
+        user_state.positions.contains_key(&pair_id),
+        "no position in pair {pair_id}"
+    );
+
+    let available = compute_available_margin(&perp_querier, &user_state)?;
+
+    ensure!(
+        amount <= available,
+        "amount ({amount}) exceeds available margin ({available})"
+    );
+
+    let isolated_margin = user_state
+        .isolated_margins
+        .get_mut(&pair_id)
+        .ok_or_else(|| anyhow!("position in pair {pair_id} is not in isolated-margin mode"))?;
+
+    isolated_margin.checked_add_assign(amount)?;
+
+    let isolated_margin = *isolated_margin;
+
+    user_state.margin.checked_sub_assign(amount)?;
+
+    USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+
+    Ok(Response::new().add_event(IsolatedMarginAdded {
+        user: ctx.sender,
+        pair_id,
+        amount,
+        isolated_margin,
+    })?)
+}
+
+/// Move margin from the isolated margin of the sender's position in the given
+/// pair back into their cross margin.
+///
+/// The isolated margin plus the position's unrealized PnL must still cover its
+/// initial margin afterwards, same as a withdrawal from a cross account.
+///
+/// Mutates: `USER_STATES` (isolated margin decreased, `margin` increased).
+///
+/// Returns: `Response` with an `IsolatedMarginRemoved` event.
+pub fn remove_isolated_margin(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    amount: UsdValue,
+) -> anyhow::Result<Response> {
+    ensure!(
+        amount.is_positive(),
+        "can only remove positive amount of isolated margin"
+    );
+
+    let perp_querier = NoCachePerpQuerier::new_local(ctx.storage);
+
+    let mut user_state = USER_STATES
+        .may_load(ctx.storage, ctx.sender)?
+        .unwrap_or_default();
+
+    ensure!(
+        user_state.is_isolated(&pair_id),
+        "pair {pair_id} is not in isolated-margin mode"
+    );
+
+    // Treat the isolated position as its own account: what can be removed is
+    // what that account could withdraw.
+    let available = {
+        let isolated_view = compute_isolated_view(&user_state, &pair_id);
+        compute_available_margin(&perp_querier, &isolated_view)?
+    };
+
+    ensure!(
+        amount <= available,
+        "amount ({amount}) exceeds available isolated margin ({available})"
+    );
+
+    let isolated_margin = user_state.isolated_margins.get_mut(&pair_id).unwrap();
+
+    isolated_margin.checked_sub_assign(amount)?;
+
+    let isolated_margin = *isolated_margin;
+
+    user_state.margin.checked_add_assign(amount)?;
+
+    USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+
+    Ok(Response::new().add_event(IsolatedMarginRemoved {
+        user: ctx.sender,
+        pair_id,
+        amount,
+        isolated_margin,
+    })?)
+}
+
+/// Return whether the user has any resting limit order in the given pair.
+fn has_resting_orders(storage: &dyn Storage, user: Addr, pair_id: &PairId) -> StdResult<bool> {
+    for book in [BIDS, ASKS] {
+        let orders = book
+            .idx
+            .user
+            .prefix(user)
+            .range(storage, None, None, IterationOrder::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        if orders.iter().any(|(key, _)| &key.0 == pair_id) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::state::PAIR_STATES,
+        dango_order_book::{Dimensionless, FundingPerUnit, Quantity, UsdPrice},
+        dango_primitives::{Coins, MockContext, ResultExt},
+        dango_types::perps::{PairParam, PairState, Position, UserState},
+        std::collections::BTreeMap,
+    };
+
+    const USER: Addr = Addr::mock(1);
+
+    fn pair() -> PairId {
+        "perp/btcusd".parse().unwrap()
+    }
+
+    fn setup_pair(storage: &mut dyn Storage) {
+        PAIR_PARAMS
+            .save(
+                storage,
+                &pair(),
+                &PairParam {
+                    initial_margin_ratio: Dimensionless::new_percent(10),
+                    maintenance_margin_ratio: Dimensionless::new_percent(5),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        PAIR_STATES
+            .save(
+                storage,
+                &pair(),
+                &PairState {
+                    index_price: UsdPrice::new_int(50_000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    fn long_one_btc() -> BTreeMap<PairId, Position> {
+        let mut positions = BTreeMap::new();
+        positions.insert(
+            pair(),
+            Position {
+                size: Quantity::new_int(1),
+                entry_price: UsdPrice::new_int(50_000),
+                entry_funding_per_unit: FundingPerUnit::ZERO,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
+            },
+        );
+        positions
+    }
+
+    #[test]
+    fn set_margin_mode_requires_no_position() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        setup_pair(&mut ctx.storage);
+
+        // Switching to isolated with no position creates an empty bucket.
+        set_margin_mode(ctx.as_mutable(), pair(), MarginMode::Isolated).should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        assert!(user_state.is_isolated(&pair()));
+
+        // With an open position, the mode can't be changed.
+        USER_STATES
+            .save(
+                &mut ctx.storage,
+                USER,
+                &UserState {
+                    positions: long_one_btc(),
+                    ..user_state
+                },
+            )
+            .unwrap();
+
+        set_margin_mode(ctx.as_mutable(), pair(), MarginMode::Cross)
+            .should_fail_with_error("can't change margin mode with an open position");
+    }
+
+    #[test]
+    fn add_and_remove_isolated_margin() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        setup_pair(&mut ctx.storage);
+
+        // Long 1 BTC @ $50,000 in isolated mode, with exactly its initial
+        // margin ($5,000) isolated. $10,000 of cross margin on top.
+        let mut isolated_margins = BTreeMap::new();
+        isolated_margins.insert(pair(), UsdValue::new_int(5_000));
+
+        USER_STATES
+            .save(
+                &mut ctx.storage,
+                USER,
+                &UserState {
+                    margin: UsdValue::new_int(10_000),
+                    positions: long_one_btc(),
+                    isolated_margins,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // Can't add more than the available cross margin.
+        add_isolated_margin(ctx.as_mutable(), pair(), UsdValue::new_int(10_001))
+            .should_fail_with_error("exceeds available margin");
+
+        add_isolated_margin(ctx.as_mutable(), pair(), UsdValue::new_int(2_000)).should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        assert_eq!(user_state.margin, UsdValue::new_int(8_000));
+        assert_eq!(
+            user_state.isolated_margins[&pair()],
+            UsdValue::new_int(7_000)
+        );
+
+        // Only the excess over the position's initial margin can be removed.
+        remove_isolated_margin(ctx.as_mutable(), pair(), UsdValue::new_int(2_001))
+            .should_fail_with_error("exceeds available isolated margin");
+
+        remove_isolated_margin(ctx.as_mutable(), pair(), UsdValue::new_int(2_000)).should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        assert_eq!(user_state.margin, UsdValue::new_int(10_000));
+        assert_eq!(
+            user_state.isolated_margins[&pair()],
+            UsdValue::new_int(5_000)
+        );
+    }
+}
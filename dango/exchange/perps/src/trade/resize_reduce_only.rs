@@ -386,8 +386,8 @@ mod tests {
                     size: Quantity::new_int(position),
                     entry_price: UsdPrice::new_int(2_000),
                     entry_funding_per_unit: FundingPerUnit::ZERO,
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: BTreeMap::new(),
+                    conditional_orders_below: BTreeMap::new(),
                 },
             );
         }
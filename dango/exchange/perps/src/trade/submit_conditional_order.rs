@@ -1,12 +1,17 @@
 use {
-    crate::{PAIR_PARAMS, USER_STATES, state::PARAM, trade::ensure_trading_enabled},
+    crate::{
+        MAX_CONDITIONAL_ORDERS_PER_DIRECTION, PAIR_PARAMS, PAIR_STATES, USER_STATES,
+        state::PARAM,
+        trade::{ensure_pair_accepts_order, ensure_trading_enabled},
+    },
     anyhow::{anyhow, ensure},
     dango_math::NumberConst,
     dango_order_book::{
         ConditionalOrder, ConditionalOrderId, ConditionalOrderPlaced, Dimensionless, NEXT_ORDER_ID,
-        PairId, Quantity, TriggerDirection, UsdPrice, validate_slippage,
+        PairId, Quantity, TrailingOffset, TriggerDirection, UsdPrice,
+        compute_trailing_trigger_price, validate_slippage, validate_trailing_offset,
     },
-    dango_primitives::{MutableCtx, Response},
+    dango_primitives::{Addr, EventBuilder, MutableCtx, Response, Storage},
 };
 
 pub fn submit_conditional_order(
@@ -16,14 +21,53 @@ pub fn submit_conditional_order(
     trigger_price: UsdPrice,
     trigger_direction: TriggerDirection,
     max_slippage: Dimensionless,
+    limit_price: Option<UsdPrice>,
+    trailing_offset: Option<TrailingOffset>,
 ) -> anyhow::Result<Response> {
     ensure_trading_enabled(&PARAM.load(ctx.storage)?)?;
 
-    let mut user_state = USER_STATES.load(ctx.storage, ctx.sender)?;
-    let pair_param = PAIR_PARAMS.load(ctx.storage, &pair_id)?;
+    let mut events = EventBuilder::new();
+
+    _submit_conditional_order(
+        ctx.storage,
+        ctx.sender,
+        pair_id,
+        size,
+        trigger_price,
+        trigger_direction,
+        max_slippage,
+        limit_price,
+        trailing_offset,
+        &mut events,
+    )?;
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// Intermediate layer of `submit_conditional_order`: takes individual
+/// components of `MutableCtx`, so that it can also be used to place the
+/// conditional orders of an order group. Returns the ID of the new order.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn _submit_conditional_order(
+    storage: &mut dyn Storage,
+    sender: Addr,
+    pair_id: PairId,
+    size: Option<Quantity>,
+    trigger_price: UsdPrice,
+    trigger_direction: TriggerDirection,
+    max_slippage: Dimensionless,
+    limit_price: Option<UsdPrice>,
+    trailing_offset: Option<TrailingOffset>,
+    events: &mut EventBuilder,
+) -> anyhow::Result<ConditionalOrderId> {
+    let mut user_state = USER_STATES.load(storage, sender)?;
+    let pair_param = PAIR_PARAMS.load(storage, &pair_id)?;
 
     // -------------------------------- Checks ---------------------------------
 
+    // Conditional orders are always reduce-only.
+    ensure_pair_accepts_order(&pair_id, &pair_param, true)?;
+
     ensure!(
         trigger_price.is_positive(),
         "price must be positive: {trigger_price}"
@@ -31,13 +75,41 @@ pub fn submit_conditional_order(
 
     validate_slippage(max_slippage, pair_param.max_market_slippage)?;
 
+    if let Some(limit_price) = limit_price {
+        ensure!(
+            limit_price.is_positive(),
+            "price must be positive: {limit_price}"
+        );
+
+        // Same as for a limit order submitted directly; otherwise the order
+        // would only be rejected once triggered.
+        if pair_param.tick_size.is_non_zero() {
+            ensure!(
+                limit_price.checked_rem(pair_param.tick_size)?.is_zero(),
+                "limit price ({}) is not a multiple of tick size ({})",
+                limit_price,
+                pair_param.tick_size,
+            );
+        }
+    }
+
+    if let Some(trailing_offset) = trailing_offset {
+        ensure!(
+            limit_price.is_none(),
+            "a trailing stop can't have a limit price"
+        );
+
+        validate_trailing_offset(trailing_offset)?;
+    }
+
     // 1. User must have an open position in this pair.
     // 2. If size is specified: sign must oppose position, |size| <= |position.size|.
-    // 3. Must not already have a conditional order of the same direction for this pair.
+    // 3. The position must not already have the max number of conditional
+    //    orders of the same direction.
 
     let position = user_state
         .positions
-        .get(&pair_id)
+        .get_mut(&pair_id)
         .ok_or_else(|| anyhow!("no position in pair {pair_id}"))?;
 
     if let Some(size) = size {
@@ -48,40 +120,77 @@ pub fn submit_conditional_order(
         );
     }
 
-    // ----------------------------- State changes -----------------------------
+    // A stop-limit order closes the position, so it sells a long position and
+    // buys a short one. Its limit price must not be worse than the trigger
+    // price for the other side, or the order may rest without ever filling
+    // once triggered.
+    if let Some(limit_price) = limit_price {
+        if position.size.is_positive() {
+            ensure!(
+                limit_price <= trigger_price,
+                "limit price ({limit_price}) of a sell stop-limit order can't be above the trigger price ({trigger_price})"
+            );
+        } else {
+            ensure!(
+                limit_price >= trigger_price,
+                "limit price ({limit_price}) of a buy stop-limit order can't be below the trigger price ({trigger_price})"
+            );
+        }
+    }
 
-    // Assign order ID and increment.
-    let order_id = NEXT_ORDER_ID.load(ctx.storage)?;
-    NEXT_ORDER_ID.save(ctx.storage, &(order_id + ConditionalOrderId::ONE))?;
+    ensure!(
+        position.conditional_orders(trigger_direction).len() < MAX_CONDITIONAL_ORDERS_PER_DIRECTION,
+        "too many conditional orders in pair {pair_id}! max allowed: {MAX_CONDITIONAL_ORDERS_PER_DIRECTION}"
+    );
 
-    let conditional_order = ConditionalOrder {
-        order_id,
-        size,
-        trigger_price,
-        max_slippage,
+    // ----------------------------- State changes -----------------------------
+
+    // A trailing stop starts at the given trigger price, or at the offset from
+    // the current oracle price, whichever is tighter.
+    let trigger_price = match trailing_offset {
+        Some(trailing_offset) => {
+            let oracle_price = PAIR_STATES.load(storage, &pair_id)?.index_price;
+            compute_trailing_trigger_price(
+                trigger_price,
+                trigger_direction,
+                trailing_offset,
+                oracle_price,
+            )?
+        },
+        None => trigger_price,
     };
 
-    // Set the field on the position.
-    let position = user_state
-        .positions
-        .get_mut(&pair_id)
-        .ok_or_else(|| anyhow!("no position in pair {pair_id}"))?;
+    // Assign order ID and increment.
+    let order_id = NEXT_ORDER_ID.load(storage)?;
+    NEXT_ORDER_ID.save(storage, &(order_id + ConditionalOrderId::ONE))?;
 
-    match trigger_direction {
-        TriggerDirection::Above => position.conditional_order_above = Some(conditional_order),
-        TriggerDirection::Below => position.conditional_order_below = Some(conditional_order),
-    }
+    position.conditional_orders_mut(trigger_direction).insert(
+        order_id,
+        ConditionalOrder {
+            order_id,
+            size,
+            trigger_price,
+            max_slippage,
+            limit_price,
+            trailing_offset,
+        },
+    );
 
-    USER_STATES.save(ctx.storage, ctx.sender, &user_state)?;
+    USER_STATES.save(storage, sender, &user_state)?;
 
-    Ok(Response::new().add_event(ConditionalOrderPlaced {
+    events.push(ConditionalOrderPlaced {
+        order_id,
         pair_id,
-        user: ctx.sender,
+        user: sender,
         trigger_price,
         trigger_direction,
         size,
         max_slippage,
-    })?)
+        limit_price,
+        trailing_offset,
+    })?;
+
+    Ok(order_id)
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -96,8 +205,10 @@ mod tests {
             Dimensionless, FundingPerUnit, NEXT_ORDER_ID, OrderId, Quantity, TriggerDirection,
             UsdPrice, UsdValue,
         },
-        dango_primitives::{Addr, Coins, MockContext, ResultExt, Storage},
-        dango_types::perps::{PairParam, Param, Position, UserState},
+        dango_primitives::{
+            Addr, Coins, MockContext, Order as IterationOrder, ResultExt, StdResult, Storage,
+        },
+        dango_types::perps::{PairParam, PairState, Param, Position, UserState},
         std::collections::BTreeMap,
     };
 
@@ -119,8 +230,8 @@ mod tests {
             size: Quantity::new_int(size),
             entry_price: UsdPrice::new_int(2_000),
             entry_funding_per_unit: FundingPerUnit::ZERO,
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
         }
     }
 
@@ -129,8 +240,8 @@ mod tests {
             size: Quantity::new_int(-size),
             entry_price: UsdPrice::new_int(2_000),
             entry_funding_per_unit: FundingPerUnit::ZERO,
-            conditional_order_above: None,
-            conditional_order_below: None,
+            conditional_orders_above: BTreeMap::new(),
+            conditional_orders_below: BTreeMap::new(),
         }
     }
 
@@ -156,7 +267,7 @@ mod tests {
     /// Take-profit on a long position: sell 5 of 10 ETH when price rises
     /// above $2,500.
     ///
-    /// Expected: order stored in `conditional_order_above` with correct
+    /// Expected: order stored in `conditional_orders_above` with correct
     /// trigger price, size, and a freshly allocated order ID. The global
     /// `NEXT_ORDER_ID` counter advances by one.
     #[test]
@@ -177,15 +288,17 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
-        // Order stored in position's conditional_order_above field.
+        // Order stored in position's conditional_orders_above field.
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_above.is_some());
+        assert_eq!(position.conditional_orders_above.len(), 1);
 
-        let order = position.conditional_order_above.as_ref().unwrap();
+        let order = position.conditional_orders_above.get(&Uint64::ONE).unwrap();
         assert_eq!(order.order_id, Uint64::ONE);
         assert_eq!(order.size, Some(Quantity::new_int(-5)));
         assert_eq!(order.trigger_price, UsdPrice::new_int(2_500));
@@ -198,7 +311,7 @@ mod tests {
     /// Stop-loss on a long position: sell all 10 ETH when price drops
     /// below $1,800.
     ///
-    /// Expected: order stored in `conditional_order_below`. Mirrors p1 but
+    /// Expected: order stored in `conditional_orders_below`. Mirrors p1 but
     /// for the opposite trigger direction.
     #[test]
     fn p2_valid_sl_on_long() {
@@ -218,25 +331,27 @@ mod tests {
             UsdPrice::new_int(1_800),
             TriggerDirection::Below,
             Dimensionless::new_percent(2),
+            None,
+            None,
         )
         .should_succeed();
 
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_below.is_some());
+        assert_eq!(position.conditional_orders_below.len(), 1);
 
-        let order = position.conditional_order_below.as_ref().unwrap();
+        let order = position.conditional_orders_below.get(&Uint64::ONE).unwrap();
         assert_eq!(order.order_id, Uint64::ONE);
     }
 
     /// Take-profit on a short position: buy 5 of 10 ETH when price drops
     /// below $1,500.
     ///
-    /// Expected: order stored in `conditional_order_below`. For shorts, TP
+    /// Expected: order stored in `conditional_orders_below`. For shorts, TP
     /// triggers *below* (profit when price falls), which is the opposite of
     /// longs.
     ///
-    /// Wrong behavior: storing it in `conditional_order_above` — that would
+    /// Wrong behavior: storing it in `conditional_orders_above` — that would
     /// make it a stop-loss for a short.
     #[test]
     fn p3_valid_tp_on_short() {
@@ -256,14 +371,16 @@ mod tests {
             UsdPrice::new_int(1_500),
             TriggerDirection::Below,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_below.is_some());
+        assert_eq!(position.conditional_orders_below.len(), 1);
 
-        let order = position.conditional_order_below.as_ref().unwrap();
+        let order = position.conditional_orders_below.get(&Uint64::ONE).unwrap();
         assert_eq!(order.order_id, Uint64::ONE);
     }
 
@@ -295,6 +412,8 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_fail_with_error("size must oppose position direction");
     }
@@ -325,12 +444,14 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        let order = position.conditional_order_above.as_ref().unwrap();
+        let order = position.conditional_orders_above.get(&Uint64::ONE).unwrap();
         assert_eq!(order.size, Some(Quantity::new_int(-5)));
     }
 
@@ -363,22 +484,24 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_fail_with_error("no position");
     }
 
     /// Submitting a second conditional order with the same trigger direction
-    /// overwrites the first. The other direction is not affected.
+    /// adds it alongside the first, so that a position can be scaled out of
+    /// in tranches. The other direction is not affected.
     ///
     /// Expected: the second Above order (trigger $3,000, size -3, order_id 2)
-    /// replaces the first (trigger $2,500, size -5, order_id 1). A subsequent
-    /// Below order coexists without disturbing the Above.
+    /// is stored next to the first (trigger $2,500, size -5, order_id 1). A
+    /// subsequent Below order coexists without disturbing the Above ones.
     ///
-    /// Wrong behavior (old): rejecting the second order with "conditional
-    /// order already exists". This forced users to cancel-then-resubmit,
-    /// which is a poor UX and creates a window with no protection.
+    /// Wrong behavior (old): overwriting the first order. This made it
+    /// impossible to take profit at more than one price level.
     #[test]
-    fn p7_overwrite_duplicate_direction() {
+    fn p7_multiple_in_same_direction() {
         let mut ctx = MockContext::new()
             .with_sender(USER)
             .with_funds(Coins::default());
@@ -396,10 +519,12 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
-        // Second Above order for same pair — overwrites the first.
+        // Second Above order for same pair — added next to the first.
         submit_conditional_order(
             ctx.as_mutable(),
             pair_id(),
@@ -407,16 +532,25 @@ mod tests {
             UsdPrice::new_int(3_000),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
-        // Verify the overwrite: trigger_price and order_id reflect the new order.
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        let order = position.conditional_order_above.as_ref().unwrap();
-        assert_eq!(order.order_id, Uint64::new(2));
-        assert_eq!(order.trigger_price, UsdPrice::new_int(3_000));
-        assert_eq!(order.size, Some(Quantity::new_int(-3)));
+        assert_eq!(position.conditional_orders_above.len(), 2);
+
+        let first = position.conditional_orders_above.get(&Uint64::ONE).unwrap();
+        assert_eq!(first.trigger_price, UsdPrice::new_int(2_500));
+        assert_eq!(first.size, Some(Quantity::new_int(-5)));
+
+        let second = position
+            .conditional_orders_above
+            .get(&Uint64::new(2))
+            .unwrap();
+        assert_eq!(second.trigger_price, UsdPrice::new_int(3_000));
+        assert_eq!(second.size, Some(Quantity::new_int(-3)));
 
         // Below order for same pair — should still succeed (different direction).
         submit_conditional_order(
@@ -426,20 +560,16 @@ mod tests {
             UsdPrice::new_int(1_800),
             TriggerDirection::Below,
             Dimensionless::new_percent(2),
+            None,
+            None,
         )
         .should_succeed();
 
         // Verify Above was NOT affected by the Below submission.
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert_eq!(
-            position
-                .conditional_order_above
-                .as_ref()
-                .unwrap()
-                .trigger_price,
-            UsdPrice::new_int(3_000)
-        );
+        assert_eq!(position.conditional_orders_above.len(), 2);
+        assert_eq!(position.conditional_orders_below.len(), 1);
     }
 
     /// A position can hold both an Above and a Below conditional order at
@@ -467,6 +597,8 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_succeed();
 
@@ -478,6 +610,8 @@ mod tests {
             UsdPrice::new_int(1_800),
             TriggerDirection::Below,
             Dimensionless::new_percent(2),
+            None,
+            None,
         )
         .should_succeed();
 
@@ -485,14 +619,14 @@ mod tests {
         let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
 
         let position = user_state.positions.get(&pair_id()).unwrap();
-        assert!(position.conditional_order_above.is_some());
-
-        let above = position.conditional_order_above.as_ref().unwrap();
+        let above = position.conditional_orders_above.get(&Uint64::ONE).unwrap();
         assert_eq!(above.order_id, Uint64::ONE);
         assert_eq!(above.trigger_price, UsdPrice::new_int(2_500));
 
-        assert!(position.conditional_order_below.is_some());
-        let below = position.conditional_order_below.as_ref().unwrap();
+        let below = position
+            .conditional_orders_below
+            .get(&Uint64::new(2))
+            .unwrap();
         assert_eq!(below.order_id, Uint64::new(2));
         assert_eq!(below.trigger_price, UsdPrice::new_int(1_800));
     }
@@ -521,6 +655,8 @@ mod tests {
             UsdPrice::new_int(-2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_fail_with_error("price must be positive");
     }
@@ -544,6 +680,8 @@ mod tests {
             UsdPrice::ZERO,
             TriggerDirection::Below,
             Dimensionless::new_percent(1),
+            None,
+            None,
         )
         .should_fail_with_error("price must be positive");
     }
@@ -573,6 +711,8 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_int(-1),
+            None,
+            None,
         )
         .should_fail_with_error("max slippage can't be negative");
     }
@@ -595,6 +735,8 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(100),
+            None,
+            None,
         )
         .should_fail_with_error("max slippage must be less than 1, got");
     }
@@ -617,6 +759,8 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_percent(150),
+            None,
+            None,
         )
         .should_fail_with_error("max slippage must be less than 1, got");
     }
@@ -641,7 +785,299 @@ mod tests {
             UsdPrice::new_int(2_500),
             TriggerDirection::Above,
             Dimensionless::new_permille(110), // 11% > 10% cap
+            None,
+            None,
         )
         .should_fail_with_error("exceeds the pair cap");
     }
+
+    /// A position can have at most `MAX_CONDITIONAL_ORDERS_PER_DIRECTION`
+    /// conditional orders in each direction.
+    #[test]
+    fn p15_reject_too_many_in_same_direction() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(
+            &mut ctx.storage,
+            user_state_with_position(long_position(10)),
+        );
+
+        for i in 0..MAX_CONDITIONAL_ORDERS_PER_DIRECTION {
+            submit_conditional_order(
+                ctx.as_mutable(),
+                pair_id(),
+                Some(Quantity::new_int(-1)),
+                UsdPrice::new_int(2_500 + i as i128),
+                TriggerDirection::Above,
+                Dimensionless::new_percent(1),
+                None,
+                None,
+            )
+            .should_succeed();
+        }
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            Some(Quantity::new_int(-1)),
+            UsdPrice::new_int(3_000),
+            TriggerDirection::Above,
+            Dimensionless::new_percent(1),
+            None,
+            None,
+        )
+        .should_fail_with_error("too many conditional orders");
+
+        // The other direction has its own limit.
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            Some(Quantity::new_int(-1)),
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            None,
+            None,
+        )
+        .should_succeed();
+    }
+
+    /// Stop-limit on a long position: place a sell limit order at $1,790 when
+    /// the price drops below $1,800.
+    #[test]
+    fn p16_valid_stop_limit() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(
+            &mut ctx.storage,
+            user_state_with_position(long_position(10)),
+        );
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(1_790)),
+            None,
+        )
+        .should_succeed();
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        let position = user_state.positions.get(&pair_id()).unwrap();
+        let order = position.conditional_orders_below.get(&Uint64::ONE).unwrap();
+        assert_eq!(order.limit_price, Some(UsdPrice::new_int(1_790)));
+        assert_eq!(order.trailing_offset, None);
+
+        // Limit price must be positive.
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::ZERO),
+            None,
+        )
+        .should_fail_with_error("price must be positive");
+    }
+
+    /// The limit price of a stop-limit order must be a multiple of the tick
+    /// size, and not worse than the trigger price for the closing side.
+    #[test]
+    fn p16_reject_invalid_stop_limit() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(
+            &mut ctx.storage,
+            user_state_with_position(long_position(10)),
+        );
+
+        PAIR_PARAMS
+            .save(
+                &mut ctx.storage,
+                &pair_id(),
+                &PairParam {
+                    tick_size: UsdPrice::new_int(10),
+                    ..test_pair_param()
+                },
+            )
+            .unwrap();
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(1_795)),
+            None,
+        )
+        .should_fail_with_error("is not a multiple of tick size");
+
+        // Selling a long position above the trigger price.
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(1_810)),
+            None,
+        )
+        .should_fail_with_error("of a sell stop-limit order can't be above the trigger price");
+
+        // Buying back a short position below the trigger price.
+        USER_STATES
+            .save(
+                &mut ctx.storage,
+                USER,
+                &user_state_with_position(short_position(10)),
+            )
+            .unwrap();
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(2_200),
+            TriggerDirection::Above,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(2_190)),
+            None,
+        )
+        .should_fail_with_error("of a buy stop-limit order can't be below the trigger price");
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(2_200),
+            TriggerDirection::Above,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(2_210)),
+            None,
+        )
+        .should_succeed();
+    }
+
+    /// Trailing stop on a long position, with the oracle price at $2,000.
+    ///
+    /// Expected: with a $100 offset, the trigger starts at $1,900 even though
+    /// a looser $1,500 was requested; with a 50% offset, the requested $1,500
+    /// is tighter than $1,000 and is kept.
+    #[test]
+    fn p17_valid_trailing_stop() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(
+            &mut ctx.storage,
+            user_state_with_position(long_position(10)),
+        );
+
+        PAIR_STATES
+            .save(
+                &mut ctx.storage,
+                &pair_id(),
+                &PairState {
+                    index_price: UsdPrice::new_int(2_000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        for trailing_offset in [
+            TrailingOffset::Absolute(UsdPrice::new_int(100)),
+            TrailingOffset::Percent(Dimensionless::new_percent(50)),
+        ] {
+            submit_conditional_order(
+                ctx.as_mutable(),
+                pair_id(),
+                None,
+                UsdPrice::new_int(1_500),
+                TriggerDirection::Below,
+                Dimensionless::new_percent(1),
+                None,
+                Some(trailing_offset),
+            )
+            .should_succeed();
+        }
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        let position = user_state.positions.get(&pair_id()).unwrap();
+
+        let order = position.conditional_orders_below.get(&Uint64::ONE).unwrap();
+        assert_eq!(order.trigger_price, UsdPrice::new_int(1_900));
+
+        let order = position
+            .conditional_orders_below
+            .get(&Uint64::new(2))
+            .unwrap();
+        assert_eq!(order.trigger_price, UsdPrice::new_int(1_500));
+
+        // Both orders are tracked by the trailing stop index.
+        let trailing = USER_STATES
+            .idx
+            .trailing_conditional_orders
+            .sub_prefix(pair_id())
+            .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            trailing,
+            vec![
+                ((TriggerDirection::Below, Uint64::ONE), USER),
+                ((TriggerDirection::Below, Uint64::new(2)), USER),
+            ]
+        );
+    }
+
+    #[test]
+    fn p18_reject_invalid_trailing_stop() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(
+            &mut ctx.storage,
+            user_state_with_position(long_position(10)),
+        );
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            Some(UsdPrice::new_int(1_790)),
+            Some(TrailingOffset::Absolute(UsdPrice::new_int(100))),
+        )
+        .should_fail_with_error("a trailing stop can't have a limit price");
+
+        submit_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            None,
+            UsdPrice::new_int(1_800),
+            TriggerDirection::Below,
+            Dimensionless::new_percent(1),
+            None,
+            Some(TrailingOffset::Percent(Dimensionless::ONE)),
+        )
+        .should_fail_with_error("trailing offset percent must be between 0 and 1 exclusive");
+    }
 }
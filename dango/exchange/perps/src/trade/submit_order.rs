@@ -2,28 +2,31 @@ use {
     crate::{
         VOLUME_LOOKBACK,
         core::{
-            FillPnl, check_margin, check_oi_constraint, compute_available_margin, compute_notional,
-            compute_required_margin, compute_trading_fee, execute_fill,
+            FillPnl, MakerOrderUpdate, allocate_isolated_margin, check_margin, check_oi_constraint,
+            compute_available_margin, compute_notional, compute_required_margin,
+            compute_trading_fee, execute_fill, margin_for_pair_mut, release_isolated_margin,
         },
+        order_group,
         position_index::{
             PositionIndexUpdate, apply_position_index_updates, compute_position_diff,
         },
         querier::NoCachePerpQuerier,
-        query::query_volume,
+        query::query_fee_tier_volume,
         referral::{FeeCommissionsOutcome, apply_fee_commissions},
         state::{FEE_RATE_OVERRIDES, PAIR_PARAMS, PAIR_STATES, PARAM, STATE, USER_STATES},
-        trade::{ensure_trading_enabled, resize_reduce_only_orders},
+        trade::{ensure_pair_accepts_order, ensure_trading_enabled, resize_reduce_only_orders},
     },
     anyhow::{bail, ensure},
     dango_math::{MathResult, Number, NumberConst},
     dango_order_book::{
         ASKS, BIDS, ChildOrder, ClientOrderId, ConditionalOrder, ConditionalOrderPlaced,
         Dimensionless, FillId, LimitOrder, NEXT_FILL_ID, NEXT_ORDER_ID, OrderId, OrderKind,
-        OrderPersisted, OrderRemoved, PairId, Quantity, RawFill, ReasonForOrderRemoval,
-        RemovedMaker, TimeInForce, TriggerDirection, UsdPrice, UsdValue, WalkBookOutcome, WalkStep,
-        check_minimum_order_size, check_price_band, compute_target_price, decompose_fill,
-        decrease_liquidity_depths, flush_volumes, increase_liquidity_depths, may_invert_price,
-        validate_slippage, walk_book,
+        OrderPersisted, OrderRemoved, OrderResized, PairId, Quantity, RawFill,
+        ReasonForOrderRemoval, RemovedMaker, SelfTrade, SelfTradePrevented,
+        SelfTradePreventionMode, TimeInForce, TriggerDirection, UsdPrice, UsdValue,
+        WalkBookOutcome, WalkStep, check_minimum_order_size, check_price_band,
+        compute_target_price, decompose_fill, decrease_liquidity_depths, flush_volumes,
+        increase_liquidity_depths, may_invert_price, validate_slippage, walk_book,
     },
     dango_primitives::{
         Addr, EventBuilder, MutableCtx, Order as IterationOrder, QuerierWrapper, Response,
@@ -41,6 +44,7 @@ pub fn submit_order(
     reduce_only: bool,
     tp: Option<ChildOrder>,
     sl: Option<ChildOrder>,
+    stp_mode: SelfTradePreventionMode,
 ) -> anyhow::Result<Response> {
     ensure_trading_enabled(&PARAM.load(ctx.storage)?)?;
 
@@ -58,6 +62,7 @@ pub fn submit_order(
         reduce_only,
         tp,
         sl,
+        stp_mode,
         &mut events,
     )?;
 
@@ -86,6 +91,7 @@ pub(crate) fn _submit_order(
     reduce_only: bool,
     tp: Option<ChildOrder>,
     sl: Option<ChildOrder>,
+    stp_mode: SelfTradePreventionMode,
     events: &mut EventBuilder,
 ) -> anyhow::Result<()> {
     #[cfg(feature = "metrics")]
@@ -105,19 +111,7 @@ pub(crate) fn _submit_order(
 
     // --------------------------- 2. Business logic ---------------------------
 
-    let SubmitOrderOutcome {
-        state,
-        pair_state,
-        taker_state,
-        mut maker_states,
-        order_mutations,
-        order_to_store,
-        next_order_id,
-        next_fill_id,
-        index_updates,
-        volumes,
-        fee_breakdowns,
-    } = compute_submit_order_outcome(
+    let outcome = compute_submit_order_outcome(
         storage,
         sender,
         contract,
@@ -134,11 +128,106 @@ pub(crate) fn _submit_order(
         reduce_only,
         tp,
         sl,
+        stp_mode,
         events,
     )?;
 
+    #[cfg(feature = "metrics")]
+    let (long_oi, short_oi) = (outcome.pair_state.long_oi, outcome.pair_state.short_oi);
+
     // ------------------------ 3. Apply state changes -------------------------
 
+    apply_submit_order_outcome(
+        storage,
+        querier,
+        current_time,
+        contract,
+        sender,
+        &pair_id,
+        &param,
+        &pair_param,
+        size,
+        reduce_only,
+        outcome,
+        events,
+    )?;
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(
+            user = %sender,
+            %pair_id,
+            %size,
+            "Order submitted"
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        let pair_label = pair_id.to_string();
+
+        metrics::counter!(
+            crate::metrics::LABEL_ORDERS_SUBMITTED,
+            "pair_id" => pair_label.clone()
+        )
+        .increment(1);
+
+        metrics::gauge!(
+            crate::metrics::LABEL_OPEN_INTEREST_LONG,
+            "pair_id" => pair_label.clone()
+        )
+        .set(long_oi.to_f64());
+
+        metrics::gauge!(
+            crate::metrics::LABEL_OPEN_INTEREST_SHORT,
+            "pair_id" => pair_label.clone()
+        )
+        .set(short_oi.to_f64());
+
+        metrics::histogram!(
+            crate::metrics::LABEL_DURATION_SUBMIT_ORDER,
+            "pair_id" => pair_label
+        )
+        .record(start.elapsed().as_secs_f64());
+    }
+
+    Ok(())
+}
+
+/// Write the outcome of a successful [`compute_submit_order_outcome`] call
+/// back to storage: the contract, pair, and user states, the order book and
+/// its liquidity depths, and the re-sized reduce-only orders of every user
+/// whose position the order moved.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_submit_order_outcome(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    current_time: Timestamp,
+    contract: Addr,
+    sender: Addr,
+    pair_id: &PairId,
+    param: &Param,
+    pair_param: &PairParam,
+    size: Quantity,
+    reduce_only: bool,
+    outcome: SubmitOrderOutcome,
+    events: &mut EventBuilder,
+) -> anyhow::Result<()> {
+    let SubmitOrderOutcome {
+        state,
+        pair_state,
+        taker_state,
+        mut maker_states,
+        order_mutations,
+        maker_order_updates,
+        order_to_store,
+        next_order_id,
+        next_fill_id,
+        index_updates,
+        volumes,
+        fee_breakdowns,
+    } = outcome;
+
     flush_volumes(storage, current_time, &volumes)?;
 
     maker_states.insert(sender, taker_state);
@@ -150,7 +239,7 @@ pub(crate) fn _submit_order(
         querier,
         contract,
         current_time,
-        &param,
+        param,
         &maker_states,
         fee_breakdowns,
         &volumes,
@@ -164,7 +253,7 @@ pub(crate) fn _submit_order(
 
     STATE.save(storage, &state)?;
 
-    PAIR_STATES.save(storage, &pair_id, &pair_state)?;
+    PAIR_STATES.save(storage, pair_id, &pair_state)?;
 
     for (addr, user_state) in &maker_states {
         USER_STATES.save(storage, *addr, user_state)?;
@@ -192,7 +281,7 @@ pub(crate) fn _submit_order(
         // for detail.
         decrease_liquidity_depths(
             storage,
-            &pair_id,
+            pair_id,
             maker_is_bid,
             real_price,
             pre_fill_abs_size,
@@ -203,7 +292,7 @@ pub(crate) fn _submit_order(
             Some(order) => {
                 increase_liquidity_depths(
                     storage,
-                    &pair_id,
+                    pair_id,
                     maker_is_bid,
                     real_price,
                     order.size.checked_abs()?,
@@ -233,7 +322,7 @@ pub(crate) fn _submit_order(
 
         increase_liquidity_depths(
             storage,
-            &pair_id,
+            pair_id,
             is_bid,
             limit_price,
             order.size.checked_abs()?,
@@ -252,6 +341,13 @@ pub(crate) fn _submit_order(
         })?;
     }
 
+    // Resolve or activate the order groups of the makers filled above, then
+    // dissolve those of conditional orders dropped along with a closed or
+    // flipped position. Done before the re-size below, which may cancel some
+    // of the same orders.
+    order_group::on_maker_orders_updated(storage, &maker_order_updates, events)?;
+    order_group::on_positions_updated(storage, &index_updates, events)?;
+
     // ---------- Step 9. Dynamic re-size of reduce-only orders ----------------
     //
     // Every fill in this transaction may have moved a user's position, so each
@@ -274,7 +370,7 @@ pub(crate) fn _submit_order(
     // any other user's state is touched. A reduce-only placement whose
     // sum-clamp leaves the new order no budget evicts it here, which we surface
     // as a rejection (the bail rolls back the whole transaction).
-    let removed = resize_reduce_only_orders(storage, sender, &pair_id, events)?;
+    let removed = resize_reduce_only_orders(storage, sender, pair_id, events)?;
     if let Some(new_order_id) = new_reduce_only_order_id
         && removed.contains(&new_order_id)
     {
@@ -285,46 +381,7 @@ pub(crate) fn _submit_order(
         if *maker == sender {
             continue;
         }
-        resize_reduce_only_orders(storage, *maker, &pair_id, events)?;
-    }
-
-    #[cfg(feature = "tracing")]
-    {
-        tracing::info!(
-            user = %sender,
-            %pair_id,
-            %size,
-            "Order submitted"
-        );
-    }
-
-    #[cfg(feature = "metrics")]
-    {
-        let pair_label = pair_id.to_string();
-
-        metrics::counter!(
-            crate::metrics::LABEL_ORDERS_SUBMITTED,
-            "pair_id" => pair_label.clone()
-        )
-        .increment(1);
-
-        metrics::gauge!(
-            crate::metrics::LABEL_OPEN_INTEREST_LONG,
-            "pair_id" => pair_label.clone()
-        )
-        .set(pair_state.long_oi.to_f64());
-
-        metrics::gauge!(
-            crate::metrics::LABEL_OPEN_INTEREST_SHORT,
-            "pair_id" => pair_label.clone()
-        )
-        .set(pair_state.short_oi.to_f64());
-
-        metrics::histogram!(
-            crate::metrics::LABEL_DURATION_SUBMIT_ORDER,
-            "pair_id" => pair_label
-        )
-        .record(start.elapsed().as_secs_f64());
+        resize_reduce_only_orders(storage, *maker, pair_id, events)?;
     }
 
     Ok(())
@@ -343,6 +400,7 @@ pub struct SubmitOrderOutcome {
     pub taker_state: UserState,
     pub maker_states: BTreeMap<Addr, UserState>,
     pub order_mutations: Vec<(UsdPrice, OrderId, Option<LimitOrder>, Quantity)>,
+    pub maker_order_updates: Vec<(OrderId, MakerOrderUpdate)>,
     pub order_to_store: Option<(UsdPrice, OrderId, LimitOrder)>,
     pub next_order_id: OrderId,
     pub next_fill_id: FillId,
@@ -379,6 +437,7 @@ pub(crate) fn compute_submit_order_outcome(
     reduce_only: bool,
     tp: Option<ChildOrder>,
     sl: Option<ChildOrder>,
+    stp_mode: SelfTradePreventionMode,
     events: &mut EventBuilder,
 ) -> anyhow::Result<SubmitOrderOutcome> {
     // Clone at entry and mutate locals freely. `events` is the one
@@ -393,7 +452,9 @@ pub(crate) fn compute_submit_order_outcome(
     // resting orders cannot walk the band away from the true price.
     let reference_price = pair_state.oracle_price;
 
-    // -------------- Step 0. Validate prices and slippage --------------------
+    // ----------- Step 0. Validate pair status, prices and slippage -----------
+
+    ensure_pair_accepts_order(pair_id, pair_param, reduce_only)?;
 
     match &kind {
         OrderKind::Market { max_slippage } => {
@@ -507,6 +568,7 @@ pub(crate) fn compute_submit_order_outcome(
             taker_state,
             maker_states: BTreeMap::new(),
             order_mutations: Vec::new(),
+            maker_order_updates: Vec::new(),
             order_to_store: Some((stored_price, order_id, order)),
             next_order_id: taker_order_id + OrderId::ONE,
             // Post-only orders cannot match, so no fill id is allocated.
@@ -531,7 +593,7 @@ pub(crate) fn compute_submit_order_outcome(
         taker_rate_override
     } else {
         let volume_since = Some(current_time.saturating_sub(VOLUME_LOOKBACK));
-        let taker_volume = query_volume(storage, taker, volume_since)?;
+        let taker_volume = query_fee_tier_volume(storage, taker, volume_since)?;
         param.taker_fee_rates.resolve(taker_volume)
     };
 
@@ -561,15 +623,18 @@ pub(crate) fn compute_submit_order_outcome(
         taker_state: updated_taker_state,
         maker_states,
         unfilled,
+        self_trade_canceled,
         volumes,
         fee_breakdowns,
         order_mutations,
+        maker_order_updates,
         index_updates,
         next_order_id: updated_next_order_id,
         next_fill_id: updated_next_fill_id,
     } = match_order(
         storage,
         taker,
+        stp_mode,
         contract,
         current_time,
         param,
@@ -594,6 +659,7 @@ pub(crate) fn compute_submit_order_outcome(
         target_price,
         reference_price,
         pair_param.max_limit_price_deviation,
+        pair_param.initial_margin_ratio,
         fillable_size,
         next_order_id,
         next_fill_id,
@@ -670,7 +736,9 @@ pub(crate) fn compute_submit_order_outcome(
     // - the taker now has a position of non-zero size;
     // - the position is of the same direction as the order.
 
-    let had_fills = unfilled != fillable_size;
+    // Size canceled by self-trade prevention was neither filled nor left
+    // unfilled.
+    let had_fills = unfilled.checked_add(self_trade_canceled)? != fillable_size;
 
     if had_fills
         && (tp.is_some() || sl.is_some())
@@ -679,10 +747,10 @@ pub(crate) fn compute_submit_order_outcome(
     {
         let (above, below) = map_child_orders(position.size, &tp, &sl, &mut next_order_id);
 
-        position.conditional_order_above = above;
-        position.conditional_order_below = below;
+        emit_child_order_events(events, pair_id, taker, &above, &below, position.size)?;
 
-        emit_child_order_events(events, pair_id, taker, &tp, &sl, position.size)?;
+        position.conditional_orders_above = above.into_iter().map(|o| (o.order_id, o)).collect();
+        position.conditional_orders_below = below.into_iter().map(|o| (o.order_id, o)).collect();
     }
 
     // `match_order` has already settled fees and PnLs per-fill and
@@ -695,6 +763,7 @@ pub(crate) fn compute_submit_order_outcome(
         taker_state,
         maker_states,
         order_mutations,
+        maker_order_updates,
         order_to_store,
         next_order_id,
         next_fill_id,
@@ -719,9 +788,11 @@ pub struct MatchOrderOutcome {
     pub taker_state: UserState,
     pub maker_states: BTreeMap<Addr, UserState>,
     pub unfilled: Quantity,
+    pub self_trade_canceled: Quantity,
     pub volumes: BTreeMap<Addr, UsdValue>,
     pub fee_breakdowns: BTreeMap<Addr, FeeBreakdown>,
     pub order_mutations: Vec<(UsdPrice, OrderId, Option<LimitOrder>, Quantity)>,
+    pub maker_order_updates: Vec<(OrderId, MakerOrderUpdate)>,
     pub index_updates: Vec<PositionIndexUpdate>,
     pub next_order_id: OrderId,
     pub next_fill_id: FillId,
@@ -736,13 +807,15 @@ pub struct MatchOrderOutcome {
 /// in [`MatchOrderOutcome`]. A failed call drops the locals and leaves
 /// the caller's inputs untouched — see `dango/exchange/perps/purity.md`.
 ///
-/// Self-trade prevention (EXPIRE_MAKER): if a resting order belongs to
-/// the taker, the order is cancelled and the taker continues matching
-/// deeper in the book. This is consistent with Binance's EXPIRE_MAKER
-/// mode: <https://developers.binance.com/docs/binance-spot-api-docs/faqs/stp_faq>
+/// Self-trade prevention: if a resting order belongs to the taker, the
+/// resting order, the taker, or both are canceled in full or in part
+/// according to `stp_mode`, and the taker continues matching deeper in the
+/// book with whatever is left. `unfilled` excludes the size canceled this
+/// way, which is reported separately as `self_trade_canceled`.
 pub fn match_order(
     storage: &dyn Storage,
     taker: Addr,
+    stp_mode: SelfTradePreventionMode,
     contract: Addr,
     current_time: Timestamp,
     param: &Param,
@@ -759,6 +832,7 @@ pub fn match_order(
     target_price: UsdPrice,
     oracle_price: UsdPrice,
     max_limit_price_deviation: Dimensionless,
+    initial_margin_ratio: Dimensionless,
     remaining_size: Quantity,
     mut next_order_id: OrderId,
     mut next_fill_id: FillId,
@@ -789,6 +863,7 @@ pub fn match_order(
     let mut volumes: BTreeMap<Addr, UsdValue> = BTreeMap::new();
     let mut fee_breakdowns: BTreeMap<Addr, FeeBreakdown> = BTreeMap::new();
     let mut order_mutations = Vec::new();
+    let mut maker_order_updates = Vec::new();
     let mut index_updates = Vec::new();
 
     // Walk the book in price-time priority. The walker is generic over
@@ -824,10 +899,12 @@ pub fn match_order(
     let WalkBookOutcome {
         steps,
         remaining_size,
+        self_trade_canceled,
     } = walk_book(
         storage,
         pair_id,
         taker,
+        stp_mode,
         contract,
         taker_is_bid,
         target_price,
@@ -841,12 +918,10 @@ pub fn match_order(
         match step {
             // ---------------------- Walked-past removal ----------------------
             //
-            // Self-trade prevention or out-of-band cancellation. Update the
-            // affected user's state (release reserved margin, decrement
-            // `open_order_count`), record the storage mutation as an
-            // unconditional removal, and push `OrderRemoved` with the reason
-            // the walker supplied so STP and out-of-band cancels stay
-            // distinguishable in the event stream.
+            // Out-of-band cancellation. Update the maker's state (release
+            // reserved margin, decrement `open_order_count`), record the
+            // storage mutation as an unconditional removal, and push
+            // `OrderRemoved` with the reason the walker supplied.
             WalkStep::Removed(RemovedMaker {
                 maker_order_id,
                 maker_addr,
@@ -857,29 +932,23 @@ pub fn match_order(
                 maker_order,
                 reason,
             }) => {
-                // STP touches the taker's own state; out-of-band cancels
-                // touch the maker's state (loaded from `maker_states` /
-                // `USER_STATES` if absent).
-                if maker_addr == taker {
-                    taker_state.open_order_count -= 1;
-                    (taker_state.reserved_margin)
-                        .checked_sub_assign(maker_order.reserved_margin)?;
-                } else {
-                    let maker_state = match maker_states.entry(maker_addr) {
-                        Entry::Vacant(e) => {
-                            let s = USER_STATES
-                                .may_load(storage, maker_addr)?
-                                .unwrap_or_default();
-                            e.insert(s)
-                        },
-                        Entry::Occupied(e) => e.into_mut(),
-                    };
+                // The walker only walks past other users' orders; the
+                // taker's own orders are reported as `SelfTrade`. Load the
+                // maker's state from `maker_states` / `USER_STATES` if absent.
+                let maker_state = match maker_states.entry(maker_addr) {
+                    Entry::Vacant(e) => {
+                        let s = USER_STATES
+                            .may_load(storage, maker_addr)?
+                            .unwrap_or_default();
+                        e.insert(s)
+                    },
+                    Entry::Occupied(e) => e.into_mut(),
+                };
 
-                    maker_state.open_order_count -= 1;
-                    maker_state
-                        .reserved_margin
-                        .checked_sub_assign(maker_order.reserved_margin)?;
-                }
+                maker_state.open_order_count -= 1;
+                maker_state
+                    .reserved_margin
+                    .checked_sub_assign(maker_order.reserved_margin)?;
 
                 order_mutations.push((
                     maker_stored_price,
@@ -888,6 +957,8 @@ pub fn match_order(
                     maker_pre_fill_size,
                 ));
 
+                maker_order_updates.push((maker_order_id, MakerOrderUpdate::Removed));
+
                 events.push(OrderRemoved {
                     order_id: maker_order_id,
                     pair_id: pair_id.clone(),
@@ -897,6 +968,88 @@ pub fn match_order(
                 })?;
             },
 
+            // -------------------- Self-trade prevention ----------------------
+            //
+            // The resting order belongs to the taker, so only the taker's own
+            // state is touched. A resting order canceled in full is removed
+            // like any other walked-past order; one canceled in part is
+            // rewritten with its size and reserved margin decremented, so the
+            // liquidity depth is re-added at the new size when the mutation
+            // is applied.
+            WalkStep::SelfTrade(SelfTrade {
+                maker_order_id,
+                maker_client_order_id,
+                maker_pre_fill_size,
+                maker_stored_price,
+                maker_canceled_size,
+                taker_canceled_size,
+                maker_order,
+            }) => {
+                taker_remaining_size.checked_sub_assign(taker_canceled_size)?;
+
+                if maker_canceled_size == maker_order.size {
+                    taker_state.open_order_count -= 1;
+                    (taker_state.reserved_margin)
+                        .checked_sub_assign(maker_order.reserved_margin)?;
+
+                    order_mutations.push((
+                        maker_stored_price,
+                        maker_order_id,
+                        None,
+                        maker_pre_fill_size,
+                    ));
+
+                    maker_order_updates.push((maker_order_id, MakerOrderUpdate::Removed));
+
+                    events.push(OrderRemoved {
+                        order_id: maker_order_id,
+                        pair_id: pair_id.clone(),
+                        user: taker,
+                        reason: ReasonForOrderRemoval::SelfTradePrevention,
+                        client_order_id: maker_client_order_id,
+                    })?;
+                } else if maker_canceled_size.is_non_zero() {
+                    let margin_to_release = (maker_order.reserved_margin)
+                        .checked_mul(maker_canceled_size)?
+                        .checked_div(maker_order.size)?;
+
+                    (taker_state.reserved_margin).checked_sub_assign(margin_to_release)?;
+
+                    let old_size = maker_order.size;
+                    let mut updated_maker_order = maker_order;
+                    updated_maker_order
+                        .reserved_margin
+                        .checked_sub_assign(margin_to_release)?;
+                    updated_maker_order.size = old_size.checked_sub(maker_canceled_size)?;
+
+                    events.push(OrderResized {
+                        order_id: maker_order_id,
+                        pair_id: pair_id.clone(),
+                        user: taker,
+                        old_size,
+                        new_size: updated_maker_order.size,
+                        client_order_id: maker_client_order_id,
+                    })?;
+
+                    order_mutations.push((
+                        maker_stored_price,
+                        maker_order_id,
+                        Some(updated_maker_order),
+                        maker_pre_fill_size,
+                    ));
+                }
+
+                events.push(SelfTradePrevented {
+                    pair_id: pair_id.clone(),
+                    user: taker,
+                    mode: stp_mode,
+                    taker_order_id,
+                    maker_order_id,
+                    taker_canceled_size,
+                    maker_canceled_size,
+                })?;
+            },
+
             // ---------------------- Settle one fill --------------------------
             WalkStep::Fill(RawFill {
                 maker_order_id,
@@ -939,6 +1092,7 @@ pub fn match_order(
                     taker_fill_size,
                     fill_price,
                     taker_fee_rate,
+                    initial_margin_ratio,
                     Some((
                         events,
                         taker_order_id,
@@ -981,7 +1135,7 @@ pub fn match_order(
                     maker_rate_override
                 } else {
                     let volume_since = Some(current_time.saturating_sub(VOLUME_LOOKBACK));
-                    let maker_volume = query_volume(storage, maker_user, volume_since)?;
+                    let maker_volume = query_fee_tier_volume(storage, maker_user, volume_since)?;
                     param.maker_fee_rates.resolve(maker_volume)
                 };
 
@@ -1006,6 +1160,7 @@ pub fn match_order(
                     maker_fill_size,
                     fill_price,
                     maker_fee_rate,
+                    initial_margin_ratio,
                     Some((
                         events,
                         maker_order_id,
@@ -1051,6 +1206,7 @@ pub fn match_order(
                         contract,
                         param,
                         &mut state,
+                        pair_id,
                         taker,
                         &mut taker_state,
                         taker_settlement.pnl.total()?,
@@ -1083,17 +1239,19 @@ pub fn match_order(
                         &mut next_order_id,
                     );
 
-                    maker_pos.conditional_order_above = above;
-                    maker_pos.conditional_order_below = below;
-
                     emit_child_order_events(
                         events,
                         pair_id,
                         maker_user,
-                        &maker_order.tp,
-                        &maker_order.sl,
+                        &above,
+                        &below,
                         maker_pos.size,
                     )?;
+
+                    maker_pos.conditional_orders_above =
+                        above.into_iter().map(|o| (o.order_id, o)).collect();
+                    maker_pos.conditional_orders_below =
+                        below.into_iter().map(|o| (o.order_id, o)).collect();
                 }
 
                 // ------------ Update maker's order and user state ------------
@@ -1115,6 +1273,11 @@ pub fn match_order(
                     .reserved_margin
                     .checked_sub_assign(margin_to_release)?;
 
+                maker_order_updates.push((
+                    maker_order_id,
+                    MakerOrderUpdate::from_fill(maker_post_fill_size),
+                ));
+
                 if maker_post_fill_size.is_zero() {
                     maker_state.open_order_count -= 1;
 
@@ -1178,9 +1341,11 @@ pub fn match_order(
         taker_state,
         maker_states,
         unfilled: remaining_size,
+        self_trade_canceled,
         volumes,
         fee_breakdowns,
         order_mutations,
+        maker_order_updates,
         index_updates,
         next_order_id,
         next_fill_id,
@@ -1207,6 +1372,9 @@ pub struct FillSettlement {
 ///
 /// - `pair_state.long_oi` / `pair_state.short_oi` — updated by `execute_fill`.
 /// - `user_state.positions` — opened / closed / flipped by `execute_fill`.
+/// - `user_state.margin` / `user_state.isolated_margins` — if the pair is in
+///   isolated-margin mode, the initial margin of the opening portion is moved
+///   from the former into the latter.
 /// - `events` — `OrderFilled` event pushed (if `Some`).
 ///
 /// Returns: per-fill `pnl`, `fee`, and `volume` for `user`. The caller
@@ -1221,6 +1389,7 @@ pub fn settle_fill(
     fill_size: Quantity,
     fill_price: UsdPrice,
     fee_rate: Dimensionless,
+    initial_margin_ratio: Dimensionless,
     events: Option<(
         &mut EventBuilder,
         OrderId,
@@ -1245,6 +1414,14 @@ pub fn settle_fill(
         pair_id, pair_state, user_state, fill_price, closing, opening,
     )?;
 
+    allocate_isolated_margin(
+        user_state,
+        pair_id,
+        opening,
+        fill_price,
+        initial_margin_ratio,
+    )?;
+
     // The vault is exempt from trading fees.
     let fee = if user != contract {
         compute_trading_fee(fill_size, fill_price, fee_rate)?
@@ -1382,10 +1559,15 @@ pub fn merge_fee_breakdown(
 /// - `maker_state.margin` — adjusted by `maker_pnl` and `−maker_fee`.
 ///   When `maker == contract`, also credited with `vault_fee`.
 /// - `vault_state.margin` (when `Some`) — credited with `vault_fee`.
+///
+/// If the taker's or maker's position in the pair is in isolated-margin mode,
+/// their PnL and fee go to the isolated margin instead of `margin`. If the fill
+/// fully closed such a position, the isolated margin is returned to `margin`.
 pub fn settle_pnls(
     contract: Addr,
     param: &Param,
     state: &mut State,
+    pair_id: &PairId,
     taker: Addr,
     taker_state: &mut UserState,
     taker_pnl: UsdValue,
@@ -1419,10 +1601,10 @@ pub fn settle_pnls(
 
     // Fee-side margin adjustments. Vault is fee-exempt on its own fills.
     if taker != contract && !taker_fee.is_zero() {
-        taker_state.margin.checked_sub_assign(taker_fee)?;
+        margin_for_pair_mut(taker_state, pair_id).checked_sub_assign(taker_fee)?;
     }
     if maker != contract && !maker_fee.is_zero() {
-        maker_state.margin.checked_sub_assign(maker_fee)?;
+        margin_for_pair_mut(maker_state, pair_id).checked_sub_assign(maker_fee)?;
     }
 
     // Vault receives its cut. Route it to whichever state corresponds
@@ -1436,12 +1618,15 @@ pub fn settle_pnls(
 
     // PnL adjustments.
     if !taker_pnl.is_zero() {
-        taker_state.margin.checked_add_assign(taker_pnl)?;
+        margin_for_pair_mut(taker_state, pair_id).checked_add_assign(taker_pnl)?;
     }
     if !maker_pnl.is_zero() {
-        maker_state.margin.checked_add_assign(maker_pnl)?;
+        margin_for_pair_mut(maker_state, pair_id).checked_add_assign(maker_pnl)?;
     }
 
+    release_isolated_margin(taker_state, pair_id)?;
+    release_isolated_margin(maker_state, pair_id)?;
+
     // Per-party FeeBreakdown. When both sides pay positive fees, the taker
     // takes a weighted slice and the maker the exact residual so the
     // breakdowns sum to `protocol_fee` and `vault_fee` precisely. When only
@@ -1653,6 +1838,9 @@ fn store_limit_order(
 ///
 /// - Long positions: TP → Above, SL → Below
 /// - Short positions: TP → Below, SL → Above
+///
+/// The resulting orders replace all existing conditional orders of the
+/// position.
 fn map_child_orders(
     position_size: Quantity,
     tp: &Option<ChildOrder>,
@@ -1668,6 +1856,8 @@ fn map_child_orders(
             size: child.size,
             trigger_price: child.trigger_price,
             max_slippage: child.max_slippage,
+            limit_price: None,
+            trailing_offset: None,
         }
     };
 
@@ -1690,49 +1880,42 @@ fn map_child_orders(
     (above, below)
 }
 
-/// Emit `ConditionalOrderPlaced` events for child orders that were applied.
+/// Emit `ConditionalOrderPlaced` events for child orders that were applied,
+/// TP first.
 fn emit_child_order_events(
     events: &mut EventBuilder,
     pair_id: &PairId,
     user: Addr,
-    tp: &Option<ChildOrder>,
-    sl: &Option<ChildOrder>,
+    above: &Option<ConditionalOrder>,
+    below: &Option<ConditionalOrder>,
     position_size: Quantity,
 ) -> anyhow::Result<()> {
-    let is_long = position_size.is_positive();
-
-    if let Some(child) = tp {
-        let direction = if is_long {
-            TriggerDirection::Above
-        } else {
-            TriggerDirection::Below
-        };
-
-        events.push(ConditionalOrderPlaced {
-            pair_id: pair_id.clone(),
-            user,
-            trigger_price: child.trigger_price,
-            trigger_direction: direction,
-            size: child.size,
-            max_slippage: child.max_slippage,
-        })?;
-    }
-
-    if let Some(child) = sl {
-        let direction = if is_long {
-            TriggerDirection::Below
-        } else {
-            TriggerDirection::Above
-        };
+    let (tp, sl) = if position_size.is_positive() {
+        (
+            (above, TriggerDirection::Above),
+            (below, TriggerDirection::Below),
+        )
+    } else {
+        (
+            (below, TriggerDirection::Below),
+            (above, TriggerDirection::Above),
+        )
+    };
 
-        events.push(ConditionalOrderPlaced {
-            pair_id: pair_id.clone(),
-            user,
-            trigger_price: child.trigger_price,
-            trigger_direction: direction,
-            size: child.size,
-            max_slippage: child.max_slippage,
-        })?;
+    for (order, trigger_direction) in [tp, sl] {
+        if let Some(order) = order {
+            events.push(ConditionalOrderPlaced {
+                order_id: order.order_id,
+                pair_id: pair_id.clone(),
+                user,
+                trigger_price: order.trigger_price,
+                trigger_direction,
+                size: order.size,
+                max_slippage: order.max_slippage,
+                limit_price: order.limit_price,
+                trailing_offset: order.trailing_offset,
+            })?;
+        }
     }
 
     Ok(())
@@ -1748,7 +1931,7 @@ mod tests {
         dango_math::Uint64,
         dango_order_book::{Dimensionless, FundingPerUnit},
         dango_primitives::{Coins, EventName, JsonDeExt, MockContext, ResultExt, Timestamp},
-        dango_types::perps::{Position, RateSchedule},
+        dango_types::perps::{PairStatus, Position, RateSchedule},
     };
 
     const CONTRACT: Addr = Addr::mock(0);
@@ -1908,6 +2091,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -1971,6 +2155,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2019,6 +2204,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -2075,6 +2261,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2130,6 +2317,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2189,6 +2377,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2246,6 +2435,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2303,6 +2493,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -2340,8 +2531,8 @@ mod tests {
                 size: Quantity::new_int(5),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
 
@@ -2369,6 +2560,7 @@ mod tests {
             true,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2415,6 +2607,7 @@ mod tests {
             true,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -2470,6 +2663,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2530,6 +2724,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2587,6 +2782,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2649,6 +2845,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -2697,6 +2894,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -2753,6 +2951,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2818,6 +3017,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2869,6 +3069,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -2972,6 +3173,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -3070,6 +3272,7 @@ mod tests {
                 false,
                 None,
                 None,
+                SelfTradePreventionMode::ExpireMaker,
                 &mut EventBuilder::new(),
             )
             .unwrap();
@@ -3128,6 +3331,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -3228,6 +3432,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -3349,6 +3554,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -3404,6 +3610,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -3441,6 +3648,7 @@ mod tests {
                 CONTRACT,
                 param,
                 state,
+                &pair_id(),
                 taker,
                 taker_state,
                 taker_pnl,
@@ -3459,6 +3667,7 @@ mod tests {
                 CONTRACT,
                 param,
                 state,
+                &pair_id(),
                 taker,
                 taker_state,
                 taker_pnl,
@@ -4178,6 +4387,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4296,6 +4506,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4368,6 +4579,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4465,6 +4677,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4531,6 +4744,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4591,6 +4805,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4643,6 +4858,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -4688,6 +4904,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -4739,6 +4956,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4790,6 +5008,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -4840,6 +5059,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4873,8 +5093,8 @@ mod tests {
                 size: Quantity::new_int(5),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
 
@@ -4904,6 +5124,7 @@ mod tests {
             true,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -4962,6 +5183,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         );
 
@@ -5027,6 +5249,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5061,6 +5284,172 @@ mod tests {
         assert_eq!(maker_states[&MAKER_A].open_order_count, 0);
     }
 
+    /// Submit a market buy of `size` for TAKER, who has a resting ask of 10 @
+    /// $50,000 (order_id 100) in front of MAKER_A's ask of 10 @ $50,100
+    /// (order_id 101). Returns the outcome, and the `SelfTradePrevented` and
+    /// `OrderResized` events emitted.
+    fn submit_against_own_ask(
+        stp_mode: SelfTradePreventionMode,
+        size: i128,
+    ) -> (
+        SubmitOrderOutcome,
+        Vec<SelfTradePrevented>,
+        Vec<OrderResized>,
+    ) {
+        let mut ctx = MockContext::new()
+            .with_sender(TAKER)
+            .with_funds(Coins::default());
+
+        setup_storage(&mut ctx.storage);
+        place_ask(&mut ctx.storage, TAKER, 50_000, 10, 100);
+        place_ask(&mut ctx.storage, MAKER_A, 50_100, 10, 101);
+
+        let param = test_param();
+        let pair_param = test_pair_param();
+        let pair_state = PAIR_STATES.load(&ctx.storage, &pair_id()).unwrap();
+        let mut taker_state = USER_STATES.load(&ctx.storage, TAKER).unwrap();
+        taker_state.margin = LARGE_COLLATERAL;
+
+        let mut events = EventBuilder::new();
+
+        let outcome = compute_submit_order_outcome(
+            &ctx.storage,
+            TAKER,
+            CONTRACT,
+            Timestamp::ZERO,
+            &param,
+            &State::default(),
+            &pair_id(),
+            &pair_param,
+            &pair_state,
+            &taker_state,
+            UsdPrice::new_int(50_100),
+            Quantity::new_int(size),
+            OrderKind::Market {
+                max_slippage: Dimensionless::new_permille(100), // 10%
+            },
+            false,
+            None,
+            None,
+            stp_mode,
+            &mut events,
+        )
+        .unwrap();
+
+        let events: Vec<_> = events.into_iter().collect();
+
+        let prevented = events
+            .iter()
+            .filter(|e| e.ty == SelfTradePrevented::EVENT_NAME)
+            .map(|e| e.data.clone().deserialize_json().unwrap())
+            .collect();
+
+        let resized = events
+            .iter()
+            .filter(|e| e.ty == OrderResized::EVENT_NAME)
+            .map(|e| e.data.clone().deserialize_json().unwrap())
+            .collect();
+
+        (outcome, prevented, resized)
+    }
+
+    #[test]
+    fn self_trade_prevention_expire_taker() {
+        let (outcome, prevented, _) =
+            submit_against_own_ask(SelfTradePreventionMode::ExpireTaker, 10);
+
+        // The resting ask is left in place, with its reserved margin.
+        assert!(outcome.order_mutations.is_empty());
+        assert_eq!(outcome.taker_state.open_order_count, 1);
+        assert_eq!(
+            outcome.taker_state.reserved_margin,
+            UsdValue::new_int(25_000)
+        );
+
+        // The taker is canceled in full before reaching MAKER_A.
+        assert!(outcome.taker_state.positions.is_empty());
+        assert!(outcome.order_to_store.is_none());
+
+        assert_eq!(prevented.len(), 1);
+        assert_eq!(prevented[0].maker_order_id, Uint64::new(100));
+        assert_eq!(prevented[0].taker_canceled_size, Quantity::new_int(10));
+        assert_eq!(prevented[0].maker_canceled_size, Quantity::ZERO);
+    }
+
+    #[test]
+    fn self_trade_prevention_expire_both() {
+        let (outcome, prevented, _) =
+            submit_against_own_ask(SelfTradePreventionMode::ExpireBoth, 10);
+
+        // The resting ask is removed, releasing its reserved margin.
+        assert_eq!(outcome.order_mutations.len(), 1);
+        assert!(outcome.order_mutations[0].2.is_none());
+        assert_eq!(outcome.taker_state.open_order_count, 0);
+        assert_eq!(outcome.taker_state.reserved_margin, UsdValue::ZERO);
+
+        // The taker is canceled as well.
+        assert!(outcome.taker_state.positions.is_empty());
+
+        assert_eq!(prevented.len(), 1);
+        assert_eq!(prevented[0].taker_canceled_size, Quantity::new_int(10));
+        assert_eq!(prevented[0].maker_canceled_size, Quantity::new_int(-10));
+    }
+
+    /// The resting ask is smaller than the taker: it's canceled in full, and
+    /// the taker, decremented by the same size, fills the rest against MAKER_A.
+    #[test]
+    fn self_trade_prevention_decrement_and_cancel_maker() {
+        let (outcome, prevented, _) =
+            submit_against_own_ask(SelfTradePreventionMode::DecrementAndCancel, 15);
+
+        assert_eq!(outcome.order_mutations.len(), 2);
+        assert!(outcome.order_mutations[0].2.is_none());
+        assert_eq!(outcome.taker_state.open_order_count, 0);
+
+        let pos = outcome.taker_state.positions.get(&pair_id()).unwrap();
+        assert_eq!(pos.size, Quantity::new_int(5));
+        assert_eq!(pos.entry_price, UsdPrice::new_int(50_100));
+
+        let maker_a_order = outcome.order_mutations[1].2.as_ref().unwrap();
+        assert_eq!(maker_a_order.size, Quantity::new_int(-5));
+
+        assert_eq!(prevented.len(), 1);
+        assert_eq!(prevented[0].taker_canceled_size, Quantity::new_int(10));
+        assert_eq!(prevented[0].maker_canceled_size, Quantity::new_int(-10));
+    }
+
+    /// The resting ask is larger than the taker: the taker is canceled in
+    /// full, and the resting ask is decremented by the same size, releasing
+    /// reserved margin proportionally.
+    #[test]
+    fn self_trade_prevention_decrement_and_cancel_taker() {
+        let (outcome, prevented, resized) =
+            submit_against_own_ask(SelfTradePreventionMode::DecrementAndCancel, 4);
+
+        assert_eq!(outcome.order_mutations.len(), 1);
+        let (_, order_id, order, pre_fill_abs_size) = &outcome.order_mutations[0];
+        let order = order.as_ref().unwrap();
+        assert_eq!(*order_id, Uint64::new(100));
+        assert_eq!(*pre_fill_abs_size, Quantity::new_int(10));
+        assert_eq!(order.size, Quantity::new_int(-6));
+        assert_eq!(order.reserved_margin, UsdValue::new_int(15_000));
+
+        assert_eq!(outcome.taker_state.open_order_count, 1);
+        assert_eq!(
+            outcome.taker_state.reserved_margin,
+            UsdValue::new_int(15_000)
+        );
+        assert!(outcome.taker_state.positions.is_empty());
+
+        assert_eq!(resized.len(), 1);
+        assert_eq!(resized[0].old_size, Quantity::new_int(-10));
+        assert_eq!(resized[0].new_size, Quantity::new_int(-6));
+
+        assert_eq!(prevented.len(), 1);
+        assert_eq!(prevented[0].taker_canceled_size, Quantity::new_int(4));
+        assert_eq!(prevented[0].maker_canceled_size, Quantity::new_int(-4));
+    }
+
     // ======= Match-time price-band re-check ==============================
 
     /// Far-end drift: a stale maker whose price is above the upper band
@@ -5125,6 +5514,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5204,6 +5594,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5284,6 +5675,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5380,6 +5772,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5486,6 +5879,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5544,6 +5938,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5625,8 +6020,8 @@ mod tests {
                 size: Quantity::new_int(-10),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         USER_STATES
@@ -5678,6 +6073,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5747,6 +6143,7 @@ mod tests {
                 false,
                 None,
                 None,
+                SelfTradePreventionMode::ExpireMaker,
                 &mut EventBuilder::new(),
             )
             .unwrap();
@@ -5815,6 +6212,7 @@ mod tests {
                 false,
                 None,
                 None,
+                SelfTradePreventionMode::ExpireMaker,
                 &mut EventBuilder::new(),
             )
             .unwrap();
@@ -5887,6 +6285,7 @@ mod tests {
             false,
             make_tp(55_000),
             make_sl(45_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5895,11 +6294,11 @@ mod tests {
         assert!(pos.size.is_positive(), "should be long");
 
         // TP → Above for long
-        let above = pos.conditional_order_above.as_ref().unwrap();
+        let above = pos.conditional_orders_above.values().next().unwrap();
         assert_eq!(above.trigger_price, UsdPrice::new_int(55_000));
 
         // SL → Below for long
-        let below = pos.conditional_order_below.as_ref().unwrap();
+        let below = pos.conditional_orders_below.values().next().unwrap();
         assert_eq!(below.trigger_price, UsdPrice::new_int(45_000));
     }
 
@@ -5941,6 +6340,7 @@ mod tests {
             false,
             make_tp(45_000),
             make_sl(55_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -5949,11 +6349,11 @@ mod tests {
         assert!(pos.size.is_negative(), "should be short");
 
         // TP → Below for short
-        let below = pos.conditional_order_below.as_ref().unwrap();
+        let below = pos.conditional_orders_below.values().next().unwrap();
         assert_eq!(below.trigger_price, UsdPrice::new_int(45_000));
 
         // SL → Above for short
-        let above = pos.conditional_order_above.as_ref().unwrap();
+        let above = pos.conditional_orders_above.values().next().unwrap();
         assert_eq!(above.trigger_price, UsdPrice::new_int(55_000));
     }
 
@@ -5976,8 +6376,8 @@ mod tests {
                 size: Quantity::new_int(10),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         USER_STATES.save(&mut ctx.storage, TAKER, &ts).unwrap();
@@ -6015,6 +6415,7 @@ mod tests {
             false,
             make_tp(45_000),
             make_sl(55_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6062,14 +6463,15 @@ mod tests {
             },
             false,
             make_tp(55_000),
-            None, // no SL
+            None,
+            SelfTradePreventionMode::ExpireMaker, // no SL
             &mut EventBuilder::new(),
         )
         .unwrap();
 
         let pos = ts.positions.get(&pair_id()).unwrap();
-        assert!(pos.conditional_order_above.is_some(), "TP should be set");
-        assert!(pos.conditional_order_below.is_none(), "SL should be None");
+        assert!(!pos.conditional_orders_above.is_empty(), "TP should be set");
+        assert!(pos.conditional_orders_below.is_empty(), "SL should be None");
     }
 
     /// Limit order partially fills → TP/SL applied to position AND stored on
@@ -6117,14 +6519,15 @@ mod tests {
             false,
             make_tp(55_000),
             make_sl(45_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
 
         // Position should have TP/SL from partial fill.
         let pos = ts.positions.get(&pair_id()).unwrap();
-        assert!(pos.conditional_order_above.is_some());
-        assert!(pos.conditional_order_below.is_some());
+        assert!(!pos.conditional_orders_above.is_empty());
+        assert!(!pos.conditional_orders_below.is_empty());
 
         // Resting order should also carry TP/SL.
         let (_, _, resting_order) = order_to_store.expect("should have resting order");
@@ -6152,8 +6555,8 @@ mod tests {
                 size: Quantity::new_int(5),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: None,
-                conditional_order_below: None,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
             },
         );
         USER_STATES.save(&mut ctx.storage, TAKER, &ts).unwrap();
@@ -6189,14 +6592,15 @@ mod tests {
             false,
             make_tp(55_000),
             make_sl(45_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
 
         // Position should NOT have conditional orders (no fills).
         let pos = ts.positions.get(&pair_id()).unwrap();
-        assert!(pos.conditional_order_above.is_none());
-        assert!(pos.conditional_order_below.is_none());
+        assert!(pos.conditional_orders_above.is_empty());
+        assert!(pos.conditional_orders_below.is_empty());
 
         // Resting order carries TP/SL for later.
         let (_, _, resting_order) = order_to_store.expect("should have resting order");
@@ -6268,6 +6672,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6278,10 +6683,10 @@ mod tests {
         assert!(pos.size.is_negative(), "maker should be short");
 
         // For shorts: TP → Below, SL → Above
-        let below = pos.conditional_order_below.as_ref().unwrap();
+        let below = pos.conditional_orders_below.values().next().unwrap();
         assert_eq!(below.trigger_price, UsdPrice::new_int(45_000));
 
-        let above = pos.conditional_order_above.as_ref().unwrap();
+        let above = pos.conditional_orders_above.values().next().unwrap();
         assert_eq!(above.trigger_price, UsdPrice::new_int(55_000));
     }
 
@@ -6326,6 +6731,7 @@ mod tests {
             false,
             make_tp(55_000),
             make_sl(45_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6354,18 +6760,28 @@ mod tests {
                 size: Quantity::new_int(5),
                 entry_price: UsdPrice::new_int(50_000),
                 entry_funding_per_unit: FundingPerUnit::ZERO,
-                conditional_order_above: Some(ConditionalOrder {
-                    order_id: Uint64::new(99),
-                    size: None,
-                    trigger_price: UsdPrice::new_int(60_000),
-                    max_slippage: Dimensionless::new_percent(1),
-                }),
-                conditional_order_below: Some(ConditionalOrder {
-                    order_id: Uint64::new(98),
-                    size: None,
-                    trigger_price: UsdPrice::new_int(40_000),
-                    max_slippage: Dimensionless::new_percent(2),
-                }),
+                conditional_orders_above: BTreeMap::from([(
+                    Uint64::new(99),
+                    ConditionalOrder {
+                        order_id: Uint64::new(99),
+                        size: None,
+                        trigger_price: UsdPrice::new_int(60_000),
+                        max_slippage: Dimensionless::new_percent(1),
+                        limit_price: None,
+                        trailing_offset: None,
+                    },
+                )]),
+                conditional_orders_below: BTreeMap::from([(
+                    Uint64::new(98),
+                    ConditionalOrder {
+                        order_id: Uint64::new(98),
+                        size: None,
+                        trigger_price: UsdPrice::new_int(40_000),
+                        max_slippage: Dimensionless::new_percent(2),
+                        limit_price: None,
+                        trailing_offset: None,
+                    },
+                )]),
             },
         );
         USER_STATES.save(&mut ctx.storage, TAKER, &ts).unwrap();
@@ -6399,18 +6815,23 @@ mod tests {
             },
             false,
             make_tp(58_000), // different from existing 60k
-            make_sl(42_000), // different from existing 40k
+            make_sl(42_000),
+            SelfTradePreventionMode::ExpireMaker, // different from existing 40k
             &mut EventBuilder::new(),
         )
         .unwrap();
 
         let pos = ts.positions.get(&pair_id()).unwrap();
 
-        let above = pos.conditional_order_above.as_ref().unwrap();
+        // The existing orders are replaced, not added to.
+        assert_eq!(pos.conditional_orders_above.len(), 1);
+        assert_eq!(pos.conditional_orders_below.len(), 1);
+
+        let above = pos.conditional_orders_above.values().next().unwrap();
         assert_eq!(above.trigger_price, UsdPrice::new_int(58_000));
         assert_ne!(above.order_id, Uint64::new(99)); // new ID
 
-        let below = pos.conditional_order_below.as_ref().unwrap();
+        let below = pos.conditional_orders_below.values().next().unwrap();
         assert_eq!(below.trigger_price, UsdPrice::new_int(42_000));
         assert_ne!(below.order_id, Uint64::new(98)); // new ID
     }
@@ -6442,8 +6863,8 @@ mod tests {
                         size: Quantity::new_int(-10),
                         entry_price: UsdPrice::new_int(50_000),
                         entry_funding_per_unit: FundingPerUnit::ZERO,
-                        conditional_order_above: None,
-                        conditional_order_below: None,
+                        conditional_orders_above: BTreeMap::new(),
+                        conditional_orders_below: BTreeMap::new(),
                     },
                 );
                 p
@@ -6483,6 +6904,7 @@ mod tests {
             false,
             make_tp(55_000),
             make_sl(45_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6490,8 +6912,8 @@ mod tests {
         // Position is still short (-5) → direction mismatch → TP/SL NOT applied.
         let pos = ts.positions.get(&pair_id()).unwrap();
         assert_eq!(pos.size, Quantity::new_int(-5));
-        assert!(pos.conditional_order_above.is_none());
-        assert!(pos.conditional_order_below.is_none());
+        assert!(pos.conditional_orders_above.is_empty());
+        assert!(pos.conditional_orders_below.is_empty());
     }
 
     /// Maker's resting bid (buy) has TP/SL, but maker's position flipped to
@@ -6520,8 +6942,8 @@ mod tests {
                         size: Quantity::new_int(-10),
                         entry_price: UsdPrice::new_int(50_000),
                         entry_funding_per_unit: FundingPerUnit::ZERO,
-                        conditional_order_above: None,
-                        conditional_order_below: None,
+                        conditional_orders_above: BTreeMap::new(),
+                        conditional_orders_below: BTreeMap::new(),
                     },
                 );
                 p
@@ -6578,6 +7000,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6587,8 +7010,8 @@ mod tests {
         let maker_state = maker_states.get(&MAKER_A).unwrap();
         let pos = maker_state.positions.get(&pair_id()).unwrap();
         assert_eq!(pos.size, Quantity::new_int(-5));
-        assert!(pos.conditional_order_above.is_none());
-        assert!(pos.conditional_order_below.is_none());
+        assert!(pos.conditional_orders_above.is_empty());
+        assert!(pos.conditional_orders_below.is_empty());
     }
 
     /// Fill price is below the SL trigger price — the SL condition is already
@@ -6643,6 +7066,7 @@ mod tests {
             false,
             None,
             make_sl(49_000),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -6651,7 +7075,7 @@ mod tests {
         assert_eq!(pos.entry_price, UsdPrice::new_int(48_000));
 
         // SL is applied even though the trigger condition is already met.
-        let below = pos.conditional_order_below.as_ref().unwrap();
+        let below = pos.conditional_orders_below.values().next().unwrap();
         assert_eq!(below.trigger_price, UsdPrice::new_int(49_000));
     }
 
@@ -6700,6 +7124,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage can't be negative");
@@ -6747,6 +7172,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("deviates too far");
@@ -6793,6 +7219,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("deviates too far");
@@ -6839,6 +7266,7 @@ mod tests {
                 size: None,
             }),
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("price must be positive");
@@ -6885,6 +7313,7 @@ mod tests {
                 max_slippage: Dimensionless::new_percent(1),
                 size: None,
             }),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("price must be positive");
@@ -6931,6 +7360,7 @@ mod tests {
                 size: None,
             }),
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage can't be negative");
@@ -6977,6 +7407,7 @@ mod tests {
                 max_slippage: Dimensionless::new_int(-1),
                 size: None,
             }),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage can't be negative");
@@ -7023,6 +7454,7 @@ mod tests {
                 size: None,
             }),
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage must be less than 1, got");
@@ -7069,6 +7501,7 @@ mod tests {
                 max_slippage: Dimensionless::new_percent(150),
                 size: None,
             }),
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage must be less than 1, got");
@@ -7112,6 +7545,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage must be less than 1, got");
@@ -7153,6 +7587,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("max slippage must be less than 1, got");
@@ -7217,6 +7652,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -7280,6 +7716,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .should_fail_with_error("client_order_id is not allowed");
@@ -7416,6 +7853,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut events,
         )
         .unwrap();
@@ -7520,6 +7958,7 @@ mod tests {
             false,
             None,
             None,
+            SelfTradePreventionMode::ExpireMaker,
             &mut EventBuilder::new(),
         )
         .unwrap();
@@ -7542,4 +7981,100 @@ mod tests {
                 .is_none()
         );
     }
+
+    // ========== Pair status tests ==========
+
+    /// A reduce-only pair rejects orders that aren't reduce-only.
+    #[test]
+    fn reject_order_in_reduce_only_pair() {
+        let mut ctx = MockContext::new()
+            .with_sender(TAKER)
+            .with_funds(Coins::default());
+
+        setup_storage(&mut ctx.storage);
+        place_ask(&mut ctx.storage, MAKER_A, 47_500, 10, 100);
+
+        let param = test_param();
+        let pair_param = PairParam {
+            status: PairStatus::ReduceOnly,
+            ..test_pair_param()
+        };
+        let pair_state = PAIR_STATES.load(&ctx.storage, &pair_id()).unwrap();
+        let taker_state = UserState {
+            margin: LARGE_COLLATERAL,
+            ..Default::default()
+        };
+
+        compute_submit_order_outcome(
+            &ctx.storage,
+            TAKER,
+            CONTRACT,
+            Timestamp::ZERO,
+            &param,
+            &State::default(),
+            &pair_id(),
+            &pair_param,
+            &pair_state,
+            &taker_state,
+            UsdPrice::new_int(50_000),
+            Quantity::new_int(10),
+            OrderKind::Market {
+                max_slippage: Dimensionless::new_permille(50),
+            },
+            false,
+            None,
+            None,
+            SelfTradePreventionMode::ExpireMaker,
+            &mut EventBuilder::new(),
+        )
+        .should_fail_with_error("is in reduce-only mode");
+    }
+
+    /// A settling pair rejects all orders.
+    #[test]
+    fn reject_order_in_settling_pair() {
+        let mut ctx = MockContext::new()
+            .with_sender(TAKER)
+            .with_funds(Coins::default());
+
+        setup_storage(&mut ctx.storage);
+        place_ask(&mut ctx.storage, MAKER_A, 47_500, 10, 100);
+
+        let param = test_param();
+        let pair_param = PairParam {
+            status: PairStatus::Settling {
+                settlement_price: UsdPrice::new_int(50_000),
+            },
+            ..test_pair_param()
+        };
+        let pair_state = PAIR_STATES.load(&ctx.storage, &pair_id()).unwrap();
+        let taker_state = UserState {
+            margin: LARGE_COLLATERAL,
+            ..Default::default()
+        };
+
+        compute_submit_order_outcome(
+            &ctx.storage,
+            TAKER,
+            CONTRACT,
+            Timestamp::ZERO,
+            &param,
+            &State::default(),
+            &pair_id(),
+            &pair_param,
+            &pair_state,
+            &taker_state,
+            UsdPrice::new_int(50_000),
+            Quantity::new_int(10),
+            OrderKind::Market {
+                max_slippage: Dimensionless::new_permille(50),
+            },
+            false,
+            None,
+            None,
+            SelfTradePreventionMode::ExpireMaker,
+            &mut EventBuilder::new(),
+        )
+        .should_fail_with_error("has been delisted");
+    }
 }
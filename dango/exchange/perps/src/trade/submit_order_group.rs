@@ -0,0 +1,908 @@
+use {
+    crate::{
+        MAX_ORDER_GROUPS_PER_USER, MAX_ORDERS_PER_GROUP,
+        order_group::{may_load_limit_order, resolve_oco_group, save_order_group},
+        state::{
+            ACTIVATED_ORDER_GROUPS, ORDER_GROUPS, PAIR_PARAMS, PAIR_STATES, PARAM, STATE,
+            USER_STATES,
+        },
+        trade::{
+            _submit_conditional_order, apply_submit_order_outcome, compute_submit_order_outcome,
+            ensure_trading_enabled,
+        },
+    },
+    anyhow::{bail, ensure},
+    dango_math::NumberConst,
+    dango_order_book::{
+        NEXT_FILL_ID, NEXT_ORDER_ID, OrderId, OrderKind, PairId, SelfTradePreventionMode,
+        TimeInForce, validate_slippage, validate_trailing_offset,
+    },
+    dango_primitives::{
+        Addr, EventBuilder, MutableCtx, Order as IterationOrder, QuerierWrapper, Response, Storage,
+        Timestamp,
+    },
+    dango_types::perps::{
+        GroupConditionalOrder, GroupLimitOrder, GroupMember, GroupOrder, OrderGroup, OrderGroupId,
+        OrderGroupKind, OrderGroupPlaced, PairParam, SubmitOrderGroupRequest,
+    },
+};
+
+pub fn submit_order_group(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    group: SubmitOrderGroupRequest,
+) -> anyhow::Result<Response> {
+    ensure_trading_enabled(&PARAM.load(ctx.storage)?)?;
+
+    let pair_param = PAIR_PARAMS.load(ctx.storage, &pair_id)?;
+
+    // -------------------------------- Checks ---------------------------------
+
+    match &group {
+        SubmitOrderGroupRequest::Oco { orders } => {
+            ensure!(
+                orders.len() >= 2,
+                "an oco group must have at least 2 orders"
+            );
+
+            ensure!(
+                orders.len() <= MAX_ORDERS_PER_GROUP,
+                "too many orders in group! max allowed: {MAX_ORDERS_PER_GROUP}"
+            );
+        },
+        SubmitOrderGroupRequest::Oto {
+            primary,
+            secondaries,
+        } => {
+            ensure!(
+                !secondaries.is_empty(),
+                "an oto group must have at least 1 secondary order"
+            );
+
+            ensure!(
+                secondaries.len() <= MAX_ORDERS_PER_GROUP,
+                "too many orders in group! max allowed: {MAX_ORDERS_PER_GROUP}"
+            );
+
+            validate_limit_order(primary)?;
+
+            // The secondaries are only placed once the primary is filled, so
+            // validate them now, rather than drop them at activation.
+            for order in secondaries {
+                match order {
+                    GroupOrder::Limit(order) => {
+                        validate_limit_order(order)?;
+                    },
+                    GroupOrder::Conditional(order) => {
+                        validate_conditional_order(order, &pair_param)?;
+
+                        if let Some(size) = order.size {
+                            ensure!(
+                                size.is_positive() != primary.size.is_positive(),
+                                "size must oppose the primary order's direction"
+                            );
+                        }
+                    },
+                }
+            }
+        },
+    }
+
+    let num_groups = ORDER_GROUPS
+        .idx
+        .user
+        .prefix(ctx.sender)
+        .keys(ctx.storage, None, None, IterationOrder::Ascending)
+        .count();
+
+    ensure!(
+        num_groups < MAX_ORDER_GROUPS_PER_USER,
+        "too many order groups! max allowed: {MAX_ORDER_GROUPS_PER_USER}"
+    );
+
+    // ----------------------------- State changes -----------------------------
+
+    // Assign group ID and increment.
+    let group_id = NEXT_ORDER_ID.load(ctx.storage)?;
+    NEXT_ORDER_ID.save(ctx.storage, &(group_id + OrderGroupId::ONE))?;
+
+    let mut events = EventBuilder::new();
+
+    match group {
+        SubmitOrderGroupRequest::Oco { orders } => {
+            place_oco_group(
+                ctx.storage,
+                ctx.querier,
+                ctx.block.timestamp,
+                ctx.contract,
+                ctx.sender,
+                &pair_id,
+                group_id,
+                &orders,
+                true,
+                &mut events,
+            )?;
+        },
+        SubmitOrderGroupRequest::Oto {
+            primary,
+            secondaries,
+        } => {
+            let placed = place_limit_order(
+                ctx.storage,
+                ctx.querier,
+                ctx.block.timestamp,
+                ctx.contract,
+                ctx.sender,
+                &pair_id,
+                &primary,
+                &mut events,
+            )?;
+
+            let group = OrderGroup {
+                user: ctx.sender,
+                pair_id,
+                kind: OrderGroupKind::Oto {
+                    primary: placed.order_id,
+                    secondaries,
+                },
+            };
+
+            match placed.member {
+                // The primary is resting: activate the group once it's filled.
+                Some(_) => {
+                    save_order_group(ctx.storage, group_id, &group)?;
+                },
+                // The primary was fully filled on placement: the cron
+                // activates the group right away.
+                None if placed.filled => {
+                    ORDER_GROUPS.save(ctx.storage, group_id, &group)?;
+                    ACTIVATED_ORDER_GROUPS.insert(ctx.storage, group_id)?;
+                },
+                None => bail!("reduce-only order would exceed position size"),
+            }
+
+            events.push(OrderGroupPlaced {
+                group_id,
+                user: group.user,
+                pair_id: group.pair_id,
+                kind: group.kind,
+            })?;
+        },
+    }
+
+    Ok(Response::new().add_events(events)?)
+}
+
+/// An order of a group that has been placed.
+#[derive(Debug)]
+pub(crate) struct PlacedOrder {
+    pub order_id: OrderId,
+
+    /// The order as a group member, if it's still live: a conditional order,
+    /// or a limit order that's still resting in the book.
+    pub member: Option<GroupMember>,
+
+    /// Whether the order was filled, fully or partially, on placement.
+    pub filled: bool,
+}
+
+/// Place the orders of an OCO group one by one, then store the group.
+///
+/// If a limit order is filled, even partially, on placement, the group is
+/// resolved right away: the orders placed before it are canceled, and the ones
+/// after it aren't placed.
+///
+/// If `strict`, an order that can't be placed fails the whole group. Otherwise,
+/// the order is skipped, and if fewer than two orders are placed, they are left
+/// as standalone orders without a group.
+///
+/// Returns the IDs of the placed orders.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn place_oco_group(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    current_time: Timestamp,
+    contract: Addr,
+    user: Addr,
+    pair_id: &PairId,
+    group_id: OrderGroupId,
+    orders: &[GroupOrder],
+    strict: bool,
+    events: &mut EventBuilder,
+) -> anyhow::Result<Vec<OrderId>> {
+    let mut order_ids = Vec::new();
+    let mut members = Vec::new();
+    let mut executed_order_id = None;
+
+    for order in orders {
+        let placed = match place_group_order(
+            storage,
+            querier,
+            current_time,
+            contract,
+            user,
+            pair_id,
+            order,
+            events,
+        ) {
+            Ok(placed) => placed,
+            Err(err) if strict => return Err(err),
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::info!(
+                        %group_id,
+                        %user,
+                        %pair_id,
+                        err = %_err,
+                        "Order of group not placed"
+                    );
+                }
+
+                continue;
+            },
+        };
+
+        if placed.member.is_none() && !placed.filled {
+            ensure!(!strict, "reduce-only order would exceed position size");
+            continue;
+        }
+
+        order_ids.push(placed.order_id);
+        members.push(placed.member.unwrap_or(GroupMember::Limit {
+            order_id: placed.order_id,
+        }));
+
+        if placed.filled {
+            executed_order_id = Some(placed.order_id);
+            break;
+        }
+    }
+
+    if orders.len() < 2 || (executed_order_id.is_none() && members.len() < 2) {
+        return Ok(order_ids);
+    }
+
+    let group = OrderGroup {
+        user,
+        pair_id: pair_id.clone(),
+        kind: OrderGroupKind::Oco { members },
+    };
+
+    save_order_group(storage, group_id, &group)?;
+
+    events.push(OrderGroupPlaced {
+        group_id,
+        user,
+        pair_id: pair_id.clone(),
+        kind: group.kind.clone(),
+    })?;
+
+    if let Some(executed_order_id) = executed_order_id {
+        resolve_oco_group(storage, group_id, group, executed_order_id, events)?;
+    }
+
+    Ok(order_ids)
+}
+
+/// Place a single order of a group.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn place_group_order(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    current_time: Timestamp,
+    contract: Addr,
+    user: Addr,
+    pair_id: &PairId,
+    order: &GroupOrder,
+    events: &mut EventBuilder,
+) -> anyhow::Result<PlacedOrder> {
+    match order {
+        GroupOrder::Limit(order) => place_limit_order(
+            storage,
+            querier,
+            current_time,
+            contract,
+            user,
+            pair_id,
+            order,
+            events,
+        ),
+        GroupOrder::Conditional(order) => {
+            let order_id = _submit_conditional_order(
+                storage,
+                user,
+                pair_id.clone(),
+                order.size,
+                order.trigger_price,
+                order.trigger_direction,
+                order.max_slippage,
+                order.limit_price,
+                order.trailing_offset,
+                events,
+            )?;
+
+            Ok(PlacedOrder {
+                order_id,
+                member: Some(GroupMember::Conditional {
+                    order_id,
+                    trigger_direction: order.trigger_direction,
+                }),
+                filled: false,
+            })
+        },
+    }
+}
+
+/// Submit a limit order of a group.
+///
+/// Returns an error only if the order is rejected before any state is written,
+/// so that the cron can skip the order. A reduce-only order left with no
+/// position to close is canceled by the re-size instead of failing; the
+/// returned `PlacedOrder` then has neither a `member` nor a fill.
+#[allow(clippy::too_many_arguments)]
+fn place_limit_order(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    current_time: Timestamp,
+    contract: Addr,
+    user: Addr,
+    pair_id: &PairId,
+    order: &GroupLimitOrder,
+    events: &mut EventBuilder,
+) -> anyhow::Result<PlacedOrder> {
+    let param = PARAM.load(storage)?;
+    let state = STATE.load(storage)?;
+    let pair_param = PAIR_PARAMS.load(storage, pair_id)?;
+    let pair_state = PAIR_STATES.load(storage, pair_id)?;
+    let user_state = USER_STATES.may_load(storage, user)?.unwrap_or_default();
+
+    let order_id = NEXT_ORDER_ID.load(storage)?;
+    let fill_id = NEXT_FILL_ID.load(storage)?;
+
+    let time_in_force = if order.post_only {
+        TimeInForce::PostOnly
+    } else {
+        TimeInForce::GoodTilCanceled
+    };
+
+    let outcome = compute_submit_order_outcome(
+        storage,
+        user,
+        contract,
+        current_time,
+        &param,
+        &state,
+        pair_id,
+        &pair_param,
+        &pair_state,
+        &user_state,
+        pair_state.index_price,
+        order.size,
+        OrderKind::Limit {
+            limit_price: order.limit_price,
+            time_in_force,
+            client_order_id: order.client_order_id,
+        },
+        order.reduce_only,
+        None, // tp
+        None, // sl
+        SelfTradePreventionMode::ExpireMaker,
+        events,
+    )?;
+
+    // Every fill allocates a fill ID, so the order was filled iff the counter
+    // has moved.
+    let filled = outcome.next_fill_id != fill_id;
+
+    apply_submit_order_outcome(
+        storage,
+        querier,
+        current_time,
+        contract,
+        user,
+        pair_id,
+        &param,
+        &pair_param,
+        order.size,
+        false, // see the function's doc
+        outcome,
+        events,
+    )?;
+
+    let member = may_load_limit_order(storage, order_id)?.map(|_| GroupMember::Limit { order_id });
+
+    Ok(PlacedOrder {
+        order_id,
+        member,
+        filled,
+    })
+}
+
+fn validate_limit_order(order: &GroupLimitOrder) -> anyhow::Result<()> {
+    ensure!(order.size.is_non_zero(), "size can't be zero");
+
+    ensure!(
+        order.limit_price.is_positive(),
+        "price must be positive: {}",
+        order.limit_price
+    );
+
+    Ok(())
+}
+
+fn validate_conditional_order(
+    order: &GroupConditionalOrder,
+    pair_param: &PairParam,
+) -> anyhow::Result<()> {
+    ensure!(
+        order.trigger_price.is_positive(),
+        "price must be positive: {}",
+        order.trigger_price
+    );
+
+    validate_slippage(order.max_slippage, pair_param.max_market_slippage)?;
+
+    if let Some(limit_price) = order.limit_price {
+        ensure!(
+            limit_price.is_positive(),
+            "price must be positive: {limit_price}"
+        );
+    }
+
+    if let Some(trailing_offset) = order.trailing_offset {
+        ensure!(
+            order.limit_price.is_none(),
+            "a trailing stop can't have a limit price"
+        );
+
+        validate_trailing_offset(trailing_offset)?;
+    }
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            order_group::on_conditional_order_triggered,
+            state::ORDER_GROUP_MEMBERS,
+            trade::{cancel_one_conditional_order, cancel_order_group, submit_order},
+        },
+        dango_math::Uint64,
+        dango_order_book::{
+            BIDS, Dimensionless, FillId, FundingPerUnit, LimitOrder, Quantity, TriggerDirection,
+            UsdPrice, UsdValue,
+        },
+        dango_primitives::{Coins, EventName, MockContext, MockQuerier, ResultExt},
+        dango_types::{
+            config::AppConfig,
+            perps::{OrderGroupRemoved, PairState, Param, Position, UserState},
+        },
+        std::collections::BTreeMap,
+    };
+
+    const USER: Addr = Addr::mock(1);
+    const OTHER_USER: Addr = Addr::mock(2);
+    const CONTRACT: Addr = Addr::mock(9);
+
+    fn pair_id() -> PairId {
+        "perp/ethusd".parse().unwrap()
+    }
+
+    fn init_storage(storage: &mut dyn Storage) {
+        let mut positions = BTreeMap::new();
+        positions.insert(
+            pair_id(),
+            Position {
+                size: Quantity::new_int(10),
+                entry_price: UsdPrice::new_int(2_000),
+                entry_funding_per_unit: FundingPerUnit::ZERO,
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::new(),
+            },
+        );
+
+        PARAM.save(storage, &Param::default()).unwrap();
+        PAIR_PARAMS
+            .save(
+                storage,
+                &pair_id(),
+                &PairParam {
+                    max_market_slippage: Dimensionless::new_permille(100), // 10%
+                    ..PairParam::new_mock()
+                },
+            )
+            .unwrap();
+        NEXT_ORDER_ID.save(storage, &OrderId::ONE).unwrap();
+        USER_STATES
+            .save(
+                storage,
+                USER,
+                &UserState {
+                    margin: UsdValue::new_int(10_000),
+                    positions,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    fn conditional_order(trigger_price: i128, trigger_direction: TriggerDirection) -> GroupOrder {
+        GroupOrder::Conditional(GroupConditionalOrder {
+            size: Some(Quantity::new_int(-10)),
+            trigger_price: UsdPrice::new_int(trigger_price),
+            trigger_direction,
+            max_slippage: Dimensionless::new_percent(1),
+            limit_price: None,
+            trailing_offset: None,
+        })
+    }
+
+    /// Take-profit at $2,500 and stop-loss at $1,800 on a long position.
+    fn tp_sl() -> SubmitOrderGroupRequest {
+        SubmitOrderGroupRequest::Oco {
+            orders: vec![
+                conditional_order(2_500, TriggerDirection::Above),
+                conditional_order(1_800, TriggerDirection::Below),
+            ],
+        }
+    }
+
+    fn limit_order(size: i128) -> GroupLimitOrder {
+        GroupLimitOrder {
+            size: Quantity::new_int(size),
+            limit_price: UsdPrice::new_int(2_000),
+            post_only: true,
+            reduce_only: false,
+            client_order_id: None,
+        }
+    }
+
+    fn conditional_orders(storage: &dyn Storage, trigger_direction: TriggerDirection) -> usize {
+        USER_STATES
+            .load(storage, USER)
+            .unwrap()
+            .positions
+            .get(&pair_id())
+            .unwrap()
+            .conditional_orders(trigger_direction)
+            .len()
+    }
+
+    /// An OCO group of a TP and an SL: the group takes the first order ID,
+    /// the orders the next two.
+    #[test]
+    fn g1_oco_tp_sl() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+
+        let group = ORDER_GROUPS.load(&ctx.storage, Uint64::ONE).unwrap();
+        assert_eq!(group.user, USER);
+        assert_eq!(
+            group.kind,
+            OrderGroupKind::Oco {
+                members: vec![
+                    GroupMember::Conditional {
+                        order_id: Uint64::new(2),
+                        trigger_direction: TriggerDirection::Above,
+                    },
+                    GroupMember::Conditional {
+                        order_id: Uint64::new(3),
+                        trigger_direction: TriggerDirection::Below,
+                    },
+                ],
+            }
+        );
+
+        for order_id in [2, 3] {
+            assert_eq!(
+                ORDER_GROUP_MEMBERS
+                    .load(&ctx.storage, Uint64::new(order_id))
+                    .unwrap(),
+                Uint64::ONE
+            );
+        }
+
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Above), 1);
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Below), 1);
+        assert_eq!(NEXT_ORDER_ID.load(&ctx.storage).unwrap(), Uint64::new(4));
+    }
+
+    /// Triggering the TP cancels the SL and removes the group.
+    #[test]
+    fn g2_oco_trigger_cancels_other() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+
+        let canceled = on_conditional_order_triggered(
+            &mut ctx.storage,
+            Uint64::new(2),
+            &mut EventBuilder::new(),
+        )
+        .unwrap();
+
+        assert_eq!(canceled, vec![Uint64::new(3)]);
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Below), 0);
+        assert!(
+            ORDER_GROUPS
+                .may_load(&ctx.storage, Uint64::ONE)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            ORDER_GROUP_MEMBERS
+                .may_load(&ctx.storage, Uint64::new(2))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            ORDER_GROUP_MEMBERS
+                .may_load(&ctx.storage, Uint64::new(3))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// Canceling a member dissolves the group, leaving the other order in place.
+    #[test]
+    fn g3_cancel_member_dissolves_group() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+
+        cancel_one_conditional_order(
+            ctx.as_mutable(),
+            pair_id(),
+            TriggerDirection::Above,
+            Some(Uint64::new(2)),
+        )
+        .should_succeed();
+
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Above), 0);
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Below), 1);
+        assert!(
+            ORDER_GROUPS
+                .may_load(&ctx.storage, Uint64::ONE)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            ORDER_GROUP_MEMBERS
+                .may_load(&ctx.storage, Uint64::new(3))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// Canceling the group cancels all of its orders. Only the owner can.
+    #[test]
+    fn g4_cancel_group() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+
+        cancel_order_group(
+            MutableCtx {
+                sender: OTHER_USER,
+                ..ctx.as_mutable()
+            },
+            Uint64::ONE,
+        )
+        .should_fail_with_error("order group 1 doesn't belong to the sender");
+
+        cancel_order_group(ctx.as_mutable(), Uint64::ONE).should_succeed();
+
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Above), 0);
+        assert_eq!(conditional_orders(&ctx.storage, TriggerDirection::Below), 0);
+        assert!(
+            ORDER_GROUPS
+                .may_load(&ctx.storage, Uint64::ONE)
+                .unwrap()
+                .is_none()
+        );
+
+        cancel_order_group(ctx.as_mutable(), Uint64::ONE)
+            .should_fail_with_error("order group not found with id 1");
+    }
+
+    #[test]
+    fn g5_reject_invalid_groups() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_order_group(
+            ctx.as_mutable(),
+            pair_id(),
+            SubmitOrderGroupRequest::Oco {
+                orders: vec![conditional_order(2_500, TriggerDirection::Above)],
+            },
+        )
+        .should_fail_with_error("an oco group must have at least 2 orders");
+
+        submit_order_group(
+            ctx.as_mutable(),
+            pair_id(),
+            SubmitOrderGroupRequest::Oco {
+                orders: vec![conditional_order(2_500, TriggerDirection::Above); 6],
+            },
+        )
+        .should_fail_with_error("too many orders in group! max allowed: 5");
+
+        submit_order_group(
+            ctx.as_mutable(),
+            pair_id(),
+            SubmitOrderGroupRequest::Oto {
+                primary: limit_order(1),
+                secondaries: vec![],
+            },
+        )
+        .should_fail_with_error("an oto group must have at least 1 secondary order");
+
+        submit_order_group(
+            ctx.as_mutable(),
+            pair_id(),
+            SubmitOrderGroupRequest::Oto {
+                primary: limit_order(-1),
+                secondaries: vec![conditional_order(2_500, TriggerDirection::Above)],
+            },
+        )
+        .should_fail_with_error("size must oppose the primary order's direction");
+
+        submit_order_group(
+            ctx.as_mutable(),
+            pair_id(),
+            SubmitOrderGroupRequest::Oto {
+                primary: limit_order(1),
+                secondaries: vec![GroupOrder::Limit(limit_order(0))],
+            },
+        )
+        .should_fail_with_error("size can't be zero");
+
+        // Nothing was written.
+        assert_eq!(NEXT_ORDER_ID.load(&ctx.storage).unwrap(), Uint64::ONE);
+    }
+
+    #[test]
+    fn g6_reject_too_many_groups() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        for _ in 0..MAX_ORDER_GROUPS_PER_USER {
+            submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+        }
+
+        submit_order_group(ctx.as_mutable(), pair_id(), tp_sl())
+            .should_fail_with_error("too many order groups! max allowed: 10");
+    }
+
+    /// Closing the position drops its TP and SL, which dissolves their group,
+    /// so that it no longer counts toward the user's maximum.
+    #[test]
+    fn g7_position_close_dissolves_group() {
+        let mut ctx = MockContext::new()
+            // Needed for the fee commissions of the fill.
+            .with_querier(
+                MockQuerier::new()
+                    .with_app_config(AppConfig::default())
+                    .unwrap(),
+            )
+            .with_contract(CONTRACT)
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        for _ in 0..MAX_ORDER_GROUPS_PER_USER {
+            submit_order_group(ctx.as_mutable(), pair_id(), tp_sl()).should_succeed();
+        }
+
+        // A resting bid of another user, for the user to close the long into.
+        STATE.save(&mut ctx.storage, &Default::default()).unwrap();
+        PAIR_STATES
+            .save(
+                &mut ctx.storage,
+                &pair_id(),
+                &PairState {
+                    index_price: UsdPrice::new_int(2_000),
+                    oracle_price: UsdPrice::new_int(2_000),
+                    long_oi: Quantity::new_int(10),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        NEXT_FILL_ID.save(&mut ctx.storage, &FillId::ONE).unwrap();
+        BIDS.save(
+            &mut ctx.storage,
+            (pair_id(), !UsdPrice::new_int(2_000), Uint64::new(100)),
+            &LimitOrder {
+                user: OTHER_USER,
+                size: Quantity::new_int(10),
+                reduce_only: false,
+                reserved_margin: UsdValue::new_int(1_000),
+                created_at: Timestamp::ZERO,
+                tp: None,
+                sl: None,
+                client_order_id: None,
+            },
+        )
+        .unwrap();
+        USER_STATES
+            .save(
+                &mut ctx.storage,
+                OTHER_USER,
+                &UserState {
+                    margin: UsdValue::new_int(10_000),
+                    reserved_margin: UsdValue::new_int(1_000),
+                    open_order_count: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let events = submit_order(
+            ctx.as_mutable(),
+            pair_id(),
+            Quantity::new_int(-10),
+            OrderKind::Market {
+                max_slippage: Dimensionless::new_percent(1),
+            },
+            false,
+            None,
+            None,
+            SelfTradePreventionMode::ExpireMaker,
+        )
+        .should_succeed()
+        .subevents;
+
+        let user_state = USER_STATES.load(&ctx.storage, USER).unwrap();
+        assert!(user_state.positions.is_empty());
+        assert!(
+            ORDER_GROUPS
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .next()
+                .is_none()
+        );
+        assert!(
+            ORDER_GROUP_MEMBERS
+                .keys(&ctx.storage, None, None, IterationOrder::Ascending)
+                .next()
+                .is_none()
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.ty == OrderGroupRemoved::EVENT_NAME)
+                .count(),
+            MAX_ORDER_GROUPS_PER_USER
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use {
+    crate::{
+        MAX_TWAP_DURATION, MAX_TWAP_ORDERS_PER_USER, MIN_TWAP_SLICE_INTERVAL, PAIR_PARAMS,
+        PAIR_STATES, TWAP_ORDERS,
+        state::PARAM,
+        trade::{ensure_pair_accepts_order, ensure_trading_enabled},
+    },
+    anyhow::ensure,
+    dango_math::NumberConst,
+    dango_order_book::{
+        Dimensionless, NEXT_ORDER_ID, PairId, Quantity, check_minimum_order_size, validate_slippage,
+    },
+    dango_primitives::{Duration, MutableCtx, Order as IterationOrder, Response},
+    dango_types::perps::{TwapOrder, TwapOrderId, TwapOrderPlaced},
+};
+
+pub fn submit_twap_order(
+    ctx: MutableCtx,
+    pair_id: PairId,
+    size: Quantity,
+    duration: Duration,
+    slice_interval: Duration,
+    max_slippage: Dimensionless,
+    reduce_only: bool,
+) -> anyhow::Result<Response> {
+    ensure_trading_enabled(&PARAM.load(ctx.storage)?)?;
+
+    let pair_param = PAIR_PARAMS.load(ctx.storage, &pair_id)?;
+
+    // -------------------------------- Checks ---------------------------------
+
+    ensure_pair_accepts_order(&pair_id, &pair_param, reduce_only)?;
+
+    ensure!(size.is_non_zero(), "size can't be zero");
+
+    validate_slippage(max_slippage, pair_param.max_market_slippage)?;
+
+    ensure!(
+        slice_interval >= MIN_TWAP_SLICE_INTERVAL,
+        "invalid `slice_interval`! min: {MIN_TWAP_SLICE_INTERVAL:?}, found: {slice_interval:?}"
+    );
+
+    ensure!(
+        duration <= MAX_TWAP_DURATION,
+        "invalid `duration`! max: {MAX_TWAP_DURATION:?}, found: {duration:?}"
+    );
+
+    let num_slices = duration.into_nanos() / slice_interval.into_nanos();
+
+    ensure!(
+        num_slices >= 1,
+        "duration must be no shorter than the slice interval"
+    );
+
+    // Safe to cast: the number of slices is bounded by
+    // `MAX_TWAP_DURATION / MIN_TWAP_SLICE_INTERVAL`.
+    let num_slices = num_slices as u32;
+
+    // Each slice must be above the minimum order size at the current oracle
+    // price, otherwise all of them would be rejected by the cron. Reduce-only
+    // orders are exempt, same as in `submit_order`.
+    if !reduce_only {
+        let oracle_price = PAIR_STATES.load(ctx.storage, &pair_id)?.index_price;
+        let slice_size = size.checked_div(Dimensionless::new_int(num_slices as i128))?;
+
+        check_minimum_order_size(slice_size, oracle_price, pair_param.min_order_size)?;
+    }
+
+    let num_twap_orders = TWAP_ORDERS
+        .idx
+        .user
+        .prefix(ctx.sender)
+        .keys(ctx.storage, None, None, IterationOrder::Ascending)
+        .count();
+
+    ensure!(
+        num_twap_orders < MAX_TWAP_ORDERS_PER_USER,
+        "too many twap orders! max allowed: {MAX_TWAP_ORDERS_PER_USER}"
+    );
+
+    // ----------------------------- State changes -----------------------------
+
+    // Assign order ID and increment.
+    let twap_id = NEXT_ORDER_ID.load(ctx.storage)?;
+    NEXT_ORDER_ID.save(ctx.storage, &(twap_id + TwapOrderId::ONE))?;
+
+    // The first slice is executed by the next cron.
+    TWAP_ORDERS.save(
+        ctx.storage,
+        twap_id,
+        &TwapOrder {
+            user: ctx.sender,
+            pair_id: pair_id.clone(),
+            size,
+            filled_size: Quantity::ZERO,
+            max_slippage,
+            reduce_only,
+            slice_interval,
+            remaining_slices: num_slices,
+            next_slice_time: ctx.block.timestamp,
+        },
+    )?;
+
+    Ok(Response::new().add_event(TwapOrderPlaced {
+        twap_id,
+        user: ctx.sender,
+        pair_id,
+        size,
+        num_slices,
+        slice_interval,
+        max_slippage,
+        reduce_only,
+    })?)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{PAIR_PARAMS, PAIR_STATES, PARAM, TWAP_ORDERS},
+        dango_math::Uint64,
+        dango_order_book::{OrderId, UsdPrice, UsdValue},
+        dango_primitives::{Addr, Coins, MockContext, ResultExt, Storage, Timestamp},
+        dango_types::perps::{PairParam, PairState, Param},
+        test_case::test_case,
+    };
+
+    const USER: Addr = Addr::mock(1);
+
+    fn pair_id() -> PairId {
+        "perp/ethusd".parse().unwrap()
+    }
+
+    fn init_storage(storage: &mut dyn Storage) {
+        PARAM.save(storage, &Param::default()).unwrap();
+        PAIR_PARAMS
+            .save(
+                storage,
+                &pair_id(),
+                &PairParam {
+                    max_market_slippage: Dimensionless::new_permille(100), // 10%
+                    min_order_size: UsdValue::new_int(500),
+                    ..PairParam::new_mock()
+                },
+            )
+            .unwrap();
+        PAIR_STATES
+            .save(
+                storage,
+                &pair_id(),
+                &PairState {
+                    index_price: UsdPrice::new_int(2_000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        NEXT_ORDER_ID.save(storage, &OrderId::ONE).unwrap();
+    }
+
+    fn submit(
+        ctx: MutableCtx,
+        size: i128,
+        duration: Duration,
+        slice_interval: Duration,
+        reduce_only: bool,
+    ) -> anyhow::Result<Response> {
+        submit_twap_order(
+            ctx,
+            pair_id(),
+            Quantity::new_int(size),
+            duration,
+            slice_interval,
+            Dimensionless::new_percent(1),
+            reduce_only,
+        )
+    }
+
+    /// Sell 10 ETH over 10 minutes in 1-minute slices.
+    ///
+    /// Expected: order stored with 10 remaining slices, the first one due
+    /// immediately, and the global `NEXT_ORDER_ID` counter advanced by one.
+    #[test]
+    fn submitting_twap_order() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default())
+            .with_block_timestamp(Timestamp::from_seconds(100));
+
+        init_storage(&mut ctx.storage);
+
+        submit(
+            ctx.as_mutable(),
+            -10,
+            Duration::from_minutes(10),
+            Duration::from_minutes(1),
+            false,
+        )
+        .should_succeed();
+
+        let order = TWAP_ORDERS.load(&ctx.storage, Uint64::ONE).unwrap();
+        assert_eq!(
+            order,
+            TwapOrder {
+                user: USER,
+                pair_id: pair_id(),
+                size: Quantity::new_int(-10),
+                filled_size: Quantity::ZERO,
+                max_slippage: Dimensionless::new_percent(1),
+                reduce_only: false,
+                slice_interval: Duration::from_minutes(1),
+                remaining_slices: 10,
+                next_slice_time: Timestamp::from_seconds(100),
+            }
+        );
+
+        assert_eq!(NEXT_ORDER_ID.load(&ctx.storage).unwrap(), Uint64::new(2));
+    }
+
+    #[test_case(0, 600, 60, false, "size can't be zero" ; "zero size")]
+    #[test_case(10, 600, 10, false, "invalid `slice_interval`" ; "slice interval too short")]
+    #[test_case(10, 172_800, 60, false, "invalid `duration`" ; "duration too long")]
+    #[test_case(10, 30, 60, false, "duration must be no shorter than the slice interval" ; "duration shorter than slice interval")]
+    #[test_case(1, 600, 60, false, "order size is below minimum" ; "slice below min order size")]
+    fn rejecting_invalid_twap_order(
+        size: i128,
+        duration_secs: u128,
+        slice_interval_secs: u128,
+        reduce_only: bool,
+        error: &str,
+    ) {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit(
+            ctx.as_mutable(),
+            size,
+            Duration::from_seconds(duration_secs),
+            Duration::from_seconds(slice_interval_secs),
+            reduce_only,
+        )
+        .should_fail_with_error(error);
+    }
+
+    /// A reduce-only TWAP order is exempt from the minimum order size, same as
+    /// a reduce-only market order.
+    #[test]
+    fn reduce_only_twap_order_skips_min_order_size() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit(
+            ctx.as_mutable(),
+            1,
+            Duration::from_minutes(10),
+            Duration::from_minutes(1),
+            true,
+        )
+        .should_succeed();
+    }
+
+    #[test]
+    fn rejecting_slippage_above_pair_cap() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        submit_twap_order(
+            ctx.as_mutable(),
+            pair_id(),
+            Quantity::new_int(10),
+            Duration::from_minutes(10),
+            Duration::from_minutes(1),
+            Dimensionless::new_percent(20),
+            false,
+        )
+        .should_fail_with_error("max slippage");
+    }
+
+    #[test]
+    fn rejecting_too_many_twap_orders() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        for _ in 0..MAX_TWAP_ORDERS_PER_USER {
+            submit(
+                ctx.as_mutable(),
+                10,
+                Duration::from_minutes(10),
+                Duration::from_minutes(1),
+                false,
+            )
+            .should_succeed();
+        }
+
+        submit(
+            ctx.as_mutable(),
+            10,
+            Duration::from_minutes(10),
+            Duration::from_minutes(1),
+            false,
+        )
+        .should_fail_with_error("too many twap orders");
+    }
+
+    #[test]
+    fn rejecting_twap_order_when_trading_disabled() {
+        let mut ctx = MockContext::new()
+            .with_sender(USER)
+            .with_funds(Coins::default());
+
+        init_storage(&mut ctx.storage);
+
+        PARAM
+            .save(
+                &mut ctx.storage,
+                &Param {
+                    trading_enabled: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        submit(
+            ctx.as_mutable(),
+            10,
+            Duration::from_minutes(10),
+            Duration::from_minutes(1),
+            false,
+        )
+        .should_fail_with_error("trading is disabled");
+    }
+}
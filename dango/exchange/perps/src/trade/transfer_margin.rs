@@ -0,0 +1,235 @@
+use {
+    crate::{
+        core::compute_available_margin, querier::NoCachePerpQuerier, state::USER_STATES,
+        trade::load_or_register_account_owner,
+    },
+    anyhow::{anyhow, ensure},
+    dango_order_book::UsdValue,
+    dango_primitives::{Addr, MutableCtx, Response},
+    dango_types::perps::MarginTransferred,
+};
+
+/// Move margin from the sender's account to another account owned by the same
+/// user.
+///
+/// Like a withdrawal, the amount is validated against the sender's available
+/// margin, so the transfer can't leave the sender's positions and resting
+/// orders under-collateralized. Unlike a withdrawal, no tokens leave the
+/// contract.
+///
+/// Mutates: `USER_STATES` (sender's margin decreased, possibly removed if
+/// empty; recipient's margin increased), `ACCOUNT_OWNERS`.
+pub fn transfer_margin(ctx: MutableCtx, to: Addr, amount: UsdValue) -> anyhow::Result<Response> {
+    ensure!(
+        amount.is_positive(),
+        "can only transfer positive amount of margin"
+    );
+
+    ensure!(
+        to != ctx.sender,
+        "can't transfer margin to the same account"
+    );
+
+    // ---------------------------- 1. Check owners ----------------------------
+
+    let sender_owner = load_or_register_account_owner(ctx.storage, ctx.querier, ctx.sender)?
+        .ok_or_else(|| anyhow!("sender {} is not an account", ctx.sender))?;
+
+    let recipient_owner = load_or_register_account_owner(ctx.storage, ctx.querier, to)?
+        .ok_or_else(|| anyhow!("recipient {to} is not an account"))?;
+
+    ensure!(
+        sender_owner == recipient_owner,
+        "can only transfer margin between accounts owned by the same user"
+    );
+
+    // ---------------------- 2. Compute available margin ----------------------
+
+    let perp_querier = NoCachePerpQuerier::new_local(ctx.storage);
+
+    let mut sender_state = USER_STATES
+        .may_load(ctx.storage, ctx.sender)?
+        .unwrap_or_default();
+
+    let available = compute_available_margin(&perp_querier, &sender_state)?;
+
+    ensure!(
+        amount <= available,
+        "transfer amount ({amount}) exceeds available margin ({available})"
+    );
+
+    // ------------------- 3. Update and persist user states -------------------
+
+    sender_state.margin.checked_sub_assign(amount)?;
+
+    if sender_state.is_empty() {
+        USER_STATES.remove(ctx.storage, ctx.sender)?;
+    } else {
+        USER_STATES.save(ctx.storage, ctx.sender, &sender_state)?;
+    }
+
+    let mut recipient_state = USER_STATES.may_load(ctx.storage, to)?.unwrap_or_default();
+
+    recipient_state.margin.checked_add_assign(amount)?;
+
+    USER_STATES.save(ctx.storage, to, &recipient_state)?;
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(
+            from = %ctx.sender,
+            %to,
+            %amount,
+            "Margin transferred"
+        );
+    }
+
+    Ok(Response::new().add_event(MarginTransferred {
+        from: ctx.sender,
+        to,
+        amount,
+    })?)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::state::ACCOUNT_OWNERS,
+        dango_account_factory::USERS,
+        dango_primitives::{Coins, MockContext, MockQuerier, ResultExt, Storage, btree_map},
+        dango_types::{
+            account_factory::{User, Username},
+            config::{AppAddresses, AppConfig},
+            perps::UserState,
+        },
+    };
+
+    const ACCOUNT_FACTORY: Addr = Addr::mock(100);
+    const SENDER: Addr = Addr::mock(1);
+    const SUBACCOUNT: Addr = Addr::mock(2);
+    const STRANGER: Addr = Addr::mock(3);
+    const NOT_AN_ACCOUNT: Addr = Addr::mock(4);
+
+    /// Querier whose account factory knows `SENDER` and `SUBACCOUNT` as owned
+    /// by user 1, and `STRANGER` as owned by user 2.
+    fn querier() -> MockQuerier {
+        MockQuerier::new()
+            .with_app_config(AppConfig {
+                addresses: AppAddresses {
+                    account_factory: ACCOUNT_FACTORY,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap()
+            .with_raw_contract_storage(ACCOUNT_FACTORY, |storage| {
+                for (index, accounts) in [
+                    (1, btree_map! { 0 => SENDER, 1 => SUBACCOUNT }),
+                    (2, btree_map! { 2 => STRANGER }),
+                ] {
+                    let user = User {
+                        index,
+                        name: Username::default_for_index(index),
+                        accounts,
+                        keys: btree_map! {},
+                    };
+                    USERS.save(storage, index, &user).unwrap();
+                }
+            })
+    }
+
+    fn save_margin(storage: &mut dyn Storage, user: Addr, margin: i128) {
+        USER_STATES
+            .save(
+                storage,
+                user,
+                &UserState {
+                    margin: UsdValue::new_int(margin),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn transferring_margin_to_subaccount() {
+        let mut ctx = MockContext::new()
+            .with_querier(querier())
+            .with_sender(SENDER)
+            .with_funds(Coins::default());
+
+        save_margin(&mut ctx.storage, SENDER, 1_000);
+
+        transfer_margin(ctx.as_mutable(), SUBACCOUNT, UsdValue::new_int(400)).should_succeed();
+
+        assert_eq!(
+            USER_STATES.load(&ctx.storage, SENDER).unwrap().margin,
+            UsdValue::new_int(600)
+        );
+        assert_eq!(
+            USER_STATES.load(&ctx.storage, SUBACCOUNT).unwrap().margin,
+            UsdValue::new_int(400)
+        );
+
+        // Both accounts are now known to belong to the same user.
+        assert_eq!(ACCOUNT_OWNERS.load(&ctx.storage, SENDER).unwrap(), 1);
+        assert_eq!(ACCOUNT_OWNERS.load(&ctx.storage, SUBACCOUNT).unwrap(), 1);
+
+        // Transferring the rest removes the sender's now-empty state.
+        transfer_margin(ctx.as_mutable(), SUBACCOUNT, UsdValue::new_int(600)).should_succeed();
+
+        assert!(!USER_STATES.has(&ctx.storage, SENDER));
+        assert_eq!(
+            USER_STATES.load(&ctx.storage, SUBACCOUNT).unwrap().margin,
+            UsdValue::new_int(1_000)
+        );
+    }
+
+    #[test]
+    fn rejecting_transfer_exceeding_available_margin() {
+        let mut ctx = MockContext::new()
+            .with_querier(querier())
+            .with_sender(SENDER)
+            .with_funds(Coins::default());
+
+        save_margin(&mut ctx.storage, SENDER, 1_000);
+
+        transfer_margin(ctx.as_mutable(), SUBACCOUNT, UsdValue::new_int(1_001))
+            .should_fail_with_error("exceeds available margin");
+    }
+
+    #[test]
+    fn rejecting_transfer_to_other_user() {
+        let mut ctx = MockContext::new()
+            .with_querier(querier())
+            .with_sender(SENDER)
+            .with_funds(Coins::default());
+
+        save_margin(&mut ctx.storage, SENDER, 1_000);
+
+        transfer_margin(ctx.as_mutable(), STRANGER, UsdValue::new_int(100))
+            .should_fail_with_error("owned by the same user");
+
+        transfer_margin(ctx.as_mutable(), NOT_AN_ACCOUNT, UsdValue::new_int(100))
+            .should_fail_with_error("is not an account");
+    }
+
+    #[test]
+    fn rejecting_invalid_transfer() {
+        let mut ctx = MockContext::new()
+            .with_querier(querier())
+            .with_sender(SENDER)
+            .with_funds(Coins::default());
+
+        save_margin(&mut ctx.storage, SENDER, 1_000);
+
+        transfer_margin(ctx.as_mutable(), SUBACCOUNT, UsdValue::ZERO)
+            .should_fail_with_error("can only transfer positive amount");
+
+        transfer_margin(ctx.as_mutable(), SENDER, UsdValue::new_int(100))
+            .should_fail_with_error("same account");
+    }
+}
@@ -622,8 +622,8 @@ mod tests {
                     size: Quantity::new_int(1),
                     entry_price: UsdPrice::new_int(2000),
                     entry_funding_per_unit: FundingPerUnit::new_int(0),
-                    conditional_order_above: None,
-                    conditional_order_below: None,
+                    conditional_orders_above: Default::default(),
+                    conditional_orders_below: Default::default(),
                 },
             },
             ..Default::default()
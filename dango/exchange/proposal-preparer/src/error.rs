@@ -0,0 +1,45 @@
+use {
+    dango_app::AppError,
+    dango_backtrace::Backtraceable,
+    dango_primitives::{StdError, Timestamp},
+};
+
+#[dango_backtrace::backtrace]
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProposalError {
+    #[error(transparent)]
+    Std(StdError),
+
+    #[error("injected tx at index {index} is malformed")]
+    MalformedInjectedTx { index: usize },
+
+    #[error("unexpected injected tx at index {index}")]
+    UnexpectedInjectedTx { index: usize },
+
+    #[error("missing perps maintenance tx at index {index}")]
+    MissingMaintenanceTx { index: usize },
+
+    #[error(
+        "stale price update! publish time: {} ms, block time: {} ms",
+        .publish_time.into_millis(),
+        .block_time.into_millis()
+    )]
+    StalePriceUpdate {
+        publish_time: Timestamp,
+        block_time: Timestamp,
+    },
+
+    #[error("maker-priority order violated at index {index}")]
+    MakerPriorityViolated { index: usize },
+}
+
+impl From<ProposalError> for AppError {
+    fn from(err: ProposalError) -> Self {
+        AppError::PrepareProposal {
+            error: err.error(),
+            backtrace: err.backtrace(),
+        }
+    }
+}
+
+pub type ProposalResult<T> = core::result::Result<T, ProposalError>;
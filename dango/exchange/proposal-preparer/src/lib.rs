@@ -1,5 +1,6 @@
+mod error;
 mod maker_priority_handler;
 mod proposal_preparer;
 mod pyth_handler;
 
-pub use {maker_priority_handler::*, proposal_preparer::*, pyth_handler::*};
+pub use {error::*, maker_priority_handler::*, proposal_preparer::*, pyth_handler::*};
@@ -1,6 +1,9 @@
 use {
+    crate::{ProposalError, ProposalResult},
     dango_order_book::{OrderKind, TimeInForce},
-    dango_primitives::{Addr, Inner, JsonDeExt, Message, QuerierExt, QuerierWrapper, StdError, Tx},
+    dango_primitives::{
+        Addr, BlockInfo, Inner, JsonDeExt, Message, QuerierExt, QuerierWrapper, Tx,
+    },
     dango_types::{
         config::AppConfig,
         perps::{self, SubmitOrCancelOrderRequest, TraderMsg},
@@ -30,17 +33,28 @@ use {
 pub struct MakerPriorityHandler;
 
 impl dango_app::ProposalPreparer for MakerPriorityHandler {
-    type Error = StdError;
+    type Error = ProposalError;
 
     fn prepare_proposal(
         &self,
         querier: QuerierWrapper,
+        _block: BlockInfo,
         txs: Vec<Bytes>,
         _max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error> {
         let cfg: AppConfig = querier.query_app_config()?;
         Ok(promote_priority_txs(txs, &cfg.addresses.perps))
     }
+
+    fn process_proposal(
+        &self,
+        querier: QuerierWrapper,
+        _block: BlockInfo,
+        txs: &[Bytes],
+    ) -> Result<(), Self::Error> {
+        let cfg: AppConfig = querier.query_app_config()?;
+        check_priority_order(txs, 0, &cfg.addresses.perps)
+    }
 }
 
 /// Bucket assigned to a transaction during proposal preparation.
@@ -61,6 +75,17 @@ pub enum PriorityClass {
     Other,
 }
 
+impl PriorityClass {
+    /// Position of the bucket in the block; lower comes first.
+    fn rank(self) -> u8 {
+        match self {
+            PriorityClass::Placement => 0,
+            PriorityClass::Cancel => 1,
+            PriorityClass::Other => 2,
+        }
+    }
+}
+
 /// Per-message classification used while scanning a tx.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MsgClass {
@@ -95,6 +120,28 @@ fn promote_priority_txs(txs: Vec<Bytes>, perps: &Addr) -> Vec<Bytes> {
     placements
 }
 
+/// Check that the transactions are in the order `promote_priority_txs` would
+/// have put them in: post-only placements first, then cancellations, then
+/// everything else.
+///
+/// The first `skip` transactions are the ones injected by the Pyth handler,
+/// which sit on top of the priority group and are exempt from the check.
+pub fn check_priority_order(txs: &[Bytes], skip: usize, perps: &Addr) -> ProposalResult<()> {
+    let mut previous = PriorityClass::Placement;
+
+    for (index, raw_tx) in txs.iter().enumerate().skip(skip) {
+        let class = classify_tx(raw_tx.as_ref(), perps);
+
+        if class.rank() < previous.rank() {
+            return Err(ProposalError::maker_priority_violated(index));
+        }
+
+        previous = class;
+    }
+
+    Ok(())
+}
+
 /// Classify a raw tx into one of the three priority buckets.
 ///
 /// `Other` covers malformed bytes, non-perps execute targets, non-trade
@@ -140,7 +187,13 @@ pub fn classify_tx(raw_tx: &[u8], perps: &Addr) -> PriorityClass {
 
 fn classify_trader_msg(msg: &perps::TraderMsg) -> MsgClass {
     match msg {
-        TraderMsg::CancelOrder(_) | TraderMsg::CancelConditionalOrder(_) => MsgClass::CancelOnly,
+        // Arming the dead-man's switch only ever cancels orders, and market
+        // makers refresh it in the same tx as their quotes.
+        TraderMsg::CancelOrder(_)
+        | TraderMsg::CancelConditionalOrder(_)
+        | TraderMsg::CancelTwapOrder(_)
+        | TraderMsg::CancelOrderGroup { .. }
+        | TraderMsg::CancelAllAfter { .. } => MsgClass::CancelOnly,
         TraderMsg::SubmitOrder(req) => {
             if is_post_only(&req.kind) {
                 MsgClass::HasPlacement
@@ -167,7 +220,13 @@ fn classify_trader_msg(msg: &perps::TraderMsg) -> MsgClass {
         // `TraderMsg` variant forces an explicit decision at compile time.
         TraderMsg::Deposit { .. }
         | TraderMsg::Withdraw { .. }
-        | TraderMsg::SubmitConditionalOrder { .. } => MsgClass::NotPriority,
+        | TraderMsg::TransferMargin { .. }
+        | TraderMsg::SubmitConditionalOrder { .. }
+        | TraderMsg::SubmitTwapOrder { .. }
+        | TraderMsg::SubmitOrderGroup { .. }
+        | TraderMsg::SetMarginMode { .. }
+        | TraderMsg::AddIsolatedMargin { .. }
+        | TraderMsg::RemoveIsolatedMargin { .. } => MsgClass::NotPriority,
     }
 }
 
@@ -205,12 +264,13 @@ mod tests {
             ChildOrder, Dimensionless, OrderKind, Quantity, TimeInForce, TriggerDirection,
             UsdPrice, UsdValue,
         },
-        dango_primitives::{Coins, Json, JsonSerExt, MsgExecute, NonEmpty},
+        dango_primitives::{Coins, Duration, Json, JsonSerExt, MsgExecute, NonEmpty},
         dango_types::{
             constants::perp_btc,
             perps::{
-                CancelConditionalOrderRequest, CancelOrderRequest, ExecuteMsg, MaintainerMsg,
-                ReferralMsg, SubmitOrCancelOrderRequest, SubmitOrderRequest, TraderMsg,
+                CancelConditionalOrderRequest, CancelOrderRequest, CancelTwapOrderRequest,
+                ExecuteMsg, MaintainerMsg, ReferralMsg, SubmitOrCancelOrderRequest,
+                SubmitOrderRequest, TraderMsg,
             },
         },
         test_case::test_case,
@@ -270,6 +330,7 @@ mod tests {
             reduce_only: false,
             tp: None,
             sl: None,
+            stp_mode: Default::default(),
         }
     }
 
@@ -289,6 +350,7 @@ mod tests {
             reduce_only: false,
             tp: Some(child_order()),
             sl: Some(child_order()),
+            stp_mode: Default::default(),
         }
     }
 
@@ -310,6 +372,7 @@ mod tests {
         TraderMsg::CancelConditionalOrder(CancelConditionalOrderRequest::One {
             pair_id: perp_btc::DENOM.clone(),
             trigger_direction: TriggerDirection::Above,
+            order_id: None,
         })
     }
 
@@ -323,6 +386,16 @@ mod tests {
         TraderMsg::CancelConditionalOrder(CancelConditionalOrderRequest::All)
     }
 
+    fn cancel_twap_all() -> TraderMsg {
+        TraderMsg::CancelTwapOrder(CancelTwapOrderRequest::All)
+    }
+
+    fn cancel_all_after() -> TraderMsg {
+        TraderMsg::CancelAllAfter {
+            timeout: Some(Duration::from_seconds(10)),
+        }
+    }
+
     fn submit_post_only() -> TraderMsg {
         TraderMsg::SubmitOrder(submit(post_only_limit()))
     }
@@ -405,6 +478,19 @@ mod tests {
             trigger_price: UsdPrice::new_int(100),
             trigger_direction: TriggerDirection::Above,
             max_slippage: Dimensionless::new_int(0),
+            limit_price: None,
+            trailing_offset: None,
+        }
+    }
+
+    fn submit_twap() -> TraderMsg {
+        TraderMsg::SubmitTwapOrder {
+            pair_id: perp_btc::DENOM.clone(),
+            size: Quantity::new_int(1),
+            duration: Duration::from_minutes(10),
+            slice_interval: Duration::from_minutes(1),
+            max_slippage: Dimensionless::new_int(0),
+            reduce_only: false,
         }
     }
 
@@ -438,6 +524,8 @@ mod tests {
     #[test_case(cancel_cond_one()                 => MsgClass::CancelOnly   ; "case_cancel_conditional_one")]
     #[test_case(cancel_cond_all_for_pair()        => MsgClass::CancelOnly   ; "case_cancel_conditional_all_for_pair")]
     #[test_case(cancel_cond_all()                 => MsgClass::CancelOnly   ; "case_cancel_conditional_all")]
+    #[test_case(cancel_twap_all()                 => MsgClass::CancelOnly   ; "case_cancel_twap_all")]
+    #[test_case(cancel_all_after()                => MsgClass::CancelOnly   ; "case_cancel_all_after")]
     #[test_case(submit_post_only()                => MsgClass::HasPlacement ; "case_submit_post_only")]
     #[test_case(submit_post_only_with_tp_sl()     => MsgClass::HasPlacement ; "case_submit_post_only_with_tp_sl")]
     #[test_case(submit_post_only_with_client_id() => MsgClass::HasPlacement ; "case_submit_post_only_with_client_id")]
@@ -453,6 +541,7 @@ mod tests {
     #[test_case(deposit()                         => MsgClass::NotPriority  ; "case_deposit")]
     #[test_case(withdraw()                        => MsgClass::NotPriority  ; "case_withdraw")]
     #[test_case(submit_conditional()              => MsgClass::NotPriority  ; "case_submit_conditional")]
+    #[test_case(submit_twap()                     => MsgClass::NotPriority  ; "case_submit_twap")]
     fn priority_trader_msg(msg: TraderMsg) -> MsgClass {
         classify_trader_msg(&msg)
     }
@@ -629,4 +718,47 @@ mod tests {
         let txs = promote_priority_txs(vec![n1.clone(), p.clone(), n2.clone()], &perps());
         assert_eq!(txs, vec![p, n1, n2]);
     }
+
+    // --- check_priority_order ----------------------------------------------
+
+    #[test]
+    fn check_promoted_order_passes() {
+        let txs = promote_priority_txs(
+            vec![
+                perps_tx(vec![cancel_all()]),
+                perps_tx(vec![submit_market()]),
+                perps_tx(vec![submit_post_only()]),
+                perps_tx(vec![deposit()]),
+                perps_tx(vec![batch_mixed_priority()]),
+            ],
+            &perps(),
+        );
+        check_priority_order(&txs, 0, &perps()).unwrap();
+    }
+
+    #[test_case(vec![perps_tx(vec![cancel_all()]), perps_tx(vec![submit_post_only()])]  => Some(1) ; "cancel_before_placement")]
+    #[test_case(vec![perps_tx(vec![submit_market()]), perps_tx(vec![cancel_one()])]     => Some(1) ; "other_before_cancel")]
+    #[test_case(vec![perps_tx(vec![submit_post_only()]), perps_tx(vec![deposit()]), perps_tx(vec![submit_post_only()])] => Some(2) ; "other_before_placement")]
+    #[test_case(vec![perps_tx(vec![submit_post_only()]), perps_tx(vec![cancel_one()]), perps_tx(vec![withdraw()])] => None ; "in_order")]
+    fn check_priority_order_violation(txs: Vec<Bytes>) -> Option<usize> {
+        match check_priority_order(&txs, 0, &perps()) {
+            Ok(()) => None,
+            Err(ProposalError::MakerPriorityViolated { index, .. }) => Some(index),
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    /// The injected txs on top of the block are non-priority, but they are
+    /// skipped rather than treated as a violation.
+    #[test]
+    fn check_priority_order_skips_injected_txs() {
+        let txs = vec![
+            perps_tx(vec![deposit()]),
+            perps_tx(vec![submit_post_only()]),
+            perps_tx(vec![cancel_all()]),
+        ];
+
+        assert!(check_priority_order(&txs, 0, &perps()).is_err());
+        check_priority_order(&txs, 1, &perps()).unwrap();
+    }
 }
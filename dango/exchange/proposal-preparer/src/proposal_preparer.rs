@@ -1,11 +1,13 @@
 use {
     crate::{
-        maker_priority_handler::MakerPriorityHandler,
-        pyth_handler::{PythHandler, QueryPythId},
+        ProposalError,
+        maker_priority_handler::{MakerPriorityHandler, check_priority_order},
+        pyth_handler::{PythHandler, QueryPythId, check_injected_txs},
     },
-    dango_primitives::{Lengthy, NonEmpty, QuerierWrapper, StdError},
+    dango_primitives::{BlockInfo, Lengthy, NonEmpty, QuerierExt, QuerierWrapper},
     dango_pyth_client::{PythClient, PythClientCache, PythClientTrait},
     dango_pyth_types::constants::LAZER_ENDPOINTS_TEST,
+    dango_types::{config::AppConfig, perps},
     prost::bytes::Bytes,
     reqwest::IntoUrl,
     std::fmt::Debug,
@@ -63,11 +65,12 @@ where
     P: PythClientTrait + QueryPythId + Send + 'static,
     P::Error: Debug,
 {
-    type Error = StdError;
+    type Error = ProposalError;
 
     fn prepare_proposal(
         &self,
         querier: QuerierWrapper,
+        block: BlockInfo,
         txs: Vec<Bytes>,
         max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error> {
@@ -76,7 +79,26 @@ where
         // traffic. Final layout: `[oracle, priority_makers..., others...]`.
         let txs = self
             .maker_priority
-            .prepare_proposal(querier, txs, max_tx_bytes)?;
-        self.pyth.prepare_proposal(querier, txs, max_tx_bytes)
+            .prepare_proposal(querier, block, txs, max_tx_bytes)?;
+        self.pyth
+            .prepare_proposal(querier, block, txs, max_tx_bytes)
+    }
+
+    fn process_proposal(
+        &self,
+        querier: QuerierWrapper,
+        block: BlockInfo,
+        txs: &[Bytes],
+    ) -> Result<(), Self::Error> {
+        // Mirror `prepare_proposal`: the injected txs on top, then the maker
+        // priority order for the rest of the block.
+        let cfg: AppConfig = querier.query_app_config()?;
+        let trading_enabled = querier
+            .query_wasm_smart(cfg.addresses.perps, perps::QueryParamRequest {})?
+            .trading_enabled;
+
+        let injected = check_injected_txs(txs, &cfg, trading_enabled, block.timestamp)?;
+
+        check_priority_order(txs, injected, &cfg.addresses.perps)
     }
 }
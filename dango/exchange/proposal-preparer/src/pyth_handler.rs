@@ -1,10 +1,11 @@
 use {
+    crate::{ProposalError, ProposalResult},
     dango_primitives::{
-        Addr, Coins, Json, JsonSerExt, Lengthy, Message, NonEmpty, QuerierExt, QuerierWrapper,
-        Shared, StdError, StdResult, Tx,
+        Addr, BlockInfo, Coins, Duration, Inner, Json, JsonDeExt, JsonSerExt, Lengthy, Message,
+        NonEmpty, QuerierExt, QuerierWrapper, Shared, StdResult, Timestamp, Tx,
     },
     dango_pyth_client::{PythClient, PythClientCache, PythClientTrait},
-    dango_pyth_types::{PriceUpdate, PythLazerSubscriptionDetails},
+    dango_pyth_types::{PayloadData, PriceUpdate, PythLazerSubscriptionDetails},
     dango_types::{
         config::AppConfig,
         oracle::{ExecuteMsg, QueryPriceSourcesRequest},
@@ -19,7 +20,6 @@ use {
             atomic::{AtomicBool, Ordering},
         },
         thread,
-        time::Duration,
     },
     tokio::{runtime::Runtime, time::sleep},
     tokio_stream::StreamExt,
@@ -38,6 +38,22 @@ const CONNECT_ATTEMPTS: usize = 3;
 /// each block.
 const GAS_LIMIT: u64 = 50_000_000;
 
+/// Perps maintenance messages injected after the oracle price-feed
+/// transaction, one per transaction, in this order.
+const MAINTENANCE_MSGS: [perps::MaintainerMsg; 2] = [
+    perps::MaintainerMsg::RefreshIndexPrices {},
+    perps::MaintainerMsg::RefreshVaultOrders {},
+];
+
+/// Validators reject a proposal whose injected price update contains a
+/// payload published longer than this before the proposed block's time, and
+/// proposers don't inject such an update in the first place.
+///
+/// Much looser than the staleness the perps contract tolerates, since the
+/// block time and the proposer's clock may drift apart by a few seconds. The
+/// aim is only to catch proposers feeding cached prices.
+pub const MAX_PRICE_UPDATE_AGE: Duration = Duration::from_seconds(5);
+
 /// Handler for the PythClient to be used in the ProposalPreparer, used to
 /// keep all code related to Pyth for PP in a single structure.
 pub struct PythHandler<P>
@@ -183,11 +199,12 @@ where
     P: PythClientTrait + QueryPythId + Send + 'static,
     P::Error: Debug,
 {
-    type Error = StdError;
+    type Error = ProposalError;
 
     fn prepare_proposal(
         &self,
         querier: QuerierWrapper,
+        block: BlockInfo,
         mut txs: Vec<Bytes>,
         _max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error> {
@@ -211,19 +228,16 @@ where
             return Ok(txs);
         };
 
+        // Validators would reject the block if the prices are stale, so leave
+        // them out, along with the perps maintenance txs that depend on them.
+        if let Err(err) = check_price_update(&price_update, block.timestamp, 0) {
+            warn!(err = err.to_string(), "Not injecting price update");
+            return Ok(txs);
+        }
+
         // Build the oracle price-feed tx and insert it at the front of the block.
         {
-            let oracle_tx = Tx {
-                sender: cfg.addresses.oracle,
-                gas_limit: GAS_LIMIT,
-                msgs: NonEmpty::new_unchecked(vec![Message::execute(
-                    cfg.addresses.oracle,
-                    &ExecuteMsg::FeedPrices(price_update),
-                    Coins::new(),
-                )?]),
-                data: Json::null(),
-                credential: Json::null(),
-            };
+            let oracle_tx = oracle_tx(cfg.addresses.oracle, price_update)?;
 
             txs.insert(0, oracle_tx.to_json_vec()?.into());
         }
@@ -244,38 +258,12 @@ where
             return Ok(txs);
         }
 
-        // Insert perps index-price refresh transaction.
-        {
-            let index_tx = Tx {
-                sender: cfg.addresses.perps,
-                gas_limit: GAS_LIMIT,
-                msgs: NonEmpty::new_unchecked(vec![Message::execute(
-                    cfg.addresses.perps,
-                    &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::RefreshIndexPrices {}),
-                    Coins::new(),
-                )?]),
-                data: Json::null(),
-                credential: Json::null(),
-            };
+        // Insert perps index-price refresh transaction, then perps vault-order
+        // refresh transaction.
+        for (index, msg) in MAINTENANCE_MSGS.into_iter().enumerate() {
+            let maintenance_tx = maintenance_tx(cfg.addresses.perps, msg)?;
 
-            txs.insert(1, index_tx.to_json_vec()?.into());
-        }
-
-        // Insert perps vault-order refresh transaction.
-        {
-            let vault_tx = Tx {
-                sender: cfg.addresses.perps,
-                gas_limit: GAS_LIMIT,
-                msgs: NonEmpty::new_unchecked(vec![Message::execute(
-                    cfg.addresses.perps,
-                    &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::RefreshVaultOrders {}),
-                    Coins::new(),
-                )?]),
-                data: Json::null(),
-                credential: Json::null(),
-            };
-
-            txs.insert(2, vault_tx.to_json_vec()?.into());
+            txs.insert(index + 1, maintenance_tx.to_json_vec()?.into());
         }
 
         #[cfg(feature = "metrics")]
@@ -284,6 +272,168 @@ where
 
         Ok(txs)
     }
+
+    fn process_proposal(
+        &self,
+        querier: QuerierWrapper,
+        block: BlockInfo,
+        txs: &[Bytes],
+    ) -> Result<(), Self::Error> {
+        // Unlike `prepare_proposal`, this doesn't depend on the handler being
+        // enabled: a validator that doesn't stream prices itself still checks
+        // the prices injected by others.
+        let cfg: AppConfig = querier.query_app_config()?;
+        let trading_enabled = querier
+            .query_wasm_smart(cfg.addresses.perps, perps::QueryParamRequest {})?
+            .trading_enabled;
+
+        check_injected_txs(txs, &cfg, trading_enabled, block.timestamp)?;
+
+        Ok(())
+    }
+}
+
+/// Build the oracle price-feed transaction injected at the top of the block.
+fn oracle_tx(oracle: Addr, price_update: PriceUpdate) -> StdResult<Tx> {
+    Ok(Tx {
+        sender: oracle,
+        gas_limit: GAS_LIMIT,
+        msgs: NonEmpty::new_unchecked(vec![Message::execute(
+            oracle,
+            &ExecuteMsg::FeedPrices(price_update),
+            Coins::new(),
+        )?]),
+        data: Json::null(),
+        credential: Json::null(),
+    })
+}
+
+/// Build a perps maintenance transaction injected after the oracle tx.
+fn maintenance_tx(perps: Addr, msg: perps::MaintainerMsg) -> StdResult<Tx> {
+    Ok(Tx {
+        sender: perps,
+        gas_limit: GAS_LIMIT,
+        msgs: NonEmpty::new_unchecked(vec![Message::execute(
+            perps,
+            &perps::ExecuteMsg::Maintain(msg),
+            Coins::new(),
+        )?]),
+        data: Json::null(),
+        credential: Json::null(),
+    })
+}
+
+/// Check the transactions a proposer has injected at the top of the block,
+/// the way `prepare_proposal` would have injected them:
+///
+/// - at most one oracle price-feed tx, at index 0, with fresh prices;
+/// - if there is one and trading is enabled, the two perps maintenance txs,
+///   at index 1 and 2 respectively.
+///
+/// The oracle and perps contracts only authenticate txs during block
+/// finalization, so any tx sent by them can only have been injected by the
+/// proposer. Any of them that isn't one of the above rejects the block.
+///
+/// Returns the number of injected transactions.
+pub fn check_injected_txs(
+    txs: &[Bytes],
+    cfg: &AppConfig,
+    trading_enabled: bool,
+    block_time: Timestamp,
+) -> ProposalResult<usize> {
+    let oracle = cfg.addresses.oracle;
+    let perps = cfg.addresses.perps;
+
+    let mut has_oracle_tx = false;
+    let mut has_maintenance_tx = [false; MAINTENANCE_MSGS.len()];
+
+    for (index, raw_tx) in txs.iter().enumerate() {
+        // Bytes that don't deserialize can't be an injected tx. They fail when
+        // the block is finalized, same as in a block that we'd have proposed.
+        let Ok(tx) = raw_tx.deserialize_json::<Tx>() else {
+            continue;
+        };
+
+        if tx.sender == oracle {
+            if index != 0 {
+                return Err(ProposalError::unexpected_injected_tx(index));
+            }
+
+            let price_update = parse_oracle_tx(&tx, oracle)
+                .ok_or_else(|| ProposalError::malformed_injected_tx(index))?;
+
+            check_price_update(&price_update, block_time, index)?;
+
+            has_oracle_tx = true;
+        } else if tx.sender == perps {
+            let Some(msg) = index
+                .checked_sub(1)
+                .and_then(|i| MAINTENANCE_MSGS.get(i).cloned())
+            else {
+                return Err(ProposalError::unexpected_injected_tx(index));
+            };
+
+            if !has_oracle_tx || !trading_enabled {
+                return Err(ProposalError::unexpected_injected_tx(index));
+            }
+
+            if tx != maintenance_tx(perps, msg)? {
+                return Err(ProposalError::malformed_injected_tx(index));
+            }
+
+            has_maintenance_tx[index - 1] = true;
+        }
+    }
+
+    if !has_oracle_tx {
+        return Ok(0);
+    }
+
+    if !trading_enabled {
+        return Ok(1);
+    }
+
+    if let Some(i) = has_maintenance_tx.iter().position(|has| !has) {
+        return Err(ProposalError::missing_maintenance_tx(i + 1));
+    }
+
+    Ok(1 + MAINTENANCE_MSGS.len())
+}
+
+/// Extract the price update from an oracle tx, if the tx is exactly the one
+/// `prepare_proposal` would have built for it.
+fn parse_oracle_tx(tx: &Tx, oracle: Addr) -> Option<PriceUpdate> {
+    let [Message::Execute(msg)] = tx.msgs.inner().as_slice() else {
+        return None;
+    };
+
+    let Ok(ExecuteMsg::FeedPrices(price_update)) = msg.msg.clone().deserialize_json() else {
+        return None;
+    };
+
+    oracle_tx(oracle, price_update.clone())
+        .is_ok_and(|expected| expected == *tx)
+        .then_some(price_update)
+}
+
+/// Check that every payload in the price update parses, and isn't older than
+/// `MAX_PRICE_UPDATE_AGE` at the proposed block's time.
+fn check_price_update(
+    price_update: &PriceUpdate,
+    block_time: Timestamp,
+    index: usize,
+) -> ProposalResult<()> {
+    for message in price_update.inner() {
+        let payload = PayloadData::deserialize_slice_le(&message.payload)
+            .map_err(|_| ProposalError::malformed_injected_tx(index))?;
+        let publish_time = Timestamp::from_micros(payload.timestamp_us.as_micros().into());
+
+        if publish_time + MAX_PRICE_UPDATE_AGE < block_time {
+            return Err(ProposalError::stale_price_update(publish_time, block_time));
+        }
+    }
+
+    Ok(())
 }
 
 impl<P> PythHandlerInner<P>
@@ -350,7 +500,7 @@ where
 
                                 if attempts < CONNECT_ATTEMPTS {
                                     error!(error = err.to_string(), "Failed to create Pyth stream; attempts: {attempts}");
-                                    sleep(Duration::from_millis(100)).await;
+                                    sleep(tokio::time::Duration::from_millis(100)).await;
                                 } else {
                                     error!("Failed to create Pyth stream after {attempts} attempts, stop retrying");
                                     keep_running.store(false, Ordering::SeqCst);
@@ -479,9 +629,155 @@ pub fn init_metrics() {
 mod tests {
     use {
         super::*,
+        byteorder::LE,
         dango_app::{NaiveQuerier, ProposalPreparer as _},
+        dango_primitives::{Binary, ByteArray, Hash256},
+        dango_pyth_types::LeEcdsaMessage,
+        dango_types::config::AppAddresses,
+        pyth_lazer_protocol::{ChannelId, time::TimestampUs},
+        test_case::test_case,
     };
 
+    const BLOCK_TIME: Timestamp = Timestamp::from_seconds(1_700_000_000);
+
+    fn block() -> BlockInfo {
+        BlockInfo {
+            height: 1,
+            timestamp: BLOCK_TIME,
+            hash: Hash256::ZERO,
+        }
+    }
+
+    fn cfg() -> AppConfig {
+        AppConfig {
+            addresses: AppAddresses {
+                oracle: Addr::mock(1),
+                perps: Addr::mock(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A price update with a single payload published at the given time. The
+    /// signature isn't valid, but it's only checked by the oracle contract.
+    fn price_update(publish_time: Timestamp) -> PriceUpdate {
+        let payload = PayloadData {
+            timestamp_us: TimestampUs::from_micros(publish_time.into_micros() as u64),
+            channel_id: ChannelId::REAL_TIME,
+            feeds: vec![],
+        };
+
+        let mut payload_bytes = Vec::new();
+        payload.serialize::<LE>(&mut payload_bytes).unwrap();
+
+        NonEmpty::new_unchecked(vec![LeEcdsaMessage {
+            payload: Binary::from_inner(payload_bytes),
+            signature: ByteArray::from_inner([0; 64]),
+            recovery_id: 0,
+        }])
+    }
+
+    fn encode(tx: Tx) -> Bytes {
+        tx.to_json_vec().unwrap().into()
+    }
+
+    fn fresh_oracle_tx() -> Bytes {
+        encode(oracle_tx(cfg().addresses.oracle, price_update(BLOCK_TIME)).unwrap())
+    }
+
+    fn refresh_index_tx() -> Bytes {
+        encode(maintenance_tx(cfg().addresses.perps, MAINTENANCE_MSGS[0].clone()).unwrap())
+    }
+
+    fn refresh_vault_tx() -> Bytes {
+        encode(maintenance_tx(cfg().addresses.perps, MAINTENANCE_MSGS[1].clone()).unwrap())
+    }
+
+    fn user_tx() -> Bytes {
+        encode(Tx {
+            sender: Addr::mock(99),
+            gas_limit: 100_000,
+            msgs: NonEmpty::new_unchecked(vec![
+                Message::transfer(Addr::mock(98), Coins::new()).unwrap(),
+            ]),
+            data: Json::null(),
+            credential: Json::null(),
+        })
+    }
+
+    #[test_case(vec![], true => Some(0) ; "empty_block")]
+    #[test_case(vec![user_tx()], true => Some(0) ; "no_injected_txs")]
+    #[test_case(vec![Bytes::from_static(b"garbage")], true => Some(0) ; "undecodable_tx_is_ignored")]
+    #[test_case(vec![fresh_oracle_tx(), refresh_index_tx(), refresh_vault_tx(), user_tx()], true => Some(3) ; "all_injected_txs")]
+    #[test_case(vec![fresh_oracle_tx(), user_tx()], false => Some(1) ; "oracle_tx_only_when_trading_disabled")]
+    #[test_case(vec![fresh_oracle_tx(), user_tx()], true => None ; "missing_maintenance_txs")]
+    #[test_case(vec![fresh_oracle_tx(), refresh_index_tx(), user_tx()], true => None ; "missing_vault_tx")]
+    #[test_case(vec![fresh_oracle_tx(), refresh_index_tx(), refresh_vault_tx()], false => None ; "maintenance_txs_when_trading_disabled")]
+    #[test_case(vec![refresh_index_tx(), refresh_vault_tx()], true => None ; "maintenance_txs_without_oracle_tx")]
+    #[test_case(vec![user_tx(), fresh_oracle_tx()], true => None ; "oracle_tx_not_on_top")]
+    #[test_case(vec![fresh_oracle_tx(), fresh_oracle_tx(), refresh_index_tx(), refresh_vault_tx()], true => None ; "duplicate_oracle_tx")]
+    #[test_case(vec![fresh_oracle_tx(), refresh_index_tx(), refresh_vault_tx(), refresh_vault_tx()], true => None ; "duplicate_maintenance_tx")]
+    #[test_case(vec![fresh_oracle_tx(), refresh_vault_tx(), refresh_index_tx()], true => None ; "swapped_maintenance_txs")]
+    fn checking_injected_txs_layout(txs: Vec<Bytes>, trading_enabled: bool) -> Option<usize> {
+        check_injected_txs(&txs, &cfg(), trading_enabled, BLOCK_TIME).ok()
+    }
+
+    #[test]
+    fn checking_malformed_oracle_tx() {
+        let mut tx = oracle_tx(cfg().addresses.oracle, price_update(BLOCK_TIME)).unwrap();
+        tx.gas_limit += 1;
+
+        let err = check_injected_txs(&[encode(tx)], &cfg(), false, BLOCK_TIME).unwrap_err();
+        assert!(matches!(
+            err,
+            ProposalError::MalformedInjectedTx { index: 0, .. }
+        ));
+
+        // The oracle tx must call nothing but the oracle contract.
+        let tx = Tx {
+            msgs: NonEmpty::new_unchecked(vec![
+                Message::transfer(Addr::mock(98), Coins::new()).unwrap(),
+            ]),
+            ..oracle_tx(cfg().addresses.oracle, price_update(BLOCK_TIME)).unwrap()
+        };
+
+        let err = check_injected_txs(&[encode(tx)], &cfg(), false, BLOCK_TIME).unwrap_err();
+        assert!(matches!(
+            err,
+            ProposalError::MalformedInjectedTx { index: 0, .. }
+        ));
+
+        // Payloads must parse.
+        let price_update = NonEmpty::new_unchecked(vec![LeEcdsaMessage {
+            payload: Binary::from_inner(b"garbage".to_vec()),
+            signature: ByteArray::from_inner([0; 64]),
+            recovery_id: 0,
+        }]);
+
+        let tx = oracle_tx(cfg().addresses.oracle, price_update).unwrap();
+
+        let err = check_injected_txs(&[encode(tx)], &cfg(), false, BLOCK_TIME).unwrap_err();
+        assert!(matches!(
+            err,
+            ProposalError::MalformedInjectedTx { index: 0, .. }
+        ));
+    }
+
+    #[test_case(BLOCK_TIME + Duration::from_seconds(1) => true ; "newer_than_block")]
+    #[test_case(BLOCK_TIME => true ; "same_as_block")]
+    #[test_case(BLOCK_TIME - MAX_PRICE_UPDATE_AGE => true ; "at_max_age")]
+    #[test_case(BLOCK_TIME - MAX_PRICE_UPDATE_AGE - Duration::from_millis(1) => false ; "older_than_max_age")]
+    fn checking_price_update_age(publish_time: Timestamp) -> bool {
+        let tx = oracle_tx(cfg().addresses.oracle, price_update(publish_time)).unwrap();
+
+        match check_injected_txs(&[encode(tx)], &cfg(), false, BLOCK_TIME) {
+            Ok(_) => true,
+            Err(ProposalError::StalePriceUpdate { .. }) => false,
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
     /// Disabled handler must short-circuit `prepare_proposal` before touching
     /// the querier — passing `NaiveQuerier` (which panics on any query)
     /// proves the early-return path runs.
@@ -493,7 +789,7 @@ mod tests {
         let querier = QuerierWrapper::new(&NaiveQuerier);
         let txs: Vec<Bytes> = vec![Bytes::from_static(b"tx1"), Bytes::from_static(b"tx2")];
         let out = handler
-            .prepare_proposal(querier, txs.clone(), usize::MAX)
+            .prepare_proposal(querier, block(), txs.clone(), usize::MAX)
             .unwrap();
         assert_eq!(out, txs);
     }
@@ -506,7 +802,7 @@ mod tests {
         let querier = QuerierWrapper::new(&NaiveQuerier);
         let txs: Vec<Bytes> = vec![Bytes::from_static(b"tx1")];
         let out = handler
-            .prepare_proposal(querier, txs.clone(), usize::MAX)
+            .prepare_proposal(querier, block(), txs.clone(), usize::MAX)
             .unwrap();
         assert_eq!(out, txs);
     }
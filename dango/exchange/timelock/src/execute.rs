@@ -0,0 +1,196 @@
+use {
+    crate::{ACTIONS, NEXT_ACTION_ID, PARAMS},
+    anyhow::{bail, ensure},
+    dango_primitives::{Addr, Duration, JsonDeExt, Message, MsgExecute, MutableCtx, Response},
+    dango_types::{
+        DangoQuerier, perps,
+        timelock::{
+            Action, ActionCanceled, ActionExecuted, ActionId, ActionQueued, EmergencyExecuted,
+            ExecuteMsg, InstantiateMsg, Params,
+        },
+    },
+};
+
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+    validate_params(&msg.params)?;
+
+    PARAMS.save(ctx.storage, &msg.params)?;
+
+    Ok(Response::new())
+}
+
+// The timelock is expected to become the chain owner, which receives e.g. the
+// gas fees and the perps treasury. Accept all transfers.
+pub fn receive(_ctx: MutableCtx) -> anyhow::Result<Response> {
+    Ok(Response::new())
+}
+
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::Queue { messages, delay } => queue(ctx, messages, delay),
+        ExecuteMsg::Cancel { action_id } => cancel(ctx, action_id),
+        ExecuteMsg::Execute { action_id } => do_execute(ctx, action_id),
+        ExecuteMsg::Emergency { messages } => emergency(ctx, messages),
+        ExecuteMsg::UpdateParams(params) => update_params(ctx, params),
+    }
+}
+
+fn queue(
+    ctx: MutableCtx,
+    messages: Vec<Message>,
+    delay: Option<Duration>,
+) -> anyhow::Result<Response> {
+    let params = PARAMS.load(ctx.storage)?;
+
+    ensure!(
+        ctx.sender == params.admin,
+        "only the admin can queue actions"
+    );
+
+    ensure!(
+        !messages.is_empty(),
+        "action must contain at least one message"
+    );
+
+    let delay = delay.unwrap_or(params.min_delay);
+
+    ensure!(
+        delay >= params.min_delay,
+        "invalid `delay`! bounds: [{:?}, inf), found: {delay:?}",
+        params.min_delay
+    );
+
+    let (action_id, _) = NEXT_ACTION_ID.increment(ctx.storage)?;
+    let executable_at = ctx.block.timestamp + delay;
+
+    ACTIONS.save(
+        ctx.storage,
+        action_id,
+        &Action {
+            messages: messages.clone(),
+            queued_at: ctx.block.timestamp,
+            executable_at,
+        },
+    )?;
+
+    Ok(Response::new().add_event(ActionQueued {
+        action_id,
+        messages,
+        executable_at,
+    })?)
+}
+
+fn cancel(ctx: MutableCtx, action_id: ActionId) -> anyhow::Result<Response> {
+    let params = PARAMS.load(ctx.storage)?;
+
+    ensure!(
+        is_admin_or_guardian(&params, ctx.sender),
+        "only the admin or a guardian can cancel actions"
+    );
+
+    ensure!(
+        ACTIONS.has(ctx.storage, action_id),
+        "action {action_id} doesn't exist"
+    );
+
+    ACTIONS.remove(ctx.storage, action_id);
+
+    Ok(Response::new().add_event(ActionCanceled {
+        action_id,
+        canceled_by: ctx.sender,
+    })?)
+}
+
+fn do_execute(ctx: MutableCtx, action_id: ActionId) -> anyhow::Result<Response> {
+    let Some(action) = ACTIONS.may_load(ctx.storage, action_id)? else {
+        bail!("action {action_id} doesn't exist");
+    };
+
+    ensure!(
+        ctx.block.timestamp >= action.executable_at,
+        "action {action_id} isn't executable until {:?}",
+        action.executable_at
+    );
+
+    ACTIONS.remove(ctx.storage, action_id);
+
+    Ok(Response::new()
+        .add_messages(action.messages)
+        .add_event(ActionExecuted { action_id })?)
+}
+
+fn emergency(ctx: MutableCtx, messages: Vec<Message>) -> anyhow::Result<Response> {
+    let params = PARAMS.load(ctx.storage)?;
+
+    ensure!(
+        is_admin_or_guardian(&params, ctx.sender),
+        "only the admin or a guardian can perform emergency actions"
+    );
+
+    ensure!(
+        !messages.is_empty(),
+        "action must contain at least one message"
+    );
+
+    let perps = ctx.querier.query_perps()?;
+
+    ensure!(
+        messages.iter().all(|msg| is_emergency_action(perps, msg)),
+        "only emergency actions can be executed without delay"
+    );
+
+    Ok(Response::new()
+        .add_messages(messages.clone())
+        .add_event(EmergencyExecuted {
+            messages,
+            executed_by: ctx.sender,
+        })?)
+}
+
+fn update_params(ctx: MutableCtx, params: Params) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the timelock itself can update its parameters"
+    );
+
+    validate_params(&params)?;
+
+    PARAMS.save(ctx.storage, &params)?;
+
+    Ok(Response::new())
+}
+
+fn validate_params(params: &Params) -> anyhow::Result<()> {
+    ensure!(
+        params.min_delay.into_nanos() > 0,
+        "minimum delay must be greater than zero"
+    );
+
+    Ok(())
+}
+
+fn is_admin_or_guardian(params: &Params, sender: Addr) -> bool {
+    sender == params.admin || params.guardians.contains(&sender)
+}
+
+/// An emergency action is one that can only reduce risk, such that it's safe to
+/// perform without giving users time to react.
+fn is_emergency_action(perps: Addr, msg: &Message) -> bool {
+    let Message::Execute(MsgExecute {
+        contract,
+        msg,
+        funds,
+    }) = msg
+    else {
+        return false;
+    };
+
+    *contract == perps
+        && funds.is_empty()
+        && matches!(
+            msg.clone().deserialize_json(),
+            Ok(perps::ExecuteMsg::Maintain(
+                perps::MaintainerMsg::DisableTrading {}
+            ))
+        )
+}
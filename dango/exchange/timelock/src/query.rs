@@ -0,0 +1,47 @@
+use {
+    crate::{ACTIONS, PARAMS},
+    dango_primitives::{
+        Bound, DEFAULT_PAGE_LIMIT, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage,
+    },
+    dango_types::timelock::{Action, ActionId, Params, QueryMsg},
+    std::collections::BTreeMap,
+};
+
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+    match msg {
+        QueryMsg::Params {} => {
+            let res = query_params(ctx.storage)?;
+            res.to_json_value()
+        },
+        QueryMsg::Action { action_id } => {
+            let res = query_action(ctx.storage, action_id)?;
+            res.to_json_value()
+        },
+        QueryMsg::Actions { start_after, limit } => {
+            let res = query_actions(ctx.storage, start_after, limit)?;
+            res.to_json_value()
+        },
+    }
+}
+
+fn query_params(storage: &dyn Storage) -> StdResult<Params> {
+    PARAMS.load(storage)
+}
+
+fn query_action(storage: &dyn Storage, action_id: ActionId) -> StdResult<Action> {
+    ACTIONS.load(storage, action_id)
+}
+
+fn query_actions(
+    storage: &dyn Storage,
+    start_after: Option<ActionId>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<ActionId, Action>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    ACTIONS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,11 @@
+use {
+    dango_storage::{Counter, Item, Map},
+    dango_types::timelock::{Action, ActionId, Params},
+};
+
+pub const PARAMS: Item<Params> = Item::new("params");
+
+pub const NEXT_ACTION_ID: Counter<ActionId> = Counter::new("action_id", 0, 1);
+
+/// Actions that have been queued, and neither executed nor canceled yet.
+pub const ACTIONS: Map<ActionId, Action> = Map::new("action");
@@ -24,6 +24,15 @@ pub struct AccountRegistered {
     pub owner: UserIndex,
 }
 
+/// An event indicating a new multi-signature account has been created.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("multisig_registered")]
+pub struct MultisigRegistered {
+    pub account_index: AccountIndex,
+    pub address: Addr,
+    pub creator: UserIndex,
+}
+
 /// An event indicating a username begins to own an account.
 #[dango_primitives::derive(Serde)]
 #[dango_primitives::event("account_owned")]
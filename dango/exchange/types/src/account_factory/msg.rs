@@ -1,6 +1,7 @@
 use {
     crate::{
         account_factory::{Account, AccountIndex, NewUserSalt, UserIndex, Username},
+        account_multi,
         auth::{Key, Signature},
     },
     dango_primitives::{Addr, Hash256, JsonSerExt, Op, SignData, StdError, StdResult},
@@ -73,6 +74,9 @@ pub struct InstantiateMsg {
     /// Code hash to be associated with the Dango account contract.
     pub account_code_hash: Hash256,
 
+    /// Code hash to be associated with the Dango multi-signature account contract.
+    pub multi_code_hash: Hash256,
+
     /// Users with associated key to set up during genesis.
     /// Each genesis user is to be associated with exactly one key.
     /// A single-signature account will be created for each genesis user.
@@ -99,6 +103,12 @@ pub enum ExecuteMsg {
     /// Register a new account for an existing user.
     RegisterAccount {},
 
+    /// Register a new multi-signature account with the given parameters.
+    ///
+    /// Sender must be an account owned by an existing user, who doesn't need
+    /// to be a member of the multisig.
+    RegisterMultisig { params: account_multi::Params },
+
     /// Associate a new or disassociate an existing key with a username.
     UpdateKey { key_hash: Hash256, key: Op<Key> },
 
@@ -121,6 +131,11 @@ pub enum QueryMsg {
     #[returns(Hash256)]
     CodeHash {},
 
+    /// Query the code hash associated with the Dango multi-signature account
+    /// contract.
+    #[returns(Hash256)]
+    MultiCodeHash {},
+
     /// Query the next user index.
     #[returns(UserIndex)]
     NextUserIndex {},
@@ -151,6 +166,13 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Enumerate all multi-signature accounts and their account indexes.
+    #[returns(BTreeMap<Addr, AccountIndex>)]
+    Multisigs {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
     /// Query users associated with a given key hash.
     /// Useful if user forgot their username but still have access to the key.
     #[returns(Vec<User>)]
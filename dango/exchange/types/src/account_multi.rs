@@ -0,0 +1,166 @@
+use {
+    crate::{
+        account_factory::UserIndex,
+        auth::{AccountStatus, Nonce},
+    },
+    dango_primitives::{Duration, Message, Timestamp},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// Identifier of a multisig proposal.
+pub type ProposalId = u32;
+
+/// Parameters of a multi-signature account.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct Params {
+    /// Members of the multisig and their voting weights.
+    pub members: BTreeMap<UserIndex, u32>,
+
+    /// Duration a proposal remains open for voting after it's created.
+    pub voting_period: Duration,
+
+    /// Total weight of YES votes required for a proposal to pass.
+    pub threshold: u32,
+}
+
+impl Params {
+    /// Sum of the weights of all members.
+    pub fn total_weight(&self) -> u32 {
+        self.members.values().sum()
+    }
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct Proposal {
+    pub title: String,
+    pub description: Option<String>,
+    /// Messages to be executed, with the multisig as sender, once the proposal
+    /// has passed.
+    pub messages: Vec<Message>,
+    pub status: Status,
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub enum Status {
+    /// The proposal is open for voting.
+    ///
+    /// The multisig's parameters at the time the proposal was created are
+    /// recorded here, such that member rotation doesn't affect proposals that
+    /// are already being voted on.
+    Voting {
+        params: Params,
+        until: Timestamp,
+        yes: u32,
+        no: u32,
+    },
+    /// The proposal has received enough YES votes, and is ready to be executed.
+    Passed,
+    /// The proposal has received enough NO votes that it can no longer pass,
+    /// or its voting period ended before it has passed.
+    Failed,
+    /// The proposal has passed and its messages have been executed.
+    Executed,
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy)]
+pub enum Vote {
+    Yes,
+    No,
+}
+
+#[dango_primitives::derive(Serde)]
+pub struct InstantiateMsg {
+    pub params: Params,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Create a proposal.
+    ///
+    /// Must be sent by the multisig itself, in a transaction signed by one of
+    /// its members.
+    Propose {
+        title: String,
+        description: Option<String>,
+        messages: Vec<Message>,
+    },
+    /// Vote on a proposal.
+    ///
+    /// Must be sent by the multisig itself, in a transaction signed by the
+    /// voter, who must be a member at the time the proposal was created.
+    Vote {
+        proposal_id: ProposalId,
+        voter: UserIndex,
+        vote: Vote,
+        /// Whether to execute the proposal right away, if this vote makes it
+        /// pass.
+        execute: bool,
+    },
+    /// Execute a passed proposal. Callable by anyone.
+    Execute { proposal_id: ProposalId },
+    /// Update the multisig's parameters, e.g. to add, remove, or re-weight
+    /// members, or to change the threshold.
+    ///
+    /// Only callable by the multisig itself, i.e. through a proposal.
+    UpdateParams(Params),
+}
+
+/// Query messages for the multi-signature account
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the account's status.
+    #[returns(AccountStatus)]
+    Status {},
+    /// Query the most recent transaction nonces.
+    #[returns(BTreeSet<Nonce>)]
+    SeenNonces {},
+    /// Query the multisig's parameters.
+    #[returns(Params)]
+    Params {},
+    /// Query a single proposal by ID.
+    #[returns(Proposal)]
+    Proposal { proposal_id: ProposalId },
+    /// Enumerate all proposals.
+    #[returns(BTreeMap<ProposalId, Proposal>)]
+    Proposals {
+        start_after: Option<ProposalId>,
+        limit: Option<u32>,
+    },
+    /// Query a member's vote on a proposal.
+    #[returns(Option<Vote>)]
+    Vote {
+        proposal_id: ProposalId,
+        member: UserIndex,
+    },
+    /// Enumerate all votes on a proposal.
+    #[returns(BTreeMap<UserIndex, Vote>)]
+    Votes {
+        proposal_id: ProposalId,
+        start_after: Option<UserIndex>,
+        limit: Option<u32>,
+    },
+}
+
+/// An event indicating a proposal has been created.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("proposed")]
+pub struct Proposed {
+    pub proposal_id: ProposalId,
+}
+
+/// An event indicating a member has voted on a proposal.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("voted")]
+pub struct Voted {
+    pub proposal_id: ProposalId,
+    pub voter: UserIndex,
+    pub vote: Vote,
+}
+
+/// An event indicating a passed proposal has been executed.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("proposal_executed")]
+pub struct ProposalExecuted {
+    pub proposal_id: ProposalId,
+}
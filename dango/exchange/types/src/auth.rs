@@ -1,12 +1,15 @@
 use {
     crate::account_factory::UserIndex,
     dango_primitives::{
-        Addr, Binary, ByteArray, Hash256, JsonSerExt, Message, NonEmpty, SignData, StdError,
-        Timestamp,
+        Addr, Binary, ByteArray, Coins, Hash256, Json, JsonSerExt, Message, NonEmpty, SignData,
+        StdError, StdResult, Timestamp,
     },
     serde::{Deserialize, Serialize},
     sha2::Sha256,
-    std::fmt::Display,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt::Display,
+    },
 };
 
 /// The status of an account. Only accounts in the `Active` state may send transactions.
@@ -145,6 +148,52 @@ pub struct SessionInfo {
     pub session_key: SessionKey,
     /// Expiry time of the session key.
     pub expire_at: Timestamp,
+    /// What the session key is allowed to do. `None` means the session key may
+    /// sign any transaction the user key could.
+    pub permissions: Option<SessionPermissions>,
+}
+
+/// An allow-list restricting the transactions a session key may sign.
+///
+/// A scoped session key may only send `Message::Execute`; transfers, uploads,
+/// instantiations and migrations are always rejected.
+#[dango_primitives::derive(Serde)]
+pub struct SessionPermissions {
+    /// Contracts the session key may execute, each mapped to the execute
+    /// message variants it may send to that contract.
+    ///
+    /// A variant is identified by the path of enum tags leading to it in the
+    /// message's JSON, joined by dots. E.g. `trade.submit_order` matches the
+    /// perps contract's `ExecuteMsg::Trade(TraderMsg::SubmitOrder(..))`. An
+    /// empty set allows any message to that contract.
+    pub contracts: BTreeMap<Addr, BTreeSet<String>>,
+    /// The maximum amount of coins the session key may attach to its messages,
+    /// cumulatively over its lifetime. Denoms not listed may not be spent.
+    /// `None` means no limit.
+    pub spend_limit: Option<Coins>,
+}
+
+impl SessionInfo {
+    /// The payload the user key signs to authorize the session: the session
+    /// info's canonical JSON, but with `permissions`, if any, replaced by its
+    /// own canonical JSON string.
+    ///
+    /// EIP-712 has no map type, so that's how an EIP-712 signature commits to
+    /// the allow-list. Other signers sign the same payload, so that clients
+    /// build one message regardless of the key type.
+    pub fn to_signing_json(&self) -> StdResult<Json> {
+        let mut json = self.to_json_value()?;
+
+        if let Some(permissions) = &self.permissions {
+            let permissions = permissions.to_json_value()?.to_json_string()?;
+
+            if let Some(obj) = json.as_object_mut() {
+                obj.insert("permissions".to_string(), permissions.into());
+            }
+        }
+
+        Ok(json)
+    }
 }
 
 impl SignData for SessionInfo {
@@ -154,7 +203,7 @@ impl SignData for SessionInfo {
     fn to_prehash_sign_data(&self) -> Result<Vec<u8>, Self::Error> {
         // Convert to JSON value first, then to bytes, such that the struct fields
         // are ordered alphabetically.
-        self.to_json_value()?.to_json_vec()
+        self.to_signing_json()?.to_json_vec()
     }
 }
 
@@ -1,5 +1,6 @@
 mod events;
+mod hooks;
 mod metadata;
 mod msgs;
 
-pub use {events::*, metadata::*, msgs::*};
+pub use {events::*, hooks::*, metadata::*, msgs::*};
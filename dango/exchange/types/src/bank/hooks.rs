@@ -0,0 +1,32 @@
+use dango_primitives::{Addr, Coins};
+
+/// The interface that a namespace's send hook contract must implement.
+///
+/// The bank contract queries the hook before each transfer of tokens under the
+/// namespace. The hook rejects the transfer by returning an error.
+#[dango_primitives::derive(Serde)]
+pub enum SendHookQuery {
+    /// Check a transfer. Return nothing if accepted; throw error if rejected.
+    ///
+    /// `coins` only includes the denoms under the hook's namespace(s).
+    BeforeSend { from: Addr, to: Addr, coins: Coins },
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum SendHookQueryResponse {
+    BeforeSend(()),
+}
+
+impl SendHookQueryResponse {
+    pub fn into_before_send(self) {
+        match self {
+            SendHookQueryResponse::BeforeSend(res) => res,
+        }
+    }
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum SendHookQueryMsg {
+    #[returns(SendHookQueryResponse)]
+    BeforeSendHook(SendHookQuery),
+}
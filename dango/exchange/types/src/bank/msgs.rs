@@ -22,13 +22,24 @@ pub enum ExecuteMsg {
     /// Currently, we don't support:
     ///
     /// 1. granting the top-level namespace;
-    /// 2. chain owner canceling a grant;
-    /// 3. namespace owner renouncing or transferring a grant;
-    /// 4. a namespace to have more than one owner;
-    /// 5. before-send hooks.
+    /// 2. a namespace to have more than one owner.
     ///
     /// We may implement some of these in the future.
     SetNamespaceOwner { namespace: Part, owner: Addr },
+    /// Revoke the control of a namespace from its owner.
+    /// Can only be called by the chain owner.
+    RevokeNamespaceOwner { namespace: Part },
+    /// Transfer the control of a namespace to another account.
+    /// Can only be called by the namespace owner.
+    TransferNamespace { namespace: Part, new_owner: Addr },
+    /// Give up the control of a namespace, leaving it without an owner.
+    /// Can only be called by the namespace owner.
+    RenounceNamespace { namespace: Part },
+    /// Set or unset the contract to be queried before each transfer of tokens
+    /// under a namespace, which may reject the transfer.
+    /// See [`SendHookQuery`](crate::bank::SendHookQuery) for the interface.
+    /// Can only be called by the namespace owner.
+    SetSendHook { namespace: Part, hook: Option<Addr> },
     /// Set metadata of a denom.
     /// Can only be called by the namespace owner, or the chain owner in case of
     /// top-level denoms.
@@ -55,6 +66,15 @@ pub enum QueryMsg {
         start_after: Option<Part>,
         limit: Option<u32>,
     },
+    /// Query the send hook of a namespace.
+    #[returns(Option<Addr>)]
+    SendHook { namespace: Part },
+    /// Enumerate send hooks of all namespaces.
+    #[returns(BTreeMap<Part, Addr>)]
+    SendHooks {
+        start_after: Option<Part>,
+        limit: Option<u32>,
+    },
     /// Query the metadata of a denom.
     #[returns(Metadata)]
     Metadata { denom: Denom },
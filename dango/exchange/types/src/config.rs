@@ -49,18 +49,76 @@ impl Default for AppAddresses {
 
 #[dango_primitives::derive(Serde)]
 #[derive(Copy)]
+#[serde(bound(deserialize = "T: serde::Deserialize<'de> + From<Addr>"))]
 pub struct Hyperlane<T> {
+    /// Zero on a chain that predates the aggregation ISM, until its upgrade
+    /// deploys one.
+    #[serde(default = "undeployed")]
+    pub aggregation_ism: T,
+    /// Zero on a chain that predates the IGP, until its upgrade deploys one.
+    #[serde(default = "undeployed")]
+    pub igp: T,
     pub ism: T,
     pub mailbox: T,
+    /// Zero on a chain that predates the routing ISM, until its upgrade
+    /// deploys one.
+    #[serde(default = "undeployed")]
+    pub routing_ism: T,
     pub va: T,
 }
 
+/// The address of a contract that hasn't been deployed yet.
+fn undeployed<T>() -> T
+where
+    T: From<Addr>,
+{
+    Addr::ZERO.into()
+}
+
 impl Default for Hyperlane<Addr> {
     fn default() -> Self {
         Hyperlane {
+            aggregation_ism: Addr::mock(0),
+            igp: Addr::mock(0),
             ism: Addr::mock(0),
             mailbox: Addr::mock(0),
+            routing_ism: Addr::mock(0),
             va: Addr::mock(0),
         }
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, dango_primitives::JsonDeExt, serde_json::json};
+
+    /// An app config stored before the IGP and the aggregation and routing
+    /// ISMs were added still loads, with their addresses zero.
+    #[test]
+    fn loading_config_without_new_hyperlane_contracts_works() {
+        let config: AppConfig = json!({
+            "addresses": {
+                "account_factory": Addr::mock(1),
+                "gateway": Addr::mock(2),
+                "hyperlane": {
+                    "ism": Addr::mock(4),
+                    "mailbox": Addr::mock(5),
+                    "va": Addr::mock(7),
+                },
+                "oracle": Addr::mock(8),
+                "perps": Addr::mock(9),
+                "warp": Addr::mock(10),
+            },
+            "minimum_deposit": Coins::new(),
+        })
+        .deserialize_json()
+        .unwrap();
+
+        assert_eq!(config.addresses.hyperlane.aggregation_ism, Addr::ZERO);
+        assert_eq!(config.addresses.hyperlane.igp, Addr::ZERO);
+        assert_eq!(config.addresses.hyperlane.routing_ism, Addr::ZERO);
+        assert_eq!(config.addresses.hyperlane.mailbox, Addr::mock(5));
+    }
+}
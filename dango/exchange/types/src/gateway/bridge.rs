@@ -1,4 +1,4 @@
-use {super::Remote, dango_hyperlane_types::Addr32, dango_math::Uint128};
+use {super::Remote, dango_hyperlane_types::Addr32, dango_math::Uint128, dango_primitives::Coins};
 
 /// Each bridge contract must implement this execute API.
 #[dango_primitives::derive(Serde)]
@@ -9,9 +9,42 @@ pub enum ExecuteMsg {
 
 #[dango_primitives::derive(Serde)]
 pub enum BridgeMsg {
+    /// The funds attached must cover the fee quoted by
+    /// `BridgeQuery::QuoteTransferRemote`.
     TransferRemote {
         remote: Remote,
         amount: Uint128,
         recipient: Addr32,
     },
 }
+
+/// Each bridge contract must implement this query API.
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    #[returns(BridgeQueryResponse)]
+    Bridge(BridgeQuery),
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum BridgeQuery {
+    /// Quote the fee the bridge charges for a remote transfer, e.g. to pay
+    /// relayers. Empty if the bridge doesn't charge a fee.
+    QuoteTransferRemote {
+        remote: Remote,
+        amount: Uint128,
+        recipient: Addr32,
+    },
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum BridgeQueryResponse {
+    QuoteTransferRemote(Coins),
+}
+
+impl BridgeQueryResponse {
+    pub fn into_quote_transfer_remote(self) -> Coins {
+        match self {
+            BridgeQueryResponse::QuoteTransferRemote(fee) => fee,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use {
     super::{Addr32, Remote},
     dango_math::Uint128,
-    dango_primitives::{Addr, Denom},
+    dango_primitives::{Addr, Coins, Denom},
 };
 
 /// Event indicating tokens have been received from a remote chain and
@@ -35,6 +35,9 @@ pub struct WithdrawalRequested {
     pub denom: Denom,
     /// The full escrowed amount, before any withdrawal fee.
     pub amount: Uint128,
+    /// The interchain gas payment escrowed alongside the amount, as quoted
+    /// by the bridge; empty if the bridge doesn't charge a fee.
+    pub interchain_gas: Coins,
 }
 
 /// Event indicating a withdrawal request has been rejected, and the escrowed
@@ -119,4 +122,7 @@ pub struct Withdrawn {
     /// The withdrawal fee charged; zero if no fee is configured for the
     /// route.
     pub fee: Uint128,
+    /// The interchain gas payment paid to the bridge; empty if the bridge
+    /// doesn't charge a fee.
+    pub interchain_gas: Coins,
 }
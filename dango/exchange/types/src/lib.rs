@@ -1,5 +1,6 @@
 pub mod account;
 pub mod account_factory;
+pub mod account_multi;
 pub mod auth;
 pub mod bank;
 pub mod config;
@@ -9,6 +10,7 @@ pub mod oracle;
 pub mod perps;
 mod querier;
 pub mod signer;
+pub mod timelock;
 pub mod vesting;
 pub mod warp;
 
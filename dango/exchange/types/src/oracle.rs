@@ -1,5 +1,6 @@
+mod circuit_breaker;
 mod msg;
 mod price;
 mod price_source;
 
-pub use {msg::*, price::*, price_source::*};
+pub use {circuit_breaker::*, msg::*, price::*, price_source::*};
@@ -0,0 +1,57 @@
+use {
+    anyhow::ensure,
+    dango_order_book::{Dimensionless, UsdPrice},
+    dango_primitives::{Denom, Duration, Timestamp},
+};
+
+/// A per-denom guard against a bad print: if the denom's price moves by more
+/// than `max_change` from one price feed to the next, the oracle reports the
+/// price as unavailable for up to `cooldown`.
+///
+/// The trip ends early if a later feed brings the price back within
+/// `max_change` of the last accepted price (the print was an outlier). If it
+/// doesn't, the new level is accepted once `cooldown` has passed (the market
+/// really moved).
+#[dango_primitives::derive(Serde)]
+pub struct CircuitBreaker {
+    /// The largest relative move between consecutive feeds that is accepted,
+    /// e.g. `0.1` for 10%.
+    pub max_change: Dimensionless,
+
+    /// How long the price stays unavailable after a trip.
+    pub cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Validate the circuit breaker: `max_change` must be positive.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.max_change.is_positive(),
+            "circuit breaker `max_change` must be positive, got `{}`",
+            self.max_change
+        );
+
+        Ok(())
+    }
+}
+
+/// A circuit breaker's running state, updated by the oracle every time prices
+/// are fed.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct CircuitBreakerState {
+    /// The last accepted price, which the next feed is compared against.
+    pub reference_price: UsdPrice,
+
+    /// If the breaker is tripped, the time at which the trip expires.
+    pub tripped_until: Option<Timestamp>,
+}
+
+/// Emitted when a denom's circuit breaker trips.
+#[dango_primitives::event("circuit_breaker_tripped")]
+#[dango_primitives::derive(Serde)]
+pub struct CircuitBreakerTripped {
+    pub denom: Denom,
+    pub reference_price: UsdPrice,
+    pub price: UsdPrice,
+    pub tripped_until: Timestamp,
+}
@@ -1,5 +1,5 @@
 use {
-    crate::oracle::{Price, PriceConfig},
+    crate::oracle::{CircuitBreaker, CircuitBreakerState, Price, PriceConfig},
     dango_primitives::{Binary, Denom, Timestamp},
     dango_pyth_types::PriceUpdate,
     std::collections::{BTreeMap, BTreeSet},
@@ -26,6 +26,14 @@ pub enum ExecuteMsg {
     /// on the price sources being removed.
     RemovePriceSources(BTreeSet<Denom>),
 
+    /// Set the circuit breakers for the given denoms. Replacing a denom's
+    /// circuit breaker keeps its running state.
+    RegisterCircuitBreakers(BTreeMap<Denom, CircuitBreaker>),
+
+    /// Remove the circuit breakers for the given denoms, along with their
+    /// running state. A tripped denom's price becomes available again.
+    RemoveCircuitBreakers(BTreeSet<Denom>),
+
     /// Register a trusted signer for Pyth Lazer.
     RegisterTrustedSigner {
         public_key: Binary,
@@ -69,4 +77,14 @@ pub enum QueryMsg {
         start_after: Option<Denom>,
         limit: Option<u32>,
     },
+
+    /// Query the circuit breaker of the given denom, if any.
+    #[returns(Option<CircuitBreaker>)]
+    CircuitBreaker { denom: Denom },
+
+    /// Query the running state of the given denom's circuit breaker. `None`
+    /// if the denom has no circuit breaker, or no price has been fed since it
+    /// was registered.
+    #[returns(Option<CircuitBreakerState>)]
+    CircuitBreakerState { denom: Denom },
 }
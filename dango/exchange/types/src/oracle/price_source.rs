@@ -1,6 +1,8 @@
 use {
-    anyhow::ensure, dango_math::MathResult, dango_order_book::Dimensionless,
-    dango_primitives::Timestamp,
+    anyhow::ensure,
+    dango_math::MathResult,
+    dango_order_book::Dimensionless,
+    dango_primitives::{Duration, Timestamp},
 };
 
 /// A single Pyth Lazer price feed subscription (feed id + channel). Identical in
@@ -15,6 +17,9 @@ pub type PriceSource = dango_pyth_types::PythLazerSubscriptionDetails;
 /// discrete fixings as the front contract approaches its final trading day.
 /// The blend weight is a pure function of the block timestamp, so the roll
 /// runs on-chain without a transaction per fixing.
+///
+/// Assets where a single bad print is costly can instead be priced from the
+/// median of several feeds, discarding outliers.
 #[dango_primitives::derive(Serde)]
 pub enum PriceConfig {
     /// Priced from a single feed. The common case (crypto, spot).
@@ -22,6 +27,32 @@ pub enum PriceConfig {
 
     /// Priced from a two-contract futures roll, blended by the block timestamp.
     Roll(RollState),
+
+    /// Priced from the median of several feeds, with outlier rejection.
+    Median(MedianConfig),
+}
+
+/// The median of several feeds quoting the same asset.
+///
+/// The oracle takes the median of the sources that have a price no older than
+/// `max_age`, discards the ones further than `max_deviation` from it, and
+/// serves the median of the rest, provided at least `min_quorum` remain.
+/// Otherwise the price is unavailable.
+#[dango_primitives::derive(Serde)]
+pub struct MedianConfig {
+    pub sources: Vec<PriceSource>,
+
+    /// The minimum number of sources that must agree (lie within
+    /// `max_deviation` of the median) for the price to be served.
+    pub min_quorum: u32,
+
+    /// The largest relative distance from the median at which a source still
+    /// counts, e.g. `0.02` for 2%.
+    pub max_deviation: Dimensionless,
+
+    /// The oldest a source's price may be, relative to the block timestamp,
+    /// for the source to count.
+    pub max_age: Duration,
 }
 
 /// The state of an in-progress (or not-yet-started) futures roll.
@@ -56,13 +87,25 @@ pub struct Fixing {
 
 impl PriceConfig {
     /// The feeds to blend at `now`, paired with their weights. Always one or two
-    /// entries, and the weights always sum to one. Zero-weight components are
-    /// omitted, so an expired contract whose feed has gone stale is not fetched
-    /// once its weight has reached zero.
+    /// entries for a single or roll config, and the weights always sum to one.
+    /// Zero-weight components are omitted, so an expired contract whose feed
+    /// has gone stale is not fetched once its weight has reached zero.
+    ///
+    /// A median config isn't blended; its sources are listed at equal weight.
     pub fn components_at(&self, now: Timestamp) -> MathResult<Vec<(PriceSource, Dimensionless)>> {
         match self {
             PriceConfig::Single(source) => Ok(vec![(source.clone(), Dimensionless::ONE)]),
             PriceConfig::Roll(roll) => roll.components_at(now),
+            PriceConfig::Median(median) => {
+                let weight = Dimensionless::ONE
+                    .checked_div(Dimensionless::new_int(median.sources.len() as i128))?;
+
+                Ok(median
+                    .sources
+                    .iter()
+                    .map(|source| (source.clone(), weight))
+                    .collect())
+            },
         }
     }
 
@@ -72,15 +115,57 @@ impl PriceConfig {
         match self {
             PriceConfig::Single(source) => vec![source.clone()],
             PriceConfig::Roll(roll) => vec![roll.current.clone(), roll.next.clone()],
+            PriceConfig::Median(median) => median.sources.clone(),
         }
     }
 
-    /// Validate the config; see [`RollState::validate`].
+    /// Validate the config; see [`RollState::validate`] and
+    /// [`MedianConfig::validate`].
     pub fn validate(&self) -> anyhow::Result<()> {
         match self {
             PriceConfig::Single(_) => Ok(()),
             PriceConfig::Roll(roll) => roll.validate(),
+            PriceConfig::Median(median) => median.validate(),
+        }
+    }
+}
+
+impl MedianConfig {
+    /// Validate the median:
+    /// - `sources` must be non-empty and contain no feed twice;
+    /// - `min_quorum` must be in `[1, sources.len()]`;
+    /// - `max_deviation` must be positive;
+    /// - `max_age` must be non-zero.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(!self.sources.is_empty(), "median has no sources");
+
+        for (i, source) in self.sources.iter().enumerate() {
+            ensure!(
+                self.sources[..i].iter().all(|other| other.id != source.id),
+                "median sources must be different feeds, found `{}` twice",
+                source.id
+            );
         }
+
+        ensure!(
+            self.min_quorum >= 1 && self.min_quorum as usize <= self.sources.len(),
+            "median `min_quorum` must be in [1, {}], got {}",
+            self.sources.len(),
+            self.min_quorum
+        );
+
+        ensure!(
+            self.max_deviation.is_positive(),
+            "median `max_deviation` must be positive, got `{}`",
+            self.max_deviation
+        );
+
+        ensure!(
+            self.max_age > Duration::ZERO,
+            "median `max_age` must be non-zero"
+        );
+
+        Ok(())
     }
 }
 
@@ -277,6 +362,87 @@ mod tests {
         );
     }
 
+    fn median() -> MedianConfig {
+        MedianConfig {
+            sources: vec![src(1), src(2), src(3)],
+            min_quorum: 2,
+            max_deviation: Dimensionless::new_percent(2),
+            max_age: Duration::from_seconds(10),
+        }
+    }
+
+    #[test]
+    fn median_lists_every_source() {
+        let config = PriceConfig::Median(median());
+
+        assert_eq!(config.feeds(), vec![src(1), src(2), src(3)]);
+        assert_eq!(
+            config.to_json_string().unwrap(),
+            concat!(
+                r#"{"median":{"sources":[{"id":1,"channel":"real_time"},"#,
+                r#"{"id":2,"channel":"real_time"},{"id":3,"channel":"real_time"}],"#,
+                r#""min_quorum":2,"max_deviation":"0.02","max_age":"10"}}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_medians() {
+        median().validate().unwrap();
+
+        let mut config = median();
+        config.sources.clear();
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("no sources")
+        );
+
+        let mut config = median();
+        config.sources[2] = src(1);
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("different feeds")
+        );
+
+        for min_quorum in [0, 4] {
+            let mut config = median();
+            config.min_quorum = min_quorum;
+            assert!(
+                config
+                    .validate()
+                    .unwrap_err()
+                    .to_string()
+                    .contains("min_quorum")
+            );
+        }
+
+        let mut config = median();
+        config.max_deviation = Dimensionless::ZERO;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("max_deviation")
+        );
+
+        let mut config = median();
+        config.max_age = Duration::ZERO;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("max_age")
+        );
+    }
+
     #[test]
     fn validate_accepts_a_well_formed_roll() {
         PriceConfig::Roll(five_step_roll()).validate().unwrap();
@@ -2,10 +2,10 @@ use {
     crate::account_factory::UserIndex,
     dango_math::{MathResult, Uint128},
     dango_order_book::{
-        ChildOrder, ClientOrderId, ConditionalOrder, Dimensionless, FillId, FundingPerUnit,
-        FundingRate, LiquidityDepthResponse, OrderId, OrderKind, PairId, Quantity,
+        ChildOrder, ClientOrderId, ConditionalOrder, ConditionalOrderId, Dimensionless, FillId,
+        FundingPerUnit, FundingRate, LiquidityDepthResponse, OrderId, OrderKind, PairId, Quantity,
         QueryOrderByClientOrderIdResponse, QueryOrderResponse, QueryOrdersByUserResponseItem,
-        TriggerDirection, UsdPrice, UsdValue,
+        SelfTradePreventionMode, TrailingOffset, TriggerDirection, UsdPrice, UsdValue,
     },
     dango_primitives::{Addr, Duration, NonEmpty, Op, Order as IterationOrder, Part, Timestamp},
     std::{
@@ -228,7 +228,9 @@ pub struct Param {
     /// returns funds to the user — withdrawals, cancellations, vault
     /// withdrawals, liquidations — remains available.
     ///
-    /// Set to false by the wind-down chain upgrade.
+    /// Set to false by the wind-down chain upgrade, or by the chain owner with
+    /// `MaintainerMsg::DisableTrading` in an emergency. Re-enabling requires
+    /// `Configure`.
     pub trading_enabled: bool,
 }
 
@@ -433,9 +435,16 @@ pub struct PairParam {
     /// Bounds: `>= 0`. Zero disables inventory skew (skew always 0).
     pub vault_max_skew_size: Quantity,
 
+    /// How the vault's quotes on each side of the book are spread over
+    /// multiple price levels. See [`VaultLadder`].
+    ///
+    /// The default is a single level, i.e. one bid and one ask.
+    #[serde(default)]
+    pub vault_ladder: VaultLadder,
+
     /// Multiplier applied to the funding-rate premium so governance can tune
-    /// funding independently of the vault's quoting parameters. The full
-    /// formula is:
+    /// funding independently of the vault's quoting parameters. Only applies
+    /// in [`FundingMode::VaultSkew`]. The full formula is:
     ///
     /// ```plain
     /// premium = -halfSpread × skew × spreadSkewFactor × fundingRateMultiplier
@@ -448,11 +457,23 @@ pub struct PairParam {
     /// invert the economic incentive.
     pub funding_rate_multiplier: Dimensionless,
 
+    /// How the funding rate is derived. See [`FundingMode`].
+    #[serde(default)]
+    pub funding_mode: FundingMode,
+
     /// Price bucket sizes for which aggregated order book depth is maintained.
     /// Each entry defines a granularity level for the depth query.
     ///
     /// Bounds: each entry `> 0`.
     pub bucket_sizes: BTreeSet<UsdPrice>,
+
+    /// Whether the pair accepts new orders. See [`PairStatus`].
+    ///
+    /// Only `Active` and `ReduceOnly` may be set via `Configure`. A pair is
+    /// put in `Settling` by `MaintainerMsg::SettlePair`, after which it can
+    /// no longer be configured.
+    #[serde(default)]
+    pub status: PairStatus,
 }
 
 impl PairParam {
@@ -471,6 +492,150 @@ impl PairParam {
     }
 }
 
+/// Lifecycle status of a trading pair, used to wind down and delist it.
+///
+/// The delisting workflow is:
+///
+/// 1. `Configure` the pair as `ReduceOnly`, giving traders time to close their
+///    positions.
+/// 2. `SettlePair` at a final price. The pair becomes `Settling` and is
+///    removed from `PAIR_IDS`, which stops its funding, index price updates,
+///    conditional orders, and vault quoting.
+/// 3. `ProcessPairSettlement`, as many times as needed, cancels the pair's
+///    orders and closes its positions at the final price. Once none are left,
+///    the pair becomes `Delisted`.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy, Default)]
+pub enum PairStatus {
+    /// All orders are accepted.
+    #[default]
+    Active,
+
+    /// Only reduce-only orders are accepted. Conditional orders, which are
+    /// always reduce-only, are still placed and triggered. Resting orders
+    /// placed before the switch are left in the book.
+    ReduceOnly,
+
+    /// The pair's orders are being canceled and its positions closed at
+    /// `settlement_price`. No orders are accepted.
+    Settling { settlement_price: UsdPrice },
+
+    /// All of the pair's orders have been canceled and its positions closed
+    /// at `settlement_price`.
+    Delisted { settlement_price: UsdPrice },
+}
+
+/// Shape of the vault's quotes on each side of a pair's book.
+///
+/// The vault's quote size for a side (after inventory skew) is split across
+/// `levels` orders. Level `i` (starting from 0, the top of the book) is placed
+/// at a spread of:
+///
+/// ```plain
+/// effective_spread + i × spread_step
+/// ```
+///
+/// from the oracle price, where `effective_spread` is the skewed
+/// `vault_half_spread`. Its size is proportional to `size_ratio ^ i`.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy)]
+pub struct VaultLadder {
+    /// Number of orders on each side of the book.
+    ///
+    /// Bounds: `[1, 10]`.
+    pub levels: u32,
+
+    /// Spread, as a fraction of the oracle price, added between consecutive
+    /// levels.
+    ///
+    /// Bounds: `[0, 1)`. Must be `> 0` if `levels > 1`, so that each level
+    /// has a distinct price.
+    pub spread_step: Dimensionless,
+
+    /// Ratio of each level's size to the size of the level above it. `1`
+    /// spreads the size evenly; `< 1` concentrates it at the top of the book;
+    /// `> 1` puts more of it behind the top.
+    ///
+    /// Bounds: `(0, 10]`.
+    pub size_ratio: Dimensionless,
+}
+
+impl Default for VaultLadder {
+    fn default() -> Self {
+        Self {
+            levels: 1,
+            spread_step: Dimensionless::ZERO,
+            size_ratio: Dimensionless::ONE,
+        }
+    }
+}
+
+/// How the funding rate of a trading pair is derived.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy, Default)]
+pub enum FundingMode {
+    /// Derive the premium from the vault's inventory skew:
+    ///
+    /// ```plain
+    /// premium = -halfSpread × skew × spreadSkewFactor × fundingRateMultiplier
+    /// ```
+    ///
+    /// Suited to pairs where the vault is the dominant maker, so its skew-aware
+    /// quotes determine the mid-market price.
+    #[default]
+    VaultSkew,
+
+    /// Sample the premium of the order book's impact bid/ask mid price over the
+    /// index price on every cron run, and settle the average of the samples
+    /// over the funding period, plus an interest rate:
+    ///
+    /// ```plain
+    /// premium = (impactBid + impactAsk) / 2 / indexPrice - 1
+    /// funding_rate = avg(premium) + interest_rate
+    /// ```
+    ///
+    /// Samples taken while either side of the book is thinner than
+    /// `impact_size` are skipped. Suited to pairs where third-party makers
+    /// dominate the book.
+    PremiumIndex {
+        /// Per-day interest rate added to the average premium, reflecting the
+        /// difference between the borrowing rates of the base and quote assets.
+        ///
+        /// Bounds: `[-max_abs_funding_rate, max_abs_funding_rate]`.
+        interest_rate: FundingRate,
+    },
+}
+
+/// Premium samples of a pair in [`FundingMode::PremiumIndex`], taken since the
+/// last funding.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Default)]
+pub struct PremiumSamples {
+    /// Sum of the sampled premiums.
+    pub sum: Dimensionless,
+
+    /// Number of samples taken.
+    pub count: u32,
+}
+
+/// A record of the funding applied to a pair at a funding time.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct FundingRecord {
+    /// The clamped per-day funding rate applied. Positive means longs pay
+    /// shorts; negative means shorts pay longs.
+    pub funding_rate: FundingRate,
+
+    /// The amount added to `PairState::funding_per_unit`.
+    pub funding_delta: FundingPerUnit,
+
+    /// The premium the rate was derived from, before adding interest and
+    /// clamping. For [`FundingMode::PremiumIndex`], the average of the samples.
+    pub premium: Dimensionless,
+
+    /// The index price the funding was computed at.
+    pub index_price: UsdPrice,
+}
+
 /// State of an individual trading pair.
 #[dango_primitives::derive(Serde, Borsh)]
 #[derive(Default)]
@@ -536,6 +701,16 @@ pub struct UserState {
 
     /// Number of resting limit orders the user currently has on the book.
     pub open_order_count: usize,
+
+    /// Pairs the user trades in isolated-margin mode, mapped to the margin
+    /// allocated to the position in that pair.
+    ///
+    /// A position in a pair listed here is backed by its own margin only. It
+    /// is excluded from the account-level equity and margin requirements, and
+    /// is liquidated against its own margin without touching `margin` or the
+    /// other positions. An entry without an open position merely records the
+    /// user's choice of margin mode for the pair; its margin is zero.
+    pub isolated_margins: BTreeMap<PairId, UsdValue>,
 }
 
 impl UserState {
@@ -543,6 +718,34 @@ impl UserState {
     pub fn is_empty(&self) -> bool {
         self == &Self::default()
     }
+
+    /// Return whether the user trades the given pair in isolated-margin mode.
+    pub fn is_isolated(&self, pair_id: &PairId) -> bool {
+        self.isolated_margins.contains_key(pair_id)
+    }
+
+    /// Iterate the positions backed by the account-level `margin`, i.e. those
+    /// _not_ in isolated-margin mode.
+    pub fn cross_positions(&self) -> impl Iterator<Item = (&PairId, &Position)> {
+        self.positions
+            .iter()
+            .filter(|(pair_id, _)| !self.is_isolated(pair_id))
+    }
+}
+
+/// How a user's position in a trading pair is collateralized.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy, Default)]
+pub enum MarginMode {
+    /// The position shares the account-level `margin` with all other cross
+    /// positions. A loss on one position may lead to the liquidation of all.
+    #[default]
+    Cross,
+
+    /// The position is backed by margin allocated to it alone. It can lose
+    /// at most that margin, and its liquidation doesn't affect the rest of
+    /// the account.
+    Isolated,
 }
 
 /// State of a specific user, containing a few fields not saved in contract
@@ -556,14 +759,17 @@ pub struct UserStateExtended {
     pub unlocks: VecDeque<Unlock>,
     pub reserved_margin: UsdValue,
     pub open_order_count: usize,
+    pub isolated_margins: BTreeMap<PairId, UsdValue>,
 
     /// The user's equity, defined as:
     ///
     /// ```plain
-    /// margin + sum_all_pairs(unrealized_pnl - unrealized_funding)
+    /// margin + sum_cross_pairs(unrealized_pnl - unrealized_funding)
     /// ```
     ///
-    /// Equity reflects the total value of the account's collaterals and positions.
+    /// Equity reflects the total value of the account's collaterals and
+    /// cross-margin positions. Isolated positions are reported separately in
+    /// `PositionExtended::isolated_equity`.
     ///
     /// `None` if the client elects not to compute this in `QueryMsg::UserStateExtended`.
     pub equity: Option<UsdValue>,
@@ -571,8 +777,8 @@ pub struct UserStateExtended {
     /// The user's available margin, defined as:
     ///
     /// ```plain
-    /// margin
-    ///   - sum_all_pairs(|size| * oracle_price * initial_margin_ratio)
+    /// equity
+    ///   - sum_cross_pairs(|size| * oracle_price * initial_margin_ratio)
     ///   - sum_all_orders(reserved_margin)
     /// ```
     ///
@@ -593,11 +799,12 @@ pub struct UserStateExtended {
     /// Defined as:
     ///
     /// ```plain
-    /// sum_all_pairs(|position.size| * oracle_price * maintenance_margin_ratio)
+    /// sum_cross_pairs(|position.size| * oracle_price * maintenance_margin_ratio)
     /// ```
     ///
     /// Used as liquidation trigger: if equity < maintenance margin, the user is
-    /// liquidatable.
+    /// liquidatable. Isolated positions are reported separately in
+    /// `PositionExtended::isolated_maintenance_margin`.
     pub maintenance_margin: Option<UsdValue>,
 
     /// The user's open positions, enriched with optional computed data.
@@ -617,13 +824,37 @@ pub struct Position {
     /// this position was last opened, modified, or funding settled.
     pub entry_funding_per_unit: FundingPerUnit,
 
-    /// Conditional order that triggers when oracle_price >= trigger_price.
-    /// Used for: TP on longs, SL on shorts.
-    pub conditional_order_above: Option<ConditionalOrder>,
+    /// Conditional orders that trigger when oracle_price >= trigger_price,
+    /// indexed by order ID. Used for: TP on longs, SL on shorts.
+    pub conditional_orders_above: BTreeMap<ConditionalOrderId, ConditionalOrder>,
 
-    /// Conditional order that triggers when oracle_price <= trigger_price.
-    /// Used for: SL on longs, TP on shorts.
-    pub conditional_order_below: Option<ConditionalOrder>,
+    /// Conditional orders that trigger when oracle_price <= trigger_price,
+    /// indexed by order ID. Used for: SL on longs, TP on shorts.
+    pub conditional_orders_below: BTreeMap<ConditionalOrderId, ConditionalOrder>,
+}
+
+impl Position {
+    /// Return the conditional orders of the given trigger direction.
+    pub fn conditional_orders(
+        &self,
+        trigger_direction: TriggerDirection,
+    ) -> &BTreeMap<ConditionalOrderId, ConditionalOrder> {
+        match trigger_direction {
+            TriggerDirection::Above => &self.conditional_orders_above,
+            TriggerDirection::Below => &self.conditional_orders_below,
+        }
+    }
+
+    /// Return the conditional orders of the given trigger direction, mutably.
+    pub fn conditional_orders_mut(
+        &mut self,
+        trigger_direction: TriggerDirection,
+    ) -> &mut BTreeMap<ConditionalOrderId, ConditionalOrder> {
+        match trigger_direction {
+            TriggerDirection::Above => &mut self.conditional_orders_above,
+            TriggerDirection::Below => &mut self.conditional_orders_below,
+        }
+    }
 }
 
 /// A user's position enriched with optional computed data.
@@ -636,8 +867,8 @@ pub struct PositionExtended {
     pub size: Quantity,
     pub entry_price: UsdPrice,
     pub entry_funding_per_unit: FundingPerUnit,
-    pub conditional_order_above: Option<ConditionalOrder>,
-    pub conditional_order_below: Option<ConditionalOrder>,
+    pub conditional_orders_above: BTreeMap<ConditionalOrderId, ConditionalOrder>,
+    pub conditional_orders_below: BTreeMap<ConditionalOrderId, ConditionalOrder>,
 
     /// Unrealized PnL for this position:
     ///
@@ -663,11 +894,53 @@ pub struct PositionExtended {
 
     /// The oracle price of this pair at which the account-level liquidation
     /// condition triggers, assuming all other pair prices remain constant
-    /// (cross-margin partial-derivative approach).
+    /// (cross-margin partial-derivative approach). For an isolated position,
+    /// the price at which its isolated margin no longer covers its maintenance
+    /// margin.
     ///
     /// `None` if the client elects not to compute this, or if no valid
     /// liquidation price exists (the position alone cannot trigger liquidation).
     pub liquidation_price: Option<UsdPrice>,
+
+    /// For an isolated position, its isolated margin plus unrealized PnL minus
+    /// unrealized funding.
+    ///
+    /// `None` for cross positions, or if the client elects not to compute
+    /// equity in `QueryMsg::UserStateExtended`.
+    pub isolated_equity: Option<UsdValue>,
+
+    /// For an isolated position, its maintenance margin. The position is
+    /// liquidatable if `isolated_equity` falls below this.
+    ///
+    /// `None` for cross positions, or if the client elects not to compute
+    /// maintenance margin in `QueryMsg::UserStateExtended`.
+    pub isolated_maintenance_margin: Option<UsdValue>,
+}
+
+/// States of all accounts owned by a user, as well as the totals across them.
+/// Used in query response.
+#[dango_primitives::derive(Serde)]
+pub struct SubaccountStates {
+    /// Sum of the subaccounts' margins.
+    pub margin: UsdValue,
+
+    /// Sum of the subaccounts' equities.
+    pub equity: UsdValue,
+
+    /// Sum of the subaccounts' available margins. Margin is only available to
+    /// the subaccount holding it, but can be moved between subaccounts with
+    /// `TraderMsg::TransferMargin`.
+    pub available_margin: UsdValue,
+
+    /// Sum of the subaccounts' maintenance margins.
+    pub maintenance_margin: UsdValue,
+
+    /// Sum of the subaccounts' vault shares.
+    pub vault_shares: Uint128,
+
+    /// State of each subaccount that has one, with all optional fields
+    /// computed.
+    pub subaccounts: BTreeMap<Addr, UserStateExtended>,
 }
 
 /// A pending withdrawal of liquidity from the counterparty vault, awaiting the
@@ -764,6 +1037,11 @@ pub struct SubmitOrderRequest {
 
     /// Stop-loss child order. Applied to the resulting position after fill.
     pub sl: Option<ChildOrder>,
+
+    /// How to handle the order crossing a resting order of the same user.
+    /// Defaults to canceling the resting order.
+    #[serde(default)]
+    pub stp_mode: SelfTradePreventionMode,
 }
 
 #[dango_primitives::derive(Serde)]
@@ -793,10 +1071,12 @@ pub enum SubmitOrCancelOrderRequest {
 
 #[dango_primitives::derive(Serde)]
 pub enum CancelConditionalOrderRequest {
-    /// Cancel a single conditional order identified by pair and direction.
+    /// Cancel the conditional orders of a pair in the given direction. If
+    /// `order_id` is given, only that order is canceled.
     One {
         pair_id: PairId,
         trigger_direction: TriggerDirection,
+        order_id: Option<ConditionalOrderId>,
     },
 
     /// Cancel all conditional orders for a specific pair.
@@ -806,6 +1086,160 @@ pub enum CancelConditionalOrderRequest {
     All,
 }
 
+/// Identifier of a TWAP order. Drawn from the same counter as the IDs of limit
+/// and conditional orders.
+pub type TwapOrderId = OrderId;
+
+/// A time-weighted average price (TWAP) order: a parent order that the cron
+/// executes as a series of market orders ("slices"), `slice_interval` apart.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct TwapOrder {
+    pub user: Addr,
+    pub pair_id: PairId,
+
+    /// The total size to execute.
+    /// Positive indicates buy, negative indicates sell.
+    pub size: Quantity,
+
+    /// The portion of `size` that has been filled so far.
+    pub filled_size: Quantity,
+
+    /// Max slippage of each slice's market order.
+    pub max_slippage: Dimensionless,
+
+    /// Whether the slices are reduce-only.
+    pub reduce_only: bool,
+
+    /// Time between two consecutive slices.
+    pub slice_interval: Duration,
+
+    /// Number of slices yet to be executed. Each slice's size is the unfilled
+    /// size divided by this number, so if a slice doesn't fully fill (e.g. due
+    /// to a lack of liquidity within the slippage), the subsequent slices make
+    /// up for it.
+    pub remaining_slices: u32,
+
+    /// The earliest time the next slice is to be executed.
+    pub next_slice_time: Timestamp,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum CancelTwapOrderRequest {
+    /// Cancel a single TWAP order by its ID.
+    One(TwapOrderId),
+
+    /// Cancel all TWAP orders associated with the sender.
+    All,
+}
+
+/// Identifier of an order group. Drawn from the same counter as the IDs of
+/// limit, conditional, and TWAP orders.
+pub type OrderGroupId = OrderId;
+
+/// A group of orders in the same pair whose lifecycles are linked.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct OrderGroup {
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub kind: OrderGroupKind,
+}
+
+impl OrderGroup {
+    /// The orders of the group that may still be live: the members of an OCO
+    /// group, or the primary order of an OTO group.
+    pub fn members(&self) -> Vec<GroupMember> {
+        match &self.kind {
+            OrderGroupKind::Oco { members } => members.clone(),
+            OrderGroupKind::Oto { primary, .. } => vec![GroupMember::Limit { order_id: *primary }],
+        }
+    }
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub enum OrderGroupKind {
+    /// One-cancels-other (OCO): once any member is filled, even partially, or
+    /// triggered in case of a conditional order, the other members are
+    /// canceled.
+    Oco { members: Vec<GroupMember> },
+
+    /// One-triggers-other (OTO): once the primary limit order is fully filled,
+    /// the secondary orders are placed. Two or more secondaries are placed as
+    /// a new OCO group.
+    Oto {
+        primary: OrderId,
+        secondaries: Vec<GroupOrder>,
+    },
+}
+
+/// A live order that is a member of an OCO group.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Copy)]
+pub enum GroupMember {
+    /// A resting limit order.
+    Limit { order_id: OrderId },
+
+    /// A conditional order attached to the user's position.
+    Conditional {
+        order_id: ConditionalOrderId,
+        trigger_direction: TriggerDirection,
+    },
+}
+
+impl GroupMember {
+    pub fn order_id(&self) -> OrderId {
+        match self {
+            GroupMember::Limit { order_id } | GroupMember::Conditional { order_id, .. } => {
+                *order_id
+            },
+        }
+    }
+}
+
+/// An order to be placed as part of an order group.
+#[dango_primitives::derive(Serde, Borsh)]
+pub enum GroupOrder {
+    Limit(GroupLimitOrder),
+    Conditional(GroupConditionalOrder),
+}
+
+/// A good-til-canceled limit order in an order group.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct GroupLimitOrder {
+    /// Positive indicates buy, negative indicates sell.
+    pub size: Quantity,
+    pub limit_price: UsdPrice,
+    /// If true, the order is rejected if it would match on placement.
+    pub post_only: bool,
+    pub reduce_only: bool,
+    pub client_order_id: Option<ClientOrderId>,
+}
+
+/// A conditional (TP/SL) order in an order group. Same parameters as
+/// `TraderMsg::SubmitConditionalOrder`.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct GroupConditionalOrder {
+    /// If `None`, closes the entire position at trigger time.
+    pub size: Option<Quantity>,
+    pub trigger_price: UsdPrice,
+    pub trigger_direction: TriggerDirection,
+    pub max_slippage: Dimensionless,
+    pub limit_price: Option<UsdPrice>,
+    pub trailing_offset: Option<TrailingOffset>,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum SubmitOrderGroupRequest {
+    /// Place the orders as an OCO group.
+    Oco { orders: Vec<GroupOrder> },
+
+    /// Place the primary limit order, and the secondaries once it's fully
+    /// filled.
+    Oto {
+        primary: GroupLimitOrder,
+        secondaries: Vec<GroupOrder>,
+    },
+}
+
 /// A point-in-time snapshot of the market-making vault's redemption value.
 ///
 /// Stored daily by the cron handler. The ratio `equity / share_supply` is the
@@ -856,8 +1290,20 @@ pub enum MaintainerMsg {
     ///
     /// Unfilled positions are ADL'd against counter-parties at the bankruptcy
     /// price. Any remaining bad debt is absorbed by the insurance fund.
+    ///
+    /// Positions in isolated-margin mode are not affected; see
+    /// `LiquidateIsolated`.
     Liquidate { user: Addr },
 
+    /// Forcibly close a user's isolated position in the given pair, if the
+    /// position's isolated margin plus unrealized PnL has fallen below its
+    /// maintenance margin.
+    ///
+    /// Same as `Liquidate`, except only the isolated position and its margin
+    /// are involved. The user's cross margin and other positions are left
+    /// untouched, and so are their resting orders in other pairs.
+    LiquidateIsolated { user: Addr, pair_id: PairId },
+
     /// Accept a USDC donation to the perps contract.
     /// Only callable by the chain owner. Must attach exactly USDC, nonzero.
     Donate {},
@@ -867,6 +1313,16 @@ pub enum MaintainerMsg {
     /// Only callable by the chain owner.
     WithdrawTreasury {},
 
+    /// Set `Param::trading_enabled` to false, leaving all other parameters
+    /// unchanged.
+    ///
+    /// Unlike `Configure`, this can only reduce risk, so a timelocked chain
+    /// owner may perform it without delay in an emergency. Trading is
+    /// re-enabled with `Configure`.
+    ///
+    /// Only callable by the chain owner.
+    DisableTrading {},
+
     /// Override a user's fee rate, overriding the tier-based fee rates derived
     /// from the user's recent trading volume.
     ///
@@ -886,6 +1342,31 @@ pub enum MaintainerMsg {
 
     /// Refresh vault market-making orders based on the current index prices.
     RefreshVaultOrders {},
+
+    /// Start delisting a pair: close all of its positions at the given final
+    /// price, and cancel all of its orders. See [`PairStatus`] for the full
+    /// workflow.
+    ///
+    /// Puts the pair in `PairStatus::Settling` and removes it from `PAIR_IDS`.
+    /// The pair's index price is set to `settlement_price`, so that the
+    /// margins of positions not yet closed are computed at the final price.
+    ///
+    /// Only callable by the chain owner.
+    SettlePair {
+        pair_id: PairId,
+        settlement_price: UsdPrice,
+    },
+
+    /// Cancel the resting orders of a settling pair, then close its positions
+    /// at the pair's settlement price, up to `limit` of either in total. Once
+    /// none of either are left, the pair becomes `PairStatus::Delisted`.
+    ///
+    /// Conditional orders are removed along with their positions. Closing a
+    /// position incurs no fee. If it leaves the account with no positions and
+    /// a negative margin, the insurance fund covers the bad debt.
+    ///
+    /// Callable by anyone.
+    ProcessPairSettlement { pair_id: PairId, limit: u32 },
 }
 
 #[dango_primitives::derive(Serde)]
@@ -904,6 +1385,10 @@ pub enum TraderMsg {
     /// current oracle price (floor-rounded) and transferred to the user.
     Withdraw { amount: UsdValue },
 
+    /// Move margin from the sender's account to another account owned by the
+    /// same user (a subaccount). Bounded by the sender's available margin.
+    TransferMargin { to: Addr, amount: UsdValue },
+
     /// Submit an order.
     SubmitOrder(SubmitOrderRequest),
 
@@ -925,7 +1410,11 @@ pub enum TraderMsg {
 
     /// Submit a conditional (TP/SL) order that triggers when the oracle price
     /// crosses the specified trigger price. Always reduce-only, executed as a
-    /// market order at trigger time.
+    /// market order at trigger time, or as a GTC limit order if `limit_price`
+    /// is set.
+    ///
+    /// A position can have up to `MAX_CONDITIONAL_ORDERS_PER_DIRECTION` orders
+    /// in each direction, e.g. to scale out in tranches.
     SubmitConditionalOrder {
         pair_id: PairId,
         /// If `None`, closes the entire position at trigger time.
@@ -933,10 +1422,79 @@ pub enum TraderMsg {
         trigger_price: UsdPrice,
         trigger_direction: TriggerDirection,
         max_slippage: Dimensionless,
+        /// If set, place a reduce-only limit order at this price at trigger
+        /// time (stop-limit), instead of a market order.
+        limit_price: Option<UsdPrice>,
+        /// If set, the trigger price trails the oracle price by this offset
+        /// as the price moves in the position's favor (trailing stop). The
+        /// initial trigger is `trigger_price`, or the offset from the current
+        /// oracle price, whichever is tighter.
+        trailing_offset: Option<TrailingOffset>,
     },
 
     /// Cancel one or more conditional orders.
     CancelConditionalOrder(CancelConditionalOrderRequest),
+
+    /// Submit a time-weighted average price (TWAP) order, which is executed by
+    /// the cron as `duration / slice_interval` market orders of equal size,
+    /// the first one right away and the rest `slice_interval` apart.
+    ///
+    /// A user can have up to `MAX_TWAP_ORDERS_PER_USER` active TWAP orders.
+    SubmitTwapOrder {
+        pair_id: PairId,
+        /// The total size to execute.
+        /// Positive indicates buy, negative indicates sell.
+        size: Quantity,
+        duration: Duration,
+        slice_interval: Duration,
+        /// Max slippage of each slice's market order.
+        max_slippage: Dimensionless,
+        reduce_only: bool,
+    },
+
+    /// Cancel one or all of the sender's active TWAP orders. Slices that have
+    /// already been executed are not reverted.
+    CancelTwapOrder(CancelTwapOrderRequest),
+
+    /// Submit a group of orders in one pair whose lifecycles are linked: an
+    /// OCO group of 2 to `MAX_ORDERS_PER_GROUP` orders, or an OTO group of a
+    /// primary limit order and up to `MAX_ORDERS_PER_GROUP` secondaries.
+    ///
+    /// A user can have up to `MAX_ORDER_GROUPS_PER_USER` active groups.
+    SubmitOrderGroup {
+        pair_id: PairId,
+        group: SubmitOrderGroupRequest,
+    },
+
+    /// Cancel an order group, along with all of its orders that are still
+    /// live.
+    CancelOrderGroup { group_id: OrderGroupId },
+
+    /// Arm, refresh, or disarm the sender's dead-man's switch.
+    ///
+    /// With `Some(timeout)`, all of the sender's resting limit orders are
+    /// canceled by the cron once `timeout` has elapsed, unless the switch is
+    /// refreshed by sending this message again before then. With `None`, the
+    /// switch is disarmed.
+    CancelAllAfter { timeout: Option<Duration> },
+
+    /// Choose how the sender's position in the given pair is collateralized.
+    ///
+    /// Can only be changed while the sender has neither a position nor
+    /// resting orders in the pair.
+    SetMarginMode {
+        pair_id: PairId,
+        margin_mode: MarginMode,
+    },
+
+    /// Move margin from the sender's cross margin into the isolated margin of
+    /// their position in the given pair. Bounded by the available margin.
+    AddIsolatedMargin { pair_id: PairId, amount: UsdValue },
+
+    /// Move margin from the isolated margin of the sender's position in the
+    /// given pair back into their cross margin. The isolated margin must still
+    /// cover the position's initial margin afterwards.
+    RemoveIsolatedMargin { pair_id: PairId, amount: UsdValue },
 }
 
 #[dango_primitives::derive(Serde)]
@@ -1070,6 +1628,11 @@ pub enum QueryMsg {
         include_all: bool,
     },
 
+    /// Query the states of all accounts owned by a user, with all additional
+    /// data computed on-the-fly, as well as the totals across them.
+    #[returns(SubaccountStates)]
+    SubaccountStates { user: UserIndex },
+
     /// Enumeate the states of all users with additional data computed on-the-fly.
     #[returns(BTreeMap<Addr, UserStateExtended>)]
     UserStatesExtended {
@@ -1113,6 +1676,27 @@ pub enum QueryMsg {
         client_order_id: ClientOrderId,
     },
 
+    /// Query a single active TWAP order by ID.
+    #[returns(Option<TwapOrder>)]
+    TwapOrder { twap_id: TwapOrderId },
+
+    /// Query all active TWAP orders of a user.
+    #[returns(BTreeMap<TwapOrderId, TwapOrder>)]
+    TwapOrdersByUser { user: Addr },
+
+    /// Query a single active order group by ID.
+    #[returns(Option<OrderGroup>)]
+    OrderGroup { group_id: OrderGroupId },
+
+    /// Query all active order groups of a user.
+    #[returns(BTreeMap<OrderGroupId, OrderGroup>)]
+    OrderGroupsByUser { user: Addr },
+
+    /// Query the time at which a user's dead-man's switch cancels all of their
+    /// resting limit orders. `None` if the switch isn't armed.
+    #[returns(Option<Timestamp>)]
+    CancelAllDeadline { user: Addr },
+
     /// Query aggregated order book depth at a specific bucket size.
     #[returns(LiquidityDepthResponse)]
     LiquidityDepth {
@@ -1148,6 +1732,16 @@ pub enum QueryMsg {
         max: Option<Timestamp>,
     },
 
+    /// Enumerate the funding applied to a pair, keyed by funding time. `min`
+    /// and `max` are both inclusive timestamp bounds.
+    #[returns(BTreeMap<Timestamp, FundingRecord>)]
+    FundingHistory {
+        pair_id: PairId,
+        min: Option<Timestamp>,
+        max: Option<Timestamp>,
+        limit: Option<u32>,
+    },
+
     /// Query the referrer of a given referee.
     #[returns(Option<Referrer>)]
     Referrer { referee: UserIndex },
@@ -1274,6 +1868,51 @@ pub struct Withdrew {
     pub amount: UsdValue,
 }
 
+/// Event indicating margin has been moved between two accounts owned by the
+/// same user.
+#[dango_primitives::event("margin_transferred")]
+#[dango_primitives::derive(Serde)]
+pub struct MarginTransferred {
+    pub from: Addr,
+    pub to: Addr,
+    pub amount: UsdValue,
+}
+
+/// Event indicating a user has changed the margin mode of a trading pair.
+#[dango_primitives::event("margin_mode_set")]
+#[dango_primitives::derive(Serde)]
+pub struct MarginModeSet {
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub margin_mode: MarginMode,
+}
+
+/// Event indicating a user has moved margin from their cross margin into the
+/// isolated margin of a position.
+#[dango_primitives::event("isolated_margin_added")]
+#[dango_primitives::derive(Serde)]
+pub struct IsolatedMarginAdded {
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub amount: UsdValue,
+
+    /// The position's isolated margin after the change.
+    pub isolated_margin: UsdValue,
+}
+
+/// Event indicating a user has moved margin from the isolated margin of a
+/// position back into their cross margin.
+#[dango_primitives::event("isolated_margin_removed")]
+#[dango_primitives::derive(Serde)]
+pub struct IsolatedMarginRemoved {
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub amount: UsdValue,
+
+    /// The position's isolated margin after the change.
+    pub isolated_margin: UsdValue,
+}
+
 /// Event indicating a user has deposited liquidity from his perp account margin
 /// into the vault.
 #[dango_primitives::event("liquidity_added")]
@@ -1500,7 +2139,40 @@ pub struct Deleveraged {
     pub remaining_position_size: Option<Quantity>,
 }
 
-/// Event indicating the insurance fund absorbed bad debt from a liquidation.
+/// Event indicating the settlement of a pair has started.
+#[dango_primitives::event("pair_settlement_started")]
+#[dango_primitives::derive(Serde)]
+pub struct PairSettlementStarted {
+    pub pair_id: PairId,
+    pub settlement_price: UsdPrice,
+}
+
+/// Event indicating a position in a settling pair has been closed at the
+/// settlement price.
+#[dango_primitives::event("position_settled")]
+#[dango_primitives::derive(Serde)]
+pub struct PositionSettled {
+    pub user: Addr,
+    pub pair_id: PairId,
+    /// The size by which the position was closed, i.e. the negated position
+    /// size.
+    pub closing_size: Quantity,
+    pub settlement_price: UsdPrice,
+    pub realized_pnl: UsdValue,
+    pub realized_funding: UsdValue,
+}
+
+/// Event indicating all orders of a settling pair have been canceled and all
+/// of its positions closed.
+#[dango_primitives::event("pair_delisted")]
+#[dango_primitives::derive(Serde)]
+pub struct PairDelisted {
+    pub pair_id: PairId,
+    pub settlement_price: UsdPrice,
+}
+
+/// Event indicating the insurance fund absorbed bad debt from a liquidation,
+/// or from the settlement of a delisted pair.
 #[dango_primitives::event("bad_debt_covered")]
 #[dango_primitives::derive(Serde)]
 pub struct BadDebtCovered {
@@ -1540,6 +2212,191 @@ pub struct ReferralSet {
     pub referee: UserIndex,
 }
 
+/// Event indicating a user has submitted a TWAP order.
+#[dango_primitives::event("twap_order_placed")]
+#[dango_primitives::derive(Serde)]
+pub struct TwapOrderPlaced {
+    pub twap_id: TwapOrderId,
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub size: Quantity,
+    pub num_slices: u32,
+    pub slice_interval: Duration,
+    pub max_slippage: Dimensionless,
+    pub reduce_only: bool,
+}
+
+/// Event indicating a slice of a TWAP order has been executed and (partially)
+/// filled. The individual fills are reported as `OrderFilled` events.
+#[dango_primitives::event("twap_slice_filled")]
+#[dango_primitives::derive(Serde)]
+pub struct TwapSliceFilled {
+    pub twap_id: TwapOrderId,
+    pub user: Addr,
+    pub pair_id: PairId,
+
+    /// The size the slice attempted to fill.
+    pub slice_size: Quantity,
+
+    /// The size the slice actually filled.
+    pub filled_size: Quantity,
+
+    /// The size filled by all slices of the TWAP order so far.
+    pub total_filled_size: Quantity,
+
+    /// The number of slices yet to be executed.
+    pub remaining_slices: u32,
+}
+
+/// Event indicating a slice of a TWAP order couldn't be executed, e.g. due to
+/// exceeded slippage, a lack of liquidity, or insufficient margin. Its size is
+/// carried over to the subsequent slices.
+#[dango_primitives::event("twap_slice_skipped")]
+#[dango_primitives::derive(Serde)]
+pub struct TwapSliceSkipped {
+    pub twap_id: TwapOrderId,
+    pub user: Addr,
+    pub pair_id: PairId,
+
+    /// The size the slice attempted to fill.
+    pub slice_size: Quantity,
+
+    /// Why the slice couldn't be executed.
+    pub reason: String,
+
+    /// The number of slices yet to be executed.
+    pub remaining_slices: u32,
+}
+
+/// Event indicating a TWAP order has been removed.
+#[dango_primitives::event("twap_order_removed")]
+#[dango_primitives::derive(Serde)]
+pub struct TwapOrderRemoved {
+    pub twap_id: TwapOrderId,
+    pub user: Addr,
+    pub pair_id: PairId,
+
+    /// The size filled by all slices of the TWAP order.
+    pub filled_size: Quantity,
+    pub reason: ReasonForTwapRemoval,
+}
+
+#[dango_primitives::derive(Serde)]
+#[derive(Copy)]
+pub enum ReasonForTwapRemoval {
+    /// All slices have been executed.
+    Completed,
+
+    /// The user voluntarily canceled the order.
+    Canceled,
+
+    /// The user was liquidated.
+    Liquidated,
+
+    /// Trading has been disabled, globally or in the order's pair, and the
+    /// order isn't reduce-only; or the order's pair is being delisted.
+    TradingDisabled,
+}
+
+/// Event indicating a user has submitted an order group. The orders placed
+/// along with it are reported as `OrderPersisted` / `ConditionalOrderPlaced`
+/// events.
+#[dango_primitives::event("order_group_placed")]
+#[dango_primitives::derive(Serde)]
+pub struct OrderGroupPlaced {
+    pub group_id: OrderGroupId,
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub kind: OrderGroupKind,
+}
+
+/// Event indicating the primary order of an OTO group has been fully filled,
+/// and the secondary orders have been placed.
+#[dango_primitives::event("order_group_activated")]
+#[dango_primitives::derive(Serde)]
+pub struct OrderGroupActivated {
+    pub group_id: OrderGroupId,
+    pub user: Addr,
+    pub pair_id: PairId,
+
+    /// IDs of the secondary orders that were placed. Secondaries that could
+    /// not be placed (e.g. a conditional order whose position no longer
+    /// exists) are omitted.
+    pub order_ids: Vec<OrderId>,
+}
+
+/// Event indicating an order group has been removed.
+#[dango_primitives::event("order_group_removed")]
+#[dango_primitives::derive(Serde)]
+pub struct OrderGroupRemoved {
+    pub group_id: OrderGroupId,
+    pub user: Addr,
+    pub pair_id: PairId,
+    pub reason: ReasonForOrderGroupRemoval,
+}
+
+#[dango_primitives::derive(Serde)]
+#[derive(Copy)]
+pub enum ReasonForOrderGroupRemoval {
+    /// A member of the OCO group was filled or triggered, and the other
+    /// members were canceled.
+    Resolved,
+
+    /// The user voluntarily canceled the group.
+    Canceled,
+
+    /// A member was removed for a reason other than a fill or trigger (e.g.
+    /// canceled by the user, or liquidated). The remaining members of an OCO
+    /// group are left as standalone orders; the secondaries of an OTO group
+    /// are discarded.
+    MemberRemoved,
+}
+
+/// Event indicating a user has armed, refreshed, or disarmed their dead-man's
+/// switch.
+#[dango_primitives::event("cancel_all_deadline_set")]
+#[dango_primitives::derive(Serde)]
+pub struct CancelAllDeadlineSet {
+    pub user: Addr,
+
+    /// The time at which all of the user's resting limit orders are to be
+    /// canceled. `None` if the switch has been disarmed.
+    pub deadline: Option<Timestamp>,
+}
+
+/// Event indicating a user's dead-man's switch has expired without being
+/// refreshed. The canceled orders are reported as `OrderRemoved` events.
+#[dango_primitives::event("cancel_all_triggered")]
+#[dango_primitives::derive(Serde)]
+pub struct CancelAllTriggered {
+    pub user: Addr,
+    pub deadline: Timestamp,
+}
+
+/// Event indicating the vault has replaced its resting quotes. All of the
+/// vault's previously resting orders were canceled, and `orders` are the ones
+/// now resting in their place (empty if the vault quotes nothing this block).
+///
+/// The vault's order churn doesn't emit `OrderPersisted` / `OrderRemoved`
+/// events; this single event per refresh lets off-chain consumers (e.g. the
+/// `l2Book` WebSocket channel) keep the order book in sync without them.
+#[dango_primitives::event("vault_orders_refreshed")]
+#[dango_primitives::derive(Serde)]
+pub struct VaultOrdersRefreshed {
+    pub orders: Vec<VaultOrder>,
+}
+
+/// A resting order placed by the vault, as reported in [`VaultOrdersRefreshed`].
+#[dango_primitives::derive(Serde)]
+pub struct VaultOrder {
+    pub order_id: OrderId,
+    pub pair_id: PairId,
+    pub limit_price: UsdPrice,
+
+    /// Signed size: positive for a bid, negative for an ask.
+    pub size: Quantity,
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
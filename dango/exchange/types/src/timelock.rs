@@ -0,0 +1,116 @@
+use {
+    dango_primitives::{Addr, Duration, Message, Timestamp},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// Identifier of a queued action.
+pub type ActionId = u32;
+
+/// Parameters of the timelock contract.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct Params {
+    /// The account that may queue actions, e.g. a multisig.
+    pub admin: Addr,
+
+    /// Accounts that may cancel queued actions, and perform emergency actions.
+    pub guardians: BTreeSet<Addr>,
+
+    /// The minimum delay between an action being queued and it becoming
+    /// executable.
+    pub min_delay: Duration,
+}
+
+/// A queued action.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct Action {
+    /// Messages to be executed, with the timelock as sender.
+    pub messages: Vec<Message>,
+    pub queued_at: Timestamp,
+    /// The earliest time at which the action can be executed.
+    pub executable_at: Timestamp,
+}
+
+#[dango_primitives::derive(Serde)]
+pub struct InstantiateMsg {
+    pub params: Params,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Queue messages to be executed after a delay.
+    ///
+    /// The delay defaults to `Params::min_delay`, and can't be shorter.
+    ///
+    /// Only callable by the admin.
+    Queue {
+        messages: Vec<Message>,
+        delay: Option<Duration>,
+    },
+    /// Cancel a queued action.
+    ///
+    /// Only callable by the admin or a guardian.
+    Cancel { action_id: ActionId },
+    /// Execute a queued action whose delay has elapsed. Callable by anyone.
+    Execute { action_id: ActionId },
+    /// Execute messages right away, without queueing them.
+    ///
+    /// Every message must be an emergency action, i.e. one that can only
+    /// reduce risk. Currently this is:
+    ///
+    /// - `perps::MaintainerMsg::DisableTrading`.
+    ///
+    /// Only callable by the admin or a guardian.
+    Emergency { messages: Vec<Message> },
+    /// Update the timelock's parameters.
+    ///
+    /// Only callable by the timelock itself, i.e. through a queued action.
+    UpdateParams(Params),
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the timelock's parameters.
+    #[returns(Params)]
+    Params {},
+    /// Query a single queued action by ID.
+    #[returns(Action)]
+    Action { action_id: ActionId },
+    /// Enumerate all queued actions.
+    #[returns(BTreeMap<ActionId, Action>)]
+    Actions {
+        start_after: Option<ActionId>,
+        limit: Option<u32>,
+    },
+}
+
+/// An event indicating an action has been queued.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("action_queued")]
+pub struct ActionQueued {
+    pub action_id: ActionId,
+    pub messages: Vec<Message>,
+    pub executable_at: Timestamp,
+}
+
+/// An event indicating a queued action has been canceled.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("action_canceled")]
+pub struct ActionCanceled {
+    pub action_id: ActionId,
+    pub canceled_by: Addr,
+}
+
+/// An event indicating a queued action has been executed.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("action_executed")]
+pub struct ActionExecuted {
+    pub action_id: ActionId,
+}
+
+/// An event indicating emergency actions have been executed without delay.
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("emergency_executed")]
+pub struct EmergencyExecuted {
+    pub messages: Vec<Message>,
+    pub executed_by: Addr,
+}
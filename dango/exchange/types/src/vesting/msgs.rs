@@ -1,5 +1,5 @@
 use {
-    crate::vesting::{Position, Schedule},
+    crate::vesting::{Position, PositionId, Schedule},
     dango_math::Uint128,
     dango_primitives::{Addr, Duration},
     std::collections::BTreeMap,
@@ -20,29 +20,37 @@ pub struct InstantiateMsg {
 #[dango_primitives::derive(Serde)]
 pub enum ExecuteMsg {
     /// Create a vesting position for a user with the given schedule.
+    /// A user may have any number of positions, each identified by an ID.
     ///
-    /// Sender must be the chain owner, and attach a non-zero amount of Dango
-    /// token and nothing else.
+    /// Sender must be the chain owner, and attach a non-zero amount of exactly
+    /// one denom. Positions of the Dango token are additionally subject to the
+    /// token's unlocking schedule.
     Create { user: Addr, schedule: Schedule },
     /// Terminate a user's vesting position.
     ///
     /// Sender must be the chain owner.
-    Terminate { user: Addr },
-    /// Claim the withdrawable amount from the vesting position.
+    Terminate { user: Addr, id: PositionId },
+    /// Claim the withdrawable amount from a vesting position, to the recipient
+    /// if specified, or the sender otherwise.
     ///
-    /// Sender must have a non-zero amount of claimable tokens.
-    Claim {},
+    /// Sender must own the position, which must have a non-zero amount of
+    /// claimable tokens.
+    Claim {
+        id: PositionId,
+        recipient: Option<Addr>,
+    },
 }
 
 #[dango_primitives::derive(Serde, QueryRequest)]
 pub enum QueryMsg {
-    /// Query a single vesting position by user address.
+    /// Query a single vesting position by user address and position ID.
     #[returns(PositionResponse)]
-    Position { user: Addr },
-    /// Enumerate all vesting positions.
-    #[returns(BTreeMap<Addr, PositionResponse>)]
+    Position { user: Addr, id: PositionId },
+    /// Enumerate a user's vesting positions.
+    #[returns(BTreeMap<PositionId, PositionResponse>)]
     Positions {
-        start_after: Option<Addr>,
+        user: Addr,
+        start_after: Option<PositionId>,
         limit: Option<u32>,
     },
 }
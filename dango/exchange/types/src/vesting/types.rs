@@ -1,17 +1,23 @@
 use {
-    dango_math::{MathResult, MultiplyFraction, Number, NumberConst, Udec128, Uint128},
-    dango_primitives::{Duration, Timestamp},
+    crate::constants::dango,
+    dango_math::{
+        MathResult, MultiplyFraction, MultiplyRatio, Number, NumberConst, Udec128, Uint128,
+    },
+    dango_primitives::{Denom, Duration, Timestamp},
     std::cmp::min,
 };
 
+pub type PositionId = u32;
+
+/// A schedule under which tokens vest/unlock continuously through time.
 #[dango_primitives::derive(Serde, Borsh)]
-pub struct Schedule {
+pub struct LinearSchedule {
     pub start_time: Timestamp,
     pub cliff: Duration,
     pub period: Duration,
 }
 
-impl Schedule {
+impl LinearSchedule {
     pub fn compute_claimable(&self, now: Timestamp, total: Uint128) -> MathResult<Uint128> {
         let claim_percent = if now < self.start_time + self.cliff {
             // Before the cliff, no token is vested/unlocked.
@@ -32,6 +38,49 @@ impl Schedule {
     }
 }
 
+/// A schedule under which tokens vest in equal amounts at discrete steps,
+/// e.g. monthly.
+///
+/// The `k`-th step (starting from 1) happens at `start_time + k * interval`,
+/// after which `k / num_steps` of the tokens are vested. Steps that happen
+/// before the cliff vest at the cliff.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct StepSchedule {
+    pub start_time: Timestamp,
+    pub cliff: Duration,
+    pub interval: Duration,
+    pub num_steps: u32,
+}
+
+impl StepSchedule {
+    pub fn compute_claimable(&self, now: Timestamp, total: Uint128) -> MathResult<Uint128> {
+        if now < self.start_time + self.cliff {
+            return Ok(Uint128::ZERO);
+        }
+
+        let steps = (now - self.start_time).into_nanos() / self.interval.into_nanos();
+        let steps = min(steps, self.num_steps as u128);
+
+        total
+            .checked_multiply_ratio_floor(Uint128::new(steps), Uint128::new(self.num_steps as u128))
+    }
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub enum Schedule {
+    Linear(LinearSchedule),
+    Step(StepSchedule),
+}
+
+impl Schedule {
+    pub fn compute_claimable(&self, now: Timestamp, total: Uint128) -> MathResult<Uint128> {
+        match self {
+            Schedule::Linear(schedule) => schedule.compute_claimable(now, total),
+            Schedule::Step(schedule) => schedule.compute_claimable(now, total),
+        }
+    }
+}
+
 #[dango_primitives::derive(Serde, Borsh)]
 pub enum VestingStatus {
     /// Position is actively being vested.
@@ -54,6 +103,7 @@ impl VestingStatus {
 
 #[dango_primitives::derive(Serde, Borsh)]
 pub struct Position {
+    pub denom: Denom,
     pub vesting_status: VestingStatus,
     pub total: Uint128,
     pub claimed: Uint128,
@@ -63,15 +113,57 @@ impl Position {
     pub fn compute_claimable(
         &self,
         now: Timestamp,
-        unlocking_schedule: &Schedule,
+        unlocking_schedule: &LinearSchedule,
     ) -> MathResult<Uint128> {
-        // The claimable amount is the minimum between the claimable amount
-        // from the vesting status and the unlocking schedule
-        let claimable = min(
-            self.vesting_status.compute_claimable(now, self.total)?,
-            unlocking_schedule.compute_claimable(now, self.total)?,
-        );
+        let mut claimable = self.vesting_status.compute_claimable(now, self.total)?;
+
+        // For the Dango token, the claimable amount is the minimum between the
+        // claimable amount from the vesting status and the unlocking schedule.
+        // Other denoms aren't subject to the unlocking schedule.
+        if self.denom == *dango::DENOM {
+            claimable = min(
+                claimable,
+                unlocking_schedule.compute_claimable(now, self.total)?,
+            );
+        }
 
         Ok(claimable.checked_sub(self.claimed).unwrap_or_default())
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONTH: Duration = Duration::from_weeks(4);
+
+    #[test]
+    fn step_schedule_vesting() {
+        let schedule = StepSchedule {
+            start_time: Timestamp::from_weeks(1),
+            cliff: MONTH * 2,
+            interval: MONTH,
+            num_steps: 4,
+        };
+
+        for (weeks, expected) in [
+            (0, 0),     // at start
+            (4, 0),     // in the cliff, after the first step
+            (8, 50),    // at the cliff
+            (11, 50),   // between steps
+            (12, 75),   // at a step
+            (16, 100),  // at the last step
+            (100, 100), // after the last step
+        ] {
+            assert_eq!(
+                schedule
+                    .compute_claimable(Timestamp::from_weeks(1 + weeks), Uint128::new(100))
+                    .unwrap(),
+                Uint128::new(expected),
+                "weeks: {weeks}"
+            );
+        }
+    }
+}
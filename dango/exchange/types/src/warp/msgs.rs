@@ -1,5 +1,5 @@
 use {
-    crate::gateway::bridge::BridgeMsg,
+    crate::gateway::bridge::{BridgeMsg, BridgeQuery, BridgeQueryResponse},
     dango_hyperlane_types::recipients::{RecipientMsg, RecipientQuery, RecipientQueryResponse},
     dango_primitives::Addr,
 };
@@ -26,4 +26,7 @@ pub enum QueryMsg {
     /// Required Hyperlane recipient interface.
     #[returns(RecipientQueryResponse)]
     Recipient(RecipientQuery),
+    /// Required Dango Gateway interface.
+    #[returns(BridgeQueryResponse)]
+    Bridge(BridgeQuery),
 }
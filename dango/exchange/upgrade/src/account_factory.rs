@@ -0,0 +1,35 @@
+use {
+    crate::{ACCOUNT_MULTI_CODE_INDEX, upload},
+    dango_account_factory::MULTI_CODE_HASH,
+    dango_app::{APP_CONFIG, AppError, AppResult, CONTRACT_NAMESPACE, StorageProvider, Vm},
+    dango_primitives::{Addr, BlockInfo, JsonDeExt, Storage},
+};
+
+/// Upload the multisig account code, and record its hash in the account
+/// factory, which only does so on instantiation.
+pub fn do_account_factory_upgrades<VM>(
+    storage: Box<dyn Storage>,
+    vm: VM,
+    block: BlockInfo,
+) -> AppResult<()>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    // Only the account factory's address is needed, so read it from the raw
+    // JSON rather than deserializing the whole `AppConfig`.
+    let account_factory = APP_CONFIG.load(&storage)?["addresses"]["account_factory"]
+        .clone()
+        .deserialize_json::<Addr>()?;
+
+    let code_hash = upload(vm, storage.clone(), block, ACCOUNT_MULTI_CODE_INDEX)?;
+
+    let mut factory_storage =
+        StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &account_factory]);
+
+    MULTI_CODE_HASH.save(&mut factory_storage, &code_hash)?;
+
+    tracing::info!(%code_hash, "Stored multisig account code hash");
+
+    Ok(())
+}
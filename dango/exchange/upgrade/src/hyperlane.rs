@@ -0,0 +1,125 @@
+use {
+    crate::{
+        AGGREGATION_ISM_CODE_INDEX, IGP_CODE_INDEX, ROUTING_ISM_CODE_INDEX,
+        process_as_genesis_sender, upload,
+    },
+    dango_app::{APP_CONFIG, AppError, AppResult, CONFIG, Vm},
+    dango_hyperlane_types::{hooks::igp, isms},
+    dango_primitives::{
+        Addr, BlockInfo, Coins, GENESIS_SENDER, Json, JsonDeExt, JsonSerExt, Message, Storage,
+    },
+    dango_types::{config::AppConfig, constants::usdc},
+    std::collections::BTreeMap,
+};
+
+/// Deploy the Hyperlane contracts added since the chain launched, and record
+/// their addresses in the app config.
+///
+/// The contracts are deployed the same way as in genesis, so they have the
+/// same addresses as on a chain that includes them from the start. Connecting
+/// them to the mailbox is left to the chain owner, once they're configured.
+pub fn do_hyperlane_upgrades<VM>(
+    mut storage: Box<dyn Storage>,
+    vm: VM,
+    block: BlockInfo,
+) -> AppResult<()>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    let owner = CONFIG.load(&storage)?.owner;
+    let mut app_config: AppConfig = APP_CONFIG.load(&storage)?.deserialize_json()?;
+    let hyperlane = &mut app_config.addresses.hyperlane;
+
+    // Gas configs of destination domains are to be set by the owner.
+    hyperlane.igp = deploy(
+        vm.clone(),
+        storage.clone(),
+        block,
+        IGP_CODE_INDEX,
+        igp::InstantiateMsg {
+            mailbox: hyperlane.mailbox,
+            fee_denom: usdc::DENOM.clone(),
+            beneficiary: owner,
+            destination_gas_configs: BTreeMap::new(),
+        }
+        .to_json_value()?,
+        "hyperlane/hook/igp",
+        owner,
+    )?;
+
+    tracing::info!(address = %hyperlane.igp, "Deployed Hyperlane IGP");
+
+    // Like in genesis, the aggregation ISM starts out as the multisig ISM
+    // alone, and the routing ISM falls back to the multisig ISM.
+    hyperlane.aggregation_ism = deploy(
+        vm.clone(),
+        storage.clone(),
+        block,
+        AGGREGATION_ISM_CODE_INDEX,
+        isms::aggregation::InstantiateMsg {
+            ism_set: isms::aggregation::IsmSet {
+                threshold: 1,
+                isms: vec![hyperlane.ism],
+            },
+        }
+        .to_json_value()?,
+        "hyperlane/ism/aggregation",
+        owner,
+    )?;
+
+    tracing::info!(
+        address = %hyperlane.aggregation_ism,
+        "Deployed Hyperlane aggregation ISM"
+    );
+
+    hyperlane.routing_ism = deploy(
+        vm.clone(),
+        storage.clone(),
+        block,
+        ROUTING_ISM_CODE_INDEX,
+        isms::routing::InstantiateMsg {
+            default_ism: Some(hyperlane.ism),
+            routes: BTreeMap::new(),
+        }
+        .to_json_value()?,
+        "hyperlane/ism/routing",
+        owner,
+    )?;
+
+    tracing::info!(
+        address = %hyperlane.routing_ism,
+        "Deployed Hyperlane routing ISM"
+    );
+
+    APP_CONFIG.save(&mut storage, &app_config.to_json_value()?)?;
+
+    Ok(())
+}
+
+/// Upload the Rust contract of the given index, unless it already exists, and
+/// instantiate it as the genesis sender. Return the contract's address.
+fn deploy<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    code_index: usize,
+    msg: Json,
+    salt: &str,
+    admin: Addr,
+) -> AppResult<Addr>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    let code_hash = upload(vm.clone(), storage.clone(), block, code_index)?;
+
+    process_as_genesis_sender(
+        vm,
+        storage,
+        block,
+        Message::instantiate(code_hash, &msg, salt, Some(salt), Some(admin), Coins::new())?,
+    )?;
+
+    Ok(Addr::derive(GENESIS_SENDER, code_hash, salt.as_bytes()))
+}
@@ -1,14 +1,109 @@
+mod account_factory;
+mod hyperlane;
 mod perps;
+mod vesting;
 
 use {
-    dango_app::AppResult,
-    dango_primitives::{BlockInfo, Storage},
+    dango_app::{AppError, AppResult, CODES, GasTracker, TraceOption, Vm, process_msg},
+    dango_primitives::{Binary, BlockInfo, GENESIS_SENDER, Hash256, HashExt, Message, Storage},
+    dango_vm_rust::ContractWrapper,
 };
 
-pub fn do_upgrade<VM>(_storage: Box<dyn Storage>, _vm: VM, _block: BlockInfo) -> AppResult<()> {
-    // Call relevant upgrade functions here.
+// Indexes of the Rust contracts that the upgrades deploy or locate, in the
+// order `GenesisCodes` builds them.
+const ACCOUNT_MULTI_CODE_INDEX: usize = 1;
+const VESTING_CODE_INDEX: usize = 11;
+const IGP_CODE_INDEX: usize = 14;
+const AGGREGATION_ISM_CODE_INDEX: usize = 15;
+const ROUTING_ISM_CODE_INDEX: usize = 16;
 
-    tracing::info!("Nothing to do for this upgrade");
+pub fn do_upgrade<VM>(storage: Box<dyn Storage>, vm: VM, block: BlockInfo) -> AppResult<()>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    account_factory::do_account_factory_upgrades(storage.clone(), vm.clone(), block)?;
+
+    hyperlane::do_hyperlane_upgrades(storage.clone(), vm, block)?;
+
+    perps::do_perps_upgrades(storage.clone())?;
+
+    vesting::do_vesting_upgrades(storage)
+}
+
+/// Upload the Rust contract of the given index as the genesis sender, unless
+/// it already exists. Return its code hash.
+fn upload<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    code_index: usize,
+) -> AppResult<Hash256>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    let code: Binary = ContractWrapper::from_index(code_index).into();
+    let code_hash = code.sha2_256();
+
+    if !CODES.has(&storage, code_hash) {
+        process_as_genesis_sender(vm, storage, block, Message::upload(code))?;
+    }
+
+    Ok(code_hash)
+}
+
+/// Process a message as the genesis sender, which is allowed to upload codes
+/// and instantiate contracts regardless of the chain's permissions.
+fn process_as_genesis_sender<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    msg: Message,
+) -> AppResult<()>
+where
+    VM: Vm + Clone + Send + Sync + 'static,
+    AppError: From<VM::Error>,
+{
+    process_msg(
+        vm,
+        storage,
+        GasTracker::new_limitless(),
+        block,
+        0,
+        GENESIS_SENDER,
+        msg,
+        TraceOption::LOUD,
+    )
+    .into_result()
+    .map_err(|(_, err)| err)?;
 
     Ok(())
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, dango_genesis::GenesisCodes, dango_vm_rust::RustVm};
+
+    // The Rust contracts are registered each time `genesis_codes` is called,
+    // so it must only be called once in this test binary.
+    #[test]
+    fn code_indexes_match_genesis_codes() {
+        let codes = RustVm::genesis_codes();
+
+        for (code, index) in [
+            (codes.account_multi, ACCOUNT_MULTI_CODE_INDEX),
+            (codes.vesting, VESTING_CODE_INDEX),
+            (codes.hyperlane.igp, IGP_CODE_INDEX),
+            (codes.hyperlane.aggregation_ism, AGGREGATION_ISM_CODE_INDEX),
+            (codes.hyperlane.routing_ism, ROUTING_ISM_CODE_INDEX),
+        ] {
+            assert_eq!(
+                Binary::from(code),
+                Binary::from(ContractWrapper::from_index(index))
+            );
+        }
+    }
+}
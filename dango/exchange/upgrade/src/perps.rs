@@ -1,9 +1,11 @@
-// This file contains only the boilerplate.
-#![allow(dead_code)]
-
 use {
-    dango_app::{AppResult, CHAIN_ID, CONTRACT_NAMESPACE, StorageProvider},
-    dango_primitives::{Addr, Storage, addr},
+    dango_account_factory::USERS,
+    dango_app::{APP_CONFIG, AppResult, CHAIN_ID, CONTRACT_NAMESPACE, StorageProvider},
+    dango_order_book::PairId,
+    dango_perps::state::ACCOUNT_OWNERS,
+    dango_primitives::{Addr, JsonDeExt, Order as IterationOrder, StdResult, Storage, addr},
+    dango_storage::Map,
+    dango_types::perps::{PairParam, UserState},
 };
 
 const MAINNET_CHAIN_ID: &str = "dango-1";
@@ -14,9 +16,173 @@ const TESTNET_PERPS_ADDRESS: Addr = addr!("f6344c5e2792e8f9202c58a2d88fbbde4cd31
 
 /// Pre-migration perps storage shapes.
 mod legacy_perps {
-    // Add content here.
+    use {
+        dango_math::Uint128,
+        dango_order_book::{
+            ConditionalOrder, ConditionalOrderId, Dimensionless, FundingPerUnit, FundingRate,
+            PairId, Quantity, UsdPrice, UsdValue,
+        },
+        dango_primitives::Addr,
+        dango_storage::Map,
+        dango_types::perps::{
+            FundingMode, PairParam, PairStatus, Position, Unlock, UserState, VaultLadder,
+        },
+        std::collections::{BTreeMap, BTreeSet, VecDeque},
+    };
+
+    /// `ConditionalOrder` before the introduction of stop-limit and trailing
+    /// stop orders.
+    #[dango_primitives::derive(Borsh)]
+    pub struct LegacyConditionalOrder {
+        pub order_id: ConditionalOrderId,
+        pub size: Option<Quantity>,
+        pub trigger_price: UsdPrice,
+        pub max_slippage: Dimensionless,
+    }
+
+    impl From<LegacyConditionalOrder> for ConditionalOrder {
+        fn from(legacy: LegacyConditionalOrder) -> Self {
+            ConditionalOrder {
+                order_id: legacy.order_id,
+                size: legacy.size,
+                trigger_price: legacy.trigger_price,
+                max_slippage: legacy.max_slippage,
+                limit_price: None,
+                trailing_offset: None,
+            }
+        }
+    }
+
+    /// `Position` before multiple conditional orders per direction were
+    /// allowed.
+    #[dango_primitives::derive(Borsh)]
+    pub struct LegacyPosition {
+        pub size: Quantity,
+        pub entry_price: UsdPrice,
+        pub entry_funding_per_unit: FundingPerUnit,
+        pub conditional_order_above: Option<LegacyConditionalOrder>,
+        pub conditional_order_below: Option<LegacyConditionalOrder>,
+    }
+
+    impl From<LegacyPosition> for Position {
+        fn from(legacy: LegacyPosition) -> Self {
+            fn into_map(
+                order: Option<LegacyConditionalOrder>,
+            ) -> BTreeMap<ConditionalOrderId, ConditionalOrder> {
+                order
+                    .into_iter()
+                    .map(|order| (order.order_id, order.into()))
+                    .collect()
+            }
+
+            Position {
+                size: legacy.size,
+                entry_price: legacy.entry_price,
+                entry_funding_per_unit: legacy.entry_funding_per_unit,
+                conditional_orders_above: into_map(legacy.conditional_order_above),
+                conditional_orders_below: into_map(legacy.conditional_order_below),
+            }
+        }
+    }
+
+    /// `UserState` before the introduction of isolated margin.
+    #[dango_primitives::derive(Borsh)]
+    pub struct LegacyUserState {
+        pub margin: UsdValue,
+        pub vault_shares: Uint128,
+        pub positions: BTreeMap<PairId, LegacyPosition>,
+        pub unlocks: VecDeque<Unlock>,
+        pub reserved_margin: UsdValue,
+        pub open_order_count: usize,
+    }
+
+    impl From<LegacyUserState> for UserState {
+        fn from(legacy: LegacyUserState) -> Self {
+            UserState {
+                margin: legacy.margin,
+                vault_shares: legacy.vault_shares,
+                positions: legacy
+                    .positions
+                    .into_iter()
+                    .map(|(pair_id, position)| (pair_id, position.into()))
+                    .collect(),
+                unlocks: legacy.unlocks,
+                reserved_margin: legacy.reserved_margin,
+                open_order_count: legacy.open_order_count,
+                // All existing positions are cross-margin.
+                isolated_margins: BTreeMap::new(),
+            }
+        }
+    }
+
+    /// The primary map of `USER_STATES`. Its existing indexes are keyed by
+    /// (pair, direction, trigger price, order ID), which the migration doesn't
+    /// change, so only the primary entries need to be rewritten. The trailing
+    /// stop index starts out empty, as no legacy order is a trailing stop.
+    pub const USER_STATES: Map<Addr, LegacyUserState> = Map::new("us");
+
+    /// `PairParam` before the introduction of pair statuses, funding modes, and
+    /// vault quote ladders.
+    #[dango_primitives::derive(Borsh)]
+    pub struct LegacyPairParam {
+        pub tick_size: UsdPrice,
+        pub min_order_size: UsdValue,
+        pub max_limit_price_deviation: Dimensionless,
+        pub max_market_slippage: Dimensionless,
+        pub max_abs_oi: Quantity,
+        pub max_abs_funding_rate: FundingRate,
+        pub initial_margin_ratio: Dimensionless,
+        pub maintenance_margin_ratio: Dimensionless,
+        pub impact_size: UsdValue,
+        pub vault_liquidity_weight: Dimensionless,
+        pub vault_half_spread: Dimensionless,
+        pub vault_max_quote_size: Quantity,
+        pub vault_size_skew_factor: Dimensionless,
+        pub vault_spread_skew_factor: Dimensionless,
+        pub vault_max_skew_size: Quantity,
+        pub funding_rate_multiplier: Dimensionless,
+        pub bucket_sizes: BTreeSet<UsdPrice>,
+    }
+
+    impl From<LegacyPairParam> for PairParam {
+        fn from(legacy: LegacyPairParam) -> Self {
+            PairParam {
+                tick_size: legacy.tick_size,
+                min_order_size: legacy.min_order_size,
+                max_limit_price_deviation: legacy.max_limit_price_deviation,
+                max_market_slippage: legacy.max_market_slippage,
+                max_abs_oi: legacy.max_abs_oi,
+                max_abs_funding_rate: legacy.max_abs_funding_rate,
+                initial_margin_ratio: legacy.initial_margin_ratio,
+                maintenance_margin_ratio: legacy.maintenance_margin_ratio,
+                impact_size: legacy.impact_size,
+                vault_liquidity_weight: legacy.vault_liquidity_weight,
+                vault_half_spread: legacy.vault_half_spread,
+                vault_max_quote_size: legacy.vault_max_quote_size,
+                vault_size_skew_factor: legacy.vault_size_skew_factor,
+                vault_spread_skew_factor: legacy.vault_spread_skew_factor,
+                vault_max_skew_size: legacy.vault_max_skew_size,
+                // All existing pairs quote a single level on each side.
+                vault_ladder: VaultLadder::default(),
+                funding_rate_multiplier: legacy.funding_rate_multiplier,
+                // All existing pairs derive funding from the vault's skew.
+                funding_mode: FundingMode::VaultSkew,
+                bucket_sizes: legacy.bucket_sizes,
+                // All existing pairs are active.
+                status: PairStatus::Active,
+            }
+        }
+    }
+
+    pub const PAIR_PARAMS: Map<&PairId, LegacyPairParam> = Map::new("pair_param");
 }
 
+/// The primary map of `USER_STATES`, in the new shape.
+const USER_STATES: Map<Addr, UserState> = Map::new("us");
+
+/// `PAIR_PARAMS`, in the new shape.
+const PAIR_PARAMS: Map<&PairId, PairParam> = Map::new("pair_param");
+
 pub fn do_perps_upgrades(storage: Box<dyn Storage>) -> AppResult<()> {
     let perps_address = {
         let chain_id = CHAIN_ID.load(&storage)?;
@@ -27,16 +193,285 @@ pub fn do_perps_upgrades(storage: Box<dyn Storage>) -> AppResult<()> {
         }
     };
 
-    let mut _perps_storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &perps_address]);
+    // Only the account factory's address is needed, so read it from the raw
+    // JSON rather than deserializing the whole `AppConfig`.
+    let account_factory = APP_CONFIG.load(&storage)?["addresses"]["account_factory"]
+        .clone()
+        .deserialize_json::<Addr>()?;
+
+    let factory_storage =
+        StorageProvider::new(storage.clone(), &[CONTRACT_NAMESPACE, &account_factory]);
+
+    let mut perps_storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &perps_address]);
+
+    let count = migrate_user_states(&mut perps_storage)?;
+
+    tracing::info!(count, "Migrated perps user states");
+
+    let count = migrate_pair_params(&mut perps_storage)?;
 
-    // Add migration logic here.
+    tracing::info!(count, "Migrated perps pair params");
+
+    let count = backfill_account_owners(&mut perps_storage, &factory_storage)?;
+
+    tracing::info!(count, "Backfilled perps account owners");
 
     Ok(())
 }
 
+/// Add the empty `isolated_margins` field to every perps user state, and move
+/// each position's conditional orders into the per-direction maps.
+fn migrate_user_states(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy_user_states = legacy_perps::USER_STATES
+        .range(storage, None, None, IterationOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let count = legacy_user_states.len();
+
+    for (user, legacy_user_state) in legacy_user_states {
+        USER_STATES.save(storage, user, &legacy_user_state.into())?;
+    }
+
+    Ok(count)
+}
+
+/// Set the status of every perps pair to active, its funding mode to vault
+/// skew, and its vault ladder to a single level.
+fn migrate_pair_params(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy_pair_params = legacy_perps::PAIR_PARAMS
+        .range(storage, None, None, IterationOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let count = legacy_pair_params.len();
+
+    for (pair_id, legacy_pair_param) in legacy_pair_params {
+        PAIR_PARAMS.save(storage, &pair_id, &legacy_pair_param.into())?;
+    }
+
+    Ok(count)
+}
+
+/// Record the owner of every account that has a perps user state, as
+/// `ACCOUNT_OWNERS` is otherwise only populated when an account first receives
+/// margin. Accounts the account factory doesn't know as user accounts (e.g.
+/// multisigs) are skipped.
+fn backfill_account_owners(
+    storage: &mut dyn Storage,
+    factory_storage: &dyn Storage,
+) -> StdResult<usize> {
+    let accounts = USER_STATES
+        .keys(storage, None, None, IterationOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut count = 0;
+
+    for account in accounts {
+        if ACCOUNT_OWNERS.has(storage, account) {
+            continue;
+        }
+
+        if let Some(user) = USERS
+            .idx
+            .by_account
+            .may_load_key(factory_storage, account)?
+        {
+            ACCOUNT_OWNERS.save(storage, account, &user)?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
 mod tests {
-    // Add tests here.
+    use {
+        super::*,
+        dango_math::{Uint64, Uint128},
+        dango_order_book::{
+            ConditionalOrder, Dimensionless, FundingPerUnit, FundingRate, Quantity, UsdPrice,
+            UsdValue,
+        },
+        dango_primitives::{MockStorage, btree_map},
+        dango_types::{
+            account_factory::{User, Username},
+            constants::perp_eth,
+            perps::{FundingMode, PairStatus, Position, VaultLadder},
+        },
+        std::collections::{BTreeMap, BTreeSet, VecDeque},
+    };
+
+    #[test]
+    fn migrating_user_states_works() {
+        let mut storage = MockStorage::new();
+
+        let legacy_positions = BTreeMap::from([(
+            perp_eth::DENOM.clone(),
+            legacy_perps::LegacyPosition {
+                size: Quantity::new_int(-5),
+                entry_price: UsdPrice::new_int(2_000),
+                entry_funding_per_unit: FundingPerUnit::new_int(1),
+                conditional_order_above: None,
+                conditional_order_below: Some(legacy_perps::LegacyConditionalOrder {
+                    order_id: Uint64::new(7),
+                    size: None,
+                    trigger_price: UsdPrice::new_int(2_200),
+                    max_slippage: Dimensionless::new_percent(1),
+                }),
+            },
+        )]);
+
+        let positions = BTreeMap::from([(
+            perp_eth::DENOM.clone(),
+            Position {
+                size: Quantity::new_int(-5),
+                entry_price: UsdPrice::new_int(2_000),
+                entry_funding_per_unit: FundingPerUnit::new_int(1),
+                conditional_orders_above: BTreeMap::new(),
+                conditional_orders_below: BTreeMap::from([(
+                    Uint64::new(7),
+                    ConditionalOrder {
+                        order_id: Uint64::new(7),
+                        size: None,
+                        trigger_price: UsdPrice::new_int(2_200),
+                        max_slippage: Dimensionless::new_percent(1),
+                        limit_price: None,
+                        trailing_offset: None,
+                    },
+                )]),
+            },
+        )]);
+
+        legacy_perps::USER_STATES
+            .save(
+                &mut storage,
+                Addr::mock(1),
+                &legacy_perps::LegacyUserState {
+                    margin: UsdValue::new_int(10_000),
+                    vault_shares: Uint128::new(123),
+                    positions: legacy_positions,
+                    unlocks: VecDeque::new(),
+                    reserved_margin: UsdValue::new_int(100),
+                    open_order_count: 2,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(migrate_user_states(&mut storage).unwrap(), 1);
+
+        assert_eq!(
+            USER_STATES.load(&storage, Addr::mock(1)).unwrap(),
+            UserState {
+                margin: UsdValue::new_int(10_000),
+                vault_shares: Uint128::new(123),
+                positions,
+                unlocks: VecDeque::new(),
+                reserved_margin: UsdValue::new_int(100),
+                open_order_count: 2,
+                isolated_margins: BTreeMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn migrating_pair_params_works() {
+        let mut storage = MockStorage::new();
+
+        legacy_perps::PAIR_PARAMS
+            .save(
+                &mut storage,
+                &perp_eth::DENOM,
+                &legacy_perps::LegacyPairParam {
+                    tick_size: UsdPrice::new_int(1),
+                    min_order_size: UsdValue::new_int(10),
+                    max_limit_price_deviation: Dimensionless::new_percent(10),
+                    max_market_slippage: Dimensionless::new_percent(5),
+                    max_abs_oi: Quantity::new_int(1_000),
+                    max_abs_funding_rate: FundingRate::new_percent(5),
+                    initial_margin_ratio: Dimensionless::new_percent(10),
+                    maintenance_margin_ratio: Dimensionless::new_percent(5),
+                    impact_size: UsdValue::new_int(10_000),
+                    vault_liquidity_weight: Dimensionless::new_int(1),
+                    vault_half_spread: Dimensionless::new_percent(1),
+                    vault_max_quote_size: Quantity::new_int(100),
+                    vault_size_skew_factor: Dimensionless::ZERO,
+                    vault_spread_skew_factor: Dimensionless::ZERO,
+                    vault_max_skew_size: Quantity::ZERO,
+                    funding_rate_multiplier: Dimensionless::new_int(1),
+                    bucket_sizes: BTreeSet::from([UsdPrice::new_int(10)]),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(migrate_pair_params(&mut storage).unwrap(), 1);
+
+        assert_eq!(
+            PAIR_PARAMS.load(&storage, &perp_eth::DENOM).unwrap(),
+            PairParam {
+                tick_size: UsdPrice::new_int(1),
+                min_order_size: UsdValue::new_int(10),
+                max_limit_price_deviation: Dimensionless::new_percent(10),
+                max_market_slippage: Dimensionless::new_percent(5),
+                max_abs_oi: Quantity::new_int(1_000),
+                max_abs_funding_rate: FundingRate::new_percent(5),
+                initial_margin_ratio: Dimensionless::new_percent(10),
+                maintenance_margin_ratio: Dimensionless::new_percent(5),
+                impact_size: UsdValue::new_int(10_000),
+                vault_liquidity_weight: Dimensionless::new_int(1),
+                vault_half_spread: Dimensionless::new_percent(1),
+                vault_max_quote_size: Quantity::new_int(100),
+                vault_size_skew_factor: Dimensionless::ZERO,
+                vault_spread_skew_factor: Dimensionless::ZERO,
+                vault_max_skew_size: Quantity::ZERO,
+                vault_ladder: VaultLadder::default(),
+                funding_rate_multiplier: Dimensionless::new_int(1),
+                funding_mode: FundingMode::VaultSkew,
+                bucket_sizes: BTreeSet::from([UsdPrice::new_int(10)]),
+                status: PairStatus::Active,
+            }
+        );
+    }
+
+    #[test]
+    fn backfilling_account_owners_works() {
+        let mut storage = MockStorage::new();
+        let mut factory_storage = MockStorage::new();
+
+        // Accounts 1 and 2 belong to user 7. Account 3 is a multisig, which
+        // isn't a user account. Account 4 is already recorded.
+        USERS
+            .save(
+                &mut factory_storage,
+                7,
+                &User {
+                    index: 7,
+                    name: Username::default_for_index(7),
+                    accounts: btree_map! { 0 => Addr::mock(1), 1 => Addr::mock(2) },
+                    keys: btree_map! {},
+                },
+            )
+            .unwrap();
+
+        for account in 1..=4 {
+            USER_STATES
+                .save(&mut storage, Addr::mock(account), &UserState::default())
+                .unwrap();
+        }
+
+        ACCOUNT_OWNERS
+            .save(&mut storage, Addr::mock(4), &8)
+            .unwrap();
+
+        assert_eq!(
+            backfill_account_owners(&mut storage, &factory_storage).unwrap(),
+            2
+        );
+
+        assert_eq!(ACCOUNT_OWNERS.load(&storage, Addr::mock(1)).unwrap(), 7);
+        assert_eq!(ACCOUNT_OWNERS.load(&storage, Addr::mock(2)).unwrap(), 7);
+        assert!(!ACCOUNT_OWNERS.has(&storage, Addr::mock(3)));
+        assert_eq!(ACCOUNT_OWNERS.load(&storage, Addr::mock(4)).unwrap(), 8);
+    }
 }
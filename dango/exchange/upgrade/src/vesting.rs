@@ -0,0 +1,185 @@
+use {
+    crate::VESTING_CODE_INDEX,
+    dango_app::{AppResult, CONTRACT_NAMESPACE, StorageProvider},
+    dango_primitives::{
+        Addr, Binary, GENESIS_SENDER, HashExt, Order as IterationOrder, StdResult, Storage,
+    },
+    dango_vesting::{NEXT_POSITION_ID, POSITIONS},
+    dango_vm_rust::ContractWrapper,
+};
+
+/// Pre-migration vesting storage shapes.
+mod legacy_vesting {
+    use {
+        dango_math::Uint128,
+        dango_primitives::Addr,
+        dango_storage::Map,
+        dango_types::{
+            constants::dango,
+            vesting::{LinearSchedule, Position, Schedule, VestingStatus},
+        },
+    };
+
+    /// `VestingStatus` before the introduction of step schedules.
+    #[dango_primitives::derive(Borsh)]
+    pub enum LegacyVestingStatus {
+        Active(LinearSchedule),
+        Terminated(Uint128),
+    }
+
+    impl From<LegacyVestingStatus> for VestingStatus {
+        fn from(legacy: LegacyVestingStatus) -> Self {
+            match legacy {
+                LegacyVestingStatus::Active(schedule) => {
+                    VestingStatus::Active(Schedule::Linear(schedule))
+                },
+                LegacyVestingStatus::Terminated(vested) => VestingStatus::Terminated(vested),
+            }
+        }
+    }
+
+    /// `Position` before vesting denoms other than the Dango token were
+    /// allowed.
+    #[dango_primitives::derive(Borsh)]
+    pub struct LegacyPosition {
+        pub vesting_status: LegacyVestingStatus,
+        pub total: Uint128,
+        pub claimed: Uint128,
+    }
+
+    impl From<LegacyPosition> for Position {
+        fn from(legacy: LegacyPosition) -> Self {
+            Position {
+                // All existing positions vest the Dango token.
+                denom: dango::DENOM.clone(),
+                vesting_status: legacy.vesting_status.into(),
+                total: legacy.total,
+                claimed: legacy.claimed,
+            }
+        }
+    }
+
+    /// `POSITIONS` before a user could have more than one position.
+    pub const POSITIONS: Map<Addr, LegacyPosition> = Map::new("position");
+}
+
+pub fn do_vesting_upgrades(storage: Box<dyn Storage>) -> AppResult<()> {
+    // The vesting contract is instantiated in genesis, so its address can be
+    // derived the same way.
+    let vesting_address = {
+        let code_hash = Binary::from(ContractWrapper::from_index(VESTING_CODE_INDEX)).sha2_256();
+        Addr::derive(GENESIS_SENDER, code_hash, b"dango/vesting")
+    };
+
+    let mut vesting_storage =
+        StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &vesting_address]);
+
+    let count = migrate_positions(&mut vesting_storage)?;
+
+    tracing::info!(count, "Migrated vesting positions");
+
+    Ok(())
+}
+
+/// Re-key every vesting position by user and position ID, assigning each
+/// a fresh ID. The positions were keyed by user alone, under the same
+/// namespace, so the legacy entries are removed first.
+fn migrate_positions(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy_positions = legacy_vesting::POSITIONS
+        .range(storage, None, None, IterationOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let count = legacy_positions.len();
+
+    for (user, _) in &legacy_positions {
+        legacy_vesting::POSITIONS.remove(storage, *user);
+    }
+
+    for (user, legacy_position) in legacy_positions {
+        let (id, _) = NEXT_POSITION_ID.increment(storage)?;
+        POSITIONS.save(storage, (user, id), &legacy_position.into())?;
+    }
+
+    Ok(count)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_math::Uint128,
+        dango_primitives::{Duration, MockStorage, Timestamp},
+        dango_types::constants::dango,
+        dango_types::vesting::{LinearSchedule, Position, Schedule, VestingStatus},
+    };
+
+    #[test]
+    fn migrating_positions_works() {
+        let mut storage = MockStorage::new();
+
+        let schedule = LinearSchedule {
+            start_time: Timestamp::from_seconds(100),
+            cliff: Duration::from_seconds(10),
+            period: Duration::from_seconds(1_000),
+        };
+
+        legacy_vesting::POSITIONS
+            .save(
+                &mut storage,
+                Addr::mock(1),
+                &legacy_vesting::LegacyPosition {
+                    vesting_status: legacy_vesting::LegacyVestingStatus::Active(schedule.clone()),
+                    total: Uint128::new(1_000),
+                    claimed: Uint128::new(100),
+                },
+            )
+            .unwrap();
+
+        legacy_vesting::POSITIONS
+            .save(
+                &mut storage,
+                Addr::mock(2),
+                &legacy_vesting::LegacyPosition {
+                    vesting_status: legacy_vesting::LegacyVestingStatus::Terminated(Uint128::new(
+                        500,
+                    )),
+                    total: Uint128::new(2_000),
+                    claimed: Uint128::new(200),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(migrate_positions(&mut storage).unwrap(), 2);
+
+        assert_eq!(
+            POSITIONS
+                .range(&storage, None, None, IterationOrder::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap(),
+            vec![
+                (
+                    (Addr::mock(1), 0),
+                    Position {
+                        denom: dango::DENOM.clone(),
+                        vesting_status: VestingStatus::Active(Schedule::Linear(schedule)),
+                        total: Uint128::new(1_000),
+                        claimed: Uint128::new(100),
+                    }
+                ),
+                (
+                    (Addr::mock(2), 1),
+                    Position {
+                        denom: dango::DENOM.clone(),
+                        vesting_status: VestingStatus::Terminated(Uint128::new(500)),
+                        total: Uint128::new(2_000),
+                        claimed: Uint128::new(200),
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(NEXT_POSITION_ID.current(&storage).unwrap(), 2);
+    }
+}
@@ -1,18 +1,17 @@
 use {
-    crate::{POSITIONS, UNLOCKING_SCHEDULE},
+    crate::{NEXT_POSITION_ID, POSITIONS, UNLOCKING_SCHEDULE},
     anyhow::{bail, ensure},
     dango_math::{IsZero, Number, NumberConst, Uint128},
     dango_primitives::{Addr, Coin, Message, MutableCtx, QuerierExt, Response},
-    dango_types::{
-        constants::dango,
-        vesting::{ExecuteMsg, InstantiateMsg, Position, Schedule, VestingStatus},
+    dango_types::vesting::{
+        ExecuteMsg, InstantiateMsg, LinearSchedule, Position, PositionId, Schedule, VestingStatus,
     },
 };
 
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
     UNLOCKING_SCHEDULE.save(
         ctx.storage,
-        &Schedule {
+        &LinearSchedule {
             // Unlocking start time is defined as the token generation time.
             // Since this contract is to be deployed at genesis, and the token is
             // generated also at genesis, the start time is simply the block time.
@@ -28,8 +27,8 @@ pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Respo
 pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
     match msg {
         ExecuteMsg::Create { user, schedule } => create(ctx, user, schedule),
-        ExecuteMsg::Terminate { user } => terminate(ctx, user),
-        ExecuteMsg::Claim {} => claim(ctx),
+        ExecuteMsg::Terminate { user, id } => terminate(ctx, user, id),
+        ExecuteMsg::Claim { id, recipient } => claim(ctx, id, recipient),
     }
 }
 
@@ -39,12 +38,26 @@ fn create(ctx: MutableCtx, user: Addr, schedule: Schedule) -> anyhow::Result<Res
         "you don't have the right, O you don't have the right"
     );
 
-    let coin = ctx.funds.into_one_coin_of_denom(&dango::DENOM)?;
+    if let Schedule::Step(schedule) = &schedule {
+        ensure!(
+            schedule.interval.into_nanos() > 0,
+            "step interval must be greater than zero"
+        );
+
+        ensure!(
+            schedule.num_steps > 0,
+            "number of steps must be greater than zero"
+        );
+    }
+
+    let coin = ctx.funds.into_one_coin()?;
+    let (id, _) = NEXT_POSITION_ID.increment(ctx.storage)?;
 
     POSITIONS.save(
         ctx.storage,
-        user,
+        (user, id),
         &Position {
+            denom: coin.denom,
             vesting_status: VestingStatus::Active(schedule),
             total: coin.amount,
             claimed: Uint128::ZERO,
@@ -54,7 +67,7 @@ fn create(ctx: MutableCtx, user: Addr, schedule: Schedule) -> anyhow::Result<Res
     Ok(Response::new())
 }
 
-fn terminate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
+fn terminate(ctx: MutableCtx, user: Addr, id: PositionId) -> anyhow::Result<Response> {
     let owner = ctx.querier.query_owner()?;
 
     ensure!(
@@ -62,7 +75,7 @@ fn terminate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
         "you don't have the right, O you don't have the right"
     );
 
-    let mut position = POSITIONS.load(ctx.storage, user)?;
+    let mut position = POSITIONS.load(ctx.storage, (user, id))?;
 
     let vested = if let VestingStatus::Active(schedule) = &position.vesting_status {
         schedule.compute_claimable(ctx.block.timestamp, position.total)?
@@ -77,20 +90,20 @@ fn terminate(ctx: MutableCtx, user: Addr) -> anyhow::Result<Response> {
     let refund_msg = if refund.is_non_zero() {
         Some(Message::transfer(
             owner,
-            Coin::new(dango::DENOM.clone(), refund)?,
+            Coin::new(position.denom.clone(), refund)?,
         )?)
     } else {
         None
     };
 
-    POSITIONS.save(ctx.storage, user, &position)?;
+    POSITIONS.save(ctx.storage, (user, id), &position)?;
 
     Ok(Response::new().may_add_message(refund_msg))
 }
 
-fn claim(ctx: MutableCtx) -> anyhow::Result<Response> {
+fn claim(ctx: MutableCtx, id: PositionId, recipient: Option<Addr>) -> anyhow::Result<Response> {
     let unlocking_schedule = UNLOCKING_SCHEDULE.load(ctx.storage)?;
-    let mut position = POSITIONS.load(ctx.storage, ctx.sender)?;
+    let mut position = POSITIONS.load(ctx.storage, (ctx.sender, id))?;
 
     let claimable = position.compute_claimable(ctx.block.timestamp, &unlocking_schedule)?;
 
@@ -98,10 +111,10 @@ fn claim(ctx: MutableCtx) -> anyhow::Result<Response> {
 
     position.claimed.checked_add_assign(claimable)?;
 
-    POSITIONS.save(ctx.storage, ctx.sender, &position)?;
+    POSITIONS.save(ctx.storage, (ctx.sender, id), &position)?;
 
     Ok(Response::new().add_message(Message::transfer(
-        ctx.sender,
-        Coin::new(dango::DENOM.clone(), claimable)?,
+        recipient.unwrap_or(ctx.sender),
+        Coin::new(position.denom, claimable)?,
     )?))
 }
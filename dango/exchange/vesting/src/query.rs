@@ -3,26 +3,30 @@ use {
     dango_primitives::{
         Addr, Bound, DEFAULT_PAGE_LIMIT, ImmutableCtx, Json, JsonSerExt, Order, StdResult,
     },
-    dango_types::vesting::{PositionResponse, QueryMsg},
+    dango_types::vesting::{PositionId, PositionResponse, QueryMsg},
     std::collections::BTreeMap,
 };
 
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
-        QueryMsg::Position { user } => {
-            let res = query_position(ctx, user)?;
+        QueryMsg::Position { user, id } => {
+            let res = query_position(ctx, user, id)?;
             res.to_json_value()
         },
-        QueryMsg::Positions { start_after, limit } => {
-            let res = query_positions(ctx, start_after, limit)?;
+        QueryMsg::Positions {
+            user,
+            start_after,
+            limit,
+        } => {
+            let res = query_positions(ctx, user, start_after, limit)?;
             res.to_json_value()
         },
     }
 }
 
-fn query_position(ctx: ImmutableCtx, user: Addr) -> StdResult<PositionResponse> {
+fn query_position(ctx: ImmutableCtx, user: Addr, id: PositionId) -> StdResult<PositionResponse> {
     let unlocking_schedule = UNLOCKING_SCHEDULE.load(ctx.storage)?;
-    let position = POSITIONS.load(ctx.storage, user)?;
+    let position = POSITIONS.load(ctx.storage, (user, id))?;
     let claimable = position.compute_claimable(ctx.block.timestamp, &unlocking_schedule)?;
 
     Ok(PositionResponse {
@@ -33,22 +37,24 @@ fn query_position(ctx: ImmutableCtx, user: Addr) -> StdResult<PositionResponse>
 
 fn query_positions(
     ctx: ImmutableCtx,
-    start_after: Option<Addr>,
+    user: Addr,
+    start_after: Option<PositionId>,
     limit: Option<u32>,
-) -> StdResult<BTreeMap<Addr, PositionResponse>> {
+) -> StdResult<BTreeMap<PositionId, PositionResponse>> {
     let start = start_after.map(Bound::Exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
     let unlocking_schedule = UNLOCKING_SCHEDULE.load(ctx.storage)?;
 
     POSITIONS
+        .prefix(user)
         .range(ctx.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|res| {
-            let (user, position) = res?;
+            let (id, position) = res?;
             let claimable = position.compute_claimable(ctx.block.timestamp, &unlocking_schedule)?;
 
             Ok((
-                user,
+                id,
                 PositionResponse {
                     position,
                     claimable,
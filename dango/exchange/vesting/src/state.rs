@@ -1,9 +1,11 @@
 use {
     dango_primitives::Addr,
-    dango_storage::{Item, Map},
-    dango_types::vesting::{Position, Schedule},
+    dango_storage::{Counter, Item, Map},
+    dango_types::vesting::{LinearSchedule, Position, PositionId},
 };
 
-pub const UNLOCKING_SCHEDULE: Item<Schedule> = Item::new("unlocking_schedule");
+pub const UNLOCKING_SCHEDULE: Item<LinearSchedule> = Item::new("unlocking_schedule");
 
-pub const POSITIONS: Map<Addr, Position> = Map::new("position");
+pub const NEXT_POSITION_ID: Counter<PositionId> = Counter::new("position_id", 0, 1);
+
+pub const POSITIONS: Map<(Addr, PositionId), Position> = Map::new("position");
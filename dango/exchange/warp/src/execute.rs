@@ -51,6 +51,8 @@ fn transfer_remote(
         bail!("incorrect remote type! expecting: warp, found: {remote:?}");
     };
 
+    // Forward the funds to the mailbox to pay for the post-dispatch hooks,
+    // i.e. the interchain gas payment.
     Ok(Response::new().add_message({
         let mailbox = MAILBOX.load(ctx.storage)?;
         Message::execute(
@@ -67,7 +69,7 @@ fn transfer_remote(
                 }
                 .encode(),
             },
-            Coins::new(),
+            ctx.funds,
         )?
     }))
 }
@@ -1,11 +1,24 @@
 use {
     crate::MAILBOX,
-    dango_hyperlane_types::recipients::{RecipientQuery, RecipientQueryResponse},
-    dango_primitives::{Addr, ImmutableCtx, Json, JsonSerExt, StdResult},
-    dango_types::warp::QueryMsg,
+    anyhow::bail,
+    dango_hyperlane_types::{
+        Addr32, mailbox,
+        recipients::{RecipientQuery, RecipientQueryResponse},
+    },
+    dango_math::Uint128,
+    dango_primitives::{
+        Addr, Coins, HexBinary, ImmutableCtx, Json, JsonSerExt, QuerierExt, StdResult,
+    },
+    dango_types::{
+        gateway::{
+            Remote,
+            bridge::{BridgeQuery, BridgeQueryResponse},
+        },
+        warp::{QueryMsg, TokenMessage},
+    },
 };
 
-pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
     match msg {
         QueryMsg::Mailbox {} => {
             let res = query_mailbox(ctx)?;
@@ -16,7 +29,17 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             let res = RecipientQueryResponse::InterchainSecurityModule(ism);
             res.to_json_value()
         },
+        QueryMsg::Bridge(BridgeQuery::QuoteTransferRemote {
+            remote,
+            amount,
+            recipient,
+        }) => {
+            let fee = query_quote_transfer_remote(ctx, remote, amount, recipient)?;
+            let res = BridgeQueryResponse::QuoteTransferRemote(fee);
+            res.to_json_value()
+        },
     }
+    .map_err(Into::into)
 }
 
 fn query_mailbox(ctx: ImmutableCtx) -> StdResult<Addr> {
@@ -28,3 +51,34 @@ fn query_interchain_security_module(_ctx: ImmutableCtx) -> Option<Addr> {
     // Currently we just use the default ISM.
     None
 }
+
+/// The fee of a remote transfer is the fee the mailbox charges for
+/// dispatching the token message, i.e. the interchain gas payment.
+fn query_quote_transfer_remote(
+    ctx: ImmutableCtx,
+    remote: Remote,
+    amount: Uint128,
+    recipient: Addr32,
+) -> anyhow::Result<Coins> {
+    let Remote::Warp { domain, contract } = remote else {
+        bail!("incorrect remote type! expecting: warp, found: {remote:?}");
+    };
+
+    let mailbox = MAILBOX.load(ctx.storage)?;
+
+    Ok(ctx.querier.query_wasm_smart(
+        mailbox,
+        mailbox::QueryQuoteDispatchRequest {
+            sender: ctx.contract,
+            destination_domain: domain,
+            recipient: contract,
+            body: TokenMessage {
+                recipient,
+                amount,
+                // Metadata isn't supported at this time.
+                metadata: HexBinary::default(),
+            }
+            .encode(),
+        },
+    )?)
+}
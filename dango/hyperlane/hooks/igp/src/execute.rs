@@ -0,0 +1,276 @@
+use {
+    crate::{CONFIG, DESTINATION_GAS_CONFIGS, quote_gas_payment},
+    anyhow::{anyhow, ensure},
+    dango_hyperlane_types::{
+        hooks::{
+            HookMsg,
+            igp::{
+                Claim, Config, DEFAULT_GAS_LIMIT, DestinationGasConfig, ExecuteMsg, GasPayment,
+                InstantiateMsg,
+            },
+        },
+        mailbox::{Domain, Message},
+    },
+    dango_math::IsZero,
+    dango_primitives::{Addr, Hash, HexBinary, MutableCtx, QuerierExt, Response, StdResult, coins},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
+    CONFIG.save(
+        ctx.storage,
+        &Config {
+            mailbox: msg.mailbox,
+            fee_denom: msg.fee_denom,
+            beneficiary: msg.beneficiary,
+        },
+    )?;
+
+    for (domain, gas_config) in msg.destination_gas_configs {
+        DESTINATION_GAS_CONFIGS.save(ctx.storage, domain, &gas_config)?;
+    }
+
+    Ok(Response::new())
+}
+
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::SetDestinationGasConfigs(gas_configs) => {
+            set_destination_gas_configs(ctx, gas_configs)
+        },
+        ExecuteMsg::RemoveDestinationGasConfigs(domains) => {
+            remove_destination_gas_configs(ctx, domains)
+        },
+        ExecuteMsg::SetBeneficiary { beneficiary } => set_beneficiary(ctx, beneficiary),
+        ExecuteMsg::Claim {} => claim(ctx),
+        ExecuteMsg::Hook(HookMsg::PostDispatch { raw_message }) => post_dispatch(ctx, raw_message),
+    }
+}
+
+#[inline]
+fn set_destination_gas_configs(
+    ctx: MutableCtx,
+    gas_configs: BTreeMap<Domain, DestinationGasConfig>,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_destination_gas_configs`"
+    );
+
+    for (domain, gas_config) in gas_configs {
+        DESTINATION_GAS_CONFIGS.save(ctx.storage, domain, &gas_config)?;
+    }
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn remove_destination_gas_configs(
+    ctx: MutableCtx,
+    domains: BTreeSet<Domain>,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `remove_destination_gas_configs`"
+    );
+
+    for domain in domains {
+        DESTINATION_GAS_CONFIGS.remove(ctx.storage, domain);
+    }
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn set_beneficiary(ctx: MutableCtx, beneficiary: Addr) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_beneficiary`"
+    );
+
+    CONFIG.update(ctx.storage, |mut cfg| -> StdResult<_> {
+        cfg.beneficiary = beneficiary;
+        Ok(cfg)
+    })?;
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn claim(ctx: MutableCtx) -> anyhow::Result<Response> {
+    let cfg = CONFIG.load(ctx.storage)?;
+    let balance = ctx
+        .querier
+        .query_balance(ctx.contract, cfg.fee_denom.clone())?;
+
+    ensure!(balance.is_non_zero(), "no fee to claim");
+
+    let amount = coins! { cfg.fee_denom => balance };
+
+    Ok(Response::new()
+        .add_message(dango_primitives::Message::transfer(
+            cfg.beneficiary,
+            amount.clone(),
+        )?)
+        .add_event(Claim {
+            beneficiary: cfg.beneficiary,
+            amount,
+        })?)
+}
+
+#[inline]
+fn post_dispatch(ctx: MutableCtx, raw_message: HexBinary) -> anyhow::Result<Response> {
+    let cfg = CONFIG.load(ctx.storage)?;
+
+    ensure!(
+        ctx.sender == cfg.mailbox,
+        "only mailbox can call `post_dispatch`"
+    );
+
+    let message = Message::decode(&raw_message)?;
+    let message_id = Hash::from_inner(ctx.api.keccak256(&raw_message));
+    let payment = quote_gas_payment(ctx.storage, message.destination_domain, DEFAULT_GAS_LIMIT)?;
+
+    // Keep the quoted payment; refund the excess to the mailbox, which
+    // forwards exactly the quote, so there shouldn't be any in practice.
+    let mut excess = ctx.funds;
+    excess.deduct_many(payment.clone()).map_err(|err| {
+        anyhow!("insufficient interchain gas payment! required: {payment}, reason: {err}")
+    })?;
+
+    Ok(Response::new()
+        .may_add_message(if excess.is_non_empty() {
+            Some(dango_primitives::Message::transfer(ctx.sender, excess)?)
+        } else {
+            None
+        })
+        .may_add_event(if payment.is_non_empty() {
+            Some(GasPayment {
+                message_id,
+                destination_domain: message.destination_domain,
+                gas_limit: DEFAULT_GAS_LIMIT,
+                payment,
+            })
+        } else {
+            None
+        })?)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_hyperlane_types::{
+            Addr32,
+            hooks::igp::GasOracle,
+            mailbox::{MAILBOX_VERSION, Message},
+        },
+        dango_math::{Udec128, Uint128},
+        dango_primitives::{Coins, Denom, MockContext, MockStorage, ResultExt},
+    };
+
+    const MAILBOX: Addr = Addr::mock(1);
+    const BENEFICIARY: Addr = Addr::mock(2);
+    const CONFIGURED_DOMAIN: Domain = 123;
+    const UNCONFIGURED_DOMAIN: Domain = 456;
+
+    fn fee_denom() -> Denom {
+        Denom::new_unchecked(["ugas"])
+    }
+
+    fn mock_raw_message(destination_domain: Domain) -> HexBinary {
+        Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain: 88888888,
+            sender: Addr32::from(Addr::mock(3)),
+            destination_domain,
+            recipient: Addr32::from(Addr::mock(4)),
+            body: HexBinary::default(),
+        }
+        .encode()
+    }
+
+    fn mock_storage() -> MockStorage {
+        let mut storage = MockStorage::new();
+
+        CONFIG
+            .save(
+                &mut storage,
+                &Config {
+                    mailbox: MAILBOX,
+                    fee_denom: fee_denom(),
+                    beneficiary: BENEFICIARY,
+                },
+            )
+            .unwrap();
+
+        DESTINATION_GAS_CONFIGS
+            .save(
+                &mut storage,
+                CONFIGURED_DOMAIN,
+                &DestinationGasConfig {
+                    gas_oracle: GasOracle {
+                        token_exchange_rate: Udec128::new_percent(50),
+                        gas_price: Uint128::new(3),
+                    },
+                    gas_overhead: Uint128::new(10_000),
+                },
+            )
+            .unwrap();
+
+        storage
+    }
+
+    #[test]
+    fn quoting_gas_payment() {
+        let storage = mock_storage();
+
+        // (50,000 default gas limit + 10,000 overhead) * 3 gas price * 0.5
+        quote_gas_payment(&storage, CONFIGURED_DOMAIN, DEFAULT_GAS_LIMIT)
+            .should_succeed_and_equal(coins! { fee_denom() => 90_000 });
+
+        // A domain without a gas config isn't charged.
+        quote_gas_payment(&storage, UNCONFIGURED_DOMAIN, DEFAULT_GAS_LIMIT)
+            .should_succeed_and_equal(Coins::new());
+    }
+
+    #[test]
+    fn paying_for_gas() {
+        // Exactly the quote: nothing is refunded.
+        let mut ctx = MockContext::new()
+            .with_storage(mock_storage())
+            .with_sender(MAILBOX)
+            .with_funds(coins! { fee_denom() => 90_000 });
+        let res =
+            post_dispatch(ctx.as_mutable(), mock_raw_message(CONFIGURED_DOMAIN)).should_succeed();
+        assert!(res.submsgs.is_empty());
+
+        // Less than the quote.
+        let mut ctx = ctx.with_funds(coins! { fee_denom() => 89_999 });
+        post_dispatch(ctx.as_mutable(), mock_raw_message(CONFIGURED_DOMAIN))
+            .should_fail_with_error("insufficient interchain gas payment");
+
+        // More than the quote: the excess is refunded.
+        let mut ctx = ctx.with_funds(coins! { fee_denom() => 100_000 });
+        let res =
+            post_dispatch(ctx.as_mutable(), mock_raw_message(CONFIGURED_DOMAIN)).should_succeed();
+        assert_eq!(res.submsgs.len(), 1);
+
+        // A domain without a gas config: nothing is charged.
+        let mut ctx = ctx.with_funds(Coins::new());
+        post_dispatch(ctx.as_mutable(), mock_raw_message(UNCONFIGURED_DOMAIN)).should_succeed();
+    }
+
+    #[test]
+    fn only_mailbox_can_post_dispatch() {
+        let mut ctx = MockContext::new()
+            .with_storage(mock_storage())
+            .with_sender(Addr::mock(5))
+            .with_funds(coins! { fee_denom() => 90_000 });
+        post_dispatch(ctx.as_mutable(), mock_raw_message(CONFIGURED_DOMAIN))
+            .should_fail_with_error("only mailbox can call `post_dispatch`");
+    }
+}
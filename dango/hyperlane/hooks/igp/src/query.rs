@@ -0,0 +1,104 @@
+use {
+    crate::{CONFIG, DESTINATION_GAS_CONFIGS},
+    dango_hyperlane_types::{
+        hooks::{
+            HookQuery, HookQueryResponse,
+            igp::{Config, DEFAULT_GAS_LIMIT, DestinationGasConfig, QueryMsg},
+        },
+        mailbox::{Domain, Message},
+    },
+    dango_math::Uint128,
+    dango_primitives::{
+        Bound, Coin, Coins, DEFAULT_PAGE_LIMIT, ImmutableCtx, Json, JsonSerExt, Order, StdResult,
+        Storage,
+    },
+    std::collections::BTreeMap,
+};
+
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
+    match msg {
+        QueryMsg::Config {} => {
+            let res = query_config(ctx)?;
+            res.to_json_value()
+        },
+        QueryMsg::DestinationGasConfig { domain } => {
+            let res = query_destination_gas_config(ctx, domain)?;
+            res.to_json_value()
+        },
+        QueryMsg::DestinationGasConfigs { start_after, limit } => {
+            let res = query_destination_gas_configs(ctx, start_after, limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::QuoteGasPayment {
+            destination_domain,
+            gas_limit,
+        } => {
+            let res = quote_gas_payment(ctx.storage, destination_domain, gas_limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::Hook(HookQuery::QuoteDispatch { raw_message }) => {
+            let message = Message::decode(&raw_message)?;
+            let res = HookQueryResponse::QuoteDispatch(quote_gas_payment(
+                ctx.storage,
+                message.destination_domain,
+                DEFAULT_GAS_LIMIT,
+            )?);
+            res.to_json_value()
+        },
+    }
+    .map_err(Into::into)
+}
+
+#[inline]
+fn query_config(ctx: ImmutableCtx) -> StdResult<Config> {
+    CONFIG.load(ctx.storage)
+}
+
+#[inline]
+fn query_destination_gas_config(
+    ctx: ImmutableCtx,
+    domain: Domain,
+) -> StdResult<Option<DestinationGasConfig>> {
+    DESTINATION_GAS_CONFIGS.may_load(ctx.storage, domain)
+}
+
+#[inline]
+fn query_destination_gas_configs(
+    ctx: ImmutableCtx,
+    start_after: Option<Domain>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Domain, DestinationGasConfig>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+
+    DESTINATION_GAS_CONFIGS
+        .range(ctx.storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect()
+}
+
+/// Quote the payment for delivering a message that uses `gas_limit` gas on
+/// the destination domain.
+///
+/// Messages to a domain without a gas config aren't charged, so that a
+/// domain can be relayed for free (e.g. paid off-chain) simply by not
+/// configuring it.
+pub(crate) fn quote_gas_payment(
+    storage: &dyn Storage,
+    destination_domain: Domain,
+    gas_limit: Uint128,
+) -> StdResult<Coins> {
+    let Some(gas_config) = DESTINATION_GAS_CONFIGS.may_load(storage, destination_domain)? else {
+        return Ok(Coins::new());
+    };
+
+    let cfg = CONFIG.load(storage)?;
+    let mut payment = Coins::new();
+
+    payment.insert(Coin {
+        denom: cfg.fee_denom,
+        amount: gas_config.quote(gas_limit)?,
+    })?;
+
+    Ok(payment)
+}
@@ -0,0 +1,12 @@
+use {
+    dango_hyperlane_types::{
+        hooks::igp::{Config, DestinationGasConfig},
+        mailbox::Domain,
+    },
+    dango_storage::{Item, Map},
+};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const DESTINATION_GAS_CONFIGS: Map<Domain, DestinationGasConfig> =
+    Map::new("destination_gas_config");
@@ -0,0 +1,156 @@
+use {
+    crate::ISM_SET,
+    anyhow::ensure,
+    dango_hyperlane_types::isms::aggregation::{ExecuteMsg, InstantiateMsg, IsmSet},
+    dango_primitives::{Addr, MutableCtx, QuerierExt, Response},
+    std::collections::BTreeSet,
+};
+
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+    validate_ism_set(ctx.contract, &msg.ism_set)?;
+
+    ISM_SET.save(ctx.storage, &msg.ism_set)?;
+
+    Ok(Response::new())
+}
+
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::SetIsms { threshold, isms } => set_isms(ctx, threshold, isms),
+    }
+}
+
+#[inline]
+fn set_isms(ctx: MutableCtx, threshold: u32, isms: Vec<Addr>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_isms`"
+    );
+
+    let ism_set = IsmSet { threshold, isms };
+
+    validate_ism_set(ctx.contract, &ism_set)?;
+
+    ISM_SET.save(ctx.storage, &ism_set)?;
+
+    Ok(Response::new())
+}
+
+fn validate_ism_set(contract: Addr, ism_set: &IsmSet) -> anyhow::Result<()> {
+    ensure!(ism_set.threshold > 0, "threshold must be greater than zero");
+
+    ensure!(
+        ism_set.isms.len() >= ism_set.threshold as usize,
+        "not enough ISMs! threshold: {}, ISMs: {}",
+        ism_set.threshold,
+        ism_set.isms.len()
+    );
+
+    // A duplicate ISM would verify the same message twice, counting twice
+    // towards the threshold.
+    let mut seen = BTreeSet::new();
+    for ism in &ism_set.isms {
+        ensure!(seen.insert(ism), "duplicate ISM: {ism}");
+    }
+
+    // The contract querying itself would recurse until running out of gas.
+    ensure!(
+        !seen.contains(&contract),
+        "aggregation ISM can't include itself"
+    );
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_primitives::{
+            Coins, Config, Duration, MockContext, MockQuerier, Permission, Permissions, ResultExt,
+        },
+        std::collections::BTreeMap,
+    };
+
+    const OWNER: Addr = Addr::mock(1);
+    const ISM_1: Addr = Addr::mock(2);
+    const ISM_2: Addr = Addr::mock(3);
+
+    fn mock_config() -> Config {
+        Config {
+            owner: OWNER,
+            bank: Addr::mock(10),
+            gas_token: dango_primitives::Denom::new_unchecked(["ugas"]),
+            gas_fee_rate: Default::default(),
+            gas_exemptions: Default::default(),
+            cronjobs: BTreeMap::new(),
+            permissions: Permissions {
+                upload: Permission::Everybody,
+                instantiate: Permission::Everybody,
+            },
+            max_orphan_age: Duration::from_seconds(3600),
+        }
+    }
+
+    #[test]
+    fn instantiate_rejects_invalid_ism_sets() {
+        let mut ctx = MockContext::new()
+            .with_sender(OWNER)
+            .with_funds(Coins::default());
+
+        for (threshold, isms, error) in [
+            (0, vec![ISM_1], "threshold must be greater than zero"),
+            (2, vec![ISM_1], "not enough ISMs! threshold: 2, ISMs: 1"),
+            (2, vec![ISM_1, ISM_1], "duplicate ISM"),
+            (
+                1,
+                vec![ISM_1, ctx.contract],
+                "aggregation ISM can't include itself",
+            ),
+        ] {
+            instantiate(
+                ctx.as_mutable(),
+                InstantiateMsg {
+                    ism_set: IsmSet { threshold, isms },
+                },
+            )
+            .should_fail_with_error(error);
+        }
+
+        instantiate(
+            ctx.as_mutable(),
+            InstantiateMsg {
+                ism_set: IsmSet {
+                    threshold: 2,
+                    isms: vec![ISM_1, ISM_2],
+                },
+            },
+        )
+        .should_succeed();
+    }
+
+    #[test]
+    fn only_owner_can_set_isms() {
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(Addr::mock(99))
+            .with_funds(Coins::default());
+
+        set_isms(ctx.as_mutable(), 1, vec![ISM_1])
+            .should_fail_with_error("only the chain owner can call `set_isms`");
+
+        let mut ctx = MockContext::new()
+            .with_querier(MockQuerier::new().with_config(mock_config()))
+            .with_sender(OWNER)
+            .with_funds(Coins::default());
+
+        set_isms(ctx.as_mutable(), 1, vec![ISM_1, ISM_2]).should_succeed();
+
+        ISM_SET.load(&ctx.storage).should_succeed_and_equal(IsmSet {
+            threshold: 1,
+            isms: vec![ISM_1, ISM_2],
+        });
+    }
+}
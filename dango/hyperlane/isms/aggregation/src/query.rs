@@ -0,0 +1,186 @@
+use {
+    crate::ISM_SET,
+    anyhow::{anyhow, ensure},
+    dango_hyperlane_types::isms::{
+        IsmQuery, IsmQueryResponse, QueryIsmRequest,
+        aggregation::{IsmSet, Metadata, QueryMsg},
+    },
+    dango_primitives::{HexBinary, ImmutableCtx, Json, JsonSerExt, QuerierExt, StdResult},
+};
+
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
+    match msg {
+        QueryMsg::IsmSet {} => {
+            let res = query_ism_set(ctx)?;
+            res.to_json_value()
+        },
+        QueryMsg::Ism(IsmQuery::Verify {
+            raw_message,
+            raw_metadata,
+        }) => {
+            let res = IsmQueryResponse::Verify(verify(ctx, raw_message, &raw_metadata)?);
+            res.to_json_value()
+        },
+    }
+    .map_err(Into::into)
+}
+
+#[inline]
+fn query_ism_set(ctx: ImmutableCtx) -> StdResult<IsmSet> {
+    ISM_SET.load(ctx.storage)
+}
+
+fn verify(ctx: ImmutableCtx, raw_message: HexBinary, raw_metadata: &[u8]) -> anyhow::Result<()> {
+    let ism_set = ISM_SET.load(ctx.storage)?;
+    let metadata = Metadata::decode(raw_metadata, ism_set.isms.len())?;
+
+    // Query the ISMs for which metadata is provided, until enough of them have
+    // verified the message. Any of them failing fails the verification.
+    let mut verified = 0;
+
+    for (ism, raw_metadata) in ism_set.isms.into_iter().zip(metadata.metadatas) {
+        if verified >= ism_set.threshold {
+            break;
+        }
+
+        let Some(raw_metadata) = raw_metadata else {
+            continue;
+        };
+
+        ctx.querier
+            .query_wasm_smart(
+                ism,
+                QueryIsmRequest(IsmQuery::Verify {
+                    raw_message: raw_message.clone(),
+                    raw_metadata,
+                }),
+            )
+            .map(|res| res.into_verify())
+            .map_err(|err| anyhow!("ISM {ism} failed to verify the message: {err}"))?;
+
+        verified += 1;
+    }
+
+    ensure!(
+        verified >= ism_set.threshold,
+        "not enough ISMs verified the message! threshold: {}, verified: {}",
+        ism_set.threshold,
+        verified
+    );
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_primitives::{
+            Addr, GenericResultExt, JsonDeExt, MockContext, MockQuerier, ResultExt,
+        },
+        std::{cell::RefCell, rc::Rc},
+        test_case::test_case,
+    };
+
+    const ISM_1: Addr = Addr::mock(1);
+    const ISM_2: Addr = Addr::mock(2);
+    const ISM_3: Addr = Addr::mock(3);
+
+    /// The mock sub-ISMs accept the metadata `b"ok"` and reject anything else.
+    /// The ISMs that have been queried are recorded.
+    fn mock_querier(queried: Rc<RefCell<Vec<Addr>>>) -> MockQuerier {
+        MockQuerier::new().with_smart_query_handler(move |addr, msg| {
+            queried.borrow_mut().push(addr);
+
+            let QueryMsg::Ism(IsmQuery::Verify { raw_metadata, .. }) =
+                msg.deserialize_json().unwrap()
+            else {
+                panic!("unexpected query to {addr}");
+            };
+
+            if raw_metadata.as_ref() != b"ok" {
+                return Err(anyhow!("invalid signature")).into_generic_result();
+            }
+
+            IsmQueryResponse::Verify(())
+                .to_json_value()
+                .into_generic_result()
+        })
+    }
+
+    fn ok() -> Option<HexBinary> {
+        Some(b"ok".to_vec().into())
+    }
+
+    fn bad() -> Option<HexBinary> {
+        Some(b"bad".to_vec().into())
+    }
+
+    #[test_case(
+        vec![ok(), ok(), None],
+        vec![ISM_1, ISM_2],
+        None;
+        "first two verify"
+    )]
+    #[test_case(
+        vec![None, ok(), ok()],
+        vec![ISM_2, ISM_3],
+        None;
+        "last two verify"
+    )]
+    #[test_case(
+        vec![ok(), ok(), bad()],
+        vec![ISM_1, ISM_2],
+        None;
+        "threshold reached before the bad one"
+    )]
+    #[test_case(
+        vec![ok(), None, None],
+        vec![ISM_1],
+        Some("not enough ISMs verified the message! threshold: 2, verified: 1");
+        "not enough metadata"
+    )]
+    #[test_case(
+        vec![bad(), ok(), ok()],
+        vec![ISM_1],
+        Some("failed to verify the message");
+        "a sub-ISM rejects"
+    )]
+    fn verifying(
+        metadatas: Vec<Option<HexBinary>>,
+        expected_queried: Vec<Addr>,
+        expected_error: Option<&str>,
+    ) {
+        let queried = Rc::new(RefCell::new(Vec::new()));
+        let mut ctx = MockContext::new().with_querier(mock_querier(queried.clone()));
+
+        ISM_SET
+            .save(
+                &mut ctx.storage,
+                &IsmSet {
+                    threshold: 2,
+                    isms: vec![ISM_1, ISM_2, ISM_3],
+                },
+            )
+            .unwrap();
+
+        let res = verify(
+            ctx.as_immutable(),
+            b"message".to_vec().into(),
+            &Metadata { metadatas }.encode(),
+        );
+
+        match expected_error {
+            Some(error) => {
+                res.should_fail_with_error(error);
+            },
+            None => {
+                res.should_succeed();
+            },
+        }
+
+        assert_eq!(*queried.borrow(), expected_queried);
+    }
+}
@@ -0,0 +1,3 @@
+use {dango_hyperlane_types::isms::aggregation::IsmSet, dango_storage::Item};
+
+pub const ISM_SET: Item<IsmSet> = Item::new("ism_set");
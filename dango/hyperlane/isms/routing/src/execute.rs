@@ -0,0 +1,82 @@
+use {
+    crate::{DEFAULT_ISM, ROUTES},
+    anyhow::ensure,
+    dango_hyperlane_types::{
+        isms::routing::{ExecuteMsg, InstantiateMsg},
+        mailbox::Domain,
+    },
+    dango_primitives::{Addr, MutableCtx, QuerierExt, Response},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+    for (domain, ism) in msg.routes {
+        ensure!(ism != ctx.contract, "routing ISM can't route to itself");
+
+        ROUTES.save(ctx.storage, domain, &ism)?;
+    }
+
+    ensure!(
+        msg.default_ism != Some(ctx.contract),
+        "routing ISM can't route to itself"
+    );
+
+    DEFAULT_ISM.save(ctx.storage, &msg.default_ism)?;
+
+    Ok(Response::new())
+}
+
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::SetRoutes(routes) => set_routes(ctx, routes),
+        ExecuteMsg::RemoveRoutes(domains) => remove_routes(ctx, domains),
+        ExecuteMsg::SetDefaultIsm { ism } => set_default_ism(ctx, ism),
+    }
+}
+
+#[inline]
+fn set_routes(ctx: MutableCtx, routes: BTreeMap<Domain, Addr>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_routes`"
+    );
+
+    for (domain, ism) in routes {
+        ensure!(ism != ctx.contract, "routing ISM can't route to itself");
+
+        ROUTES.save(ctx.storage, domain, &ism)?;
+    }
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn remove_routes(ctx: MutableCtx, domains: BTreeSet<Domain>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `remove_routes`"
+    );
+
+    for domain in domains {
+        ROUTES.remove(ctx.storage, domain);
+    }
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn set_default_ism(ctx: MutableCtx, ism: Option<Addr>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_default_ism`"
+    );
+
+    ensure!(
+        ism != Some(ctx.contract),
+        "routing ISM can't route to itself"
+    );
+
+    DEFAULT_ISM.save(ctx.storage, &ism)?;
+
+    Ok(Response::new())
+}
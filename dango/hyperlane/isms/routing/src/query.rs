@@ -0,0 +1,172 @@
+use {
+    crate::{DEFAULT_ISM, ROUTES},
+    anyhow::{anyhow, bail},
+    dango_hyperlane_types::{
+        isms::{IsmQuery, IsmQueryResponse, QueryIsmRequest, routing::QueryMsg},
+        mailbox::{Domain, Message},
+    },
+    dango_primitives::{
+        Addr, Bound, DEFAULT_PAGE_LIMIT, HexBinary, ImmutableCtx, Json, JsonSerExt, Order,
+        QuerierExt, StdResult, Storage,
+    },
+    std::collections::BTreeMap,
+};
+
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
+    match msg {
+        QueryMsg::DefaultIsm {} => {
+            let res = query_default_ism(ctx)?;
+            res.to_json_value()
+        },
+        QueryMsg::Route { domain } => {
+            let res = route(ctx.storage, domain)?;
+            res.to_json_value()
+        },
+        QueryMsg::Routes { start_after, limit } => {
+            let res = query_routes(ctx, start_after, limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::Ism(IsmQuery::Verify {
+            raw_message,
+            raw_metadata,
+        }) => {
+            let res = IsmQueryResponse::Verify(verify(ctx, raw_message, raw_metadata)?);
+            res.to_json_value()
+        },
+    }
+    .map_err(Into::into)
+}
+
+#[inline]
+fn query_default_ism(ctx: ImmutableCtx) -> StdResult<Option<Addr>> {
+    DEFAULT_ISM.load(ctx.storage)
+}
+
+#[inline]
+fn query_routes(
+    ctx: ImmutableCtx,
+    start_after: Option<Domain>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Domain, Addr>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+
+    ROUTES
+        .range(ctx.storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect()
+}
+
+/// Find the ISM for the origin domain, falling back to the default ISM.
+fn route(storage: &dyn Storage, domain: Domain) -> anyhow::Result<Addr> {
+    if let Some(ism) = ROUTES.may_load(storage, domain)? {
+        return Ok(ism);
+    }
+
+    let Some(ism) = DEFAULT_ISM.load(storage)? else {
+        bail!("no ISM for origin domain {domain}");
+    };
+
+    Ok(ism)
+}
+
+fn verify(
+    ctx: ImmutableCtx,
+    raw_message: HexBinary,
+    raw_metadata: HexBinary,
+) -> anyhow::Result<()> {
+    let message = Message::decode(&raw_message)?;
+    let ism = route(ctx.storage, message.origin_domain)?;
+
+    // The metadata is passed through as is, in the format of the routed ISM.
+    ctx.querier
+        .query_wasm_smart(
+            ism,
+            QueryIsmRequest(IsmQuery::Verify {
+                raw_message,
+                raw_metadata,
+            }),
+        )
+        .map(|res| res.into_verify())
+        .map_err(|err| anyhow!("ISM {ism} failed to verify the message: {err}"))
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_hyperlane_types::{addr32, mailbox::MAILBOX_VERSION},
+        dango_primitives::{GenericResultExt, JsonDeExt, MockContext, MockQuerier, ResultExt},
+        test_case::test_case,
+    };
+
+    const ISM_1: Addr = Addr::mock(1);
+    const ISM_2: Addr = Addr::mock(2);
+    const DEFAULT: Addr = Addr::mock(3);
+
+    fn mock_message(origin_domain: Domain) -> HexBinary {
+        Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain,
+            sender: addr32!("0000000000000000000000000000000000000000000000000000000000000000"),
+            destination_domain: 88888888,
+            recipient: addr32!("0000000000000000000000000000000000000000000000000000000000000000"),
+            body: Vec::new().into(),
+        }
+        .encode()
+    }
+
+    /// The mock ISMs only accept the metadata being their own address.
+    fn mock_querier() -> MockQuerier {
+        MockQuerier::new().with_smart_query_handler(|addr, msg| {
+            let raw_metadata = match msg.deserialize_json() {
+                Ok(QueryMsg::Ism(IsmQuery::Verify { raw_metadata, .. })) => raw_metadata,
+                _ => panic!("unexpected query to {addr}"),
+            };
+
+            if raw_metadata.as_ref() != addr.as_ref() {
+                return Err(anyhow!("invalid signature")).into_generic_result();
+            }
+
+            IsmQueryResponse::Verify(())
+                .to_json_value()
+                .into_generic_result()
+        })
+    }
+
+    #[test_case(1, Some(DEFAULT), ISM_1, None; "routed")]
+    #[test_case(2, Some(DEFAULT), ISM_2, None; "routed to another ISM")]
+    #[test_case(3, Some(DEFAULT), DEFAULT, None; "falling back to default")]
+    #[test_case(1, Some(DEFAULT), DEFAULT, Some("failed to verify the message"); "wrong ISM")]
+    #[test_case(3, None, DEFAULT, Some("no ISM for origin domain 3"); "no route no default")]
+    fn verifying(
+        origin_domain: Domain,
+        default_ism: Option<Addr>,
+        signer: Addr,
+        expected_error: Option<&str>,
+    ) {
+        let mut ctx = MockContext::new().with_querier(mock_querier());
+
+        ROUTES.save(&mut ctx.storage, 1, &ISM_1).unwrap();
+        ROUTES.save(&mut ctx.storage, 2, &ISM_2).unwrap();
+        DEFAULT_ISM.save(&mut ctx.storage, &default_ism).unwrap();
+
+        let res = verify(
+            ctx.as_immutable(),
+            mock_message(origin_domain),
+            signer.to_vec().into(),
+        );
+
+        match expected_error {
+            Some(error) => {
+                res.should_fail_with_error(error);
+            },
+            None => {
+                res.should_succeed();
+            },
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use {
+    dango_hyperlane_types::mailbox::Domain,
+    dango_primitives::Addr,
+    dango_storage::{Item, Map},
+};
+
+pub const DEFAULT_ISM: Item<Option<Addr>> = Item::new("default_ism");
+
+pub const ROUTES: Map<Domain, Addr> = Map::new("route");
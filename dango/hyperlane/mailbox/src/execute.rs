@@ -1,20 +1,24 @@
 use {
-    crate::{CONFIG, DELIVERIES, MERKLE_TREE, NONCE},
+    crate::{CONFIG, DELIVERIES, HOOKS, MERKLE_TREE, NONCE},
     anyhow::{anyhow, ensure},
     dango_hyperlane_types::{
         Addr32, IncrementalMerkleTree,
+        hooks::{self, HookMsg, HookQuery, QueryHookRequest},
         isms::{IsmQuery, QueryIsmRequest},
         mailbox::{
-            Dispatch, DispatchId, DispatchV2, Domain, ExecuteMsg, InsertedIntoTree, InstantiateMsg,
-            MAILBOX_VERSION, Message, PostDispatch, Process, ProcessId,
+            Dispatch, DispatchId, DispatchV2, Domain, ExecuteMsg, Hooks, InsertedIntoTree,
+            InstantiateMsg, MAILBOX_VERSION, Message, PostDispatch, Process, ProcessId,
         },
         recipients::{self, QueryRecipientRequest, RecipientMsg, RecipientQuery},
     },
-    dango_primitives::{Coins, Hash, HexBinary, MutableCtx, QuerierExt, Response, StdResult},
+    dango_primitives::{
+        Addr, Coins, Hash, HexBinary, MutableCtx, QuerierExt, QuerierWrapper, Response, StdResult,
+    },
 };
 
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
     CONFIG.save(ctx.storage, &msg.config)?;
+    HOOKS.save(ctx.storage, &msg.hooks)?;
     MERKLE_TREE.save(ctx.storage, &IncrementalMerkleTree::default())?;
 
     Ok(Response::new())
@@ -31,9 +35,41 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             raw_message,
             raw_metadata,
         } => process(ctx, raw_message, raw_metadata),
+        ExecuteMsg::SetRequiredHook { hook } => set_required_hook(ctx, hook),
+        ExecuteMsg::SetDefaultHook { hook } => set_default_hook(ctx, hook),
     }
 }
 
+#[inline]
+fn set_required_hook(ctx: MutableCtx, hook: Option<Addr>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_required_hook`"
+    );
+
+    let mut hooks = HOOKS.may_load(ctx.storage)?.unwrap_or_default();
+    hooks.required = hook;
+
+    HOOKS.save(ctx.storage, &hooks)?;
+
+    Ok(Response::new())
+}
+
+#[inline]
+fn set_default_hook(ctx: MutableCtx, hook: Option<Addr>) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "only the chain owner can call `set_default_hook`"
+    );
+
+    let mut hooks = HOOKS.may_load(ctx.storage)?.unwrap_or_default();
+    hooks.default = hook;
+
+    HOOKS.save(ctx.storage, &hooks)?;
+
+    Ok(Response::new())
+}
+
 #[inline]
 fn dispatch(
     ctx: MutableCtx,
@@ -72,7 +108,35 @@ fn dispatch(
 
     let root = tree.root();
 
+    // Call the post-dispatch hooks, each with the fee it quotes. The funds
+    // sent along must cover the sum of the fees; the excess is refunded.
+    let hooks = HOOKS.may_load(ctx.storage)?.unwrap_or_default();
+    let mut funds = ctx.funds;
+    let mut hook_msgs = Vec::new();
+
+    for (hook, fee) in quote_hooks(ctx.querier, hooks, &raw_message)? {
+        funds.deduct_many(fee.clone()).map_err(|err| {
+            anyhow!(
+                "insufficient fee for post-dispatch hook {hook}! required: {fee}, reason: {err}"
+            )
+        })?;
+
+        hook_msgs.push(dango_primitives::Message::execute(
+            hook,
+            &hooks::ExecuteMsg::Hook(HookMsg::PostDispatch {
+                raw_message: raw_message.clone(),
+            }),
+            fee,
+        )?);
+    }
+
     Ok(Response::new()
+        .add_messages(hook_msgs)
+        .may_add_message(if funds.is_non_empty() {
+            Some(dango_primitives::Message::transfer(ctx.sender, funds)?)
+        } else {
+            None
+        })
         .add_event(Dispatch(message.clone()))?
         .add_event(DispatchV2 {
             message,
@@ -90,6 +154,31 @@ fn dispatch(
         })?)
 }
 
+/// Query the fee that each post-dispatch hook requires for the message, in
+/// the order they are called: the required hook first, then the default.
+pub(crate) fn quote_hooks(
+    querier: QuerierWrapper,
+    hooks: Hooks,
+    raw_message: &HexBinary,
+) -> StdResult<Vec<(Addr, Coins)>> {
+    [hooks.required, hooks.default]
+        .into_iter()
+        .flatten()
+        .map(|hook| {
+            let fee = querier
+                .query_wasm_smart(
+                    hook,
+                    QueryHookRequest(HookQuery::QuoteDispatch {
+                        raw_message: raw_message.clone(),
+                    }),
+                )?
+                .into_quote_dispatch();
+
+            Ok((hook, fee))
+        })
+        .collect()
+}
+
 #[inline]
 fn process(
     ctx: MutableCtx,
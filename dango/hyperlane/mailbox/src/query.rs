@@ -1,10 +1,12 @@
 use {
-    crate::{CONFIG, DELIVERIES, MERKLE_TREE, NONCE},
+    crate::{CONFIG, DELIVERIES, HOOKS, MERKLE_TREE, NONCE, quote_hooks},
     dango_hyperlane_types::{
-        IncrementalMerkleTree,
-        mailbox::{Config, QueryMsg},
+        Addr32, IncrementalMerkleTree,
+        mailbox::{Config, Domain, Hooks, MAILBOX_VERSION, Message, QueryMsg},
+    },
+    dango_primitives::{
+        Addr, Coins, Hash256, HexBinary, ImmutableCtx, Json, JsonSerExt, StdResult,
     },
-    dango_primitives::{Hash256, ImmutableCtx, Json, JsonSerExt, StdResult},
 };
 
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
@@ -13,6 +15,10 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             let res = query_config(ctx)?;
             res.to_json_value()
         },
+        QueryMsg::Hooks {} => {
+            let res = query_hooks(ctx)?;
+            res.to_json_value()
+        },
         QueryMsg::Nonce {} => {
             let res = query_nonce(ctx)?;
             res.to_json_value()
@@ -25,6 +31,15 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             let res = query_delivered(ctx, message_id);
             res.to_json_value()
         },
+        QueryMsg::QuoteDispatch {
+            sender,
+            destination_domain,
+            recipient,
+            body,
+        } => {
+            let res = query_quote_dispatch(ctx, sender, destination_domain, recipient, body)?;
+            res.to_json_value()
+        },
     }
 }
 
@@ -33,6 +48,11 @@ fn query_config(ctx: ImmutableCtx) -> StdResult<Config> {
     CONFIG.load(ctx.storage)
 }
 
+#[inline]
+fn query_hooks(ctx: ImmutableCtx) -> StdResult<Hooks> {
+    Ok(HOOKS.may_load(ctx.storage)?.unwrap_or_default())
+}
+
 #[inline]
 fn query_nonce(ctx: ImmutableCtx) -> StdResult<u32> {
     NONCE.current(ctx.storage)
@@ -47,3 +67,34 @@ fn query_tree(ctx: ImmutableCtx) -> StdResult<IncrementalMerkleTree> {
 fn query_delivered(ctx: ImmutableCtx, message_id: Hash256) -> bool {
     DELIVERIES.has(ctx.storage, message_id)
 }
+
+fn query_quote_dispatch(
+    ctx: ImmutableCtx,
+    sender: Addr,
+    destination_domain: Domain,
+    recipient: Addr32,
+    body: HexBinary,
+) -> StdResult<Coins> {
+    let cfg = CONFIG.load(ctx.storage)?;
+    let hooks = HOOKS.may_load(ctx.storage)?.unwrap_or_default();
+
+    // Compose the message as `dispatch` would if it were called now.
+    let raw_message = Message {
+        version: MAILBOX_VERSION,
+        nonce: NONCE.current(ctx.storage)?,
+        origin_domain: cfg.local_domain,
+        sender: Addr32::from(sender),
+        destination_domain,
+        recipient,
+        body,
+    }
+    .encode();
+
+    let mut fee = Coins::new();
+
+    for (_, hook_fee) in quote_hooks(ctx.querier, hooks, &raw_message)? {
+        fee.insert_many(hook_fee)?;
+    }
+
+    Ok(fee)
+}
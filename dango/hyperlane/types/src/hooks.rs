@@ -0,0 +1,45 @@
+pub mod igp;
+
+use dango_primitives::{Coins, HexBinary};
+
+// ----------------------------------- types -----------------------------------
+
+#[dango_primitives::derive(Serde)]
+pub enum HookMsg {
+    /// Called by the mailbox after a message has been dispatched.
+    /// The funds attached are the fee quoted by `HookQuery::QuoteDispatch`.
+    PostDispatch { raw_message: HexBinary },
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum HookQuery {
+    /// Quote the fee that `HookMsg::PostDispatch` requires for the message.
+    /// Return empty coins if the hook doesn't charge a fee.
+    QuoteDispatch { raw_message: HexBinary },
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum HookQueryResponse {
+    QuoteDispatch(Coins),
+}
+
+impl HookQueryResponse {
+    pub fn into_quote_dispatch(self) -> Coins {
+        match self {
+            HookQueryResponse::QuoteDispatch(fee) => fee,
+        }
+    }
+}
+
+// --------------------------------- messages ----------------------------------
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    Hook(HookMsg),
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    #[returns(HookQueryResponse)]
+    Hook(HookQuery),
+}
@@ -0,0 +1,128 @@
+use {
+    crate::{
+        hooks::{HookMsg, HookQuery, HookQueryResponse},
+        mailbox::Domain,
+    },
+    dango_math::{MathResult, MultiplyFraction, Number, Udec128, Uint128},
+    dango_primitives::{Addr, Coins, Denom, Hash256},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// The gas limit assumed for handling a message on the destination domain,
+/// before adding the domain's gas overhead.
+///
+/// Same as the default in Hyperlane's `StandardHookMetadata`.
+pub const DEFAULT_GAS_LIMIT: Uint128 = Uint128::new(50_000);
+
+// ----------------------------------- types -----------------------------------
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct Config {
+    /// The mailbox contract. Only the mailbox can call `PostDispatch`.
+    pub mailbox: Addr,
+    /// The denom in which interchain gas is paid.
+    pub fee_denom: Denom,
+    /// The account that the collected fees are claimed to.
+    pub beneficiary: Addr,
+}
+
+/// Gas price data of a destination domain.
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct GasOracle {
+    /// Units of `fee_denom` per unit of the destination domain's gas token,
+    /// both in base units.
+    pub token_exchange_rate: Udec128,
+    /// Gas price on the destination domain, in base units of its gas token.
+    pub gas_price: Uint128,
+}
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct DestinationGasConfig {
+    pub gas_oracle: GasOracle,
+    /// Gas used on the destination domain on top of the message's gas
+    /// limit, e.g. for verifying the ISM metadata.
+    pub gas_overhead: Uint128,
+}
+
+impl DestinationGasConfig {
+    /// Quote the payment, in base units of `fee_denom`, for delivering a
+    /// message that uses `gas_limit` gas on the destination domain.
+    pub fn quote(&self, gas_limit: Uint128) -> MathResult<Uint128> {
+        gas_limit
+            .checked_add(self.gas_overhead)?
+            .checked_mul(self.gas_oracle.gas_price)?
+            .checked_mul_dec_ceil(self.gas_oracle.token_exchange_rate)
+    }
+}
+
+// --------------------------------- messages ----------------------------------
+
+#[dango_primitives::derive(Serde)]
+pub struct InstantiateMsg {
+    pub mailbox: Addr,
+    pub fee_denom: Denom,
+    pub beneficiary: Addr,
+    pub destination_gas_configs: BTreeMap<Domain, DestinationGasConfig>,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Set the gas configs of destination domains.
+    /// Only callable by the chain owner.
+    SetDestinationGasConfigs(BTreeMap<Domain, DestinationGasConfig>),
+    /// Remove the gas configs of destination domains. Messages to these
+    /// domains are no longer charged interchain gas.
+    /// Only callable by the chain owner.
+    RemoveDestinationGasConfigs(BTreeSet<Domain>),
+    /// Set the account that the collected fees are claimed to.
+    /// Only callable by the chain owner.
+    SetBeneficiary { beneficiary: Addr },
+    /// Send all collected fees to the beneficiary. Callable by anyone.
+    Claim {},
+    /// Required Hyperlane hook interface.
+    Hook(HookMsg),
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the IGP configuration.
+    #[returns(Config)]
+    Config {},
+    /// Query the gas config of a destination domain.
+    #[returns(Option<DestinationGasConfig>)]
+    DestinationGasConfig { domain: Domain },
+    /// Enumerate the gas configs of all destination domains.
+    #[returns(BTreeMap<Domain, DestinationGasConfig>)]
+    DestinationGasConfigs {
+        start_after: Option<Domain>,
+        limit: Option<u32>,
+    },
+    /// Quote the payment for delivering a message that uses `gas_limit` gas
+    /// on the destination domain. Empty if the domain has no gas config.
+    #[returns(Coins)]
+    QuoteGasPayment {
+        destination_domain: Domain,
+        gas_limit: Uint128,
+    },
+    /// Required Hyperlane hook interface.
+    #[returns(HookQueryResponse)]
+    Hook(HookQuery),
+}
+
+// ---------------------------------- events -----------------------------------
+
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("igp_gas_payment")]
+pub struct GasPayment {
+    pub message_id: Hash256,
+    pub destination_domain: Domain,
+    pub gas_limit: Uint128,
+    pub payment: Coins,
+}
+
+#[dango_primitives::derive(Serde)]
+#[dango_primitives::event("igp_claim")]
+pub struct Claim {
+    pub beneficiary: Addr,
+    pub amount: Coins,
+}
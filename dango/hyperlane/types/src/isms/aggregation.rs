@@ -0,0 +1,164 @@
+use {
+    super::IsmQueryResponse,
+    crate::isms::IsmQuery,
+    anyhow::ensure,
+    dango_primitives::{Addr, HexBinary},
+};
+
+/// Size of a sub-ISM's metadata range in the aggregation ISM metadata: the
+/// start and end offsets, each a big-endian `u32`.
+const RANGE_SIZE: usize = 8;
+
+#[dango_primitives::derive(Serde, Borsh)]
+pub struct IsmSet {
+    /// The number of sub-ISMs that must verify a message.
+    pub threshold: u32,
+    /// The sub-ISMs, in the order their metadata appear in the aggregation ISM
+    /// metadata.
+    pub isms: Vec<Addr>,
+}
+
+/// Metadata of the aggregation ISM, in the same format as the Solidity
+/// implementation:
+///
+/// ```plain
+/// [  0:  4] start offset of ISM 0's metadata
+/// [  4:  8] end offset of ISM 0's metadata
+/// ...
+/// [8n-8:8n-4] start offset of ISM n-1's metadata
+/// [8n-4:8n  ] end offset of ISM n-1's metadata
+/// [8n:      ] the metadata of the ISMs
+/// ```
+///
+/// Offsets are relative to the beginning of the entire metadata. A zero start
+/// offset means no metadata is provided for that ISM, which is then skipped.
+#[dango_primitives::derive(Serde)]
+pub struct Metadata {
+    pub metadatas: Vec<Option<HexBinary>>,
+}
+
+impl Metadata {
+    pub fn encode(&self) -> HexBinary {
+        let mut ranges = Vec::with_capacity(self.metadatas.len() * RANGE_SIZE);
+        let mut buf = Vec::new();
+        let mut offset = self.metadatas.len() * RANGE_SIZE;
+
+        for metadata in &self.metadatas {
+            match metadata {
+                Some(metadata) => {
+                    let end = offset + metadata.len();
+                    ranges.extend((offset as u32).to_be_bytes());
+                    ranges.extend((end as u32).to_be_bytes());
+                    buf.extend_from_slice(metadata);
+                    offset = end;
+                },
+                None => {
+                    ranges.extend([0; RANGE_SIZE]);
+                },
+            }
+        }
+
+        ranges.extend(buf);
+        ranges.into()
+    }
+
+    /// Decode the metadata for the given number of sub-ISMs.
+    pub fn decode(buf: &[u8], num_isms: usize) -> anyhow::Result<Self> {
+        let header_len = num_isms * RANGE_SIZE;
+
+        ensure!(
+            buf.len() >= header_len,
+            "aggregation ISM metadata should be at least {} bytes, got: {}",
+            header_len,
+            buf.len()
+        );
+
+        let metadatas = buf[..header_len]
+            .as_chunks::<RANGE_SIZE>()
+            .0
+            .iter()
+            .map(|range| {
+                let start = u32::from_be_bytes(range[0..4].try_into().unwrap()) as usize;
+                let end = u32::from_be_bytes(range[4..8].try_into().unwrap()) as usize;
+
+                if start == 0 {
+                    return Ok(None);
+                }
+
+                ensure!(
+                    header_len <= start && start <= end && end <= buf.len(),
+                    "invalid metadata range! start: {start}, end: {end}, length: {}",
+                    buf.len()
+                );
+
+                Ok(Some(buf[start..end].to_vec().into()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { metadatas })
+    }
+}
+
+// --------------------------------- messages ----------------------------------
+
+#[dango_primitives::derive(Serde)]
+pub struct InstantiateMsg {
+    pub ism_set: IsmSet,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Set the sub-ISMs and the number of them that must verify a message.
+    SetIsms { threshold: u32, isms: Vec<Addr> },
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the sub-ISMs and the threshold.
+    #[returns(IsmSet)]
+    IsmSet {},
+    /// Required Hyperlane ISM interface.
+    #[returns(IsmQueryResponse)]
+    Ism(IsmQuery),
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, dango_primitives::ResultExt};
+
+    #[test]
+    fn encoding_decoding_metadata() {
+        let metadata = Metadata {
+            metadatas: vec![
+                Some(b"foo".to_vec().into()),
+                None,
+                Some(b"fuzzbuzz".to_vec().into()),
+            ],
+        };
+
+        let raw_metadata = metadata.encode();
+        assert_eq!(raw_metadata.len(), 3 * RANGE_SIZE + 3 + 8);
+        assert_eq!(&raw_metadata[0..8], &[0, 0, 0, 24, 0, 0, 0, 27]);
+        assert_eq!(&raw_metadata[8..16], &[0; 8]);
+        assert_eq!(&raw_metadata[16..24], &[0, 0, 0, 27, 0, 0, 0, 35]);
+
+        Metadata::decode(&raw_metadata, 3).should_succeed_and_equal(metadata);
+    }
+
+    #[test]
+    fn rejecting_invalid_metadata() {
+        // Too short to contain the ranges.
+        Metadata::decode(&[0; 15], 2)
+            .should_fail_with_error("aggregation ISM metadata should be at least 16 bytes");
+
+        // The range overlaps with the header.
+        Metadata::decode(&[0, 0, 0, 4, 0, 0, 0, 8], 1)
+            .should_fail_with_error("invalid metadata range");
+
+        // The range exceeds the metadata.
+        Metadata::decode(&[0, 0, 0, 8, 0, 0, 0, 10, 1], 1)
+            .should_fail_with_error("invalid metadata range");
+    }
+}
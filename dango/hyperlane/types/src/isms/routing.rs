@@ -0,0 +1,46 @@
+use {
+    super::IsmQueryResponse,
+    crate::{isms::IsmQuery, mailbox::Domain},
+    dango_primitives::Addr,
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+// --------------------------------- messages ----------------------------------
+
+#[dango_primitives::derive(Serde)]
+pub struct InstantiateMsg {
+    /// The ISM to use for messages from origin domains without a route.
+    /// If `None`, such messages are rejected.
+    pub default_ism: Option<Addr>,
+    pub routes: BTreeMap<Domain, Addr>,
+}
+
+#[dango_primitives::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Set the ISMs for origin domains.
+    SetRoutes(BTreeMap<Domain, Addr>),
+    /// Remove the ISMs for origin domains, which fall back to the default ISM.
+    RemoveRoutes(BTreeSet<Domain>),
+    /// Set the ISM to use for origin domains without a route.
+    SetDefaultIsm { ism: Option<Addr> },
+}
+
+#[dango_primitives::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the ISM to use for origin domains without a route.
+    #[returns(Option<Addr>)]
+    DefaultIsm {},
+    /// Query the ISM that verifies messages from an origin domain, taking the
+    /// default ISM into account.
+    #[returns(Addr)]
+    Route { domain: Domain },
+    /// Enumerate the ISMs of all origin domains with a route.
+    #[returns(BTreeMap<Domain, Addr>)]
+    Routes {
+        start_after: Option<Domain>,
+        limit: Option<u32>,
+    },
+    /// Required Hyperlane ISM interface.
+    #[returns(IsmQueryResponse)]
+    Ism(IsmQuery),
+}
@@ -1,6 +1,7 @@
 mod address;
 pub mod constants;
 mod crypto;
+pub mod hooks;
 mod incremental_merkle_tree;
 pub mod isms;
 pub mod mailbox;
@@ -1,7 +1,7 @@
 use {
     crate::{Addr32, IncrementalMerkleTree},
     anyhow::ensure,
-    dango_primitives::{Addr, Hash256, HexBinary, Inner},
+    dango_primitives::{Addr, Coins, Hash256, HexBinary, Inner},
 };
 
 pub const MAILBOX_VERSION: u8 = 3;
@@ -61,16 +61,30 @@ pub struct Config {
     pub default_ism: Addr,
 }
 
+/// Post-dispatch hooks called by the mailbox after each dispatched message.
+///
+/// The required hook is called first, then the default hook. The sender of
+/// the message pays the sum of their quoted fees.
+#[dango_primitives::derive(Serde, Borsh)]
+#[derive(Default)]
+pub struct Hooks {
+    pub required: Option<Addr>,
+    pub default: Option<Addr>,
+}
+
 // --------------------------------- messages ----------------------------------
 
 #[dango_primitives::derive(Serde)]
 pub struct InstantiateMsg {
     pub config: Config,
+    pub hooks: Hooks,
 }
 
 #[dango_primitives::derive(Serde)]
 pub enum ExecuteMsg {
     /// Send a message.
+    /// The funds attached must cover the fee quoted by `QueryMsg::QuoteDispatch`;
+    /// any excess is refunded to the sender.
     Dispatch {
         destination_domain: Domain,
         recipient: Addr32,
@@ -81,6 +95,13 @@ pub enum ExecuteMsg {
         raw_message: HexBinary,
         raw_metadata: HexBinary,
     },
+    /// Set the hook that is called after every dispatched message.
+    /// Only callable by the chain owner.
+    SetRequiredHook { hook: Option<Addr> },
+    /// Set the hook that is called after every dispatched message, after the
+    /// required hook.
+    /// Only callable by the chain owner.
+    SetDefaultHook { hook: Option<Addr> },
 }
 
 #[dango_primitives::derive(Serde, QueryRequest)]
@@ -88,6 +109,9 @@ pub enum QueryMsg {
     /// Query the mailbox configuration.
     #[returns(Config)]
     Config {},
+    /// Query the post-dispatch hooks.
+    #[returns(Hooks)]
+    Hooks {},
     /// Query the current nonce.
     #[returns(u32)]
     Nonce {},
@@ -97,6 +121,15 @@ pub enum QueryMsg {
     /// Query whether a message has been delivered.
     #[returns(bool)]
     Delivered { message_id: Hash256 },
+    /// Query the fee for dispatching a message, as the sum of the fees quoted
+    /// by the post-dispatch hooks.
+    #[returns(Coins)]
+    QuoteDispatch {
+        sender: Addr,
+        destination_domain: Domain,
+        recipient: Addr32,
+        body: HexBinary,
+    },
 }
 
 // ---------------------------------- events -----------------------------------
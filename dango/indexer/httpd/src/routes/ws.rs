@@ -16,8 +16,9 @@
 //! channel to attribute them to — an unparseable frame, or an `unsubscribe` for
 //! an unknown `id` — use the dedicated `error` channel.
 //!
-//! The following channel types are served, all reusing the in-memory validator
-//! stream that backed the `full_block` / `perps_events` GraphQL subscriptions:
+//! The following channel types are served, all but `candle` reusing the
+//! in-memory validator stream that backed the `full_block` / `perps_events`
+//! GraphQL subscriptions:
 //!
 //! - `perpsEvents` — perps-contract events grouped per block, narrowed by the
 //!   `eventTypes` / `pairIds` / `users` / `orderIds` / `clientOrderIds` filters.
@@ -39,6 +40,16 @@
 //!   share executions through the memo. Frames are `{blockHeight, response}`
 //!   on the alias's own channel, with `response` unwrapped to the raw
 //!   contract response, exactly what the REST twin returns.
+//! - `l2Book` — one pair's order book at one of its configured bucket sizes,
+//!   maintained incrementally by the validator stream: a `snapshot` frame at
+//!   subscribe time, then a `delta` frame of the changed buckets for every
+//!   block that changes one. Frames carry a `seq` (the block height the book
+//!   is as of), and deltas a `prevSeq` (the previous frame's `seq`), so a
+//!   client rebuilding the book locally can detect a gap.
+//! - `candle` — one pair's OHLCV candle at one `interval`, read from the
+//!   ClickHouse indexer's in-memory candle cache: the in-progress candle at
+//!   subscribe time and again after every indexed block, plus a `final: true`
+//!   frame for a candle once its interval has closed.
 //!
 //! Two one-shot request/response methods ride the same socket: `broadcast`
 //! (submit a signed transaction to the mempool) and `query` (run a read-only
@@ -49,8 +60,8 @@
 //! The transport is otherwise a thin shell over
 //! [`dango_indexer_stream::Context`]; only the framing differs from the SSE and
 //! GraphQL transports. The message enums are left open (no `#[non_exhaustive]`
-//! barrier, but room in the protocol) so further read channels (`l2Book`,
-//! `candle`, …) can be added without breaking existing clients.
+//! barrier, but room in the protocol) so further read channels can be added
+//! without breaking existing clients.
 
 use {
     crate::{
@@ -63,8 +74,15 @@ use {
     },
     actix_web::{HttpRequest, HttpResponse, Resource, guard, web},
     actix_ws::{AggregatedMessage, Session},
-    dango_indexer_stream::make_perps_filter,
-    dango_primitives::{HttpRequestDetails, Json, Query, QueryResponse, Tx},
+    chrono::{DateTime, Utc},
+    dango_indexer_clickhouse::{
+        entities::{CandleInterval, perps_candle::PerpsCandle},
+        indexer::perps_candles::cache::{PerpsCandleCache, PerpsCandleCacheKey},
+    },
+    dango_indexer_stream::{L2BookSubscribeError, make_perps_filter},
+    dango_math::Udec128_6,
+    dango_order_book::{PairId, UsdPrice},
+    dango_primitives::{HttpRequestDetails, Json, JsonDeExt, Query, QueryResponse, Timestamp, Tx},
     dango_types::perps,
     futures_util::{StreamExt, stream},
     serde::{Deserialize, Serialize},
@@ -127,14 +145,22 @@ pub fn services() -> Resource {
                    `/perps/*` REST routes, taking the same snake_case \
                    parameters plus `interval`, with the contract address \
                    resolved server-side and each frame's `response` being \
-                   the raw contract response — plus `unsubscribe`, `ping`, \
+                   the raw contract response, or an `l2Book` feed (one \
+                   pair's order book at one of its configured bucket sizes, \
+                   by `pairId` and `bucketSize`: a `snapshot`, then per-block \
+                   `delta`s of the changed buckets, sequenced by `seq` / \
+                   `prevSeq`), or a `candle` feed (one pair's OHLCV candle, \
+                   by `pairId` and `interval`: the in-progress candle every \
+                   block, and a `final` frame once its interval closes) — \
+                   plus `unsubscribe`, `ping`, \
                    `broadcast` (submit a signed `Tx` over the socket), and \
                    `query` (run a one-time read-only state query, the same \
                    `Query`/`QueryResponse` shapes as `POST /query`). \
                    Server frames are `channel`-tagged: `subscriptionResponse`, \
                    `perpsEvents`, `blockInfo`, `block`, `fullBlock`, \
                    `perpsPairState`, `perpsUserState`, `perpsOrdersByUser`, \
-                   `perpsLiquidityDepth`, `broadcast`, `query`, `pong`, and \
+                   `perpsLiquidityDepth`, `l2Book`, `candle`, `broadcast`, \
+                   `query`, `pong`, and \
                    `error`. The server pings every 20 seconds and closes a \
                    socket idle for 60 seconds. \
                    **Swagger UI cannot open WebSocket connections** — this \
@@ -304,6 +330,29 @@ enum Subscription {
         #[serde(default = "default_query_interval")]
         interval: u64,
     },
+
+    /// One pair's order book at one of its configured bucket sizes: a
+    /// `snapshot`, then a `delta` of the changed buckets for every block that
+    /// changes one, maintained incrementally from the perps events. Delivered
+    /// on the `l2Book` channel.
+    ///
+    /// Live-only, like `query`: on a gap or reconnect, resubscribe and take the
+    /// fresh snapshot.
+    L2Book {
+        pair_id: PairId,
+        bucket_size: UsdPrice,
+    },
+
+    /// One pair's OHLCV candle at one `interval` (`1s`, `1m`, …, `1w`): the
+    /// in-progress candle at subscribe time and after every indexed block,
+    /// then a `final` frame for each candle whose interval has closed.
+    /// Delivered on the `candle` channel.
+    ///
+    /// Live-only: backfill closed candles from the history API.
+    Candle {
+        pair_id: PairId,
+        interval: CandleInterval,
+    },
 }
 
 /// A `query` subscription that does not say otherwise re-runs every 10 blocks
@@ -327,6 +376,8 @@ impl Subscription {
             Subscription::PerpsUserState { .. } => "perpsUserState",
             Subscription::PerpsOrdersByUser { .. } => "perpsOrdersByUser",
             Subscription::PerpsLiquidityDepth { .. } => "perpsLiquidityDepth",
+            Subscription::L2Book { .. } => "l2Book",
+            Subscription::Candle { .. } => "candle",
         }
     }
 }
@@ -760,6 +811,191 @@ async fn open_stream(
                 app_ctx,
             )
         },
+        Subscription::L2Book {
+            pair_id,
+            bucket_size,
+        } => {
+            check_bucket_size(app_ctx, pair_id, *bucket_size).await?;
+
+            let updates = stream_ctx
+                .subscribe_l2_book(pair_id.clone(), *bucket_size)
+                .map_err(|err| match err {
+                    L2BookSubscribeError::Math(err) => SubscribeError::BadRequest(err.to_string()),
+                    L2BookSubscribeError::Resync(resync) => {
+                        SubscribeError::Resync(resync.to_string())
+                    },
+                })?;
+            let frames = guard_subscription_stream(updates, Some(guard))
+                .map(move |update| data_frame("l2Book", id, &update));
+
+            Ok(with_snapshot_terminal("l2Book", id, frames))
+        },
+        Subscription::Candle { pair_id, interval } => {
+            let key = PerpsCandleCacheKey::new(pair_id.to_string(), *interval);
+            let pubsub = app_ctx.clickhouse_context.pubsub.clone();
+            let cache = app_ctx.clickhouse_context.perps_candle_cache.clone();
+
+            let candles = async_stream::stream! {
+                // Connect to the pubsub before reading the snapshot, so no
+                // block indexed in between is missed.
+                let Ok(mut heights) = pubsub.subscribe().await else {
+                    return;
+                };
+
+                let mut open = None;
+                let mut last_height = 0;
+
+                let frames = candle_frames(&*cache.read().await, &key, 0, &mut open);
+
+                for frame in frames {
+                    yield frame;
+                }
+
+                while let Some(height) = heights.next().await {
+                    if height < last_height {
+                        continue;
+                    }
+
+                    last_height = height;
+
+                    let frames = candle_frames(&*cache.read().await, &key, height, &mut open);
+
+                    for frame in frames {
+                        yield frame;
+                    }
+                }
+            };
+            let frames = guard_subscription_stream(candles, Some(guard))
+                .map(move |frame| data_frame("candle", id, &frame));
+
+            Ok(with_snapshot_terminal("candle", id, frames))
+        },
+    }
+}
+
+/// One `candle` frame: a candle's current values, flagged `final` on the last
+/// frame of a candle whose interval has closed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CandleFrame {
+    #[serde(rename = "final")]
+    is_final: bool,
+    pair_id: String,
+    interval: CandleInterval,
+    /// RFC 3339; `time_start_unix` is the same instant in milliseconds.
+    time_start: String,
+    time_start_unix: i64,
+    time_end: String,
+    time_end_unix: i64,
+    open: Udec128_6,
+    high: Udec128_6,
+    low: Udec128_6,
+    close: Udec128_6,
+    volume: Udec128_6,
+    volume_usd: Udec128_6,
+    min_block_height: u64,
+    max_block_height: u64,
+}
+
+impl CandleFrame {
+    fn new(candle: &PerpsCandle, is_final: bool) -> Self {
+        let time_end = candle.time_start + candle.interval.duration();
+
+        Self {
+            is_final,
+            pair_id: candle.pair_id.clone(),
+            interval: candle.interval,
+            time_start: Timestamp::from(candle.time_start.naive_utc()).to_rfc3339_string(),
+            time_start_unix: candle.time_start.timestamp_millis(),
+            time_end: Timestamp::from(time_end.naive_utc()).to_rfc3339_string(),
+            time_end_unix: time_end.timestamp_millis(),
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume: candle.volume,
+            volume_usd: candle.volume_usd,
+            min_block_height: candle.min_block_height,
+            max_block_height: candle.max_block_height,
+        }
+    }
+}
+
+/// The frames a `candle` subscription sends once the ClickHouse indexer has
+/// processed `block_height` (`0` for the subscribe-time snapshot): a `final`
+/// frame for the candle last sent as in progress, if its interval has since
+/// closed, then the in-progress candle. `open` tracks the start of the candle
+/// last sent as in progress.
+///
+/// Nothing is sent while the cache lags `block_height`; the next block's
+/// frames catch up.
+fn candle_frames(
+    cache: &PerpsCandleCache,
+    key: &PerpsCandleCacheKey,
+    block_height: u64,
+    open: &mut Option<DateTime<Utc>>,
+) -> Vec<CandleFrame> {
+    let Some(candles) = cache.get_candles(key) else {
+        return Vec::new();
+    };
+
+    let Some(current) = candles.last() else {
+        return Vec::new();
+    };
+
+    if current.max_block_height < block_height {
+        return Vec::new();
+    }
+
+    let mut frames = Vec::with_capacity(2);
+
+    if let Some(time_start) = *open
+        && time_start < current.time_start
+        && let Some(closed) = candles.iter().rfind(|c| c.time_start == time_start)
+    {
+        frames.push(CandleFrame::new(closed, true));
+    }
+
+    *open = Some(current.time_start);
+    frames.push(CandleFrame::new(current, false));
+
+    frames
+}
+
+/// Reject a bucket size that isn't one of the pair's configured
+/// `bucket_sizes`, or a pair that doesn't exist — the checks the contract's
+/// `liquidity_depth` query makes, which the in-memory book can't.
+async fn check_bucket_size(
+    app_ctx: &FullContext,
+    pair_id: &PairId,
+    bucket_size: UsdPrice,
+) -> Result<(), SubscribeError> {
+    let query = desugar_perps_query(
+        app_ctx,
+        &perps::QueryMsg::PairParam {
+            pair_id: pair_id.clone(),
+        },
+    )
+    .await?;
+
+    let res = CoreQuery::_query_app(&app_ctx.base, query)
+        .await
+        .map_err(|err| SubscribeError::Unavailable(err.message))?;
+
+    let pair_param: Option<perps::PairParam> = res
+        .response
+        .into_wasm_smart()
+        .deserialize_json()
+        .map_err(|err| SubscribeError::Unavailable(err.to_string()))?;
+
+    match pair_param {
+        Some(pair_param) if pair_param.bucket_sizes.contains(&bucket_size) => Ok(()),
+        Some(_) => Err(SubscribeError::BadRequest(format!(
+            "bucket size {bucket_size} not configured for pair {pair_id}"
+        ))),
+        None => Err(SubscribeError::BadRequest(format!(
+            "pair {pair_id} not found"
+        ))),
     }
 }
 
@@ -864,6 +1100,25 @@ where
     Box::pin(frames.chain(terminal))
 }
 
+/// Like [`with_terminal`], for the live-only subscriptions that open with a
+/// snapshot: there is no `since` to reconnect with, so the client is told to
+/// resubscribe for a fresh snapshot.
+fn with_snapshot_terminal<S>(channel: &'static str, id: u64, frames: S) -> FrameStream
+where
+    S: stream::Stream<Item = String> + Send + 'static,
+{
+    let terminal = stream::once(async move {
+        channel_error(
+            channel,
+            id,
+            "resync",
+            "subscription ended; resubscribe for a fresh snapshot",
+        )
+    });
+
+    Box::pin(frames.chain(terminal))
+}
+
 /// How a standing query's executions are rendered into data frames.
 #[derive(Clone, Copy)]
 enum Projection {
@@ -1522,6 +1777,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserializes_l2_book_subscription() {
+        let message: ClientMessage = serde_json::from_str(
+            r#"{"method":"subscribe","id":9,"subscription":{"type":"l2Book","pairId":"perp/btcusd","bucketSize":"10"}}"#,
+        )
+        .unwrap();
+
+        let ClientMessage::Subscribe {
+            id: 9,
+            subscription,
+        } = message
+        else {
+            panic!("expected a subscribe message with id 9");
+        };
+
+        assert_eq!(subscription.channel(), "l2Book");
+
+        let Subscription::L2Book {
+            pair_id,
+            bucket_size,
+        } = subscription
+        else {
+            panic!("expected an l2Book subscription");
+        };
+
+        assert_eq!(pair_id.to_string(), "perp/btcusd");
+        assert_eq!(bucket_size, UsdPrice::new_int(10));
+    }
+
+    #[test]
+    fn deserializes_candle_subscription() {
+        let message: ClientMessage = serde_json::from_str(
+            r#"{"method":"subscribe","id":10,"subscription":{"type":"candle","pairId":"perp/btcusd","interval":"5m"}}"#,
+        )
+        .unwrap();
+
+        let ClientMessage::Subscribe { subscription, .. } = message else {
+            panic!("expected a subscribe message");
+        };
+
+        assert_eq!(subscription.channel(), "candle");
+
+        let Subscription::Candle { pair_id, interval } = subscription else {
+            panic!("expected a candle subscription");
+        };
+
+        assert_eq!(pair_id.to_string(), "perp/btcusd");
+        assert_eq!(interval, CandleInterval::FiveMinutes);
+    }
+
+    #[test]
+    fn candle_frames_finalize_closed_candles() {
+        use dango_indexer_clickhouse::entities::perps_pair_price::PerpsPairPrice;
+
+        let price = |block_height, secs, close| PerpsPairPrice {
+            pair_id: "perp/btcusd".to_string(),
+            high: Udec128_6::new(close),
+            low: Udec128_6::new(close),
+            close: Udec128_6::new(close),
+            volume: Udec128_6::new(1),
+            volume_usd: Udec128_6::new(close),
+            created_at: DateTime::from_timestamp(secs, 0).unwrap(),
+            block_height,
+        };
+
+        let mut cache = PerpsCandleCache::default();
+        let key = PerpsCandleCacheKey::new("perp/btcusd".to_string(), CandleInterval::OneMinute);
+        let mut open = None;
+
+        // Nothing cached yet: no snapshot.
+        assert!(candle_frames(&cache, &key, 0, &mut open).is_empty());
+
+        for (block_height, secs, close) in [(1, 10, 100), (2, 50, 110)] {
+            let pair_price = price(block_height, secs, close);
+            cache.add_pair_prices(block_height, pair_price.created_at, vec![pair_price]);
+        }
+
+        // The snapshot is the in-progress candle.
+        let frames = candle_frames(&cache, &key, 0, &mut open);
+        assert_eq!(frames.len(), 1);
+        assert!(!frames[0].is_final);
+        assert_eq!(frames[0].close, Udec128_6::new(110));
+        assert_eq!(frames[0].max_block_height, 2);
+        assert_eq!(frames[0].time_end_unix, 60_000);
+
+        // The cache lags the published block: nothing is sent.
+        assert!(candle_frames(&cache, &key, 3, &mut open).is_empty());
+
+        let pair_price = price(3, 65, 120);
+        cache.add_pair_prices(3, pair_price.created_at, vec![pair_price]);
+
+        // The first minute closed: its final frame, then the new candle.
+        let frames = candle_frames(&cache, &key, 3, &mut open);
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].is_final);
+        assert_eq!(frames[0].time_start_unix, 0);
+        assert_eq!(frames[0].close, Udec128_6::new(110));
+        assert!(!frames[1].is_final);
+        assert_eq!(frames[1].time_start_unix, 60_000);
+        assert_eq!(frames[1].open, Udec128_6::new(110));
+        assert_eq!(frames[1].close, Udec128_6::new(120));
+
+        let frame = serde_json::to_value(&frames[0]).unwrap();
+        assert_eq!(frame["final"], json!(true));
+        assert_eq!(frame["interval"], json!("1m"));
+        assert_eq!(frame["timeStart"], json!("1970-01-01T00:00:00.000000000Z"));
+    }
+
     #[test]
     fn alias_frames_unwrap_the_wasm_smart_envelope() {
         let frame = QueryFrame {
@@ -1,11 +1,19 @@
 use {
-    crate::{perps_events::PerpsEventBlock, recent_stream::RecentStream},
+    crate::{
+        l2_book::{L2Book, L2BookBlock, L2BookUpdate},
+        perps_events::PerpsEventBlock,
+        recent_stream::{RecentStream, ResyncRequired},
+    },
+    dango_math::MathError,
+    dango_order_book::{PairId, UsdPrice},
     dango_primitives::FullBlock,
+    futures_util::stream::{self, Stream, StreamExt},
+    std::sync::{Arc, Mutex},
 };
 
 /// A cheap-to-clone reader handle to the realtime stream's in-memory state,
 /// held by the httpd server. The `/ws` WebSocket handler uses it to open the
-/// `perpsEvents` and `fullBlock` channel subscriptions.
+/// `perpsEvents`, `fullBlock`, and `l2Book` channel subscriptions.
 ///
 /// Cloning shares the underlying rings + broadcasts with the live [`Indexer`].
 ///
@@ -14,14 +22,33 @@ use {
 pub struct Context {
     perps: RecentStream<PerpsEventBlock>,
     blocks: RecentStream<FullBlock>,
+    l2_book: RecentStream<L2BookBlock>,
+    book: Arc<Mutex<L2Book>>,
+}
+
+/// Why an `l2Book` subscription could not be opened.
+#[derive(Debug)]
+pub enum L2BookSubscribeError {
+    /// The book could not be aggregated at the requested bucket size.
+    Math(MathError),
+
+    /// The ring could not serve the live tail.
+    Resync(ResyncRequired),
 }
 
 impl Context {
     pub(crate) fn new(
         perps: RecentStream<PerpsEventBlock>,
         blocks: RecentStream<FullBlock>,
+        l2_book: RecentStream<L2BookBlock>,
+        book: Arc<Mutex<L2Book>>,
     ) -> Self {
-        Self { perps, blocks }
+        Self {
+            perps,
+            blocks,
+            l2_book,
+            book,
+        }
     }
 
     /// The perps-events stream backing the `/ws` `perpsEvents` channel.
@@ -33,4 +60,50 @@ impl Context {
     pub fn blocks(&self) -> &RecentStream<FullBlock> {
         &self.blocks
     }
+
+    /// Open an `l2Book` subscription for one pair at one bucket size: a
+    /// `Snapshot` of the book as of the latest committed block, then a
+    /// `Delta` for every later block that changes a bucket.
+    ///
+    /// The caller is responsible for checking `bucket_size` is one of the
+    /// pair's configured bucket sizes. The stream ends if the subscriber falls
+    /// out of the ring's window; the client then resubscribes for a fresh
+    /// snapshot.
+    pub fn subscribe_l2_book(
+        &self,
+        pair_id: PairId,
+        bucket_size: UsdPrice,
+    ) -> Result<impl Stream<Item = L2BookUpdate> + Send + 'static, L2BookSubscribeError> {
+        // Hold the book's lock while subscribing: the indexer applies a block
+        // to the book and appends it to the ring under the same lock, so the
+        // snapshot and the ring tip the live feed starts after agree.
+        let book = self.book.lock().unwrap();
+
+        let mut view = book
+            .view(pair_id.clone(), bucket_size)
+            .map_err(L2BookSubscribeError::Math)?;
+        let snapshot = view.snapshot();
+
+        // Project each block to the pair's changes only; blocks that don't
+        // touch the pair are suppressed.
+        let changes = self
+            .l2_book
+            .subscribe(None, move |block: &L2BookBlock| {
+                let changes = block.changes.get(&pair_id)?;
+                Some((block.block_height, changes.clone()))
+            })
+            .map_err(L2BookSubscribeError::Resync)?;
+
+        drop(book);
+
+        let deltas = changes
+            .scan((), move |_, (block_height, changes)| {
+                // Folding can only fail on an arithmetic overflow, after which
+                // the view is unusable; end the stream.
+                std::future::ready(view.apply(block_height, &changes).ok())
+            })
+            .filter_map(std::future::ready);
+
+        Ok(stream::once(std::future::ready(snapshot)).chain(deltas))
+    }
 }
@@ -1,12 +1,17 @@
 use {
     crate::{
         context::Context,
+        l2_book::{L2Book, L2BookBlock},
         perps_events::{PerpsEventBlock, extract_perps_event_block},
         recent_stream::RecentStream,
     },
     async_trait::async_trait,
-    dango_app::IndexerResult,
-    dango_primitives::{Addr, Block, BlockOutcome, Config, FullBlock, Json, JsonDeExt},
+    dango_app::{APP_CONFIG, CONTRACT_NAMESPACE, IndexerResult, LAST_FINALIZED_BLOCK},
+    dango_order_book::{ASKS, BIDS, may_invert_price},
+    dango_primitives::{
+        Addr, Block, BlockOutcome, Config, FullBlock, Json, JsonDeExt, Order as IterationOrder,
+        Record, StdResult, Storage, concat, increment_last_byte, trim,
+    },
     dango_types::config::AppConfig,
     std::{
         collections::HashMap,
@@ -55,26 +60,81 @@ struct Inner {
     /// In-memory ring of perpetual futures-related events, backing the
     /// `perps_events` subscription.
     perps: RecentStream<PerpsEventBlock>,
+
+    /// In-memory ring of per-block order book changes, backing the `l2Book`
+    /// subscription. Shares the perps ring's capacity.
+    l2_book: RecentStream<L2BookBlock>,
+
+    /// The perps order book as of the last published block. Locked while a
+    /// block is applied AND appended to `l2_book`, so a subscriber taking a
+    /// snapshot under the lock sees a book consistent with the ring tip.
+    book: Arc<Mutex<L2Book>>,
 }
 
 impl Indexer {
-    /// `perps_ring_capacity` and `block_ring_capacity` size the two in-memory
-    /// rings (reconnect window + broadcast buffer) for the `perps_events` and
-    /// `full_block` subscriptions respectively. See [`DEFAULT_RING_CAPACITY`]
-    /// and [`DEFAULT_BLOCK_RING_CAPACITY`].
+    /// `perps_ring_capacity` and `block_ring_capacity` size the in-memory
+    /// rings (reconnect window + broadcast buffer) for the `perps_events` (and
+    /// `l2Book`) and `full_block` subscriptions respectively. See
+    /// [`DEFAULT_PERPS_RING_CAPACITY`] and [`DEFAULT_BLOCK_RING_CAPACITY`].
     pub fn new(block_ring_capacity: usize, perps_ring_capacity: usize) -> Self {
         Self {
             inner: Arc::new(Inner {
                 pending: Mutex::new(HashMap::new()),
                 blocks: RecentStream::new(block_ring_capacity),
                 perps: RecentStream::new(perps_ring_capacity),
+                l2_book: RecentStream::new(perps_ring_capacity),
+                book: Arc::new(Mutex::new(L2Book::default())),
             }),
         }
     }
 
     /// A cheap-to-clone reader handle for the httpd server.
     pub fn context(&self) -> Context {
-        Context::new(self.inner.perps.clone(), self.inner.blocks.clone())
+        Context::new(
+            self.inner.perps.clone(),
+            self.inner.blocks.clone(),
+            self.inner.l2_book.clone(),
+            self.inner.book.clone(),
+        )
+    }
+
+    /// Load every resting order of the perps contract from `storage` into the
+    /// in-memory order book. A no-op if the chain has no app config yet (it
+    /// hasn't run genesis), in which case the book is correctly empty.
+    fn seed_l2_book(&self, storage: &dyn Storage) -> StdResult<()> {
+        let Some(app_cfg) = APP_CONFIG.may_load(storage)? else {
+            return Ok(());
+        };
+
+        let perps_addr = app_cfg.deserialize_json::<AppConfig>()?.addresses.perps;
+        let height = LAST_FINALIZED_BLOCK
+            .may_load(storage)?
+            .map(|block| block.height);
+
+        let perps_storage = ContractStorage {
+            storage,
+            namespace: concat(CONTRACT_NAMESPACE, &perps_addr),
+        };
+
+        let mut orders = Vec::new();
+
+        for (is_bid, book) in [(true, BIDS), (false, ASKS)] {
+            for record in book.range(&perps_storage, None, None, IterationOrder::Ascending) {
+                let ((pair_id, stored_price, order_id), order) = record?;
+                let price = may_invert_price(stored_price, is_bid);
+                orders.push((
+                    order_id,
+                    pair_id,
+                    price,
+                    order.size,
+                    order.user == perps_addr,
+                ));
+            }
+        }
+
+        self.inner.book.lock().unwrap().seed(height, orders)?;
+
+        Ok(())
     }
 
     /// Drain the `index_block` stash for `block_height` and publish it to the
@@ -109,6 +169,15 @@ impl Indexer {
                     .increment(batch.events.len() as u64);
             }
 
+            // Order book feed: apply the events to the book and publish the
+            // level changes, under the book's lock (see `Inner::book`).
+            {
+                let mut book = self.inner.book.lock().unwrap();
+                let changes = book.apply(&batch, perps_addr);
+
+                self.inner.l2_book.append(Arc::new(changes));
+            }
+
             self.inner.perps.append(Arc::new(batch));
         }
 
@@ -129,8 +198,20 @@ impl dango_app::Indexer for Indexer {
         "dango-indexer-stream"
     }
 
-    // `start`, `shutdown`, `pre_indexing`, `wait_for_finish` use the trait's
-    // default no-op impls: there is no durable store to migrate or drain.
+    // `shutdown`, `pre_indexing`, `wait_for_finish` use the trait's default
+    // no-op impls: there is no durable store to migrate or drain.
+
+    async fn start(&mut self, storage: &dyn Storage) -> IndexerResult<()> {
+        // Best-effort, like the rest of this indexer: a book that fails to
+        // seed must not keep the node from starting. It then starts empty and
+        // only reflects orders placed from here on.
+        if let Err(_err) = self.seed_l2_book(storage) {
+            #[cfg(feature = "tracing")]
+            tracing::error!(err = %_err, "failed to seed the l2 book from storage");
+        }
+
+        Ok(())
+    }
 
     async fn index_block(&self, block: &Block, block_outcome: &BlockOutcome) -> IndexerResult<()> {
         // Stash only. `index_block` runs at FinalizeBlock — the block is
@@ -187,6 +268,88 @@ impl dango_app::Indexer for Indexer {
     }
 }
 
+/// A read-only view of one contract's substore, borrowed from the app's
+/// storage — what `StorageProvider` gives the VM, without taking ownership,
+/// since `start` only lends the storage.
+#[derive(Clone)]
+struct ContractStorage<'a> {
+    storage: &'a dyn Storage,
+    namespace: Vec<u8>,
+}
+
+impl ContractStorage<'_> {
+    fn range_bounds(&self, min: Option<&[u8]>, max: Option<&[u8]>) -> (Vec<u8>, Vec<u8>) {
+        let min = match min {
+            Some(bytes) => concat(&self.namespace, bytes),
+            None => self.namespace.clone(),
+        };
+        let max = match max {
+            Some(bytes) => concat(&self.namespace, bytes),
+            None => increment_last_byte(self.namespace.clone()),
+        };
+
+        (min, max)
+    }
+}
+
+impl Storage for ContractStorage<'_> {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.read(&concat(&self.namespace, key))
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: IterationOrder,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let (min, max) = self.range_bounds(min, max);
+        let iter = self
+            .storage
+            .scan(Some(&min), Some(&max), order)
+            .map(|(key, value)| (trim(&self.namespace, &key), value));
+
+        Box::new(iter)
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: IterationOrder,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let (min, max) = self.range_bounds(min, max);
+        let iter = self
+            .storage
+            .scan_keys(Some(&min), Some(&max), order)
+            .map(|key| trim(&self.namespace, &key));
+
+        Box::new(iter)
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: IterationOrder,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let (min, max) = self.range_bounds(min, max);
+        self.storage.scan_values(Some(&min), Some(&max), order)
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("contract storage is read-only");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("contract storage is read-only");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("contract storage is read-only");
+    }
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
@@ -0,0 +1,760 @@
+//! The incrementally maintained L2 order book behind the `l2Book`
+//! subscription.
+//!
+//! The indexer keeps every resting limit order in memory — seeded from the
+//! perps contract's storage when the node starts, then kept in sync from the
+//! contract's events:
+//!
+//! - `order_persisted` inserts an order;
+//! - `order_filled` shrinks it to its `remaining_order_size` (removing it at
+//!   zero) — a taker's fill refers to an order not yet on the book and is
+//!   ignored, its remainder arriving as an `order_persisted`;
+//! - `order_resized` shrinks it to its `new_size`;
+//! - `order_removed` removes it;
+//! - `vault_orders_refreshed` replaces all of the vault's orders, whose churn
+//!   emits none of the above.
+//!
+//! Orders are aggregated by exact price level. Each block publishes the net
+//! per-level changes as an [`L2BookBlock`]; a subscriber's [`L2BookView`]
+//! folds them into its own bucket size, emitting the buckets that changed.
+
+use {
+    crate::{perps_events::PerpsEventBlock, recent_stream::HasHeight},
+    dango_math::MathResult,
+    dango_order_book::{
+        LiquidityDepth, OrderId, OrderPersisted, OrderRemoved, OrderResized, PairId, Quantity,
+        UsdPrice, UsdValue,
+    },
+    dango_primitives::{Addr, EventName, JsonDeExt, StdResult},
+    dango_types::perps::{OrderFilled, VaultOrdersRefreshed},
+    std::collections::{BTreeMap, BTreeSet, HashMap},
+};
+
+/// The net change of one exact price level within one block: the signed
+/// difference in the level's aggregated size and notional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelChange {
+    pub is_bid: bool,
+    pub price: UsdPrice,
+    pub size: Quantity,
+    pub notional: UsdValue,
+}
+
+/// The net level changes of every pair whose book changed in one block. A
+/// block that touches no book is still published (with no changes), so that
+/// heights stay contiguous.
+#[derive(Debug, Clone, Default)]
+pub struct L2BookBlock {
+    pub block_height: u64,
+    pub changes: BTreeMap<PairId, Vec<LevelChange>>,
+}
+
+impl HasHeight for L2BookBlock {
+    fn height(&self) -> u64 {
+        self.block_height
+    }
+}
+
+/// One frame of the `l2Book` channel. `seq` is the block height the book is
+/// as of; a delta's `prev_seq` is the `seq` of the frame before it, so a
+/// client detects a gap when it doesn't match the last `seq` it applied.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum L2BookUpdate {
+    /// The full book at `seq`: bucket price to aggregated size and notional.
+    Snapshot {
+        seq: u64,
+        bids: BTreeMap<UsdPrice, LiquidityDepth>,
+        asks: BTreeMap<UsdPrice, LiquidityDepth>,
+    },
+
+    /// The buckets that changed since `prev_seq`, with their new totals. A
+    /// zero size means the bucket is now empty.
+    #[serde(rename_all = "camelCase")]
+    Delta {
+        seq: u64,
+        prev_seq: u64,
+        bids: BTreeMap<UsdPrice, LiquidityDepth>,
+        asks: BTreeMap<UsdPrice, LiquidityDepth>,
+    },
+}
+
+// ----------------------------------- book ------------------------------------
+
+struct RestingOrder {
+    pair_id: PairId,
+    price: UsdPrice,
+
+    /// Signed: positive for a bid, negative for an ask.
+    size: Quantity,
+}
+
+#[derive(Default)]
+struct PairLevels {
+    bids: BTreeMap<UsdPrice, LiquidityDepth>,
+    asks: BTreeMap<UsdPrice, LiquidityDepth>,
+}
+
+/// Per-block accumulator of level changes: `(pair, is_bid, price)` to the net
+/// `(size, notional)` change.
+type Changes = BTreeMap<(PairId, bool, UsdPrice), (Quantity, UsdValue)>;
+
+/// Every resting limit order of the perps contract, aggregated by price level.
+#[derive(Default)]
+pub(crate) struct L2Book {
+    /// The last block applied to the book, or the block the book was seeded
+    /// at. `None` if neither has happened.
+    height: Option<u64>,
+    orders: HashMap<OrderId, RestingOrder>,
+    vault_orders: BTreeSet<OrderId>,
+    levels: BTreeMap<PairId, PairLevels>,
+}
+
+impl L2Book {
+    /// Seed the book with the orders resting at `height`, replacing whatever
+    /// it held. Each order is `(order_id, pair_id, price, signed size,
+    /// is_vault)`.
+    pub fn seed<I>(&mut self, height: Option<u64>, orders: I) -> MathResult<()>
+    where
+        I: IntoIterator<Item = (OrderId, PairId, UsdPrice, Quantity, bool)>,
+    {
+        *self = Self::default();
+
+        let mut changes = Changes::new();
+
+        for (order_id, pair_id, price, size, is_vault) in orders {
+            self.insert(order_id, pair_id, price, size, is_vault, &mut changes)?;
+        }
+
+        self.height = height;
+
+        Ok(())
+    }
+
+    /// Apply one committed block's perps-contract events, returning the net
+    /// level changes. `vault` is the perps contract's address, which owns the
+    /// vault's orders.
+    ///
+    /// An event that can't be applied (a malformed payload, or an arithmetic
+    /// overflow) is skipped and logged; the book can't stop following the
+    /// chain.
+    pub fn apply(&mut self, batch: &PerpsEventBlock, vault: Addr) -> L2BookBlock {
+        let mut changes = Changes::new();
+
+        for event in &batch.events {
+            if let Err(_err) = self.apply_event(&event.event_type, &event.data, vault, &mut changes)
+            {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    block_height = batch.block_height,
+                    event_type = event.event_type,
+                    err = %_err,
+                    "failed to apply perps event to the l2 book"
+                );
+            }
+        }
+
+        self.height = Some(batch.block_height);
+
+        let mut block = L2BookBlock {
+            block_height: batch.block_height,
+            changes: BTreeMap::new(),
+        };
+
+        // Drop levels whose changes cancelled out within the block, e.g. an
+        // order canceled and replaced at the same price and size.
+        for ((pair_id, is_bid, price), (size, notional)) in changes {
+            if size.is_zero() && notional.is_zero() {
+                continue;
+            }
+
+            block.changes.entry(pair_id).or_default().push(LevelChange {
+                is_bid,
+                price,
+                size,
+                notional,
+            });
+        }
+
+        block
+    }
+
+    fn apply_event(
+        &mut self,
+        event_type: &str,
+        data: &serde_json::Value,
+        vault: Addr,
+        changes: &mut Changes,
+    ) -> StdResult<()> {
+        match event_type {
+            OrderPersisted::EVENT_NAME => {
+                let event: OrderPersisted = data.clone().deserialize_json()?;
+                self.insert(
+                    event.order_id,
+                    event.pair_id,
+                    event.limit_price,
+                    event.size,
+                    event.user == vault,
+                    changes,
+                )?;
+            },
+            OrderFilled::EVENT_NAME => {
+                let event: OrderFilled = data.clone().deserialize_json()?;
+                if let Some(remaining_order_size) = event.remaining_order_size {
+                    self.resize(event.order_id, remaining_order_size, changes)?;
+                }
+            },
+            OrderResized::EVENT_NAME => {
+                let event: OrderResized = data.clone().deserialize_json()?;
+                self.resize(event.order_id, event.new_size, changes)?;
+            },
+            OrderRemoved::EVENT_NAME => {
+                let event: OrderRemoved = data.clone().deserialize_json()?;
+                self.remove(event.order_id, changes)?;
+            },
+            VaultOrdersRefreshed::EVENT_NAME => {
+                let event: VaultOrdersRefreshed = data.clone().deserialize_json()?;
+
+                for order_id in std::mem::take(&mut self.vault_orders) {
+                    self.remove(order_id, changes)?;
+                }
+
+                for order in event.orders {
+                    self.insert(
+                        order.order_id,
+                        order.pair_id,
+                        order.limit_price,
+                        order.size,
+                        true,
+                        changes,
+                    )?;
+                }
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    fn insert(
+        &mut self,
+        order_id: OrderId,
+        pair_id: PairId,
+        price: UsdPrice,
+        size: Quantity,
+        is_vault: bool,
+        changes: &mut Changes,
+    ) -> MathResult<()> {
+        // Order IDs are never reused; this only guards against applying the
+        // same placement twice.
+        self.remove(order_id, changes)?;
+
+        if size.is_zero() {
+            return Ok(());
+        }
+
+        let order = RestingOrder {
+            pair_id,
+            price,
+            size,
+        };
+
+        self.update_level(&order, true, changes)?;
+        self.orders.insert(order_id, order);
+
+        if is_vault {
+            self.vault_orders.insert(order_id);
+        }
+
+        Ok(())
+    }
+
+    /// Change a tracked order's size, removing it at zero. A no-op for an
+    /// order the book doesn't hold.
+    fn resize(
+        &mut self,
+        order_id: OrderId,
+        new_size: Quantity,
+        changes: &mut Changes,
+    ) -> MathResult<()> {
+        let Some(order) = self.orders.get(&order_id) else {
+            return Ok(());
+        };
+
+        let pair_id = order.pair_id.clone();
+        let price = order.price;
+        let is_vault = self.vault_orders.contains(&order_id);
+
+        self.remove(order_id, changes)?;
+        self.insert(order_id, pair_id, price, new_size, is_vault, changes)
+    }
+
+    fn remove(&mut self, order_id: OrderId, changes: &mut Changes) -> MathResult<()> {
+        let Some(order) = self.orders.remove(&order_id) else {
+            return Ok(());
+        };
+
+        self.vault_orders.remove(&order_id);
+        self.update_level(&order, false, changes)
+    }
+
+    /// Add (`increase`) or subtract an order's size and notional to its price
+    /// level, recording the change.
+    fn update_level(
+        &mut self,
+        order: &RestingOrder,
+        increase: bool,
+        changes: &mut Changes,
+    ) -> MathResult<()> {
+        let is_bid = order.size.is_positive();
+        let mut size = order.size.checked_abs()?;
+        let mut notional = size.checked_mul(order.price)?;
+
+        if !increase {
+            size = size.checked_neg()?;
+            notional = notional.checked_neg()?;
+        }
+
+        let pair = self.levels.entry(order.pair_id.clone()).or_default();
+        let side = if is_bid {
+            &mut pair.bids
+        } else {
+            &mut pair.asks
+        };
+
+        add_to_level(side, order.price, size, notional)?;
+
+        if pair.bids.is_empty() && pair.asks.is_empty() {
+            self.levels.remove(&order.pair_id);
+        }
+
+        let (net_size, net_notional) = changes
+            .entry((order.pair_id.clone(), is_bid, order.price))
+            .or_default();
+        net_size.checked_add_assign(size)?;
+        net_notional.checked_add_assign(notional)?;
+
+        Ok(())
+    }
+
+    /// Open a subscriber's view of one pair's book at `bucket_size`, as of
+    /// the book's current height.
+    pub fn view(&self, pair_id: PairId, bucket_size: UsdPrice) -> MathResult<L2BookView> {
+        let mut view = L2BookView {
+            pair_id,
+            bucket_size,
+            seq: self.height.unwrap_or(0),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        };
+
+        if let Some(pair) = self.levels.get(&view.pair_id) {
+            for (is_bid, levels) in [(true, &pair.bids), (false, &pair.asks)] {
+                for (price, depth) in levels {
+                    view.add(is_bid, *price, depth.size, depth.notional)?;
+                }
+            }
+        }
+
+        Ok(view)
+    }
+}
+
+/// Add a signed change to a price level, removing the level once empty.
+fn add_to_level(
+    levels: &mut BTreeMap<UsdPrice, LiquidityDepth>,
+    price: UsdPrice,
+    size: Quantity,
+    notional: UsdValue,
+) -> MathResult<()> {
+    let level = levels.entry(price).or_insert(LiquidityDepth {
+        size: Quantity::ZERO,
+        notional: UsdValue::ZERO,
+    });
+
+    level.size.checked_add_assign(size)?;
+    level.notional.checked_add_assign(notional)?;
+
+    if level.size.is_zero() {
+        levels.remove(&price);
+    }
+
+    Ok(())
+}
+
+// ----------------------------------- view ------------------------------------
+
+/// One subscriber's copy of a pair's book, aggregated at its bucket size the
+/// same way the perps contract aggregates `liquidity_depth`: bids floored and
+/// asks ceiled to a multiple of the bucket size.
+pub struct L2BookView {
+    pair_id: PairId,
+    bucket_size: UsdPrice,
+    seq: u64,
+    bids: BTreeMap<UsdPrice, LiquidityDepth>,
+    asks: BTreeMap<UsdPrice, LiquidityDepth>,
+}
+
+impl L2BookView {
+    /// The view's current book, as a `Snapshot` frame.
+    pub fn snapshot(&self) -> L2BookUpdate {
+        L2BookUpdate::Snapshot {
+            seq: self.seq,
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+        }
+    }
+
+    /// The pair the view follows.
+    pub fn pair_id(&self) -> &PairId {
+        &self.pair_id
+    }
+
+    /// Fold the pair's changes in the block at `block_height` into the view,
+    /// returning a `Delta` frame of the buckets that changed, or `None` if
+    /// none did.
+    pub fn apply(
+        &mut self,
+        block_height: u64,
+        changes: &[LevelChange],
+    ) -> MathResult<Option<L2BookUpdate>> {
+        let mut touched = BTreeSet::new();
+
+        for change in changes {
+            let bucket = self.add(change.is_bid, change.price, change.size, change.notional)?;
+            touched.insert((change.is_bid, bucket));
+        }
+
+        let mut bids = BTreeMap::new();
+        let mut asks = BTreeMap::new();
+
+        for (is_bid, bucket) in touched {
+            let (levels, out) = if is_bid {
+                (&self.bids, &mut bids)
+            } else {
+                (&self.asks, &mut asks)
+            };
+
+            let depth = levels.get(&bucket).cloned().unwrap_or(LiquidityDepth {
+                size: Quantity::ZERO,
+                notional: UsdValue::ZERO,
+            });
+
+            out.insert(bucket, depth);
+        }
+
+        if bids.is_empty() && asks.is_empty() {
+            return Ok(None);
+        }
+
+        let prev_seq = self.seq;
+        self.seq = block_height;
+
+        Ok(Some(L2BookUpdate::Delta {
+            seq: self.seq,
+            prev_seq,
+            bids,
+            asks,
+        }))
+    }
+
+    /// Add a signed change at an exact price to its bucket, returning the
+    /// bucket.
+    fn add(
+        &mut self,
+        is_bid: bool,
+        price: UsdPrice,
+        size: Quantity,
+        notional: UsdValue,
+    ) -> MathResult<UsdPrice> {
+        let (bucket, levels) = if is_bid {
+            (
+                price.checked_floor_multiple(self.bucket_size)?,
+                &mut self.bids,
+            )
+        } else {
+            (
+                price.checked_ceil_multiple(self.bucket_size)?,
+                &mut self.asks,
+            )
+        };
+
+        add_to_level(levels, bucket, size, notional)?;
+
+        Ok(bucket)
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::perps_events::PerpsEvent, dango_math::Uint64,
+        dango_order_book::ReasonForOrderRemoval, dango_types::perps::VaultOrder, std::str::FromStr,
+    };
+
+    const USER: Addr = Addr::mock(1);
+    const VAULT: Addr = Addr::mock(0);
+
+    fn pair() -> PairId {
+        PairId::from_str("perp/btcusd").unwrap()
+    }
+
+    fn event<E>(event: E) -> PerpsEvent
+    where
+        E: EventName + serde::Serialize,
+    {
+        PerpsEvent {
+            idx: 0,
+            event_type: E::EVENT_NAME.to_string(),
+            user: None,
+            pair_id: None,
+            order_id: None,
+            client_order_id: None,
+            data: serde_json::to_value(event).unwrap(),
+        }
+    }
+
+    fn batch(block_height: u64, events: Vec<PerpsEvent>) -> PerpsEventBlock {
+        PerpsEventBlock {
+            block_height,
+            created_at: "2026-10-18T00:00:00Z".to_string(),
+            events,
+        }
+    }
+
+    fn persisted(order_id: u64, price: i128, size: i128) -> PerpsEvent {
+        event(OrderPersisted {
+            order_id: Uint64::new(order_id),
+            pair_id: pair(),
+            user: USER,
+            limit_price: UsdPrice::new_int(price),
+            size: Quantity::new_int(size),
+            client_order_id: None,
+        })
+    }
+
+    fn removed(order_id: u64) -> PerpsEvent {
+        event(OrderRemoved {
+            order_id: Uint64::new(order_id),
+            pair_id: pair(),
+            user: USER,
+            reason: ReasonForOrderRemoval::Canceled,
+            client_order_id: None,
+        })
+    }
+
+    fn filled(order_id: u64, price: i128, remaining: i128) -> PerpsEvent {
+        event(OrderFilled {
+            order_id: Uint64::new(order_id),
+            pair_id: pair(),
+            user: USER,
+            fill_price: UsdPrice::new_int(price),
+            fill_size: Quantity::ZERO,
+            closing_size: Quantity::ZERO,
+            opening_size: Quantity::ZERO,
+            realized_pnl: UsdValue::ZERO,
+            realized_funding: None,
+            fee: UsdValue::ZERO,
+            client_order_id: None,
+            fill_id: None,
+            is_maker: Some(true),
+            remaining_order_size: Some(Quantity::new_int(remaining)),
+            remaining_position_size: None,
+        })
+    }
+
+    fn apply(view: &mut L2BookView, block: &L2BookBlock) -> Option<L2BookUpdate> {
+        let changes = block.changes.get(view.pair_id())?;
+        view.apply(block.block_height, changes).unwrap()
+    }
+
+    fn depth(size: i128, notional: i128) -> LiquidityDepth {
+        LiquidityDepth {
+            size: Quantity::new_int(size),
+            notional: UsdValue::new_int(notional),
+        }
+    }
+
+    #[test]
+    fn tracks_order_lifecycle() {
+        let mut book = L2Book::default();
+
+        // Two bids and an ask rest on the book.
+        let block = book.apply(
+            &batch(
+                1,
+                vec![
+                    persisted(1, 100, 2),
+                    persisted(2, 101, 1),
+                    persisted(3, 105, -3),
+                ],
+            ),
+            VAULT,
+        );
+        assert_eq!(block.changes[&pair()].len(), 3);
+
+        // The ask is partially filled, a bid canceled.
+        let block = book.apply(&batch(2, vec![filled(3, 105, -1), removed(2)]), VAULT);
+        assert_eq!(
+            block.changes[&pair()],
+            vec![
+                LevelChange {
+                    is_bid: false,
+                    price: UsdPrice::new_int(105),
+                    size: Quantity::new_int(-2),
+                    notional: UsdValue::new_int(-210),
+                },
+                LevelChange {
+                    is_bid: true,
+                    price: UsdPrice::new_int(101),
+                    size: Quantity::new_int(-1),
+                    notional: UsdValue::new_int(-101),
+                },
+            ]
+        );
+
+        // A taker's fill refers to an order not on the book: ignored.
+        let block = book.apply(&batch(3, vec![filled(99, 105, 0)]), VAULT);
+        assert!(block.changes.is_empty());
+
+        let view = book.view(pair(), UsdPrice::new_int(1)).unwrap();
+        assert_eq!(
+            view.snapshot(),
+            L2BookUpdate::Snapshot {
+                seq: 3,
+                bids: BTreeMap::from([(UsdPrice::new_int(100), depth(2, 200))]),
+                asks: BTreeMap::from([(UsdPrice::new_int(105), depth(1, 105))]),
+            }
+        );
+    }
+
+    #[test]
+    fn vault_refresh_replaces_vault_orders() {
+        let mut book = L2Book::default();
+
+        let vault_orders = |ids: [u64; 2], bid: i128, ask: i128| {
+            event(VaultOrdersRefreshed {
+                orders: vec![
+                    VaultOrder {
+                        order_id: Uint64::new(ids[0]),
+                        pair_id: pair(),
+                        limit_price: UsdPrice::new_int(bid),
+                        size: Quantity::new_int(1),
+                    },
+                    VaultOrder {
+                        order_id: Uint64::new(ids[1]),
+                        pair_id: pair(),
+                        limit_price: UsdPrice::new_int(ask),
+                        size: Quantity::new_int(-1),
+                    },
+                ],
+            })
+        };
+
+        book.apply(
+            &batch(1, vec![persisted(1, 98, 1), vault_orders([2, 3], 99, 101)]),
+            VAULT,
+        );
+
+        // The next refresh re-quotes the same bid, and moves the ask. The
+        // user's order is untouched.
+        let block = book.apply(&batch(2, vec![vault_orders([4, 5], 99, 102)]), VAULT);
+        assert_eq!(
+            block.changes[&pair()],
+            vec![
+                LevelChange {
+                    is_bid: false,
+                    price: UsdPrice::new_int(101),
+                    size: Quantity::new_int(-1),
+                    notional: UsdValue::new_int(-101),
+                },
+                LevelChange {
+                    is_bid: false,
+                    price: UsdPrice::new_int(102),
+                    size: Quantity::new_int(1),
+                    notional: UsdValue::new_int(102),
+                },
+            ]
+        );
+
+        let view = book.view(pair(), UsdPrice::new_int(1)).unwrap();
+        assert_eq!(
+            view.snapshot(),
+            L2BookUpdate::Snapshot {
+                seq: 2,
+                bids: BTreeMap::from([
+                    (UsdPrice::new_int(98), depth(1, 98)),
+                    (UsdPrice::new_int(99), depth(1, 99)),
+                ]),
+                asks: BTreeMap::from([(UsdPrice::new_int(102), depth(1, 102))]),
+            }
+        );
+    }
+
+    #[test]
+    fn view_buckets_changes_with_sequence_numbers() {
+        let mut book = L2Book::default();
+        book.seed(
+            Some(10),
+            [
+                (
+                    Uint64::new(1),
+                    pair(),
+                    UsdPrice::new_int(101),
+                    Quantity::new_int(1),
+                    false,
+                ),
+                (
+                    Uint64::new(2),
+                    pair(),
+                    UsdPrice::new_int(109),
+                    Quantity::new_int(-1),
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+        // Bids floor and asks ceil to the bucket size.
+        let mut view = book.view(pair(), UsdPrice::new_int(10)).unwrap();
+        assert_eq!(
+            view.snapshot(),
+            L2BookUpdate::Snapshot {
+                seq: 10,
+                bids: BTreeMap::from([(UsdPrice::new_int(100), depth(1, 101))]),
+                asks: BTreeMap::from([(UsdPrice::new_int(110), depth(1, 109))]),
+            }
+        );
+
+        // A block that doesn't touch the pair produces no frame.
+        let block = book.apply(&batch(11, vec![]), VAULT);
+        assert_eq!(apply(&mut view, &block), None);
+
+        // A new bid lands in the existing bucket; the ask is filled away.
+        let block = book.apply(
+            &batch(12, vec![persisted(3, 105, 2), filled(2, 109, 0)]),
+            VAULT,
+        );
+        assert_eq!(
+            apply(&mut view, &block),
+            Some(L2BookUpdate::Delta {
+                seq: 12,
+                prev_seq: 10,
+                bids: BTreeMap::from([(UsdPrice::new_int(100), depth(3, 311))]),
+                asks: BTreeMap::from([(UsdPrice::new_int(110), depth(0, 0))]),
+            })
+        );
+
+        // A move within a bucket that nets to zero produces no frame.
+        let block = book.apply(&batch(13, vec![removed(3), persisted(4, 105, 2)]), VAULT);
+        assert!(block.changes.is_empty());
+        assert_eq!(apply(&mut view, &block), None);
+
+        // The view stays in sync with a fresh one.
+        let fresh = book.view(pair(), UsdPrice::new_int(10)).unwrap();
+        assert_eq!(view.bids, fresh.bids);
+        assert_eq!(view.asks, fresh.asks);
+    }
+}
@@ -1,13 +1,14 @@
 //! Validator-side, in-memory, low-latency event streaming for Dango.
 //!
-//! This crate provides two ephemeral, purely in-memory real-time feeds, each a
-//! ring of the last `N` blocks broadcast live, in-process with the state
+//! This crate provides three ephemeral, purely in-memory real-time feeds, each
+//! a ring of the last `N` blocks broadcast live, in-process with the state
 //! machine (no validator -> indexer-node hop) — the lowest-latency surface for
 //! real-time data, served to clients over the WebSocket `/ws` endpoint as the
-//! `perpsEvents` and `fullBlock` channels:
+//! `perpsEvents`, `fullBlock`, and `l2Book` channels:
 //!
 //! - per-block perps-exchange contract events, for bots and algo-traders.
 //! - each finalized block in full (`Block` + `BlockOutcome`).
+//! - per-block changes to the perps order book, for market makers.
 //!
 //! It implements [`dango_app::Indexer`] — but, despite the name, it does no
 //! durable indexing. It only maintains in-memory state for real-time
@@ -18,12 +19,14 @@
 //! - [`RecentStream`] — the generic in-memory ring + live broadcast, with a
 //!   reliable subscription builder (snapshot then live, in strict height order,
 //!   no silent drops). It fixes the `event_by_addresses` failure modes; see its
-//!   module docs. It is instantiated twice: over [`PerpsEventBlock`] and over
-//!   [`BlockAndOutcome`].
+//!   module docs. It is instantiated three times: over [`PerpsEventBlock`],
+//!   [`BlockAndOutcome`], and [`L2BookBlock`].
 //! - [`Indexer`] — stashes each block at `index_block` (FinalizeBlock) and
-//!   publishes both rings from `post_indexing`, in height order, once the
+//!   publishes the rings from `post_indexing`, in height order, once the
 //!   block is committed (the perps address it also needs only arrives with
-//!   `app_cfg` there).
+//!   `app_cfg` there). It also keeps the perps order book in memory, seeded
+//!   from storage at `start` and updated from each block's perps events; see
+//!   the `l2_book` module docs.
 //! - [`Context`] — the reader handle the httpd holds; the `/ws` WebSocket
 //!   handler lives in the httpd crate and drives [`RecentStream::subscribe`].
 //!
@@ -35,7 +38,8 @@
 
 mod context;
 mod indexer;
+mod l2_book;
 mod perps_events;
 mod recent_stream;
 
-pub use {context::*, indexer::*, perps_events::*, recent_stream::*};
+pub use {context::*, indexer::*, l2_book::*, perps_events::*, recent_stream::*};
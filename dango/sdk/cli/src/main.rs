@@ -1,6 +1,7 @@
 mod config;
 mod home_directory;
 mod keys;
+mod perps;
 mod prompt;
 mod query;
 mod tx;
@@ -28,6 +29,10 @@ enum Command {
     #[command(subcommand, next_display_order = None)]
     Keys(keys::KeysCmd),
 
+    /// Interact with the perpetual futures exchange
+    #[command(next_display_order = None)]
+    Perps(perps::PerpsCmd),
+
     /// Make a query [alias: q]
     #[command(next_display_order = None, alias = "q")]
     Query(query::QueryCmd),
@@ -44,6 +49,7 @@ async fn main() -> anyhow::Result<()> {
 
     match cli.command {
         Command::Keys(cmd) => cmd.run(app_dir.keys_dir())?,
+        Command::Perps(cmd) => cmd.run(app_dir).await?,
         Command::Query(cmd) => cmd.run(app_dir).await?,
         Command::Tx(cmd) => cmd.run(app_dir).await?,
     }
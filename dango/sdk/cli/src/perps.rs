@@ -0,0 +1,683 @@
+use {
+    crate::{
+        config::ClientConfig,
+        home_directory::HomeDirectory,
+        prompt::{print_json_pretty, print_table},
+        tx::SignerArgs,
+    },
+    anyhow::{anyhow, ensure},
+    clap::{Args, Parser, Subcommand, ValueEnum},
+    colored::Colorize,
+    dango_config_parser::parse_config,
+    dango_math::Uint128,
+    dango_order_book::{
+        ChildOrder, ClientOrderId, Dimensionless, LiquidityDepth, OrderId, OrderKind, PairId,
+        Quantity, SelfTradePreventionMode, TimeInForce, TriggerDirection, UsdPrice, UsdValue,
+    },
+    dango_primitives::{Addr, Coins, JsonDeExt, Message, NonEmpty, QueryClientExt},
+    dango_sdk::HttpClient,
+    dango_types::{
+        config::AppConfig,
+        constants::usdc,
+        perps::{
+            self, CancelOrderRequest, ExecuteMsg, SubmitOrCancelOrderRequest, SubmitOrderRequest,
+            TraderMsg, VaultMsg,
+        },
+    },
+    std::fmt::Display,
+};
+
+#[derive(Parser)]
+pub struct PerpsCmd {
+    #[command(subcommand)]
+    subcmd: SubCmd,
+}
+
+#[derive(Subcommand)]
+enum SubCmd {
+    /// Deposit USDC into the sender's margin account
+    Deposit {
+        /// Amount of USDC in the smallest unit (1 USDC = 1000000)
+        amount: Uint128,
+        /// Credit the deposit to this address instead of the sender's
+        #[arg(long)]
+        to: Option<Addr>,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Withdraw margin as USDC
+    Withdraw {
+        /// Amount of margin in USD
+        amount: UsdValue,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Submit a market or limit order
+    Order {
+        /// Whether to buy (go long) or sell (go short)
+        side: Side,
+        /// Trading pair, e.g. perp/btcusd
+        pair_id: PairId,
+        /// Order size in units of the base asset
+        size: Quantity,
+        /// Limit price; a market order is submitted if omitted
+        #[arg(long)]
+        limit_price: Option<UsdPrice>,
+        /// Time in force of a limit order
+        #[arg(long, value_enum, default_value_t = TimeInForceArg::Gtc)]
+        time_in_force: TimeInForceArg,
+        /// Caller-assigned ID of a limit order, which it can be canceled by
+        #[arg(long)]
+        client_order_id: Option<ClientOrderId>,
+        /// Max slippage relative to the oracle price of a market order, and of
+        /// the take-profit and stop-loss orders, e.g. 0.01 for 1%
+        #[arg(long)]
+        max_slippage: Option<Dimensionless>,
+        /// Only reduce the existing position
+        #[arg(long)]
+        reduce_only: bool,
+        /// Trigger price of a take-profit order attached to the position
+        #[arg(long)]
+        tp: Option<UsdPrice>,
+        /// Trigger price of a stop-loss order attached to the position
+        #[arg(long)]
+        sl: Option<UsdPrice>,
+        /// What to cancel if the order crosses a resting order of the sender
+        #[arg(long, value_enum, default_value_t = StpModeArg::ExpireMaker)]
+        stp_mode: StpModeArg,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Cancel one or all resting limit orders
+    Cancel {
+        #[command(flatten)]
+        target: CancelTarget,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Submit and cancel orders atomically
+    Batch {
+        /// JSON array of actions, e.g. `[{"cancel":"all"},{"submit":{...}}]`
+        actions: String,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Place take-profit and/or stop-loss orders on an open position
+    TpSl {
+        /// Trading pair, e.g. perp/btcusd
+        pair_id: PairId,
+        /// Trigger price of the take-profit order
+        #[arg(long)]
+        tp: Option<UsdPrice>,
+        /// Trigger price of the stop-loss order
+        #[arg(long)]
+        sl: Option<UsdPrice>,
+        /// Size to close in units of the base asset [default: entire position]
+        #[arg(long)]
+        size: Option<Quantity>,
+        /// Max slippage relative to the oracle price when triggered
+        #[arg(long)]
+        max_slippage: Dimensionless,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Manage liquidity in the counterparty vault
+    #[command(subcommand)]
+    Vault(VaultCmd),
+    /// Show a user's margin and positions
+    Positions {
+        /// User's address
+        user: Addr,
+        /// Print the raw JSON response instead of tables
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a user's resting limit orders
+    Orders {
+        /// User's address
+        user: Addr,
+        /// Print the raw JSON response instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the order book depth of a pair
+    Book {
+        /// Trading pair, e.g. perp/btcusd
+        pair_id: PairId,
+        /// Price bucket size; must be one of the pair's configured bucket sizes
+        bucket_size: UsdPrice,
+        /// Maximum number of price levels on each side
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Print the raw JSON response instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VaultCmd {
+    /// Move margin into the vault in exchange for vault shares
+    Add {
+        /// Amount of margin in USD
+        amount: UsdValue,
+        /// Revert if fewer than this many shares are minted
+        #[arg(long)]
+        min_shares: Option<Uint128>,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Burn vault shares to withdraw margin after the cooldown period
+    Remove {
+        /// Number of vault shares to burn
+        shares: Uint128,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct CancelTarget {
+    /// ID of the order to cancel
+    order_id: Option<OrderId>,
+    /// Cancel the order with this client order ID
+    #[arg(long)]
+    client_order_id: Option<ClientOrderId>,
+    /// Cancel all orders
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TimeInForceArg {
+    Gtc,
+    Ioc,
+    Post,
+}
+
+impl From<TimeInForceArg> for TimeInForce {
+    fn from(arg: TimeInForceArg) -> Self {
+        match arg {
+            TimeInForceArg::Gtc => TimeInForce::GoodTilCanceled,
+            TimeInForceArg::Ioc => TimeInForce::ImmediateOrCancel,
+            TimeInForceArg::Post => TimeInForce::PostOnly,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StpModeArg {
+    ExpireMaker,
+    ExpireTaker,
+    ExpireBoth,
+    DecrementAndCancel,
+}
+
+impl From<StpModeArg> for SelfTradePreventionMode {
+    fn from(arg: StpModeArg) -> Self {
+        match arg {
+            StpModeArg::ExpireMaker => SelfTradePreventionMode::ExpireMaker,
+            StpModeArg::ExpireTaker => SelfTradePreventionMode::ExpireTaker,
+            StpModeArg::ExpireBoth => SelfTradePreventionMode::ExpireBoth,
+            StpModeArg::DecrementAndCancel => SelfTradePreventionMode::DecrementAndCancel,
+        }
+    }
+}
+
+impl PerpsCmd {
+    pub async fn run(self, app_dir: HomeDirectory) -> anyhow::Result<()> {
+        let cfg: ClientConfig = parse_config(app_dir.config_file())?;
+
+        let client = HttpClient::new(&cfg.url)?;
+
+        let perps = client
+            .query_app_config::<AppConfig>()
+            .await?
+            .addresses
+            .perps;
+
+        let (signer, msgs) = match self.subcmd {
+            SubCmd::Deposit { amount, to, signer } => {
+                let funds = Coins::one(usdc::DENOM.clone(), amount)?;
+                let msg = ExecuteMsg::Trade(TraderMsg::Deposit { to });
+                (signer, vec![Message::execute(perps, &msg, funds)?])
+            },
+            SubCmd::Withdraw { amount, signer } => {
+                let msg = ExecuteMsg::Trade(TraderMsg::Withdraw { amount });
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::Order {
+                side,
+                pair_id,
+                size,
+                limit_price,
+                time_in_force,
+                client_order_id,
+                max_slippage,
+                reduce_only,
+                tp,
+                sl,
+                stp_mode,
+                signer,
+            } => {
+                ensure!(size.is_positive(), "size must be positive");
+
+                let kind = match limit_price {
+                    Some(limit_price) => OrderKind::Limit {
+                        limit_price,
+                        time_in_force: time_in_force.into(),
+                        client_order_id,
+                    },
+                    None => OrderKind::Market {
+                        max_slippage: max_slippage.ok_or_else(|| {
+                            anyhow!("`--max-slippage` is required for a market order")
+                        })?,
+                    },
+                };
+
+                let child_order = |trigger_price| -> anyhow::Result<ChildOrder> {
+                    Ok(ChildOrder {
+                        trigger_price,
+                        max_slippage: max_slippage.ok_or_else(|| {
+                            anyhow!("`--max-slippage` is required for take-profit and stop-loss")
+                        })?,
+                        size: None,
+                    })
+                };
+
+                let msg = ExecuteMsg::Trade(TraderMsg::SubmitOrder(SubmitOrderRequest {
+                    pair_id,
+                    size: signed_size(side, size)?,
+                    kind,
+                    reduce_only,
+                    tp: tp.map(child_order).transpose()?,
+                    sl: sl.map(child_order).transpose()?,
+                    stp_mode: stp_mode.into(),
+                }));
+
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::Cancel { target, signer } => {
+                let req = match target {
+                    CancelTarget {
+                        order_id: Some(order_id),
+                        ..
+                    } => CancelOrderRequest::One(order_id),
+                    CancelTarget {
+                        client_order_id: Some(client_order_id),
+                        ..
+                    } => CancelOrderRequest::OneByClientOrderId(client_order_id),
+                    _ => CancelOrderRequest::All,
+                };
+
+                let msg = ExecuteMsg::Trade(TraderMsg::CancelOrder(req));
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::Batch { actions, signer } => {
+                let actions =
+                    actions.deserialize_json::<NonEmpty<Vec<SubmitOrCancelOrderRequest>>>()?;
+                let msg = ExecuteMsg::Trade(TraderMsg::BatchUpdateOrders(actions));
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::TpSl {
+                pair_id,
+                tp,
+                sl,
+                size,
+                max_slippage,
+                signer,
+            } => {
+                ensure!(
+                    tp.is_some() || sl.is_some(),
+                    "at least one of `--tp` and `--sl` is required"
+                );
+
+                // The trigger directions and the sign of the closing size
+                // depend on whether the position is long or short.
+                let position_size = client
+                    .query_wasm_smart(
+                        perps,
+                        perps::QueryUserStateRequest {
+                            user: signer.address,
+                        },
+                    )
+                    .await?
+                    .and_then(|user_state| user_state.positions.get(&pair_id).map(|p| p.size))
+                    .ok_or_else(|| anyhow!("no position in pair {pair_id}"))?;
+
+                let is_long = position_size.is_positive();
+
+                let size = size
+                    .map(|size| {
+                        ensure!(size.is_positive(), "size must be positive");
+                        if is_long {
+                            Ok(size.checked_neg()?)
+                        } else {
+                            Ok(size)
+                        }
+                    })
+                    .transpose()?;
+
+                let (tp_direction, sl_direction) = if is_long {
+                    (TriggerDirection::Above, TriggerDirection::Below)
+                } else {
+                    (TriggerDirection::Below, TriggerDirection::Above)
+                };
+
+                let msgs = [(tp, tp_direction), (sl, sl_direction)]
+                    .into_iter()
+                    .filter_map(|(trigger_price, trigger_direction)| {
+                        trigger_price.map(|trigger_price| (trigger_price, trigger_direction))
+                    })
+                    .map(|(trigger_price, trigger_direction)| {
+                        let msg = ExecuteMsg::Trade(TraderMsg::SubmitConditionalOrder {
+                            pair_id: pair_id.clone(),
+                            size,
+                            trigger_price,
+                            trigger_direction,
+                            max_slippage,
+                            limit_price: None,
+                            trailing_offset: None,
+                        });
+                        Message::execute(perps, &msg, Coins::new())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                (signer, msgs)
+            },
+            SubCmd::Vault(VaultCmd::Add {
+                amount,
+                min_shares,
+                signer,
+            }) => {
+                let msg = ExecuteMsg::Vault(VaultMsg::AddLiquidity {
+                    amount,
+                    min_shares_to_mint: min_shares,
+                });
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::Vault(VaultCmd::Remove { shares, signer }) => {
+                let msg = ExecuteMsg::Vault(VaultMsg::RemoveLiquidity {
+                    shares_to_burn: shares,
+                });
+                (signer, vec![Message::execute(perps, &msg, Coins::new())?])
+            },
+            SubCmd::Positions { user, json } => {
+                return query_positions(&client, perps, user, json).await;
+            },
+            SubCmd::Orders { user, json } => {
+                return query_orders(&client, perps, user, json).await;
+            },
+            SubCmd::Book {
+                pair_id,
+                bucket_size,
+                limit,
+                json,
+            } => {
+                return query_book(&client, perps, pair_id, bucket_size, limit, json).await;
+            },
+        };
+
+        signer
+            .send(&app_dir, &cfg, &client, NonEmpty::new_unchecked(msgs))
+            .await
+    }
+}
+
+async fn query_positions(
+    client: &HttpClient,
+    perps: Addr,
+    user: Addr,
+    json: bool,
+) -> anyhow::Result<()> {
+    let user_state = client
+        .query_wasm_smart(
+            perps,
+            perps::QueryUserStateExtendedRequest {
+                user,
+                include_equity: false,
+                include_available_margin: false,
+                include_maintenance_margin: false,
+                include_unrealized_pnl: false,
+                include_unrealized_funding: false,
+                include_liquidation_price: false,
+                include_all: true,
+            },
+        )
+        .await?;
+
+    if json {
+        return print_json_pretty(user_state);
+    }
+
+    print_table(
+        ["ACCOUNT", "USD"],
+        &[
+            ["margin".to_string(), user_state.margin.to_string()],
+            ["equity".to_string(), or_dash(user_state.equity)],
+            [
+                "available margin".to_string(),
+                or_dash(user_state.available_margin),
+            ],
+            [
+                "maintenance margin".to_string(),
+                or_dash(user_state.maintenance_margin),
+            ],
+            [
+                "reserved margin".to_string(),
+                user_state.reserved_margin.to_string(),
+            ],
+        ],
+    );
+
+    println!();
+
+    let positions = user_state
+        .positions
+        .iter()
+        .map(|(pair_id, position)| {
+            [
+                pair_id.to_string(),
+                side_of(position.size).to_string(),
+                position.size.to_string(),
+                position.entry_price.to_string(),
+                or_dash(position.unrealized_pnl),
+                or_dash(position.unrealized_funding),
+                or_dash(position.liquidation_price),
+                match user_state.isolated_margins.get(pair_id) {
+                    Some(margin) => format!("isolated ({margin})"),
+                    None => "cross".to_string(),
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(
+        [
+            "PAIR",
+            "SIDE",
+            "SIZE",
+            "ENTRY PRICE",
+            "UNREALIZED PNL",
+            "UNREALIZED FUNDING",
+            "LIQUIDATION PRICE",
+            "MARGIN",
+        ],
+        &positions,
+    );
+
+    let conditional_orders = user_state
+        .positions
+        .iter()
+        .flat_map(|(pair_id, position)| {
+            [
+                (TriggerDirection::Above, &position.conditional_orders_above),
+                (TriggerDirection::Below, &position.conditional_orders_below),
+            ]
+            .into_iter()
+            .flat_map(move |(trigger_direction, orders)| {
+                orders.values().map(move |order| {
+                    [
+                        order.order_id.to_string(),
+                        pair_id.to_string(),
+                        match trigger_direction {
+                            TriggerDirection::Above => "above".to_string(),
+                            TriggerDirection::Below => "below".to_string(),
+                        },
+                        order.trigger_price.to_string(),
+                        order
+                            .size
+                            .map_or_else(|| "entire position".to_string(), |s| s.to_string()),
+                        or_dash(order.limit_price),
+                    ]
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if !conditional_orders.is_empty() {
+        println!();
+        print_table(
+            [
+                "ID",
+                "PAIR",
+                "TRIGGER",
+                "TRIGGER PRICE",
+                "SIZE",
+                "LIMIT PRICE",
+            ],
+            &conditional_orders,
+        );
+    }
+
+    Ok(())
+}
+
+async fn query_orders(
+    client: &HttpClient,
+    perps: Addr,
+    user: Addr,
+    json: bool,
+) -> anyhow::Result<()> {
+    let orders = client
+        .query_wasm_smart(perps, perps::QueryOrdersByUserRequest { user })
+        .await?;
+
+    if json {
+        return print_json_pretty(orders);
+    }
+
+    let rows = orders
+        .iter()
+        .map(|(order_id, order)| {
+            [
+                order_id.to_string(),
+                order.pair_id.to_string(),
+                side_of(order.size).to_string(),
+                order.size.to_string(),
+                order.limit_price.to_string(),
+                order.reduce_only.to_string(),
+                order.reserved_margin.to_string(),
+                or_dash(order.client_order_id),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(
+        [
+            "ID",
+            "PAIR",
+            "SIDE",
+            "SIZE",
+            "LIMIT PRICE",
+            "REDUCE ONLY",
+            "RESERVED MARGIN",
+            "CLIENT ID",
+        ],
+        &rows,
+    );
+
+    Ok(())
+}
+
+async fn query_book(
+    client: &HttpClient,
+    perps: Addr,
+    pair_id: PairId,
+    bucket_size: UsdPrice,
+    limit: Option<u32>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let depth = client
+        .query_wasm_smart(
+            perps,
+            perps::QueryLiquidityDepthRequest {
+                pair_id,
+                bucket_size,
+                limit,
+            },
+        )
+        .await?;
+
+    if json {
+        return print_json_pretty(depth);
+    }
+
+    let level = |side: &str, price: &UsdPrice, depth: &LiquidityDepth| {
+        [
+            side.to_string(),
+            price.to_string(),
+            depth.size.to_string(),
+            depth.notional.to_string(),
+        ]
+    };
+
+    // Both sides are listed from the highest price to the lowest, so that the
+    // best ask and the best bid meet in the middle.
+    let rows = depth
+        .asks
+        .iter()
+        .rev()
+        .map(|(price, depth)| level(&"ask".red().to_string(), price, depth))
+        .chain(
+            depth
+                .bids
+                .iter()
+                .rev()
+                .map(|(price, depth)| level(&"bid".green().to_string(), price, depth)),
+        )
+        .collect::<Vec<_>>();
+
+    print_table(["SIDE", "PRICE", "SIZE", "NOTIONAL"], &rows);
+
+    Ok(())
+}
+
+fn signed_size(side: Side, size: Quantity) -> anyhow::Result<Quantity> {
+    match side {
+        Side::Buy => Ok(size),
+        Side::Sell => Ok(size.checked_neg()?),
+    }
+}
+
+fn side_of(size: Quantity) -> &'static str {
+    if size.is_negative() {
+        "short"
+    } else {
+        "long"
+    }
+}
+
+fn or_dash<T>(value: Option<T>) -> String
+where
+    T: Display,
+{
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
@@ -1,4 +1,6 @@
-use {colored_json::ToColoredJson, dango_primitives::JsonSerExt, serde::Serialize};
+use {
+    colored::Colorize, colored_json::ToColoredJson, dango_primitives::JsonSerExt, serde::Serialize,
+};
 
 pub fn print_json_pretty<T>(data: T) -> anyhow::Result<()>
 where
@@ -39,3 +41,35 @@ where
         .with_prompt(prompt.to_string())
         .interact()
 }
+
+/// Print rows of cells as a table, with the columns padded to equal width and
+/// the header in bold.
+pub fn print_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) {
+    let mut widths = headers.map(|header| header.chars().count());
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header = headers
+        .iter()
+        .zip(widths)
+        .map(|(header, width)| format!("{header:<width$}"))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    println!("{}", header.trim_end().bold());
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        println!("{}", line.trim_end());
+    }
+}
@@ -4,7 +4,7 @@ use {
         home_directory::HomeDirectory,
         prompt::{confirm, print_json_pretty, read_password},
     },
-    clap::{Parser, Subcommand},
+    clap::{Args, Parser, Subcommand},
     colored::Colorize,
     dango_app::GAS_COSTS,
     dango_config_parser::parse_config,
@@ -19,9 +19,20 @@ use {
 
 #[derive(Parser)]
 pub struct TxCmd {
+    #[command(flatten)]
+    signer: SignerArgs,
+
+    #[command(subcommand)]
+    subcmd: SubCmd,
+}
+
+/// Options for signing and broadcasting a transaction, shared by all commands
+/// that send one.
+#[derive(Args)]
+pub struct SignerArgs {
     /// Transaction sender's address
     #[arg(long)]
-    address: Addr,
+    pub address: Addr,
 
     /// Name of the key to sign transactions
     #[arg(long)]
@@ -42,9 +53,6 @@ pub struct TxCmd {
     /// Simulate gas usage without submitting the transaction to mempool.
     #[arg(long)]
     simulate: bool,
-
-    #[command(subcommand)]
-    subcmd: SubCmd,
 }
 
 #[derive(Subcommand)]
@@ -184,6 +192,22 @@ impl TxCmd {
 
         let client = HttpClient::new(&cfg.url)?;
 
+        self.signer
+            .send(&app_dir, &cfg, &client, NonEmpty::new_unchecked(vec![msg]))
+            .await
+    }
+}
+
+impl SignerArgs {
+    /// Sign a transaction containing the given messages and, unless simulating,
+    /// broadcast it after the user's confirmation.
+    pub async fn send(
+        self,
+        app_dir: &HomeDirectory,
+        cfg: &ClientConfig,
+        client: &HttpClient,
+        msgs: NonEmpty<Vec<Message>>,
+    ) -> anyhow::Result<()> {
         let mut signer = {
             let key_path = app_dir.keys_dir().join(format!("{}.json", self.key));
             let password = read_password("🔑 Enter the password to decrypt the key".bold())?;
@@ -195,19 +219,18 @@ impl TxCmd {
             let signer = if let Some(user_index) = self.user_index {
                 signer.with_user_index(user_index)
             } else {
-                signer.with_query_user_index(&client).await?
+                signer.with_query_user_index(client).await?
             };
 
             // Set or query the signer's nonce.
             if let Some(nonce) = self.nonce {
                 signer.with_nonce(nonce)
             } else {
-                signer.with_query_nonce(&client).await?
+                signer.with_query_nonce(client).await?
             }
         };
 
         if self.simulate {
-            let msgs = NonEmpty::new_unchecked(vec![msg]);
             let unsigned_tx = signer.unsigned_transaction(msgs, &cfg.chain_id)?;
             let outcome = client.simulate(unsigned_tx).await?;
             print_json_pretty(outcome)?;
@@ -225,7 +248,7 @@ impl TxCmd {
             };
 
             let maybe_res = client
-                .send_message_with_confirmation(&mut signer, msg, gas_opt, &cfg.chain_id, |tx| {
+                .send_messages_with_confirmation(&mut signer, msgs, gas_opt, &cfg.chain_id, |tx| {
                     print_json_pretty(tx)?;
                     Ok(confirm("🤔 Broadcast transaction?".bold())?)
                 })
@@ -163,6 +163,7 @@ fn sign_submit_order(
             reduce_only: false,
             tp: None,
             sl: None,
+            stp_mode: Default::default(),
         })),
         Coins::new(),
     )?;
@@ -5,11 +5,12 @@ pub mod archive;
 mod client;
 mod keystore;
 mod secret;
+mod session;
 mod signer;
 mod subscription;
 mod ws;
 
 pub use {
     archive::ArchiveClient, client::*, dango_indexer_graphql_types::*, keystore::*, secret::*,
-    signer::*, subscription::*, ws::*,
+    session::*, signer::*, subscription::*, ws::*,
 };
@@ -2,7 +2,7 @@ use {
     bip32::{Mnemonic, XPrv},
     dango_auth::VerifyData,
     dango_primitives::{Addr, ByteArray, Hash256, HashExt, JsonSerExt, SignData},
-    dango_types::auth::{Eip712Signature, Key, SignDoc, Signature},
+    dango_types::auth::{Eip712Signature, Key, SessionInfo, SignDoc, Signature},
     k256::{ecdsa::signature::hazmat::PrehashSigner, elliptic_curve::Generate},
 };
 
@@ -40,6 +40,9 @@ pub trait Secret: Sized {
 
     /// Sign the given sign doc.
     fn sign_transaction(&self, sign_doc: SignDoc) -> anyhow::Result<Signature>;
+
+    /// Sign the given session info, authorizing the session key therein.
+    fn sign_session_info(&self, session_info: SessionInfo) -> anyhow::Result<Signature>;
 }
 
 // --------------------------------- Secp256r1 ---------------------------------
@@ -109,12 +112,25 @@ impl Secret for Secp256k1 {
     }
 
     fn sign_transaction(&self, sign_doc: SignDoc) -> anyhow::Result<Signature> {
-        let sign_data = sign_doc.to_sign_data()?;
+        self.sign(&sign_doc).map(Signature::Secp256k1)
+    }
+
+    fn sign_session_info(&self, session_info: SessionInfo) -> anyhow::Result<Signature> {
+        self.sign(&session_info).map(Signature::Secp256k1)
+    }
+}
+
+impl Secp256k1 {
+    /// Sign the given data, returning the raw signature.
+    pub fn sign<T>(&self, data: &T) -> anyhow::Result<ByteArray<64>>
+    where
+        T: SignData,
+        anyhow::Error: From<T::Error>,
+    {
+        let sign_data = data.to_sign_data()?;
         let signature: k256::ecdsa::Signature = self.inner.sign_prehash(&sign_data)?;
 
-        Ok(Signature::Secp256k1(ByteArray::from_inner(
-            signature.to_bytes().into(),
-        )))
+        Ok(ByteArray::from_inner(signature.to_bytes().into()))
     }
 }
 
@@ -163,11 +179,22 @@ impl Secret for Eip712 {
     }
 
     fn sign_transaction(&self, sign_doc: SignDoc) -> anyhow::Result<Signature> {
+        // `messages` is bound as an array of canonical JSON strings (EIP-712
+        // can't express the `Message` enum as a struct).
+        self.sign(VerifyData::Transaction(sign_doc))
+    }
+
+    fn sign_session_info(&self, session_info: SessionInfo) -> anyhow::Result<Signature> {
+        // `permissions`, if any, is bound as its canonical JSON string.
+        self.sign(VerifyData::Session(session_info))
+    }
+}
+
+impl Eip712 {
+    fn sign(&self, data: VerifyData) -> anyhow::Result<Signature> {
         // Build the typed data via `dango_auth`'s shared builder, so the SDK and
-        // the on-chain verifier produce byte-identical EIP-712 hashes. `messages`
-        // is bound as an array of canonical JSON strings (EIP-712 can't express
-        // the `Message` enum as a struct).
-        let data = dango_auth::build_eip712_typed_data(&VerifyData::Transaction(sign_doc))?;
+        // the on-chain verifier produce byte-identical EIP-712 hashes.
+        let data = dango_auth::build_eip712_typed_data(&data)?;
 
         let sign_bytes = data.eip712_signing_hash()?;
         let (signature, recovery_id) = self.inner.inner.sign_prehash_recoverable(&sign_bytes.0);
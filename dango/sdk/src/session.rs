@@ -0,0 +1,348 @@
+use {
+    crate::{Secp256k1, Secret, SingleSigner},
+    dango_primitives::{
+        Addr, Addressable, JsonSerExt, Message, NonEmpty, QueryClient, QueryClientExt, Signer,
+        StdError, StdResult, Timestamp, Tx, UnsignedTx,
+    },
+    dango_types::{
+        account,
+        account_factory::UserIndex,
+        auth::{
+            Credential, Metadata, Nonce, SessionCredential, SessionInfo, SessionPermissions,
+            SignDoc, StandardCredential,
+        },
+        signer::SequencedSigner,
+    },
+};
+
+/// Utility for signing transactions with a session key, on behalf of a Dango
+/// single-signature account.
+///
+/// Created by [`SingleSigner::create_session`].
+#[derive(Debug)]
+pub struct SessionSigner {
+    pub address: Addr,
+    pub user_index: UserIndex,
+    pub session_key: Secp256k1,
+    pub session_info: SessionInfo,
+    /// The user key's signature of `session_info`.
+    pub authorization: StandardCredential,
+    pub nonce: Nonce,
+}
+
+impl<S> SingleSigner<S>
+where
+    S: Secret,
+{
+    /// Authorize a new random session key to sign transactions on this
+    /// account's behalf until `expire_at`.
+    ///
+    /// If `permissions` is provided, the session key may only execute the
+    /// contracts and messages it lists, and attach no more than its spend limit.
+    ///
+    /// The session key tracks its nonces separately from the user key. Its
+    /// first nonce must exceed the user key's most recent one, so it starts
+    /// from this signer's next nonce.
+    pub fn create_session(
+        &self,
+        chain_id: &str,
+        expire_at: Timestamp,
+        permissions: Option<SessionPermissions>,
+    ) -> anyhow::Result<SessionSigner> {
+        let session_key = Secp256k1::new_random();
+
+        let session_info = SessionInfo {
+            chain_id: chain_id.to_string(),
+            session_key: session_key.public_key().into(),
+            expire_at,
+            permissions,
+        };
+
+        let authorization = StandardCredential {
+            key_hash: self.secret.key_hash(),
+            signature: self.secret.sign_session_info(session_info.clone())?,
+        };
+
+        Ok(SessionSigner {
+            address: self.address,
+            user_index: self.user_index(),
+            session_key,
+            session_info,
+            authorization,
+            nonce: self.nonce(),
+        })
+    }
+}
+
+impl SessionSigner {
+    pub async fn query_next_nonce<C>(&self, client: &C) -> anyhow::Result<Nonce>
+    where
+        C: QueryClient,
+        anyhow::Error: From<C::Error>,
+    {
+        // If the session key hasn't sent any transaction yet, its first nonce
+        // must exceed the user key's latest seen nonce. Otherwise, use the
+        // session key's latest seen nonce + 1.
+        let session_nonces = client
+            .query_wasm_smart(
+                self.address,
+                account::QuerySessionSeenNoncesRequest {
+                    session_key: self.session_info.session_key,
+                },
+            )
+            .await?;
+
+        let latest = match session_nonces.last() {
+            Some(newest_nonce) => Some(*newest_nonce),
+            None => client
+                .query_wasm_smart(self.address, account::QuerySeenNoncesRequest {})
+                .await?
+                .last()
+                .copied(),
+        };
+
+        Ok(latest.map(|nonce| nonce + 1).unwrap_or(0))
+    }
+}
+
+impl Addressable for SessionSigner {
+    fn address(&self) -> Addr {
+        self.address
+    }
+}
+
+impl Signer for SessionSigner {
+    fn unsigned_transaction(
+        &self,
+        msgs: NonEmpty<Vec<Message>>,
+        chain_id: &str,
+    ) -> StdResult<UnsignedTx> {
+        Ok(UnsignedTx {
+            sender: self.address,
+            msgs,
+            data: Metadata {
+                chain_id: chain_id.to_string(),
+                user_index: self.user_index,
+                nonce: self.nonce,
+                expiry: None,
+            }
+            .to_json_value()?,
+        })
+    }
+
+    fn sign_transaction(
+        &mut self,
+        msgs: NonEmpty<Vec<Message>>,
+        chain_id: &str,
+        gas_limit: u64,
+    ) -> StdResult<Tx> {
+        let nonce = self.nonce;
+        self.nonce += 1;
+
+        let metadata = Metadata {
+            user_index: self.user_index,
+            chain_id: chain_id.to_string(),
+            nonce,
+            expiry: None,
+        };
+
+        let sign_doc = SignDoc {
+            gas_limit,
+            sender: self.address,
+            messages: msgs.clone(),
+            data: metadata.clone(),
+        };
+
+        let credential = Credential::Session(SessionCredential {
+            session_info: self.session_info.clone(),
+            session_signature: self
+                .session_key
+                .sign(&sign_doc)
+                .map_err(|err| StdError::host(err.to_string()))?,
+            authorization: self.authorization.clone(),
+        });
+
+        Ok(Tx {
+            sender: self.address,
+            gas_limit,
+            msgs,
+            data: metadata.to_json_value()?,
+            credential: credential.to_json_value()?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SequencedSigner for SessionSigner {
+    async fn query_nonce<C>(&self, client: &C) -> anyhow::Result<Nonce>
+    where
+        C: QueryClient,
+        anyhow::Error: From<C::Error>,
+    {
+        self.query_next_nonce(client).await
+    }
+
+    async fn update_nonce<C>(&mut self, client: &C) -> anyhow::Result<()>
+    where
+        C: QueryClient,
+        anyhow::Error: From<C::Error>,
+    {
+        self.nonce = self.query_next_nonce(client).await?;
+
+        Ok(())
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::Eip712,
+        dango_account_factory::USERS,
+        dango_auth::{account::STATUS, authenticate_tx},
+        dango_primitives::{
+            AuthMode, Coins, MockContext, MockQuerier, MockStorage, ResultExt, btree_map,
+        },
+        dango_types::{
+            account_factory::{User, Username},
+            auth::AccountStatus,
+            config::{AppAddresses, AppConfig},
+        },
+        serde_json::json,
+        std::collections::BTreeSet,
+    };
+
+    fn authenticate<S>(signer: &SingleSigner<S>, tx: Tx) -> anyhow::Result<()>
+    where
+        S: Secret,
+    {
+        let account_factory = Addr::mock(1);
+
+        let mut mock_storage = MockStorage::new();
+
+        STATUS
+            .save(&mut mock_storage, &AccountStatus::Active)
+            .unwrap();
+
+        let mock_querier = MockQuerier::new()
+            .with_raw_contract_storage(account_factory, |storage| {
+                let user = User {
+                    index: signer.user_index(),
+                    name: Username::default_for_index(signer.user_index()),
+                    accounts: btree_map! { 0u32 => signer.address },
+                    keys: btree_map! { signer.secret.key_hash() => signer.secret.key() },
+                };
+                USERS.save(storage, signer.user_index(), &user).unwrap();
+            })
+            .with_app_config(AppConfig {
+                addresses: AppAddresses {
+                    account_factory,
+                    // the other addresses don't matter
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mut mock_ctx = MockContext::default()
+            .with_chain_id("dango-1")
+            .with_contract(signer.address)
+            .with_storage(mock_storage)
+            .with_querier(mock_querier)
+            .with_mode(AuthMode::Finalize);
+
+        authenticate_tx(mock_ctx.as_auth(), tx, None)
+    }
+
+    fn perps_deposit() -> NonEmpty<Vec<Message>> {
+        NonEmpty::new_unchecked(vec![
+            Message::execute(
+                Addr::mock(3),
+                &json!({ "trade": { "deposit": {} } }),
+                Coins::one("bridge/usdc", 100).unwrap(),
+            )
+            .unwrap(),
+        ])
+    }
+
+    fn scoped_permissions() -> SessionPermissions {
+        SessionPermissions {
+            contracts: btree_map! {
+                Addr::mock(3) => BTreeSet::from(["trade.deposit".to_string()]),
+            },
+            spend_limit: Some(Coins::one("bridge/usdc", 100).unwrap()),
+        }
+    }
+
+    #[test]
+    fn sign_with_secp256k1_scoped_session_works() {
+        let signer = SingleSigner::new(Addr::mock(0), Secp256k1::new_random())
+            .with_nonce(0)
+            .with_user_index(123);
+
+        let mut session = signer
+            .create_session(
+                "dango-1",
+                Timestamp::from_nanos(u128::MAX),
+                Some(scoped_permissions()),
+            )
+            .unwrap();
+
+        let tx = session
+            .sign_transaction(perps_deposit(), "dango-1", 100_000_000)
+            .unwrap();
+
+        authenticate(&signer, tx).should_succeed();
+    }
+
+    #[test]
+    fn sign_with_eip712_scoped_session_works() {
+        let signer = SingleSigner::new(Addr::mock(0), Eip712::new_random())
+            .with_nonce(0)
+            .with_user_index(234);
+
+        let mut session = signer
+            .create_session(
+                "dango-1",
+                Timestamp::from_nanos(u128::MAX),
+                Some(scoped_permissions()),
+            )
+            .unwrap();
+
+        let tx = session
+            .sign_transaction(perps_deposit(), "dango-1", 100_000_000)
+            .unwrap();
+
+        authenticate(&signer, tx).should_succeed();
+    }
+
+    #[test]
+    fn scoped_session_rejects_other_messages() {
+        let signer = SingleSigner::new(Addr::mock(0), Secp256k1::new_random())
+            .with_nonce(0)
+            .with_user_index(345);
+
+        let mut session = signer
+            .create_session(
+                "dango-1",
+                Timestamp::from_nanos(u128::MAX),
+                Some(scoped_permissions()),
+            )
+            .unwrap();
+
+        let tx = session
+            .sign_transaction(
+                NonEmpty::new_unchecked(vec![
+                    Message::transfer(Addr::mock(2), Coins::one("bridge/usdc", 100).unwrap())
+                        .unwrap(),
+                ]),
+                "dango-1",
+                100_000_000,
+            )
+            .unwrap();
+
+        authenticate(&signer, tx).should_fail_with_error("only allowed to execute contracts");
+    }
+}
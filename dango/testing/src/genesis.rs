@@ -383,10 +383,15 @@ impl Preset for HyperlaneOption {
                 mock_arbitrum::DOMAIN => mock_validator_set.clone(),
                 mock_ethereum::DOMAIN => mock_validator_set,
             },
+            ism_routes: Default::default(),
             va_announce_fee_per_byte: Coin {
                 denom: usdc::DENOM.clone(),
                 amount: Uint128::new(100),
             },
+            igp_fee_denom: usdc::DENOM.clone(),
+            // No interchain gas is charged by default. Tests that exercise
+            // the IGP set gas configs at runtime.
+            igp_destination_gas_configs: Default::default(),
         }
     }
 }
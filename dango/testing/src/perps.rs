@@ -85,6 +85,7 @@ pub async fn create_perps_fill(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -104,6 +105,7 @@ pub async fn create_perps_fill(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -281,7 +281,7 @@ where
             .collect();
         let txs = self
             .app
-            .do_prepare_proposal(raw_txs, usize::MAX)
+            .do_prepare_proposal(new_block, raw_txs, usize::MAX)
             .into_iter()
             .map(|raw_tx| (raw_tx.deserialize_json().unwrap(), raw_tx.sha2_256()))
             .collect::<Vec<_>>();
@@ -12,6 +12,7 @@ use {
         },
         constants::{dango, eth, usdc},
     },
+    dango_vm_rust::ContractBuilder,
     std::collections::BTreeSet,
 };
 
@@ -618,3 +619,451 @@ fn query_supplies_works() {
             eth::DENOM.clone() => 20000000200000000000,
         });
 }
+
+#[tokio::test]
+async fn transferring_and_renouncing_namespace() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(Default::default());
+
+    let namespace = Part::new_unchecked("testing");
+    let denom = Denom::new_unchecked(["testing", "test"]);
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::SetNamespaceOwner {
+                namespace: namespace.clone(),
+                owner: accounts.user1.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Only the namespace owner can transfer the namespace.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.bank,
+            &bank::ExecuteMsg::TransferNamespace {
+                namespace: namespace.clone(),
+                new_owner: accounts.user2.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("sender does not own the namespace `testing`");
+
+    // The new owner must exist.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::TransferNamespace {
+                namespace: namespace.clone(),
+                new_owner: addr!("000000000000000000000000000000000000dead"),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("doesn't exist");
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::TransferNamespace {
+                namespace: namespace.clone(),
+                new_owner: accounts.user2.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            contracts.bank,
+            bank::QueryNamespaceOwnerRequest {
+                namespace: namespace.clone(),
+            },
+        )
+        .should_succeed_and_equal(accounts.user2.address());
+
+    // The old owner can no longer mint; the new owner can.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::Mint {
+                to: accounts.user1.address(),
+                coins: coins! { denom.clone() => 100 },
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("sender does not own the namespace `testing`");
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.bank,
+            &bank::ExecuteMsg::Mint {
+                to: accounts.user2.address(),
+                coins: coins! { denom.clone() => 100 },
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // The new owner renounces the namespace. Nobody can mint anymore.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.bank,
+            &bank::ExecuteMsg::RenounceNamespace {
+                namespace: namespace.clone(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            contracts.bank,
+            bank::QueryNamespaceOwnerRequest {
+                namespace: namespace.clone(),
+            },
+        )
+        .should_fail_with_error("data not found");
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.bank,
+            &bank::ExecuteMsg::Mint {
+                to: accounts.user2.address(),
+                coins: coins! { denom => 100 },
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("sender does not own the namespace `testing`");
+
+    // The chain owner can grant the namespace again.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::SetNamespaceOwner {
+                namespace,
+                owner: accounts.user1.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+}
+
+#[tokio::test]
+async fn revoking_namespace_owner() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(Default::default());
+
+    let namespace = Part::new_unchecked("testing");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::SetNamespaceOwner {
+                namespace: namespace.clone(),
+                owner: accounts.user1.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Only the chain owner can revoke. Not even the namespace owner.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::RevokeNamespaceOwner {
+                namespace: namespace.clone(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("you don't have the right, O you don't have the right");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::RevokeNamespaceOwner {
+                namespace: namespace.clone(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Revoking again fails, as the namespace isn't granted.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::RevokeNamespaceOwner {
+                namespace: namespace.clone(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("namespace `testing` isn't granted to anyone");
+
+    // The namespace can be granted to another account.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::SetNamespaceOwner {
+                namespace: namespace.clone(),
+                owner: accounts.user2.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            contracts.bank,
+            bank::QueryNamespaceOwnerRequest { namespace },
+        )
+        .should_succeed_and_equal(accounts.user2.address());
+}
+
+/// A send hook that rejects transfers from or to a blocked address.
+mod blocklist {
+    use {
+        anyhow::ensure,
+        dango_primitives::{Addr, ImmutableCtx, Json, JsonSerExt, MutableCtx, Response},
+        dango_storage::Item,
+        dango_types::bank::{SendHookQuery, SendHookQueryMsg, SendHookQueryResponse},
+    };
+
+    const BLOCKED: Item<Addr> = Item::new("blocked");
+
+    #[dango_primitives::derive(Serde)]
+    pub struct InstantiateMsg {
+        pub blocked: Addr,
+    }
+
+    pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+        BLOCKED.save(ctx.storage, &msg.blocked)?;
+
+        Ok(Response::new())
+    }
+
+    pub fn query(ctx: ImmutableCtx, msg: SendHookQueryMsg) -> anyhow::Result<Json> {
+        let SendHookQueryMsg::BeforeSendHook(SendHookQuery::BeforeSend { from, to, .. }) = msg;
+        let blocked = BLOCKED.load(ctx.storage)?;
+
+        ensure!(
+            from != blocked && to != blocked,
+            "address `{blocked}` is blocked"
+        );
+
+        Ok(SendHookQueryResponse::BeforeSend(()).to_json_value()?)
+    }
+}
+
+#[tokio::test]
+async fn send_hook_can_reject_transfers() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(Default::default());
+
+    let namespace = Part::new_unchecked("testing");
+    let denom = Denom::new_unchecked(["testing", "test"]);
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.bank,
+            &bank::ExecuteMsg::SetNamespaceOwner {
+                namespace: namespace.clone(),
+                owner: accounts.user1.address(),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::Mint {
+                to: accounts.user2.address(),
+                coins: coins! { denom.clone() => 100 },
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Deploy a hook that blocks user2.
+    let blocklist_code = ContractBuilder::new(Box::new(blocklist::instantiate))
+        .with_query(Box::new(blocklist::query))
+        .build();
+
+    let hook = suite
+        .upload_and_instantiate(
+            &mut accounts.owner,
+            blocklist_code,
+            &blocklist::InstantiateMsg {
+                blocked: accounts.user2.address(),
+            },
+            "blocklist",
+            Some("blocklist"),
+            None,
+            Coins::new(),
+        )
+        .await
+        .should_succeed()
+        .address;
+
+    // Only the namespace owner can set the hook, and the hook must exist.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.bank,
+            &bank::ExecuteMsg::SetSendHook {
+                namespace: namespace.clone(),
+                hook: Some(hook),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("sender does not own the namespace `testing`");
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::SetSendHook {
+                namespace: namespace.clone(),
+                hook: Some(addr!("000000000000000000000000000000000000dead")),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("doesn't exist");
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::SetSendHook {
+                namespace: namespace.clone(),
+                hook: Some(hook),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            contracts.bank,
+            bank::QuerySendHookRequest {
+                namespace: namespace.clone(),
+            },
+        )
+        .should_succeed_and_equal(Some(hook));
+
+    // User2 can't send the namespace's tokens...
+    suite
+        .transfer(
+            &mut accounts.user2,
+            accounts.user1.address(),
+            coins! { denom.clone() => 10 },
+        )
+        .await
+        .should_fail_with_error(format!("address `{}` is blocked", accounts.user2.address()));
+
+    // ...nor receive them.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::Mint {
+                to: accounts.user1.address(),
+                coins: coins! { denom.clone() => 100 },
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .transfer(
+            &mut accounts.user1,
+            accounts.user2.address(),
+            coins! { denom.clone() => 10 },
+        )
+        .await
+        .should_fail_with_error("transfer rejected by send hook");
+
+    // Tokens of other namespaces aren't affected.
+    suite
+        .transfer(
+            &mut accounts.user2,
+            accounts.user1.address(),
+            coins! { usdc::DENOM.clone() => 10 },
+        )
+        .await
+        .should_succeed();
+
+    // Transfers not involving user2 go through.
+    suite
+        .transfer(
+            &mut accounts.user1,
+            accounts.user3.address(),
+            coins! { denom.clone() => 10 },
+        )
+        .await
+        .should_succeed();
+
+    // Unset the hook. User2 can send again.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.bank,
+            &bank::ExecuteMsg::SetSendHook {
+                namespace,
+                hook: None,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().record(&accounts.user2);
+
+    suite
+        .transfer(
+            &mut accounts.user2,
+            accounts.user1.address(),
+            coins! { denom.clone() => 10 },
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user2,
+        btree_map! { denom => BalanceChange::Decreased(10) },
+    );
+}
@@ -648,6 +648,7 @@ async fn deposit_and_withdraw_events() {
                 denom: usdc::DENOM.clone(),
                 amount: Uint128::new(50_000_000),
                 fee: Uint128::new(ARBITRUM_USDC_WITHDRAWAL_FEE),
+                interchain_gas: Coins::new(),
             }]
         );
     }
@@ -3757,6 +3758,7 @@ async fn withdrawal_approval_coin_accounting() {
             recipient: withdraw_recipient(),
             denom: usdc::DENOM.clone(),
             amount: Uint128::new(WITHDRAW),
+            interchain_gas: Coins::new(),
         }
     );
 
@@ -798,6 +798,7 @@ async fn ws_perps_alias_subscriptions_stream_unwrapped_responses() -> anyhow::Re
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
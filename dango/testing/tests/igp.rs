@@ -0,0 +1,374 @@
+use {
+    dango_hyperlane_types::{
+        Addr32, addr32,
+        hooks::igp::{self, DestinationGasConfig, GasOracle},
+        mailbox::{self, Hooks},
+    },
+    dango_math::{NumberConst, Udec128, Uint128},
+    dango_primitives::{Addressable, Coins, HexBinary, QuerierExt, ResultExt, btree_map, coins},
+    dango_testing::{BalanceChange, mock_ethereum, setup_test},
+    dango_types::{
+        constants::{eth, usdc},
+        gateway::{self, Remote, WithdrawalResponse},
+    },
+};
+
+const RECIPIENT: Addr32 =
+    addr32!("0000000000000000000000000000000000000000000000000000000000000000");
+
+const ETHEREUM_USDC: Remote = Remote::Warp {
+    domain: mock_ethereum::DOMAIN,
+    contract: mock_ethereum::USDC_WARP,
+};
+
+const ETHEREUM_ETH: Remote = Remote::Warp {
+    domain: mock_ethereum::DOMAIN,
+    contract: mock_ethereum::ETH_WARP,
+};
+
+/// (50,000 default gas limit + 0 overhead) * 10 gas price * 1 exchange rate.
+const INTERCHAIN_GAS: u128 = 500_000;
+
+const SEND_AMOUNT: u128 = 888_000_000;
+
+fn ethereum_gas_config(gas_price: u128) -> DestinationGasConfig {
+    DestinationGasConfig {
+        gas_oracle: GasOracle {
+            token_exchange_rate: Udec128::ONE,
+            gas_price: Uint128::new(gas_price),
+        },
+        gas_overhead: Uint128::ZERO,
+    }
+}
+
+#[tokio::test]
+async fn igp_is_the_default_hook() {
+    let (suite, _, _, contracts, _) = setup_test(Default::default());
+
+    suite
+        .query_wasm_smart(contracts.hyperlane.mailbox, mailbox::QueryHooksRequest {})
+        .should_succeed_and_equal(Hooks {
+            required: None,
+            default: Some(contracts.hyperlane.igp),
+        });
+
+    // No gas config in the genesis; dispatching is free.
+    suite
+        .query_wasm_smart(
+            contracts.hyperlane.mailbox,
+            mailbox::QueryQuoteDispatchRequest {
+                sender: contracts.warp,
+                destination_domain: mock_ethereum::DOMAIN,
+                recipient: mock_ethereum::USDC_WARP,
+                body: HexBinary::default(),
+            },
+        )
+        .should_succeed_and_equal(Coins::new());
+}
+
+#[tokio::test]
+async fn paying_interchain_gas_on_withdrawal() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test(Default::default());
+
+    const ETHEREUM_USDC_WITHDRAWAL_FEE: u128 = 1_000_000;
+
+    // Only the owner can set gas configs.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(10),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the chain owner can call `set_destination_gas_configs`");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(10),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // The mailbox and Warp quote the IGP's payment.
+    suite
+        .query_wasm_smart(
+            contracts.hyperlane.igp,
+            igp::QueryQuoteGasPaymentRequest {
+                destination_domain: mock_ethereum::DOMAIN,
+                gas_limit: igp::DEFAULT_GAS_LIMIT,
+            },
+        )
+        .should_succeed_and_equal(coins! { usdc::DENOM.clone() => INTERCHAIN_GAS });
+
+    suite
+        .query_wasm_smart(
+            contracts.warp,
+            gateway::bridge::QueryBridgeRequest(
+                gateway::bridge::BridgeQuery::QuoteTransferRemote {
+                    remote: ETHEREUM_USDC,
+                    amount: Uint128::new(SEND_AMOUNT),
+                    recipient: RECIPIENT,
+                },
+            ),
+        )
+        .should_succeed_and_equal(gateway::bridge::BridgeQueryResponse::QuoteTransferRemote(
+            coins! { usdc::DENOM.clone() => INTERCHAIN_GAS },
+        ));
+
+    suite.balances().record_many([
+        &accounts.user1.address(),
+        &accounts.owner.address(),
+        &contracts.hyperlane.igp,
+    ]);
+
+    // User1 withdraws USDC, paying the interchain gas in USDC on top of the
+    // amount: the funds are a single coin covering both.
+    suite
+        .transfer_remote(
+            &mut accounts.user1,
+            &mut accounts.owner,
+            contracts.gateway,
+            ETHEREUM_USDC,
+            RECIPIENT,
+            coins! { usdc::DENOM.clone() => SEND_AMOUNT + INTERCHAIN_GAS },
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! {
+            usdc::DENOM.clone() => BalanceChange::Decreased(SEND_AMOUNT + INTERCHAIN_GAS),
+        },
+    );
+
+    suite.balances().should_change(
+        &accounts.owner,
+        btree_map! {
+            usdc::DENOM.clone() => BalanceChange::Increased(ETHEREUM_USDC_WITHDRAWAL_FEE),
+        },
+    );
+
+    suite.balances().should_change(
+        &contracts.hyperlane.igp,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(INTERCHAIN_GAS) },
+    );
+
+    // Nothing is left in the gateway, Warp, or the mailbox.
+    for contract in [
+        contracts.gateway,
+        contracts.warp,
+        contracts.hyperlane.mailbox,
+    ] {
+        suite
+            .query_balance(&contract, usdc::DENOM.clone())
+            .should_succeed_and_equal(Uint128::ZERO);
+    }
+
+    // Anyone can claim the collected fees to the beneficiary (the owner).
+    suite.balances().record(&accounts.owner);
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::Claim {},
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.owner,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(INTERCHAIN_GAS) },
+    );
+
+    suite
+        .query_balance(&contracts.hyperlane.igp, usdc::DENOM.clone())
+        .should_succeed_and_equal(Uint128::ZERO);
+}
+
+#[tokio::test]
+async fn paying_interchain_gas_in_another_denom() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test(Default::default());
+
+    const SEND_ETH: u128 = 1_000_000_000_000_000_000; // 1 ETH
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(10),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Without the interchain gas payment. Fails at request time.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.gateway,
+            &gateway::ExecuteMsg::TransferRemote {
+                remote: ETHEREUM_ETH,
+                recipient: RECIPIENT,
+            },
+            coins! { eth::DENOM.clone() => SEND_ETH },
+        )
+        .await
+        .should_fail_with_error("insufficient interchain gas payment");
+
+    suite
+        .balances()
+        .record_many([&accounts.user1.address(), &contracts.hyperlane.igp]);
+
+    // Withdraw ETH, paying the interchain gas in USDC.
+    suite
+        .transfer_remote(
+            &mut accounts.user1,
+            &mut accounts.owner,
+            contracts.gateway,
+            ETHEREUM_ETH,
+            RECIPIENT,
+            coins! {
+                eth::DENOM.clone() => SEND_ETH,
+                usdc::DENOM.clone() => INTERCHAIN_GAS,
+            },
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! {
+            eth::DENOM.clone() => BalanceChange::Decreased(SEND_ETH),
+            usdc::DENOM.clone() => BalanceChange::Decreased(INTERCHAIN_GAS),
+        },
+    );
+
+    suite.balances().should_change(
+        &contracts.hyperlane.igp,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(INTERCHAIN_GAS) },
+    );
+}
+
+#[tokio::test]
+async fn interchain_gas_quote_changes_while_pending() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test(Default::default());
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(10),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .balances()
+        .record_many([&accounts.user1.address(), &contracts.hyperlane.igp]);
+
+    let id = suite
+        .request_transfer_remote(
+            &mut accounts.user1,
+            contracts.gateway,
+            ETHEREUM_USDC,
+            RECIPIENT,
+            coins! { usdc::DENOM.clone() => SEND_AMOUNT + INTERCHAIN_GAS },
+        )
+        .await;
+
+    // The gas price doubles while the request is pending. The escrowed
+    // payment no longer covers the quote, so the approval refunds the
+    // escrow, interchain gas included.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(20),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .respond_to_withdrawal(
+            &mut accounts.owner,
+            contracts.gateway,
+            id,
+            WithdrawalResponse::Approve,
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Unchanged },
+    );
+
+    // The gas price halves while the next request is pending. The excess
+    // escrowed payment is refunded upon approval.
+    let id = suite
+        .request_transfer_remote(
+            &mut accounts.user1,
+            contracts.gateway,
+            ETHEREUM_USDC,
+            RECIPIENT,
+            coins! { usdc::DENOM.clone() => SEND_AMOUNT + 2 * INTERCHAIN_GAS },
+        )
+        .await;
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.igp,
+            &igp::ExecuteMsg::SetDestinationGasConfigs(btree_map! {
+                mock_ethereum::DOMAIN => ethereum_gas_config(10),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .respond_to_withdrawal(
+            &mut accounts.owner,
+            contracts.gateway,
+            id,
+            WithdrawalResponse::Approve,
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! {
+            usdc::DENOM.clone() => BalanceChange::Decreased(SEND_AMOUNT + INTERCHAIN_GAS),
+        },
+    );
+
+    suite.balances().should_change(
+        &contracts.hyperlane.igp,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(INTERCHAIN_GAS) },
+    );
+
+    suite
+        .query_balance(&contracts.gateway, usdc::DENOM.clone())
+        .should_succeed_and_equal(Uint128::ZERO);
+}
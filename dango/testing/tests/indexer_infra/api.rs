@@ -235,6 +235,7 @@ async fn perps_aliases_mirror_contract_queries() -> anyhow::Result<()> {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -51,6 +51,7 @@ async fn place_limit_ask(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -78,6 +79,7 @@ async fn market_buy(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -0,0 +1,123 @@
+use {
+    dango_hyperlane_types::{
+        isms::{aggregation, routing},
+        mailbox,
+    },
+    dango_math::Uint128,
+    dango_primitives::{Coins, QuerierExt, ResultExt, btree_map, btree_set},
+    dango_testing::{BalanceChange, HyperlaneTestSuite, mock_arbitrum, setup_test},
+    dango_types::constants::eth,
+};
+
+#[tokio::test]
+async fn routing_ism_is_the_default_ism() {
+    let (suite, _, _, contracts, _) = setup_test(Default::default());
+
+    suite
+        .query_wasm_smart(contracts.hyperlane.mailbox, mailbox::QueryConfigRequest {})
+        .should_succeed_and_equal(mailbox::Config {
+            local_domain: dango_testing::MOCK_HYPERLANE_LOCAL_DOMAIN,
+            default_ism: contracts.hyperlane.routing_ism,
+        });
+
+    // No routes in the genesis; all domains fall back to the multisig ISM.
+    suite
+        .query_wasm_smart(
+            contracts.hyperlane.routing_ism,
+            routing::QueryDefaultIsmRequest {},
+        )
+        .should_succeed_and_equal(Some(contracts.hyperlane.ism));
+
+    suite
+        .query_wasm_smart(
+            contracts.hyperlane.routing_ism,
+            routing::QueryRouteRequest {
+                domain: mock_arbitrum::DOMAIN,
+            },
+        )
+        .should_succeed_and_equal(contracts.hyperlane.ism);
+
+    // The aggregation ISM starts out as the multisig ISM alone.
+    suite
+        .query_wasm_smart(
+            contracts.hyperlane.aggregation_ism,
+            aggregation::QueryIsmSetRequest {},
+        )
+        .should_succeed_and_equal(aggregation::IsmSet {
+            threshold: 1,
+            isms: vec![contracts.hyperlane.ism],
+        });
+}
+
+#[tokio::test]
+async fn routing_a_domain_to_the_aggregation_ism() {
+    let (suite, mut accounts, _, contracts, validator_sets) = setup_test(Default::default());
+    let mut suite = HyperlaneTestSuite::new(suite, validator_sets, &contracts);
+
+    // Only the owner can set routes.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.hyperlane.routing_ism,
+            &routing::ExecuteMsg::SetRoutes(btree_map! {
+                mock_arbitrum::DOMAIN => contracts.hyperlane.aggregation_ism,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the chain owner can call `set_routes`");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.routing_ism,
+            &routing::ExecuteMsg::SetRoutes(btree_map! {
+                mock_arbitrum::DOMAIN => contracts.hyperlane.aggregation_ism,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Messages from Arbitrum are now verified by the aggregation ISM, which
+    // expects its own metadata format. Plain multisig metadata is rejected.
+    suite
+        .receive_warp_transfer(
+            &mut accounts.owner,
+            mock_arbitrum::DOMAIN,
+            mock_arbitrum::ETH_WARP,
+            &accounts.user1,
+            Uint128::new(88),
+        )
+        .await
+        .should_fail_with_error("ISM verification failed");
+
+    // Remove the route. Messages from Arbitrum fall back to the multisig ISM.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.hyperlane.routing_ism,
+            &routing::ExecuteMsg::RemoveRoutes(btree_set! { mock_arbitrum::DOMAIN }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().record(&accounts.user1);
+
+    suite
+        .receive_warp_transfer(
+            &mut accounts.owner,
+            mock_arbitrum::DOMAIN,
+            mock_arbitrum::ETH_WARP,
+            &accounts.user1,
+            Uint128::new(88),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! { eth::DENOM.clone() => BalanceChange::Increased(88) },
+    );
+}
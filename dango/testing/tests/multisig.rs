@@ -0,0 +1,472 @@
+use {
+    dango_primitives::{
+        Addr, Addressable, Coins, Duration, Message, QuerierExt, ResultExt, btree_map,
+    },
+    dango_testing::{BalanceChange, Multi, TestAccounts, TestSuiteNaive, setup_test_naive},
+    dango_types::{
+        account_factory::{self, UserIndex},
+        account_multi::{
+            self, Params, QueryParamsRequest, QueryProposalRequest, QueryVoteRequest, Status, Vote,
+        },
+        constants::usdc,
+    },
+    std::collections::BTreeMap,
+};
+
+const VOTING_PERIOD: Duration = Duration::from_days(1);
+
+/// Set up a multisig with users 1, 2, and 3 as members, each of weight 1, and a
+/// threshold of 2.
+async fn setup_test() -> (TestSuiteNaive, TestAccounts, Addr) {
+    let (mut suite, mut accounts, _codes, contracts, _) = setup_test_naive(Default::default());
+
+    let params = Params {
+        members: btree_map! {
+            accounts.user1.user_index() => 1,
+            accounts.user2.user_index() => 1,
+            accounts.user3.user_index() => 1,
+        },
+        voting_period: VOTING_PERIOD,
+        threshold: 2,
+    };
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterMultisig { params },
+            Coins::one(usdc::DENOM.clone(), 100_000_000).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    let multisigs = suite
+        .query_wasm_smart(
+            contracts.account_factory,
+            account_factory::QueryMultisigsRequest {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .should_succeed();
+
+    assert_eq!(multisigs.len(), 1);
+
+    let (multi, _) = multisigs.into_iter().next().unwrap();
+
+    (suite, accounts, multi)
+}
+
+async fn propose(
+    suite: &mut TestSuiteNaive,
+    multi: &mut Multi<'_>,
+    messages: Vec<Message>,
+) -> account_multi::ProposalId {
+    let multi_addr = multi.address();
+
+    suite
+        .execute(
+            multi,
+            multi_addr,
+            &account_multi::ExecuteMsg::Propose {
+                title: "title".to_string(),
+                description: None,
+                messages,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let proposals = suite
+        .query_wasm_smart(
+            multi_addr,
+            account_multi::QueryProposalsRequest {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .should_succeed();
+
+    *proposals.keys().last().unwrap()
+}
+
+#[tokio::test]
+async fn proposal_passes_and_executes() {
+    let (mut suite, mut accounts, multi_addr) = setup_test().await;
+    let mut multi = Multi::new(multi_addr);
+
+    let recipient = accounts.user4.address();
+    let amount = Coins::one(usdc::DENOM.clone(), 10_000_000).unwrap();
+
+    let proposal_id = propose(
+        &mut suite,
+        multi.with_signer(&accounts.user1),
+        vec![Message::transfer(recipient, amount).unwrap()],
+    )
+    .await;
+
+    suite.balances().record(&accounts.user4);
+
+    // First YES vote: weight 1 < threshold 2. Still voting.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user1),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user1.user_index(),
+                vote: Vote::Yes,
+                execute: true,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(multi_addr, QueryProposalRequest { proposal_id })
+        .should_succeed_and(|proposal| matches!(proposal.status, Status::Voting { yes: 1, .. }));
+
+    // A member can't vote twice.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user1),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user1.user_index(),
+                vote: Vote::Yes,
+                execute: true,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("has already voted");
+
+    // A member can't vote on behalf of another member.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user2),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user3.user_index(),
+                vote: Vote::Yes,
+                execute: true,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("doesn't match the signer");
+
+    // Second YES vote reaches the threshold. The proposal is executed right away.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user2),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user2.user_index(),
+                vote: Vote::Yes,
+                execute: true,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user4,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(10_000_000) },
+    );
+
+    suite
+        .query_wasm_smart(multi_addr, QueryProposalRequest { proposal_id })
+        .should_succeed_and(|proposal| proposal.status == Status::Executed);
+
+    suite
+        .query_wasm_smart(
+            multi_addr,
+            QueryVoteRequest {
+                proposal_id,
+                member: accounts.user2.user_index(),
+            },
+        )
+        .should_succeed_and_equal(Some(Vote::Yes));
+
+    // An executed proposal can't be executed again.
+    suite
+        .execute(
+            &mut accounts.user4,
+            multi_addr,
+            &account_multi::ExecuteMsg::Execute { proposal_id },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("has not passed");
+}
+
+#[tokio::test]
+async fn proposal_fails_on_no_votes() {
+    let (mut suite, accounts, multi_addr) = setup_test().await;
+    let mut multi = Multi::new(multi_addr);
+
+    let proposal_id = propose(&mut suite, multi.with_signer(&accounts.user1), vec![]).await;
+
+    // Two NO votes out of a total weight of 3: a threshold of 2 can no longer
+    // be reached.
+    for voter in [&accounts.user2, &accounts.user3] {
+        suite
+            .execute(
+                multi.with_signer(voter),
+                multi_addr,
+                &account_multi::ExecuteMsg::Vote {
+                    proposal_id,
+                    voter: voter.user_index(),
+                    vote: Vote::No,
+                    execute: false,
+                },
+                Coins::new(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    suite
+        .query_wasm_smart(multi_addr, QueryProposalRequest { proposal_id })
+        .should_succeed_and(|proposal| proposal.status == Status::Failed);
+
+    // The remaining member can no longer vote.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user1),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user1.user_index(),
+                vote: Vote::Yes,
+                execute: false,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("is not in the voting state");
+}
+
+#[tokio::test]
+async fn proposal_expires() {
+    let (mut suite, accounts, multi_addr) = setup_test().await;
+    let mut multi = Multi::new(multi_addr);
+
+    let proposal_id = propose(&mut suite, multi.with_signer(&accounts.user1), vec![]).await;
+
+    suite.increase_time(VOTING_PERIOD).await;
+
+    suite
+        .query_wasm_smart(multi_addr, QueryProposalRequest { proposal_id })
+        .should_succeed_and(|proposal| proposal.status == Status::Failed);
+
+    suite
+        .execute(
+            multi.with_signer(&accounts.user1),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id,
+                voter: accounts.user1.user_index(),
+                vote: Vote::Yes,
+                execute: false,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("voting period");
+}
+
+#[tokio::test]
+async fn non_member_cannot_propose() {
+    let (mut suite, mut accounts, multi_addr) = setup_test().await;
+    let mut multi = Multi::new(multi_addr);
+
+    suite
+        .execute(
+            multi.with_signer(&accounts.user4),
+            multi_addr,
+            &account_multi::ExecuteMsg::Propose {
+                title: "title".to_string(),
+                description: None,
+                messages: vec![],
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("isn't a member");
+
+    // Nor can the multisig's parameters be updated other than by a proposal.
+    suite
+        .execute(
+            &mut accounts.user1,
+            multi_addr,
+            &account_multi::ExecuteMsg::UpdateParams(Params {
+                members: btree_map! { accounts.user1.user_index() => 1 },
+                voting_period: VOTING_PERIOD,
+                threshold: 1,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the multisig itself");
+}
+
+#[tokio::test]
+async fn rotate_members() {
+    let (mut suite, accounts, multi_addr) = setup_test().await;
+    let mut multi = Multi::new(multi_addr);
+
+    // Replace user 3 with user 4.
+    let new_params = Params {
+        members: btree_map! {
+            accounts.user1.user_index() => 1,
+            accounts.user2.user_index() => 1,
+            accounts.user4.user_index() => 1,
+        },
+        voting_period: VOTING_PERIOD,
+        threshold: 2,
+    };
+
+    let proposal_id = propose(
+        &mut suite,
+        multi.with_signer(&accounts.user3),
+        vec![
+            Message::execute(
+                multi_addr,
+                &account_multi::ExecuteMsg::UpdateParams(new_params.clone()),
+                Coins::new(),
+            )
+            .unwrap(),
+        ],
+    )
+    .await;
+
+    // An open proposal, created before the rotation, which user 3 can still
+    // vote on.
+    let open_proposal_id = propose(&mut suite, multi.with_signer(&accounts.user3), vec![]).await;
+
+    for voter in [&accounts.user1, &accounts.user2] {
+        suite
+            .execute(
+                multi.with_signer(voter),
+                multi_addr,
+                &account_multi::ExecuteMsg::Vote {
+                    proposal_id,
+                    voter: voter.user_index(),
+                    vote: Vote::Yes,
+                    execute: true,
+                },
+                Coins::new(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    suite
+        .query_wasm_smart(multi_addr, QueryParamsRequest {})
+        .should_succeed_and_equal(new_params);
+
+    // User 3 is no longer a member, and can't create new proposals.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user3),
+            multi_addr,
+            &account_multi::ExecuteMsg::Propose {
+                title: "title".to_string(),
+                description: None,
+                messages: vec![],
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("isn't a member");
+
+    // User 4 now can.
+    propose(&mut suite, multi.with_signer(&accounts.user4), vec![]).await;
+
+    // Proposals created before the rotation are voted on by the members at the
+    // time: user 3 can vote, user 4 can't.
+    suite
+        .execute(
+            multi.with_signer(&accounts.user3),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id: open_proposal_id,
+                voter: accounts.user3.user_index(),
+                vote: Vote::Yes,
+                execute: false,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            multi.with_signer(&accounts.user4),
+            multi_addr,
+            &account_multi::ExecuteMsg::Vote {
+                proposal_id: open_proposal_id,
+                voter: accounts.user4.user_index(),
+                vote: Vote::Yes,
+                execute: false,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("isn't a member");
+}
+
+#[tokio::test]
+async fn invalid_params() {
+    let (mut suite, mut accounts, _codes, contracts, _) = setup_test_naive(Default::default());
+
+    let members: BTreeMap<UserIndex, u32> = btree_map! {
+        accounts.user1.user_index() => 1,
+        accounts.user2.user_index() => 1,
+    };
+
+    for (params, error) in [
+        (
+            Params {
+                members: members.clone(),
+                voting_period: VOTING_PERIOD,
+                threshold: 3,
+            },
+            "invalid `threshold`",
+        ),
+        (
+            Params {
+                members: members.clone(),
+                voting_period: Duration::ZERO,
+                threshold: 1,
+            },
+            "voting period must be greater than zero",
+        ),
+        (
+            Params {
+                members: btree_map! { 12345 => 1 },
+                voting_period: VOTING_PERIOD,
+                threshold: 1,
+            },
+            "user 12345 doesn't exist",
+        ),
+    ] {
+        suite
+            .execute(
+                &mut accounts.user1,
+                contracts.account_factory,
+                &account_factory::ExecuteMsg::RegisterMultisig { params },
+                Coins::new(),
+            )
+            .await
+            .should_fail_with_error(error);
+    }
+}
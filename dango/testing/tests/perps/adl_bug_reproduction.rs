@@ -131,6 +131,7 @@ async fn adl_bug_absurd_book_price() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -168,6 +169,7 @@ async fn adl_bug_absurd_book_price() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -234,6 +236,7 @@ async fn adl_bug_absurd_book_price() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -135,6 +135,7 @@ async fn solvent_partial_close_adl_price() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -164,6 +165,7 @@ async fn solvent_partial_close_adl_price() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
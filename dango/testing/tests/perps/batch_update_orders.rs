@@ -32,6 +32,7 @@ fn limit_bid(price: i128, size: i128, cid: Option<u64>) -> SubmitOrderRequest {
         reduce_only: false,
         tp: None,
         sl: None,
+        stp_mode: Default::default(),
     }
 }
 
@@ -47,6 +48,7 @@ fn limit_ask(price: i128, size: i128, cid: Option<u64>) -> SubmitOrderRequest {
         reduce_only: false,
         tp: None,
         sl: None,
+        stp_mode: Default::default(),
     }
 }
 
@@ -497,6 +499,7 @@ async fn batch_fill_reverts_on_later_failure() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     SubmitOrCancelOrderRequest::Submit(limit_bid(1_900, 1, Some(cid))),
                     SubmitOrCancelOrderRequest::Submit(limit_bid(1_800, 1, Some(cid))),
@@ -787,6 +790,7 @@ async fn batch_across_two_pairs() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     SubmitOrCancelOrderRequest::Submit(SubmitOrderRequest {
                         pair_id: btc_pair.clone(),
@@ -799,6 +803,7 @@ async fn batch_across_two_pairs() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     SubmitOrCancelOrderRequest::Cancel(CancelOrderRequest::OneByClientOrderId(
                         Uint64::new(eth_cid),
@@ -873,6 +878,7 @@ async fn batch_stp_fires_for_self_match() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                 ])
                 .unwrap(),
@@ -999,6 +1005,7 @@ async fn batch_cancel_fails_for_filled_order() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     SubmitOrCancelOrderRequest::Cancel(CancelOrderRequest::OneByClientOrderId(
                         Uint64::new(cid),
@@ -1152,6 +1159,7 @@ async fn batch_referral_commissions_rollback() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     SubmitOrCancelOrderRequest::Submit(limit_bid(1_800, 1, Some(cid))),
                     SubmitOrCancelOrderRequest::Submit(limit_bid(1_700, 1, Some(cid))),
@@ -0,0 +1,195 @@
+use {
+    crate::register_oracle_prices,
+    dango_math::Uint128,
+    dango_order_book::{OrderKind, Quantity, TimeInForce, UsdPrice},
+    dango_primitives::{Addressable, Coins, Duration, QuerierExt, ResultExt},
+    dango_testing::{TestOption, pair_id, setup_test_naive},
+    dango_types::{constants::usdc, perps},
+};
+
+/// Maker arms a 30-second dead-man's switch and keeps it alive by refreshing
+/// it. Once the refreshes stop, the cron cancels the maker's resting orders
+/// and disarms the switch.
+#[tokio::test]
+async fn cancel_all_after_cancels_orders_unless_refreshed() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let pair = pair_id();
+    let user1 = accounts.user1.address();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    // Maker places bid: 1 ETH @ $1,900.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(1),
+                kind: OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(1_900),
+                    time_in_force: TimeInForce::PostOnly,
+                    client_order_id: None,
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let arm = perps::ExecuteMsg::Trade(perps::TraderMsg::CancelAllAfter {
+        timeout: Some(Duration::from_seconds(30)),
+    });
+
+    suite
+        .execute(&mut accounts.user1, contracts.perps, &arm, Coins::new())
+        .await
+        .should_succeed();
+
+    let deadline = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryCancelAllDeadlineRequest { user: user1 },
+        )
+        .should_succeed()
+        .expect("switch should be armed");
+
+    // Refresh the switch every 20 seconds; the order stays on the book.
+    for _ in 0..3 {
+        suite.increase_time(Duration::from_seconds(20)).await;
+
+        suite
+            .execute(&mut accounts.user1, contracts.perps, &arm, Coins::new())
+            .await
+            .should_succeed();
+    }
+
+    let refreshed_deadline = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryCancelAllDeadlineRequest { user: user1 },
+        )
+        .should_succeed()
+        .expect("switch should still be armed");
+
+    assert!(refreshed_deadline > deadline);
+
+    let orders = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(orders.len(), 1);
+
+    // Stop refreshing. Once the deadline passes, the order is canceled.
+    suite.increase_time(Duration::from_seconds(40)).await;
+
+    let orders = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert!(orders.is_empty());
+
+    suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryCancelAllDeadlineRequest { user: user1 },
+        )
+        .should_succeed_and_equal(None);
+
+    let state = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap();
+
+    assert!(state.reserved_margin.is_zero());
+    assert_eq!(state.open_order_count, 0);
+}
+
+/// A disarmed switch never fires.
+#[tokio::test]
+async fn cancel_all_after_disarmed() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let user1 = accounts.user1.address();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair_id(),
+                size: Quantity::new_int(1),
+                kind: OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(1_900),
+                    time_in_force: TimeInForce::PostOnly,
+                    client_order_id: None,
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    for timeout in [Some(Duration::from_seconds(30)), None] {
+        suite
+            .execute(
+                &mut accounts.user1,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::CancelAllAfter { timeout }),
+                Coins::new(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    suite.increase_time(Duration::from_minutes(1)).await;
+
+    let orders = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(orders.len(), 1);
+}
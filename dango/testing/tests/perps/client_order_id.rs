@@ -54,6 +54,7 @@ async fn submit_cancel_resubmit_by_client_order_id() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -114,6 +115,7 @@ async fn submit_cancel_resubmit_by_client_order_id() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -178,6 +180,7 @@ async fn query_order_by_client_order_id() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
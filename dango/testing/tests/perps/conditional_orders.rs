@@ -3,13 +3,13 @@ use {
     dango_math::Uint128,
     dango_order_book::{
         ChildOrder, Dimensionless, OrderId, OrderKind, Quantity, QueryOrdersByUserResponseItem,
-        TimeInForce, TriggerDirection, UsdPrice, UsdValue,
+        TimeInForce, TrailingOffset, TriggerDirection, UsdPrice, UsdValue,
     },
     dango_primitives::{
         Addressable, CheckedContractEvent, Coins, Duration, Inner, JsonDeExt, QuerierExt,
         ResultExt, SearchEvent, btree_map,
     },
-    dango_testing::{TestOption, pair_id, setup_test_naive},
+    dango_testing::{TestOption, TestSuiteNaive, pair_id, setup_test_naive},
     dango_types::{
         constants::usdc,
         perps::{self, OrderFilled, PairParam, UserState},
@@ -64,6 +64,7 @@ async fn conditional_order_tp_triggers_on_price_rise() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -84,6 +85,7 @@ async fn conditional_order_tp_triggers_on_price_rise() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -117,6 +119,8 @@ async fn conditional_order_tp_triggers_on_price_rise() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -138,7 +142,7 @@ async fn conditional_order_tp_triggers_on_price_rise() {
         .get(&pair)
         .expect("should have ETH position");
     assert!(
-        pos.conditional_order_above.is_some(),
+        !pos.conditional_orders_above.is_empty(),
         "should have a conditional order above (TP)"
     );
 
@@ -158,6 +162,7 @@ async fn conditional_order_tp_triggers_on_price_rise() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -249,6 +254,7 @@ async fn conditional_order_sl_triggers_on_price_drop() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -268,6 +274,7 @@ async fn conditional_order_sl_triggers_on_price_drop() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -285,6 +292,8 @@ async fn conditional_order_sl_triggers_on_price_drop() {
                 trigger_price: UsdPrice::new_int(1_800),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -307,6 +316,7 @@ async fn conditional_order_sl_triggers_on_price_drop() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -419,6 +429,7 @@ async fn conditional_orders_follow_price_time_priority() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -442,6 +453,7 @@ async fn conditional_orders_follow_price_time_priority() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -465,6 +477,7 @@ async fn conditional_orders_follow_price_time_priority() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -485,6 +498,8 @@ async fn conditional_orders_follow_price_time_priority() {
                 trigger_price: UsdPrice::new_int(1_900),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -505,6 +520,8 @@ async fn conditional_orders_follow_price_time_priority() {
                 trigger_price: UsdPrice::new_int(1_800),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -532,6 +549,7 @@ async fn conditional_orders_follow_price_time_priority() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -553,6 +571,7 @@ async fn conditional_orders_follow_price_time_priority() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -703,6 +722,7 @@ async fn conditional_order_failure_does_not_block_others() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -722,6 +742,7 @@ async fn conditional_order_failure_does_not_block_others() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -747,6 +768,7 @@ async fn conditional_order_failure_does_not_block_others() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -766,6 +788,7 @@ async fn conditional_order_failure_does_not_block_others() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -818,6 +841,8 @@ async fn conditional_order_failure_does_not_block_others() {
                 trigger_price: UsdPrice::new_int(1_900),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -839,6 +864,8 @@ async fn conditional_order_failure_does_not_block_others() {
                 trigger_price: UsdPrice::new_int(1_800),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -865,6 +892,7 @@ async fn conditional_order_failure_does_not_block_others() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -928,8 +956,8 @@ async fn conditional_order_failure_does_not_block_others() {
         .get(&pair)
         .expect("User1 should still have a position");
     assert!(
-        pos_user1.conditional_order_below.is_none(),
-        "User1 conditional_order_below should be None after graceful cancel"
+        pos_user1.conditional_orders_below.is_empty(),
+        "User1 conditional_orders_below should be empty after graceful cancel"
     );
 
     // User3: position is closed (already asserted above), so no conditional
@@ -1000,6 +1028,7 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1020,6 +1049,7 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1043,6 +1073,7 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1082,6 +1113,8 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
                 trigger_price: UsdPrice::new_int(1_960),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(5),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1121,8 +1154,8 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
             .positions
             .get(&pair)
             .unwrap()
-            .conditional_order_below
-            .is_none(),
+            .conditional_orders_below
+            .is_empty(),
         "User1's stop-loss should be cleared after graceful cancel"
     );
 
@@ -1168,6 +1201,7 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1193,7 +1227,7 @@ async fn conditional_order_self_trade_failure_preserves_user_state() {
 
 // ===================== Child order e2e tests ================================
 
-/// Market buy with TP child order → position has conditional_order_above →
+/// Market buy with TP child order → position has conditional_orders_above →
 /// oracle rises → cron triggers TP → position closed with profit.
 #[tokio::test]
 async fn child_order_market_with_tp_triggers() {
@@ -1232,6 +1266,7 @@ async fn child_order_market_with_tp_triggers() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1256,6 +1291,7 @@ async fn child_order_market_with_tp_triggers() {
                     size: None,
                 }),
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1274,8 +1310,8 @@ async fn child_order_market_with_tp_triggers() {
         .unwrap();
 
     let pos = state.positions.get(&pair).expect("should have position");
-    assert!(pos.conditional_order_above.is_some(), "TP should be set");
-    assert!(pos.conditional_order_below.is_none(), "no SL");
+    assert!(!pos.conditional_orders_above.is_empty(), "TP should be set");
+    assert!(pos.conditional_orders_below.is_empty(), "no SL");
 
     // Oracle rises to $2,500 → trigger TP.
     register_oracle_prices(&mut suite, &mut accounts, 2_500).await;
@@ -1296,6 +1332,7 @@ async fn child_order_market_with_tp_triggers() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1362,6 +1399,7 @@ async fn child_order_market_with_sl_triggers() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1386,6 +1424,7 @@ async fn child_order_market_with_sl_triggers() {
                     max_slippage: Dimensionless::new_percent(2),
                     size: None,
                 }),
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1404,8 +1443,8 @@ async fn child_order_market_with_sl_triggers() {
         .unwrap();
 
     let pos = state.positions.get(&pair).unwrap();
-    assert!(pos.conditional_order_below.is_some(), "SL should be set");
-    assert!(pos.conditional_order_above.is_none(), "no TP");
+    assert!(!pos.conditional_orders_below.is_empty(), "SL should be set");
+    assert!(pos.conditional_orders_above.is_empty(), "no TP");
 
     // Oracle drops to $1,800 → trigger SL.
     register_oracle_prices(&mut suite, &mut accounts, 1_800).await;
@@ -1426,6 +1465,7 @@ async fn child_order_market_with_sl_triggers() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1489,6 +1529,7 @@ async fn child_order_ignored_when_position_closed() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1508,6 +1549,7 @@ async fn child_order_ignored_when_position_closed() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1530,6 +1572,7 @@ async fn child_order_ignored_when_position_closed() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1557,6 +1600,7 @@ async fn child_order_ignored_when_position_closed() {
                     max_slippage: Dimensionless::new_percent(2),
                     size: None,
                 }),
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1615,6 +1659,7 @@ async fn child_order_overwrites_existing() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1634,6 +1679,7 @@ async fn child_order_overwrites_existing() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1651,6 +1697,8 @@ async fn child_order_overwrites_existing() {
                 trigger_price: UsdPrice::new_int(3_000),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1679,6 +1727,7 @@ async fn child_order_overwrites_existing() {
                     max_slippage: Dimensionless::new_percent(2),
                     size: None,
                 }),
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1698,17 +1747,17 @@ async fn child_order_overwrites_existing() {
     let pos = state.positions.get(&pair).unwrap();
 
     // Old TP was at $3,000, new should be at $2,500.
-    let above = pos.conditional_order_above.as_ref().unwrap();
+    let above = pos.conditional_orders_above.values().next().unwrap();
     assert_eq!(above.trigger_price, UsdPrice::new_int(2_500));
 
     // SL (new) should be set.
-    let below = pos.conditional_order_below.as_ref().unwrap();
+    let below = pos.conditional_orders_below.values().next().unwrap();
     assert_eq!(below.trigger_price, UsdPrice::new_int(1_800));
 }
 
-/// SubmitConditionalOrder twice with same direction → second overwrites first.
+/// SubmitConditionalOrder twice with same direction → both orders are kept.
 #[tokio::test]
-async fn conditional_order_overwrite_same_direction() {
+async fn conditional_order_multiple_same_direction() {
     let (mut suite, mut accounts, _codes, contracts, _mock_validators) =
         setup_test_naive(TestOption::default());
 
@@ -1751,6 +1800,7 @@ async fn conditional_order_overwrite_same_direction() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1770,6 +1820,7 @@ async fn conditional_order_overwrite_same_direction() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1787,13 +1838,15 @@ async fn conditional_order_overwrite_same_direction() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
         .await
         .should_succeed();
 
-    // Second TP (same direction) → should overwrite, not error.
+    // Second TP (same direction) → added next to the first.
     suite
         .execute(
             &mut accounts.user1,
@@ -1804,6 +1857,8 @@ async fn conditional_order_overwrite_same_direction() {
                 trigger_price: UsdPrice::new_int(3_000),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1821,14 +1876,144 @@ async fn conditional_order_overwrite_same_direction() {
         .unwrap();
 
     let pos = state.positions.get(&pair).unwrap();
-    let above = pos.conditional_order_above.as_ref().unwrap();
+    let trigger_prices = pos
+        .conditional_orders_above
+        .values()
+        .map(|order| order.trigger_price)
+        .collect::<Vec<_>>();
     assert_eq!(
-        above.trigger_price,
-        UsdPrice::new_int(3_000),
-        "should be overwritten to $3,000"
+        trigger_prices,
+        vec![UsdPrice::new_int(2_500), UsdPrice::new_int(3_000)],
+        "both TPs should be kept"
     );
 }
 
+/// Trailing stop: the SL trigger follows the oracle high-water mark up, but
+/// never moves back down when the price retreats.
+#[tokio::test]
+async fn trailing_stop_follows_high_water_mark() {
+    let (mut suite, mut accounts, _codes, contracts, _mock_validators) =
+        setup_test_naive(TestOption::default());
+
+    let pair = pair_id();
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    // Establish long position.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(-10),
+                kind: OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(2_000),
+                    time_in_force: TimeInForce::PostOnly,
+                    client_order_id: None,
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(10),
+                kind: OrderKind::Market {
+                    max_slippage: Dimensionless::new_percent(50),
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Trailing SL $100 below the oracle price. The initial trigger is
+    // max($1,000, $2,000 - $100) = $1,900.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitConditionalOrder {
+                pair_id: pair.clone(),
+                size: None,
+                trigger_price: UsdPrice::new_int(1_000),
+                trigger_direction: TriggerDirection::Below,
+                max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: Some(TrailingOffset::Absolute(UsdPrice::new_int(100))),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let user1 = accounts.user1.address();
+    let query_sl_trigger_price = |suite: &TestSuiteNaive| {
+        let state: UserState = suite
+            .query_wasm_smart(
+                contracts.perps,
+                perps::QueryUserStateRequest { user: user1 },
+            )
+            .should_succeed()
+            .unwrap();
+
+        state.positions[&pair]
+            .conditional_orders_below
+            .values()
+            .next()
+            .unwrap()
+            .trigger_price
+    };
+
+    assert_eq!(query_sl_trigger_price(&suite), UsdPrice::new_int(1_900));
+
+    // Oracle rises to $2,300 → trigger ratchets up to $2,200.
+    register_oracle_prices(&mut suite, &mut accounts, 2_300).await;
+    suite.increase_time(Duration::from_minutes(2)).await;
+
+    assert_eq!(query_sl_trigger_price(&suite), UsdPrice::new_int(2_200));
+
+    // Oracle retreats to $2,250 → still above the trigger; trigger stays put.
+    register_oracle_prices(&mut suite, &mut accounts, 2_250).await;
+    suite.increase_time(Duration::from_minutes(2)).await;
+
+    assert_eq!(query_sl_trigger_price(&suite), UsdPrice::new_int(2_200));
+}
+
 /// SubmitConditionalOrder with size > position → now allowed (previously errored).
 #[tokio::test]
 async fn conditional_order_size_exceeds_position_allowed() {
@@ -1874,6 +2059,7 @@ async fn conditional_order_size_exceeds_position_allowed() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1893,6 +2079,7 @@ async fn conditional_order_size_exceeds_position_allowed() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1910,6 +2097,8 @@ async fn conditional_order_size_exceeds_position_allowed() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1928,7 +2117,7 @@ async fn conditional_order_size_exceeds_position_allowed() {
         .unwrap();
 
     let pos = state.positions.get(&pair).unwrap();
-    let above = pos.conditional_order_above.as_ref().unwrap();
+    let above = pos.conditional_orders_above.values().next().unwrap();
     assert_eq!(above.size, Some(Quantity::new_int(-5)));
 }
 
@@ -2003,6 +2192,7 @@ async fn conditional_order_cancelled_when_slippage_cap_tightened() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2022,6 +2212,7 @@ async fn conditional_order_cancelled_when_slippage_cap_tightened() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2039,6 +2230,8 @@ async fn conditional_order_cancelled_when_slippage_cap_tightened() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(10),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -2082,6 +2275,7 @@ async fn conditional_order_cancelled_when_slippage_cap_tightened() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2112,7 +2306,7 @@ async fn conditional_order_cancelled_when_slippage_cap_tightened() {
         .expect("position should still be open — TP cancelled, not executed");
     assert_eq!(pos.size, Quantity::new_int(10));
     assert!(
-        pos.conditional_order_above.is_none(),
+        pos.conditional_orders_above.is_empty(),
         "conditional order should have been removed by the cap-tightened cancel"
     );
 }
@@ -2166,6 +2360,7 @@ async fn conditional_order_trigger_fills_carry_fill_id() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2185,6 +2380,7 @@ async fn conditional_order_trigger_fills_carry_fill_id() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2201,6 +2397,8 @@ async fn conditional_order_trigger_fills_carry_fill_id() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(5),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -2223,6 +2421,7 @@ async fn conditional_order_trigger_fills_carry_fill_id() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2333,6 +2532,7 @@ async fn two_conditional_triggers_in_one_cron_tick_have_consecutive_fill_ids() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -2356,6 +2556,7 @@ async fn two_conditional_triggers_in_one_cron_tick_have_consecutive_fill_ids() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -2379,6 +2580,8 @@ async fn two_conditional_triggers_in_one_cron_tick_have_consecutive_fill_ids() {
                     trigger_price: UsdPrice::new_int(2_500),
                     trigger_direction: TriggerDirection::Above,
                     max_slippage: Dimensionless::new_percent(5),
+                    limit_price: None,
+                    trailing_offset: None,
                 }),
                 Coins::new(),
             )
@@ -2404,6 +2607,7 @@ async fn two_conditional_triggers_in_one_cron_tick_have_consecutive_fill_ids() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -0,0 +1,209 @@
+use {
+    crate::{default_pair_param, default_param, register_oracle_prices},
+    dango_math::Uint128,
+    dango_order_book::{Dimensionless, OrderKind, Quantity, TimeInForce, UsdPrice},
+    dango_primitives::{Addressable, Coins, QuerierExt, ResultExt, btree_map},
+    dango_testing::{TestOption, pair_id, setup_test_naive},
+    dango_types::{
+        constants::usdc,
+        perps::{self, PairParam, PairStatus, SubmitOrderRequest, UserState},
+    },
+};
+
+fn order(size: i128, kind: OrderKind, reduce_only: bool) -> perps::ExecuteMsg {
+    perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(SubmitOrderRequest {
+        pair_id: pair_id(),
+        size: Quantity::new_int(size),
+        kind,
+        reduce_only,
+        tp: None,
+        sl: None,
+        stp_mode: Default::default(),
+    }))
+}
+
+fn market(max_slippage_percent: i128) -> OrderKind {
+    OrderKind::Market {
+        max_slippage: Dimensionless::new_percent(max_slippage_percent),
+    }
+}
+
+/// Walk a pair through the delisting workflow: reduce-only, then settled at a
+/// final price, after which its positions are closed and its orders canceled.
+#[tokio::test]
+async fn delisting_a_pair_closes_positions_at_settlement_price() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let user1 = accounts.user1.address();
+    let user2 = accounts.user2.address();
+
+    for account in [&mut accounts.user1, &mut accounts.user2] {
+        suite
+            .execute(
+                account,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+                Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // user2 rests an ask of 3 ETH @ $2,000; user1 buys 2 ETH from it.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &order(
+                -3,
+                OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(2_000),
+                    time_in_force: TimeInForce::GoodTilCanceled,
+                    client_order_id: None,
+                },
+                false,
+            ),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &order(2, market(5), false),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // ------------------------------ Reduce-only ------------------------------
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.perps,
+            &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::Configure {
+                param: default_param(),
+                pair_params: btree_map! {
+                    pair_id() => PairParam {
+                        status: PairStatus::ReduceOnly,
+                        ..default_pair_param()
+                    },
+                },
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Increasing the position is rejected...
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &order(1, market(5), false),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("reduce-only mode");
+
+    // ...but reducing it is allowed: user2 bids to reduce their short, and
+    // user1 sells into the bid to reduce their long.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &order(
+                1,
+                OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(1_990),
+                    time_in_force: TimeInForce::GoodTilCanceled,
+                    client_order_id: None,
+                },
+                true,
+            ),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &order(-1, market(5), true),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // ------------------------------- Settlement ------------------------------
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.perps,
+            &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::SettlePair {
+                pair_id: pair_id(),
+                settlement_price: UsdPrice::new_int(2_100),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // No order is accepted anymore.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &order(-1, market(5), true),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("has been delisted");
+
+    // Anyone can process the settlement.
+    suite
+        .execute(
+            &mut accounts.user3,
+            contracts.perps,
+            &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::ProcessPairSettlement {
+                pair_id: pair_id(),
+                limit: 100,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let pair_param: Option<PairParam> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryPairParamRequest { pair_id: pair_id() },
+        )
+        .should_succeed();
+
+    assert_eq!(
+        pair_param.unwrap().status,
+        PairStatus::Delisted {
+            settlement_price: UsdPrice::new_int(2_100),
+        }
+    );
+
+    // Both remaining positions are closed, and user2's remaining ask canceled.
+    for user in [user1, user2] {
+        let state: Option<UserState> = suite
+            .query_wasm_smart(contracts.perps, perps::QueryUserStateRequest { user })
+            .should_succeed();
+
+        let state = state.unwrap();
+
+        assert!(state.positions.is_empty());
+        assert_eq!(state.open_order_count, 0);
+    }
+}
@@ -89,6 +89,7 @@ async fn e2_close_drift_reopen() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -111,6 +112,7 @@ async fn e2_close_drift_reopen() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -268,6 +270,7 @@ async fn e6_oracle_flapping() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -391,6 +394,7 @@ async fn e7_closed_session_drift_bounded_and_band_anchored_to_oracle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -440,6 +444,7 @@ async fn e7_closed_session_drift_bounded_and_band_anchored_to_oracle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
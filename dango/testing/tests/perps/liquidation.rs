@@ -134,6 +134,7 @@ async fn liquidation_on_order_book() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -158,6 +159,7 @@ async fn liquidation_on_order_book() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -223,6 +225,7 @@ async fn liquidation_on_order_book() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -489,6 +492,7 @@ async fn liquidation_snaps_to_full_close_when_remainder_would_be_dust() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -512,6 +516,7 @@ async fn liquidation_snaps_to_full_close_when_remainder_would_be_dust() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -554,6 +559,7 @@ async fn liquidation_snaps_to_full_close_when_remainder_would_be_dust() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -731,6 +737,7 @@ async fn liquidation_with_adl() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -755,6 +762,7 @@ async fn liquidation_with_adl() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -803,6 +811,7 @@ async fn liquidation_with_adl() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -826,6 +835,7 @@ async fn liquidation_with_adl() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1089,6 +1099,7 @@ async fn liquidation_cancels_conditional_orders() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1109,6 +1120,7 @@ async fn liquidation_cancels_conditional_orders() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1126,6 +1138,8 @@ async fn liquidation_cancels_conditional_orders() {
                 trigger_price: UsdPrice::new_int(2_500),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1142,6 +1156,8 @@ async fn liquidation_cancels_conditional_orders() {
                 trigger_price: UsdPrice::new_int(1_500),
                 trigger_direction: TriggerDirection::Below,
                 max_slippage: Dimensionless::new_percent(2),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -1160,11 +1176,11 @@ async fn liquidation_cancels_conditional_orders() {
         .unwrap();
     let pos = state.positions.get(&pair).expect("should have position");
     assert!(
-        pos.conditional_order_above.is_some(),
+        !pos.conditional_orders_above.is_empty(),
         "should have TP (above) conditional order"
     );
     assert!(
-        pos.conditional_order_below.is_some(),
+        !pos.conditional_orders_below.is_empty(),
         "should have SL (below) conditional order"
     );
 
@@ -1197,6 +1213,7 @@ async fn liquidation_cancels_conditional_orders() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1246,12 +1263,12 @@ async fn liquidation_cancels_conditional_orders() {
     if let Some(ref st) = state {
         for (pid, pos) in &st.positions {
             assert!(
-                pos.conditional_order_above.is_none(),
-                "conditional_order_above should be None for pair {pid} after liquidation"
+                pos.conditional_orders_above.is_empty(),
+                "conditional_orders_above should be empty for pair {pid} after liquidation"
             );
             assert!(
-                pos.conditional_order_below.is_none(),
-                "conditional_order_below should be None for pair {pid} after liquidation"
+                pos.conditional_orders_below.is_empty(),
+                "conditional_orders_below should be empty for pair {pid} after liquidation"
             );
         }
     }
@@ -1407,6 +1424,7 @@ async fn vault_liquidation_on_order_book() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1511,6 +1529,7 @@ async fn vault_liquidation_on_order_book() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1696,6 +1715,7 @@ async fn liquidation_book_fills_have_fill_id_adl_does_not() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1715,6 +1735,7 @@ async fn liquidation_book_fills_have_fill_id_adl_does_not() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1749,6 +1770,7 @@ async fn liquidation_book_fills_have_fill_id_adl_does_not() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1768,6 +1790,7 @@ async fn liquidation_book_fills_have_fill_id_adl_does_not() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1797,6 +1820,7 @@ async fn liquidation_book_fills_have_fill_id_adl_does_not() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
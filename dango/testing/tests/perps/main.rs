@@ -11,15 +11,20 @@ use {
 mod adl_bug_reproduction;
 mod bankruptcy_bug_reproduction;
 mod batch_update_orders;
+mod cancel_all_after;
 mod client_order_id;
 mod conditional_orders;
+mod delisting;
 mod index_price;
 mod liquidation;
 mod liquidation_spec;
+mod order_groups;
 mod price_band;
 mod reduce_only;
 mod referral;
+mod subaccounts;
 mod trading;
+mod twap;
 mod vault;
 mod vault_snapshots;
 mod vault_withdrawal_health;
@@ -0,0 +1,282 @@
+use {
+    crate::register_oracle_prices,
+    dango_math::Uint128,
+    dango_order_book::{
+        Dimensionless, OrderId, OrderKind, Quantity, QueryOrdersByUserResponseItem, TimeInForce,
+        TriggerDirection, UsdPrice,
+    },
+    dango_primitives::{Addressable, Coins, Duration, QuerierExt, ResultExt},
+    dango_testing::{TestOption, pair_id, setup_test_naive},
+    dango_types::{
+        constants::usdc,
+        perps::{
+            self, GroupConditionalOrder, GroupLimitOrder, GroupMember, GroupOrder, OrderGroup,
+            OrderGroupId, OrderGroupKind, SubmitOrderGroupRequest, UserState,
+        },
+    },
+    std::collections::BTreeMap,
+};
+
+fn limit_order(size: i128, limit_price: i128) -> GroupLimitOrder {
+    GroupLimitOrder {
+        size: Quantity::new_int(size),
+        limit_price: UsdPrice::new_int(limit_price),
+        post_only: true,
+        reduce_only: false,
+        client_order_id: None,
+    }
+}
+
+fn conditional_order(
+    size: i128,
+    trigger_price: i128,
+    trigger_direction: TriggerDirection,
+) -> GroupOrder {
+    GroupOrder::Conditional(GroupConditionalOrder {
+        size: Some(Quantity::new_int(size)),
+        trigger_price: UsdPrice::new_int(trigger_price),
+        trigger_direction,
+        max_slippage: Dimensionless::new_percent(1),
+        limit_price: None,
+        trailing_offset: None,
+    })
+}
+
+fn market_order(size: i128) -> perps::ExecuteMsg {
+    perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+        pair_id: pair_id(),
+        size: Quantity::new_int(size),
+        kind: OrderKind::Market {
+            max_slippage: Dimensionless::new_percent(50),
+        },
+        reduce_only: false,
+        tp: None,
+        sl: None,
+        stp_mode: Default::default(),
+    }))
+}
+
+/// OCO of a bid and an ask around the price: filling the bid cancels the ask,
+/// and removes the group.
+#[tokio::test]
+async fn oco_fill_cancels_other_order() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let user1 = accounts.user1.address();
+
+    for (account, amount) in [
+        (&mut accounts.user1, 10_000_000_000),
+        (&mut accounts.user2, 10_000_000_000),
+    ] {
+        suite
+            .execute(
+                account,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+                Coins::one(usdc::DENOM.clone(), Uint128::new(amount)).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // Bid 1 ETH @ $1,990, or ask 1 ETH @ $2,010.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrderGroup {
+                pair_id: pair_id(),
+                group: SubmitOrderGroupRequest::Oco {
+                    orders: vec![
+                        GroupOrder::Limit(limit_order(1, 1_990)),
+                        GroupOrder::Limit(limit_order(-1, 2_010)),
+                    ],
+                },
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let groups: BTreeMap<OrderGroupId, OrderGroup> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrderGroupsByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(groups.len(), 1);
+    assert!(matches!(
+        &groups.values().next().unwrap().kind,
+        OrderGroupKind::Oco { members } if members.len() == 2
+    ));
+
+    // Sell into the bid.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &market_order(-1),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // The ask has been canceled, and the group removed.
+    let orders: BTreeMap<OrderId, QueryOrdersByUserResponseItem> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert!(orders.is_empty());
+
+    suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrderGroupsByUserRequest { user: user1 },
+        )
+        .should_succeed_and_equal(BTreeMap::new());
+
+    let state: UserState = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap();
+
+    assert_eq!(
+        state.positions.get(&pair_id()).unwrap().size,
+        Quantity::new_int(1)
+    );
+}
+
+/// OTO of a bid with a TP and an SL: once the bid is filled, the cron places
+/// the TP and the SL as an OCO group.
+#[tokio::test]
+async fn oto_fill_places_secondaries() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let user1 = accounts.user1.address();
+
+    for (account, amount) in [
+        (&mut accounts.user1, 10_000_000_000),
+        (&mut accounts.user2, 10_000_000_000),
+    ] {
+        suite
+            .execute(
+                account,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+                Coins::one(usdc::DENOM.clone(), Uint128::new(amount)).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // Bid 2 ETH @ $2,000; once filled, TP @ $2,500 and SL @ $1,800.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrderGroup {
+                pair_id: pair_id(),
+                group: SubmitOrderGroupRequest::Oto {
+                    primary: limit_order(2, 2_000),
+                    secondaries: vec![
+                        conditional_order(-2, 2_500, TriggerDirection::Above),
+                        conditional_order(-2, 1_800, TriggerDirection::Below),
+                    ],
+                },
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Partially fill the bid: the group isn't activated yet.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &market_order(-1),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.increase_time(Duration::from_minutes(1)).await;
+
+    let groups: BTreeMap<OrderGroupId, OrderGroup> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrderGroupsByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(groups.len(), 1);
+    assert!(matches!(
+        groups.values().next().unwrap().kind,
+        OrderGroupKind::Oto { .. }
+    ));
+
+    // Fill the rest of the bid. The cron then places the secondaries.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &market_order(-1),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.increase_time(Duration::from_minutes(1)).await;
+
+    let groups: BTreeMap<OrderGroupId, OrderGroup> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrderGroupsByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(groups.len(), 1);
+
+    let OrderGroupKind::Oco { members } = &groups.values().next().unwrap().kind else {
+        panic!("expected an oco group");
+    };
+
+    assert!(matches!(
+        members[..],
+        [
+            GroupMember::Conditional {
+                trigger_direction: TriggerDirection::Above,
+                ..
+            },
+            GroupMember::Conditional {
+                trigger_direction: TriggerDirection::Below,
+                ..
+            },
+        ]
+    ));
+
+    let state: UserState = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap();
+
+    let position = state.positions.get(&pair_id()).unwrap();
+
+    assert_eq!(position.size, Quantity::new_int(2));
+    assert_eq!(position.conditional_orders_above.len(), 1);
+    assert_eq!(position.conditional_orders_below.len(), 1);
+}
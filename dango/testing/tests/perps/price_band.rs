@@ -79,6 +79,7 @@ macro_rules! submit_limit {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -194,6 +195,7 @@ async fn banding_does_not_affect_market_orders() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -286,6 +288,7 @@ async fn banding_drift_maker_cancelled_at_match_time() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -318,6 +321,7 @@ async fn banding_drift_maker_cancelled_at_match_time() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -111,6 +111,7 @@ fn post_only(pair: &PairId, size: i128, price: i128, reduce_only: bool) -> perps
         reduce_only,
         tp: None,
         sl: None,
+        stp_mode: Default::default(),
     }))
 }
 
@@ -125,6 +126,7 @@ fn market(pair: &PairId, size: i128, reduce_only: bool) -> perps::ExecuteMsg {
         reduce_only,
         tp: None,
         sl: None,
+        stp_mode: Default::default(),
     }))
 }
 
@@ -1634,6 +1636,7 @@ async fn reduce_only_resize_within_batch() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )])
                 .unwrap(),
@@ -1730,6 +1733,7 @@ async fn reduce_only_batch_rejection_rolls_back_fill() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                     perps::SubmitOrCancelOrderRequest::Submit(perps::SubmitOrderRequest {
                         pair_id: pair.clone(),
@@ -1742,6 +1746,7 @@ async fn reduce_only_batch_rejection_rolls_back_fill() {
                         reduce_only: true,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     }),
                 ])
                 .unwrap(),
@@ -1843,6 +1848,7 @@ async fn reduce_only_partial_fill_evicts_worse_order() {
                 reduce_only: true,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2120,6 +2126,8 @@ async fn reduce_only_resize_on_conditional_trigger() {
                 trigger_price: UsdPrice::new_int(2_100),
                 trigger_direction: TriggerDirection::Above,
                 max_slippage: Dimensionless::new_percent(1),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
@@ -2354,6 +2362,7 @@ async fn reduce_only_child_orders_on_resized_order() {
                     max_slippage: Dimensionless::new_percent(1),
                     size: None,
                 }),
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
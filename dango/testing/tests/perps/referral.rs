@@ -2786,6 +2786,7 @@ async fn place_ask_order(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2812,6 +2813,7 @@ async fn place_market_buy(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2857,6 +2859,7 @@ async fn place_market_buy_with_events(
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
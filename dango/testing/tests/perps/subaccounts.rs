@@ -0,0 +1,95 @@
+use {
+    dango_math::Uint128,
+    dango_order_book::UsdValue,
+    dango_primitives::{Addressable, Coins, QuerierExt, ResultExt},
+    dango_testing::{TestOption, setup_test_naive},
+    dango_types::{
+        account_factory::{self, UserIndexOrName},
+        constants::usdc,
+        perps,
+    },
+};
+
+/// A user moves margin from their main account to a subaccount, then reads the
+/// combined state of both. Margin can't be moved to another user's account.
+#[tokio::test]
+async fn transfer_margin_between_subaccounts() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    let user1 = accounts.user1.address();
+    let user_index = accounts.user1.user_index();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(1_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    // Open a second account for the same user.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterAccount {},
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let subaccount = suite
+        .query_wasm_smart(
+            contracts.account_factory,
+            account_factory::QueryUserRequest(UserIndexOrName::Index(user_index)),
+        )
+        .should_succeed()
+        .accounts
+        .into_values()
+        .find(|address| *address != user1)
+        .unwrap();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::TransferMargin {
+                to: subaccount,
+                amount: UsdValue::new_int(400),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let states = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QuerySubaccountStatesRequest { user: user_index },
+        )
+        .should_succeed();
+
+    assert_eq!(states.margin, UsdValue::new_int(1_000));
+    assert_eq!(states.subaccounts.len(), 2);
+    assert_eq!(states.subaccounts[&user1].margin, UsdValue::new_int(600));
+    assert_eq!(
+        states.subaccounts[&subaccount].margin,
+        UsdValue::new_int(400)
+    );
+
+    // Transferring to another user's account is rejected.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::TransferMargin {
+                to: accounts.user2.address(),
+                amount: UsdValue::new_int(100),
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("can only transfer margin between accounts owned by the same user");
+}
@@ -92,6 +92,7 @@ async fn trading_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -128,6 +129,7 @@ async fn trading_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -280,6 +282,7 @@ async fn limit_order_partial_fill_and_cancel() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -307,6 +310,7 @@ async fn limit_order_partial_fill_and_cancel() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -508,6 +512,7 @@ async fn liquidity_depth_tracking() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -548,6 +553,7 @@ async fn liquidity_depth_tracking() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -592,6 +598,7 @@ async fn liquidity_depth_tracking() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -724,6 +731,7 @@ async fn protocol_fee_accumulates_across_fills() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -743,6 +751,7 @@ async fn protocol_fee_accumulates_across_fills() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -778,6 +787,7 @@ async fn protocol_fee_accumulates_across_fills() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -797,6 +807,7 @@ async fn protocol_fee_accumulates_across_fills() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -905,6 +916,7 @@ async fn negative_maker_fee_rebate_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -932,6 +944,7 @@ async fn negative_maker_fee_rebate_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1054,6 +1067,7 @@ async fn ioc_limit_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1081,6 +1095,7 @@ async fn ioc_limit_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1176,6 +1191,7 @@ async fn ioc_limit_order_no_fill_rejected() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1252,6 +1268,7 @@ async fn slippage_cap_market_at_cap_accepted_at_submission() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1280,6 +1297,7 @@ async fn slippage_cap_market_above_cap_rejected() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1312,6 +1330,7 @@ async fn slippage_cap_tpsl_child_order_above_cap_rejected() {
                     size: None,
                 }),
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1344,6 +1363,7 @@ async fn slippage_cap_does_not_affect_limit_orders() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1405,6 +1425,7 @@ async fn fill_id_is_shared_across_match_sides_and_increments_per_match() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )),
                 Coins::new(),
@@ -1427,6 +1448,7 @@ async fn fill_id_is_shared_across_match_sides_and_increments_per_match() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1574,6 +1596,7 @@ async fn order_filled_reports_resulting_position_size_on_flip() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1592,6 +1615,7 @@ async fn order_filled_reports_resulting_position_size_on_flip() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1615,6 +1639,7 @@ async fn order_filled_reports_resulting_position_size_on_flip() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1633,6 +1658,7 @@ async fn order_filled_reports_resulting_position_size_on_flip() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1713,6 +1739,7 @@ async fn order_filled_reports_maker_remainder_on_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1733,6 +1760,7 @@ async fn order_filled_reports_maker_remainder_on_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1858,6 +1886,7 @@ async fn order_filled_taker_remainder_matches_persisted_order() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1880,6 +1909,7 @@ async fn order_filled_taker_remainder_matches_persisted_order() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2039,6 +2069,7 @@ async fn sell_side_market_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2067,6 +2098,7 @@ async fn sell_side_market_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2168,6 +2200,7 @@ async fn buy_side_market_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -2193,6 +2226,7 @@ async fn buy_side_market_order_partial_fill() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -0,0 +1,324 @@
+use {
+    crate::register_oracle_prices,
+    dango_math::Uint128,
+    dango_order_book::{Dimensionless, OrderKind, Quantity, TimeInForce, UsdPrice},
+    dango_primitives::{
+        Addressable, CheckedContractEvent, Coins, Duration, JsonDeExt, QuerierExt, ResultExt,
+        SearchEvent,
+    },
+    dango_testing::{TestOption, pair_id, setup_test_naive},
+    dango_types::{
+        constants::usdc,
+        perps::{self, CancelTwapOrderRequest, TwapOrder, TwapSliceSkipped, UserState},
+    },
+    std::collections::BTreeMap,
+};
+
+/// Full lifecycle: maker places ask → trader submits a TWAP buy of 4 ETH in
+/// 4 one-minute slices → the cron executes one slice per minute → the order is
+/// removed once fully filled.
+#[tokio::test]
+async fn twap_order_executes_in_slices() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let pair = pair_id();
+    let user1 = accounts.user1.address();
+
+    for (account, amount) in [
+        (&mut accounts.user1, 10_000_000_000),
+        (&mut accounts.user2, 100_000_000_000),
+    ] {
+        suite
+            .execute(
+                account,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+                Coins::one(usdc::DENOM.clone(), Uint128::new(amount)).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // Maker places ask: 10 ETH @ $2,000.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(-10),
+                kind: OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(2_000),
+                    time_in_force: TimeInForce::PostOnly,
+                    client_order_id: None,
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Trader submits a TWAP buy: 4 ETH over 4 minutes, 1 ETH per slice.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitTwapOrder {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(4),
+                duration: Duration::from_minutes(4),
+                slice_interval: Duration::from_minutes(1),
+                max_slippage: Dimensionless::new_percent(1),
+                reduce_only: false,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let twap_orders: BTreeMap<perps::TwapOrderId, TwapOrder> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryTwapOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed();
+
+    assert_eq!(twap_orders.len(), 1);
+
+    let (twap_id, twap_order) = twap_orders.into_iter().next().unwrap();
+
+    assert_eq!(twap_order.size, Quantity::new_int(4));
+    assert_eq!(twap_order.filled_size, Quantity::ZERO);
+    assert_eq!(twap_order.remaining_slices, 4);
+
+    // After one cron run, some but not all of the order has been filled.
+    suite.increase_time(Duration::from_minutes(1)).await;
+
+    let twap_order = suite
+        .query_wasm_smart(contracts.perps, perps::QueryTwapOrderRequest { twap_id })
+        .should_succeed()
+        .expect("twap order should still be active");
+
+    assert!(twap_order.filled_size.is_positive());
+    assert!(twap_order.filled_size < Quantity::new_int(4));
+
+    // Let the remaining slices run.
+    for _ in 0..4 {
+        suite.increase_time(Duration::from_minutes(1)).await;
+    }
+
+    // The order has been fully filled and removed.
+    suite
+        .query_wasm_smart(contracts.perps, perps::QueryTwapOrderRequest { twap_id })
+        .should_succeed_and_equal(None);
+
+    let state: UserState = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap();
+
+    assert_eq!(
+        state.positions.get(&pair).unwrap().size,
+        Quantity::new_int(4)
+    );
+}
+
+/// Canceling a TWAP order stops further slices; the executed ones stay.
+#[tokio::test]
+async fn twap_order_cancel_stops_slices() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let pair = pair_id();
+    let user1 = accounts.user1.address();
+
+    for (account, amount) in [
+        (&mut accounts.user1, 10_000_000_000),
+        (&mut accounts.user2, 100_000_000_000),
+    ] {
+        suite
+            .execute(
+                account,
+                contracts.perps,
+                &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+                Coins::one(usdc::DENOM.clone(), Uint128::new(amount)).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // Maker places bid: 10 ETH @ $2,000.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(10),
+                kind: OrderKind::Limit {
+                    limit_price: UsdPrice::new_int(2_000),
+                    time_in_force: TimeInForce::PostOnly,
+                    client_order_id: None,
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Trader submits a TWAP sell: 5 ETH over 10 minutes, 0.5 ETH per slice.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitTwapOrder {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(-5),
+                duration: Duration::from_minutes(10),
+                slice_interval: Duration::from_minutes(1),
+                max_slippage: Dimensionless::new_percent(1),
+                reduce_only: false,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.increase_time(Duration::from_minutes(1)).await;
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::CancelTwapOrder(
+                CancelTwapOrderRequest::All,
+            )),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let size_after_cancel = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap()
+        .positions
+        .get(&pair)
+        .unwrap()
+        .size;
+
+    assert!(size_after_cancel.is_negative());
+    assert!(size_after_cancel > Quantity::new_int(-5));
+
+    suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryTwapOrdersByUserRequest { user: user1 },
+        )
+        .should_succeed_and_equal(BTreeMap::new());
+
+    // No more slices are executed.
+    suite.increase_time(Duration::from_minutes(5)).await;
+
+    let state = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryUserStateRequest { user: user1 },
+        )
+        .should_succeed()
+        .unwrap();
+
+    assert_eq!(state.positions.get(&pair).unwrap().size, size_after_cancel);
+}
+
+/// A slice that can't fill (here, for lack of liquidity) emits a
+/// `twap_slice_skipped` event, and its size is carried over to the remaining
+/// slices.
+#[tokio::test]
+async fn twap_slice_without_liquidity_is_skipped() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let pair = pair_id();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    // Trader submits a TWAP buy: 4 ETH over 4 minutes. There's no ask to buy
+    // from.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitTwapOrder {
+                pair_id: pair.clone(),
+                size: Quantity::new_int(4),
+                duration: Duration::from_minutes(4),
+                slice_interval: Duration::from_minutes(1),
+                max_slippage: Dimensionless::new_percent(1),
+                reduce_only: false,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // `increase_time` discards the block outcome, so inline its body to keep
+    // access to the cron-emitted events.
+    let old_block_time = suite.block_time;
+    suite.block_time = Duration::from_minutes(1);
+    let outcome = suite.make_empty_block().await;
+    suite.block_time = old_block_time;
+
+    let skipped = outcome
+        .block_outcome
+        .search_event::<CheckedContractEvent>()
+        .with_predicate(|e| e.ty == "twap_slice_skipped")
+        .take()
+        .one()
+        .event
+        .data
+        .deserialize_json::<TwapSliceSkipped>()
+        .unwrap();
+
+    assert_eq!(skipped.slice_size, Quantity::new_int(1));
+    assert_eq!(skipped.remaining_slices, 3);
+
+    let twap_order = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryTwapOrderRequest {
+                twap_id: skipped.twap_id,
+            },
+        )
+        .should_succeed()
+        .expect("twap order should still be active");
+
+    assert_eq!(twap_order.filled_size, Quantity::ZERO);
+    assert_eq!(twap_order.remaining_slices, 3);
+}
@@ -7,16 +7,21 @@ use {
         UsdValue,
     },
     dango_primitives::{
-        Addressable, Coins, Duration, QuerierExt, ResultExt, Timestamp, btree_map, concat,
+        Addr, Addressable, Coins, Duration, QuerierExt, ResultExt, Timestamp, btree_map, concat,
     },
     dango_pyth_types::MarketSession,
-    dango_testing::{OracleTestEntry, TestOption, pair_id, setup_test_naive},
+    dango_testing::{
+        OracleTestEntry, TestAccounts, TestOption, TestSuiteNaive, pair_id, setup_test_naive,
+    },
     dango_types::{
         constants::usdc,
         oracle::QueryPriceRequest,
-        perps::{self, PairParam, Param},
+        perps::{self, PairParam, Param, VaultLadder},
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        str::FromStr,
     },
-    std::{collections::BTreeMap, str::FromStr},
 };
 
 /// Covers: add liquidity → vault trades → realized PnL reflected in share
@@ -220,6 +225,7 @@ async fn vault_lp_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -296,6 +302,7 @@ async fn vault_lp_lifecycle() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -871,6 +878,7 @@ async fn vault_overcommits_margin_after_position_and_price_drop() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1018,6 +1026,7 @@ async fn vault_overcommits_margin_after_position_and_price_drop() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -1071,3 +1080,189 @@ async fn vault_overcommits_margin_after_position_and_price_drop() {
         "vault should be healthy: equity ({equity}) >= maintenance_margin ({maintenance_margin})"
     );
 }
+
+/// Refresh the index prices, then the vault's orders, in a fresh block.
+/// Returns the vault's resting orders.
+async fn refresh_vault_orders(
+    suite: &mut TestSuiteNaive,
+    accounts: &mut TestAccounts,
+    perps_addr: Addr,
+) -> BTreeMap<OrderId, QueryOrdersByUserResponseItem> {
+    suite.make_empty_block().await;
+
+    for msg in [
+        perps::MaintainerMsg::RefreshIndexPrices {},
+        perps::MaintainerMsg::RefreshVaultOrders {},
+    ] {
+        suite
+            .execute(
+                &mut accounts.owner,
+                perps_addr,
+                &perps::ExecuteMsg::Maintain(msg),
+                Coins::new(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    suite
+        .query_wasm_smart(
+            perps_addr,
+            perps::QueryOrdersByUserRequest { user: perps_addr },
+        )
+        .should_succeed()
+}
+
+/// The vault quotes a ladder of orders on each side of the book, and replaces
+/// the whole ladder on every refresh.
+///
+/// Setup: oracle = $2,000, vault margin = $5,000, half_spread = 5%,
+/// max_quote_size = 3 ETH, ladder = 3 levels, 1% apart, evenly sized.
+///
+/// base_size = min(2,500 / (2,000 × 10%), 3) = 3 ETH per side, 1 ETH per level.
+///
+/// | level | bid          | ask          |
+/// | ----- | ------------ | ------------ |
+/// | 0     | 1 @ $1,900   | 1 @ $2,100   |
+/// | 1     | 1 @ $1,880   | 1 @ $2,120   |
+/// | 2     | 1 @ $1,860   | 1 @ $2,140   |
+#[tokio::test]
+async fn vault_quotes_ladder() {
+    let (mut suite, mut accounts, _, contracts, _) = setup_test_naive(TestOption::default());
+
+    register_oracle_prices(&mut suite, &mut accounts, 2_000).await;
+
+    let pair = pair_id();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.perps,
+            &perps::ExecuteMsg::Vault(perps::VaultMsg::AddLiquidity {
+                amount: UsdValue::new_int(5_000),
+                min_shares_to_mint: None,
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.perps,
+            &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::Configure {
+                param: Param {
+                    vault_total_weight: Dimensionless::new_int(1),
+                    ..default_param()
+                },
+                pair_params: btree_map! {
+                    pair.clone() => PairParam {
+                        vault_liquidity_weight: Dimensionless::new_int(1),
+                        vault_half_spread: Dimensionless::new_permille(50), // 5%
+                        vault_max_quote_size: Quantity::new_int(3),
+                        vault_ladder: VaultLadder {
+                            levels: 3,
+                            spread_step: Dimensionless::new_permille(10), // 1%
+                            size_ratio: Dimensionless::ONE,
+                        },
+                        ..default_pair_param()
+                    },
+                },
+            }),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let vault_orders = refresh_vault_orders(&mut suite, &mut accounts, contracts.perps).await;
+
+    let quotes = |orders: &BTreeMap<OrderId, QueryOrdersByUserResponseItem>| {
+        orders
+            .values()
+            .map(|o| (o.limit_price, o.size))
+            .collect::<BTreeSet<_>>()
+    };
+
+    assert_eq!(
+        quotes(&vault_orders),
+        BTreeSet::from([
+            (UsdPrice::new_int(1_860), Quantity::new_int(1)),
+            (UsdPrice::new_int(1_880), Quantity::new_int(1)),
+            (UsdPrice::new_int(1_900), Quantity::new_int(1)),
+            (UsdPrice::new_int(2_100), Quantity::new_int(-1)),
+            (UsdPrice::new_int(2_120), Quantity::new_int(-1)),
+            (UsdPrice::new_int(2_140), Quantity::new_int(-1)),
+        ])
+    );
+
+    // A taker sells 1.5 ETH, consuming the top bid and half of the next one.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None }),
+            Coins::one(usdc::DENOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::SubmitOrder(perps::SubmitOrderRequest {
+                pair_id: pair.clone(),
+                size: Quantity::new_raw(-1_500_000),
+                kind: OrderKind::Market {
+                    max_slippage: Dimensionless::new_percent(50),
+                },
+                reduce_only: false,
+                tp: None,
+                sl: None,
+                stp_mode: Default::default(),
+            })),
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    let vault_orders_after_fill: BTreeMap<OrderId, QueryOrdersByUserResponseItem> = suite
+        .query_wasm_smart(
+            contracts.perps,
+            perps::QueryOrdersByUserRequest {
+                user: contracts.perps,
+            },
+        )
+        .should_succeed();
+
+    assert_eq!(vault_orders_after_fill.len(), 5);
+
+    // The next refresh replaces the whole ladder: every order is new, and
+    // each side has three levels again.
+    let new_vault_orders = refresh_vault_orders(&mut suite, &mut accounts, contracts.perps).await;
+
+    assert_eq!(new_vault_orders.len(), 6);
+    assert!(
+        new_vault_orders
+            .keys()
+            .all(|order_id| !vault_orders.contains_key(order_id))
+    );
+    assert_eq!(
+        new_vault_orders
+            .values()
+            .filter(|o| o.size.is_positive())
+            .count(),
+        3
+    );
+}
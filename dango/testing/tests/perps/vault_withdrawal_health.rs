@@ -189,6 +189,7 @@ async fn vault_withdrawal_at_breakeven_makes_vault_liquidatable() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -54,6 +54,7 @@ async fn disabling_trading_blocks_new_risk_but_not_exits() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -94,6 +95,7 @@ async fn disabling_trading_blocks_new_risk_but_not_exits() {
                 reduce_only: false,
                 tp: None,
                 sl: None,
+                stp_mode: Default::default(),
             })),
             Coins::new(),
         )
@@ -118,6 +120,7 @@ async fn disabling_trading_blocks_new_risk_but_not_exits() {
                         reduce_only: false,
                         tp: None,
                         sl: None,
+                        stp_mode: Default::default(),
                     },
                 )]),
             )),
@@ -239,6 +242,8 @@ async fn disabling_trading_blocks_conditional_orders() {
                 trigger_price: UsdPrice::new_int(1_800),
                 trigger_direction: dango_order_book::TriggerDirection::Below,
                 max_slippage: dango_order_book::Dimensionless::new_permille(100),
+                limit_price: None,
+                trailing_offset: None,
             }),
             Coins::new(),
         )
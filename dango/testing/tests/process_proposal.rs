@@ -0,0 +1,251 @@
+use {
+    dango_order_book::{OrderKind, Quantity, TimeInForce, UsdPrice},
+    dango_primitives::{
+        Addr, Addressable, BlockInfo, Coins, Duration as CoreDuration, Inner, Json, JsonDeExt,
+        JsonSerExt, Message, NonEmpty, Timestamp, Tx,
+    },
+    dango_proposal_preparer::MAX_PRICE_UPDATE_AGE,
+    dango_pyth_types::PayloadData,
+    dango_testing::{TestSuite, setup_test},
+    dango_types::{
+        constants::perp_btc,
+        oracle,
+        perps::{CancelOrderRequest, ExecuteMsg, SubmitOrderRequest, TraderMsg},
+    },
+    prost::bytes::Bytes,
+    std::{thread::sleep, time::Duration},
+};
+
+fn trade_tx(sender: Addr, perps: Addr, msg: TraderMsg) -> Bytes {
+    Tx {
+        sender,
+        gas_limit: 1_000_000,
+        msgs: NonEmpty::new_unchecked(vec![
+            Message::execute(perps, &ExecuteMsg::Trade(msg), Coins::new()).unwrap(),
+        ]),
+        data: Json::null(),
+        credential: Json::null(),
+    }
+    .to_json_vec()
+    .unwrap()
+    .into()
+}
+
+fn post_only(sender: Addr, perps: Addr) -> Bytes {
+    trade_tx(
+        sender,
+        perps,
+        TraderMsg::SubmitOrder(SubmitOrderRequest {
+            pair_id: perp_btc::DENOM.clone(),
+            size: Quantity::new_int(1),
+            kind: OrderKind::Limit {
+                limit_price: UsdPrice::new_int(100),
+                time_in_force: TimeInForce::PostOnly,
+                client_order_id: None,
+            },
+            reduce_only: false,
+            tp: None,
+            sl: None,
+            stp_mode: Default::default(),
+        }),
+    )
+}
+
+fn cancel_all(sender: Addr, perps: Addr) -> Bytes {
+    trade_tx(
+        sender,
+        perps,
+        TraderMsg::CancelOrder(CancelOrderRequest::All),
+    )
+}
+
+/// Prepare proposals until the Pyth handler has a price update to inject.
+/// Return the proposal, and the publish time of the injected prices.
+fn prepare_proposal_with_prices(suite: &TestSuite, txs: Vec<Bytes>) -> (Vec<Bytes>, Timestamp) {
+    for _ in 0..20 {
+        let block = next_block(suite, suite.block.timestamp + suite.block_time);
+        let proposal = suite
+            .app
+            .do_prepare_proposal(block, txs.clone(), usize::MAX);
+
+        let oracle_tx: Tx = proposal[0].deserialize_json().unwrap();
+
+        if oracle_tx.sender == suite.contracts.oracle {
+            let Message::Execute(msg) = oracle_tx.msgs.into_inner().remove(0) else {
+                panic!("oracle tx doesn't execute a contract");
+            };
+
+            let oracle::ExecuteMsg::FeedPrices(price_update) = msg.msg.deserialize_json().unwrap()
+            else {
+                panic!("oracle tx doesn't feed prices");
+            };
+
+            let publish_time = price_update
+                .into_inner()
+                .into_iter()
+                .map(|message| {
+                    let payload = PayloadData::deserialize_slice_le(&message.payload).unwrap();
+                    Timestamp::from_micros(payload.timestamp_us.as_micros().into())
+                })
+                .min()
+                .unwrap();
+
+            return (proposal, publish_time);
+        }
+
+        sleep(Duration::from_millis(200));
+    }
+
+    panic!("the proposal preparer didn't inject any price update");
+}
+
+/// The block a validator is asked to process. Its time is given, since the
+/// cached Pyth prices used in tests have nothing to do with the test suite's
+/// clock.
+fn next_block(suite: &TestSuite, timestamp: Timestamp) -> BlockInfo {
+    BlockInfo {
+        height: suite.block.height + 1,
+        timestamp,
+        hash: suite.block.hash,
+    }
+}
+
+#[tokio::test]
+async fn process_proposal_accepts_prepared_proposal() {
+    let (suite, accounts, ..) = setup_test(Default::default());
+
+    let perps = suite.contracts.perps;
+    let user = accounts.user1.address();
+
+    // Submitted out of order; the preparer promotes the placement.
+    let (proposal, publish_time) = prepare_proposal_with_prices(
+        &suite,
+        vec![cancel_all(user, perps), post_only(user, perps)],
+    );
+
+    let block = next_block(&suite, publish_time);
+
+    assert!(suite.app.do_process_proposal(block, &proposal));
+
+    // A block without any injected tx is fine too: the proposer may not have
+    // had a price update to feed.
+    let user_txs = proposal
+        .iter()
+        .filter(|raw_tx| {
+            let tx: Tx = raw_tx.deserialize_json().unwrap();
+            tx.sender != suite.contracts.oracle && tx.sender != perps
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        user_txs,
+        vec![post_only(user, perps), cancel_all(user, perps)]
+    );
+    assert!(suite.app.do_process_proposal(block, &user_txs));
+}
+
+#[tokio::test]
+async fn process_proposal_rejects_violated_maker_priority() {
+    let (suite, accounts, ..) = setup_test(Default::default());
+
+    let perps = suite.contracts.perps;
+    let user = accounts.user1.address();
+
+    let (mut proposal, publish_time) = prepare_proposal_with_prices(
+        &suite,
+        vec![post_only(user, perps), cancel_all(user, perps)],
+    );
+
+    let block = next_block(&suite, publish_time);
+
+    assert!(suite.app.do_process_proposal(block, &proposal));
+
+    // Put the cancellation ahead of the placement.
+    let len = proposal.len();
+    proposal.swap(len - 2, len - 1);
+
+    assert!(!suite.app.do_process_proposal(block, &proposal));
+}
+
+#[tokio::test]
+async fn process_proposal_rejects_bad_injected_txs() {
+    let (suite, accounts, ..) = setup_test(Default::default());
+
+    let perps = suite.contracts.perps;
+    let user = accounts.user1.address();
+
+    let (proposal, publish_time) =
+        prepare_proposal_with_prices(&suite, vec![post_only(user, perps)]);
+
+    let block = next_block(&suite, publish_time);
+
+    assert!(suite.app.do_process_proposal(block, &proposal));
+
+    // Duplicated oracle tx.
+    {
+        let mut proposal = proposal.clone();
+        proposal.insert(1, proposal[0].clone());
+
+        assert!(!suite.app.do_process_proposal(block, &proposal));
+    }
+
+    // Duplicated perps maintenance tx, appended at the bottom of the block.
+    {
+        let mut proposal = proposal.clone();
+        proposal.push(proposal[1].clone());
+
+        assert!(!suite.app.do_process_proposal(block, &proposal));
+    }
+
+    // Oracle tx not on top of the block.
+    {
+        let mut proposal = proposal.clone();
+        let oracle_tx = proposal.remove(0);
+        proposal.push(oracle_tx);
+
+        assert!(!suite.app.do_process_proposal(block, &proposal));
+    }
+
+    // Oracle tx with a different gas limit.
+    {
+        let mut proposal = proposal.clone();
+        let mut oracle_tx: Tx = proposal[0].deserialize_json().unwrap();
+        oracle_tx.gas_limit += 1;
+        proposal[0] = oracle_tx.to_json_vec().unwrap().into();
+
+        assert!(!suite.app.do_process_proposal(block, &proposal));
+    }
+
+    // Tx impersonating the perps contract that isn't a maintenance tx.
+    {
+        let mut proposal = proposal.clone();
+        proposal.push(cancel_all(perps, perps));
+
+        assert!(!suite.app.do_process_proposal(block, &proposal));
+    }
+}
+
+#[tokio::test]
+async fn process_proposal_rejects_stale_prices() {
+    let (suite, accounts, ..) = setup_test(Default::default());
+
+    let perps = suite.contracts.perps;
+    let user = accounts.user1.address();
+
+    let (proposal, publish_time) =
+        prepare_proposal_with_prices(&suite, vec![post_only(user, perps)]);
+
+    // Exactly at the max age, the prices are still fresh enough.
+    let block = next_block(&suite, publish_time + MAX_PRICE_UPDATE_AGE);
+
+    assert!(suite.app.do_process_proposal(block, &proposal));
+
+    // Any later, the block is rejected.
+    let block = next_block(
+        &suite,
+        publish_time + MAX_PRICE_UPDATE_AGE + CoreDuration::from_millis(1),
+    );
+
+    assert!(!suite.app.do_process_proposal(block, &proposal));
+}
@@ -1,7 +1,7 @@
 use {
-    dango_primitives::{Addressable, Coin, Coins, Duration, ResultExt},
+    dango_primitives::{Addressable, Coin, Coins, Duration, ResultExt, btree_map},
     dango_testing::setup_test_naive,
-    dango_types::constants::usdc,
+    dango_types::{auth::SessionPermissions, constants::usdc, perps},
     session_account::SessionAccount,
 };
 
@@ -13,8 +13,8 @@ mod session_account {
         },
         dango_testing::{TestAccount, create_signature, generate_random_key},
         dango_types::auth::{
-            Credential, Metadata, Nonce, SessionCredential, SessionInfo, SignDoc, Signature,
-            StandardCredential,
+            Credential, Metadata, Nonce, SessionCredential, SessionInfo, SessionPermissions,
+            SignDoc, Signature, StandardCredential,
         },
         k256::ecdsa::SigningKey,
         std::ops::{Deref, DerefMut},
@@ -91,11 +91,23 @@ mod session_account {
             self,
             chain_id: &str,
             expire_at: Timestamp,
+        ) -> anyhow::Result<SessionAccount<Defined<SessionInfoBuffer>>> {
+            self.sign_scoped_session_key(chain_id, expire_at, None)
+        }
+
+        // Sign the `SessionInfo` with the username key, restricting the
+        // session key to the given permissions.
+        pub fn sign_scoped_session_key(
+            self,
+            chain_id: &str,
+            expire_at: Timestamp,
+            permissions: Option<SessionPermissions>,
         ) -> anyhow::Result<SessionAccount<Defined<SessionInfoBuffer>>> {
             let session_info = SessionInfo {
                 chain_id: chain_id.to_string(),
                 session_key: self.session_pk,
                 expire_at,
+                permissions,
             };
 
             let sign_data = session_info.to_sign_data()?;
@@ -419,3 +431,74 @@ async fn first_session_nonce_must_clear_account_high_water_mark() {
         .await
         .should_succeed();
 }
+
+/// A scoped session key may only send the contract messages in its allow-list,
+/// and only attach coins up to its spend limit.
+#[tokio::test]
+async fn scoped_session_key() {
+    let (mut suite, accounts, _, contracts, _) = setup_test_naive(Default::default());
+
+    suite.block_time = Duration::from_seconds(1);
+
+    let mut bot = SessionAccount::new(accounts.owner)
+        .sign_scoped_session_key(
+            &suite.chain_id,
+            suite.block.timestamp + Duration::from_seconds(1000),
+            Some(SessionPermissions {
+                contracts: btree_map! {
+                    contracts.perps => ["trade.deposit".to_string()].into(),
+                },
+                spend_limit: Some(Coins::one(usdc::DENOM.clone(), 100).unwrap()),
+            }),
+        )
+        .unwrap();
+
+    let deposit = perps::ExecuteMsg::Trade(perps::TraderMsg::Deposit { to: None });
+
+    // Allowed message, within the spend limit.
+    suite
+        .execute(
+            &mut bot,
+            contracts.perps,
+            &deposit,
+            Coins::one(usdc::DENOM.clone(), 60).unwrap(),
+        )
+        .await
+        .should_succeed();
+
+    // Allowed message, but cumulatively over the spend limit.
+    suite
+        .execute(
+            &mut bot,
+            contracts.perps,
+            &deposit,
+            Coins::one(usdc::DENOM.clone(), 41).unwrap(),
+        )
+        .await
+        .should_fail_with_error("session spend limit exceeded");
+    bot.session_nonce -= 1;
+
+    // A message variant not in the allow-list.
+    suite
+        .execute(
+            &mut bot,
+            contracts.perps,
+            &perps::ExecuteMsg::Trade(perps::TraderMsg::Withdraw {
+                amount: Default::default(),
+            }),
+            Coins::default(),
+        )
+        .await
+        .should_fail_with_error("not allowed to send this message");
+    bot.session_nonce -= 1;
+
+    // A bank transfer.
+    suite
+        .transfer(
+            &mut bot,
+            accounts.user1.address(),
+            Coin::new(usdc::DENOM.clone(), 1).unwrap(),
+        )
+        .await
+        .should_fail_with_error("only allowed to execute contracts");
+}
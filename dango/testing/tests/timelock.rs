@@ -0,0 +1,339 @@
+use {
+    dango_primitives::{
+        Addr, Addressable, Coins, Duration, HashExt, Json, Message, QuerierExt, ResultExt,
+        btree_map, btree_set,
+    },
+    dango_testing::{BalanceChange, TestAccounts, TestSuiteNaive, setup_test_naive},
+    dango_types::{
+        constants::usdc,
+        perps,
+        timelock::{self, Params, QueryActionsRequest, QueryParamsRequest},
+    },
+};
+
+const MIN_DELAY: Duration = Duration::from_days(2);
+
+/// Instantiate a timelock with the genesis owner as admin and user 1 as
+/// guardian, then make it the chain owner.
+async fn setup_test() -> (TestSuiteNaive, TestAccounts, Addr, Addr) {
+    let (mut suite, mut accounts, codes, contracts, _) = setup_test_naive(Default::default());
+
+    let timelock = suite
+        .instantiate(
+            &mut accounts.owner,
+            codes.timelock.to_bytes().sha2_256(),
+            &timelock::InstantiateMsg {
+                params: Params {
+                    admin: accounts.owner.address(),
+                    guardians: btree_set! { accounts.user1.address() },
+                    min_delay: MIN_DELAY,
+                },
+            },
+            "dango/timelock",
+            Some("dango/timelock"),
+            None,
+            Coins::one(usdc::DENOM.clone(), 100_000_000).unwrap(),
+        )
+        .await
+        .should_succeed()
+        .address;
+
+    let mut new_cfg = suite.query_config().unwrap();
+    new_cfg.owner = timelock;
+    suite
+        .configure::<Json>(&mut accounts.owner, Some(new_cfg), None)
+        .await
+        .should_succeed();
+
+    (suite, accounts, timelock, contracts.perps)
+}
+
+#[tokio::test]
+async fn queued_action_executes_after_delay() {
+    let (mut suite, mut accounts, timelock, _) = setup_test().await;
+
+    let transfer = Message::transfer(
+        accounts.user2.address(),
+        Coins::one(usdc::DENOM.clone(), 10_000_000).unwrap(),
+    )
+    .unwrap();
+
+    // Only the admin can queue actions.
+    suite
+        .execute(
+            &mut accounts.user1,
+            timelock,
+            &timelock::ExecuteMsg::Queue {
+                messages: vec![transfer.clone()],
+                delay: None,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the admin can queue actions");
+
+    // The delay can't be shorter than the minimum.
+    suite
+        .execute(
+            &mut accounts.owner,
+            timelock,
+            &timelock::ExecuteMsg::Queue {
+                messages: vec![transfer.clone()],
+                delay: Some(Duration::from_days(1)),
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("invalid `delay`");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            timelock,
+            &timelock::ExecuteMsg::Queue {
+                messages: vec![transfer.clone()],
+                delay: None,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            timelock,
+            QueryActionsRequest {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .should_succeed_and(|actions| actions.len() == 1 && actions[&0].messages == [transfer]);
+
+    // The action can't be executed before the delay has elapsed.
+    suite
+        .execute(
+            &mut accounts.user3,
+            timelock,
+            &timelock::ExecuteMsg::Execute { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("isn't executable until");
+
+    suite.increase_time(MIN_DELAY).await;
+
+    suite.balances().record(&accounts.user2);
+
+    // Once it has, anyone can execute it.
+    suite
+        .execute(
+            &mut accounts.user3,
+            timelock,
+            &timelock::ExecuteMsg::Execute { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user2,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(10_000_000) },
+    );
+
+    suite
+        .query_wasm_smart(
+            timelock,
+            QueryActionsRequest {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .should_succeed_and(|actions| actions.is_empty());
+}
+
+#[tokio::test]
+async fn guardian_cancels_action() {
+    let (mut suite, mut accounts, timelock, _) = setup_test().await;
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            timelock,
+            &timelock::ExecuteMsg::Queue {
+                messages: vec![
+                    Message::transfer(
+                        accounts.user2.address(),
+                        Coins::one(usdc::DENOM.clone(), 10_000_000).unwrap(),
+                    )
+                    .unwrap(),
+                ],
+                delay: None,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    // Only the admin or a guardian can cancel.
+    suite
+        .execute(
+            &mut accounts.user2,
+            timelock,
+            &timelock::ExecuteMsg::Cancel { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the admin or a guardian can cancel actions");
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            timelock,
+            &timelock::ExecuteMsg::Cancel { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.increase_time(MIN_DELAY).await;
+
+    suite
+        .execute(
+            &mut accounts.user3,
+            timelock,
+            &timelock::ExecuteMsg::Execute { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("action 0 doesn't exist");
+}
+
+#[tokio::test]
+async fn privileged_actions_go_through_timelock() {
+    let (mut suite, mut accounts, timelock, perps_addr) = setup_test().await;
+
+    // The former owner can no longer act directly.
+    suite
+        .execute(
+            &mut accounts.owner,
+            perps_addr,
+            &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::DisableTrading {}),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the chain owner can disable trading");
+
+    // Nor can anyone update the timelock's parameters other than through a
+    // queued action.
+    let new_params = Params {
+        admin: accounts.owner.address(),
+        guardians: btree_set! { accounts.user1.address(), accounts.user2.address() },
+        min_delay: MIN_DELAY,
+    };
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            timelock,
+            &timelock::ExecuteMsg::UpdateParams(new_params.clone()),
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the timelock itself");
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            timelock,
+            &timelock::ExecuteMsg::Queue {
+                messages: vec![
+                    Message::execute(
+                        timelock,
+                        &timelock::ExecuteMsg::UpdateParams(new_params.clone()),
+                        Coins::new(),
+                    )
+                    .unwrap(),
+                ],
+                delay: None,
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite.increase_time(MIN_DELAY).await;
+
+    suite
+        .execute(
+            &mut accounts.user3,
+            timelock,
+            &timelock::ExecuteMsg::Execute { action_id: 0 },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(timelock, QueryParamsRequest {})
+        .should_succeed_and_equal(new_params);
+}
+
+#[tokio::test]
+async fn emergency_action_bypasses_delay() {
+    let (mut suite, mut accounts, timelock, perps_addr) = setup_test().await;
+
+    // Only emergency actions can bypass the delay.
+    suite
+        .execute(
+            &mut accounts.user1,
+            timelock,
+            &timelock::ExecuteMsg::Emergency {
+                messages: vec![
+                    Message::transfer(
+                        accounts.user1.address(),
+                        Coins::one(usdc::DENOM.clone(), 10_000_000).unwrap(),
+                    )
+                    .unwrap(),
+                ],
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only emergency actions can be executed without delay");
+
+    let disable_trading = Message::execute(
+        perps_addr,
+        &perps::ExecuteMsg::Maintain(perps::MaintainerMsg::DisableTrading {}),
+        Coins::new(),
+    )
+    .unwrap();
+
+    // Only the admin or a guardian can perform emergency actions.
+    suite
+        .execute(
+            &mut accounts.user2,
+            timelock,
+            &timelock::ExecuteMsg::Emergency {
+                messages: vec![disable_trading.clone()],
+            },
+            Coins::new(),
+        )
+        .await
+        .should_fail_with_error("only the admin or a guardian");
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            timelock,
+            &timelock::ExecuteMsg::Emergency {
+                messages: vec![disable_trading],
+            },
+            Coins::new(),
+        )
+        .await
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(perps_addr, perps::QueryParamRequest {})
+        .should_succeed_and(|param| !param.trading_enabled);
+}
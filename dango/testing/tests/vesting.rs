@@ -1,12 +1,15 @@
 use {
     dango_math::{MultiplyFraction, Udec128, Uint128},
     dango_primitives::{
-        Addr, Addressable, Coin, Coins, Duration, Inner, QuerierExt, ResultExt, StdError, Timestamp,
+        Addr, Addressable, Coin, Coins, Duration, Inner, QuerierExt, ResultExt, Timestamp,
+        btree_map,
     },
-    dango_testing::{TestAccounts, TestSuiteNaive, setup_test_naive},
+    dango_testing::{BalanceChange, TestAccounts, TestSuiteNaive, setup_test_naive},
     dango_types::{
         constants::{dango, usdc},
-        vesting::{self, QueryPositionRequest, Schedule, VestingStatus},
+        vesting::{
+            self, LinearSchedule, QueryPositionRequest, Schedule, StepSchedule, VestingStatus,
+        },
     },
     std::sync::LazyLock,
 };
@@ -35,11 +38,11 @@ async fn missing_funds() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: Duration::from_seconds(0),
                     cliff: Duration::from_seconds(0),
                     period: Duration::from_seconds(0),
-                },
+                }),
             },
             Coins::default(),
         )
@@ -57,11 +60,11 @@ async fn non_owner_creating_position() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.owner.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: Duration::from_seconds(0),
                     cliff: Duration::from_seconds(0),
                     period: Duration::from_seconds(0),
-                },
+                }),
             },
             Coins::one(dango::DENOM.clone(), 100).unwrap(),
         )
@@ -69,29 +72,211 @@ async fn non_owner_creating_position() {
         .should_fail_with_error("you don't have the right");
 }
 
+/// Positions of denoms other than the Dango token aren't subject to the Dango
+/// token's unlocking schedule.
 #[tokio::test]
 async fn not_dango_token() {
     let (mut suite, mut accounts, vesting_addr) = setup_test();
 
+    // A fully vested Dango position can't be claimed before the unlocking
+    // cliff. A fully vested USDC position can.
+    for coin in [
+        Coin::new(dango::DENOM.clone(), 100).unwrap(),
+        Coin::new(usdc::DENOM.clone(), 100).unwrap(),
+    ] {
+        suite
+            .execute(
+                &mut accounts.owner,
+                vesting_addr,
+                &vesting::ExecuteMsg::Create {
+                    user: accounts.user1.address(),
+                    schedule: Schedule::Linear(LinearSchedule {
+                        start_time: Duration::from_seconds(0),
+                        cliff: Duration::from_seconds(0),
+                        period: Duration::from_seconds(0),
+                    }),
+                },
+                coin,
+            )
+            .await
+            .should_succeed();
+    }
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            vesting_addr,
+            &vesting::ExecuteMsg::Claim {
+                id: 0,
+                recipient: None,
+            },
+            Coins::default(),
+        )
+        .await
+        .should_fail_with_error("nothing to claim");
+
+    suite.balances().record(&accounts.user1);
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            vesting_addr,
+            &vesting::ExecuteMsg::Claim {
+                id: 1,
+                recipient: None,
+            },
+            Coins::default(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user1,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(100) },
+    );
+}
+
+#[tokio::test]
+async fn multiple_positions_with_step_schedule() {
+    let (mut suite, mut accounts, vesting_addr) = setup_test();
+
+    // Zero steps are rejected.
     suite
         .execute(
             &mut accounts.owner,
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
-                    start_time: Duration::from_seconds(0),
-                    cliff: Duration::from_seconds(0),
-                    period: Duration::from_seconds(0),
+                schedule: Schedule::Step(StepSchedule {
+                    start_time: suite.block.timestamp,
+                    cliff: Duration::ZERO,
+                    interval: ONE_MONTH,
+                    num_steps: 0,
+                }),
+            },
+            Coin::new(usdc::DENOM.clone(), 100).unwrap(),
+        )
+        .await
+        .should_fail_with_error("number of steps must be greater than zero");
+
+    // Two tranches of USDC: one unlocking monthly over 4 months after a
+    // 2-month cliff, the other linearly over 4 months.
+    let start_time = suite.block.timestamp;
+
+    for schedule in [
+        Schedule::Step(StepSchedule {
+            start_time,
+            cliff: ONE_MONTH * 2,
+            interval: ONE_MONTH,
+            num_steps: 4,
+        }),
+        Schedule::Linear(LinearSchedule {
+            start_time,
+            cliff: Duration::ZERO,
+            period: ONE_MONTH * 4,
+        }),
+    ] {
+        suite
+            .execute(
+                &mut accounts.owner,
+                vesting_addr,
+                &vesting::ExecuteMsg::Create {
+                    user: accounts.user1.address(),
+                    schedule,
                 },
+                Coin::new(usdc::DENOM.clone(), 100).unwrap(),
+            )
+            .await
+            .should_succeed();
+    }
+
+    // One month and a half in: the step tranche is still in its cliff; the
+    // linear tranche has vested 37.5%.
+    suite.block_time = Duration::from_weeks(6);
+    suite.make_empty_block().await;
+
+    suite
+        .query_wasm_smart(
+            vesting_addr,
+            vesting::QueryPositionsRequest {
+                user: accounts.user1.address(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .should_succeed_and(|positions| {
+            positions.len() == 2
+                && positions[&0].claimable == Uint128::new(0)
+                && positions[&1].claimable == Uint128::new(37)
+        });
+
+    // Two months and a half in: the cliff has passed, and two of the four
+    // steps have happened.
+    suite.block_time = ONE_MONTH;
+    suite.make_empty_block().await;
+
+    suite
+        .query_wasm_smart(
+            vesting_addr,
+            vesting::QueryPositionRequest {
+                user: accounts.user1.address(),
+                id: 0,
+            },
+        )
+        .should_succeed_and(|res| res.claimable == Uint128::new(50));
+
+    // Claim the step tranche to another recipient.
+    suite.block_time = Duration::ZERO;
+
+    suite
+        .balances()
+        .record_many([&accounts.user1.address(), &accounts.user2.address()]);
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            vesting_addr,
+            &vesting::ExecuteMsg::Claim {
+                id: 0,
+                recipient: Some(accounts.user2.address()),
+            },
+            Coins::default(),
+        )
+        .await
+        .should_succeed();
+
+    suite.balances().should_change(
+        &accounts.user2,
+        btree_map! { usdc::DENOM.clone() => BalanceChange::Increased(50) },
+    );
+
+    // Only the position's owner can claim it.
+    suite
+        .execute(
+            &mut accounts.user2,
+            vesting_addr,
+            &vesting::ExecuteMsg::Claim {
+                id: 1,
+                recipient: None,
             },
-            Coins::one(usdc::DENOM.clone(), 100).unwrap(),
+            Coins::default(),
         )
         .await
-        .should_fail_with_error(StdError::invalid_payment(
-            dango::DENOM.clone(),
-            usdc::DENOM.clone(),
-        ));
+        .should_fail_with_error("data not found");
+
+    // Past the last step, the step tranche is fully vested.
+    suite.block_time = ONE_MONTH * 2;
+    suite.make_empty_block().await;
+
+    suite
+        .query_wasm_smart(
+            vesting_addr,
+            vesting::QueryPositionRequest {
+                user: accounts.user1.address(),
+                id: 0,
+            },
+        )
+        .should_succeed_and(|res| res.claimable == Uint128::new(50));
 }
 
 #[tokio::test]
@@ -104,11 +289,11 @@ async fn before_unlocking_starting_time() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: suite.block.timestamp - ONE_MONTH,
                     cliff: ONE_MONTH * 9,
                     period: ONE_MONTH * 27,
-                },
+                }),
             },
             TEST_AMOUNT.clone(),
         )
@@ -127,7 +312,10 @@ async fn before_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -142,7 +330,10 @@ async fn before_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -167,7 +358,10 @@ async fn before_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -192,7 +386,10 @@ async fn before_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -208,6 +405,7 @@ async fn before_unlocking_starting_time() {
                 vesting_addr,
                 vesting::QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| res.position.claimed == res.position.total);
@@ -224,11 +422,11 @@ async fn after_unlocking_starting_time() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: suite.block.timestamp + ONE_MONTH,
                     cliff: ONE_MONTH * 9,
                     period: ONE_MONTH * 27,
-                },
+                }),
             },
             TEST_AMOUNT.clone(),
         )
@@ -247,7 +445,10 @@ async fn after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -262,7 +463,10 @@ async fn after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -278,7 +482,10 @@ async fn after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -303,7 +510,10 @@ async fn after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -328,7 +538,10 @@ async fn after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -344,6 +557,7 @@ async fn after_unlocking_starting_time() {
                 vesting_addr,
                 vesting::QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| res.position.claimed == res.position.total);
@@ -360,11 +574,11 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: suite.block.timestamp - ONE_MONTH,
                     cliff: ONE_MONTH * 9,
                     period: ONE_MONTH * 27,
-                },
+                }),
             },
             TEST_AMOUNT.clone(),
         )
@@ -392,6 +606,7 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
                 vesting_addr,
                 &vesting::ExecuteMsg::Terminate {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
                 Coins::default(),
             )
@@ -404,6 +619,7 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
                 vesting_addr,
                 QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -418,7 +634,10 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -437,7 +656,10 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -449,6 +671,7 @@ async fn terminate_before_unlocking_starting_time_never_claimed() {
                 vesting_addr,
                 vesting::QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -473,11 +696,11 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: suite.block.timestamp - ONE_MONTH,
                     cliff: ONE_MONTH * 9,
                     period: ONE_MONTH * 27,
-                },
+                }),
             },
             TEST_AMOUNT.clone(),
         )
@@ -504,7 +727,10 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -531,6 +757,7 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
                 vesting_addr,
                 &vesting::ExecuteMsg::Terminate {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
                 Coins::default(),
             )
@@ -543,6 +770,7 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
                 vesting_addr,
                 QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -560,7 +788,10 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -572,6 +803,7 @@ async fn terminate_before_unlocking_starting_time_with_claimed() {
                 vesting_addr,
                 vesting::QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -596,11 +828,11 @@ async fn terminate_after_unlocking_starting_time() {
             vesting_addr,
             &vesting::ExecuteMsg::Create {
                 user: accounts.user1.address(),
-                schedule: Schedule {
+                schedule: Schedule::Linear(LinearSchedule {
                     start_time: suite.block.timestamp + ONE_MONTH,
                     cliff: ONE_MONTH * 9,
                     period: ONE_MONTH * 27,
-                },
+                }),
             },
             TEST_AMOUNT.clone(),
         )
@@ -626,6 +858,7 @@ async fn terminate_after_unlocking_starting_time() {
                 vesting_addr,
                 &vesting::ExecuteMsg::Terminate {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
                 Coins::default(),
             )
@@ -638,6 +871,7 @@ async fn terminate_after_unlocking_starting_time() {
                 vesting_addr,
                 QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -652,7 +886,10 @@ async fn terminate_after_unlocking_starting_time() {
             .execute(
                 &mut accounts.user1,
                 vesting_addr,
-                &vesting::ExecuteMsg::Claim {},
+                &vesting::ExecuteMsg::Claim {
+                    id: 0,
+                    recipient: None,
+                },
                 Coins::default(),
             )
             .await
@@ -664,6 +901,7 @@ async fn terminate_after_unlocking_starting_time() {
                 vesting_addr,
                 vesting::QueryPositionRequest {
                     user: accounts.user1.address(),
+                    id: 0,
                 },
             )
             .should_succeed_and(|res| {
@@ -33,6 +33,7 @@ from dango.utils.types import (
     SubmitOrCancelOrderRequest,
     SubmitOrderRequest,
     TimeInForce,
+    TrailingOffset,
     TriggerDirection,
     Tx,
     dango_decimal,
@@ -620,15 +621,15 @@ class Exchange(API):
             return "all"
 
         if isinstance(spec, ConditionalOrderRef):
-            return cast(
-                "CancelConditionalOrderRequest",
-                {
-                    "one": {
-                        "pair_id": spec.pair_id,
-                        "trigger_direction": spec.trigger_direction.value,
-                    },
-                },
-            )
+            one: dict[str, Any] = {
+                "pair_id": spec.pair_id,
+                "trigger_direction": spec.trigger_direction.value,
+            }
+            # Omit `order_id` when unset so the request keeps its
+            # "every order in this direction" meaning.
+            if spec.order_id is not None:
+                one["order_id"] = spec.order_id
+            return cast("CancelConditionalOrderRequest", {"one": one})
 
         if isinstance(spec, AllForPair):
             return cast(
@@ -651,9 +652,15 @@ class Exchange(API):
         trigger_direction: TriggerDirection,
         max_slippage: float | int | str | Decimal,
         *,
+        limit_price: float | int | str | Decimal | None = None,
+        trailing_offset: TrailingOffset | None = None,
         gas_limit: int | None = None,
     ) -> dict[str, Any]:
-        """Place a conditional (TP/SL) order; reduce-only is implicit. size=None closes all."""
+        """Place a conditional (TP/SL) order; reduce-only is implicit. size=None closes all.
+
+        `limit_price` makes it a stop-limit (rests a limit order on trigger);
+        `trailing_offset` makes it a trailing stop. The two are exclusive.
+        """
 
         # Per the Rust comment on TraderMsg::SubmitConditionalOrder, the
         # caller is responsible for the size sign: negative closes a
@@ -688,6 +695,12 @@ class Exchange(API):
                 "max_slippage": dango_decimal(max_slippage),
             },
         }
+        # Only emit the optional keys when set, so plain TP/SL orders keep
+        # the original wire shape.
+        if limit_price is not None:
+            inner["submit_conditional_order"]["limit_price"] = dango_decimal(limit_price)
+        if trailing_offset is not None:
+            inner["submit_conditional_order"]["trailing_offset"] = trailing_offset
 
         return self._send_action(
             [self._wrap_perps_execute("trade", inner)],
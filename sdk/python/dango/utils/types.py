@@ -111,6 +111,7 @@ class ReasonForOrderRemoval(StrEnum):
     SLIPPAGE_EXCEEDED = "slippage_exceeded"
     PRICE_BAND_VIOLATION = "price_band_violation"
     SLIPPAGE_CAP_TIGHTENED = "slippage_cap_tightened"
+    LIMIT_ORDER_REJECTED = "limit_order_rejected"
 
 
 class KeyType(StrEnum):
@@ -364,6 +365,7 @@ SubmitOrCancelAction = SubmitAction | CancelAction
 class _CancelConditionalOnePayload(TypedDict):
     pair_id: PairId
     trigger_direction: TriggerDirection
+    order_id: NotRequired[ConditionalOrderId | None]
 
 
 class _CancelConditionalOne(TypedDict):
@@ -392,10 +394,11 @@ CancelConditionalOrderRequest = (
 
 @dataclass(frozen=True)
 class ConditionalOrderRef:
-    """User-facing form of CancelConditionalOrderRequest::One — a single conditional order."""
+    """User-facing form of CancelConditionalOrderRequest::One — one direction, or one order_id."""
 
     pair_id: PairId
     trigger_direction: TriggerDirection
+    order_id: ConditionalOrderId | None = None
 
 
 @dataclass(frozen=True)
@@ -408,11 +411,24 @@ class AllForPair:
 CancelConditionalSpec = ConditionalOrderRef | AllForPair | Literal["all"]
 
 
+class _TrailingOffsetPercent(TypedDict):
+    percent: Dimensionless
+
+
+class _TrailingOffsetAbsolute(TypedDict):
+    absolute: UsdPrice
+
+
+TrailingOffset = _TrailingOffsetPercent | _TrailingOffsetAbsolute
+
+
 class ConditionalOrder(TypedDict):
     order_id: ConditionalOrderId
     size: Quantity | None
     trigger_price: UsdPrice
     max_slippage: Dimensionless
+    limit_price: UsdPrice | None
+    trailing_offset: TrailingOffset | None
 
 
 # --- Position / user state ---------------------------------------------------
@@ -422,16 +438,16 @@ class Position(TypedDict):
     size: Quantity
     entry_price: UsdPrice
     entry_funding_per_unit: FundingPerUnit
-    conditional_order_above: ConditionalOrder | None
-    conditional_order_below: ConditionalOrder | None
+    conditional_orders_above: dict[ConditionalOrderId, ConditionalOrder]
+    conditional_orders_below: dict[ConditionalOrderId, ConditionalOrder]
 
 
 class PositionExtended(TypedDict):
     size: Quantity
     entry_price: UsdPrice
     entry_funding_per_unit: FundingPerUnit
-    conditional_order_above: ConditionalOrder | None
-    conditional_order_below: ConditionalOrder | None
+    conditional_orders_above: dict[ConditionalOrderId, ConditionalOrder]
+    conditional_orders_below: dict[ConditionalOrderId, ConditionalOrder]
     unrealized_pnl: UsdValue | None
     unrealized_funding: UsdValue | None
     liquidation_price: UsdPrice | None
@@ -639,15 +655,19 @@ class OrderRemoved(TypedDict):
 
 
 class ConditionalOrderPlaced(TypedDict):
+    order_id: ConditionalOrderId
     pair_id: PairId
     user: Addr
     trigger_price: UsdPrice
     trigger_direction: TriggerDirection
     size: Quantity | None
     max_slippage: Dimensionless
+    limit_price: UsdPrice | None
+    trailing_offset: TrailingOffset | None
 
 
 class ConditionalOrderTriggered(TypedDict):
+    order_id: ConditionalOrderId
     pair_id: PairId
     user: Addr
     trigger_price: UsdPrice
@@ -656,6 +676,7 @@ class ConditionalOrderTriggered(TypedDict):
 
 
 class ConditionalOrderRemoved(TypedDict):
+    order_id: ConditionalOrderId
     pair_id: PairId
     user: Addr
     trigger_direction: TriggerDirection
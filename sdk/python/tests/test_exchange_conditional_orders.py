@@ -8,6 +8,7 @@ from dango.utils.constants import PERPS_CONTRACT_MAINNET
 from dango.utils.types import (
     Addr,
     AllForPair,
+    ConditionalOrderId,
     ConditionalOrderRef,
     PairId,
     TriggerDirection,
@@ -73,6 +74,40 @@ class TestSubmitConditionalOrder:
         inner = _last_inner_msg(info)["trade"]["submit_conditional_order"]
         assert inner["size"] is None
 
+    def test_limit_price_makes_stop_limit(self) -> None:
+        """limit_price is emitted as a dango decimal; trailing_offset stays absent."""
+
+        info = FakeInfo()
+        ex = _exchange(info)
+        ex.submit_conditional_order(
+            _DEMO_PAIR,
+            None,
+            48_000.0,
+            TriggerDirection.BELOW,
+            0.01,
+            limit_price=47_500,
+        )
+        inner = _last_inner_msg(info)["trade"]["submit_conditional_order"]
+        assert inner["limit_price"] == "47500.000000"
+        assert "trailing_offset" not in inner
+
+    def test_trailing_offset_passes_through(self) -> None:
+        """trailing_offset is forwarded verbatim as the externally-tagged enum."""
+
+        info = FakeInfo()
+        ex = _exchange(info)
+        ex.submit_conditional_order(
+            _DEMO_PAIR,
+            None,
+            48_000.0,
+            TriggerDirection.BELOW,
+            0.01,
+            trailing_offset={"percent": "0.050000"},
+        )
+        inner = _last_inner_msg(info)["trade"]["submit_conditional_order"]
+        assert inner["trailing_offset"] == {"percent": "0.050000"}
+        assert "limit_price" not in inner
+
     def test_negative_size_preserves_minus(self) -> None:
         """Negative size flows through with the leading minus intact."""
 
@@ -234,6 +269,19 @@ class TestCancelConditionalOrder:
         inner = _last_inner_msg(info)["trade"]["cancel_conditional_order"]
         assert inner == {"one": {"pair_id": _DEMO_PAIR, "trigger_direction": "below"}}
 
+    def test_cancel_one_by_order_id(self) -> None:
+        """ConditionalOrderRef with order_id narrows the cancel to that order."""
+
+        info = FakeInfo()
+        ex = _exchange(info)
+        ex.cancel_conditional_order(
+            ConditionalOrderRef(_DEMO_PAIR, TriggerDirection.ABOVE, ConditionalOrderId("42")),
+        )
+        inner = _last_inner_msg(info)["trade"]["cancel_conditional_order"]
+        assert inner == {
+            "one": {"pair_id": _DEMO_PAIR, "trigger_direction": "above", "order_id": "42"},
+        }
+
     def test_cancel_all_for_pair(self) -> None:
         """AllForPair → {'all_for_pair': {'pair_id': ...}}."""
 
@@ -319,8 +319,8 @@ class TestReshapeUserState:
                     "size": "0.500000",
                     "entry_price": "60000.000000",
                     "entry_funding_per_unit": "0.000000",
-                    "conditional_order_above": None,
-                    "conditional_order_below": None,
+                    "conditional_orders_above": {},
+                    "conditional_orders_below": {},
                     "unrealized_pnl": "100.000000",
                     "unrealized_funding": "0.000000",
                     "liquidation_price": "55000.000000",
@@ -360,8 +360,8 @@ class TestReshapeUserState:
                     "size": "-2.000000",
                     "entry_price": "3000.000000",
                     "entry_funding_per_unit": "0.000000",
-                    "conditional_order_above": None,
-                    "conditional_order_below": None,
+                    "conditional_orders_above": {},
+                    "conditional_orders_below": {},
                     "unrealized_pnl": None,
                     "unrealized_funding": None,
                     "liquidation_price": None,
@@ -0,0 +1,54 @@
+import { describe, expect, it } from "vitest";
+import { decodeUtf8, serialize } from "@left-curve/encoding";
+import { createSession } from "./createSession.js";
+
+import type { ArbitraryTypedData, Client, Signer } from "@left-curve/types";
+
+// Produced by the chain's `SessionInfo::to_prehash_sign_data`; the Rust side
+// asserts the same string in `dango-auth`'s
+// `session_permissions_sign_data_is_canonical` test.
+const SIGN_DATA =
+  '{"chain_id":"dev-1","expire_at":"100","permissions":"{\\"contracts\\":{\\"0x0000000000000000000000000000000000000001\\":[\\"trade.cancel_order\\",\\"trade.submit_order\\"],\\"0x0000000000000000000000000000000000000002\\":[]},\\"spend_limit\\":{\\"bridge/usdc\\":\\"100\\",\\"uatom\\":\\"5\\"}}","session_key":"AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgIC"}';
+
+describe("createSession", () => {
+  it("signs permissions in the chain's canonical form", async () => {
+    let signed: ArbitraryTypedData | undefined;
+
+    const client = {
+      chain: { id: "dev-1" },
+      signer: {
+        async signArbitrary(payload: ArbitraryTypedData) {
+          signed = payload;
+          return {
+            credential: { standard: { keyHash: "", signature: { secp256k1: "" } } },
+            signed: payload,
+          };
+        },
+      },
+    } as unknown as Client<Signer>;
+
+    const { sessionInfo } = await createSession(client, {
+      pubKey: new Uint8Array(33).fill(2),
+      expireAt: 100_000,
+      permissions: {
+        contracts: {
+          "0x0000000000000000000000000000000000000002": [],
+          "0x0000000000000000000000000000000000000001": [
+            "trade.submit_order",
+            "trade.cancel_order",
+            "trade.submit_order",
+          ],
+        },
+        spendLimit: { uatom: "5", "bridge/usdc": "100" },
+      },
+    });
+
+    if (!signed) throw new Error("nothing was signed");
+
+    expect(decodeUtf8(serialize(signed.message))).toBe(SIGN_DATA);
+
+    // The session info sent to the chain keeps the permissions as an object.
+    const variants = sessionInfo.permissions?.contracts["0x0000000000000000000000000000000000000001"];
+    expect(variants).toEqual(["trade.cancel_order", "trade.submit_order"]);
+  });
+});
@@ -1,8 +1,9 @@
-import { encodeBase64 } from "@left-curve/encoding";
+import { encodeBase64, sortedJsonStringify } from "@left-curve/encoding";
 
 import type {
   Client,
   KeyHash,
+  SessionPermissions,
   Signer,
   SigningSessionInfo,
   StandardCredential,
@@ -11,6 +12,8 @@ import type {
 export type CreateSessionParameters = {
   pubKey: Uint8Array;
   expireAt: number;
+  /** Restrict the session key to an allow-list. If omitted, the session is unrestricted. */
+  permissions?: SessionPermissions;
 };
 
 export type CreateSessionReturnType = Promise<{
@@ -23,7 +26,7 @@ export async function createSession(
   client: Client<Signer>,
   parameters: CreateSessionParameters,
 ): CreateSessionReturnType {
-  const { expireAt, pubKey } = parameters;
+  const { expireAt, pubKey, permissions } = parameters;
 
   if (!client.chain) throw new Error("chain is required for session creation");
 
@@ -31,15 +34,23 @@ export async function createSession(
     chainId: client.chain.id,
     sessionKey: encodeBase64(pubKey),
     expireAt: Math.floor(expireAt / 1000).toString(),
+    ...(permissions ? { permissions: normalizePermissions(permissions) } : {}),
   };
 
+  // Every signer signs `permissions` as its canonical JSON string, the same as
+  // the chain reconstructs it, since EIP-712 can't express the nested maps.
+  const message = permissions
+    ? { ...sessionInfo, permissions: canonicalPermissions(permissions) }
+    : sessionInfo;
+
   const { credential } = await client.signer.signArbitrary({
     primaryType: "Message" as const,
-    message: sessionInfo,
+    message,
     types: {
       Message: [
         { name: "chain_id", type: "string" },
         { name: "expire_at", type: "string" },
+        ...(permissions ? [{ name: "permissions", type: "string" }] : []),
         { name: "session_key", type: "string" },
       ],
     },
@@ -54,3 +65,37 @@ export async function createSession(
   }
   throw new Error("unsupported credential type");
 }
+
+/**
+ * The chain stores the allowed contracts as a map and each contract's allowed
+ * variants as a set, which serialize sorted and deduplicated. Do the same here
+ * so the session info matches the chain's canonical form.
+ */
+function normalizePermissions(permissions: SessionPermissions): SessionPermissions {
+  const contracts = Object.fromEntries(
+    Object.entries(permissions.contracts)
+      .map(([contract, variants]) => [contract.toLowerCase(), [...new Set(variants)].sort()])
+      .sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0)),
+  );
+
+  const spendLimit = permissions.spendLimit
+    ? Object.fromEntries(
+        Object.entries(permissions.spendLimit).sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0)),
+      )
+    : undefined;
+
+  return { contracts, ...(spendLimit ? { spendLimit } : {}) };
+}
+
+/**
+ * The canonical JSON string of the permissions (snake_case, keys sorted,
+ * compact), which is how the chain binds them in the signed session info.
+ *
+ * Contract addresses and denoms are map keys, so they're left as they are
+ * rather than case-converted.
+ */
+function canonicalPermissions(permissions: SessionPermissions): string {
+  const { contracts, spendLimit } = normalizePermissions(permissions);
+
+  return sortedJsonStringify({ contracts, ...(spendLimit ? { spend_limit: spendLimit } : {}) });
+}
@@ -242,6 +242,15 @@ export {
   type GetPerpsOrdersByUserParameters,
   type GetPerpsOrdersByUserReturnType,
   getPerpsOrdersByUser,
+  type GetPerpsTwapOrdersByUserParameters,
+  type GetPerpsTwapOrdersByUserReturnType,
+  getPerpsTwapOrdersByUser,
+  type GetPerpsCancelAllDeadlineParameters,
+  type GetPerpsCancelAllDeadlineReturnType,
+  getPerpsCancelAllDeadline,
+  type GetPerpsSubaccountStatesParameters,
+  type GetPerpsSubaccountStatesReturnType,
+  getPerpsSubaccountStates,
   type GetPerpsLiquidityDepthParameters,
   type GetPerpsLiquidityDepthReturnType,
   getPerpsLiquidityDepth,
@@ -283,6 +292,9 @@ export {
   type WithdrawMarginParameters,
   type WithdrawMarginReturnType,
   withdrawMargin,
+  type TransferMarginParameters,
+  type TransferMarginReturnType,
+  transferMargin,
   type SubmitPerpsOrderParameters,
   type SubmitPerpsOrderReturnType,
   submitPerpsOrder,
@@ -311,6 +323,15 @@ export {
   type CancelConditionalOrderParameters,
   type CancelConditionalOrderReturnType,
   cancelConditionalOrder,
+  type SubmitTwapOrderParameters,
+  type SubmitTwapOrderReturnType,
+  submitTwapOrder,
+  type CancelTwapOrderParameters,
+  type CancelTwapOrderReturnType,
+  cancelTwapOrder,
+  type CancelAllAfterParameters,
+  type CancelAllAfterReturnType,
+  cancelAllAfter,
 } from "./perps/index.js";
 
 /* -------------------------------------------------------------------------- */
@@ -23,6 +23,24 @@ export {
   getPerpsOrdersByUser,
 } from "./queries/getOrdersByUser.js";
 
+export {
+  type GetPerpsTwapOrdersByUserParameters,
+  type GetPerpsTwapOrdersByUserReturnType,
+  getPerpsTwapOrdersByUser,
+} from "./queries/getTwapOrdersByUser.js";
+
+export {
+  type GetPerpsCancelAllDeadlineParameters,
+  type GetPerpsCancelAllDeadlineReturnType,
+  getPerpsCancelAllDeadline,
+} from "./queries/getCancelAllDeadline.js";
+
+export {
+  type GetPerpsSubaccountStatesParameters,
+  type GetPerpsSubaccountStatesReturnType,
+  getPerpsSubaccountStates,
+} from "./queries/getSubaccountStates.js";
+
 export {
   type GetPerpsLiquidityDepthParameters,
   type GetPerpsLiquidityDepthReturnType,
@@ -86,6 +104,12 @@ export {
   withdrawMargin,
 } from "./mutations/withdrawMargin.js";
 
+export {
+  type TransferMarginParameters,
+  type TransferMarginReturnType,
+  transferMargin,
+} from "./mutations/transferMargin.js";
+
 export {
   type SubmitPerpsOrderParameters,
   type SubmitPerpsOrderReturnType,
@@ -159,3 +183,21 @@ export {
   type CancelConditionalOrderReturnType,
   cancelConditionalOrder,
 } from "./mutations/cancelConditionalOrder.js";
+
+export {
+  type SubmitTwapOrderParameters,
+  type SubmitTwapOrderReturnType,
+  submitTwapOrder,
+} from "./mutations/submitTwapOrder.js";
+
+export {
+  type CancelTwapOrderParameters,
+  type CancelTwapOrderReturnType,
+  cancelTwapOrder,
+} from "./mutations/cancelTwapOrder.js";
+
+export {
+  type CancelAllAfterParameters,
+  type CancelAllAfterReturnType,
+  cancelAllAfter,
+} from "./mutations/cancelAllAfter.js";
@@ -0,0 +1,38 @@
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+import { execute } from "#actions/app/mutations/execute.js";
+
+import type { Address } from "@left-curve/types";
+import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
+import type { Client, Signer } from "@left-curve/types";
+
+export type CancelAllAfterParameters = {
+  sender: Address;
+  timeout: string | null;
+};
+
+export type CancelAllAfterReturnType = SignAndBroadcastTxReturnType;
+
+export async function cancelAllAfter(
+  client: Client<Signer>,
+  parameters: CancelAllAfterParameters,
+): CancelAllAfterReturnType {
+  const { sender, timeout } = parameters;
+
+  const { addresses } = await getAppConfig(client);
+
+  const msg = {
+    trade: {
+      cancelAllAfter: {
+        timeout,
+      },
+    },
+  };
+
+  return await execute(client, {
+    sender,
+    execute: {
+      msg,
+      contract: addresses.perps,
+    },
+  });
+}
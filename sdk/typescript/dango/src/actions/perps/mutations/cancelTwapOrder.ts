@@ -0,0 +1,36 @@
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+import { execute } from "#actions/app/mutations/execute.js";
+
+import type { Address } from "@left-curve/types";
+import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
+import type { Client, PerpsCancelTwapOrderRequest, Signer } from "@left-curve/types";
+
+export type CancelTwapOrderParameters = {
+  sender: Address;
+  request: PerpsCancelTwapOrderRequest;
+};
+
+export type CancelTwapOrderReturnType = SignAndBroadcastTxReturnType;
+
+export async function cancelTwapOrder(
+  client: Client<Signer>,
+  parameters: CancelTwapOrderParameters,
+): CancelTwapOrderReturnType {
+  const { sender, request } = parameters;
+
+  const { addresses } = await getAppConfig(client);
+
+  const msg = {
+    trade: {
+      cancelTwapOrder: request,
+    },
+  };
+
+  return await execute(client, {
+    sender,
+    execute: {
+      msg,
+      contract: addresses.perps,
+    },
+  });
+}
@@ -3,7 +3,7 @@ import { execute } from "#actions/app/mutations/execute.js";
 
 import type { Address } from "@left-curve/types";
 import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
-import type { Client, Signer, TriggerDirection } from "@left-curve/types";
+import type { Client, Signer, TrailingOffset, TriggerDirection } from "@left-curve/types";
 
 export type SubmitConditionalOrderParameters = {
   sender: Address;
@@ -12,6 +12,8 @@ export type SubmitConditionalOrderParameters = {
   triggerPrice: string;
   triggerDirection: TriggerDirection;
   maxSlippage: string;
+  limitPrice?: string;
+  trailingOffset?: TrailingOffset;
 };
 
 export type SubmitConditionalOrderReturnType = SignAndBroadcastTxReturnType;
@@ -20,7 +22,7 @@ export async function submitConditionalOrder(
   client: Client<Signer>,
   parameters: SubmitConditionalOrderParameters,
 ): SubmitConditionalOrderReturnType {
-  const { sender, pairId, size, triggerPrice, triggerDirection, maxSlippage } = parameters;
+  const { sender, pairId, size, triggerPrice, triggerDirection, maxSlippage, limitPrice, trailingOffset } = parameters;
 
   const { addresses } = await getAppConfig(client);
 
@@ -32,6 +34,8 @@ export async function submitConditionalOrder(
         triggerPrice,
         triggerDirection,
         maxSlippage,
+        ...(limitPrice !== undefined ? { limitPrice } : {}),
+        ...(trailingOffset !== undefined ? { trailingOffset } : {}),
       },
     },
   };
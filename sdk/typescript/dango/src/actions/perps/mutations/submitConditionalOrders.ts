@@ -3,7 +3,7 @@ import { execute } from "#actions/app/mutations/execute.js";
 
 import type { Address } from "@left-curve/types";
 import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
-import type { Client, Signer, TriggerDirection } from "@left-curve/types";
+import type { Client, Signer, TrailingOffset, TriggerDirection } from "@left-curve/types";
 
 export type SubmitConditionalOrderInput = {
   pairId: string;
@@ -11,6 +11,8 @@ export type SubmitConditionalOrderInput = {
   triggerPrice: string;
   triggerDirection: TriggerDirection;
   maxSlippage: string;
+  limitPrice?: string;
+  trailingOffset?: TrailingOffset;
 };
 
 export type SubmitConditionalOrdersParameters = {
@@ -33,7 +35,7 @@ export async function submitConditionalOrders(
   const { addresses } = await getAppConfig(client);
 
   const executeMsgs = orders.map((order) => {
-    const { pairId, size, triggerPrice, triggerDirection, maxSlippage } = order;
+    const { pairId, size, triggerPrice, triggerDirection, maxSlippage, limitPrice, trailingOffset } = order;
 
     const msg = {
       trade: {
@@ -43,6 +45,8 @@ export async function submitConditionalOrders(
           triggerPrice,
           triggerDirection,
           maxSlippage,
+          ...(limitPrice !== undefined ? { limitPrice } : {}),
+          ...(trailingOffset !== undefined ? { trailingOffset } : {}),
         },
       },
     };
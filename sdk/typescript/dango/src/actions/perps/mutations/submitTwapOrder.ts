@@ -0,0 +1,48 @@
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+import { execute } from "#actions/app/mutations/execute.js";
+
+import type { Address } from "@left-curve/types";
+import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
+import type { Client, Signer } from "@left-curve/types";
+
+export type SubmitTwapOrderParameters = {
+  sender: Address;
+  pairId: string;
+  size: string;
+  duration: string;
+  sliceInterval: string;
+  maxSlippage: string;
+  reduceOnly: boolean;
+};
+
+export type SubmitTwapOrderReturnType = SignAndBroadcastTxReturnType;
+
+export async function submitTwapOrder(
+  client: Client<Signer>,
+  parameters: SubmitTwapOrderParameters,
+): SubmitTwapOrderReturnType {
+  const { sender, pairId, size, duration, sliceInterval, maxSlippage, reduceOnly } = parameters;
+
+  const { addresses } = await getAppConfig(client);
+
+  const msg = {
+    trade: {
+      submitTwapOrder: {
+        pairId,
+        size,
+        duration,
+        sliceInterval,
+        maxSlippage,
+        reduceOnly,
+      },
+    },
+  };
+
+  return await execute(client, {
+    sender,
+    execute: {
+      msg,
+      contract: addresses.perps,
+    },
+  });
+}
@@ -0,0 +1,39 @@
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+import { execute } from "#actions/app/mutations/execute.js";
+
+import type { Address, Client, Signer } from "@left-curve/types";
+import type { SignAndBroadcastTxReturnType } from "#actions/app/mutations/signAndBroadcastTx.js";
+
+export type TransferMarginParameters = {
+  sender: Address;
+  to: Address;
+  amount: string;
+};
+
+export type TransferMarginReturnType = SignAndBroadcastTxReturnType;
+
+export async function transferMargin(
+  client: Client<Signer>,
+  parameters: TransferMarginParameters,
+): TransferMarginReturnType {
+  const { sender, to, amount } = parameters;
+
+  const { addresses } = await getAppConfig(client);
+
+  const msg = {
+    trade: {
+      transferMargin: {
+        to,
+        amount,
+      },
+    },
+  };
+
+  return await execute(client, {
+    sender,
+    execute: {
+      msg,
+      contract: addresses.perps,
+    },
+  });
+}
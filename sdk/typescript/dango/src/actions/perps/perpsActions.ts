@@ -18,6 +18,24 @@ import {
   getPerpsOrdersByUser,
 } from "./queries/getOrdersByUser.js";
 
+import {
+  type GetPerpsTwapOrdersByUserParameters,
+  type GetPerpsTwapOrdersByUserReturnType,
+  getPerpsTwapOrdersByUser,
+} from "./queries/getTwapOrdersByUser.js";
+
+import {
+  type GetPerpsCancelAllDeadlineParameters,
+  type GetPerpsCancelAllDeadlineReturnType,
+  getPerpsCancelAllDeadline,
+} from "./queries/getCancelAllDeadline.js";
+
+import {
+  type GetPerpsSubaccountStatesParameters,
+  type GetPerpsSubaccountStatesReturnType,
+  getPerpsSubaccountStates,
+} from "./queries/getSubaccountStates.js";
+
 import {
   type GetPerpsLiquidityDepthParameters,
   type GetPerpsLiquidityDepthReturnType,
@@ -81,6 +99,12 @@ import {
   withdrawMargin,
 } from "./mutations/withdrawMargin.js";
 
+import {
+  type TransferMarginParameters,
+  type TransferMarginReturnType,
+  transferMargin,
+} from "./mutations/transferMargin.js";
+
 import {
   type SubmitPerpsOrderParameters,
   type SubmitPerpsOrderReturnType,
@@ -149,12 +173,39 @@ import {
   cancelConditionalOrder,
 } from "./mutations/cancelConditionalOrder.js";
 
+import {
+  type SubmitTwapOrderParameters,
+  type SubmitTwapOrderReturnType,
+  submitTwapOrder,
+} from "./mutations/submitTwapOrder.js";
+
+import {
+  type CancelTwapOrderParameters,
+  type CancelTwapOrderReturnType,
+  cancelTwapOrder,
+} from "./mutations/cancelTwapOrder.js";
+
+import {
+  type CancelAllAfterParameters,
+  type CancelAllAfterReturnType,
+  cancelAllAfter,
+} from "./mutations/cancelAllAfter.js";
+
 export type PerpsQueryActions = {
   getPerpsUserState: (args: GetPerpsUserStateParameters) => GetPerpsUserStateReturnType;
   getPerpsUserStateExtended: (
     args: GetPerpsUserStateExtendedParameters,
   ) => GetPerpsUserStateExtendedReturnType;
   getPerpsOrdersByUser: (args: GetPerpsOrdersByUserParameters) => GetPerpsOrdersByUserReturnType;
+  getPerpsTwapOrdersByUser: (
+    args: GetPerpsTwapOrdersByUserParameters,
+  ) => GetPerpsTwapOrdersByUserReturnType;
+  getPerpsCancelAllDeadline: (
+    args: GetPerpsCancelAllDeadlineParameters,
+  ) => GetPerpsCancelAllDeadlineReturnType;
+  getPerpsSubaccountStates: (
+    args: GetPerpsSubaccountStatesParameters,
+  ) => GetPerpsSubaccountStatesReturnType;
   getPerpsLiquidityDepth: (
     args: GetPerpsLiquidityDepthParameters,
   ) => GetPerpsLiquidityDepthReturnType;
@@ -177,6 +228,9 @@ export function perpsQueryActions(client: Client): PerpsQueryActions {
     getPerpsUserState: (args) => getPerpsUserState(client, args),
     getPerpsUserStateExtended: (args) => getPerpsUserStateExtended(client, args),
     getPerpsOrdersByUser: (args) => getPerpsOrdersByUser(client, args),
+    getPerpsTwapOrdersByUser: (args) => getPerpsTwapOrdersByUser(client, args),
+    getPerpsCancelAllDeadline: (args) => getPerpsCancelAllDeadline(client, args),
+    getPerpsSubaccountStates: (args) => getPerpsSubaccountStates(client, args),
     getPerpsLiquidityDepth: (args) => getPerpsLiquidityDepth(client, args),
     getPerpsPairParam: (args) => getPerpsPairParam(client, args),
     getPerpsPairParams: (args) => getPerpsPairParams(client, args),
@@ -196,6 +250,7 @@ export function perpsQueryActions(client: Client): PerpsQueryActions {
 export type PerpsMutationActions = {
   depositMargin: (args: DepositMarginParameters) => DepositMarginReturnType;
   withdrawMargin: (args: WithdrawMarginParameters) => WithdrawMarginReturnType;
+  transferMargin: (args: TransferMarginParameters) => TransferMarginReturnType;
   submitPerpsOrder: (args: SubmitPerpsOrderParameters) => SubmitPerpsOrderReturnType;
   cancelPerpsOrder: (args: CancelPerpsOrderParameters) => CancelPerpsOrderReturnType;
   setReferral: (args: SetReferralParameters) => SetReferralReturnType;
@@ -211,12 +266,16 @@ export type PerpsMutationActions = {
   cancelConditionalOrder: (
     args: CancelConditionalOrderParameters,
   ) => CancelConditionalOrderReturnType;
+  submitTwapOrder: (args: SubmitTwapOrderParameters) => SubmitTwapOrderReturnType;
+  cancelTwapOrder: (args: CancelTwapOrderParameters) => CancelTwapOrderReturnType;
+  cancelAllAfter: (args: CancelAllAfterParameters) => CancelAllAfterReturnType;
 };
 
 export function perpsMutationActions(client: Client<Signer>): PerpsMutationActions {
   return {
     depositMargin: (args) => depositMargin(client, args),
     withdrawMargin: (args) => withdrawMargin(client, args),
+    transferMargin: (args) => transferMargin(client, args),
     submitPerpsOrder: (args) => submitPerpsOrder(client, args),
     cancelPerpsOrder: (args) => cancelPerpsOrder(client, args),
     setReferral: (args) => setReferral(client, args),
@@ -226,5 +285,8 @@ export function perpsMutationActions(client: Client<Signer>): PerpsMutationActio
     submitConditionalOrder: (args) => submitConditionalOrder(client, args),
     submitConditionalOrders: (args) => submitConditionalOrders(client, args),
     cancelConditionalOrder: (args) => cancelConditionalOrder(client, args),
+    submitTwapOrder: (args) => submitTwapOrder(client, args),
+    cancelTwapOrder: (args) => cancelTwapOrder(client, args),
+    cancelAllAfter: (args) => cancelAllAfter(client, args),
   };
 }
@@ -0,0 +1,25 @@
+import { queryWasmSmart } from "#actions/app/queries/queryWasmSmart.js";
+import type { Client, GetPerpsQueryMsg, Prettify } from "@left-curve/types";
+
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+
+type ActionMsg = GetPerpsQueryMsg<"cancelAllDeadline">;
+
+export type GetPerpsCancelAllDeadlineParameters = Prettify<ActionMsg["cancelAllDeadline"]>;
+
+export type GetPerpsCancelAllDeadlineReturnType = Promise<string | null>;
+
+export async function getPerpsCancelAllDeadline(
+  client: Client,
+  parameters: GetPerpsCancelAllDeadlineParameters,
+): GetPerpsCancelAllDeadlineReturnType {
+  const msg: ActionMsg = {
+    cancelAllDeadline: {
+      ...parameters,
+    },
+  };
+
+  const { addresses } = await getAppConfig(client);
+
+  return await queryWasmSmart(client, { contract: addresses.perps, msg });
+}
@@ -0,0 +1,25 @@
+import { queryWasmSmart } from "#actions/app/queries/queryWasmSmart.js";
+import type { Client, GetPerpsQueryMsg, PerpsSubaccountStates, Prettify } from "@left-curve/types";
+
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+
+type ActionMsg = GetPerpsQueryMsg<"subaccountStates">;
+
+export type GetPerpsSubaccountStatesParameters = Prettify<ActionMsg["subaccountStates"]>;
+
+export type GetPerpsSubaccountStatesReturnType = Promise<PerpsSubaccountStates>;
+
+export async function getPerpsSubaccountStates(
+  client: Client,
+  parameters: GetPerpsSubaccountStatesParameters,
+): GetPerpsSubaccountStatesReturnType {
+  const msg: ActionMsg = {
+    subaccountStates: {
+      ...parameters,
+    },
+  };
+
+  const { addresses } = await getAppConfig(client);
+
+  return await queryWasmSmart(client, { contract: addresses.perps, msg });
+}
@@ -0,0 +1,30 @@
+import { queryWasmSmart } from "#actions/app/queries/queryWasmSmart.js";
+import type {
+  Client,
+  GetPerpsQueryMsg,
+  PerpsTwapOrdersByUserResponse,
+  Prettify,
+} from "@left-curve/types";
+
+import { getAppConfig } from "#actions/app/queries/getAppConfig.js";
+
+type ActionMsg = GetPerpsQueryMsg<"twapOrdersByUser">;
+
+export type GetPerpsTwapOrdersByUserParameters = Prettify<ActionMsg["twapOrdersByUser"]>;
+
+export type GetPerpsTwapOrdersByUserReturnType = Promise<PerpsTwapOrdersByUserResponse>;
+
+export async function getPerpsTwapOrdersByUser(
+  client: Client,
+  parameters: GetPerpsTwapOrdersByUserParameters,
+): GetPerpsTwapOrdersByUserReturnType {
+  const msg: ActionMsg = {
+    twapOrdersByUser: {
+      ...parameters,
+    },
+  };
+
+  const { addresses } = await getAppConfig(client);
+
+  return await queryWasmSmart(client, { contract: addresses.perps, msg });
+}
@@ -31,6 +31,9 @@ export type {
   PerpsLiquidityDepthResponse,
   PerpsCancelOrderRequest,
   PerpsCancelConditionalOrderRequest,
+  PerpsCancelTwapOrderRequest,
+  PerpsTwapOrder,
+  PerpsTwapOrdersByUserResponse,
   PerpsQueryMsg,
   GetPerpsQueryMsg,
   FeeRateOverride,
@@ -38,6 +41,7 @@ export type {
   TriggerDirection,
   ChildOrder,
   ConditionalOrder,
+  TrailingOffset,
   VaultSnapshot,
 } from "@left-curve/types";
 
@@ -231,6 +231,7 @@ export type {
 export type {
   SigningSession,
   SigningSessionInfo,
+  SessionPermissions,
   SessionResponse,
 } from "./session.js";
 
@@ -269,6 +270,7 @@ export type {
   RateSchedule,
   PerpsUserState,
   PerpsUserStateExtended,
+  PerpsSubaccountStates,
   PerpsPosition,
   PerpsPositionExtended,
   PerpsUnlock,
@@ -285,6 +287,9 @@ export type {
   PerpsLiquidityDepthResponse,
   PerpsCancelOrderRequest,
   PerpsCancelConditionalOrderRequest,
+  PerpsCancelTwapOrderRequest,
+  PerpsTwapOrder,
+  PerpsTwapOrdersByUserResponse,
   PerpsQueryMsg,
   GetPerpsQueryMsg,
   FeeRateOverride,
@@ -292,6 +297,7 @@ export type {
   TriggerDirection,
   ChildOrder,
   ConditionalOrder,
+  TrailingOffset,
   VaultSnapshot,
 } from "./perps.js";
 
@@ -8,19 +8,23 @@ export type ChildOrder = {
   size?: string;
 };
 
+export type TrailingOffset = { percent: string } | { absolute: string };
+
 export type ConditionalOrder = {
   orderId: string;
   size?: string;
   triggerPrice: string;
   maxSlippage: string;
+  limitPrice?: string;
+  trailingOffset?: TrailingOffset;
 };
 
 export type PerpsPosition = {
   size: string;
   entryPrice: string;
   entryFundingPerUnit: string;
-  conditionalOrderAbove?: ConditionalOrder;
-  conditionalOrderBelow?: ConditionalOrder;
+  conditionalOrdersAbove: Record<string, ConditionalOrder>;
+  conditionalOrdersBelow: Record<string, ConditionalOrder>;
 };
 
 export type PerpsUnlock = {
@@ -35,17 +39,22 @@ export type PerpsUserState = {
   unlocks: PerpsUnlock[];
   reservedMargin: string;
   openOrderCount: number;
+  isolatedMargins: Record<string, string>;
 };
 
+export type PerpsMarginMode = "cross" | "isolated";
+
 export type PerpsPositionExtended = {
   size: string;
   entryPrice: string;
   entryFundingPerUnit: string;
-  conditionalOrderAbove?: ConditionalOrder;
-  conditionalOrderBelow?: ConditionalOrder;
+  conditionalOrdersAbove: Record<string, ConditionalOrder>;
+  conditionalOrdersBelow: Record<string, ConditionalOrder>;
   unrealizedPnl: string | null;
   unrealizedFunding: string | null;
   liquidationPrice: string | null;
+  isolatedEquity: string | null;
+  isolatedMaintenanceMargin: string | null;
 };
 
 export type PerpsUserStateExtended = {
@@ -54,12 +63,23 @@ export type PerpsUserStateExtended = {
   unlocks: PerpsUnlock[];
   reservedMargin: string;
   openOrderCount: number;
+  isolatedMargins: Record<string, string>;
   equity: string | null;
   availableMargin: string | null;
   maintenanceMargin: string | null;
   positions: Record<string, PerpsPositionExtended>;
 };
 
+/** Combined state of all perps accounts owned by the same user. */
+export type PerpsSubaccountStates = {
+  margin: string;
+  equity: string;
+  availableMargin: string;
+  maintenanceMargin: string;
+  vaultShares: string;
+  subaccounts: Record<Address, PerpsUserStateExtended>;
+};
+
 export type PerpsTimeInForce = "GTC" | "IOC" | "POST";
 
 /**
@@ -192,10 +212,26 @@ export type PerpsCancelOrderRequest =
   | "all";
 
 export type PerpsCancelConditionalOrderRequest =
-  | { one: { pairId: string; triggerDirection: TriggerDirection } }
+  | { one: { pairId: string; triggerDirection: TriggerDirection; orderId?: string } }
   | { allForPair: { pairId: string } }
   | "all";
 
+export type PerpsCancelTwapOrderRequest = { one: string } | "all";
+
+export type PerpsTwapOrder = {
+  user: Address;
+  pairId: string;
+  size: string;
+  filledSize: string;
+  maxSlippage: string;
+  reduceOnly: boolean;
+  sliceInterval: string;
+  remainingSlices: number;
+  nextSliceTime: string;
+};
+
+export type PerpsTwapOrdersByUserResponse = Record<string, PerpsTwapOrder>;
+
 export type PerpsQueryMsg =
   | { userState: { user: Address } }
   | {
@@ -211,6 +247,7 @@ export type PerpsQueryMsg =
       };
     }
   | { userStates: { startAfter?: Address; limit?: number } }
+  | { subaccountStates: { user: number } }
   | { param: Record<string, never> }
   | { pairParam: { pairId: string } }
   | { pairParams: { startAfter?: string; limit?: number } }
@@ -219,6 +256,9 @@ export type PerpsQueryMsg =
   | { pairStates: { startAfter?: string; limit?: number } }
   | { order: { orderId: string } }
   | { ordersByUser: { user: Address } }
+  | { twapOrder: { twapId: string } }
+  | { twapOrdersByUser: { user: Address } }
+  | { cancelAllDeadline: { user: Address } }
   | { liquidityDepth: { pairId: string; bucketSize: string; limit?: number } }
   | { volume: { user: Address; since?: string } }
   | { vaultState: Record<string, never> }
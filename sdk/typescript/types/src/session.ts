@@ -1,4 +1,4 @@
-import type { Base64 } from "./index.js";
+import type { Address, Base64, Coins } from "./index.js";
 import type { StandardCredential } from "./credential.js";
 import type { KeyHash } from "./key.js";
 
@@ -9,6 +9,19 @@ export type SigningSessionInfo = {
   sessionKey: Base64;
   /** Expiry time of the session key. */
   expireAt: string;
+  /** What the session key is allowed to do. If absent, the session key may sign any transaction. */
+  permissions?: SessionPermissions;
+};
+
+export type SessionPermissions = {
+  /**
+   * Contracts the session key may execute, each mapped to the execute message
+   * variants it may send, as dot-separated enum tags (e.g. `trade.submit_order`).
+   * An empty list allows any message to that contract.
+   */
+  contracts: Record<Address, string[]>;
+  /** The maximum coins the session key may attach to its messages, cumulatively. */
+  spendLimit?: Coins;
 };
 
 export type SigningSession = {
@@ -308,8 +308,8 @@ const PerpsPositionsTable: React.FC = () => {
         currentPrice: markPrice,
         pnl,
         estLiquidationPrice,
-        conditionalOrderAbove: pos.conditionalOrderAbove,
-        conditionalOrderBelow: pos.conditionalOrderBelow,
+        conditionalOrderAbove: Object.values(pos.conditionalOrdersAbove ?? {})[0],
+        conditionalOrderBelow: Object.values(pos.conditionalOrdersBelow ?? {})[0],
       });
     }
     return result;
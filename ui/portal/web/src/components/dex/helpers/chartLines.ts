@@ -34,10 +34,10 @@ export function buildPositionLines(position: PerpsPositionExtended): ChartLine[]
     });
   }
 
-  const tp = isLong ? position.conditionalOrderAbove : position.conditionalOrderBelow;
-  const sl = isLong ? position.conditionalOrderBelow : position.conditionalOrderAbove;
+  const tps = isLong ? position.conditionalOrdersAbove : position.conditionalOrdersBelow;
+  const sls = isLong ? position.conditionalOrdersBelow : position.conditionalOrdersAbove;
 
-  if (tp) {
+  for (const tp of Object.values(tps ?? {})) {
     lines.push({
       price: +Decimal(tp.triggerPrice).toFixed(),
       color: COLORS.buy,
@@ -45,7 +45,7 @@ export function buildPositionLines(position: PerpsPositionExtended): ChartLine[]
     });
   }
 
-  if (sl) {
+  for (const sl of Object.values(sls ?? {})) {
     lines.push({
       price: +Decimal(sl.triggerPrice).toFixed(),
       color: COLORS.sell,
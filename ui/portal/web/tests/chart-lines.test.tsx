@@ -17,11 +17,15 @@ const colors = {
 describe("DEX chart lines", () => {
   it("maps a long backend position into entry, liquidation, take-profit, and stop-loss lines", () => {
     const position = {
-      conditionalOrderAbove: {
-        triggerPrice: "33000",
+      conditionalOrdersAbove: {
+        "1": {
+          triggerPrice: "33000",
+        },
       },
-      conditionalOrderBelow: {
-        triggerPrice: "28000",
+      conditionalOrdersBelow: {
+        "1": {
+          triggerPrice: "28000",
+        },
       },
       entryPrice: "30000",
       liquidationPrice: "25000",
@@ -54,11 +58,15 @@ describe("DEX chart lines", () => {
 
   it("inverts TP and SL trigger direction for short backend positions", () => {
     const position = {
-      conditionalOrderAbove: {
-        triggerPrice: "2200",
-      },
-      conditionalOrderBelow: {
-        triggerPrice: "1800",
+      conditionalOrdersAbove: {
+        "1": {
+          triggerPrice: "2200",
+        },
+      },
+      conditionalOrdersBelow: {
+        "1": {
+          triggerPrice: "1800",
+        },
       },
       entryPrice: "2000",
       liquidationPrice: "2500",
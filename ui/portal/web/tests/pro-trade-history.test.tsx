@@ -129,9 +129,11 @@ vi.mock("@left-curve/store", () => {
   const userState = {
     positions: {
       "perp/btcusd": {
-        conditionalOrderAbove: {
-          orderId: "tp-btc",
-          triggerPrice: "53000",
+        conditionalOrdersAbove: {
+          "tp-btc": {
+            orderId: "tp-btc",
+            triggerPrice: "53000",
+          },
         },
         entryPrice: "50000",
         size: "2",